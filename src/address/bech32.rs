@@ -0,0 +1,250 @@
+use std::fmt;
+
+const CHARSET: &[u8; 32] = b"qpzry9x8gf2tvdw0s3jn54khce6mua7l";
+const GENERATOR: [u32; 5] = [0x3b6a57b2, 0x26508e6d, 0x1ea119fa, 0x3d4233dd, 0x2a1462b3];
+const CHECKSUM_LENGTH: usize = 6;
+/// Maximum length of an encoded string (BIP173).
+pub const MAX_LENGTH: usize = 90;
+
+/// Checksum flavour: bech32 (BIP173) for segwit v0, bech32m (BIP350) for v1 and above.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Variant {
+    Bech32,
+    Bech32m,
+}
+
+impl Variant {
+    fn constant(&self) -> u32 {
+        match self {
+            Variant::Bech32 => 1,
+            Variant::Bech32m => 0x2bc830a3,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Bech32Error {
+    /// Empty, too long or with characters outside `!`..=`~`
+    InvalidHrp(String),
+    /// The decoded human-readable part is not the expected one
+    HrpMismatch(String),
+    MixedCase,
+    MissingSeparator,
+    InvalidCharacter(char),
+    InvalidLength(usize),
+    InvalidChecksum,
+    /// Data that does not regroup into whole bytes
+    InvalidPadding,
+    InvalidWitnessVersion(u8),
+    InvalidProgramLength(usize),
+    /// bech32 used for a v1+ program or bech32m for a v0 one
+    InvalidVariant,
+}
+
+impl fmt::Display for Bech32Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        match self {
+            Bech32Error::InvalidHrp(v) => f.write_str(&format!("Invalid human-readable part {v:?}")),
+            Bech32Error::HrpMismatch(v) => f.write_str(&format!("Unexpected human-readable part {v:?}")),
+            Bech32Error::MixedCase => f.write_str("Mixed-case string"),
+            Bech32Error::MissingSeparator => f.write_str("Missing separator '1'"),
+            Bech32Error::InvalidCharacter(v) => f.write_str(&format!("Invalid bech32 character {v:?}")),
+            Bech32Error::InvalidLength(v) => f.write_str(&format!("Invalid length {v}")),
+            Bech32Error::InvalidChecksum => f.write_str("Invalid checksum"),
+            Bech32Error::InvalidPadding => f.write_str("Invalid padding"),
+            Bech32Error::InvalidWitnessVersion(v) => f.write_str(&format!("Invalid witness version {v}")),
+            Bech32Error::InvalidProgramLength(v) => f.write_str(&format!("Invalid witness program length {v}")),
+            Bech32Error::InvalidVariant => f.write_str("Wrong checksum variant for the witness version"),
+        }
+    }
+}
+
+impl std::error::Error for Bech32Error {}
+
+fn polymod(values: impl Iterator<Item = u8>) -> u32 {
+    values.fold(1u32, |chk, value| {
+        let top = chk >> 25;
+        let chk = ((chk & 0x1ffffff) << 5) ^ value as u32;
+        GENERATOR.iter().enumerate().filter(|(i, _)| (top >> i) & 1 == 1).fold(chk, |chk, (_, g)| chk ^ g)
+    })
+}
+
+fn hrp_expand(hrp: &str) -> impl Iterator<Item = u8> + '_ {
+    hrp.bytes().map(|b| b >> 5).chain([0]).chain(hrp.bytes().map(|b| b & 0x1f))
+}
+
+fn validate_hrp(hrp: &str) -> Result<(), Bech32Error> {
+    if hrp.is_empty() || hrp.len() > 83 || !hrp.bytes().all(|b| (33..=126).contains(&b)) {
+        return Err(Bech32Error::InvalidHrp(hrp.to_string()));
+    }
+    Ok(())
+}
+
+/// Regroups `data` from `from`-bit to `to`-bit values, padding the last group when `pad`.
+pub fn convert_bits(data: &[u8], from: u32, to: u32, pad: bool) -> Result<Vec<u8>, Bech32Error> {
+    let mut acc = 0u32;
+    let mut bits = 0;
+    let mut result = Vec::new();
+    let max = (1u32 << to) - 1;
+    for value in data {
+        if (*value as u32) >> from != 0 {
+            return Err(Bech32Error::InvalidPadding);
+        }
+        acc = (acc << from) | *value as u32;
+        bits += from;
+        while bits >= to {
+            bits -= to;
+            result.push(((acc >> bits) & max) as u8);
+        }
+    }
+    if pad {
+        if bits > 0 {
+            result.push(((acc << (to - bits)) & max) as u8);
+        }
+    } else if bits >= from || (acc << (to - bits)) & max != 0 {
+        return Err(Bech32Error::InvalidPadding);
+    }
+    Ok(result)
+}
+
+/// Encodes 5-bit `data` under `hrp` (lowercase).
+///
+/// Usage:
+/// ```rust
+/// use stacks_rs::address::bech32::{decode, encode, Variant};
+/// let encoded = encode("a", &[], Variant::Bech32m).unwrap();
+/// assert_eq!(encoded, "a1lqfn3a");
+/// assert_eq!(decode(&encoded).unwrap(), ("a".to_string(), vec![], Variant::Bech32m));
+/// ```
+pub fn encode(hrp: &str, data: &[u8], variant: Variant) -> Result<String, Bech32Error> {
+    validate_hrp(hrp)?;
+    if let Some(value) = data.iter().find(|value| **value >= 32) {
+        return Err(Bech32Error::InvalidCharacter(*value as char));
+    }
+    let hrp = hrp.to_lowercase();
+    let length = hrp.len() + 1 + data.len() + CHECKSUM_LENGTH;
+    if length > MAX_LENGTH {
+        return Err(Bech32Error::InvalidLength(length));
+    }
+    let checksum = polymod(hrp_expand(&hrp).chain(data.iter().copied()).chain([0; CHECKSUM_LENGTH])) ^ variant.constant();
+    let checksum = (0..CHECKSUM_LENGTH).map(|i| ((checksum >> (5 * (5 - i))) & 0x1f) as u8);
+    let data: String = data.iter().copied().chain(checksum).map(|value| CHARSET[value as usize] as char).collect();
+    Ok(format!("{hrp}1{data}"))
+}
+
+/// Returns the lowercase human-readable part, the 5-bit data and the checksum variant.
+pub fn decode(input: &str) -> Result<(String, Vec<u8>, Variant), Bech32Error> {
+    if input.len() > MAX_LENGTH {
+        return Err(Bech32Error::InvalidLength(input.len()));
+    }
+    if input.chars().any(|c| c.is_ascii_lowercase()) && input.chars().any(|c| c.is_ascii_uppercase()) {
+        return Err(Bech32Error::MixedCase);
+    }
+    let input = input.to_lowercase();
+    let (hrp, data) = input.rsplit_once('1').ok_or(Bech32Error::MissingSeparator)?;
+    validate_hrp(hrp)?;
+    if data.len() < CHECKSUM_LENGTH {
+        return Err(Bech32Error::InvalidLength(input.len()));
+    }
+    let values = data
+        .chars()
+        .map(|c| CHARSET.iter().position(|d| *d as char == c).map(|v| v as u8).ok_or(Bech32Error::InvalidCharacter(c)))
+        .collect::<Result<Vec<u8>, Bech32Error>>()?;
+    let variant = match polymod(hrp_expand(hrp).chain(values.iter().copied())) {
+        c if c == Variant::Bech32.constant() => Variant::Bech32,
+        c if c == Variant::Bech32m.constant() => Variant::Bech32m,
+        _ => return Err(Bech32Error::InvalidChecksum),
+    };
+    Ok((hrp.to_string(), values[..values.len() - CHECKSUM_LENGTH].to_vec(), variant))
+}
+
+/// Segwit address of a witness program (BIP173/BIP350), e.g. `bc1q...` or `bc1p...`.
+pub fn encode_segwit_address(hrp: &str, witness_version: u8, program: &[u8]) -> Result<String, Bech32Error> {
+    validate_witness_program(witness_version, program)?;
+    let variant = if witness_version == 0 { Variant::Bech32 } else { Variant::Bech32m };
+    let data = [&[witness_version][..], &convert_bits(program, 8, 5, true)?].concat();
+    encode(hrp, &data, variant)
+}
+
+/// Returns the witness version and program of a segwit address, checking its `hrp`.
+pub fn decode_segwit_address(hrp: &str, address: &str) -> Result<(u8, Vec<u8>), Bech32Error> {
+    let (decoded_hrp, data, variant) = decode(address)?;
+    if decoded_hrp != hrp.to_lowercase() {
+        return Err(Bech32Error::HrpMismatch(decoded_hrp));
+    }
+    let (witness_version, data) = data.split_first().ok_or(Bech32Error::InvalidLength(0))?;
+    let program = convert_bits(data, 5, 8, false)?;
+    validate_witness_program(*witness_version, &program)?;
+    if (*witness_version == 0) != (variant == Variant::Bech32) {
+        return Err(Bech32Error::InvalidVariant);
+    }
+    Ok((*witness_version, program))
+}
+
+fn validate_witness_program(witness_version: u8, program: &[u8]) -> Result<(), Bech32Error> {
+    if witness_version > 16 {
+        return Err(Bech32Error::InvalidWitnessVersion(witness_version));
+    }
+    if !(2..=40).contains(&program.len()) || (witness_version == 0 && program.len() != 20 && program.len() != 32) {
+        return Err(Bech32Error::InvalidProgramLength(program.len()));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_checksum_vectors() {
+        // BIP173 / BIP350 valid strings
+        for (input, variant) in [
+            ("A12UEL5L", Variant::Bech32),
+            ("abcdef1qpzry9x8gf2tvdw0s3jn54khce6mua7lmqqqxw", Variant::Bech32),
+            ("split1checkupstagehandshakeupstreamerranterredcaperred2y9e3w", Variant::Bech32),
+            ("?1ezyfcl", Variant::Bech32),
+            ("A1LQFN3A", Variant::Bech32m),
+            ("abcdef1l7aum6echk45nj3s0wdvt2fg8x9yrzpqzd3ryx", Variant::Bech32m),
+            ("?1v759aa", Variant::Bech32m),
+        ] {
+            let (hrp, data, decoded_variant) = decode(input).unwrap();
+            assert_eq!(decoded_variant, variant);
+            assert_eq!(encode(&hrp, &data, variant).unwrap(), input.to_lowercase());
+        }
+
+        assert_eq!(decode("pzry9x0s0muk"), Err(Bech32Error::MissingSeparator));
+        assert_eq!(decode("1pzry9x0s0muk"), Err(Bech32Error::InvalidHrp(String::new())));
+        assert_eq!(decode("x1b4n0q5v"), Err(Bech32Error::InvalidCharacter('b')));
+        assert_eq!(decode("A1G7SGD8"), Err(Bech32Error::InvalidChecksum));
+        assert_eq!(decode("a12UEL5L"), Err(Bech32Error::MixedCase));
+    }
+
+    #[test]
+    fn test_segwit_addresses() {
+        let vectors = [
+            ("bc", "BC1QW508D6QEJXTDG4Y5R3ZARVARY0C5XW7KV8F3T4", "0014751e76e8199196d454941c45d1b3a323f1433bd6"),
+            ("tb", "tb1qrp33g0q5c5txsp9arysrx4k6zdkfs4nce4xj0gdcccefvpysxf3q0sl5k7", "00201863143c14c5166804bd19203356da136c985678cd4d27a1b8c6329604903262"),
+            ("bc", "bc1p0xlxvlhemja6c4dqv22uapctqupfhlxm9h8z3k2e72q4k9hcz7vqzk5jj0", "512079be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798"),
+        ];
+        for (hrp, address, script) in vectors {
+            let (version, program) = decode_segwit_address(hrp, address).unwrap();
+            let script = hex::decode(script).unwrap();
+            assert_eq!(version, if script[0] == 0 { 0 } else { script[0] - 0x50 });
+            assert_eq!(program, script[2..]);
+            assert_eq!(encode_segwit_address(hrp, version, &program).unwrap(), address.to_lowercase());
+        }
+
+        // v0 with bech32m, v1 with bech32, wrong hrp
+        assert_eq!(
+            decode_segwit_address("bc", "bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kemeawh"),
+            Err(Bech32Error::InvalidVariant)
+        );
+        assert_eq!(
+            decode_segwit_address("bc", "bc1p0xlxvlhemja6c4dqv22uapctqupfhlxm9h8z3k2e72q4k9hcz7vqh2y7hd"),
+            Err(Bech32Error::InvalidVariant)
+        );
+        assert!(matches!(decode_segwit_address("tb", "bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4"), Err(Bech32Error::HrpMismatch(_))));
+        assert_eq!(encode_segwit_address("bc", 0, &[0u8; 21]), Err(Bech32Error::InvalidProgramLength(21)));
+        assert_eq!(encode_segwit_address("bc", 17, &[0u8; 32]), Err(Bech32Error::InvalidWitnessVersion(17)));
+    }
+}
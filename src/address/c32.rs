@@ -0,0 +1,212 @@
+use std::fmt;
+
+use crate::crypto::constant_time::ct_eq;
+use crate::crypto::hash::{DoubleSha256, Hasher};
+
+/// Crockford base32 alphabet used by c32 (no `I`, `L`, `O`, `U`).
+pub(crate) const C32_ALPHABET: &[u8; 32] = b"0123456789ABCDEFGHJKMNPQRSTVWXYZ";
+const CHECKSUM_LENGTH: usize = 4;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum C32Error {
+    InvalidCharacter(char),
+    /// Versions are a single c32 digit, so they must be below 32
+    InvalidVersion(u8),
+    InvalidChecksum,
+    /// Too short to hold a version and a checksum
+    TooShort(usize),
+    /// Addresses start with `S`
+    MissingPrefix,
+}
+
+impl fmt::Display for C32Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        match self {
+            C32Error::InvalidCharacter(v) => f.write_str(&format!("Invalid c32 character {v:?}")),
+            C32Error::InvalidVersion(v) => f.write_str(&format!("Invalid c32check version {v}")),
+            C32Error::InvalidChecksum => f.write_str("Invalid c32check checksum"),
+            C32Error::TooShort(v) => f.write_str(&format!("c32check string too short ({v} characters)")),
+            C32Error::MissingPrefix => f.write_str("Address does not start with 'S'"),
+        }
+    }
+}
+
+impl std::error::Error for C32Error {}
+
+/// Value of a c32 digit, accepting lowercase and the `O`/`I`/`L` look-alikes.
+fn digit_value(c: char) -> Result<u8, C32Error> {
+    let normalized = match c.to_ascii_uppercase() {
+        'O' => '0',
+        'I' | 'L' => '1',
+        other => other,
+    };
+    C32_ALPHABET
+        .iter()
+        .position(|digit| *digit as char == normalized)
+        .map(|value| value as u8)
+        .ok_or(C32Error::InvalidCharacter(c))
+}
+
+/// c32 encoding of `data`, keeping one `0` per leading zero byte.
+///
+/// Usage:
+/// ```rust
+/// use stacks_rs::address::c32::{c32_decode, c32_encode};
+/// assert_eq!(c32_encode(b"hello world"), "38CNP6RVS0EXQQ4V34");
+/// assert_eq!(c32_decode("38CNP6RVS0EXQQ4V34").unwrap(), b"hello world");
+/// ```
+pub fn c32_encode(data: &[u8]) -> String {
+    let mut digits = Vec::new();
+    let mut carry = 0u16;
+    let mut carry_bits = 0;
+    for byte in data.iter().rev() {
+        carry |= (*byte as u16) << carry_bits;
+        carry_bits += 8;
+        while carry_bits >= 5 {
+            digits.push(C32_ALPHABET[(carry & 0x1f) as usize]);
+            carry >>= 5;
+            carry_bits -= 5;
+        }
+    }
+    if carry_bits > 0 {
+        digits.push(C32_ALPHABET[carry as usize]);
+    }
+    while digits.last() == Some(&C32_ALPHABET[0]) {
+        digits.pop();
+    }
+    digits.extend(data.iter().take_while(|byte| **byte == 0).map(|_| C32_ALPHABET[0]));
+    digits.iter().rev().map(|digit| *digit as char).collect()
+}
+
+/// Inverse of [`c32_encode`].
+pub fn c32_decode(input: &str) -> Result<Vec<u8>, C32Error> {
+    let digits = input.chars().map(digit_value).collect::<Result<Vec<u8>, C32Error>>()?;
+    let mut bytes = Vec::new();
+    let mut carry = 0u16;
+    let mut carry_bits = 0;
+    for digit in digits.iter().rev() {
+        carry |= (*digit as u16) << carry_bits;
+        carry_bits += 5;
+        if carry_bits >= 8 {
+            bytes.push((carry & 0xff) as u8);
+            carry >>= 8;
+            carry_bits -= 8;
+        }
+    }
+    if carry_bits > 0 {
+        bytes.push(carry as u8);
+    }
+    while bytes.last() == Some(&0) {
+        bytes.pop();
+    }
+    bytes.extend(digits.iter().take_while(|digit| **digit == 0).map(|_| 0));
+    bytes.reverse();
+    Ok(bytes)
+}
+
+fn checksum(version: u8, data: &[u8]) -> [u8; CHECKSUM_LENGTH] {
+    DoubleSha256::hash(&[&[version][..], data].concat())[..CHECKSUM_LENGTH].try_into().unwrap()
+}
+
+/// c32check: the version digit followed by the c32 of `data || checksum`.
+pub fn c32check_encode(version: u8, data: &[u8]) -> Result<String, C32Error> {
+    if version >= 32 {
+        return Err(C32Error::InvalidVersion(version));
+    }
+    let payload = [data, &checksum(version, data)].concat();
+    Ok(format!("{}{}", C32_ALPHABET[version as usize] as char, c32_encode(&payload)))
+}
+
+/// Returns the version and the data of a c32check string.
+pub fn c32check_decode(input: &str) -> Result<(u8, Vec<u8>), C32Error> {
+    let mut chars = input.chars();
+    let version = digit_value(chars.next().ok_or(C32Error::TooShort(0))?)?;
+    let payload = c32_decode(chars.as_str())?;
+    if payload.len() < CHECKSUM_LENGTH {
+        return Err(C32Error::TooShort(input.len()));
+    }
+    let (data, expected) = payload.split_at(payload.len() - CHECKSUM_LENGTH);
+    if !ct_eq(&checksum(version, data), expected) {
+        return Err(C32Error::InvalidChecksum);
+    }
+    Ok((version, data.to_vec()))
+}
+
+/// Stacks address (`S` + c32check) of a version and a hash160.
+///
+/// Usage:
+/// ```rust
+/// use stacks_rs::address::c32::{c32_address, c32_address_decode};
+/// let address = c32_address(22, &[0u8; 20]).unwrap();
+/// assert_eq!(address, "SP000000000000000000002Q6VF78");
+/// assert_eq!(c32_address_decode(&address).unwrap(), (22, vec![0u8; 20]));
+/// ```
+pub fn c32_address(version: u8, hash: &[u8]) -> Result<String, C32Error> {
+    Ok(format!("S{}", c32check_encode(version, hash)?))
+}
+
+pub fn c32_address_decode(address: &str) -> Result<(u8, Vec<u8>), C32Error> {
+    let rest = address.strip_prefix('S').ok_or(C32Error::MissingPrefix)?;
+    if rest.len() < 5 {
+        return Err(C32Error::TooShort(address.len()));
+    }
+    c32check_decode(rest)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_c32_vectors() {
+        // stacks.js c32check test vectors
+        let vectors: [(&str, &str); 6] = [
+            ("", ""),
+            ("00", "0"),
+            ("0000", "00"),
+            ("a46ff88886c2ef9762d970b4d2c63678835bd39d", "MHQZH246RBQSERPSE2TD5HHPF21NQMWX"),
+            ("000000000000000000000000000000000000000000", "000000000000000000000"),
+            ("01", "1"),
+        ];
+        for (hex_data, expected) in vectors {
+            let data = hex::decode(hex_data).unwrap();
+            assert_eq!(c32_encode(&data), expected);
+            assert_eq!(c32_decode(expected).unwrap(), data);
+        }
+        // look-alike characters
+        assert_eq!(c32_decode("mhqzh246rbqserpse2td5hhpf21nqmwx").unwrap(), c32_decode("MHQZH246RBQSERPSE2TD5HHPF21NQMWX").unwrap());
+        assert_eq!(c32_decode("O1"), c32_decode("0I"));
+        assert_eq!(c32_decode("MU"), Err(C32Error::InvalidCharacter('U')));
+    }
+
+    #[test]
+    fn test_c32_address_vectors() {
+        let hash = hex::decode("a46ff88886c2ef9762d970b4d2c63678835bd39d").unwrap();
+        let vectors = [
+            (22, "SP2J6ZY48GV1EZ5V2V5RB9MP66SW86PYKKNRV9EJ7"),
+            (0, "S02J6ZY48GV1EZ5V2V5RB9MP66SW86PYKKPVKG2CE"),
+            (31, "SZ2J6ZY48GV1EZ5V2V5RB9MP66SW86PYKKQ9H6DPR"),
+            (20, "SM2J6ZY48GV1EZ5V2V5RB9MP66SW86PYKKQVX8X0G"),
+            (26, "ST2J6ZY48GV1EZ5V2V5RB9MP66SW86PYKKQYAC0RQ"),
+            (21, "SN2J6ZY48GV1EZ5V2V5RB9MP66SW86PYKKP6D2ZK9"),
+        ];
+        for (version, address) in vectors {
+            assert_eq!(c32_address(version, &hash).unwrap(), address);
+            assert_eq!(c32_address_decode(address).unwrap(), (version, hash.clone()));
+        }
+        assert_eq!(c32_address(32, &hash), Err(C32Error::InvalidVersion(32)));
+        assert_eq!(c32_address_decode("SP2J6ZY48GV1EZ5V2V5RB9MP66SW86PYKKNRV9EJ8"), Err(C32Error::InvalidChecksum));
+        assert_eq!(c32_address_decode("XP2J6ZY48GV1EZ5V2V5RB9MP66SW86PYKKNRV9EJ7"), Err(C32Error::MissingPrefix));
+        assert!(c32_address_decode("SP").is_err());
+    }
+
+    #[test]
+    fn test_matches_stacks_common() {
+        for version in [0u8, 22, 26, 31] {
+            for hash in [[0u8; 20], [0xff; 20], [0x01; 20]] {
+                let expected = stacks_common::address::c32::c32_address(version, &hash).unwrap();
+                assert_eq!(c32_address(version, &hash).unwrap(), expected);
+            }
+        }
+    }
+}
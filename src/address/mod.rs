@@ -0,0 +1,41 @@
+use std::fmt;
+
+pub mod bech32;
+pub mod c32;
+pub mod principal;
+pub mod registry;
+pub mod stacks_address;
+
+/// Length of the hash160 carried by Stacks addresses.
+pub const ADDRESS_HASH_LENGTH: usize = 20;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum AddressError {
+    /// No format is registered for the version byte
+    UnknownVersion(u8),
+    /// None of the registered formats could decode the address
+    UnrecognizedAddress(String),
+    InvalidEncoding(String),
+    InvalidHashLength(usize),
+    /// Addresses are derived from non-hardened children only
+    InvalidChildIndex(u32),
+    InvalidContractName(String),
+    /// A valid address of another network
+    WrongNetwork(String),
+}
+
+impl fmt::Display for AddressError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        match self {
+            AddressError::UnknownVersion(v) => f.write_str(&format!("No address format registered for version {v}")),
+            AddressError::UnrecognizedAddress(v) => f.write_str(&format!("Unrecognized address {v}")),
+            AddressError::InvalidEncoding(v) => f.write_str(&format!("Invalid address encoding: {v}")),
+            AddressError::InvalidHashLength(v) => f.write_str(&format!("Invalid address hash length {v}")),
+            AddressError::InvalidChildIndex(v) => f.write_str(&format!("Invalid child index {v}")),
+            AddressError::InvalidContractName(v) => f.write_str(&format!("Invalid contract name {v:?}")),
+            AddressError::WrongNetwork(v) => f.write_str(&format!("Address {v} belongs to another network")),
+        }
+    }
+}
+
+impl std::error::Error for AddressError {}
@@ -0,0 +1,191 @@
+use std::fmt;
+use std::str::FromStr;
+
+use crate::clarity::conversions::unexpected_type;
+use crate::clarity::value::ClarityValue;
+use crate::clarity::ClarityError;
+use crate::network::NetworkKind;
+
+use super::stacks_address::StacksAddress;
+use super::AddressError;
+
+/// Longest contract name, in bytes.
+pub const CONTRACT_NAME_MAX_LENGTH: usize = 128;
+
+/// A Clarity principal: an account (`SP...`) or a contract deployed by one (`SP....name`).
+///
+/// Usage:
+/// ```rust
+/// use std::str::FromStr;
+/// use stacks_rs::address::principal::Principal;
+/// use stacks_rs::network::NetworkKind;
+/// let principal = Principal::from_str("SP3FGQ8Z7JY9BWYZ5WM53E0M9NK7WHJF0691NZ159.my-token").unwrap();
+/// assert_eq!(principal.contract_name(), Some("my-token"));
+/// assert_eq!(principal.network(), Some(NetworkKind::Mainnet));
+/// assert_eq!(principal.to_string(), "SP3FGQ8Z7JY9BWYZ5WM53E0M9NK7WHJF0691NZ159.my-token");
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Principal {
+    Standard(StacksAddress),
+    Contract(StacksAddress, String),
+}
+
+impl Principal {
+    pub fn standard(address: StacksAddress) -> Self {
+        Principal::Standard(address)
+    }
+
+    /// Contract principal, checking the name rules (see [`Principal::is_valid_contract_name`]).
+    pub fn contract(address: StacksAddress, contract_name: &str) -> Result<Self, AddressError> {
+        if !Self::is_valid_contract_name(contract_name) {
+            return Err(AddressError::InvalidContractName(contract_name.to_string()));
+        }
+        Ok(Principal::Contract(address, contract_name.to_string()))
+    }
+
+    /// Contract names are 1 to 128 characters, starting with a letter and followed by
+    /// letters, digits, `-` or `_`.
+    pub fn is_valid_contract_name(contract_name: &str) -> bool {
+        let mut chars = contract_name.chars();
+        contract_name.len() <= CONTRACT_NAME_MAX_LENGTH
+            && chars.next().is_some_and(|c| c.is_ascii_alphabetic())
+            && chars.all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+    }
+
+    /// The account address, the deployer's for a contract.
+    pub fn address(&self) -> &StacksAddress {
+        match self {
+            Principal::Standard(address) | Principal::Contract(address, _) => address,
+        }
+    }
+
+    pub fn contract_name(&self) -> Option<&str> {
+        match self {
+            Principal::Standard(_) => None,
+            Principal::Contract(_, contract_name) => Some(contract_name),
+        }
+    }
+
+    pub fn is_contract(&self) -> bool {
+        matches!(self, Principal::Contract(..))
+    }
+
+    /// Network the address version belongs to, `None` for non-standard versions.
+    pub fn network(&self) -> Option<NetworkKind> {
+        self.address().network()
+    }
+}
+
+impl From<StacksAddress> for Principal {
+    fn from(address: StacksAddress) -> Self {
+        Principal::Standard(address)
+    }
+}
+
+impl fmt::Display for Principal {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        match self {
+            Principal::Standard(address) => f.write_str(&address.to_string()),
+            Principal::Contract(address, contract_name) => f.write_str(&format!("{address}.{contract_name}")),
+        }
+    }
+}
+
+impl FromStr for Principal {
+    type Err = AddressError;
+
+    fn from_str(principal: &str) -> Result<Self, Self::Err> {
+        match principal.split_once('.') {
+            None => Ok(Principal::Standard(StacksAddress::from_str(principal)?)),
+            Some((address, contract_name)) => Self::contract(StacksAddress::from_str(address)?, contract_name),
+        }
+    }
+}
+
+impl From<Principal> for ClarityValue {
+    fn from(value: Principal) -> Self {
+        ClarityValue::Principal(value)
+    }
+}
+
+impl From<StacksAddress> for ClarityValue {
+    fn from(value: StacksAddress) -> Self {
+        ClarityValue::Principal(Principal::Standard(value))
+    }
+}
+
+impl TryFrom<ClarityValue> for Principal {
+    type Error = ClarityError;
+
+    fn try_from(value: ClarityValue) -> Result<Self, Self::Error> {
+        match value {
+            ClarityValue::Principal(value) => Ok(value),
+            other => Err(unexpected_type("Principal", &other)),
+        }
+    }
+}
+
+/// Standard principals only, contract principals are rejected.
+impl TryFrom<ClarityValue> for StacksAddress {
+    type Error = ClarityError;
+
+    fn try_from(value: ClarityValue) -> Result<Self, Self::Error> {
+        match value {
+            ClarityValue::Principal(Principal::Standard(address)) => Ok(address),
+            other => Err(unexpected_type("PrincipalStandard", &other)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ADDRESS: &str = "SP3FGQ8Z7JY9BWYZ5WM53E0M9NK7WHJF0691NZ159";
+
+    #[test]
+    fn test_parse_principals() {
+        let standard = Principal::from_str(ADDRESS).unwrap();
+        assert!(!standard.is_contract());
+        assert_eq!(standard.contract_name(), None);
+        assert_eq!(standard.to_string(), ADDRESS);
+        assert_eq!(standard, Principal::from(StacksAddress::from_str(ADDRESS).unwrap()));
+
+        let contract = Principal::from_str(&format!("{ADDRESS}.pox_4-v2")).unwrap();
+        assert!(contract.is_contract());
+        assert_eq!(contract.address(), standard.address());
+        assert_eq!(contract.to_string(), format!("{ADDRESS}.pox_4-v2"));
+
+        let testnet = Principal::from_str("ST2J6ZY48GV1EZ5V2V5RB9MP66SW86PYKKQYAC0RQ.counter").unwrap();
+        assert_eq!(testnet.network(), Some(NetworkKind::Testnet));
+    }
+
+    #[test]
+    fn test_invalid_principals() {
+        for name in ["", "1token", "my.token", "my token", "-token", &"a".repeat(129)] {
+            assert_eq!(
+                Principal::from_str(&format!("{ADDRESS}.{name}")),
+                Err(AddressError::InvalidContractName(name.to_string()))
+            );
+        }
+        assert!(Principal::is_valid_contract_name(&"a".repeat(128)));
+        assert!(matches!(Principal::from_str("SP3FGQ8Z7JY9BWYZ5WM53E0M9NK7WHJF0691NZ158.token"), Err(AddressError::InvalidEncoding(_))));
+        assert!(matches!(Principal::from_str(".token"), Err(AddressError::InvalidEncoding(_))));
+    }
+    #[test]
+    fn test_clarity_principals() {
+        let address = StacksAddress::from_str(ADDRESS).unwrap();
+        let contract = Principal::from_str(&format!("{ADDRESS}.my-token")).unwrap();
+        assert_eq!(ClarityValue::from(address), ClarityValue::Principal(Principal::Standard(address)));
+        assert_eq!(StacksAddress::try_from(ClarityValue::from(address)).unwrap(), address);
+        assert_eq!(Principal::try_from(ClarityValue::from(contract.clone())).unwrap(), contract);
+        assert!(matches!(StacksAddress::try_from(ClarityValue::from(contract.clone())), Err(ClarityError::UnexpectedType(_))));
+        assert!(matches!(Principal::try_from(ClarityValue::UInt(1)), Err(ClarityError::UnexpectedType(_))));
+
+        // contract principal: type 0x06, version, hash, name length and name
+        let bytes = ClarityValue::from(contract.clone()).serialize().unwrap();
+        assert_eq!(hex::encode(&bytes[..2]), "0616");
+        assert_eq!(&bytes[22..], b"\x08my-token");
+        assert_eq!(Principal::try_from(ClarityValue::deserialize(&bytes).unwrap()).unwrap(), contract);
+    }
+}
@@ -0,0 +1,222 @@
+use std::collections::BTreeMap;
+use std::sync::{Arc, OnceLock};
+
+use crate::address::c32::{c32_address, c32_address_decode};
+
+use super::{AddressError, ADDRESS_HASH_LENGTH};
+
+/// Highest version byte of c32 addresses, which spell it as a single c32 digit.
+pub const MAX_C32_VERSION: u8 = 31;
+
+static STANDARD_REGISTRY: OnceLock<AddressRegistry> = OnceLock::new();
+
+/// Encoder/decoder of one textual address format.
+///
+/// Implement it in a downstream crate and [`AddressRegistry::register`] it for the version
+/// bytes it handles to support new address formats without touching this crate.
+pub trait AddressFormat: Send + Sync {
+    /// Short name of the format, used in error messages.
+    fn name(&self) -> &str;
+    fn encode(&self, version: u8, hash: &[u8]) -> Result<String, AddressError>;
+    /// Returns the version byte and the hash carried by `address`.
+    fn decode(&self, address: &str) -> Result<(u8, Vec<u8>), AddressError>;
+}
+
+/// Stacks c32check addresses (`S` + c32check of version and hash160).
+#[derive(Clone, Copy, Debug, Default)]
+pub struct C32Format;
+
+impl AddressFormat for C32Format {
+    fn name(&self) -> &str {
+        "c32"
+    }
+
+    fn encode(&self, version: u8, hash: &[u8]) -> Result<String, AddressError> {
+        if hash.len() != ADDRESS_HASH_LENGTH {
+            return Err(AddressError::InvalidHashLength(hash.len()));
+        }
+        c32_address(version, hash).map_err(|err| AddressError::InvalidEncoding(format!("{err}")))
+    }
+
+    fn decode(&self, address: &str) -> Result<(u8, Vec<u8>), AddressError> {
+        if !address.starts_with('S') {
+            return Err(AddressError::InvalidEncoding(format!("{address} does not start with 'S'")));
+        }
+        let (version, hash) = c32_address_decode(address).map_err(|err| AddressError::InvalidEncoding(format!("{err}")))?;
+        if hash.len() != ADDRESS_HASH_LENGTH {
+            return Err(AddressError::InvalidHashLength(hash.len()));
+        }
+        Ok((version, hash))
+    }
+}
+
+/// Maps version bytes to the [`AddressFormat`] that encodes and decodes them; see
+/// [`StacksAddress::from_str_with`](super::stacks_address::StacksAddress::from_str_with).
+///
+/// Usage:
+/// ```rust
+/// use stacks_rs::address::registry::AddressRegistry;
+/// let registry = AddressRegistry::default();
+/// let address = registry.encode(22, &[0u8; 20]).unwrap();
+/// assert_eq!(registry.decode(&address).unwrap(), (22, vec![0u8; 20]));
+/// ```
+#[derive(Clone)]
+pub struct AddressRegistry {
+    formats: BTreeMap<u8, Arc<dyn AddressFormat>>,
+}
+
+impl Default for AddressRegistry {
+    /// Registry with the c32 format for every version up to [`MAX_C32_VERSION`].
+    fn default() -> Self {
+        let mut registry = Self::empty();
+        let c32: Arc<dyn AddressFormat> = Arc::new(C32Format);
+        for version in 0..=MAX_C32_VERSION {
+            registry.register(version, c32.clone());
+        }
+        registry
+    }
+}
+
+impl AddressRegistry {
+    /// Registry without any format.
+    pub fn empty() -> Self {
+        Self { formats: BTreeMap::new() }
+    }
+
+    /// The default registry, used by the `FromStr` and `Display` of addresses.
+    pub fn standard() -> &'static AddressRegistry {
+        STANDARD_REGISTRY.get_or_init(AddressRegistry::default)
+    }
+
+    /// Handles `version` with `format`, returning the format it replaces.
+    pub fn register(&mut self, version: u8, format: Arc<dyn AddressFormat>) -> Option<Arc<dyn AddressFormat>> {
+        self.formats.insert(version, format)
+    }
+
+    pub fn unregister(&mut self, version: u8) -> Option<Arc<dyn AddressFormat>> {
+        self.formats.remove(&version)
+    }
+
+    pub fn format(&self, version: u8) -> Option<&Arc<dyn AddressFormat>> {
+        self.formats.get(&version)
+    }
+
+    /// Registered version bytes, in ascending order.
+    pub fn versions(&self) -> impl Iterator<Item = u8> + '_ {
+        self.formats.keys().copied()
+    }
+
+    pub fn encode(&self, version: u8, hash: &[u8]) -> Result<String, AddressError> {
+        self.format(version).ok_or(AddressError::UnknownVersion(version))?.encode(version, hash)
+    }
+
+    /// Decodes `address` with the first format that accepts it and is registered for the decoded version.
+    /// A registry of a single format returns the error of that format.
+    pub fn decode(&self, address: &str) -> Result<(u8, Vec<u8>), AddressError> {
+        let mut tried: Vec<&Arc<dyn AddressFormat>> = Vec::new();
+        let mut last_err = AddressError::UnrecognizedAddress(address.to_string());
+        for format in self.formats.values() {
+            if tried.iter().any(|other| Arc::ptr_eq(other, format)) {
+                continue;
+            }
+            tried.push(format);
+            match format.decode(address) {
+                Ok((version, hash)) if self.format(version).is_some_and(|registered| Arc::ptr_eq(registered, format)) => {
+                    return Ok((version, hash));
+                }
+                Ok((version, _)) => last_err = AddressError::UnknownVersion(version),
+                Err(err) => last_err = err,
+            }
+        }
+        match tried.len() {
+            1 => Err(last_err),
+            _ => Err(AddressError::UnrecognizedAddress(address.to_string())),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use crate::address::stacks_address::StacksAddress;
+
+    use super::*;
+
+    const MAINNET_ADDRESS: &str = "SP2J6ZY48GV1EZ5V2V5RB9MP66SW86PYKKNRV9EJ7";
+
+    /// Hex of the version and hash, e.g. `16:00..00`.
+    struct HexFormat;
+
+    impl AddressFormat for HexFormat {
+        fn name(&self) -> &str {
+            "hex"
+        }
+
+        fn encode(&self, version: u8, hash: &[u8]) -> Result<String, AddressError> {
+            Ok(format!("{version:02x}:{}", hex::encode(hash)))
+        }
+
+        fn decode(&self, address: &str) -> Result<(u8, Vec<u8>), AddressError> {
+            let (version, hash) = address.split_once(':').ok_or(AddressError::InvalidEncoding(address.to_string()))?;
+            let version = u8::from_str_radix(version, 16).map_err(|err| AddressError::InvalidEncoding(format!("{err}")))?;
+            let hash = hex::decode(hash).map_err(|err| AddressError::InvalidEncoding(format!("{err}")))?;
+            Ok((version, hash))
+        }
+    }
+
+    #[test]
+    fn test_default_registry() {
+        let registry = AddressRegistry::default();
+        assert_eq!(registry.versions().collect::<Vec<_>>(), (0..=MAX_C32_VERSION).collect::<Vec<_>>());
+
+        let (version, hash) = registry.decode(MAINNET_ADDRESS).unwrap();
+        assert_eq!(version, 22);
+        assert_eq!(registry.encode(version, &hash).unwrap(), MAINNET_ADDRESS);
+
+        assert_eq!(registry.encode(0x30, &hash), Err(AddressError::UnknownVersion(0x30)));
+        assert_eq!(C32Format.encode(22, &hash[1..]), Err(AddressError::InvalidHashLength(19)));
+        assert!(matches!(registry.decode("not an address"), Err(AddressError::InvalidEncoding(_))));
+        assert!(matches!(AddressRegistry::empty().decode(MAINNET_ADDRESS), Err(AddressError::UnrecognizedAddress(_))));
+    }
+
+    #[test]
+    fn test_custom_format() {
+        let mut registry = AddressRegistry::default();
+        registry.register(0x30, Arc::new(HexFormat));
+
+        let address = registry.encode(0x30, &[0xab; 20]).unwrap();
+        assert_eq!(address, format!("30:{}", "ab".repeat(20)));
+        assert_eq!(registry.decode(&address).unwrap(), (0x30, vec![0xab; 20]));
+        assert_eq!(registry.format(0x30).unwrap().name(), "hex");
+
+        // decoded versions must be registered to the format that decoded them
+        assert!(registry.decode("16:00").is_err());
+        assert_eq!(registry.decode(MAINNET_ADDRESS).unwrap().0, 22);
+
+        registry.unregister(22);
+        assert!(matches!(registry.decode(MAINNET_ADDRESS), Err(AddressError::UnrecognizedAddress(_))));
+    }
+
+    #[test]
+    fn test_stacks_address_formats() {
+        let mut registry = AddressRegistry::default();
+        let hex_format: Arc<dyn AddressFormat> = Arc::new(HexFormat);
+        registry.register(0x1f, hex_format.clone());
+        registry.register(0x30, hex_format);
+
+        let encoded = format!("1f:{}", "ab".repeat(20));
+        let address = StacksAddress::from_str_with(&encoded, &registry).unwrap();
+        assert_eq!(address.version(), 0x1f);
+        assert_eq!(address.to_string_with(&registry).unwrap(), encoded);
+        // FromStr and Display stay on the standard registry
+        assert!(address.to_string().starts_with("SZ"));
+        assert_eq!(StacksAddress::from_str(&address.to_string()).unwrap(), address);
+        assert_eq!(StacksAddress::from_str_with(MAINNET_ADDRESS, &registry).unwrap().to_string(), MAINNET_ADDRESS);
+
+        // versions stay single c32 digits
+        let encoded = format!("30:{}", "ab".repeat(20));
+        assert_eq!(StacksAddress::from_str_with(&encoded, &registry), Err(AddressError::UnknownVersion(0x30)));
+        assert_eq!(StacksAddress::from_str_with("1f:abab", &registry), Err(AddressError::InvalidHashLength(2)));
+    }
+}
@@ -0,0 +1,227 @@
+use std::fmt;
+use std::str::FromStr;
+
+use secp256k1::PublicKey;
+
+use crate::bip32::child_number::ChildNumber;
+use crate::crypto::hash::{Hash160, Hasher};
+use crate::crypto::keys::extended_public_key::{ExtendedPublicKey, ExtendedPublicKeyMethods};
+use crate::network::{AddressVersion, NetworkKind};
+
+use super::registry::{AddressRegistry, MAX_C32_VERSION};
+use super::{AddressError, ADDRESS_HASH_LENGTH};
+
+/// Single-sig or multisig, as told by the address version.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AddressType {
+    SingleSig,
+    MultiSig,
+}
+
+/// What [`StacksAddress::validate`] learns from an address string.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct AddressInfo {
+    pub address: StacksAddress,
+    /// Testnet for testnet and mocknet addresses, which share their versions
+    pub network: NetworkKind,
+    pub address_type: AddressType,
+    pub hash_bytes: [u8; ADDRESS_HASH_LENGTH],
+}
+
+impl AddressInfo {
+    /// Fails unless the address can receive funds on `network`.
+    pub fn check_network(&self, network: &NetworkKind) -> Result<(), AddressError> {
+        if self.network.chain_id() != network.chain_id() {
+            return Err(AddressError::WrongNetwork(self.address.to_string()));
+        }
+        Ok(())
+    }
+}
+
+/// Stacks address: a version byte and the hash160 of a public key (single-sig) or of a
+/// multisig redeem script.
+///
+/// Usage:
+/// ```rust
+/// use std::str::FromStr;
+/// use stacks_rs::address::stacks_address::StacksAddress;
+/// use stacks_rs::network::{AddressVersion, NetworkKind};
+/// let address = StacksAddress::from_str("SP2J6ZY48GV1EZ5V2V5RB9MP66SW86PYKKNRV9EJ7").unwrap();
+/// assert_eq!(address.address_version(), Some(AddressVersion::MainnetSingleSig));
+/// assert_eq!(address.to_string(), "SP2J6ZY48GV1EZ5V2V5RB9MP66SW86PYKKNRV9EJ7");
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct StacksAddress {
+    version: u8,
+    hash160: [u8; ADDRESS_HASH_LENGTH],
+}
+
+impl StacksAddress {
+    /// Address with a raw version byte, which must fit a c32 digit (up to [`MAX_C32_VERSION`]).
+    pub fn new(version: u8, hash160: [u8; ADDRESS_HASH_LENGTH]) -> Result<Self, AddressError> {
+        if version > MAX_C32_VERSION {
+            return Err(AddressError::UnknownVersion(version));
+        }
+        Ok(Self { version, hash160 })
+    }
+
+    pub fn from_hash160(hash160: [u8; ADDRESS_HASH_LENGTH], version: AddressVersion) -> Self {
+        Self { version: version.value(), hash160 }
+    }
+
+    /// Address of the compressed `public_key` with `version`.
+    pub fn from_public_key(public_key: &PublicKey, version: AddressVersion) -> Self {
+        Self::from_hash160(Hash160::hash(&public_key.serialize()), version)
+    }
+
+    /// Single-sig address of `public_key` on `network`.
+    pub fn single_sig(public_key: &PublicKey, network: &NetworkKind) -> Self {
+        Self::from_public_key(public_key, AddressVersion::single_sig(network))
+    }
+
+    /// Single-sig address of the non-hardened child `path_index` of `key`,
+    /// e.g. the `index`th address of an account's external chain key.
+    pub fn from_extended_public_key(key: &ExtendedPublicKey, path_index: u32, network: &NetworkKind) -> Result<Self, AddressError> {
+        let child_number = ChildNumber::new(path_index).map_err(|_| AddressError::InvalidChildIndex(path_index))?;
+        if child_number.is_hardened {
+            return Err(AddressError::InvalidChildIndex(path_index));
+        }
+        let child = key.derive_child(child_number).map_err(|_| AddressError::InvalidChildIndex(path_index))?;
+        Ok(Self::single_sig(child.public_key(), network))
+    }
+
+    /// Parses `address` with the formats of `registry` instead of the standard one.
+    pub fn from_str_with(address: &str, registry: &AddressRegistry) -> Result<Self, AddressError> {
+        let (version, hash) = registry.decode(address)?;
+        let hash160 = hash.as_slice().try_into().map_err(|_| AddressError::InvalidHashLength(hash.len()))?;
+        Self::new(version, hash160)
+    }
+
+    /// Encodes the address with the format `registry` has for its version.
+    pub fn to_string_with(&self, registry: &AddressRegistry) -> Result<String, AddressError> {
+        registry.encode(self.version, &self.hash160)
+    }
+
+    pub fn version(&self) -> u8 {
+        self.version
+    }
+
+    pub fn hash160(&self) -> &[u8; ADDRESS_HASH_LENGTH] {
+        &self.hash160
+    }
+
+    /// Known Stacks version of the address, `None` for other version bytes.
+    pub fn address_version(&self) -> Option<AddressVersion> {
+        AddressVersion::from_value(self.version)
+    }
+
+    pub fn network(&self) -> Option<NetworkKind> {
+        self.address_version().map(|version| version.network())
+    }
+
+    pub fn is_multi_sig(&self) -> bool {
+        self.address_version().is_some_and(|version| version.is_multi_sig())
+    }
+
+    /// Parses `address` and rejects versions that are not one of the four Stacks versions,
+    /// e.g. to check a withdrawal destination before building the transaction.
+    ///
+    /// Usage:
+    /// ```rust
+    /// use stacks_rs::address::stacks_address::{AddressType, StacksAddress};
+    /// use stacks_rs::network::NetworkKind;
+    /// let info = StacksAddress::validate("SM2J6ZY48GV1EZ5V2V5RB9MP66SW86PYKKQVX8X0G").unwrap();
+    /// assert_eq!(info.network, NetworkKind::Mainnet);
+    /// assert_eq!(info.address_type, AddressType::MultiSig);
+    /// assert!(info.check_network(&NetworkKind::Testnet).is_err());
+    /// ```
+    pub fn validate(address: &str) -> Result<AddressInfo, AddressError> {
+        let parsed = Self::from_str(address)?;
+        let version = parsed.address_version().ok_or(AddressError::UnknownVersion(parsed.version))?;
+        let address_type = if version.is_multi_sig() { AddressType::MultiSig } else { AddressType::SingleSig };
+        Ok(AddressInfo { address: parsed, network: version.network(), address_type, hash_bytes: parsed.hash160 })
+    }
+}
+
+impl fmt::Display for StacksAddress {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        // the version is checked on construction, and the standard registry encodes all of them
+        f.write_str(&self.to_string_with(AddressRegistry::standard()).unwrap())
+    }
+}
+
+impl FromStr for StacksAddress {
+    type Err = AddressError;
+
+    fn from_str(address: &str) -> Result<Self, Self::Err> {
+        Self::from_str_with(address, AddressRegistry::standard())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bip32::derivation_path::DerivationPath;
+    use crate::crypto::keys::extended_private_key::{ExtendedPrivateKey, ExtendedPrivateKeyMethods};
+    use crate::crypto::keys::stacks_key::StacksKeyMethods;
+
+    #[test]
+    fn test_versions() {
+        let hash = hex::decode("a46ff88886c2ef9762d970b4d2c63678835bd39d").unwrap().try_into().unwrap();
+        let vectors = [
+            (AddressVersion::MainnetSingleSig, "SP2J6ZY48GV1EZ5V2V5RB9MP66SW86PYKKNRV9EJ7"),
+            (AddressVersion::MainnetMultiSig, "SM2J6ZY48GV1EZ5V2V5RB9MP66SW86PYKKQVX8X0G"),
+            (AddressVersion::TestnetSingleSig, "ST2J6ZY48GV1EZ5V2V5RB9MP66SW86PYKKQYAC0RQ"),
+            (AddressVersion::TestnetMultiSig, "SN2J6ZY48GV1EZ5V2V5RB9MP66SW86PYKKP6D2ZK9"),
+        ];
+        for (version, expected) in vectors {
+            let address = StacksAddress::from_hash160(hash, version);
+            assert_eq!(address.to_string(), expected);
+            assert_eq!(StacksAddress::from_str(expected).unwrap(), address);
+            assert_eq!(address.address_version(), Some(version));
+            assert_eq!(address.network(), Some(version.network()));
+            assert_eq!(address.is_multi_sig(), version.is_multi_sig());
+        }
+
+        let other = StacksAddress::new(0, hash).unwrap();
+        assert_eq!(other.to_string(), "S02J6ZY48GV1EZ5V2V5RB9MP66SW86PYKKPVKG2CE");
+        assert_eq!(other.address_version(), None);
+        assert_eq!(StacksAddress::new(32, hash), Err(AddressError::UnknownVersion(32)));
+        assert!(matches!(StacksAddress::from_str("SP2J6ZY48GV1EZ5V2V5RB9MP66SW86PYKKNRV9EJ8"), Err(AddressError::InvalidEncoding(_))));
+    }
+
+    #[test]
+    fn test_validate() {
+        let info = StacksAddress::validate("ST2J6ZY48GV1EZ5V2V5RB9MP66SW86PYKKQYAC0RQ").unwrap();
+        assert_eq!(info.network, NetworkKind::Testnet);
+        assert_eq!(info.address_type, AddressType::SingleSig);
+        assert_eq!(hex::encode(info.hash_bytes), "a46ff88886c2ef9762d970b4d2c63678835bd39d");
+        assert!(info.check_network(&NetworkKind::Testnet).is_ok());
+        assert!(info.check_network(&NetworkKind::Mocknet).is_ok());
+        assert_eq!(
+            info.check_network(&NetworkKind::Mainnet),
+            Err(AddressError::WrongNetwork(String::from("ST2J6ZY48GV1EZ5V2V5RB9MP66SW86PYKKQYAC0RQ")))
+        );
+
+        assert_eq!(StacksAddress::validate("S02J6ZY48GV1EZ5V2V5RB9MP66SW86PYKKPVKG2CE"), Err(AddressError::UnknownVersion(0)));
+        assert!(matches!(StacksAddress::validate("SP2J6ZY48GV1EZ5V2V5RB9MP66SW86PYKKNRV9EJ8"), Err(AddressError::InvalidEncoding(_))));
+        assert!(matches!(StacksAddress::validate("SP2J6ZY48GV1EZ5V2V5RB9MP66SW86PYKK"), Err(AddressError::InvalidEncoding(_) | AddressError::InvalidHashLength(_))));
+    }
+
+    #[test]
+    fn test_from_keys() {
+        let seed = hex::decode("000102030405060708090a0b0c0d0e0f").unwrap();
+        let account = ExtendedPrivateKey::derive_from_path(&seed, DerivationPath::from_str("m/44'/5757'/0'/0").unwrap());
+        let child = ExtendedPrivateKey::derive_from_path(&seed, DerivationPath::from_str("m/44'/5757'/0'/0/3").unwrap());
+
+        let expected = StacksAddress::single_sig(&child.public_key(), &NetworkKind::Mainnet);
+        assert_eq!(expected.to_string(), child.public_key().stacks_address(&NetworkKind::Mainnet));
+        let xpub = ExtendedPublicKey::from(&account);
+        assert_eq!(StacksAddress::from_extended_public_key(&xpub, 3, &NetworkKind::Mainnet).unwrap(), expected);
+        assert_eq!(
+            StacksAddress::from_extended_public_key(&xpub, 0x8000_0000, &NetworkKind::Mainnet),
+            Err(AddressError::InvalidChildIndex(0x8000_0000))
+        );
+        assert!(StacksAddress::from_extended_public_key(&xpub, 3, &NetworkKind::Testnet).unwrap().to_string().starts_with("ST"));
+    }
+}
@@ -67,6 +67,15 @@ impl FromStr for ChildNumber {
     }
 }
 
+impl fmt::Display for ChildNumber {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        match self.is_hardened {
+            true => write!(f, "{}'", self.index - INDEX_THRESHOLD),
+            false => write!(f, "{}", self.index),
+        }
+    }
+}
+
 impl PartialEq for ChildNumber {
     fn eq(&self, other: &Self) -> bool {
         self.index == other.index && self.is_hardened == other.is_hardened
@@ -102,6 +111,12 @@ mod tests {
         assert_eq!(hardened_child_number.is_hardened, true);
     }
 
+    #[test]
+    fn test_child_number_to_string() {
+        assert_eq!(ChildNumber::from_str("44'").unwrap().to_string(), "44'");
+        assert_eq!(ChildNumber::from_str("7").unwrap().to_string(), "7");
+    }
+
     #[test]
     fn test_child_number_exceeds_max() {
         let index = "4294967299";
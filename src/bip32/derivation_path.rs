@@ -1,18 +1,72 @@
-use std::str::FromStr;
+use std::{fmt, str::FromStr};
 
 use super::child_number::{ChildNumber, ChildNumberError};
 
 const PATH_PREFIX: &str = "m/";
 pub const MAX_DEPTH: usize = 5;
+/// Depth of the account keys in BIP44-like paths (`m/purpose'/coin_type'/account'`).
+pub const ACCOUNT_DEPTH: u8 = 3;
 
 #[derive(Clone, Copy, Debug)]
 pub enum Error {
     MaxDepthExceeded,
     WrongPathPrefix,
     InvalidPathIndex(ChildNumberError),
-    CannotParseindex
+    CannotParseindex,
+    /// Non-hardened step at this depth, rejected by the [`DerivationPolicy`]
+    NonHardenedStep(u8),
 }
 
+/// Which steps of a derivation must be hardened.
+///
+/// Anyone holding an xpub and any non-hardened child private key can compute the
+/// parent private key, so hardening every step down to the account level keeps a
+/// leaked address key from compromising the whole wallet.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum DerivationPolicy {
+    /// Any step can be non-hardened
+    #[default]
+    Any,
+    /// Every step down to this depth (included) must be hardened
+    HardenedUntil(u8),
+}
+
+impl DerivationPolicy {
+    /// Hardened purpose, coin type and account, as BIP44 mandates.
+    pub const fn bip44() -> Self {
+        DerivationPolicy::HardenedUntil(ACCOUNT_DEPTH)
+    }
+
+    /// Whether the step `child_number` producing a key at `depth` is allowed (the master key is at depth 0).
+    pub fn allows(&self, depth: u8, child_number: &ChildNumber) -> bool {
+        match *self {
+            DerivationPolicy::Any => true,
+            DerivationPolicy::HardenedUntil(max_depth) => depth > max_depth || child_number.is_hardened,
+        }
+    }
+}
+
+impl DerivationPath {
+    /// Checks every step of the path, starting from the master key.
+    pub fn check_policy(&self, policy: &DerivationPolicy) -> Result<(), Error> {
+        for (i, child_number) in self.path.iter().enumerate() {
+            let depth = i as u8 + 1;
+            if !policy.allows(depth, child_number) {
+                return Err(Error::NonHardenedStep(depth));
+            }
+        }
+        Ok(())
+    }
+
+    /// Same as [`DerivationPath::from_str`], also checking `policy`.
+    pub fn from_str_with_policy(s: &str, policy: &DerivationPolicy) -> Result<Self, Error> {
+        let path = Self::from_str(s)?;
+        path.check_policy(policy)?;
+        Ok(path)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
 pub struct DerivationPath {
     pub path: Vec<ChildNumber>
 }
@@ -44,6 +98,15 @@ impl FromStr for DerivationPath {
     }
 }
 
+impl fmt::Display for DerivationPath {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        f.write_str("m")?;
+        for child_number in &self.path {
+            write!(f, "/{child_number}")?;
+        }
+        Ok(())
+    }
+}
 
 mod tests {
     use super::*;
@@ -63,6 +126,12 @@ mod tests {
         assert_eq!(derivation_path.path[4], normal_0);
     }
 
+    #[test]
+    fn test_derivation_path_to_string() {
+        let str_path = "m/44'/5757'/0'/0/3";
+        assert_eq!(DerivationPath::from_str(&str_path).unwrap().to_string(), str_path);
+    }
+
     #[test]
     fn test_short_derivation_path() {
         let str_path = "m/44'/0'/0";
@@ -112,6 +181,17 @@ mod tests {
         DerivationPath::from_str(&str_path).unwrap();
     }
 
+    #[test]
+    fn test_derivation_policy() {
+        let policy = DerivationPolicy::bip44();
+        assert!(DerivationPath::from_str_with_policy("m/44'/5757'/0'/0/0", &policy).is_ok());
+        assert!(matches!(
+            DerivationPath::from_str_with_policy("m/44'/5757'/0/0/0", &policy),
+            Err(Error::NonHardenedStep(3))
+        ));
+        assert!(DerivationPath::from_str_with_policy("m/44'/5757'/0/0/0", &DerivationPolicy::Any).is_ok());
+    }
+
     #[test]
     #[should_panic]
     fn test_derivation_path_no_path_prefix() {
@@ -61,7 +61,7 @@ impl FromStr for ExtendedKey {
         
         Ok(Self { 
             version: version, 
-            attrs: ExtendedKeyAttrs::new(*depth, parent_fingerprint, child_number), 
+            attrs: ExtendedKeyAttrs { metadata: version.metadata(), ..ExtendedKeyAttrs::new(*depth, parent_fingerprint, child_number) }, 
             chain_code: chain_code, 
             key_bytes: key 
         })
@@ -94,10 +94,14 @@ impl TryFrom<ExtendedKey> for ExtendedPublicKey {
     }
 }
 
+#[cfg(test)]
 mod tests {
     use std::str::FromStr;
 
+    use crate::bip32::key_version::{AddressScheme, KeyMetadata};
+    use crate::crypto::keys::derivable::DerivationError;
     use crate::crypto::keys::{extended_private_key::ExtendedPrivateKeyMethods, extended_public_key::ExtendedPublicKeyMethods};
+    use crate::network::NetworkKind;
 
     use super::*;
 
@@ -107,53 +111,155 @@ mod tests {
     fn test_new_extended_private_key() {
         let seed = hex::decode("000102030405060708090a0b0c0d0e0f").unwrap();
         let master_key = ExtendedPrivateKey::new(&seed).unwrap();
-        let b58_master_key = master_key.to_extended_key(Version::XPrv).b58_encode();
+        let b58_master_key = master_key.to_extended_key().b58_encode();
         assert_eq!(b58_master_key, "xprv9s21ZrQH143K3QTDL4LXw2F7HEK3wJUD2nW2nRk4stbPy6cq3jPPqjiChkVvvNKmPGJxWUtg6LnF5kejMRNNU3TGtRBeJgk33yuGBxrMPHi");
         let master_pub_key = ExtendedPublicKey::try_from(&master_key).unwrap();
-        let b58_master_pub_key = master_pub_key.to_extended_key(Version::XPub).b58_encode();
+        let b58_master_pub_key = master_pub_key.to_extended_key().b58_encode();
         assert_eq!(b58_master_pub_key, "xpub661MyMwAqRbcFtXgS5sYJABqqG9YLmC4Q1Rdap9gSE8NqtwybGhePY2gZ29ESFjqJoCu1Rupje8YtGqsefD265TMg7usUDFdp6W1EGMcet8");
 
         // m/0'
         let purpose_0_h = master_key.derive_child(ChildNumber::from_str("0'").unwrap());
-        let b58_purpose_0_h = purpose_0_h.to_extended_key(Version::XPrv).b58_encode();
+        let b58_purpose_0_h = purpose_0_h.to_extended_key().b58_encode();
         assert_eq!(b58_purpose_0_h, "xprv9uHRZZhk6KAJC1avXpDAp4MDc3sQKNxDiPvvkX8Br5ngLNv1TxvUxt4cV1rGL5hj6KCesnDYUhd7oWgT11eZG7XnxHrnYeSvkzY7d2bhkJ7");
         let purpose_0_h_pub = ExtendedPublicKey::try_from(&purpose_0_h).unwrap();
-        let b58_purpose_0_h_pub = purpose_0_h_pub.to_extended_key(Version::XPub).b58_encode();
+        let b58_purpose_0_h_pub = purpose_0_h_pub.to_extended_key().b58_encode();
         assert_eq!(b58_purpose_0_h_pub, "xpub68Gmy5EdvgibQVfPdqkBBCHxA5htiqg55crXYuXoQRKfDBFA1WEjWgP6LHhwBZeNK1VTsfTFUHCdrfp1bgwQ9xv5ski8PX9rL2dZXvgGDnw");
 
 
         // m/0'/1
         let coin_1 = purpose_0_h.derive_child(ChildNumber::from_str("1").unwrap());
-        let b58_coin_1 = coin_1.to_extended_key(Version::XPrv).b58_encode();
+        let b58_coin_1 = coin_1.to_extended_key().b58_encode();
         assert_eq!(b58_coin_1, "xprv9wTYmMFdV23N2TdNG573QoEsfRrWKQgWeibmLntzniatZvR9BmLnvSxqu53Kw1UmYPxLgboyZQaXwTCg8MSY3H2EU4pWcQDnRnrVA1xe8fs");
         let coin_1_pub = ExtendedPublicKey::try_from(&coin_1).unwrap();
-        let b58_coin_1_pub = coin_1_pub.to_extended_key(Version::XPub).b58_encode();
+        let b58_coin_1_pub = coin_1_pub.to_extended_key().b58_encode();
         assert_eq!(b58_coin_1_pub, "xpub6ASuArnXKPbfEwhqN6e3mwBcDTgzisQN1wXN9BJcM47sSikHjJf3UFHKkNAWbWMiGj7Wf5uMash7SyYq527Hqck2AxYysAA7xmALppuCkwQ");
 
         // m/0'/1/2'
         let account_2_h = coin_1.derive_child(ChildNumber::from_str("2'").unwrap());
-        let b58_account_2_h = account_2_h.to_extended_key(Version::XPrv).b58_encode();
+        let b58_account_2_h = account_2_h.to_extended_key().b58_encode();
         assert_eq!(b58_account_2_h, "xprv9z4pot5VBttmtdRTWfWQmoH1taj2axGVzFqSb8C9xaxKymcFzXBDptWmT7FwuEzG3ryjH4ktypQSAewRiNMjANTtpgP4mLTj34bhnZX7UiM");
         let account_2_h_pub = ExtendedPublicKey::try_from(&account_2_h).unwrap();
-        let b58_account_2_h_pub = account_2_h_pub.to_extended_key(Version::XPub).b58_encode();
+        let b58_account_2_h_pub = account_2_h_pub.to_extended_key().b58_encode();
         assert_eq!(b58_account_2_h_pub, "xpub6D4BDPcP2GT577Vvch3R8wDkScZWzQzMMUm3PWbmWvVJrZwQY4VUNgqFJPMM3No2dFDFGTsxxpG5uJh7n7epu4trkrX7x7DogT5Uv6fcLW5");
 
         // m/0'/1/2'/2
         let change_2 = account_2_h.derive_child(ChildNumber::from_str("2").unwrap());
-        let b58_change_2 = change_2.to_extended_key(Version::XPrv).b58_encode();
+        let b58_change_2 = change_2.to_extended_key().b58_encode();
         assert_eq!(b58_change_2, "xprvA2JDeKCSNNZky6uBCviVfJSKyQ1mDYahRjijr5idH2WwLsEd4Hsb2Tyh8RfQMuPh7f7RtyzTtdrbdqqsunu5Mm3wDvUAKRHSC34sJ7in334");
         let change_2_pub = ExtendedPublicKey::try_from(&change_2).unwrap();
-        let b58_change_2_pub = change_2_pub.to_extended_key(Version::XPub).b58_encode();
+        let b58_change_2_pub = change_2_pub.to_extended_key().b58_encode();
         assert_eq!(b58_change_2_pub, "xpub6FHa3pjLCk84BayeJxFW2SP4XRrFd1JYnxeLeU8EqN3vDfZmbqBqaGJAyiLjTAwm6ZLRQUMv1ZACTj37sR62cfN7fe5JnJ7dh8zL4fiyLHV");
 
 
         // m/0'/1/2'/2
         let address_1000000000 = change_2.derive_child(ChildNumber::from_str("1000000000").unwrap());
-        let b58_address_1000000000 = address_1000000000.to_extended_key(Version::XPrv).b58_encode();
+        let b58_address_1000000000 = address_1000000000.to_extended_key().b58_encode();
         assert_eq!(b58_address_1000000000, "xprvA41z7zogVVwxVSgdKUHDy1SKmdb533PjDz7J6N6mV6uS3ze1ai8FHa8kmHScGpWmj4WggLyQjgPie1rFSruoUihUZREPSL39UNdE3BBDu76");
         let address_1000000000_pub = ExtendedPublicKey::try_from(&address_1000000000).unwrap();
-        let b58_address_1000000000_pub = address_1000000000_pub.to_extended_key(Version::XPub).b58_encode();
+        let b58_address_1000000000_pub = address_1000000000_pub.to_extended_key().b58_encode();
         assert_eq!(b58_address_1000000000_pub, "xpub6H1LXWLaKsWFhvm6RVpEL9P4KfRZSW7abD2ttkWP3SSQvnyA8FSVqNTEcYFgJS2UaFcxupHiYkro49S8yGasTvXEYBVPamhGW6cFJodrTHy");
 
-    }   
-}
\ No newline at end of file
+    }
+
+    #[test]
+    fn test_derive_children_matches_derive_child() {
+        let seed = hex::decode("000102030405060708090a0b0c0d0e0f").unwrap();
+        let master_key = ExtendedPrivateKey::new(&seed).unwrap();
+        let master_pub_key = ExtendedPublicKey::from(&master_key);
+
+        let children = master_key.derive_children(0..3).collect::<Vec<_>>();
+        assert_eq!(children.len(), 3);
+        for (index, child) in children.iter().enumerate() {
+            let expected = master_key.derive_child(ChildNumber::new(index as u32).unwrap());
+            assert_eq!(child.to_extended_key().b58_encode(), expected.to_extended_key().b58_encode());
+        }
+
+        let pub_children = master_pub_key.derive_children(0..3).collect::<Vec<_>>();
+        for (child, priv_child) in pub_children.iter().zip(children.iter()) {
+            let expected = ExtendedPublicKey::from(priv_child);
+            assert_eq!(child.as_ref().unwrap().to_extended_key().b58_encode(), expected.to_extended_key().b58_encode());
+        }
+
+        let hardened = master_pub_key.derive_children(2147483648..2147483649).next().unwrap();
+        assert_eq!(hardened.err(), Some(DerivationError::HardenedFromPublicKey));
+    }
+
+    #[cfg(feature = "rayon")]
+    #[test]
+    fn test_derive_children_par_keeps_order() {
+        let seed = hex::decode("000102030405060708090a0b0c0d0e0f").unwrap();
+        let master_key = ExtendedPrivateKey::new(&seed).unwrap();
+        let master_pub_key = ExtendedPublicKey::from(&master_key);
+
+        let sequential = master_key.derive_children(0..64).map(|child| child.to_extended_key().b58_encode()).collect::<Vec<_>>();
+        let parallel = master_key.derive_children_par(0..64).iter().map(|child| child.to_extended_key().b58_encode()).collect::<Vec<_>>();
+        assert_eq!(sequential, parallel);
+
+        let sequential_pub = master_pub_key.derive_children(0..64).map(|child| child.unwrap().to_extended_key().b58_encode()).collect::<Vec<_>>();
+        let parallel_pub = master_pub_key.derive_children_par(0..64).into_iter().map(|child| child.unwrap().to_extended_key().b58_encode()).collect::<Vec<_>>();
+        assert_eq!(sequential_pub, parallel_pub);
+    }
+
+    #[test]
+    fn test_raw_bytes_roundtrip() {
+        let seed = hex::decode("000102030405060708090a0b0c0d0e0f").unwrap();
+        let xprv = ExtendedPrivateKey::new(&seed).unwrap().derive_child(ChildNumber::from_str("0'").unwrap());
+
+        let from_hex = ExtendedPrivateKey::from_hex(&xprv.to_hex(), xprv.attrs).unwrap();
+        assert_eq!(from_hex.to_extended_key().b58_encode(), xprv.to_extended_key().b58_encode());
+        assert!(from_hex == xprv);
+        assert!(from_hex != xprv.derive_child(ChildNumber::from_str("0").unwrap()));
+
+        let xpub = ExtendedPublicKey::from(&xprv);
+        let from_hex = ExtendedPublicKey::from_hex(&xpub.to_hex(), xpub.attrs).unwrap();
+        assert_eq!(from_hex.to_extended_key().b58_encode(), xpub.to_extended_key().b58_encode());
+    }
+
+    #[test]
+    fn test_raw_bytes_validation() {
+        use crate::crypto::keys::key_bytes::KeyBytesError;
+
+        let chain_code = [0u8; KEY_LENGHT];
+        assert!(matches!(
+            ExtendedPrivateKey::from_parts(&[1u8; 31], &chain_code, ExtendedKeyAttrs::default()),
+            Err(KeyBytesError::InvalidLength { expected: 32, got: 31 })
+        ));
+        // zero and the curve order are out of range
+        assert!(matches!(
+            ExtendedPrivateKey::from_parts(&[0u8; KEY_LENGHT], &chain_code, ExtendedKeyAttrs::default()),
+            Err(KeyBytesError::InvalidKey(_))
+        ));
+        let order = hex::decode("fffffffffffffffffffffffffffffffebaaedce6af48a03bbfd25e8cd0364141").unwrap();
+        assert!(matches!(
+            ExtendedPrivateKey::from_parts(&order, &chain_code, ExtendedKeyAttrs::default()),
+            Err(KeyBytesError::InvalidKey(_))
+        ));
+        assert!(matches!(
+            ExtendedPublicKey::from_parts(&[5u8; EXTENDED_KEY_LENGHT], &chain_code, ExtendedKeyAttrs::default()),
+            Err(KeyBytesError::InvalidKey(_))
+        ));
+        assert!(matches!(
+            ExtendedPublicKey::from_hex("zz", ExtendedKeyAttrs::default()),
+            Err(KeyBytesError::InvalidHex(_))
+        ));
+    }
+
+    #[test]
+    fn test_to_extended_key_uses_metadata() {
+        let seed = hex::decode("000102030405060708090a0b0c0d0e0f").unwrap();
+        let metadata = KeyMetadata::new(NetworkKind::Testnet, AddressScheme::NativeSegwit);
+        let master_key = ExtendedPrivateKey::new(&seed).unwrap().with_metadata(metadata);
+        let child = master_key.derive_child(ChildNumber::from_str("84'").unwrap());
+
+        let b58_child = child.to_extended_key().b58_encode();
+        assert!(b58_child.starts_with("vprv"));
+        let b58_child_pub = ExtendedPublicKey::from(&child).to_extended_key().b58_encode();
+        assert!(b58_child_pub.starts_with("vpub"));
+        assert!(child.to_extended_key_with_version(Version::XPrv).b58_encode().starts_with("xprv"));
+
+        let parsed = ExtendedKey::from_str(&b58_child_pub).unwrap();
+        assert_eq!(parsed.version, Version::VPub);
+        let parsed_pub = ExtendedPublicKey::try_from(parsed).unwrap();
+        assert_eq!(parsed_pub.to_extended_key().b58_encode(), b58_child_pub);
+    }
+}
@@ -1,7 +1,10 @@
 use core::{error, fmt};
 
+use crate::network::NetworkKind;
+
 const VERSION_LEN: usize = 4;
 
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum Version {
     XPrv,
     XPub,
@@ -11,9 +14,110 @@ pub enum Version {
     ZPub,
     YPrv,
     YPub,
+    UPrv,
+    UPub,
+    VPrv,
+    VPub,
+}
+
+/// Address scheme the keys derived from an extended key are meant for (SLIP-132).
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum AddressScheme {
+    /// P2PKH (BIP44): `xprv`/`xpub`, `tprv`/`tpub` on testnet
+    #[default]
+    Legacy,
+    /// P2WPKH nested in P2SH (BIP49): `yprv`/`ypub`, `uprv`/`upub` on testnet
+    NestedSegwit,
+    /// Native P2WPKH (BIP84): `zprv`/`zpub`, `vprv`/`vpub` on testnet
+    NativeSegwit,
+}
+
+impl AddressScheme {
+    /// Scheme matching the BIP43 `purpose` of a derivation path (`44'`, `49'` or `84'`).
+    pub fn from_purpose(purpose: u32) -> Option<Self> {
+        match purpose {
+            44 => Some(AddressScheme::Legacy),
+            49 => Some(AddressScheme::NestedSegwit),
+            84 => Some(AddressScheme::NativeSegwit),
+            _ => None,
+        }
+    }
+}
+
+/// Network and address scheme of an extended key, used to pick its [`Version`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct KeyMetadata {
+    pub network: NetworkKind,
+    pub scheme: AddressScheme,
+}
+
+impl KeyMetadata {
+    pub fn new(network: NetworkKind, scheme: AddressScheme) -> Self {
+        Self { network, scheme }
+    }
+}
+
+impl Default for KeyMetadata {
+    fn default() -> Self {
+        Self { network: NetworkKind::Mainnet, scheme: AddressScheme::Legacy }
+    }
 }
 
 impl Version {
+    const ALL: [Version; 12] = [
+        Version::XPrv,
+        Version::XPub,
+        Version::TPrv,
+        Version::TPub,
+        Version::ZPrv,
+        Version::ZPub,
+        Version::YPrv,
+        Version::YPub,
+        Version::UPrv,
+        Version::UPub,
+        Version::VPrv,
+        Version::VPub,
+    ];
+
+    /// Returns the SLIP-132 version for a private (or public) key with the given `metadata`.
+    pub fn from_metadata(metadata: &KeyMetadata, is_private: bool) -> Self {
+        let mainnet = metadata.network == NetworkKind::Mainnet;
+        match (metadata.scheme, mainnet, is_private) {
+            (AddressScheme::Legacy, true, true) => Version::XPrv,
+            (AddressScheme::Legacy, true, false) => Version::XPub,
+            (AddressScheme::Legacy, false, true) => Version::TPrv,
+            (AddressScheme::Legacy, false, false) => Version::TPub,
+            (AddressScheme::NestedSegwit, true, true) => Version::YPrv,
+            (AddressScheme::NestedSegwit, true, false) => Version::YPub,
+            (AddressScheme::NestedSegwit, false, true) => Version::UPrv,
+            (AddressScheme::NestedSegwit, false, false) => Version::UPub,
+            (AddressScheme::NativeSegwit, true, true) => Version::ZPrv,
+            (AddressScheme::NativeSegwit, true, false) => Version::ZPub,
+            (AddressScheme::NativeSegwit, false, true) => Version::VPrv,
+            (AddressScheme::NativeSegwit, false, false) => Version::VPub,
+        }
+    }
+
+    /// Network and address scheme encoded by this version.
+    pub fn metadata(&self) -> KeyMetadata {
+        let (network, scheme) = match *self {
+            Version::XPrv | Version::XPub => (NetworkKind::Mainnet, AddressScheme::Legacy),
+            Version::TPrv | Version::TPub => (NetworkKind::Testnet, AddressScheme::Legacy),
+            Version::YPrv | Version::YPub => (NetworkKind::Mainnet, AddressScheme::NestedSegwit),
+            Version::UPrv | Version::UPub => (NetworkKind::Testnet, AddressScheme::NestedSegwit),
+            Version::ZPrv | Version::ZPub => (NetworkKind::Mainnet, AddressScheme::NativeSegwit),
+            Version::VPrv | Version::VPub => (NetworkKind::Testnet, AddressScheme::NativeSegwit),
+        };
+        KeyMetadata::new(network, scheme)
+    }
+
+    pub fn is_private(&self) -> bool {
+        matches!(
+            *self,
+            Version::XPrv | Version::TPrv | Version::YPrv | Version::UPrv | Version::ZPrv | Version::VPrv
+        )
+    }
+
     pub fn to_bytes(&self) -> [u8; VERSION_LEN] {
         fn convert<'a>(version_string: &str, buf: &'a mut [u8; VERSION_LEN]) {
             buf.copy_from_slice(&hex::decode(version_string).unwrap());
@@ -52,6 +156,22 @@ impl Version {
                 convert("049d7cb2", &mut version_bytes);
                 version_bytes
             },
+            Version::UPrv => {
+                convert("044a4e28", &mut version_bytes);
+                version_bytes
+            },
+            Version::UPub => {
+                convert("044a5262", &mut version_bytes);
+                version_bytes
+            },
+            Version::VPrv => {
+                convert("045f18bc", &mut version_bytes);
+                version_bytes
+            },
+            Version::VPub => {
+                convert("045f1cf6", &mut version_bytes);
+                version_bytes
+            },
         }
     }
 
@@ -65,6 +185,10 @@ impl Version {
             Version::ZPub => "zpub",
             Version::YPrv => "yprv",
             Version::YPub => "ypub",
+            Version::UPrv => "uprv",
+            Version::UPub => "upub",
+            Version::VPrv => "vprv",
+            Version::VPub => "vpub",
         }
     }
 }
@@ -76,17 +200,10 @@ impl TryFrom<&[u8]> for Version {
         if value.len() != VERSION_LEN {
             Err(Error::VersionTooShort)
         } else {
-            match hex::encode(value).as_str() {
-                "xprv" => Ok(Version::XPrv),
-                "xpub" => Ok(Version::XPub),
-                "tprv" => Ok(Version::TPrv),
-                "tpub" => Ok(Version::TPub),
-                "zprv" => Ok(Version::ZPrv),
-                "zpub" => Ok(Version::ZPub),
-                "yprv" => Ok(Version::YPrv),
-                "ypub" => Ok(Version::YPub),
-                _ => Err(Error::InvalidVersion)
-            }
+            Version::ALL
+                .into_iter()
+                .find(|version| version.to_bytes()[..] == value[..])
+                .ok_or(Error::InvalidVersion)
         }
     }
 }
@@ -107,3 +224,27 @@ impl fmt::Display for Error {
 }
 
 impl error::Error for Error { }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_version_from_bytes() {
+        for version in Version::ALL {
+            assert_eq!(Version::try_from(&version.to_bytes()[..]).unwrap(), version);
+        }
+        assert!(matches!(Version::try_from(&[0u8; 4][..]), Err(Error::InvalidVersion)));
+    }
+
+    #[test]
+    fn test_version_from_metadata() {
+        for version in Version::ALL {
+            assert_eq!(Version::from_metadata(&version.metadata(), version.is_private()), version);
+        }
+        let testnet_native = KeyMetadata::new(NetworkKind::Testnet, AddressScheme::NativeSegwit);
+        assert_eq!(Version::from_metadata(&testnet_native, false), Version::VPub);
+        let mocknet_legacy = KeyMetadata::new(NetworkKind::Mocknet, AddressScheme::Legacy);
+        assert_eq!(Version::from_metadata(&mocknet_legacy, true), Version::TPrv);
+    }
+}
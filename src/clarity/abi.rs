@@ -0,0 +1,326 @@
+//! Contract interfaces as served by a node's `/v2/contracts/interface/{address}/{name}`.
+//!
+//! Types are read into [`ClarityType`], so arguments can be checked before a call is built.
+//!
+//! Usage:
+//! ```rust
+//! use stacks_rs::clarity::abi::ContractAbi;
+//! use stacks_rs::clarity::value::ClarityValue;
+//! let abi = ContractAbi::from_json(r#"{"functions":[{"name":"get-balance","access":"read_only",
+//!     "args":[{"name":"who","type":"principal"}],
+//!     "outputs":{"type":{"response":{"ok":"uint128","error":"none"}}}}],
+//!     "variables":[],"maps":[],"fungible_tokens":[],"non_fungible_tokens":[]}"#).unwrap();
+//! let function = abi.function("get-balance").unwrap();
+//! assert_eq!(function.outputs.to_string(), "(response uint UnknownType)");
+//! assert!(function.check_args(&[ClarityValue::from(1u64)]).is_err());
+//! ```
+
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+
+use super::types::{check_type, ClarityType};
+use super::value::ClarityValue;
+use super::ClarityError;
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ContractAbi {
+    pub functions: Vec<AbiFunction>,
+    pub variables: Vec<AbiVariable>,
+    pub maps: Vec<AbiMap>,
+    pub fungible_tokens: Vec<AbiFungibleToken>,
+    pub non_fungible_tokens: Vec<AbiNonFungibleToken>,
+    /// e.g. `Epoch25`, missing from interfaces of older nodes
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub epoch: Option<String>,
+    /// e.g. `Clarity2`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub clarity_version: Option<String>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FunctionAccess {
+    Public,
+    ReadOnly,
+    Private,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AbiFunction {
+    pub name: String,
+    pub access: FunctionAccess,
+    pub args: Vec<AbiArgument>,
+    #[serde(with = "abi_output")]
+    pub outputs: ClarityType,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AbiArgument {
+    pub name: String,
+    #[serde(rename = "type", with = "abi_type")]
+    pub r#type: ClarityType,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum VariableAccess {
+    Constant,
+    Variable,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AbiVariable {
+    pub name: String,
+    pub access: VariableAccess,
+    #[serde(rename = "type", with = "abi_type")]
+    pub r#type: ClarityType,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AbiMap {
+    pub name: String,
+    #[serde(with = "abi_type")]
+    pub key: ClarityType,
+    #[serde(with = "abi_type")]
+    pub value: ClarityType,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AbiFungibleToken {
+    pub name: String,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AbiNonFungibleToken {
+    pub name: String,
+    #[serde(rename = "type", with = "abi_type")]
+    pub r#type: ClarityType,
+}
+
+impl ContractAbi {
+    pub fn from_json(json: &str) -> Result<Self, ClarityError> {
+        serde_json::from_str(json).map_err(|err| ClarityError::InvalidJson(format!("{err}")))
+    }
+
+    pub fn function(&self, name: &str) -> Option<&AbiFunction> {
+        self.functions.iter().find(|function| function.name == name)
+    }
+
+    /// Functions callable by a transaction.
+    pub fn public_functions(&self) -> impl Iterator<Item = &AbiFunction> {
+        self.functions.iter().filter(|function| function.access == FunctionAccess::Public)
+    }
+
+    /// Functions callable without a transaction, through `/v2/contracts/call-read`.
+    pub fn read_only_functions(&self) -> impl Iterator<Item = &AbiFunction> {
+        self.functions.iter().filter(|function| function.access == FunctionAccess::ReadOnly)
+    }
+}
+
+impl AbiFunction {
+    /// Checks the number of arguments and that each fits its declared type.
+    pub fn check_args(&self, args: &[ClarityValue]) -> Result<(), ClarityError> {
+        if args.len() != self.args.len() {
+            return Err(ClarityError::WrongArgumentCount(self.args.len(), args.len()));
+        }
+        for (arg, value) in self.args.iter().zip(args) {
+            check_argument(&arg.name, value, &arg.r#type)?;
+        }
+        Ok(())
+    }
+}
+
+/// [`check_type`] naming the argument in the mismatch.
+pub(crate) fn check_argument(name: &str, value: &ClarityValue, expected: &ClarityType) -> Result<(), ClarityError> {
+    check_type(value, expected).map_err(|err| match err {
+        ClarityError::TypeMismatch(message) => ClarityError::TypeMismatch(format!("{message} in argument {name}")),
+        other => other,
+    })
+}
+
+/// ABI type JSON, e.g. `"uint128"` or `{"list":{"type":"int128","length":10}}`.
+fn type_from_json(json: &Value) -> Result<ClarityType, String> {
+    let invalid = || format!("invalid ABI type {json}");
+    let length = |json: &Value| {
+        json.get("length").and_then(Value::as_u64).and_then(|length| u32::try_from(length).ok()).ok_or_else(invalid)
+    };
+    if let Some(name) = json.as_str() {
+        return match name {
+            "int128" => Ok(ClarityType::Int),
+            "uint128" => Ok(ClarityType::UInt),
+            "bool" => Ok(ClarityType::Bool),
+            "principal" => Ok(ClarityType::Principal),
+            "trait_reference" => Ok(ClarityType::TraitReference(String::new())),
+            "none" => Ok(ClarityType::NoType),
+            _ => Err(invalid()),
+        };
+    }
+    let (kind, inner) = match json.as_object() {
+        Some(object) if object.len() == 1 => object.iter().next().ok_or_else(invalid)?,
+        _ => return Err(invalid()),
+    };
+    match kind.as_str() {
+        "buffer" => Ok(ClarityType::Buffer(length(inner)?)),
+        "string-ascii" => Ok(ClarityType::StringAscii(length(inner)?)),
+        "string-utf8" => Ok(ClarityType::StringUtf8(length(inner)?)),
+        "list" => Ok(ClarityType::List(Box::new(type_from_json(inner.get("type").ok_or_else(invalid)?)?), length(inner)?)),
+        "tuple" => {
+            let fields = inner.as_array().ok_or_else(invalid)?;
+            let fields = fields
+                .iter()
+                .map(|field| {
+                    let name = field.get("name").and_then(Value::as_str).ok_or_else(invalid)?;
+                    Ok((name.to_string(), type_from_json(field.get("type").ok_or_else(invalid)?)?))
+                })
+                .collect::<Result<BTreeMap<_, _>, String>>()?;
+            Ok(ClarityType::Tuple(fields))
+        }
+        "optional" => Ok(ClarityType::Optional(Box::new(type_from_json(inner)?))),
+        "response" => Ok(ClarityType::Response(
+            Box::new(type_from_json(inner.get("ok").ok_or_else(invalid)?)?),
+            Box::new(type_from_json(inner.get("error").ok_or_else(invalid)?)?),
+        )),
+        _ => Err(invalid()),
+    }
+}
+
+fn type_to_json(r#type: &ClarityType) -> Value {
+    match r#type {
+        ClarityType::Int => json!("int128"),
+        ClarityType::UInt => json!("uint128"),
+        ClarityType::Bool => json!("bool"),
+        ClarityType::Principal => json!("principal"),
+        ClarityType::TraitReference(_) => json!("trait_reference"),
+        ClarityType::NoType => json!("none"),
+        ClarityType::Buffer(length) => json!({ "buffer": { "length": length } }),
+        ClarityType::StringAscii(length) => json!({ "string-ascii": { "length": length } }),
+        ClarityType::StringUtf8(length) => json!({ "string-utf8": { "length": length } }),
+        ClarityType::List(element, length) => json!({ "list": { "type": type_to_json(element), "length": length } }),
+        ClarityType::Tuple(fields) => {
+            let fields: Vec<Value> = fields.iter().map(|(name, field)| json!({ "name": name, "type": type_to_json(field) })).collect();
+            json!({ "tuple": fields })
+        }
+        ClarityType::Optional(inner) => json!({ "optional": type_to_json(inner) }),
+        ClarityType::Response(ok, err) => json!({ "response": { "ok": type_to_json(ok), "error": type_to_json(err) } }),
+    }
+}
+
+mod abi_type {
+    use serde::de::Error as _;
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+    use serde_json::Value;
+
+    use super::ClarityType;
+
+    pub fn serialize<S: Serializer>(r#type: &ClarityType, serializer: S) -> Result<S::Ok, S::Error> {
+        super::type_to_json(r#type).serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<ClarityType, D::Error> {
+        super::type_from_json(&Value::deserialize(deserializer)?).map_err(D::Error::custom)
+    }
+}
+
+/// Function outputs are wrapped in `{"type": ...}`.
+mod abi_output {
+    use serde::de::Error as _;
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+    use serde_json::{json, Value};
+
+    use super::ClarityType;
+
+    pub fn serialize<S: Serializer>(r#type: &ClarityType, serializer: S) -> Result<S::Ok, S::Error> {
+        json!({ "type": super::type_to_json(r#type) }).serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<ClarityType, D::Error> {
+        let json = Value::deserialize(deserializer)?;
+        let r#type = json.get("type").ok_or_else(|| D::Error::missing_field("type"))?;
+        super::type_from_json(r#type).map_err(D::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use super::*;
+
+    const ADDRESS: &str = "SP3FGQ8Z7JY9BWYZ5WM53E0M9NK7WHJF0691NZ159";
+
+    /// Trimmed interface of a SIP-010 token.
+    const INTERFACE: &str = r#"{
+        "functions": [
+            {"name": "mint-internal", "access": "private", "args": [{"name": "to", "type": "principal"}], "outputs": {"type": "bool"}},
+            {"name": "transfer", "access": "public", "args": [
+                {"name": "amount", "type": "uint128"},
+                {"name": "sender", "type": "principal"},
+                {"name": "recipient", "type": "principal"},
+                {"name": "memo", "type": {"optional": {"buffer": {"length": 34}}}}
+            ], "outputs": {"type": {"response": {"ok": "bool", "error": "uint128"}}}},
+            {"name": "get-name", "access": "read_only", "args": [], "outputs": {"type": {"response": {"ok": {"string-ascii": {"length": 32}}, "error": "none"}}}},
+            {"name": "swap", "access": "public", "args": [{"name": "token", "type": "trait_reference"}], "outputs": {"type": {"response": {"ok": "bool", "error": "int128"}}}}
+        ],
+        "variables": [
+            {"name": "ERR_UNAUTHORIZED", "type": {"response": {"ok": "none", "error": "uint128"}}, "access": "constant"},
+            {"name": "token-uri", "type": {"optional": {"string-utf8": {"length": 256}}}, "access": "variable"}
+        ],
+        "maps": [
+            {"name": "approvals", "key": {"tuple": [{"name": "owner", "type": "principal"}, {"name": "spender", "type": "principal"}]},
+             "value": {"list": {"type": "uint128", "length": 10}}}
+        ],
+        "fungible_tokens": [{"name": "token"}],
+        "non_fungible_tokens": [{"name": "badge", "type": "uint128"}],
+        "epoch": "Epoch25",
+        "clarity_version": "Clarity2"
+    }"#;
+
+    #[test]
+    fn test_parse_interface() {
+        let abi = ContractAbi::from_json(INTERFACE).unwrap();
+        assert_eq!(abi.functions.len(), 4);
+        assert_eq!(abi.public_functions().map(|function| function.name.as_str()).collect::<Vec<_>>(), ["transfer", "swap"]);
+        assert_eq!(abi.read_only_functions().count(), 1);
+
+        let transfer = abi.function("transfer").unwrap();
+        assert_eq!(transfer.args[3].r#type.to_string(), "(optional (buff 34))");
+        assert_eq!(transfer.outputs.to_string(), "(response bool uint)");
+        assert_eq!(abi.variables[0].access, VariableAccess::Constant);
+        assert_eq!(abi.variables[1].r#type.to_string(), "(optional (string-utf8 256))");
+        assert_eq!(abi.maps[0].key.to_string(), "(tuple (owner principal) (spender principal))");
+        assert_eq!(abi.maps[0].value.to_string(), "(list 10 uint)");
+        assert_eq!(abi.non_fungible_tokens[0].r#type, ClarityType::UInt);
+        assert_eq!(abi.clarity_version.as_deref(), Some("Clarity2"));
+
+        let json = serde_json::to_string(&abi).unwrap();
+        assert_eq!(ContractAbi::from_json(&json).unwrap(), abi);
+    }
+
+    #[test]
+    fn test_check_args() {
+        let abi = ContractAbi::from_json(INTERFACE).unwrap();
+        let transfer = abi.function("transfer").unwrap();
+        let principal = ClarityValue::from_str(&format!("'{ADDRESS}")).unwrap();
+        let mut args = vec![ClarityValue::from(100u64), principal.clone(), principal, ClarityValue::some(vec![0u8; 34])];
+        assert!(transfer.check_args(&args).is_ok());
+
+        args[3] = ClarityValue::some(vec![0u8; 35]);
+        assert_eq!(
+            transfer.check_args(&args),
+            Err(ClarityError::TypeMismatch(String::from("expected (buff 34), found (buff 35) in argument memo")))
+        );
+        assert_eq!(transfer.check_args(&args[..2]), Err(ClarityError::WrongArgumentCount(4, 2)));
+
+        let swap = abi.function("swap").unwrap();
+        assert!(swap.check_args(&[ClarityValue::from_str(&format!("'{ADDRESS}.token")).unwrap()]).is_ok());
+    }
+
+    #[test]
+    fn test_invalid_interface() {
+        let invalid = INTERFACE.replace(r#"{"name": "amount", "type": "uint128"}"#, r#"{"name": "amount", "type": "uint256"}"#);
+        assert!(matches!(ContractAbi::from_json(&invalid), Err(ClarityError::InvalidJson(message)) if message.contains("uint256")));
+        assert!(ContractAbi::from_json("{}").is_err());
+    }
+}
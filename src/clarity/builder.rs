@@ -0,0 +1,150 @@
+use std::collections::BTreeMap;
+
+use super::value::ClarityValue;
+
+/// Builds a tuple field by field.
+///
+/// Names are checked when the tuple is serialized; a name given twice keeps the last value.
+///
+/// Usage:
+/// ```rust
+/// use stacks_rs::clarity::builder::TupleBuilder;
+/// let tuple = TupleBuilder::new().field("amount", 100u64).field("memo", None::<Vec<u8>>).build();
+/// assert_eq!(tuple.to_string(), "(tuple (amount u100) (memo none))");
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct TupleBuilder {
+    fields: BTreeMap<String, ClarityValue>,
+}
+
+impl TupleBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn field(mut self, name: &str, value: impl Into<ClarityValue>) -> Self {
+        self.fields.insert(name.to_string(), value.into());
+        self
+    }
+
+    pub fn build(self) -> ClarityValue {
+        ClarityValue::Tuple(self.fields)
+    }
+}
+
+/// Builds a Clarity tuple from `name: value` fields.
+///
+/// Values are written `uint(..)`, `int(..)`, `bool(..)`, `buffer(..)`, `ascii(..)`, `utf8(..)`,
+/// `principal(..)`, `some(..)`, `ok(..)`, `err(..)`, `none`, `true`, `false`, `[..]` for lists,
+/// `{..}` for nested tuples and `value(..)` for anything convertible into a `ClarityValue`.
+/// Names are identifiers or string literals (for names with `-`).
+///
+/// Panics if `ascii(..)` gets non-ASCII text or `principal(..)` an invalid principal; build
+/// untrusted input with [`TupleBuilder`] and the fallible constructors instead.
+///
+/// Usage:
+/// ```rust
+/// use stacks_rs::clarity;
+/// let value = clarity! {
+///     amount: uint(100),
+///     to: principal("SP3FGQ8Z7JY9BWYZ5WM53E0M9NK7WHJF0691NZ159"),
+///     "pox-addr": { version: buffer([0x04]), hashbytes: buffer([0u8; 20]) },
+///     cycles: [uint(1), uint(2)],
+///     memo: none,
+/// };
+/// assert!(value.to_string().starts_with("(tuple (amount u100) (cycles (list u1 u2)) (memo none) (pox-addr (tuple"));
+/// ```
+#[macro_export]
+macro_rules! clarity {
+    (@name $name:ident) => { stringify!($name) };
+    (@name $name:literal) => { $name };
+
+    (@value none) => { $crate::clarity::value::ClarityValue::OptionalNone };
+    (@value true) => { $crate::clarity::value::ClarityValue::Bool(true) };
+    (@value false) => { $crate::clarity::value::ClarityValue::Bool(false) };
+    (@value { $($fields:tt)* }) => { $crate::clarity!(@tuple $crate::clarity::builder::TupleBuilder::new(); $($fields)*) };
+    (@value [ $($items:tt)* ]) => { $crate::clarity!(@list []; $($items)*) };
+    // unsuffixed literals would otherwise default to `i32` when passed to `From`
+    (@value uint($value:literal)) => { $crate::clarity::value::ClarityValue::UInt($value as u128) };
+    (@value int($value:literal)) => { $crate::clarity::value::ClarityValue::Int($value as i128) };
+    (@value uint($value:expr)) => { $crate::clarity::value::ClarityValue::UInt(u128::from($value)) };
+    (@value int($value:expr)) => { $crate::clarity::value::ClarityValue::Int(i128::from($value)) };
+    (@value bool($value:expr)) => { $crate::clarity::value::ClarityValue::Bool($value) };
+    (@value buffer($value:expr)) => { $crate::clarity::value::ClarityValue::Buffer(AsRef::<[u8]>::as_ref(&$value).to_vec()) };
+    (@value ascii($value:expr)) => {
+        $crate::clarity::value::ClarityValue::string_ascii($value).expect("invalid string-ascii")
+    };
+    (@value utf8($value:expr)) => { $crate::clarity::value::ClarityValue::StringUtf8(String::from($value)) };
+    (@value principal($value:expr)) => {
+        $crate::clarity::value::ClarityValue::Principal(
+            <$crate::address::principal::Principal as std::str::FromStr>::from_str($value).expect("invalid principal"),
+        )
+    };
+    (@value some($($inner:tt)+)) => { $crate::clarity::value::ClarityValue::OptionalSome(Box::new($crate::clarity!(@value $($inner)+))) };
+    (@value ok($($inner:tt)+)) => { $crate::clarity::value::ClarityValue::ResponseOk(Box::new($crate::clarity!(@value $($inner)+))) };
+    (@value err($($inner:tt)+)) => { $crate::clarity::value::ClarityValue::ResponseErr(Box::new($crate::clarity!(@value $($inner)+))) };
+    (@value value($value:expr)) => { $crate::clarity::value::ClarityValue::from($value) };
+
+    // tuple fields: `name: kind(..)`, `name: kind`, `name: {..}` or `name: [..]`
+    (@tuple $builder:expr;) => { $builder.build() };
+    (@tuple $builder:expr; $name:tt : $kind:ident ( $($args:tt)* ) $(, $($rest:tt)*)?) => {
+        $crate::clarity!(@tuple $builder.field($crate::clarity!(@name $name), $crate::clarity!(@value $kind($($args)*))); $($($rest)*)?)
+    };
+    (@tuple $builder:expr; $name:tt : $value:tt $(, $($rest:tt)*)?) => {
+        $crate::clarity!(@tuple $builder.field($crate::clarity!(@name $name), $crate::clarity!(@value $value)); $($($rest)*)?)
+    };
+
+    // list items, accumulated in order
+    (@list [$($items:expr),*];) => { $crate::clarity::value::ClarityValue::List(vec![$($items),*]) };
+    (@list [$($items:expr),*]; $kind:ident ( $($args:tt)* ) $(, $($rest:tt)*)?) => {
+        $crate::clarity!(@list [$($items,)* $crate::clarity!(@value $kind($($args)*))]; $($($rest)*)?)
+    };
+    (@list [$($items:expr),*]; $value:tt $(, $($rest:tt)*)?) => {
+        $crate::clarity!(@list [$($items,)* $crate::clarity!(@value $value)]; $($($rest)*)?)
+    };
+
+    ($($fields:tt)+) => { $crate::clarity!(@tuple $crate::clarity::builder::TupleBuilder::new(); $($fields)+) };
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use crate::address::principal::Principal;
+
+    use super::*;
+
+    const ADDRESS: &str = "SP3FGQ8Z7JY9BWYZ5WM53E0M9NK7WHJF0691NZ159";
+
+    #[test]
+    fn test_macro_matches_repr() {
+        let amount = 250u64;
+        let value = crate::clarity! {
+            amount: uint(amount),
+            delta: int(-5),
+            flag: false,
+            name: ascii("stx"),
+            greeting: utf8("h\u{e9}llo"),
+            owner: principal(ADDRESS),
+            "pox-addr": { version: buffer([0x01]), hashbytes: buffer(vec![0xab; 20]) },
+            items: [some(uint(1)), none, value(Some(3u32))],
+            result: ok(err(true)),
+            empty: [],
+        };
+        let expected = ClarityValue::from_str(&format!(
+            "(tuple (amount u250) (delta -5) (empty (list)) (flag false) (greeting u\"h\\u{{e9}}llo\") \
+             (items (list (some u1) none (some u3))) (name \"stx\") (owner '{ADDRESS}) \
+             (pox-addr (tuple (hashbytes 0x{}) (version 0x01))) (result (ok (err true))))",
+            "ab".repeat(20)
+        ))
+        .unwrap();
+        assert_eq!(value, expected);
+    }
+
+    #[test]
+    fn test_builder() {
+        let principal = Principal::from_str(ADDRESS).unwrap();
+        let tuple = TupleBuilder::new().field("to", principal.clone()).field("amount", 1u32).field("amount", 2u32).build();
+        assert_eq!(tuple, crate::clarity! { to: value(principal), amount: uint(2u32) });
+    }
+}
@@ -0,0 +1,248 @@
+//! Typed Rust bindings generated from a contract interface, meant for build scripts.
+//!
+//! Every public and read-only function becomes a Rust function taking typed arguments and
+//! returning a [`ContractCallBuilder`] of the call, with the arguments checked against the ABI;
+//! the nonce, fee and post-conditions are then set on the builder as usual.
+//! Simple types map to Rust ones (`uint` to `u128`, `(optional (buff 34))` to `Option<Vec<u8>>`,
+//! `string-ascii` to `&str`...), tuples and other compound types are taken as [`ClarityValue`].
+//!
+//! In `build.rs`:
+//! ```rust,no_run
+//! use std::str::FromStr;
+//! use stacks_rs::address::principal::Principal;
+//! use stacks_rs::clarity::abi::ContractAbi;
+//! use stacks_rs::clarity::codegen::generate_bindings;
+//! let abi = ContractAbi::from_json(&std::fs::read_to_string("interfaces/token.json").unwrap()).unwrap();
+//! let contract = Principal::from_str("SP3FGQ8Z7JY9BWYZ5WM53E0M9NK7WHJF0691NZ159.token").unwrap();
+//! let out_dir = std::env::var("OUT_DIR").unwrap();
+//! std::fs::write(format!("{out_dir}/token.rs"), generate_bindings(&abi, &contract).unwrap()).unwrap();
+//! ```
+//! and in the crate, `mod token { include!(concat!(env!("OUT_DIR"), "/token.rs")); }`.
+
+use std::collections::HashSet;
+use std::str::FromStr;
+
+use crate::address::principal::Principal;
+use crate::address::stacks_address::StacksAddress;
+use crate::transaction::builder::ContractCallBuilder;
+
+use super::abi::{check_argument, AbiFunction, ContractAbi, FunctionAccess};
+use super::types::ClarityType;
+use super::value::ClarityValue;
+use super::ClarityError;
+
+const RUST_KEYWORDS: &[&str] = &[
+    "as", "async", "await", "box", "break", "const", "continue", "do", "dyn", "else", "enum", "extern", "false", "fn", "for", "if",
+    "impl", "in", "let", "loop", "match", "mod", "move", "mut", "pub", "ref", "return", "static", "struct", "trait", "true", "try",
+    "type", "unsafe", "use", "where", "while", "yield", "abstract", "become", "final", "gen", "macro", "override", "priv", "typeof",
+    "unsized", "virtual",
+];
+
+/// Keywords that cannot be raw identifiers.
+const RESERVED_IDENTIFIERS: &[&str] = &["crate", "self", "Self", "super"];
+
+/// Rust source of the bindings of `contract`, whose interface is `abi`.
+pub fn generate_bindings(abi: &ContractAbi, contract: &Principal) -> Result<String, ClarityError> {
+    let contract_name = contract
+        .contract_name()
+        .ok_or_else(|| ClarityError::InvalidPrincipal(format!("{contract} is not a contract principal")))?;
+
+    let mut source = format!(
+        "// Bindings of {contract}, generated by stacks_rs::clarity::codegen.\n\n\
+         pub const CONTRACT_ADDRESS: &str = \"{}\";\n\
+         pub const CONTRACT_NAME: &str = \"{contract_name}\";\n",
+        contract.address()
+    );
+    let mut names = HashSet::new();
+    for function in &abi.functions {
+        if function.access == FunctionAccess::Private {
+            continue;
+        }
+        source.push('\n');
+        source.push_str(&function_binding(function, unique_ident(&function.name, &mut names)));
+    }
+    Ok(source)
+}
+
+fn function_binding(function: &AbiFunction, ident: String) -> String {
+    let mut arg_names = HashSet::new();
+    let mut params = Vec::new();
+    let mut values = Vec::new();
+    let mut signature = Vec::new();
+    for arg in &function.args {
+        let arg_ident = unique_ident(&arg.name, &mut arg_names);
+        let (rust_type, value) = match (&arg.r#type, rust_type(&arg.r#type)) {
+            (ClarityType::StringAscii(_), _) => {
+                (String::from("&str"), format!("::stacks_rs::clarity::value::ClarityValue::string_ascii({arg_ident})?"))
+            }
+            (_, Some(rust_type)) => (rust_type, format!("::stacks_rs::clarity::value::ClarityValue::from({arg_ident})")),
+            (_, None) => (String::from("::stacks_rs::clarity::value::ClarityValue"), arg_ident.clone()),
+        };
+        params.push(format!("{arg_ident}: {rust_type}"));
+        values.push(value);
+        signature.push(format!("({:?}, {:?})", arg.name, arg.r#type.to_string()));
+    }
+
+    let access = match function.access {
+        FunctionAccess::ReadOnly => "read-only",
+        _ => "public",
+    };
+    let args: Vec<String> = function.args.iter().map(|arg| format!("({} {})", arg.name, arg.r#type)).collect();
+    format!(
+        "/// `{}` ({access}): `{}` -> `{}`\n\
+         pub fn {ident}({}) -> Result<::stacks_rs::transaction::builder::ContractCallBuilder, ::stacks_rs::clarity::ClarityError> {{\n    \
+             ::stacks_rs::clarity::codegen::contract_call(CONTRACT_ADDRESS, CONTRACT_NAME, {:?}, vec![{}], &[{}])\n\
+         }}\n",
+        function.name,
+        args.join(" "),
+        function.outputs,
+        params.join(", "),
+        function.name,
+        values.join(", "),
+        signature.join(", "),
+    )
+}
+
+/// Rust type converting into a value of `r#type` with `ClarityValue::from`, if any.
+fn rust_type(r#type: &ClarityType) -> Option<String> {
+    match r#type {
+        ClarityType::Int => Some(String::from("i128")),
+        ClarityType::UInt => Some(String::from("u128")),
+        ClarityType::Bool => Some(String::from("bool")),
+        ClarityType::Principal | ClarityType::TraitReference(_) => Some(String::from("::stacks_rs::address::principal::Principal")),
+        ClarityType::Buffer(_) => Some(String::from("Vec<u8>")),
+        ClarityType::StringUtf8(_) => Some(String::from("String")),
+        ClarityType::Optional(inner) => rust_type(inner).map(|inner| format!("Option<{inner}>")),
+        ClarityType::List(element, _) => rust_type(element).map(|element| format!("Vec<{element}>")),
+        _ => None,
+    }
+}
+
+/// Snake-case identifier for a Clarity name, e.g. `get-balance?` -> `get_balance`, made unique among `taken`.
+fn unique_ident(name: &str, taken: &mut HashSet<String>) -> String {
+    let mut ident: String = name.chars().map(|c| if c.is_ascii_alphanumeric() { c.to_ascii_lowercase() } else { '_' }).collect();
+    ident = ident.trim_matches('_').to_string();
+    if !ident.starts_with(|c: char| c.is_ascii_alphabetic()) {
+        ident = format!("f_{ident}");
+    }
+    if RESERVED_IDENTIFIERS.contains(&ident.as_str()) {
+        ident.push('_');
+    }
+    while !taken.insert(ident.clone()) {
+        ident.push('_');
+    }
+    if RUST_KEYWORDS.contains(&ident.as_str()) {
+        ident = format!("r#{ident}");
+    }
+    ident
+}
+
+/// Builder of the call of a generated binding, checking `args` against their `(name, type)` signatures.
+pub fn contract_call(
+    contract_address: &str,
+    contract_name: &str,
+    function_name: &str,
+    args: Vec<ClarityValue>,
+    signature: &[(&str, &str)],
+) -> Result<ContractCallBuilder, ClarityError> {
+    if args.len() != signature.len() {
+        return Err(ClarityError::WrongArgumentCount(signature.len(), args.len()));
+    }
+    for (value, (name, r#type)) in args.iter().zip(signature) {
+        check_argument(name, value, &ClarityType::from_str(r#type)?)?;
+    }
+    let contract = StacksAddress::from_str(contract_address)
+        .and_then(|address| Principal::contract(address, contract_name))
+        .map_err(|err| ClarityError::InvalidPrincipal(format!("{err}")))?;
+    Ok(ContractCallBuilder::new(contract, function_name, args))
+}
+
+#[cfg(test)]
+mod tests {
+    use secp256k1::{PublicKey, SecretKey};
+
+    use crate::crypto::context::secp256k1_context;
+    use crate::transaction::payload::Payload;
+
+    use super::*;
+
+    const ADDRESS: &str = "SP3FGQ8Z7JY9BWYZ5WM53E0M9NK7WHJF0691NZ159";
+
+    const INTERFACE: &str = r#"{
+        "functions": [
+            {"name": "mint", "access": "private", "args": [], "outputs": {"type": "bool"}},
+            {"name": "transfer", "access": "public", "args": [
+                {"name": "amount", "type": "uint128"},
+                {"name": "recipient", "type": "principal"},
+                {"name": "memo", "type": {"optional": {"buffer": {"length": 34}}}}
+            ], "outputs": {"type": {"response": {"ok": "bool", "error": "uint128"}}}},
+            {"name": "set-name!", "access": "public", "args": [
+                {"name": "name", "type": {"string-ascii": {"length": 32}}},
+                {"name": "type", "type": {"tuple": [{"name": "id", "type": "int128"}]}}
+            ], "outputs": {"type": {"response": {"ok": "bool", "error": "none"}}}},
+            {"name": "set-name", "access": "read_only", "args": [], "outputs": {"type": "bool"}}
+        ],
+        "variables": [], "maps": [], "fungible_tokens": [], "non_fungible_tokens": []
+    }"#;
+
+    #[test]
+    fn test_generate_bindings() {
+        let abi = ContractAbi::from_json(INTERFACE).unwrap();
+        let contract = Principal::from_str(&format!("{ADDRESS}.token")).unwrap();
+        let source = generate_bindings(&abi, &contract).unwrap();
+
+        assert!(source.contains(&format!("pub const CONTRACT_ADDRESS: &str = \"{ADDRESS}\";")));
+        assert!(!source.contains("fn mint"));
+        assert!(source.contains(
+            "pub fn transfer(amount: u128, recipient: ::stacks_rs::address::principal::Principal, memo: Option<Vec<u8>>)"
+        ));
+        assert!(source.contains("&[(\"amount\", \"uint\"), (\"recipient\", \"principal\"), (\"memo\", \"(optional (buff 34))\")]"));
+        assert!(source.contains("pub fn set_name(name: &str, r#type: ::stacks_rs::clarity::value::ClarityValue)"));
+        assert!(source.contains("ClarityValue::string_ascii(name)?"));
+        assert!(source.contains("pub fn set_name_()"));
+        assert!(generate_bindings(&abi, &Principal::from_str(ADDRESS).unwrap()).is_err());
+        assert_eq!(source, include_str!("testdata/token_bindings.rs"));
+    }
+
+    /// The bindings of [`INTERFACE`], as generated.
+    mod token {
+        include!("testdata/token_bindings.rs");
+    }
+
+    #[test]
+    fn test_generated_bindings_build_transactions() {
+        let recipient = Principal::from_str(ADDRESS).unwrap();
+        let transaction = token::transfer(100, recipient.clone(), Some(b"gm".to_vec())).unwrap().nonce(3).fee(1000).build(&public_key()).unwrap();
+        assert_eq!(transaction.auth.origin().nonce(), 3);
+        let Payload::ContractCall(payload) = transaction.payload else { panic!("not a contract call") };
+        assert_eq!((payload.contract_name.as_str(), payload.function_name.as_str()), ("token", "transfer"));
+        let memo = ClarityValue::some(b"gm".to_vec());
+        assert_eq!(payload.function_args, vec![ClarityValue::UInt(100), ClarityValue::from(recipient), memo]);
+
+        assert!(token::set_name("name", ClarityValue::UInt(1)).is_err());
+        assert!(token::set_name_().unwrap().build(&public_key()).is_ok());
+    }
+
+    fn public_key() -> PublicKey {
+        SecretKey::from_byte_array(&[1; 32]).unwrap().public_key(secp256k1_context())
+    }
+
+    #[test]
+    fn test_contract_call() {
+        let signature = [("amount", "uint"), ("memo", "(optional (buff 2))")];
+        let builder = contract_call(ADDRESS, "token", "transfer", vec![ClarityValue::from(5u64), ClarityValue::OptionalNone], &signature).unwrap();
+        let transaction = builder.fee(1000).build(&public_key()).unwrap();
+        let Payload::ContractCall(payload) = transaction.payload else { panic!("not a contract call") };
+        assert_eq!(payload.function_args, vec![ClarityValue::UInt(5), ClarityValue::OptionalNone]);
+
+        let args = vec![ClarityValue::from(5u64), ClarityValue::some(vec![0u8; 3])];
+        assert_eq!(
+            contract_call(ADDRESS, "token", "transfer", args, &signature).err(),
+            Some(ClarityError::TypeMismatch(String::from("expected (buff 2), found (buff 3) in argument memo")))
+        );
+        assert_eq!(
+            contract_call(ADDRESS, "token", "transfer", vec![], &signature).err(),
+            Some(ClarityError::WrongArgumentCount(2, 0))
+        );
+    }
+}
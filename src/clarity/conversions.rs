@@ -0,0 +1,243 @@
+//! Conversions between [`ClarityValue`] and Rust types.
+//!
+//! Integers become `int`/`uint`, strings `string-utf8`, byte vectors `buffer` and other
+//! vectors `list`; `u8` has no conversion of its own, so that `Vec<u8>` is a buffer and not
+//! a list of `uint`.
+//!
+//! Usage:
+//! ```rust
+//! use stacks_rs::clarity::value::ClarityValue;
+//! let value = ClarityValue::from(Some(vec![100u64, 200]));
+//! assert_eq!(value.to_string(), "(some (list u100 u200))");
+//! assert_eq!(Option::<Vec<u64>>::try_from(value).unwrap(), Some(vec![100, 200]));
+//! ```
+
+use super::value::ClarityValue;
+use super::ClarityError;
+
+macro_rules! integer_conversions {
+    ($variant:ident, $wide:ty, $($ty:ty),*) => {
+        $(
+            impl From<$ty> for ClarityValue {
+                fn from(value: $ty) -> Self {
+                    ClarityValue::$variant(value as $wide)
+                }
+            }
+
+            impl TryFrom<ClarityValue> for $ty {
+                type Error = ClarityError;
+
+                fn try_from(value: ClarityValue) -> Result<Self, Self::Error> {
+                    match value {
+                        ClarityValue::$variant(value) => <$ty>::try_from(value)
+                            .map_err(|_| ClarityError::IntegerOverflow(format!("{value} does not fit in {}", stringify!($ty)))),
+                        other => Err(unexpected_type(stringify!($variant), &other)),
+                    }
+                }
+            }
+        )*
+    };
+}
+
+integer_conversions!(UInt, u128, u16, u32, u64, u128, usize);
+integer_conversions!(Int, i128, i8, i16, i32, i64, i128, isize);
+
+pub(crate) fn unexpected_type(expected: &str, found: &ClarityValue) -> ClarityError {
+    ClarityError::UnexpectedType(format!("expected {expected}, found {:?}", found.type_id()))
+}
+
+impl ClarityValue {
+    /// `string-ascii` value, failing on characters Clarity does not allow in it.
+    pub fn string_ascii(value: &str) -> Result<Self, ClarityError> {
+        let value = ClarityValue::StringAscii(value.to_string());
+        value.serialize()?;
+        Ok(value)
+    }
+
+    pub fn some(value: impl Into<ClarityValue>) -> Self {
+        ClarityValue::OptionalSome(Box::new(value.into()))
+    }
+
+    pub fn ok(value: impl Into<ClarityValue>) -> Self {
+        ClarityValue::ResponseOk(Box::new(value.into()))
+    }
+
+    pub fn err(value: impl Into<ClarityValue>) -> Self {
+        ClarityValue::ResponseErr(Box::new(value.into()))
+    }
+}
+
+impl From<bool> for ClarityValue {
+    fn from(value: bool) -> Self {
+        ClarityValue::Bool(value)
+    }
+}
+
+impl From<String> for ClarityValue {
+    fn from(value: String) -> Self {
+        ClarityValue::StringUtf8(value)
+    }
+}
+
+impl From<&str> for ClarityValue {
+    fn from(value: &str) -> Self {
+        ClarityValue::StringUtf8(value.to_string())
+    }
+}
+
+impl From<Vec<u8>> for ClarityValue {
+    fn from(value: Vec<u8>) -> Self {
+        ClarityValue::Buffer(value)
+    }
+}
+
+impl From<&[u8]> for ClarityValue {
+    fn from(value: &[u8]) -> Self {
+        ClarityValue::Buffer(value.to_vec())
+    }
+}
+
+impl<T: Into<ClarityValue>> From<Vec<T>> for ClarityValue {
+    fn from(values: Vec<T>) -> Self {
+        ClarityValue::List(values.into_iter().map(Into::into).collect())
+    }
+}
+
+impl<T: Into<ClarityValue>> From<Option<T>> for ClarityValue {
+    fn from(value: Option<T>) -> Self {
+        match value {
+            Some(value) => ClarityValue::some(value),
+            None => ClarityValue::OptionalNone,
+        }
+    }
+}
+
+impl<T: Into<ClarityValue>, E: Into<ClarityValue>> From<Result<T, E>> for ClarityValue {
+    fn from(value: Result<T, E>) -> Self {
+        match value {
+            Ok(value) => ClarityValue::ok(value),
+            Err(value) => ClarityValue::err(value),
+        }
+    }
+}
+
+impl TryFrom<ClarityValue> for bool {
+    type Error = ClarityError;
+
+    fn try_from(value: ClarityValue) -> Result<Self, Self::Error> {
+        match value {
+            ClarityValue::Bool(value) => Ok(value),
+            other => Err(unexpected_type("Bool", &other)),
+        }
+    }
+}
+
+/// Accepts both string types.
+impl TryFrom<ClarityValue> for String {
+    type Error = ClarityError;
+
+    fn try_from(value: ClarityValue) -> Result<Self, Self::Error> {
+        match value {
+            ClarityValue::StringAscii(value) | ClarityValue::StringUtf8(value) => Ok(value),
+            other => Err(unexpected_type("StringAscii or StringUtf8", &other)),
+        }
+    }
+}
+
+impl TryFrom<ClarityValue> for Vec<u8> {
+    type Error = ClarityError;
+
+    fn try_from(value: ClarityValue) -> Result<Self, Self::Error> {
+        match value {
+            ClarityValue::Buffer(value) => Ok(value),
+            other => Err(unexpected_type("Buffer", &other)),
+        }
+    }
+}
+
+/// Fixed-size buffers, e.g. a 32-byte hash.
+impl<const N: usize> TryFrom<ClarityValue> for [u8; N] {
+    type Error = ClarityError;
+
+    fn try_from(value: ClarityValue) -> Result<Self, Self::Error> {
+        let bytes = Vec::<u8>::try_from(value)?;
+        bytes.as_slice().try_into().map_err(|_| ClarityError::UnexpectedLength(N, bytes.len()))
+    }
+}
+
+impl<T: TryFrom<ClarityValue, Error = ClarityError>> TryFrom<ClarityValue> for Vec<T> {
+    type Error = ClarityError;
+
+    fn try_from(value: ClarityValue) -> Result<Self, Self::Error> {
+        match value {
+            ClarityValue::List(values) => values.into_iter().map(T::try_from).collect(),
+            other => Err(unexpected_type("List", &other)),
+        }
+    }
+}
+
+impl<T: TryFrom<ClarityValue, Error = ClarityError>> TryFrom<ClarityValue> for Option<T> {
+    type Error = ClarityError;
+
+    fn try_from(value: ClarityValue) -> Result<Self, Self::Error> {
+        match value {
+            ClarityValue::OptionalNone => Ok(None),
+            ClarityValue::OptionalSome(value) => T::try_from(*value).map(Some),
+            other => Err(unexpected_type("Optional", &other)),
+        }
+    }
+}
+
+impl<T, E> TryFrom<ClarityValue> for Result<T, E>
+where
+    T: TryFrom<ClarityValue, Error = ClarityError>,
+    E: TryFrom<ClarityValue, Error = ClarityError>,
+{
+    type Error = ClarityError;
+
+    fn try_from(value: ClarityValue) -> Result<Self, Self::Error> {
+        match value {
+            ClarityValue::ResponseOk(value) => T::try_from(*value).map(Ok),
+            ClarityValue::ResponseErr(value) => E::try_from(*value).map(Err),
+            other => Err(unexpected_type("Response", &other)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_primitives() {
+        assert_eq!(ClarityValue::from(100u64), ClarityValue::UInt(100));
+        assert_eq!(ClarityValue::from(-1i32), ClarityValue::Int(-1));
+        assert_eq!(ClarityValue::from(true), ClarityValue::Bool(true));
+        assert_eq!(ClarityValue::from("hi"), ClarityValue::StringUtf8(String::from("hi")));
+        assert_eq!(ClarityValue::from(vec![1u8, 2]), ClarityValue::Buffer(vec![1, 2]));
+        assert_eq!(ClarityValue::from(vec![1u32, 2]), ClarityValue::List(vec![ClarityValue::UInt(1), ClarityValue::UInt(2)]));
+        assert_eq!(ClarityValue::from(Some(1i128)), ClarityValue::some(ClarityValue::Int(1)));
+        assert_eq!(ClarityValue::from(None::<bool>), ClarityValue::OptionalNone);
+        assert_eq!(ClarityValue::from(Err::<bool, u32>(3)), ClarityValue::err(3u32));
+
+        assert_eq!(u64::try_from(ClarityValue::UInt(100)).unwrap(), 100);
+        assert_eq!(i8::try_from(ClarityValue::Int(-128)).unwrap(), -128);
+        assert_eq!(String::try_from(ClarityValue::StringAscii(String::from("a"))).unwrap(), "a");
+        assert_eq!(<[u8; 2]>::try_from(ClarityValue::Buffer(vec![1, 2])).unwrap(), [1, 2]);
+        assert_eq!(Vec::<u16>::try_from(ClarityValue::from(vec![1u16, 2])).unwrap(), vec![1, 2]);
+        assert_eq!(Option::<bool>::try_from(ClarityValue::some(true)).unwrap(), Some(true));
+        assert_eq!(Result::<u128, u128>::try_from(ClarityValue::err(7u128)).unwrap(), Err(7));
+    }
+
+    #[test]
+    fn test_conversion_errors() {
+        assert_eq!(
+            u16::try_from(ClarityValue::UInt(70_000)),
+            Err(ClarityError::IntegerOverflow(String::from("70000 does not fit in u16")))
+        );
+        assert_eq!(u64::try_from(ClarityValue::Int(1)), Err(ClarityError::UnexpectedType(String::from("expected UInt, found Int"))));
+        assert_eq!(<[u8; 32]>::try_from(ClarityValue::Buffer(vec![0; 20])), Err(ClarityError::UnexpectedLength(32, 20)));
+        assert!(matches!(Vec::<u32>::try_from(ClarityValue::from(vec![ClarityValue::Int(1)])), Err(ClarityError::UnexpectedType(_))));
+        assert_eq!(ClarityValue::string_ascii("caf\u{e9}"), Err(ClarityError::InvalidAscii));
+    }
+}
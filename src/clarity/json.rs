@@ -0,0 +1,211 @@
+//! The JSON shape of Clarity values used by the Stacks Blockchain API and stacks.js' `cvToJSON`,
+//! e.g. `{"type":"uint","value":"100"}`.
+//!
+//! Integers are strings, buffers `0x`-prefixed hex and principals plain addresses; optionals
+//! and responses nest the JSON of their inner value, responses with a `success` flag.
+//!
+//! Usage:
+//! ```rust
+//! use stacks_rs::clarity::value::ClarityValue;
+//! let value: ClarityValue = serde_json::from_str(r#"{"type":"(optional uint)","value":{"type":"uint","value":"100"}}"#).unwrap();
+//! assert_eq!(value, ClarityValue::some(100u64));
+//! assert_eq!(serde_json::to_string(&ClarityValue::from(-5i32)).unwrap(), r#"{"type":"int","value":"-5"}"#);
+//! ```
+
+use std::collections::BTreeMap;
+use std::str::FromStr;
+
+use serde::de::Error as _;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use serde_json::{json, Map, Value};
+
+use crate::address::principal::Principal;
+
+use super::value::ClarityValue;
+use super::ClarityError;
+
+/// Type of values whose type cannot be told from the value, e.g. the err side of an ok response.
+const UNKNOWN_TYPE: &str = "UnknownType";
+
+impl ClarityValue {
+    pub fn to_json(&self) -> Value {
+        let r#type = self.json_type();
+        match self {
+            ClarityValue::Int(value) => json!({ "type": r#type, "value": value.to_string() }),
+            ClarityValue::UInt(value) => json!({ "type": r#type, "value": value.to_string() }),
+            ClarityValue::Bool(value) => json!({ "type": r#type, "value": value }),
+            ClarityValue::Buffer(value) => json!({ "type": r#type, "value": format!("0x{}", hex::encode(value)) }),
+            ClarityValue::StringAscii(value) | ClarityValue::StringUtf8(value) => json!({ "type": r#type, "value": value }),
+            ClarityValue::Principal(value) => json!({ "type": r#type, "value": value.to_string() }),
+            ClarityValue::List(values) => {
+                json!({ "type": r#type, "value": values.iter().map(ClarityValue::to_json).collect::<Vec<_>>() })
+            }
+            ClarityValue::Tuple(fields) => {
+                let fields: Map<String, Value> = fields.iter().map(|(name, value)| (name.clone(), value.to_json())).collect();
+                json!({ "type": r#type, "value": fields })
+            }
+            ClarityValue::OptionalNone => json!({ "type": r#type, "value": null }),
+            ClarityValue::OptionalSome(value) => json!({ "type": r#type, "value": value.to_json() }),
+            ClarityValue::ResponseOk(value) => json!({ "type": r#type, "value": value.to_json(), "success": true }),
+            ClarityValue::ResponseErr(value) => json!({ "type": r#type, "value": value.to_json(), "success": false }),
+        }
+    }
+
+    pub fn from_json(json: &Value) -> Result<Self, ClarityError> {
+        let r#type = json.get("type").and_then(Value::as_str).ok_or_else(|| invalid("missing type"))?;
+        let value = json.get("value").ok_or_else(|| invalid("missing value"))?;
+        let as_str = || value.as_str().ok_or_else(|| invalid(&format!("expected a string value for {type}")));
+
+        let value = match r#type {
+            "int" => ClarityValue::Int(integer(value)?),
+            "uint" => ClarityValue::UInt(integer(value)?),
+            "bool" => ClarityValue::Bool(value.as_bool().ok_or_else(|| invalid("expected a boolean value"))?),
+            "principal" => {
+                ClarityValue::Principal(Principal::from_str(as_str()?).map_err(|err| ClarityError::InvalidPrincipal(format!("{err}")))?)
+            }
+            "(optional none)" => ClarityValue::OptionalNone,
+            _ if r#type.starts_with("(buff ") => {
+                let value = as_str()?;
+                ClarityValue::Buffer(
+                    hex::decode(value.strip_prefix("0x").unwrap_or(value)).map_err(|err| ClarityError::InvalidHex(format!("{err}")))?,
+                )
+            }
+            _ if r#type.starts_with("(string-ascii ") => ClarityValue::string_ascii(as_str()?)?,
+            _ if r#type.starts_with("(string-utf8 ") => ClarityValue::StringUtf8(as_str()?.to_string()),
+            _ if r#type.starts_with("(list ") => {
+                let values = value.as_array().ok_or_else(|| invalid("expected an array value"))?;
+                ClarityValue::List(values.iter().map(ClarityValue::from_json).collect::<Result<_, _>>()?)
+            }
+            _ if r#type.starts_with("(tuple ") => {
+                let fields = value.as_object().ok_or_else(|| invalid("expected an object value"))?;
+                let fields = fields
+                    .iter()
+                    .map(|(name, value)| Ok((name.clone(), ClarityValue::from_json(value)?)))
+                    .collect::<Result<BTreeMap<_, _>, ClarityError>>()?;
+                ClarityValue::Tuple(fields)
+            }
+            _ if r#type.starts_with("(optional ") => ClarityValue::some(ClarityValue::from_json(value)?),
+            _ if r#type.starts_with("(response ") => {
+                let value = ClarityValue::from_json(value)?;
+                match json.get("success").and_then(Value::as_bool) {
+                    Some(true) => ClarityValue::ok(value),
+                    Some(false) => ClarityValue::err(value),
+                    None => return Err(invalid("missing success flag")),
+                }
+            }
+            other => return Err(invalid(&format!("unknown type {other:?}"))),
+        };
+        Ok(value)
+    }
+
+    /// Type string as printed by the API, sized to the value, e.g. `(buff 20)` or `(list 2 uint)`.
+    fn json_type(&self) -> String {
+        match self {
+            ClarityValue::Int(_) => String::from("int"),
+            ClarityValue::UInt(_) => String::from("uint"),
+            ClarityValue::Bool(_) => String::from("bool"),
+            ClarityValue::Buffer(value) => format!("(buff {})", value.len()),
+            ClarityValue::StringAscii(value) => format!("(string-ascii {})", value.len()),
+            ClarityValue::StringUtf8(value) => format!("(string-utf8 {})", value.len()),
+            ClarityValue::Principal(_) => String::from("principal"),
+            ClarityValue::List(values) => {
+                format!("(list {} {})", values.len(), values.first().map_or(String::from(UNKNOWN_TYPE), ClarityValue::json_type))
+            }
+            ClarityValue::Tuple(fields) => {
+                let fields: Vec<String> = fields.iter().map(|(name, value)| format!("({name} {})", value.json_type())).collect();
+                format!("(tuple {})", fields.join(" "))
+            }
+            ClarityValue::OptionalNone => String::from("(optional none)"),
+            ClarityValue::OptionalSome(value) => format!("(optional {})", value.json_type()),
+            ClarityValue::ResponseOk(value) => format!("(response {} {UNKNOWN_TYPE})", value.json_type()),
+            ClarityValue::ResponseErr(value) => format!("(response {UNKNOWN_TYPE} {})", value.json_type()),
+        }
+    }
+}
+
+fn invalid(message: &str) -> ClarityError {
+    ClarityError::InvalidJson(String::from(message))
+}
+
+/// Integers come as strings, small ones are also accepted as JSON numbers.
+fn integer<T: FromStr + TryFrom<i64> + TryFrom<u64>>(value: &Value) -> Result<T, ClarityError> {
+    let parsed = match value {
+        Value::String(value) => value.parse().ok(),
+        Value::Number(value) => match (value.as_u64(), value.as_i64()) {
+            (Some(value), _) => T::try_from(value).ok(),
+            (None, Some(value)) => T::try_from(value).ok(),
+            _ => None,
+        },
+        _ => None,
+    };
+    parsed.ok_or_else(|| ClarityError::IntegerOverflow(format!("{value} is not a valid integer")))
+}
+
+impl Serialize for ClarityValue {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.to_json().serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for ClarityValue {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let json = Value::deserialize(deserializer)?;
+        ClarityValue::from_json(&json).map_err(D::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ADDRESS: &str = "SP3FGQ8Z7JY9BWYZ5WM53E0M9NK7WHJF0691NZ159";
+
+    #[test]
+    fn test_to_json() {
+        let value = ClarityValue::from_str(&format!(
+            "(tuple (amount u100) (memo none) (owner '{ADDRESS}.pool) (ids (list 1 2)) (hash 0x0102) (name \"stx\") (result (err u3)))"
+        ))
+        .unwrap();
+        let expected = json!({
+            "type": "(tuple (amount uint) (hash (buff 2)) (ids (list 2 int)) (memo (optional none)) (name (string-ascii 3)) (owner principal) (result (response UnknownType uint)))",
+            "value": {
+                "amount": { "type": "uint", "value": "100" },
+                "hash": { "type": "(buff 2)", "value": "0x0102" },
+                "ids": { "type": "(list 2 int)", "value": [{ "type": "int", "value": "1" }, { "type": "int", "value": "2" }] },
+                "memo": { "type": "(optional none)", "value": null },
+                "name": { "type": "(string-ascii 3)", "value": "stx" },
+                "owner": { "type": "principal", "value": format!("{ADDRESS}.pool") },
+                "result": { "type": "(response UnknownType uint)", "value": { "type": "uint", "value": "3" }, "success": false },
+            }
+        });
+        assert_eq!(value.to_json(), expected);
+        assert_eq!(ClarityValue::from_json(&expected).unwrap(), value);
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let values = [
+            ClarityValue::Int(i128::MIN),
+            ClarityValue::UInt(u128::MAX),
+            ClarityValue::from("caf\u{e9}"),
+            ClarityValue::ok(ClarityValue::some(true)),
+            ClarityValue::List(vec![]),
+        ];
+        for value in values {
+            let json = serde_json::to_string(&value).unwrap();
+            assert_eq!(serde_json::from_str::<ClarityValue>(&json).unwrap(), value);
+        }
+    }
+
+    #[test]
+    fn test_invalid_json() {
+        let from_json = |json: Value| ClarityValue::from_json(&json);
+        assert_eq!(from_json(json!({ "value": "1" })), Err(invalid("missing type")));
+        assert_eq!(from_json(json!({ "type": "uint", "value": 7 })), Ok(ClarityValue::UInt(7)));
+        assert!(matches!(from_json(json!({ "type": "uint", "value": "-1" })), Err(ClarityError::IntegerOverflow(_))));
+        assert!(matches!(from_json(json!({ "type": "(buff 1)", "value": "0xzz" })), Err(ClarityError::InvalidHex(_))));
+        assert_eq!(from_json(json!({ "type": "(response uint UnknownType)", "value": { "type": "uint", "value": "1" } })), Err(invalid("missing success flag")));
+        assert_eq!(from_json(json!({ "type": "(string-ascii 1)", "value": "\u{e9}" })), Err(ClarityError::InvalidAscii));
+        assert!(serde_json::from_str::<ClarityValue>(r#"{"type":"float","value":"1"}"#).is_err());
+    }
+}
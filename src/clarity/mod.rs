@@ -0,0 +1,82 @@
+use std::fmt;
+
+pub mod abi;
+pub mod builder;
+pub mod codegen;
+pub mod conversions;
+pub mod json;
+pub mod repr;
+pub mod types;
+pub mod value;
+
+/// Maximum length of tuple field names (Clarity names).
+pub const CLARITY_NAME_MAX_LENGTH: usize = 128;
+/// Maximum size of a serialized value accepted by the node (1 MiB).
+pub const MAX_VALUE_SIZE: usize = 1024 * 1024;
+/// Maximum nesting of compound values accepted by the node.
+pub const MAX_TYPE_DEPTH: usize = 32;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ClarityError {
+    /// The input ends in the middle of a value
+    UnexpectedEnd,
+    UnknownTypeId(u8),
+    /// Bytes left after a complete value
+    TrailingBytes(usize),
+    InvalidUtf8,
+    /// `string-ascii` only holds printable ASCII and whitespace
+    InvalidAscii,
+    InvalidPrincipal(String),
+    InvalidTupleName(String),
+    /// Tuples cannot be empty nor hold the same name twice
+    InvalidTuple,
+    /// Longer than the length prefix, [`MAX_VALUE_SIZE`] or the decode limits allow
+    ValueTooLarge(usize),
+    /// Nested deeper than the decode limits allow
+    DepthExceeded(usize),
+    InvalidHex(String),
+    /// Not a valid human-readable representation
+    InvalidRepr(String),
+    /// The value is not of the type being converted to
+    UnexpectedType(String),
+    IntegerOverflow(String),
+    /// Expected and actual length of a fixed-size buffer
+    UnexpectedLength(usize, usize),
+    /// Not in the JSON shape of the Stacks Blockchain API
+    InvalidJson(String),
+    /// The value does not fit a type signature
+    TypeMismatch(String),
+    /// Expected and actual number of function arguments
+    WrongArgumentCount(usize, usize),
+}
+
+impl fmt::Display for ClarityError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        match self {
+            ClarityError::UnexpectedEnd => f.write_str("Unexpected end of Clarity value"),
+            ClarityError::UnknownTypeId(v) => f.write_str(&format!("Unknown Clarity type id {v:#04x}")),
+            ClarityError::TrailingBytes(v) => f.write_str(&format!("{v} trailing bytes after Clarity value")),
+            ClarityError::InvalidUtf8 => f.write_str("Invalid UTF-8 string"),
+            ClarityError::InvalidAscii => f.write_str("Invalid ASCII string"),
+            ClarityError::InvalidPrincipal(v) => f.write_str(&format!("Invalid principal: {v}")),
+            ClarityError::InvalidTupleName(v) => f.write_str(&format!("Invalid tuple field name {v:?}")),
+            ClarityError::InvalidTuple => f.write_str("Invalid tuple"),
+            ClarityError::ValueTooLarge(v) => f.write_str(&format!("Clarity value too large ({v} bytes)")),
+            ClarityError::DepthExceeded(v) => f.write_str(&format!("Clarity value nested deeper than {v}")),
+            ClarityError::InvalidHex(v) => f.write_str(&format!("Invalid hex: {v}")),
+            ClarityError::InvalidRepr(v) => f.write_str(&format!("Invalid Clarity value: {v}")),
+            ClarityError::UnexpectedType(v) => f.write_str(&format!("Unexpected Clarity type: {v}")),
+            ClarityError::IntegerOverflow(v) => f.write_str(&format!("Integer overflow: {v}")),
+            ClarityError::UnexpectedLength(expected, found) => {
+                f.write_str(&format!("Expected a buffer of {expected} bytes, found {found}"))
+            }
+            ClarityError::InvalidJson(v) => f.write_str(&format!("Invalid Clarity JSON: {v}")),
+            ClarityError::TypeMismatch(v) => f.write_str(&format!("Type mismatch: {v}")),
+            ClarityError::WrongArgumentCount(expected, found) => {
+                f.write_str(&format!("Expected {expected} arguments, found {found}"))
+            }
+        }
+    }
+}
+
+impl std::error::Error for ClarityError {}
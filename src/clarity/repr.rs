@@ -0,0 +1,381 @@
+//! The human-readable representation of Clarity values, e.g. `(tuple (amount u100) (to 'SP...))`.
+//!
+//! Values print the way they are written in Clarity source, so the output parses back to
+//! the same value; principals keep their leading `'`.
+
+use std::collections::BTreeMap;
+use std::fmt;
+use std::str::FromStr;
+
+use crate::address::principal::Principal;
+
+use super::value::ClarityValue;
+use super::{ClarityError, MAX_TYPE_DEPTH};
+
+impl fmt::Display for ClarityValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        match self {
+            ClarityValue::Int(value) => f.write_str(&format!("{value}")),
+            ClarityValue::UInt(value) => f.write_str(&format!("u{value}")),
+            ClarityValue::Bool(value) => f.write_str(&format!("{value}")),
+            ClarityValue::Buffer(value) => f.write_str(&format!("0x{}", hex::encode(value))),
+            ClarityValue::StringAscii(value) => f.write_str(&format!("\"{}\"", escape(value))),
+            ClarityValue::StringUtf8(value) => f.write_str(&format!("u\"{}\"", escape(value))),
+            ClarityValue::Principal(value) => f.write_str(&format!("'{value}")),
+            ClarityValue::List(values) => {
+                f.write_str("(list")?;
+                for value in values {
+                    f.write_str(&format!(" {value}"))?;
+                }
+                f.write_str(")")
+            }
+            ClarityValue::Tuple(fields) => {
+                f.write_str("(tuple")?;
+                for (name, value) in fields {
+                    f.write_str(&format!(" ({name} {value})"))?;
+                }
+                f.write_str(")")
+            }
+            ClarityValue::OptionalNone => f.write_str("none"),
+            ClarityValue::OptionalSome(value) => f.write_str(&format!("(some {value})")),
+            ClarityValue::ResponseOk(value) => f.write_str(&format!("(ok {value})")),
+            ClarityValue::ResponseErr(value) => f.write_str(&format!("(err {value})")),
+        }
+    }
+}
+
+/// Escapes quotes, backslashes and control characters; non-ASCII characters become `\u{...}`.
+fn escape(value: &str) -> String {
+    value
+        .chars()
+        .map(|c| match c {
+            '"' => String::from("\\\""),
+            '\\' => String::from("\\\\"),
+            '\n' => String::from("\\n"),
+            '\t' => String::from("\\t"),
+            '\r' => String::from("\\r"),
+            '\0' => String::from("\\0"),
+            c if c.is_ascii() => c.to_string(),
+            c => format!("\\u{{{:x}}}", c as u32),
+        })
+        .collect()
+}
+
+/// Parses the representation printed by `Display`, also accepting `{ name: value, ... }`
+/// tuples.
+///
+/// Usage:
+/// ```rust
+/// use std::str::FromStr;
+/// use stacks_rs::clarity::value::ClarityValue;
+/// let value = ClarityValue::from_str("(tuple (amount u100) (to 'SP3FGQ8Z7JY9BWYZ5WM53E0M9NK7WHJF0691NZ159))").unwrap();
+/// assert_eq!(value, ClarityValue::from_str("{ to: 'SP3FGQ8Z7JY9BWYZ5WM53E0M9NK7WHJF0691NZ159, amount: u100 }").unwrap());
+/// assert_eq!(value.to_string(), "(tuple (amount u100) (to 'SP3FGQ8Z7JY9BWYZ5WM53E0M9NK7WHJF0691NZ159))");
+/// ```
+impl FromStr for ClarityValue {
+    type Err = ClarityError;
+
+    fn from_str(repr: &str) -> Result<Self, Self::Err> {
+        let mut parser = Parser::new(repr);
+        let value = parser.value()?;
+        parser.skip_whitespace();
+        if parser.position != repr.len() {
+            return Err(parser.error("unexpected input after the value"));
+        }
+        Ok(value)
+    }
+}
+
+/// Also reads type signatures, see [`super::types`].
+pub(super) struct Parser<'a> {
+    pub(super) input: &'a str,
+    pub(super) position: usize,
+    /// Compound forms being parsed
+    depth: usize,
+}
+
+impl<'a> Parser<'a> {
+    pub(super) fn new(input: &'a str) -> Self {
+        Parser { input, position: 0, depth: 0 }
+    }
+
+    pub(super) fn error(&self, message: &str) -> ClarityError {
+        ClarityError::InvalidRepr(format!("{message} at offset {}", self.position))
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.input[self.position..].chars().next()
+    }
+
+    fn next_char(&mut self) -> Option<char> {
+        let c = self.peek()?;
+        self.position += c.len_utf8();
+        Some(c)
+    }
+
+    pub(super) fn skip_whitespace(&mut self) {
+        while let Some(c) = self.peek().filter(|c| c.is_whitespace()) {
+            self.position += c.len_utf8();
+        }
+    }
+
+    /// Parses the rest of a compound form with `parse`, at most [`MAX_TYPE_DEPTH`] forms deep as
+    /// the node decodes values, so deeply nested input cannot overflow the stack.
+    pub(super) fn nested<T>(&mut self, parse: fn(&mut Self) -> Result<T, ClarityError>) -> Result<T, ClarityError> {
+        if self.depth >= MAX_TYPE_DEPTH {
+            return Err(ClarityError::DepthExceeded(MAX_TYPE_DEPTH));
+        }
+        self.depth += 1;
+        let result = parse(self);
+        self.depth -= 1;
+        result
+    }
+
+    pub(super) fn expect(&mut self, expected: char) -> Result<(), ClarityError> {
+        self.skip_whitespace();
+        if self.next_char() != Some(expected) {
+            return Err(self.error(&format!("expected '{expected}'")));
+        }
+        Ok(())
+    }
+
+    /// Whether the next non-blank character is `c`, consuming it if so.
+    pub(super) fn consume(&mut self, c: char) -> bool {
+        self.skip_whitespace();
+        if self.peek() == Some(c) {
+            self.position += 1;
+            return true;
+        }
+        false
+    }
+
+    /// A run of characters up to a blank, a delimiter or a quote.
+    pub(super) fn atom(&mut self) -> Result<&'a str, ClarityError> {
+        self.skip_whitespace();
+        let start = self.position;
+        while self.peek().is_some_and(|c| !c.is_whitespace() && !"(){},:\"".contains(c)) {
+            self.next_char();
+        }
+        if start == self.position {
+            return Err(self.error("expected a value"));
+        }
+        let input = self.input;
+        Ok(&input[start..self.position])
+    }
+
+    fn value(&mut self) -> Result<ClarityValue, ClarityError> {
+        self.skip_whitespace();
+        match self.peek() {
+            Some('(') => {
+                self.position += 1;
+                self.nested(Self::form)
+            }
+            Some('{') => {
+                self.position += 1;
+                self.nested(Self::braced_tuple)
+            }
+            Some('"') => {
+                let value = self.string()?;
+                if !value.is_ascii() {
+                    return Err(self.error("non-ASCII character in string-ascii"));
+                }
+                Ok(ClarityValue::StringAscii(value))
+            }
+            Some('u') if self.input[self.position..].starts_with("u\"") => {
+                self.position += 1;
+                Ok(ClarityValue::StringUtf8(self.string()?))
+            }
+            Some('\'') => {
+                self.position += 1;
+                let principal = self.atom()?;
+                Principal::from_str(principal)
+                    .map(ClarityValue::Principal)
+                    .map_err(|error| ClarityError::InvalidPrincipal(format!("{error}")))
+            }
+            _ => self.literal(),
+        }
+    }
+
+    fn literal(&mut self) -> Result<ClarityValue, ClarityError> {
+        self.skip_whitespace();
+        let start = self.position;
+        let atom = self.atom()?;
+        let value = match atom {
+            "true" => Some(ClarityValue::Bool(true)),
+            "false" => Some(ClarityValue::Bool(false)),
+            "none" => Some(ClarityValue::OptionalNone),
+            _ if atom.starts_with("0x") => hex::decode(&atom[2..]).ok().map(ClarityValue::Buffer),
+            _ if atom.starts_with('u') => atom[1..].parse().ok().map(ClarityValue::UInt),
+            _ => atom.parse().ok().map(ClarityValue::Int),
+        };
+        value.ok_or_else(|| {
+            self.position = start;
+            self.error(&format!("invalid literal {atom:?}"))
+        })
+    }
+
+    /// The rest of a `(...)` form, after the opening parenthesis.
+    fn form(&mut self) -> Result<ClarityValue, ClarityError> {
+        let value = match self.atom()? {
+            "list" => {
+                let mut values = Vec::new();
+                while !self.consume(')') {
+                    values.push(self.value()?);
+                }
+                return Ok(ClarityValue::List(values));
+            }
+            "tuple" => {
+                let mut fields = BTreeMap::new();
+                while !self.consume(')') {
+                    self.expect('(')?;
+                    let name = self.atom()?.to_string();
+                    let value = self.value()?;
+                    self.expect(')')?;
+                    if fields.insert(name, value).is_some() {
+                        return Err(ClarityError::InvalidTuple);
+                    }
+                }
+                if fields.is_empty() {
+                    return Err(ClarityError::InvalidTuple);
+                }
+                return Ok(ClarityValue::Tuple(fields));
+            }
+            "some" => ClarityValue::OptionalSome(Box::new(self.value()?)),
+            "ok" => ClarityValue::ResponseOk(Box::new(self.value()?)),
+            "err" => ClarityValue::ResponseErr(Box::new(self.value()?)),
+            other => return Err(self.error(&format!("unknown form {other:?}"))),
+        };
+        self.expect(')')?;
+        Ok(value)
+    }
+
+    /// The rest of a `{ name: value, ... }` tuple, after the opening brace.
+    fn braced_tuple(&mut self) -> Result<ClarityValue, ClarityError> {
+        let mut fields = BTreeMap::new();
+        if self.consume('}') {
+            return Err(ClarityError::InvalidTuple);
+        }
+        loop {
+            let name = self.atom()?.to_string();
+            self.expect(':')?;
+            if fields.insert(name, self.value()?).is_some() {
+                return Err(ClarityError::InvalidTuple);
+            }
+            if self.consume('}') {
+                return Ok(ClarityValue::Tuple(fields));
+            }
+            self.expect(',')?;
+        }
+    }
+
+    /// A quoted string with its escapes resolved.
+    fn string(&mut self) -> Result<String, ClarityError> {
+        self.expect('"')?;
+        let mut value = String::new();
+        loop {
+            match self.next_char().ok_or_else(|| self.error("unterminated string"))? {
+                '"' => return Ok(value),
+                '\\' => {
+                    let escaped = match self.next_char() {
+                        Some('"') => '"',
+                        Some('\\') => '\\',
+                        Some('n') => '\n',
+                        Some('t') => '\t',
+                        Some('r') => '\r',
+                        Some('0') => '\0',
+                        Some('u') => self.unicode_escape()?,
+                        _ => return Err(self.error("invalid escape")),
+                    };
+                    value.push(escaped);
+                }
+                c => value.push(c),
+            }
+        }
+    }
+
+    /// `{...}` of a `\u{...}` escape.
+    fn unicode_escape(&mut self) -> Result<char, ClarityError> {
+        let rest = &self.input[self.position..];
+        let end = rest.find('}').ok_or_else(|| self.error("unterminated unicode escape"))?;
+        let c = rest
+            .strip_prefix('{')
+            .and_then(|rest| u32::from_str_radix(&rest[..end - 1], 16).ok())
+            .and_then(char::from_u32)
+            .ok_or_else(|| self.error("invalid unicode escape"))?;
+        self.position += end + 1;
+        Ok(c)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ADDRESS: &str = "SP3FGQ8Z7JY9BWYZ5WM53E0M9NK7WHJF0691NZ159";
+
+    #[test]
+    fn test_roundtrip() {
+        let reprs = [
+            "-170141183460469231731687303715884105728",
+            "u340282366920938463463374607431768211455",
+            "true",
+            "0x",
+            "0xdeadbeef",
+            "\"hello \\\"world\\\"\\n\"",
+            "u\"hello \\u{1f30e}\"",
+            "'SP3FGQ8Z7JY9BWYZ5WM53E0M9NK7WHJF0691NZ159",
+            "'SP3FGQ8Z7JY9BWYZ5WM53E0M9NK7WHJF0691NZ159.my-token",
+            "(list)",
+            "(list 1 2 3)",
+            "(tuple (a (list (some u1) none)) (b (ok (err \"x\"))))",
+        ];
+        for repr in reprs {
+            let value = ClarityValue::from_str(repr).unwrap();
+            assert_eq!(value.to_string(), repr);
+        }
+    }
+
+    #[test]
+    fn test_parse() {
+        let value = ClarityValue::from_str(&format!("{{ amount: u100, to: '{ADDRESS}, memo: (some 0x00) }}")).unwrap();
+        let expected = ClarityValue::Tuple(BTreeMap::from([
+            (String::from("amount"), ClarityValue::UInt(100)),
+            (String::from("memo"), ClarityValue::OptionalSome(Box::new(ClarityValue::Buffer(vec![0])))),
+            (String::from("to"), ClarityValue::Principal(Principal::from_str(ADDRESS).unwrap())),
+        ]));
+        assert_eq!(value, expected);
+        assert_eq!(ClarityValue::from_str("  ( list\n 1\t-2 ) ").unwrap(), ClarityValue::List(vec![ClarityValue::Int(1), ClarityValue::Int(-2)]));
+        assert_eq!(ClarityValue::from_str("u\"caf\u{e9}\"").unwrap(), ClarityValue::StringUtf8(String::from("caf\u{e9}")));
+    }
+
+    #[test]
+    fn test_invalid_reprs() {
+        for repr in ["", "u-1", "0xabc", "(list 1", "(tuple (a 1) (a 2))", "(foo 1)", "\"caf\u{e9}\"", "\"open", "1 2", "(some)", "\"\\q\"", "(tuple)", "{}"] {
+            assert!(ClarityValue::from_str(repr).is_err(), "{repr}");
+        }
+        assert!(matches!(ClarityValue::from_str("'SP3FGQ8Z7JY9BWYZ5WM53E0M9NK7WHJF0691NZ158"), Err(ClarityError::InvalidPrincipal(_))));
+        assert_eq!(
+            ClarityValue::from_str("(list 1 x)"),
+            Err(ClarityError::InvalidRepr(String::from("invalid literal \"x\" at offset 8")))
+        );
+    }
+
+    #[test]
+    fn test_unicode_whitespace() {
+        assert_eq!(ClarityValue::from_str("\u{3000}u1").unwrap(), ClarityValue::UInt(1));
+        assert_eq!(ClarityValue::from_str("(list\u{3000}1\u{2003}2)\u{3000}").unwrap(), ClarityValue::List(vec![ClarityValue::Int(1), ClarityValue::Int(2)]));
+    }
+
+    #[test]
+    fn test_depth_limit() {
+        let nested = |depth: usize| format!("{}true{}", "(some ".repeat(depth), ")".repeat(depth));
+        let mut value = ClarityValue::Bool(true);
+        for _ in 0..MAX_TYPE_DEPTH {
+            value = ClarityValue::some(value);
+        }
+        assert_eq!(ClarityValue::from_str(&nested(MAX_TYPE_DEPTH)).unwrap(), value);
+        assert_eq!(ClarityValue::from_str(&nested(MAX_TYPE_DEPTH + 1)), Err(ClarityError::DepthExceeded(MAX_TYPE_DEPTH)));
+        assert_eq!(ClarityValue::from_str(&"(some ".repeat(200_000)), Err(ClarityError::DepthExceeded(MAX_TYPE_DEPTH)));
+        assert_eq!(ClarityValue::from_str(&"{ a: ".repeat(200_000)), Err(ClarityError::DepthExceeded(MAX_TYPE_DEPTH)));
+    }
+}
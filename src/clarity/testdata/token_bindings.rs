@@ -0,0 +1,19 @@
+// Bindings of SP3FGQ8Z7JY9BWYZ5WM53E0M9NK7WHJF0691NZ159.token, generated by stacks_rs::clarity::codegen.
+
+pub const CONTRACT_ADDRESS: &str = "SP3FGQ8Z7JY9BWYZ5WM53E0M9NK7WHJF0691NZ159";
+pub const CONTRACT_NAME: &str = "token";
+
+/// `transfer` (public): `(amount uint) (recipient principal) (memo (optional (buff 34)))` -> `(response bool uint)`
+pub fn transfer(amount: u128, recipient: ::stacks_rs::address::principal::Principal, memo: Option<Vec<u8>>) -> Result<::stacks_rs::transaction::builder::ContractCallBuilder, ::stacks_rs::clarity::ClarityError> {
+    ::stacks_rs::clarity::codegen::contract_call(CONTRACT_ADDRESS, CONTRACT_NAME, "transfer", vec![::stacks_rs::clarity::value::ClarityValue::from(amount), ::stacks_rs::clarity::value::ClarityValue::from(recipient), ::stacks_rs::clarity::value::ClarityValue::from(memo)], &[("amount", "uint"), ("recipient", "principal"), ("memo", "(optional (buff 34))")])
+}
+
+/// `set-name!` (public): `(name (string-ascii 32)) (type (tuple (id int)))` -> `(response bool UnknownType)`
+pub fn set_name(name: &str, r#type: ::stacks_rs::clarity::value::ClarityValue) -> Result<::stacks_rs::transaction::builder::ContractCallBuilder, ::stacks_rs::clarity::ClarityError> {
+    ::stacks_rs::clarity::codegen::contract_call(CONTRACT_ADDRESS, CONTRACT_NAME, "set-name!", vec![::stacks_rs::clarity::value::ClarityValue::string_ascii(name)?, r#type], &[("name", "(string-ascii 32)"), ("type", "(tuple (id int))")])
+}
+
+/// `set-name` (read-only): `` -> `bool`
+pub fn set_name_() -> Result<::stacks_rs::transaction::builder::ContractCallBuilder, ::stacks_rs::clarity::ClarityError> {
+    ::stacks_rs::clarity::codegen::contract_call(CONTRACT_ADDRESS, CONTRACT_NAME, "set-name", vec![], &[])
+}
@@ -0,0 +1,275 @@
+//! Clarity type signatures, e.g. `(list 10 (tuple (to principal) (ustx uint)))`, and checking
+//! values against them.
+//!
+//! Lengths are maximums: a `(buff 20)` holds up to 20 bytes, a `(string-utf8 10)` up to ten
+//! characters. `none` fits any optional and an empty list any list.
+//!
+//! Usage:
+//! ```rust
+//! use std::str::FromStr;
+//! use stacks_rs::clarity::types::{check_type, ClarityType};
+//! use stacks_rs::clarity::value::ClarityValue;
+//! let signature = ClarityType::from_str("(list 2 (optional uint))").unwrap();
+//! assert!(check_type(&ClarityValue::from(vec![Some(1u64), None]), &signature).is_ok());
+//! assert!(check_type(&ClarityValue::from(vec![1u64, 2, 3]), &signature).is_err());
+//! ```
+
+use std::collections::BTreeMap;
+use std::fmt;
+use std::str::FromStr;
+
+use crate::address::principal::Principal;
+
+use super::repr::Parser;
+use super::value::ClarityValue;
+use super::ClarityError;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ClarityType {
+    Int,
+    UInt,
+    Bool,
+    Principal,
+    Buffer(u32),
+    StringAscii(u32),
+    StringUtf8(u32),
+    /// Element type and maximum length
+    List(Box<ClarityType>, u32),
+    Tuple(BTreeMap<String, ClarityType>),
+    Optional(Box<ClarityType>),
+    Response(Box<ClarityType>, Box<ClarityType>),
+    /// `<trait-name>` argument, passed as a contract principal; contract ABIs leave the name empty
+    TraitReference(String),
+    /// Type of a value that cannot exist, e.g. the err side of a response that never fails
+    NoType,
+}
+
+impl fmt::Display for ClarityType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        match self {
+            ClarityType::Int => f.write_str("int"),
+            ClarityType::UInt => f.write_str("uint"),
+            ClarityType::Bool => f.write_str("bool"),
+            ClarityType::Principal => f.write_str("principal"),
+            ClarityType::Buffer(length) => f.write_str(&format!("(buff {length})")),
+            ClarityType::StringAscii(length) => f.write_str(&format!("(string-ascii {length})")),
+            ClarityType::StringUtf8(length) => f.write_str(&format!("(string-utf8 {length})")),
+            ClarityType::List(element, length) => f.write_str(&format!("(list {length} {element})")),
+            ClarityType::Tuple(fields) => {
+                let fields: Vec<String> = fields.iter().map(|(name, field)| format!("({name} {field})")).collect();
+                f.write_str(&format!("(tuple {})", fields.join(" ")))
+            }
+            ClarityType::Optional(inner) => f.write_str(&format!("(optional {inner})")),
+            ClarityType::Response(ok, err) => f.write_str(&format!("(response {ok} {err})")),
+            ClarityType::TraitReference(name) => f.write_str(&format!("<{name}>")),
+            ClarityType::NoType => f.write_str("UnknownType"),
+        }
+    }
+}
+
+impl FromStr for ClarityType {
+    type Err = ClarityError;
+
+    fn from_str(signature: &str) -> Result<Self, Self::Err> {
+        let mut parser = Parser::new(signature);
+        let signature = parser.signature()?;
+        parser.skip_whitespace();
+        if parser.position != parser.input.len() {
+            return Err(parser.error("unexpected input after the type"));
+        }
+        Ok(signature)
+    }
+}
+
+impl Parser<'_> {
+    fn signature(&mut self) -> Result<ClarityType, ClarityError> {
+        if !self.consume('(') {
+            let atom = self.atom()?;
+            return match atom {
+                "int" => Ok(ClarityType::Int),
+                "uint" => Ok(ClarityType::UInt),
+                "bool" => Ok(ClarityType::Bool),
+                "principal" => Ok(ClarityType::Principal),
+                "UnknownType" => Ok(ClarityType::NoType),
+                _ => match atom.strip_prefix('<').and_then(|atom| atom.strip_suffix('>')) {
+                    Some(name) => Ok(ClarityType::TraitReference(name.to_string())),
+                    None => Err(self.error(&format!("unknown type {atom:?}"))),
+                },
+            };
+        }
+        self.nested(Self::compound_signature)
+    }
+
+    /// The rest of a `(...)` type, after the opening parenthesis.
+    fn compound_signature(&mut self) -> Result<ClarityType, ClarityError> {
+        let signature = match self.atom()? {
+            "buff" => ClarityType::Buffer(self.length()?),
+            "string-ascii" => ClarityType::StringAscii(self.length()?),
+            "string-utf8" => ClarityType::StringUtf8(self.length()?),
+            "list" => {
+                let length = self.length()?;
+                ClarityType::List(Box::new(self.signature()?), length)
+            }
+            "tuple" => {
+                let mut fields = BTreeMap::new();
+                while !self.consume(')') {
+                    self.expect('(')?;
+                    let name = self.atom()?.to_string();
+                    let field = self.signature()?;
+                    self.expect(')')?;
+                    if fields.insert(name, field).is_some() {
+                        return Err(ClarityError::InvalidTuple);
+                    }
+                }
+                if fields.is_empty() {
+                    return Err(ClarityError::InvalidTuple);
+                }
+                return Ok(ClarityType::Tuple(fields));
+            }
+            "optional" => ClarityType::Optional(Box::new(self.signature()?)),
+            "response" => ClarityType::Response(Box::new(self.signature()?), Box::new(self.signature()?)),
+            other => return Err(self.error(&format!("unknown type {other:?}"))),
+        };
+        self.expect(')')?;
+        Ok(signature)
+    }
+
+    fn length(&mut self) -> Result<u32, ClarityError> {
+        let atom = self.atom()?;
+        atom.parse().map_err(|_| self.error(&format!("invalid length {atom:?}")))
+    }
+}
+
+/// Checks that `value` fits `expected`, reporting the first mismatch and where it is.
+pub fn check_type(value: &ClarityValue, expected: &ClarityType) -> Result<(), ClarityError> {
+    check_at(value, expected, "")
+}
+
+fn check_at(value: &ClarityValue, expected: &ClarityType, path: &str) -> Result<(), ClarityError> {
+    let mismatch = |found: &str| {
+        let location = if path.is_empty() { String::new() } else { format!(" at {path}") };
+        Err(ClarityError::TypeMismatch(format!("expected {expected}, found {found}{location}")))
+    };
+    let fits = |length: usize, max: &u32| usize::try_from(*max).is_ok_and(|max| length <= max);
+
+    match (expected, value) {
+        (ClarityType::Int, ClarityValue::Int(_)) | (ClarityType::UInt, ClarityValue::UInt(_)) | (ClarityType::Bool, ClarityValue::Bool(_)) => Ok(()),
+        (ClarityType::Principal, ClarityValue::Principal(_)) => Ok(()),
+        (ClarityType::TraitReference(_), ClarityValue::Principal(Principal::Contract(..))) => Ok(()),
+        (ClarityType::Buffer(max), ClarityValue::Buffer(bytes)) if fits(bytes.len(), max) => Ok(()),
+        (ClarityType::StringAscii(max), ClarityValue::StringAscii(value)) if fits(value.len(), max) => Ok(()),
+        (ClarityType::StringUtf8(max), ClarityValue::StringUtf8(value)) if fits(value.chars().count(), max) => Ok(()),
+        (ClarityType::List(element, max), ClarityValue::List(values)) if fits(values.len(), max) => {
+            for (index, value) in values.iter().enumerate() {
+                check_at(value, element, &format!("{path}[{index}]"))?;
+            }
+            Ok(())
+        }
+        (ClarityType::Tuple(fields), ClarityValue::Tuple(values)) if fields.keys().eq(values.keys()) => {
+            for (name, field) in fields {
+                let field_path = if path.is_empty() { name.clone() } else { format!("{path}.{name}") };
+                check_at(&values[name], field, &field_path)?;
+            }
+            Ok(())
+        }
+        (ClarityType::Optional(_), ClarityValue::OptionalNone) => Ok(()),
+        (ClarityType::Optional(inner), ClarityValue::OptionalSome(value)) => check_at(value, inner, path),
+        (ClarityType::Response(ok, _), ClarityValue::ResponseOk(value)) => check_at(value, ok, path),
+        (ClarityType::Response(_, err), ClarityValue::ResponseErr(value)) => check_at(value, err, path),
+        _ => mismatch(&describe(value)),
+    }
+}
+
+/// Short description of a value for mismatch errors, e.g. `(buff 32)` or `tuple (a, b)`.
+fn describe(value: &ClarityValue) -> String {
+    match value {
+        ClarityValue::Int(_) => String::from("int"),
+        ClarityValue::UInt(_) => String::from("uint"),
+        ClarityValue::Bool(_) => String::from("bool"),
+        ClarityValue::Buffer(bytes) => format!("(buff {})", bytes.len()),
+        ClarityValue::StringAscii(value) => format!("(string-ascii {})", value.len()),
+        ClarityValue::StringUtf8(value) => format!("(string-utf8 {})", value.chars().count()),
+        ClarityValue::Principal(Principal::Standard(_)) => String::from("standard principal"),
+        ClarityValue::Principal(Principal::Contract(..)) => String::from("contract principal"),
+        ClarityValue::List(values) => format!("list of {}", values.len()),
+        ClarityValue::Tuple(fields) => format!("tuple ({})", fields.keys().cloned().collect::<Vec<_>>().join(", ")),
+        ClarityValue::OptionalNone | ClarityValue::OptionalSome(_) => String::from("optional"),
+        ClarityValue::ResponseOk(_) | ClarityValue::ResponseErr(_) => String::from("response"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::clarity::MAX_TYPE_DEPTH;
+
+    use super::*;
+
+    const ADDRESS: &str = "SP3FGQ8Z7JY9BWYZ5WM53E0M9NK7WHJF0691NZ159";
+
+    fn check(value: &str, signature: &str) -> Result<(), ClarityError> {
+        check_type(&ClarityValue::from_str(value).unwrap(), &ClarityType::from_str(signature).unwrap())
+    }
+
+    #[test]
+    fn test_signature_roundtrip() {
+        let signatures = [
+            "int",
+            "(buff 20)",
+            "(string-utf8 34)",
+            "(list 200 (tuple (to principal) (ustx uint)))",
+            "(optional (tuple (hashbytes (buff 32)) (version (buff 1))))",
+            "(response bool (string-ascii 10))",
+            "(response uint UnknownType)",
+            "<sip-010-trait>",
+        ];
+        for signature in signatures {
+            assert_eq!(ClarityType::from_str(signature).unwrap().to_string(), signature);
+        }
+        assert_eq!(
+            ClarityType::from_str(" ( list  5\n uint ) ").unwrap(),
+            ClarityType::List(Box::new(ClarityType::UInt), 5)
+        );
+        assert!(ClarityType::from_str("(buff -1)").is_err());
+        assert!(ClarityType::from_str("(tuple)").is_err());
+        assert!(ClarityType::from_str("uint uint").is_err());
+        assert!(ClarityType::from_str("<sip-010-trait").is_err());
+    }
+
+    #[test]
+    fn test_signature_limits() {
+        assert_eq!(ClarityType::from_str("\u{3000}(optional\u{2003}int)\u{3000}").unwrap(), ClarityType::Optional(Box::new(ClarityType::Int)));
+        let nested = |depth: usize| format!("{}int{}", "(optional ".repeat(depth), ")".repeat(depth));
+        assert!(ClarityType::from_str(&nested(MAX_TYPE_DEPTH)).is_ok());
+        assert_eq!(ClarityType::from_str(&nested(MAX_TYPE_DEPTH + 1)), Err(ClarityError::DepthExceeded(MAX_TYPE_DEPTH)));
+        assert_eq!(ClarityType::from_str(&"(list 1 ".repeat(200_000)), Err(ClarityError::DepthExceeded(MAX_TYPE_DEPTH)));
+        assert_eq!(ClarityType::from_str(&"(response int ".repeat(200_000)), Err(ClarityError::DepthExceeded(MAX_TYPE_DEPTH)));
+    }
+
+    #[test]
+    fn test_check_type() {
+        assert!(check("u1", "uint").is_ok());
+        assert!(check("0x0102", "(buff 2)").is_ok());
+        assert!(check("u\"caf\u{e9}\"", "(string-utf8 4)").is_ok());
+        assert!(check("(list)", "(list 1 int)").is_ok());
+        assert!(check("none", "(optional (buff 1))").is_ok());
+        assert!(check("(err u1)", "(response bool uint)").is_ok());
+        assert!(check(&format!("'{ADDRESS}.token"), "<ft-trait>").is_ok());
+        assert!(check(&format!("{{ to: '{ADDRESS}, ustx: u5 }}"), "(tuple (to principal) (ustx uint))").is_ok());
+
+        assert!(check("1", "uint").is_err());
+        assert!(check("0x010203", "(buff 2)").is_err());
+        assert!(check("\"abc\"", "(string-utf8 3)").is_err());
+        assert!(check("(list 1 2)", "(list 1 int)").is_err());
+        assert!(check(&format!("'{ADDRESS}"), "<ft-trait>").is_err());
+        assert!(check("{ a: 1 }", "(tuple (a int) (b int))").is_err());
+    }
+
+    #[test]
+    fn test_mismatch_location() {
+        assert_eq!(
+            check("(list { pox: { hashbytes: 0x00, version: 0x01 } })", "(list 1 (tuple (pox (tuple (hashbytes (buff 32)) (version uint)))))"),
+            Err(ClarityError::TypeMismatch(String::from("expected uint, found (buff 1) at [0].pox.version")))
+        );
+        assert_eq!(check("(some 1)", "(optional bool)"), Err(ClarityError::TypeMismatch(String::from("expected bool, found int"))));
+    }
+}
@@ -0,0 +1,546 @@
+use std::collections::BTreeMap;
+
+use crate::address::principal::Principal;
+use crate::address::stacks_address::StacksAddress;
+use crate::address::ADDRESS_HASH_LENGTH;
+
+use super::{ClarityError, CLARITY_NAME_MAX_LENGTH, MAX_TYPE_DEPTH, MAX_VALUE_SIZE};
+
+/// Type prefix of every serialized value (SIP-005).
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ClarityTypeId {
+    Int,
+    UInt,
+    Buffer,
+    BoolTrue,
+    BoolFalse,
+    PrincipalStandard,
+    PrincipalContract,
+    ResponseOk,
+    ResponseErr,
+    OptionalNone,
+    OptionalSome,
+    List,
+    Tuple,
+    StringAscii,
+    StringUtf8,
+}
+
+impl ClarityTypeId {
+    pub fn value(&self) -> u8 {
+        match *self {
+            ClarityTypeId::Int => 0x00,
+            ClarityTypeId::UInt => 0x01,
+            ClarityTypeId::Buffer => 0x02,
+            ClarityTypeId::BoolTrue => 0x03,
+            ClarityTypeId::BoolFalse => 0x04,
+            ClarityTypeId::PrincipalStandard => 0x05,
+            ClarityTypeId::PrincipalContract => 0x06,
+            ClarityTypeId::ResponseOk => 0x07,
+            ClarityTypeId::ResponseErr => 0x08,
+            ClarityTypeId::OptionalNone => 0x09,
+            ClarityTypeId::OptionalSome => 0x0a,
+            ClarityTypeId::List => 0x0b,
+            ClarityTypeId::Tuple => 0x0c,
+            ClarityTypeId::StringAscii => 0x0d,
+            ClarityTypeId::StringUtf8 => 0x0e,
+        }
+    }
+
+    pub fn from_value(value: u8) -> Option<Self> {
+        match value {
+            0x00 => Some(ClarityTypeId::Int),
+            0x01 => Some(ClarityTypeId::UInt),
+            0x02 => Some(ClarityTypeId::Buffer),
+            0x03 => Some(ClarityTypeId::BoolTrue),
+            0x04 => Some(ClarityTypeId::BoolFalse),
+            0x05 => Some(ClarityTypeId::PrincipalStandard),
+            0x06 => Some(ClarityTypeId::PrincipalContract),
+            0x07 => Some(ClarityTypeId::ResponseOk),
+            0x08 => Some(ClarityTypeId::ResponseErr),
+            0x09 => Some(ClarityTypeId::OptionalNone),
+            0x0a => Some(ClarityTypeId::OptionalSome),
+            0x0b => Some(ClarityTypeId::List),
+            0x0c => Some(ClarityTypeId::Tuple),
+            0x0d => Some(ClarityTypeId::StringAscii),
+            0x0e => Some(ClarityTypeId::StringUtf8),
+            _ => None,
+        }
+    }
+}
+
+/// A Clarity value, as passed to and returned by contract functions.
+///
+/// Tuples are kept sorted by field name, the order the node serializes them in.
+///
+/// Usage:
+/// ```rust
+/// use std::collections::BTreeMap;
+/// use stacks_rs::clarity::value::ClarityValue;
+/// let value = ClarityValue::Tuple(BTreeMap::from([
+///     (String::from("amount"), ClarityValue::UInt(100)),
+///     (String::from("memo"), ClarityValue::OptionalNone),
+/// ]));
+/// let bytes = value.serialize().unwrap();
+/// assert_eq!(ClarityValue::deserialize(&bytes).unwrap(), value);
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ClarityValue {
+    Int(i128),
+    UInt(u128),
+    Bool(bool),
+    Buffer(Vec<u8>),
+    StringAscii(String),
+    StringUtf8(String),
+    Principal(Principal),
+    List(Vec<ClarityValue>),
+    Tuple(BTreeMap<String, ClarityValue>),
+    OptionalNone,
+    OptionalSome(Box<ClarityValue>),
+    ResponseOk(Box<ClarityValue>),
+    ResponseErr(Box<ClarityValue>),
+}
+
+impl ClarityValue {
+    pub fn type_id(&self) -> ClarityTypeId {
+        match self {
+            ClarityValue::Int(_) => ClarityTypeId::Int,
+            ClarityValue::UInt(_) => ClarityTypeId::UInt,
+            ClarityValue::Bool(true) => ClarityTypeId::BoolTrue,
+            ClarityValue::Bool(false) => ClarityTypeId::BoolFalse,
+            ClarityValue::Buffer(_) => ClarityTypeId::Buffer,
+            ClarityValue::StringAscii(_) => ClarityTypeId::StringAscii,
+            ClarityValue::StringUtf8(_) => ClarityTypeId::StringUtf8,
+            ClarityValue::Principal(Principal::Standard(_)) => ClarityTypeId::PrincipalStandard,
+            ClarityValue::Principal(Principal::Contract(..)) => ClarityTypeId::PrincipalContract,
+            ClarityValue::List(_) => ClarityTypeId::List,
+            ClarityValue::Tuple(_) => ClarityTypeId::Tuple,
+            ClarityValue::OptionalNone => ClarityTypeId::OptionalNone,
+            ClarityValue::OptionalSome(_) => ClarityTypeId::OptionalSome,
+            ClarityValue::ResponseOk(_) => ClarityTypeId::ResponseOk,
+            ClarityValue::ResponseErr(_) => ClarityTypeId::ResponseErr,
+        }
+    }
+
+    /// Consensus serialization (SIP-005), e.g. for contract-call arguments.
+    pub fn serialize(&self) -> Result<Vec<u8>, ClarityError> {
+        let mut bytes = Vec::new();
+        self.serialize_into(&mut bytes)?;
+        if bytes.len() > MAX_VALUE_SIZE {
+            return Err(ClarityError::ValueTooLarge(bytes.len()));
+        }
+        Ok(bytes)
+    }
+
+    /// Inverse of [`ClarityValue::serialize`], rejecting trailing bytes and anything over the
+    /// node's own limits.
+    pub fn deserialize(bytes: &[u8]) -> Result<Self, ClarityError> {
+        Self::deserialize_with_limits(bytes, &DecodeLimits::default())
+    }
+
+    /// [`ClarityValue::deserialize`] with tighter `limits`, for bytes from untrusted peers.
+    ///
+    /// Usage:
+    /// ```rust
+    /// use stacks_rs::clarity::value::{ClarityValue, DecodeLimits};
+    /// let limits = DecodeLimits { max_depth: 1, ..DecodeLimits::default() };
+    /// let nested = ClarityValue::some(ClarityValue::some(true)).serialize().unwrap();
+    /// assert!(ClarityValue::deserialize_with_limits(&nested, &limits).is_err());
+    /// ```
+    pub fn deserialize_with_limits(bytes: &[u8], limits: &DecodeLimits) -> Result<Self, ClarityError> {
+        if bytes.len() > limits.max_size {
+            return Err(ClarityError::ValueTooLarge(bytes.len()));
+        }
+        let mut reader = Reader { bytes, limits, depth: 0 };
+        let value = reader.value()?;
+        if !reader.bytes.is_empty() {
+            return Err(ClarityError::TrailingBytes(reader.bytes.len()));
+        }
+        Ok(value)
+    }
+
+    /// Deserializes the value at the start of `bytes`, returning it with the number of bytes read.
+    pub(crate) fn deserialize_prefix(bytes: &[u8]) -> Result<(Self, usize), ClarityError> {
+        let limits = DecodeLimits::default();
+        let mut reader = Reader { bytes: &bytes[..bytes.len().min(MAX_VALUE_SIZE)], limits: &limits, depth: 0 };
+        let value = reader.value()?;
+        Ok((value, bytes.len().min(MAX_VALUE_SIZE) - reader.bytes.len()))
+    }
+
+    /// `0x`-prefixed hex of the serialization, as the Stacks API returns read-only call
+    /// results and takes map keys.
+    ///
+    /// Usage:
+    /// ```rust
+    /// use stacks_rs::clarity::value::ClarityValue;
+    /// let value = ClarityValue::ResponseOk(Box::new(ClarityValue::Bool(true)));
+    /// assert_eq!(value.to_hex().unwrap(), "0x0703");
+    /// assert_eq!(ClarityValue::from_hex("0703").unwrap(), value);
+    /// ```
+    pub fn to_hex(&self) -> Result<String, ClarityError> {
+        Ok(format!("0x{}", self.to_hex_unprefixed()?))
+    }
+
+    /// Same as [`ClarityValue::to_hex`] without the `0x` prefix.
+    pub fn to_hex_unprefixed(&self) -> Result<String, ClarityError> {
+        Ok(hex::encode(self.serialize()?))
+    }
+
+    /// Parses serialized hex, with or without the `0x` prefix.
+    pub fn from_hex(value: &str) -> Result<Self, ClarityError> {
+        let value = value.strip_prefix("0x").unwrap_or(value);
+        let bytes = hex::decode(value).map_err(|error| ClarityError::InvalidHex(format!("{error}")))?;
+        Self::deserialize(&bytes)
+    }
+
+    fn serialize_into(&self, bytes: &mut Vec<u8>) -> Result<(), ClarityError> {
+        bytes.push(self.type_id().value());
+        match self {
+            ClarityValue::Int(value) => bytes.extend(value.to_be_bytes()),
+            ClarityValue::UInt(value) => bytes.extend(value.to_be_bytes()),
+            ClarityValue::Bool(_) | ClarityValue::OptionalNone => {}
+            ClarityValue::Buffer(value) => {
+                bytes.extend(length_prefix(value.len())?);
+                bytes.extend(value);
+            }
+            ClarityValue::StringAscii(value) => {
+                if !is_valid_ascii(value.as_bytes()) {
+                    return Err(ClarityError::InvalidAscii);
+                }
+                bytes.extend(length_prefix(value.len())?);
+                bytes.extend(value.as_bytes());
+            }
+            ClarityValue::StringUtf8(value) => {
+                bytes.extend(length_prefix(value.len())?);
+                bytes.extend(value.as_bytes());
+            }
+            ClarityValue::Principal(principal) => {
+                bytes.push(principal.address().version());
+                bytes.extend(principal.address().hash160());
+                if let Some(contract_name) = principal.contract_name() {
+                    bytes.push(contract_name.len() as u8);
+                    bytes.extend(contract_name.as_bytes());
+                }
+            }
+            ClarityValue::List(values) => {
+                bytes.extend(length_prefix(values.len())?);
+                for value in values {
+                    value.serialize_into(bytes)?;
+                }
+            }
+            ClarityValue::Tuple(fields) => {
+                if fields.is_empty() {
+                    return Err(ClarityError::InvalidTuple);
+                }
+                bytes.extend(length_prefix(fields.len())?);
+                for (name, value) in fields {
+                    if !is_valid_clarity_name(name) {
+                        return Err(ClarityError::InvalidTupleName(name.clone()));
+                    }
+                    bytes.push(name.len() as u8);
+                    bytes.extend(name.as_bytes());
+                    value.serialize_into(bytes)?;
+                }
+            }
+            ClarityValue::OptionalSome(value) | ClarityValue::ResponseOk(value) | ClarityValue::ResponseErr(value) => {
+                value.serialize_into(bytes)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Bounds on the input accepted by [`ClarityValue::deserialize_with_limits`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct DecodeLimits {
+    /// Nesting of lists, tuples, optionals and responses
+    pub max_depth: usize,
+    /// Size of the whole serialized value
+    pub max_size: usize,
+    /// Bytes of a buffer or string
+    pub max_length: usize,
+    /// Elements of a list or fields of a tuple
+    pub max_items: usize,
+}
+
+/// The node's limits: [`MAX_TYPE_DEPTH`] and [`MAX_VALUE_SIZE`].
+impl Default for DecodeLimits {
+    fn default() -> Self {
+        DecodeLimits { max_depth: MAX_TYPE_DEPTH, max_size: MAX_VALUE_SIZE, max_length: MAX_VALUE_SIZE, max_items: MAX_VALUE_SIZE }
+    }
+}
+
+/// Clarity names (tuple fields, functions...): up to 128 characters, a letter followed by
+/// letters, digits and `-_!?+<>=/*`, or one of the operators `-`, `+`, `=`, `/`, `*`, `<`, `>`, `<=`, `>=`.
+pub fn is_valid_clarity_name(name: &str) -> bool {
+    if matches!(name, "-" | "+" | "=" | "/" | "*" | "<" | ">" | "<=" | ">=") {
+        return true;
+    }
+    let mut chars = name.chars();
+    name.len() <= CLARITY_NAME_MAX_LENGTH
+        && chars.next().is_some_and(|c| c.is_ascii_alphabetic())
+        && chars.all(|c| c.is_ascii_alphanumeric() || "-_!?+<>=/*".contains(c))
+}
+
+/// `string-ascii` bytes: printable ASCII, space, tab, CR and LF.
+fn is_valid_ascii(bytes: &[u8]) -> bool {
+    bytes.iter().all(|byte| byte.is_ascii_graphic() || matches!(byte, b' ' | b'\t' | b'\r' | b'\n'))
+}
+
+fn length_prefix(length: usize) -> Result<[u8; 4], ClarityError> {
+    u32::try_from(length).map(u32::to_be_bytes).map_err(|_| ClarityError::ValueTooLarge(length))
+}
+
+struct Reader<'a, 'l> {
+    bytes: &'a [u8],
+    limits: &'l DecodeLimits,
+    /// Compound values currently being read
+    depth: usize,
+}
+
+impl<'a> Reader<'a, '_> {
+    fn take(&mut self, length: usize) -> Result<&'a [u8], ClarityError> {
+        if self.bytes.len() < length {
+            return Err(ClarityError::UnexpectedEnd);
+        }
+        let (taken, rest) = self.bytes.split_at(length);
+        self.bytes = rest;
+        Ok(taken)
+    }
+
+    fn array<const N: usize>(&mut self) -> Result<[u8; N], ClarityError> {
+        Ok(self.take(N)?.try_into().unwrap())
+    }
+
+    fn length(&mut self) -> Result<usize, ClarityError> {
+        Ok(u32::from_be_bytes(self.array()?) as usize)
+    }
+
+    /// Length-prefixed bytes, checking the length against the limits and the remaining input first.
+    fn sized(&mut self) -> Result<&'a [u8], ClarityError> {
+        let length = self.length()?;
+        if length > self.limits.max_length {
+            return Err(ClarityError::ValueTooLarge(length));
+        }
+        self.take(length)
+    }
+
+    /// Number of elements of a list or tuple.
+    fn items(&mut self) -> Result<usize, ClarityError> {
+        let length = self.length()?;
+        // every element takes at least one byte, which bounds the allocation
+        if length > self.bytes.len() {
+            return Err(ClarityError::UnexpectedEnd);
+        }
+        if length > self.limits.max_items {
+            return Err(ClarityError::ValueTooLarge(length));
+        }
+        Ok(length)
+    }
+
+    /// Reads a value nested in a compound one.
+    fn nested(&mut self) -> Result<ClarityValue, ClarityError> {
+        if self.depth >= self.limits.max_depth {
+            return Err(ClarityError::DepthExceeded(self.limits.max_depth));
+        }
+        self.depth += 1;
+        let value = self.value();
+        self.depth -= 1;
+        value
+    }
+
+    fn address(&mut self) -> Result<StacksAddress, ClarityError> {
+        let [version] = self.array()?;
+        let hash160: [u8; ADDRESS_HASH_LENGTH] = self.array()?;
+        StacksAddress::new(version, hash160).map_err(|error| ClarityError::InvalidPrincipal(format!("{error}")))
+    }
+
+    fn name(&mut self) -> Result<&'a str, ClarityError> {
+        let [length] = self.array()?;
+        std::str::from_utf8(self.take(length as usize)?).map_err(|_| ClarityError::InvalidUtf8)
+    }
+
+    fn value(&mut self) -> Result<ClarityValue, ClarityError> {
+        let [type_id] = self.array()?;
+        let type_id = ClarityTypeId::from_value(type_id).ok_or(ClarityError::UnknownTypeId(type_id))?;
+        Ok(match type_id {
+            ClarityTypeId::Int => ClarityValue::Int(i128::from_be_bytes(self.array()?)),
+            ClarityTypeId::UInt => ClarityValue::UInt(u128::from_be_bytes(self.array()?)),
+            ClarityTypeId::Buffer => ClarityValue::Buffer(self.sized()?.to_vec()),
+            ClarityTypeId::BoolTrue => ClarityValue::Bool(true),
+            ClarityTypeId::BoolFalse => ClarityValue::Bool(false),
+            ClarityTypeId::PrincipalStandard => ClarityValue::Principal(Principal::Standard(self.address()?)),
+            ClarityTypeId::PrincipalContract => {
+                let address = self.address()?;
+                let contract_name = self.name()?;
+                let principal = Principal::contract(address, contract_name)
+                    .map_err(|error| ClarityError::InvalidPrincipal(format!("{error}")))?;
+                ClarityValue::Principal(principal)
+            }
+            ClarityTypeId::ResponseOk => ClarityValue::ResponseOk(Box::new(self.nested()?)),
+            ClarityTypeId::ResponseErr => ClarityValue::ResponseErr(Box::new(self.nested()?)),
+            ClarityTypeId::OptionalNone => ClarityValue::OptionalNone,
+            ClarityTypeId::OptionalSome => ClarityValue::OptionalSome(Box::new(self.nested()?)),
+            ClarityTypeId::List => {
+                let length = self.items()?;
+                let mut values = Vec::with_capacity(length);
+                for _ in 0..length {
+                    values.push(self.nested()?);
+                }
+                ClarityValue::List(values)
+            }
+            ClarityTypeId::Tuple => {
+                let length = self.items()?;
+                if length == 0 {
+                    return Err(ClarityError::InvalidTuple);
+                }
+                let mut fields = BTreeMap::new();
+                for _ in 0..length {
+                    let name = self.name()?;
+                    if !is_valid_clarity_name(name) {
+                        return Err(ClarityError::InvalidTupleName(name.to_string()));
+                    }
+                    if fields.insert(name.to_string(), self.nested()?).is_some() {
+                        return Err(ClarityError::InvalidTuple);
+                    }
+                }
+                ClarityValue::Tuple(fields)
+            }
+            ClarityTypeId::StringAscii => {
+                let bytes = self.sized()?;
+                if !is_valid_ascii(bytes) {
+                    return Err(ClarityError::InvalidAscii);
+                }
+                ClarityValue::StringAscii(String::from_utf8(bytes.to_vec()).unwrap())
+            }
+            ClarityTypeId::StringUtf8 => {
+                ClarityValue::StringUtf8(String::from_utf8(self.sized()?.to_vec()).map_err(|_| ClarityError::InvalidUtf8)?)
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use super::*;
+
+    fn principal(principal: &str) -> ClarityValue {
+        ClarityValue::Principal(Principal::from_str(principal).unwrap())
+    }
+
+    #[test]
+    fn test_serialization_vectors() {
+        // stacks.js `serializeCV` outputs
+        let vectors = [
+            (ClarityValue::Int(-1), "00ffffffffffffffffffffffffffffffff"),
+            (ClarityValue::UInt(1), "0100000000000000000000000000000001"),
+            (ClarityValue::Buffer(b"\xde\xad".to_vec()), "0200000002dead"),
+            (ClarityValue::Bool(true), "03"),
+            (ClarityValue::Bool(false), "04"),
+            (principal("SP3FGQ8Z7JY9BWYZ5WM53E0M9NK7WHJF0691NZ159"), "0516df0ba3e79792be7be5e50a370289accfc8c9e032"),
+            (
+                principal("SP3FGQ8Z7JY9BWYZ5WM53E0M9NK7WHJF0691NZ159.abc"),
+                "0616df0ba3e79792be7be5e50a370289accfc8c9e03203616263",
+            ),
+            (ClarityValue::ResponseOk(Box::new(ClarityValue::Bool(true))), "0703"),
+            (ClarityValue::ResponseErr(Box::new(ClarityValue::UInt(3))), "080100000000000000000000000000000003"),
+            (ClarityValue::OptionalNone, "09"),
+            (ClarityValue::OptionalSome(Box::new(ClarityValue::OptionalNone)), "0a09"),
+            (ClarityValue::List(vec![ClarityValue::Bool(true), ClarityValue::Bool(false)]), "0b000000020304"),
+            (ClarityValue::List(vec![]), "0b00000000"),
+            (ClarityValue::StringAscii(String::from("hello world")), "0d0000000b68656c6c6f20776f726c64"),
+            (ClarityValue::StringUtf8(String::from("hello \u{1F30E}")), "0e0000000a68656c6c6f20f09f8c8e"),
+        ];
+        for (value, expected) in vectors {
+            let bytes = value.serialize().unwrap();
+            assert_eq!(hex::encode(&bytes), expected);
+            assert_eq!(ClarityValue::deserialize(&bytes).unwrap(), value);
+        }
+    }
+
+    #[test]
+    fn test_tuple_fields_are_sorted() {
+        let tuple = ClarityValue::Tuple(BTreeMap::from([
+            (String::from("b"), ClarityValue::Bool(false)),
+            (String::from("a"), ClarityValue::Bool(true)),
+        ]));
+        let bytes = tuple.serialize().unwrap();
+        assert_eq!(hex::encode(&bytes), "0c00000002016103016204");
+        assert_eq!(ClarityValue::deserialize(&bytes).unwrap(), tuple);
+
+        assert_eq!(ClarityValue::Tuple(BTreeMap::new()).serialize(), Err(ClarityError::InvalidTuple));
+        let bad_name = ClarityValue::Tuple(BTreeMap::from([(String::from("1a"), ClarityValue::Bool(true))]));
+        assert_eq!(bad_name.serialize(), Err(ClarityError::InvalidTupleName(String::from("1a"))));
+        // the same field twice
+        assert_eq!(ClarityValue::deserialize(&hex::decode("0c00000002016103016104").unwrap()), Err(ClarityError::InvalidTuple));
+    }
+
+    #[test]
+    fn test_invalid_bytes() {
+        assert_eq!(ClarityValue::deserialize(&[]), Err(ClarityError::UnexpectedEnd));
+        assert_eq!(ClarityValue::deserialize(&[0x0f]), Err(ClarityError::UnknownTypeId(0x0f)));
+        assert_eq!(ClarityValue::deserialize(&[0x03, 0x03]), Err(ClarityError::TrailingBytes(1)));
+        assert_eq!(ClarityValue::deserialize(&hex::decode("0200000005dead").unwrap()), Err(ClarityError::UnexpectedEnd));
+        assert_eq!(ClarityValue::deserialize(&hex::decode("0bffffffff").unwrap()), Err(ClarityError::UnexpectedEnd));
+        assert_eq!(ClarityValue::deserialize(&hex::decode("0d0000000100").unwrap()), Err(ClarityError::InvalidAscii));
+        assert_eq!(ClarityValue::deserialize(&hex::decode("0e00000001ff").unwrap()), Err(ClarityError::InvalidUtf8));
+        assert!(matches!(
+            ClarityValue::deserialize(&hex::decode("0620df0ba3e79792be7be5e50a370289accfc8c9e03203616263").unwrap()),
+            Err(ClarityError::InvalidPrincipal(_))
+        ));
+        assert!(matches!(
+            ClarityValue::deserialize(&hex::decode("0616df0ba3e79792be7be5e50a370289accfc8c9e03203312d32").unwrap()),
+            Err(ClarityError::InvalidPrincipal(_))
+        ));
+        assert_eq!(ClarityValue::StringAscii(String::from("caf\u{e9}")).serialize(), Err(ClarityError::InvalidAscii));
+    }
+
+    #[test]
+    fn test_decode_limits() {
+        let mut nested = ClarityValue::Bool(true);
+        for _ in 0..MAX_TYPE_DEPTH {
+            nested = ClarityValue::some(nested);
+        }
+        let bytes = nested.serialize().unwrap();
+        assert_eq!(ClarityValue::deserialize(&bytes).unwrap(), nested);
+        let too_deep = [&[0x0a][..], &bytes].concat();
+        assert_eq!(ClarityValue::deserialize(&too_deep), Err(ClarityError::DepthExceeded(MAX_TYPE_DEPTH)));
+
+        // deeply nested lists, as a peer could send to overflow the stack
+        let hostile = [0x0b, 0x00, 0x00, 0x00, 0x01].repeat(100_000);
+        assert_eq!(ClarityValue::deserialize(&hostile), Err(ClarityError::DepthExceeded(MAX_TYPE_DEPTH)));
+
+        let limits = DecodeLimits { max_size: 64, max_length: 4, max_items: 2, ..DecodeLimits::default() };
+        let decode = |value: ClarityValue| ClarityValue::deserialize_with_limits(&value.serialize().unwrap(), &limits);
+        assert!(decode(ClarityValue::from(vec![vec![0u8; 4], vec![0u8; 4]])).is_ok());
+        assert_eq!(decode(ClarityValue::Buffer(vec![0; 5])), Err(ClarityError::ValueTooLarge(5)));
+        assert_eq!(decode(ClarityValue::from("hello")), Err(ClarityError::ValueTooLarge(5)));
+        assert_eq!(decode(ClarityValue::from(vec![true, false, true])), Err(ClarityError::ValueTooLarge(3)));
+        assert_eq!(decode(ClarityValue::from(vec![1u64, 2, 3, 4])), Err(ClarityError::ValueTooLarge(73)));
+    }
+
+    #[test]
+    fn test_hex() {
+        let value = ClarityValue::OptionalSome(Box::new(ClarityValue::UInt(1)));
+        assert_eq!(value.to_hex().unwrap(), "0x0a0100000000000000000000000000000001");
+        assert_eq!(value.to_hex_unprefixed().unwrap(), "0a0100000000000000000000000000000001");
+        for encoded in ["0x0a0100000000000000000000000000000001", "0a0100000000000000000000000000000001", "0x0A0100000000000000000000000000000001"] {
+            assert_eq!(ClarityValue::from_hex(encoded).unwrap(), value);
+        }
+        assert!(matches!(ClarityValue::from_hex("0x0"), Err(ClarityError::InvalidHex(_))));
+        assert!(matches!(ClarityValue::from_hex("0xzz"), Err(ClarityError::InvalidHex(_))));
+        assert_eq!(ClarityValue::from_hex("0x"), Err(ClarityError::UnexpectedEnd));
+    }
+
+    #[test]
+    fn test_clarity_names() {
+        for name in ["a", "amount", "is-valid?", "set!", "a/b", "+", "<=", &"a".repeat(128)] {
+            assert!(is_valid_clarity_name(name), "{name}");
+        }
+        for name in ["", "1a", "-a", "a b", "a.b", "<<", &"a".repeat(129)] {
+            assert!(!is_valid_clarity_name(name), "{name}");
+        }
+    }
+}
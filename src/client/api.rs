@@ -0,0 +1,357 @@
+//! Blocking client of the Stacks Blockchain API (`/extended`), the indexer serving what nodes do
+//! not: mempool contents, transaction status and history.
+
+use std::thread;
+use std::time::Instant;
+
+use crate::address::principal::Principal;
+use crate::address::stacks_address::StacksAddress;
+use crate::network::StacksNetwork;
+use crate::transaction::nonce::NonceManager;
+
+use super::confirmation::{outcome, ConfirmationPolicy, TransactionOutcome};
+use super::endpoints;
+use super::error::ClientError;
+use super::history::{filter_history, TransactionFilter};
+use super::http::HttpTransport;
+use super::pagination::Paginated;
+#[cfg(feature = "blocking")]
+use super::http::UreqTransport;
+use super::types::{AddressNonces, AddressTransaction, ApiTransaction, AssetEvent, Block, BlockRef, BurnBlock, NftHolding, Page};
+
+/// Blocking client of the Stacks Blockchain API of a network.
+///
+/// With the `blocking` feature, [`StacksApiClient::new`] talks to the API URL of the network;
+/// any other [`HttpTransport`] can be plugged in with [`StacksApiClient::with_transport`].
+///
+/// Usage:
+/// ```rust,no_run
+/// # #[cfg(feature = "blocking")]
+/// # {
+/// use std::str::FromStr;
+/// use stacks_rs::address::stacks_address::StacksAddress;
+/// use stacks_rs::client::api::StacksApiClient;
+/// use stacks_rs::network::StacksNetwork;
+/// let client = StacksApiClient::new(StacksNetwork::mainnet());
+/// let sender = StacksAddress::from_str("SP2J6ZY48GV1EZ5V2V5RB9MP66SW86PYKKNRV9EJ7").unwrap();
+/// for transaction in client.get_mempool_transactions(Some(&sender), None, None).unwrap().results {
+///     println!("{} pending with nonce {}", transaction.tx_id, transaction.nonce);
+/// }
+/// # }
+/// ```
+#[derive(Clone, Debug)]
+pub struct StacksApiClient<T> {
+    network: StacksNetwork,
+    transport: T,
+}
+
+#[cfg(feature = "blocking")]
+impl StacksApiClient<UreqTransport> {
+    pub fn new(network: StacksNetwork) -> Self {
+        let transport = UreqTransport::new(&network.api_url);
+        StacksApiClient { network, transport }
+    }
+}
+
+impl<T: HttpTransport> StacksApiClient<T> {
+    /// A client of `network` sending through `transport`, already pointing at its API.
+    pub fn with_transport(network: StacksNetwork, transport: T) -> Self {
+        StacksApiClient { network, transport }
+    }
+
+    pub fn network(&self) -> &StacksNetwork {
+        &self.network
+    }
+
+    pub fn transport(&self) -> &T {
+        &self.transport
+    }
+
+    /// Transactions in the mempool, of `sender` only if set, newest first.
+    pub fn get_mempool_transactions(&self, sender: Option<&StacksAddress>, limit: Option<u32>, offset: Option<u32>) -> Result<Page<ApiTransaction>, ClientError> {
+        endpoints::required(endpoints::mempool_transactions(sender, limit, offset).send(&self.transport)?)
+    }
+
+    /// Transactions recently dropped from the mempool, with the reason in their status.
+    pub fn get_dropped_mempool_transactions(&self, limit: Option<u32>, offset: Option<u32>) -> Result<Page<ApiTransaction>, ClientError> {
+        endpoints::required(endpoints::dropped_mempool_transactions(limit, offset).send(&self.transport)?)
+    }
+
+    /// A page of the transactions of `principal`, newest first.
+    pub fn get_account_transactions(&self, principal: &Principal, limit: Option<u32>, offset: Option<u32>) -> Result<Page<ApiTransaction>, ClientError> {
+        endpoints::required(endpoints::account_transactions(principal, limit, offset).send(&self.transport)?)
+    }
+
+    /// A page of the transactions of `principal` with the STX each sent and received, newest first.
+    pub fn get_address_transactions(&self, principal: &Principal, limit: Option<u32>, offset: Option<u32>) -> Result<Page<AddressTransaction>, ClientError> {
+        endpoints::required(endpoints::address_transactions(principal, limit, offset).send(&self.transport)?)
+    }
+
+    /// A page of the asset transfers, mints and burns involving `principal`, newest first.
+    pub fn get_asset_events(&self, principal: &Principal, limit: Option<u32>, offset: Option<u32>) -> Result<Page<AssetEvent>, ClientError> {
+        endpoints::required(endpoints::asset_events(principal, limit, offset).send(&self.transport)?)
+    }
+
+    /// A page of the non-fungible tokens held by `principal`.
+    pub fn get_nft_holdings(&self, principal: &Principal, limit: Option<u32>, offset: Option<u32>) -> Result<Page<NftHolding>, ClientError> {
+        endpoints::required(endpoints::nft_holdings(principal, limit, offset).send(&self.transport)?)
+    }
+
+    /// All the transactions of `principal`, newest first, fetched page by page.
+    pub fn iter_account_transactions<'a>(&'a self, principal: &'a Principal) -> Paginated<ApiTransaction, impl FnMut(u32, u32) -> Result<Page<ApiTransaction>, ClientError> + 'a> {
+        Paginated::new(move |limit, offset| self.get_account_transactions(principal, Some(limit), Some(offset)))
+    }
+
+    /// The transactions of `principal` kept by `filter`, newest first, fetched page by page until
+    /// past the start of its time range.
+    pub fn iter_transaction_history<'a>(&'a self, principal: &'a Principal, filter: TransactionFilter) -> impl Iterator<Item = Result<AddressTransaction, ClientError>> + 'a {
+        filter_history(Paginated::new(move |limit, offset| self.get_address_transactions(principal, Some(limit), Some(offset))), filter)
+    }
+
+    /// All the asset events involving `principal`, newest first, fetched page by page.
+    pub fn iter_asset_events<'a>(&'a self, principal: &'a Principal) -> Paginated<AssetEvent, impl FnMut(u32, u32) -> Result<Page<AssetEvent>, ClientError> + 'a> {
+        Paginated::new(move |limit, offset| self.get_asset_events(principal, Some(limit), Some(offset)))
+    }
+
+    /// All the non-fungible tokens held by `principal`, fetched page by page.
+    pub fn iter_nft_holdings<'a>(&'a self, principal: &'a Principal) -> Paginated<NftHolding, impl FnMut(u32, u32) -> Result<Page<NftHolding>, ClientError> + 'a> {
+        Paginated::new(move |limit, offset| self.get_nft_holdings(principal, Some(limit), Some(offset)))
+    }
+
+    /// Nonces of `address`: of its last mined transaction and of its transactions in the mempool.
+    pub fn get_address_nonces(&self, address: &StacksAddress) -> Result<AddressNonces, ClientError> {
+        endpoints::required(endpoints::address_nonces(address).send(&self.transport)?)
+    }
+
+    /// Aligns the nonces `manager` hands out for `address` with the chain and the mempool, see
+    /// [`NonceManager::reconcile`].
+    pub fn reconcile_nonces(&self, manager: &NonceManager, address: &StacksAddress) -> Result<(), ClientError> {
+        let nonces = self.get_address_nonces(address)?;
+        manager.reconcile(&address.to_string(), nonces.account_nonce(), nonces.mempool_nonces());
+        Ok(())
+    }
+
+    /// A transaction by its hex txid, pending, mined or dropped; `None` if the API never saw it.
+    pub fn get_transaction(&self, txid: &str) -> Result<Option<ApiTransaction>, ClientError> {
+        endpoints::optional(endpoints::transaction(txid).send(&self.transport)?)
+    }
+
+    /// A Stacks block, `None` if there is none at this height or hash.
+    pub fn get_block(&self, block: &BlockRef) -> Result<Option<Block>, ClientError> {
+        endpoints::optional(endpoints::block(block).send(&self.transport)?)
+    }
+
+    /// A Bitcoin block and the Stacks blocks it anchors, `None` if the API does not know it.
+    pub fn get_burn_block(&self, block: &BlockRef) -> Result<Option<BurnBlock>, ClientError> {
+        endpoints::optional(endpoints::burn_block(block).send(&self.transport)?)
+    }
+
+
+    /// Polls the status of `txid` following `policy` until it is mined, blocking the thread.
+    ///
+    /// A dropped transaction is a [`ClientError::Dropped`], one still pending at the timeout a
+    /// [`ClientError::Timeout`].
+    pub fn wait_for_confirmation(&self, txid: &str, policy: &ConfirmationPolicy) -> Result<TransactionOutcome, ClientError> {
+        let start = Instant::now();
+        for attempt in 0.. {
+            if let Some(outcome) = outcome(txid, self.get_transaction(txid)?)? {
+                return Ok(outcome);
+            }
+            let remaining = policy.timeout.saturating_sub(start.elapsed());
+            if remaining.is_zero() {
+                break;
+            }
+            thread::sleep(policy.interval(attempt).min(remaining));
+        }
+        Err(ClientError::Timeout(txid.to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+    use std::time::Duration;
+
+    use crate::clarity::value::ClarityValue;
+
+    use super::super::mock::MockTransport;
+    use super::super::types::{TxStatus, TxType};
+    use super::*;
+
+    const SENDER: &str = "SP2J6ZY48GV1EZ5V2V5RB9MP66SW86PYKKNRV9EJ7";
+    const TXID: &str = "0x84cccb05f4bd0e1b08905ef1f1350ad635a6474448310548bdccfa04e0121bab";
+
+    fn transaction(status: &str, extra: &str) -> String {
+        format!(
+            r#"{{"tx_id": "{TXID}", "tx_status": "{status}", "tx_type": "token_transfer", "nonce": 3, "fee_rate": "180",
+            "sender_address": "{SENDER}", "sponsored": false, "post_condition_mode": "deny", "anchor_mode": "any"{extra}}}"#
+        )
+    }
+
+    #[test]
+    fn test_mempool() {
+        let pending = transaction("pending", r#", "receipt_time": 1700000000, "receipt_time_iso": "2023-11-14T22:13:20.000Z""#);
+        let dropped = transaction("dropped_replace_by_fee", r#", "receipt_time": 1700000000"#);
+        let transport = MockTransport::new()
+            .with(&format!("/extended/v1/tx/mempool?sender_address={SENDER}&limit=20"), 200, &format!(r#"{{"limit": 20, "offset": 0, "total": 1, "results": [{pending}]}}"#))
+            .with("/extended/v1/tx/mempool/dropped", 200, &format!(r#"{{"limit": 96, "offset": 0, "total": 1, "results": [{dropped}]}}"#))
+            .with(&format!("/extended/v1/tx/{TXID}"), 200, &pending);
+        let client = StacksApiClient::with_transport(StacksNetwork::mainnet(), transport);
+
+        let sender = StacksAddress::from_str(SENDER).unwrap();
+        let page = client.get_mempool_transactions(Some(&sender), Some(20), None).unwrap();
+        assert_eq!((page.total, page.results.len()), (1, 1));
+        let transaction = &page.results[0];
+        assert_eq!((transaction.nonce, transaction.fee_rate, transaction.tx_status), (3, 180, TxStatus::Pending));
+        assert_eq!(transaction.pending_for(1700000600), Some(600));
+
+        let dropped = client.get_dropped_mempool_transactions(None, None).unwrap();
+        assert!(dropped.results[0].tx_status.is_dropped());
+        assert_eq!(dropped.results[0].pending_for(1700000600), None);
+        assert_eq!(client.get_transaction(TXID.trim_start_matches("0x")).unwrap().unwrap().tx_status, TxStatus::Pending);
+    }
+
+    #[test]
+    fn test_get_transaction() {
+        let mined = transaction("abort_by_response", r#", "block_height": 150000, "tx_result": {"hex": "0x0803", "repr": "(err none)"}"#);
+        let transport = MockTransport::new()
+            .with(&format!("/extended/v1/tx/{TXID}"), 200, &mined)
+            .with("/extended/v1/tx/0x00", 404, r#"{"error": "could not find transaction by ID 0x00"}"#);
+        let client = StacksApiClient::with_transport(StacksNetwork::mainnet(), transport);
+        let transaction = client.get_transaction(TXID).unwrap().unwrap();
+        assert!(transaction.tx_status.is_mined() && !transaction.tx_status.is_dropped());
+        assert_eq!(transaction.block_height, Some(150000));
+        assert_eq!(transaction.tx_result.unwrap().repr, "(err none)");
+        assert_eq!(client.get_transaction("0x00").unwrap(), None);
+
+        let unknown = transaction_with_status("some_new_status");
+        assert_eq!(unknown.tx_status, TxStatus::Unknown);
+    }
+
+    #[test]
+    fn test_reconcile_nonces() {
+        let nonces = r#"{"last_mempool_tx_nonce": 9, "last_executed_tx_nonce": 5, "possible_next_nonce": 10, "detected_missing_nonces": [7],
+            "detected_mempool_nonces": [6, 8]}"#;
+        let transport = MockTransport::new().with(&format!("/extended/v1/address/{SENDER}/nonces"), 200, nonces);
+        let client = StacksApiClient::with_transport(StacksNetwork::mainnet(), transport);
+        let sender = StacksAddress::from_str(SENDER).unwrap();
+        let nonces = client.get_address_nonces(&sender).unwrap();
+        assert_eq!((nonces.account_nonce(), nonces.mempool_nonces().collect::<Vec<_>>()), (6, vec![6, 8, 9]));
+
+        let manager = NonceManager::new();
+        client.reconcile_nonces(&manager, &sender).unwrap();
+        // the missing nonce first, then past the mempool
+        assert_eq!(manager.next_nonce(SENDER), 7);
+        assert_eq!(manager.next_nonce(SENDER), 10);
+
+        let unused = r#"{"last_mempool_tx_nonce": null, "last_executed_tx_nonce": null, "possible_next_nonce": 0, "detected_missing_nonces": [], "detected_mempool_nonces": []}"#;
+        let unused: AddressNonces = serde_json::from_str(unused).unwrap();
+        assert_eq!((unused.account_nonce(), unused.mempool_nonces().count()), (0, 0));
+    }
+
+    fn transaction_with_status(status: &str) -> ApiTransaction {
+        serde_json::from_str(&transaction(status, "")).unwrap()
+    }
+
+    #[test]
+    fn test_blocks() {
+        let block = r#"{"canonical": true, "height": 1000000, "hash": "0xaa", "block_time": 1730000000, "block_time_iso": "2024-10-27T03:33:20.000Z",
+            "tenure_height": 170000, "index_block_hash": "0xbb", "parent_block_hash": "0xcc", "parent_index_block_hash": "0xdd",
+            "burn_block_time": 1729999900, "burn_block_time_iso": "2024-10-27T03:31:40.000Z", "burn_block_hash": "0xee",
+            "burn_block_height": 867000, "miner_txid": "0xff", "tx_count": 12, "execution_cost_read_count": 1}"#;
+        let burn_block = r#"{"burn_block_time": 1729999900, "burn_block_time_iso": "2024-10-27T03:31:40.000Z", "burn_block_hash": "0xee",
+            "burn_block_height": 867000, "stacks_blocks": ["0xaa", "0xa0"], "avg_block_time": 4.2, "total_tx_count": 20}"#;
+        let transport = MockTransport::new()
+            .with("/extended/v2/blocks/1000000", 200, block)
+            .with("/extended/v2/blocks/0xaa", 200, block)
+            .with("/extended/v2/burn-blocks/867000", 200, burn_block)
+            .with("/extended/v2/burn-blocks/0x00", 404, r#"{"error": "cannot find burn block"}"#);
+        let client = StacksApiClient::with_transport(StacksNetwork::mainnet(), transport);
+
+        let by_height = client.get_block(&BlockRef::Height(1000000)).unwrap().unwrap();
+        assert_eq!((by_height.height, by_height.tenure_height, by_height.burn_block_height, by_height.tx_count), (1000000, 170000, 867000, 12));
+        assert_eq!(client.get_block(&BlockRef::Hash(String::from("aa"))).unwrap().unwrap(), by_height);
+
+        let burn_block = client.get_burn_block(&BlockRef::Height(867000)).unwrap().unwrap();
+        assert_eq!(burn_block.stacks_blocks, vec!["0xaa", "0xa0"]);
+        assert_eq!(client.get_burn_block(&BlockRef::Hash(String::from("0x00"))).unwrap(), None);
+    }
+
+    #[test]
+    fn test_wait_for_confirmation() {
+        let policy = ConfirmationPolicy { initial_interval: Duration::from_millis(1), max_interval: Duration::from_millis(2), multiplier: 2, timeout: Duration::from_secs(5) };
+        let path = format!("/extended/v1/tx/{TXID}");
+        let transport = MockTransport::new()
+            .with(&path, 404, r#"{"error": "could not find transaction by ID"}"#)
+            .with(&path, 200, &transaction("pending", ""))
+            .with(&path, 200, &transaction("success", r#", "block_height": 150000, "tx_result": {"hex": "0x0703", "repr": "(ok true)"}"#));
+        let client = StacksApiClient::with_transport(StacksNetwork::mainnet(), transport);
+        let outcome = client.wait_for_confirmation(TXID, &policy).unwrap();
+        assert!(outcome.is_success());
+        assert_eq!(outcome.result(), &ClarityValue::ResponseOk(Box::new(ClarityValue::Bool(true))));
+        assert_eq!(client.transport().requests.borrow().len(), 3);
+
+        let aborted = transaction("abort_by_post_condition", r#", "tx_result": {"hex": "0x0703", "repr": "(ok true)"}"#);
+        let client = StacksApiClient::with_transport(StacksNetwork::mainnet(), MockTransport::new().with(&path, 200, &aborted));
+        assert!(matches!(client.wait_for_confirmation(TXID, &policy), Ok(TransactionOutcome::AbortByPostCondition(_))));
+
+        let dropped = transaction("dropped_replace_by_fee", "");
+        let client = StacksApiClient::with_transport(StacksNetwork::mainnet(), MockTransport::new().with(&path, 200, &dropped));
+        assert!(matches!(client.wait_for_confirmation(TXID, &policy), Err(ClientError::Dropped { status: TxStatus::DroppedReplaceByFee, .. })));
+
+        let short = ConfirmationPolicy { timeout: Duration::from_millis(5), ..policy };
+        let client = StacksApiClient::with_transport(StacksNetwork::mainnet(), MockTransport::new().with(&path, 200, &transaction("pending", "")));
+        let error = client.wait_for_confirmation(TXID, &short).unwrap_err();
+        assert_eq!(error.to_string(), format!("Transaction {TXID} not confirmed in time"));
+    }
+
+    #[test]
+    fn test_iter_account_transactions() {
+        let principal = Principal::from_str(SENDER).unwrap();
+        let path = |offset| format!("/extended/v1/address/{SENDER}/transactions?limit=50&offset={offset}");
+        let page = |offset: u32, count: usize| format!(r#"{{"limit": 50, "offset": {offset}, "total": 51, "results": [{}]}}"#, vec![transaction("success", ""); count].join(", "));
+        let transport = MockTransport::new().with(&path(0), 200, &page(0, 50)).with(&path(50), 200, &page(50, 1));
+        let client = StacksApiClient::with_transport(StacksNetwork::mainnet(), transport);
+        assert_eq!(client.iter_account_transactions(&principal).map(Result::unwrap).count(), 51);
+        assert_eq!(client.transport().requests.borrow().len(), 2);
+
+        let failing = StacksApiClient::with_transport(StacksNetwork::mainnet(), MockTransport::new().with(&path(0), 200, &page(0, 50)).with(&path(50), 500, ""));
+        let results: Vec<_> = failing.iter_account_transactions(&principal).collect();
+        assert_eq!(results.len(), 51);
+        assert!(matches!(results[50], Err(ClientError::Api { status: 500, .. })));
+    }
+
+    #[test]
+    fn test_iter_transaction_history() {
+        let principal = Principal::from_str(SENDER).unwrap();
+        let entry = |time: u64| format!(r#"{{"tx": {}, "stx_sent": "180", "stx_received": "0"}}"#, transaction("success", &format!(r#", "block_time": {time}"#)));
+        let entries: Vec<_> = (0..50).map(|i| entry(2000 - i * 10)).collect();
+        let page = format!(r#"{{"limit": 50, "offset": 0, "total": 120, "results": [{}]}}"#, entries.join(", "));
+        let transport = MockTransport::new().with(&format!("/extended/v2/addresses/{SENDER}/transactions?limit=50&offset=0"), 200, &page);
+        let client = StacksApiClient::with_transport(StacksNetwork::mainnet(), transport);
+        let filter = TransactionFilter::new().with_type(TxType::TokenTransfer).since(1800).until(1950);
+        let history: Vec<_> = client.iter_transaction_history(&principal, filter).map(Result::unwrap).collect();
+        assert_eq!(history.len(), 16);
+        assert_eq!((history[0].tx.block_time, history[0].stx_sent), (Some(1950), 180));
+        assert_eq!(client.transport().requests.borrow().len(), 1);
+    }
+
+    #[test]
+    fn test_asset_events() {
+        let principal = Principal::from_str(SENDER).unwrap();
+        let events = format!(
+            r#"{{"limit": 2, "offset": 0, "total": 2, "results": [
+            {{"event_index": 0, "event_type": "fungible_token_asset", "tx_id": "0x01", "asset": {{"asset_event_type": "transfer",
+                "asset_id": "SP3K8BC0PPEVCV7NZ6QSRWPQ2JE9E5B6N3PA0KBR9.token-alex::alex", "sender": "{SENDER}", "recipient": "SP000000000000000000002Q6VF78", "amount": "1000"}}}},
+            {{"event_index": 1, "event_type": "non_fungible_token_asset", "tx_id": "0x02", "asset": {{"asset_event_type": "mint",
+                "asset_id": "SP2X0TZ59D5SZ8ACQ6YMCHHNR2ZN51Z32E2CJ173.guild::Guild", "recipient": "{SENDER}", "value": {{"hex": "0x0100000000000000000000000000000001", "repr": "u1"}}}}}}]}}"#
+        );
+        let transport = MockTransport::new().with(&format!("/extended/v1/address/{SENDER}/assets?limit=2"), 200, &events);
+        let client = StacksApiClient::with_transport(StacksNetwork::mainnet(), transport);
+        let page = client.get_asset_events(&principal, Some(2), None).unwrap();
+        let transfer = page.results[0].asset.as_ref().unwrap();
+        assert_eq!((transfer.asset_event_type.as_str(), transfer.amount), ("transfer", Some(1000)));
+        let mint = page.results[1].asset.as_ref().unwrap();
+        assert_eq!((mint.sender.as_deref(), mint.amount, mint.value.as_ref().map(|value| value.repr.as_str())), (None, None, Some("u1")));
+    }
+}
@@ -0,0 +1,218 @@
+//! Async client of the Stacks Blockchain API (`/extended`), the counterpart of
+//! [`StacksApiClient`](super::api::StacksApiClient) with the same requests and response types.
+
+#[cfg(feature = "tokio")]
+use futures_util::Stream;
+
+use crate::address::principal::Principal;
+use crate::address::stacks_address::StacksAddress;
+use crate::network::StacksNetwork;
+use crate::transaction::nonce::NonceManager;
+
+#[cfg(feature = "tokio")]
+use super::confirmation::{outcome, ConfirmationPolicy, TransactionOutcome};
+use super::endpoints;
+use super::error::ClientError;
+#[cfg(feature = "tokio")]
+use super::history::{filter_history_stream, TransactionFilter};
+use super::http::AsyncHttpTransport;
+#[cfg(feature = "tokio")]
+use super::http::ReqwestTransport;
+#[cfg(feature = "tokio")]
+use super::pagination::{paginate_stream, DEFAULT_PAGE_SIZE};
+use super::types::{AddressNonces, AddressTransaction, ApiTransaction, AssetEvent, Block, BlockRef, BurnBlock, NftHolding, Page};
+
+/// Async client of the Stacks Blockchain API of a network.
+///
+/// With the `tokio` feature, [`AsyncStacksApiClient::new`] talks to the API URL of the network
+/// over `reqwest`; any other [`AsyncHttpTransport`] can be plugged in with
+/// [`AsyncStacksApiClient::with_transport`].
+///
+/// Usage:
+/// ```rust,no_run
+/// # #[cfg(feature = "tokio")]
+/// # async fn mempool() {
+/// use std::str::FromStr;
+/// use stacks_rs::address::stacks_address::StacksAddress;
+/// use stacks_rs::client::async_api::AsyncStacksApiClient;
+/// use stacks_rs::network::StacksNetwork;
+/// let client = AsyncStacksApiClient::new(StacksNetwork::mainnet());
+/// let sender = StacksAddress::from_str("SP2J6ZY48GV1EZ5V2V5RB9MP66SW86PYKKNRV9EJ7").unwrap();
+/// for transaction in client.get_mempool_transactions(Some(&sender), None, None).await.unwrap().results {
+///     println!("{} pending with nonce {}", transaction.tx_id, transaction.nonce);
+/// }
+/// # }
+/// ```
+#[derive(Clone, Debug)]
+pub struct AsyncStacksApiClient<T> {
+    network: StacksNetwork,
+    transport: T,
+}
+
+#[cfg(feature = "tokio")]
+impl AsyncStacksApiClient<ReqwestTransport> {
+    pub fn new(network: StacksNetwork) -> Self {
+        let transport = ReqwestTransport::new(&network.api_url);
+        AsyncStacksApiClient { network, transport }
+    }
+}
+
+impl<T: AsyncHttpTransport> AsyncStacksApiClient<T> {
+    /// A client of `network` sending through `transport`, already pointing at its API.
+    pub fn with_transport(network: StacksNetwork, transport: T) -> Self {
+        AsyncStacksApiClient { network, transport }
+    }
+
+    pub fn network(&self) -> &StacksNetwork {
+        &self.network
+    }
+
+    pub fn transport(&self) -> &T {
+        &self.transport
+    }
+
+    /// Transactions in the mempool, of `sender` only if set, newest first.
+    pub async fn get_mempool_transactions(&self, sender: Option<&StacksAddress>, limit: Option<u32>, offset: Option<u32>) -> Result<Page<ApiTransaction>, ClientError> {
+        endpoints::required(endpoints::mempool_transactions(sender, limit, offset).send_async(&self.transport).await?)
+    }
+
+    /// Transactions recently dropped from the mempool, with the reason in their status.
+    pub async fn get_dropped_mempool_transactions(&self, limit: Option<u32>, offset: Option<u32>) -> Result<Page<ApiTransaction>, ClientError> {
+        endpoints::required(endpoints::dropped_mempool_transactions(limit, offset).send_async(&self.transport).await?)
+    }
+
+    /// A page of the transactions of `principal`, newest first.
+    pub async fn get_account_transactions(&self, principal: &Principal, limit: Option<u32>, offset: Option<u32>) -> Result<Page<ApiTransaction>, ClientError> {
+        endpoints::required(endpoints::account_transactions(principal, limit, offset).send_async(&self.transport).await?)
+    }
+
+    /// A page of the transactions of `principal` with the STX each sent and received, newest first.
+    pub async fn get_address_transactions(&self, principal: &Principal, limit: Option<u32>, offset: Option<u32>) -> Result<Page<AddressTransaction>, ClientError> {
+        endpoints::required(endpoints::address_transactions(principal, limit, offset).send_async(&self.transport).await?)
+    }
+
+    /// A page of the asset transfers, mints and burns involving `principal`, newest first.
+    pub async fn get_asset_events(&self, principal: &Principal, limit: Option<u32>, offset: Option<u32>) -> Result<Page<AssetEvent>, ClientError> {
+        endpoints::required(endpoints::asset_events(principal, limit, offset).send_async(&self.transport).await?)
+    }
+
+    /// A page of the non-fungible tokens held by `principal`.
+    pub async fn get_nft_holdings(&self, principal: &Principal, limit: Option<u32>, offset: Option<u32>) -> Result<Page<NftHolding>, ClientError> {
+        endpoints::required(endpoints::nft_holdings(principal, limit, offset).send_async(&self.transport).await?)
+    }
+
+    /// All the transactions of `principal`, newest first, fetching up to `concurrency` pages at once.
+    #[cfg(feature = "tokio")]
+    pub fn stream_account_transactions<'a>(&'a self, principal: &'a Principal, concurrency: usize) -> impl Stream<Item = Result<ApiTransaction, ClientError>> + 'a {
+        paginate_stream(move |limit, offset| self.get_account_transactions(principal, Some(limit), Some(offset)), DEFAULT_PAGE_SIZE, concurrency)
+    }
+
+    /// The transactions of `principal` kept by `filter`, newest first, fetching up to `concurrency`
+    /// pages at once until past the start of its time range.
+    #[cfg(feature = "tokio")]
+    pub fn stream_transaction_history<'a>(&'a self, principal: &'a Principal, filter: TransactionFilter, concurrency: usize) -> impl Stream<Item = Result<AddressTransaction, ClientError>> + 'a {
+        let history = paginate_stream(move |limit, offset| self.get_address_transactions(principal, Some(limit), Some(offset)), DEFAULT_PAGE_SIZE, concurrency);
+        filter_history_stream(history, filter)
+    }
+
+    /// All the asset events involving `principal`, newest first, fetching up to `concurrency` pages at once.
+    #[cfg(feature = "tokio")]
+    pub fn stream_asset_events<'a>(&'a self, principal: &'a Principal, concurrency: usize) -> impl Stream<Item = Result<AssetEvent, ClientError>> + 'a {
+        paginate_stream(move |limit, offset| self.get_asset_events(principal, Some(limit), Some(offset)), DEFAULT_PAGE_SIZE, concurrency)
+    }
+
+    /// All the non-fungible tokens held by `principal`, fetching up to `concurrency` pages at once.
+    #[cfg(feature = "tokio")]
+    pub fn stream_nft_holdings<'a>(&'a self, principal: &'a Principal, concurrency: usize) -> impl Stream<Item = Result<NftHolding, ClientError>> + 'a {
+        paginate_stream(move |limit, offset| self.get_nft_holdings(principal, Some(limit), Some(offset)), DEFAULT_PAGE_SIZE, concurrency)
+    }
+
+    /// Nonces of `address`: of its last mined transaction and of its transactions in the mempool.
+    pub async fn get_address_nonces(&self, address: &StacksAddress) -> Result<AddressNonces, ClientError> {
+        endpoints::required(endpoints::address_nonces(address).send_async(&self.transport).await?)
+    }
+
+    /// Aligns the nonces `manager` hands out for `address` with the chain and the mempool, see
+    /// [`NonceManager::reconcile`].
+    pub async fn reconcile_nonces(&self, manager: &NonceManager, address: &StacksAddress) -> Result<(), ClientError> {
+        let nonces = self.get_address_nonces(address).await?;
+        manager.reconcile(&address.to_string(), nonces.account_nonce(), nonces.mempool_nonces());
+        Ok(())
+    }
+
+    /// A transaction by its hex txid, pending, mined or dropped; `None` if the API never saw it.
+    pub async fn get_transaction(&self, txid: &str) -> Result<Option<ApiTransaction>, ClientError> {
+        endpoints::optional(endpoints::transaction(txid).send_async(&self.transport).await?)
+    }
+
+    /// A Stacks block, `None` if there is none at this height or hash.
+    pub async fn get_block(&self, block: &BlockRef) -> Result<Option<Block>, ClientError> {
+        endpoints::optional(endpoints::block(block).send_async(&self.transport).await?)
+    }
+
+    /// A Bitcoin block and the Stacks blocks it anchors, `None` if the API does not know it.
+    pub async fn get_burn_block(&self, block: &BlockRef) -> Result<Option<BurnBlock>, ClientError> {
+        endpoints::optional(endpoints::burn_block(block).send_async(&self.transport).await?)
+    }
+
+
+    /// Polls the status of `txid` following `policy` until it is mined, sleeping on the tokio
+    /// timer.
+    ///
+    /// A dropped transaction is a [`ClientError::Dropped`], one still pending at the timeout a
+    /// [`ClientError::Timeout`].
+    #[cfg(feature = "tokio")]
+    pub async fn wait_for_confirmation(&self, txid: &str, policy: &ConfirmationPolicy) -> Result<TransactionOutcome, ClientError> {
+        let start = tokio::time::Instant::now();
+        for attempt in 0.. {
+            if let Some(outcome) = outcome(txid, self.get_transaction(txid).await?)? {
+                return Ok(outcome);
+            }
+            let remaining = policy.timeout.saturating_sub(start.elapsed());
+            if remaining.is_zero() {
+                break;
+            }
+            tokio::time::sleep(policy.interval(attempt).min(remaining)).await;
+        }
+        Err(ClientError::Timeout(txid.to_string()))
+    }
+}
+
+#[cfg(all(test, feature = "tokio"))]
+mod tests {
+    use crate::clarity::value::ClarityValue;
+
+    use super::super::mock::{block_on, MockTransport};
+    use super::*;
+
+    #[test]
+    fn test_wait_for_confirmation() {
+        let txid = "0x84cccb05f4bd0e1b08905ef1f1350ad635a6474448310548bdccfa04e0121bab";
+        let result = ClarityValue::ResponseErr(Box::new(ClarityValue::UInt(1)));
+        let mined = format!(
+            r#"{{"tx_id": "{txid}", "tx_status": "abort_by_response", "tx_type": "contract_call", "nonce": 3, "fee_rate": "180",
+            "sender_address": "SP2J6ZY48GV1EZ5V2V5RB9MP66SW86PYKKNRV9EJ7", "sponsored": false, "tx_result": {{"hex": "{}", "repr": "(err u1)"}}}}"#,
+            result.to_hex().unwrap()
+        );
+        let client = AsyncStacksApiClient::with_transport(StacksNetwork::testnet(), MockTransport::new().with(&format!("/extended/v1/tx/{txid}"), 200, &mined));
+        let outcome = block_on(client.wait_for_confirmation(txid, &ConfirmationPolicy::default())).unwrap();
+        assert_eq!(outcome, TransactionOutcome::AbortByResponse(result));
+    }
+
+    #[test]
+    fn test_stream_nft_holdings() {
+        use std::str::FromStr;
+
+        use futures_util::StreamExt;
+
+        let principal = Principal::from_str("SP2J6ZY48GV1EZ5V2V5RB9MP66SW86PYKKNRV9EJ7").unwrap();
+        let holding = |id: u32| format!(r#"{{"asset_identifier": "SP2X0TZ59D5SZ8ACQ6YMCHHNR2ZN51Z32E2CJ173.guild::Guild", "value": {{"hex": "0x01", "repr": "u{id}"}}, "block_height": 10, "tx_id": "0x02"}}"#);
+        let page = |offset: u32, ids: &[u32]| format!(r#"{{"limit": 50, "offset": {offset}, "total": 51, "results": [{}]}}"#, ids.iter().map(|id| holding(*id)).collect::<Vec<_>>().join(", "));
+        let path = |offset: u32| format!("/extended/v1/tokens/nft/holdings?principal={principal}&limit=50&offset={offset}");
+        let transport = MockTransport::new().with(&path(0), 200, &page(0, &(0..50).collect::<Vec<_>>())).with(&path(50), 200, &page(50, &[50]));
+        let client = AsyncStacksApiClient::with_transport(StacksNetwork::mainnet(), transport);
+        let holdings: Vec<NftHolding> = block_on(client.stream_nft_holdings(&principal, 4).map(Result::unwrap).collect());
+        assert_eq!(holdings.len(), 51);
+        assert_eq!(holdings[50].value.repr, "u50");
+    }
+}
@@ -0,0 +1,211 @@
+//! Async client of the RPC endpoints (`/v2`) of a Stacks node, the counterpart of
+//! [`StacksRpcClient`](super::rpc::StacksRpcClient) with the same requests and response types.
+
+use crate::address::principal::Principal;
+use crate::address::stacks_address::StacksAddress;
+use crate::clarity::abi::ContractAbi;
+use crate::clarity::value::ClarityValue;
+use crate::network::StacksNetwork;
+use crate::transaction::fee::FeeEstimates;
+use crate::transaction::idempotency::IdempotencyGuard;
+use crate::transaction::stacks_transaction::StacksTransaction;
+
+use super::endpoints;
+use super::error::ClientError;
+use super::fees::FeeEstimateRequest;
+use super::http::AsyncHttpTransport;
+#[cfg(feature = "tokio")]
+use super::http::ReqwestTransport;
+use super::types::{AccountInfo, ContractSource, NetworkDetails, NodeInfo, PoxInfo, SortitionInfo, SortitionQuery, TenureInfo};
+
+/// Async client of a Stacks node.
+///
+/// With the `tokio` feature, [`AsyncStacksRpcClient::new`] talks to the node URL of the network
+/// over `reqwest`; any other [`AsyncHttpTransport`] can be plugged in with
+/// [`AsyncStacksRpcClient::with_transport`].
+///
+/// Usage:
+/// ```rust,no_run
+/// # #[cfg(feature = "tokio")]
+/// # async fn balance() {
+/// use std::str::FromStr;
+/// use stacks_rs::address::stacks_address::StacksAddress;
+/// use stacks_rs::clarity::value::ClarityValue;
+/// use stacks_rs::client::async_rpc::AsyncStacksRpcClient;
+/// use stacks_rs::network::StacksNetwork;
+/// let client = AsyncStacksRpcClient::new(StacksNetwork::mainnet());
+/// let contract = StacksAddress::from_str("SP3K8BC0PPEVCV7NZ6QSRWPQ2JE9E5B6N3PA0KBR9").unwrap();
+/// let owner = ClarityValue::from_str("'SP2J6ZY48GV1EZ5V2V5RB9MP66SW86PYKKNRV9EJ7").unwrap();
+/// let balance = client
+///     .call_read_only(&contract, "token-alex", "get-balance", &[owner], "SP2J6ZY48GV1EZ5V2V5RB9MP66SW86PYKKNRV9EJ7")
+///     .await
+///     .unwrap();
+/// # }
+/// ```
+#[derive(Clone, Debug)]
+pub struct AsyncStacksRpcClient<T> {
+    network: StacksNetwork,
+    transport: T,
+}
+
+#[cfg(feature = "tokio")]
+impl AsyncStacksRpcClient<ReqwestTransport> {
+    pub fn new(network: StacksNetwork) -> Self {
+        let transport = ReqwestTransport::new(&network.node_url);
+        AsyncStacksRpcClient { network, transport }
+    }
+
+    /// A client of the node at `node_url`, on the network the node reports, see [`NodeInfo::network`].
+    pub async fn connect(node_url: &str) -> Result<Self, ClientError> {
+        let transport = ReqwestTransport::new(node_url);
+        let info: NodeInfo = endpoints::required(endpoints::info().send_async(&transport).await?)?;
+        Ok(AsyncStacksRpcClient { network: info.network(node_url), transport })
+    }
+}
+
+impl<T: AsyncHttpTransport> AsyncStacksRpcClient<T> {
+    /// A client of `network` sending through `transport`, already pointing at its node.
+    pub fn with_transport(network: StacksNetwork, transport: T) -> Self {
+        AsyncStacksRpcClient { network, transport }
+    }
+
+    pub fn network(&self) -> &StacksNetwork {
+        &self.network
+    }
+
+    pub fn transport(&self) -> &T {
+        &self.transport
+    }
+
+    /// Micro-STX per byte of a token transfer, `/v2/fees/transfer`.
+    pub async fn get_transfer_fee_rate(&self) -> Result<u64, ClientError> {
+        endpoints::required(endpoints::transfer_fee_rate().send_async(&self.transport).await?)
+    }
+
+    /// Low, medium and high fees of a transaction, `/v2/fees/transaction`.
+    pub async fn estimate_fees(&self, request: &FeeEstimateRequest) -> Result<FeeEstimates, ClientError> {
+        endpoints::fee_estimates(endpoints::estimate_fees(request)?.send_async(&self.transport).await?)
+    }
+
+    /// Result of calling the read-only function `function_name` as `sender`.
+    pub async fn call_read_only(
+        &self,
+        contract_address: &StacksAddress,
+        contract_name: &str,
+        function_name: &str,
+        arguments: &[ClarityValue],
+        sender: &str,
+    ) -> Result<ClarityValue, ClientError> {
+        let request = endpoints::call_read_only(contract_address, contract_name, function_name, arguments, sender)?;
+        endpoints::read_only_result(request.send_async(&self.transport).await?, contract_address, contract_name)
+    }
+
+    /// Sends a signed transaction to the node, `/v2/transactions`, returning its txid as hex.
+    ///
+    /// A rejection is a [`ClientError::Rejected`] with the reason given by the node. A
+    /// transaction whose metadata can expire is checked against the tip height first, and not
+    /// sent if stale.
+    pub async fn broadcast_transaction(&self, transaction: &StacksTransaction) -> Result<String, ClientError> {
+        if transaction.metadata.is_some_and(|metadata| metadata.can_expire()) {
+            transaction.check_fresh(self.get_info().await?.stacks_tip_height)?;
+        }
+        endpoints::broadcast_result(endpoints::broadcast(transaction)?.send_async(&self.transport).await?)
+    }
+
+    /// Broadcasts `transaction` unless `guard` saw its idempotency key within its window, a
+    /// [`crate::transaction::TransactionError::Duplicate`] then; the key is forgotten if the broadcast fails, so
+    /// it can be retried.
+    pub async fn broadcast_once(&self, transaction: &StacksTransaction, guard: &mut IdempotencyGuard) -> Result<String, ClientError> {
+        let key = transaction.idempotency_key()?;
+        guard.check_and_record(key)?;
+        self.broadcast_transaction(transaction).await.inspect_err(|_| guard.forget(&key))
+    }
+
+    /// Balances and nonce of `principal`, e.g. to set the nonce of a builder; an account never
+    /// used is empty with nonce 0.
+    pub async fn get_account(&self, principal: &Principal) -> Result<AccountInfo, ClientError> {
+        endpoints::required(endpoints::account(principal).send_async(&self.transport).await?)
+    }
+
+    /// Clarity source of a contract, `None` if it does not exist.
+    pub async fn get_contract_source(&self, contract_address: &StacksAddress, contract_name: &str) -> Result<Option<ContractSource>, ClientError> {
+        endpoints::optional(endpoints::contract_source(contract_address, contract_name)?.send_async(&self.transport).await?)
+    }
+
+    /// Interface of a contract, `None` if it does not exist.
+    ///
+    /// Calls can be checked against it with [`ContractCallBuilder::abi`](crate::transaction::builder::ContractCallBuilder::abi).
+    pub async fn get_contract_interface(&self, contract_address: &StacksAddress, contract_name: &str) -> Result<Option<ContractAbi>, ClientError> {
+        endpoints::optional(endpoints::contract_interface(contract_address, contract_name)?.send_async(&self.transport).await?)
+    }
+
+    /// Value of the data variable `name`, `None` if the contract or variable does not exist.
+    pub async fn get_data_var(&self, contract_address: &StacksAddress, contract_name: &str, name: &str) -> Result<Option<ClarityValue>, ClientError> {
+        endpoints::optional_value(endpoints::data_var(contract_address, contract_name, name)?.send_async(&self.transport).await?)
+    }
+
+    /// Value of the constant `name`, `None` if the contract or constant does not exist.
+    pub async fn get_constant(&self, contract_address: &StacksAddress, contract_name: &str, name: &str) -> Result<Option<ClarityValue>, ClientError> {
+        endpoints::optional_value(endpoints::constant(contract_address, contract_name, name)?.send_async(&self.transport).await?)
+    }
+
+    /// Entry of `key` in the map `map_name`, as `(some value)` or `none` like `map-get?`.
+    pub async fn get_map_entry(&self, contract_address: &StacksAddress, contract_name: &str, map_name: &str, key: &ClarityValue) -> Result<ClarityValue, ClientError> {
+        endpoints::value(endpoints::map_entry(contract_address, contract_name, map_name, key)?.send_async(&self.transport).await?)
+    }
+
+    /// Version, network and tips of the node, `/v2/info`.
+    pub async fn get_info(&self) -> Result<NodeInfo, ClientError> {
+        endpoints::required(endpoints::info().send_async(&self.transport).await?)
+    }
+
+    /// The network of the node, its current epoch and PoX contract, from `/v2/info` and `/v2/pox`.
+    pub async fn detect_network(&self) -> Result<NetworkDetails, ClientError> {
+        Ok(NetworkDetails::new(&self.network.node_url, &self.get_info().await?, &self.get_pox_info().await?))
+    }
+
+    /// The PoX contract and its current and next reward cycles, `/v2/pox`.
+    pub async fn get_pox_info(&self) -> Result<PoxInfo, ClientError> {
+        endpoints::required(endpoints::pox_info().send_async(&self.transport).await?)
+    }
+
+    /// The ongoing Nakamoto tenure and the tip of the node, `/v3/tenures/info`.
+    pub async fn get_tenure_info(&self) -> Result<TenureInfo, ClientError> {
+        endpoints::required(endpoints::tenure_info().send_async(&self.transport).await?)
+    }
+
+    /// Sortitions of `/v3/sortitions`: the latest, or the one of a Bitcoin block; empty if the
+    /// node does not know the block.
+    pub async fn get_sortitions(&self, query: &SortitionQuery) -> Result<Vec<SortitionInfo>, ClientError> {
+        Ok(endpoints::optional(endpoints::sortitions(query).send_async(&self.transport).await?)?.unwrap_or_default())
+    }
+
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use super::super::mock::{block_on, MockTransport};
+    use super::*;
+
+    const CONTRACT: &str = "SP3K8BC0PPEVCV7NZ6QSRWPQ2JE9E5B6N3PA0KBR9";
+    const SENDER: &str = "SP2J6ZY48GV1EZ5V2V5RB9MP66SW86PYKKNRV9EJ7";
+
+    #[test]
+    fn test_async_client() {
+        let contract = StacksAddress::from_str(CONTRACT).unwrap();
+        let ok = ClarityValue::ResponseOk(Box::new(ClarityValue::UInt(1000)));
+        let transport = MockTransport::new()
+            .with(&format!("/v2/contracts/call-read/{CONTRACT}/token/get-balance"), 200, &format!(r#"{{"okay": true, "result": "{}"}}"#, ok.to_hex().unwrap()))
+            .with(&format!("/v2/data_var/{CONTRACT}/token/missing?proof=0"), 404, "")
+            .with(&format!("/v2/constant_val/{CONTRACT}/token/decimals"), 200, r#"{"data": "0x0100000000000000000000000000000006"}"#)
+            .with("/v2/fees/transfer", 500, "");
+        let client = AsyncStacksRpcClient::with_transport(StacksNetwork::mainnet(), transport);
+        assert_eq!(block_on(client.call_read_only(&contract, "token", "get-balance", &[], SENDER)).unwrap(), ok);
+        assert_eq!(block_on(client.get_data_var(&contract, "token", "missing")).unwrap(), None);
+        assert_eq!(block_on(client.get_constant(&contract, "token", "decimals")).unwrap(), Some(ClarityValue::UInt(6)));
+        assert!(matches!(block_on(client.get_transfer_fee_rate()), Err(ClientError::Api { status: 500, .. })));
+        assert_eq!(client.transport().requests.borrow().len(), 4);
+    }
+}
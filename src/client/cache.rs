@@ -0,0 +1,269 @@
+//! Caching of the responses that do not change, as a transport wrapping another one.
+//!
+//! Blocks by hash, contract sources and interfaces never change once served, token metadata
+//! seldom does: [`CachingTransport`] answers them from a [`ResponseCache`] rather than spending
+//! the quota of a public API on them again.
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use super::error::ClientError;
+use super::http::{HttpResponse, HttpTransport};
+
+/// How long token metadata is kept by the default [`CachePolicy`].
+pub const METADATA_TTL: Duration = Duration::from_secs(3600);
+
+/// Where responses are kept, by request path, as the body served.
+///
+/// A store kept across runs, e.g. on disk, spares the requests of earlier runs too.
+pub trait ResponseCache {
+    fn get(&self, path: &str) -> Option<String>;
+
+    /// Keeps `body` for `ttl`, forever if `None`.
+    fn insert(&self, path: &str, body: &str, ttl: Option<Duration>);
+}
+
+/// Caches nothing.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct NoCache;
+
+impl ResponseCache for NoCache {
+    fn get(&self, _path: &str) -> Option<String> {
+        None
+    }
+
+    fn insert(&self, _path: &str, _body: &str, _ttl: Option<Duration>) {}
+}
+
+/// Caches in memory, for the life of the cache, dropping the responses past their TTL.
+#[derive(Debug, Default)]
+pub struct MemoryCache {
+    bodies: Mutex<HashMap<String, (String, Option<Instant>)>>,
+}
+
+impl MemoryCache {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Number of responses kept, expired ones included until read or purged.
+    pub fn len(&self) -> usize {
+        self.bodies.lock().expect("cache lock").len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Drops the expired responses.
+    pub fn purge_expired(&self) {
+        let now = Instant::now();
+        self.bodies.lock().expect("cache lock").retain(|_, (_, expiry)| expiry.is_none_or(|expiry| now < expiry));
+    }
+}
+
+impl ResponseCache for MemoryCache {
+    fn get(&self, path: &str) -> Option<String> {
+        let mut bodies = self.bodies.lock().expect("cache lock");
+        match bodies.get(path) {
+            Some((_, Some(expiry))) if *expiry <= Instant::now() => {
+                bodies.remove(path);
+                None
+            }
+            entry => entry.map(|(body, _)| body.clone()),
+        }
+    }
+
+    fn insert(&self, path: &str, body: &str, ttl: Option<Duration>) {
+        let expiry = ttl.and_then(|ttl| Instant::now().checked_add(ttl));
+        self.bodies.lock().expect("cache lock").insert(path.to_string(), (body.to_string(), expiry));
+    }
+}
+
+/// Responses to cache: those of the GET paths starting with `prefix`, for `ttl`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CacheRule {
+    pub prefix: String,
+    /// Forever if `None`
+    pub ttl: Option<Duration>,
+}
+
+/// Which responses to cache and for how long, by the first [`CacheRule`] matching their path.
+///
+/// The default caches the immutable responses forever: blocks and burn blocks by hash,
+/// contract sources and interfaces; and token metadata for [`METADATA_TTL`].
+///
+/// Usage:
+/// ```rust
+/// use std::time::Duration;
+/// use stacks_rs::client::cache::CachePolicy;
+/// let policy = CachePolicy::default().with_rule("/extended/v2/blocks/", Some(Duration::from_secs(60)));
+/// assert_eq!(policy.rule("/extended/v2/blocks/0x01").unwrap().ttl, None);
+/// assert_eq!(policy.rule("/extended/v2/blocks/170000").unwrap().ttl, Some(Duration::from_secs(60)));
+/// assert_eq!(policy.rule("/v2/info"), None);
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CachePolicy {
+    pub rules: Vec<CacheRule>,
+}
+
+impl CachePolicy {
+    /// Caches nothing, until given rules.
+    pub fn none() -> Self {
+        CachePolicy { rules: Vec::new() }
+    }
+
+    /// Also caches the responses of the paths starting with `prefix`, after the rules already set.
+    pub fn with_rule(mut self, prefix: &str, ttl: Option<Duration>) -> Self {
+        self.rules.push(CacheRule { prefix: prefix.to_string(), ttl });
+        self
+    }
+
+    /// The rule caching the response of `path`, `None` if it is not cached.
+    pub fn rule(&self, path: &str) -> Option<&CacheRule> {
+        self.rules.iter().find(|rule| path.starts_with(&rule.prefix))
+    }
+}
+
+impl Default for CachePolicy {
+    fn default() -> Self {
+        CachePolicy::none()
+            .with_rule("/extended/v2/blocks/0x", None)
+            .with_rule("/extended/v2/burn-blocks/0x", None)
+            .with_rule("/v2/contracts/source/", None)
+            .with_rule("/v2/contracts/interface/", None)
+            .with_rule("/metadata/v1/", Some(METADATA_TTL))
+    }
+}
+
+/// A transport answering the GET requests of `inner` cached by its [`CachePolicy`] from a
+/// [`ResponseCache`]; only successful responses are kept.
+///
+/// Usage:
+/// ```rust,no_run
+/// # #[cfg(feature = "blocking")]
+/// # {
+/// use stacks_rs::client::api::StacksApiClient;
+/// use stacks_rs::client::cache::CachingTransport;
+/// use stacks_rs::client::http::UreqTransport;
+/// use stacks_rs::client::types::BlockRef;
+/// use stacks_rs::network::StacksNetwork;
+/// let network = StacksNetwork::mainnet();
+/// let transport = CachingTransport::new(UreqTransport::new(&network.api_url));
+/// let client = StacksApiClient::with_transport(network, transport);
+/// let block = client.get_block(&BlockRef::Hash(String::from("0x01"))).unwrap();
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct CachingTransport<T, C = MemoryCache> {
+    inner: T,
+    cache: C,
+    policy: CachePolicy,
+}
+
+impl<T> CachingTransport<T> {
+    /// Caches in memory following the default [`CachePolicy`].
+    pub fn new(inner: T) -> Self {
+        CachingTransport { inner, cache: MemoryCache::new(), policy: CachePolicy::default() }
+    }
+}
+
+impl<T, C: ResponseCache> CachingTransport<T, C> {
+    pub fn with_cache<D: ResponseCache>(self, cache: D) -> CachingTransport<T, D> {
+        CachingTransport { inner: self.inner, cache, policy: self.policy }
+    }
+
+    pub fn with_policy(mut self, policy: CachePolicy) -> Self {
+        self.policy = policy;
+        self
+    }
+
+    pub fn inner(&self) -> &T {
+        &self.inner
+    }
+
+    pub fn cache(&self) -> &C {
+        &self.cache
+    }
+
+    fn cached(&self, path: &str) -> Option<HttpResponse> {
+        self.policy.rule(path)?;
+        self.cache.get(path).map(|body| HttpResponse::new(200, &body))
+    }
+
+    fn store(&self, path: &str, response: &HttpResponse) {
+        if let Some(rule) = self.policy.rule(path).filter(|_| response.status == 200) {
+            self.cache.insert(path, &response.body, rule.ttl);
+        }
+    }
+}
+
+impl<T: HttpTransport, C: ResponseCache> HttpTransport for CachingTransport<T, C> {
+    fn get(&self, path: &str) -> Result<HttpResponse, ClientError> {
+        if let Some(response) = self.cached(path) {
+            return Ok(response);
+        }
+        let response = self.inner.get(path)?;
+        self.store(path, &response);
+        Ok(response)
+    }
+
+    fn post(&self, path: &str, content_type: &str, body: &[u8]) -> Result<HttpResponse, ClientError> {
+        self.inner.post(path, content_type, body)
+    }
+}
+
+#[cfg(feature = "tokio")]
+impl<T: super::http::AsyncHttpTransport + Sync, C: ResponseCache + Sync> super::http::AsyncHttpTransport for CachingTransport<T, C> {
+    async fn get(&self, path: &str) -> Result<HttpResponse, ClientError> {
+        if let Some(response) = self.cached(path) {
+            return Ok(response);
+        }
+        let response = self.inner.get(path).await?;
+        self.store(path, &response);
+        Ok(response)
+    }
+
+    fn post(&self, path: &str, content_type: &str, body: &[u8]) -> impl std::future::Future<Output = Result<HttpResponse, ClientError>> + Send {
+        self.inner.post(path, content_type, body)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::mock::MockTransport;
+    use super::*;
+
+    #[test]
+    fn test_caching_transport() {
+        let transport = MockTransport::new()
+            .with("/extended/v2/blocks/0x01", 200, r#"{"height": 1}"#)
+            .with("/extended/v2/blocks/0x02", 404, "")
+            .with("/extended/v2/blocks/0x02", 200, r#"{"height": 2}"#)
+            .with("/v2/info", 200, "{}");
+        let caching = CachingTransport::new(transport);
+        for _ in 0..2 {
+            assert_eq!(caching.get("/extended/v2/blocks/0x01").unwrap().body, r#"{"height": 1}"#);
+            assert_eq!(caching.get("/v2/info").unwrap().status, 200);
+        }
+        assert_eq!(caching.get("/extended/v2/blocks/0x02").unwrap().status, 404);
+        assert_eq!(caching.get("/extended/v2/blocks/0x02").unwrap().status, 200);
+        assert_eq!(caching.get("/extended/v2/blocks/0x02").unwrap().status, 200);
+        assert_eq!(caching.inner().requests.borrow().len(), 5);
+        assert_eq!(caching.cache().len(), 2);
+    }
+
+    #[test]
+    fn test_memory_cache_ttl() {
+        let cache = MemoryCache::new();
+        cache.insert("/forever", "a", None);
+        cache.insert("/expired", "b", Some(Duration::ZERO));
+        cache.insert("/later", "c", Some(Duration::from_secs(60)));
+        assert_eq!((cache.get("/forever").as_deref(), cache.get("/later").as_deref()), (Some("a"), Some("c")));
+        assert_eq!(cache.len(), 3);
+        cache.purge_expired();
+        assert_eq!(cache.len(), 2);
+        assert_eq!(cache.get("/expired"), None);
+    }
+}
@@ -0,0 +1,92 @@
+//! Waiting for a broadcast transaction to be mined, see
+//! [`StacksApiClient::wait_for_confirmation`](super::api::StacksApiClient::wait_for_confirmation).
+
+use std::time::Duration;
+
+use serde::de::Error;
+
+use crate::clarity::value::ClarityValue;
+
+use super::error::ClientError;
+use super::types::{ApiTransaction, TxStatus};
+
+/// How often to poll the status of a transaction, and for how long.
+///
+/// The interval starts at `initial_interval` and is multiplied by `multiplier` after each poll,
+/// up to `max_interval`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ConfirmationPolicy {
+    pub initial_interval: Duration,
+    pub max_interval: Duration,
+    pub multiplier: u32,
+    /// Giving up with [`ClientError::Timeout`] past it
+    pub timeout: Duration,
+}
+
+impl Default for ConfirmationPolicy {
+    /// From 2 seconds, about the time of a Nakamoto block, to 30 seconds, for 10 minutes.
+    fn default() -> Self {
+        ConfirmationPolicy { initial_interval: Duration::from_secs(2), max_interval: Duration::from_secs(30), multiplier: 2, timeout: Duration::from_secs(600) }
+    }
+}
+
+impl ConfirmationPolicy {
+    /// Wait after the poll `attempt`, from 0.
+    ///
+    /// Usage:
+    /// ```rust
+    /// use std::time::Duration;
+    /// use stacks_rs::client::confirmation::ConfirmationPolicy;
+    /// let policy = ConfirmationPolicy::default();
+    /// assert_eq!(policy.interval(0), Duration::from_secs(2));
+    /// assert_eq!(policy.interval(3), Duration::from_secs(16));
+    /// assert_eq!(policy.interval(10), Duration::from_secs(30));
+    /// ```
+    pub fn interval(&self, attempt: u32) -> Duration {
+        let factor = self.multiplier.max(1).saturating_pow(attempt);
+        self.initial_interval.saturating_mul(factor).min(self.max_interval)
+    }
+}
+
+/// How a mined transaction ended, with its result.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum TransactionOutcome {
+    Success(ClarityValue),
+    /// The contract call returned an `err`, its changes were rolled back
+    AbortByResponse(ClarityValue),
+    /// A post-condition did not hold, its changes were rolled back
+    AbortByPostCondition(ClarityValue),
+}
+
+impl TransactionOutcome {
+    /// The value returned by the transaction, e.g. `(ok true)`.
+    pub fn result(&self) -> &ClarityValue {
+        match self {
+            TransactionOutcome::Success(result) | TransactionOutcome::AbortByResponse(result) | TransactionOutcome::AbortByPostCondition(result) => result,
+        }
+    }
+
+    pub fn is_success(&self) -> bool {
+        matches!(self, TransactionOutcome::Success(_))
+    }
+}
+
+/// The outcome of `transaction` once mined, `None` while it is pending or not indexed yet.
+pub(crate) fn outcome(txid: &str, transaction: Option<ApiTransaction>) -> Result<Option<TransactionOutcome>, ClientError> {
+    let Some(transaction) = transaction else {
+        return Ok(None);
+    };
+    if transaction.tx_status.is_dropped() {
+        return Err(ClientError::Dropped { txid: txid.to_string(), status: transaction.tx_status });
+    }
+    if !transaction.tx_status.is_mined() {
+        return Ok(None);
+    }
+    let result = transaction.tx_result.ok_or_else(|| ClientError::Decode(serde_json::Error::custom("missing tx_result of a mined transaction")))?;
+    let result = ClarityValue::from_hex(&result.hex)?;
+    Ok(Some(match transaction.tx_status {
+        TxStatus::Success => TransactionOutcome::Success(result),
+        TxStatus::AbortByResponse => TransactionOutcome::AbortByResponse(result),
+        _ => TransactionOutcome::AbortByPostCondition(result),
+    }))
+}
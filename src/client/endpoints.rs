@@ -0,0 +1,232 @@
+//! Requests of the node and API endpoints and decoding of their responses, shared by the
+//! blocking and async clients so they only differ in how they send.
+
+use serde::de::DeserializeOwned;
+
+use crate::address::principal::Principal;
+use crate::address::stacks_address::StacksAddress;
+use crate::clarity::value::{is_valid_clarity_name, ClarityValue};
+use crate::transaction::fee::FeeEstimates;
+use crate::transaction::stacks_transaction::StacksTransaction;
+
+use super::error::{parse_required, parse_response, ApiErrorBody, ClientError};
+use super::fees::{FeeEstimateRequest, FeeEstimateResponse, FEES_TRANSACTION_PATH};
+use super::http::{HttpRequest, HttpResponse};
+use super::types::{BlockRef, ClarityData, ReadOnlyCallRequest, ReadOnlyCallResponse, RejectReason, SortitionQuery};
+
+/// `value` as a single path segment: every byte but letters, digits, `-`, `_` and `~` is
+/// percent-encoded, `.` included so that `.` and `..` are not resolved as dot-segments.
+fn segment(value: &str) -> String {
+    value
+        .bytes()
+        .map(|byte| match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'~' => (byte as char).to_string(),
+            _ => format!("%{byte:02X}"),
+        })
+        .collect()
+}
+
+/// The path segment of a contract name, or [`ClientError::InvalidName`].
+fn contract_segment(contract_name: &str) -> Result<String, ClientError> {
+    match Principal::is_valid_contract_name(contract_name) {
+        true => Ok(segment(contract_name)),
+        false => Err(ClientError::InvalidName(contract_name.to_string())),
+    }
+}
+
+/// The path segment of a function, variable or map name, or [`ClientError::InvalidName`].
+fn name_segment(name: &str) -> Result<String, ClientError> {
+    match is_valid_clarity_name(name) {
+        true => Ok(segment(name)),
+        false => Err(ClientError::InvalidName(name.to_string())),
+    }
+}
+
+pub(crate) fn optional<T: DeserializeOwned>(response: HttpResponse) -> Result<Option<T>, ClientError> {
+    parse_response(response.status, &response.body)
+}
+
+pub(crate) fn required<T: DeserializeOwned>(response: HttpResponse) -> Result<T, ClientError> {
+    parse_required(response.status, &response.body)
+}
+
+pub(crate) fn transfer_fee_rate() -> HttpRequest {
+    HttpRequest::get("/v2/fees/transfer")
+}
+
+pub(crate) fn estimate_fees(request: &FeeEstimateRequest) -> Result<HttpRequest, ClientError> {
+    HttpRequest::post_json(FEES_TRANSACTION_PATH, request)
+}
+
+pub(crate) fn fee_estimates(response: HttpResponse) -> Result<FeeEstimates, ClientError> {
+    let fees: FeeEstimateResponse = parse_required(response.status, &response.body)?;
+    fees.estimates().ok_or(ClientError::Api { status: response.status, body: None, raw: response.body })
+}
+
+pub(crate) fn call_read_only(
+    contract_address: &StacksAddress,
+    contract_name: &str,
+    function_name: &str,
+    arguments: &[ClarityValue],
+    sender: &str,
+) -> Result<HttpRequest, ClientError> {
+    let path = format!("/v2/contracts/call-read/{contract_address}/{}/{}", contract_segment(contract_name)?, name_segment(function_name)?);
+    HttpRequest::post_json(&path, &ReadOnlyCallRequest::new(sender, arguments)?)
+}
+
+pub(crate) fn read_only_result(response: HttpResponse, contract_address: &StacksAddress, contract_name: &str) -> Result<ClarityValue, ClientError> {
+    let response: ReadOnlyCallResponse = optional(response)?.ok_or_else(|| ClientError::CallFailed(format!("no contract {contract_address}.{contract_name}")))?;
+    match (response.okay, response.result) {
+        (true, Some(result)) => Ok(ClarityValue::from_hex(&result)?),
+        (_, _) => Err(ClientError::CallFailed(response.cause.unwrap_or_default())),
+    }
+}
+
+pub(crate) fn data_var(contract_address: &StacksAddress, contract_name: &str, name: &str) -> Result<HttpRequest, ClientError> {
+    Ok(HttpRequest::get(&format!("/v2/data_var/{contract_address}/{}/{}?proof=0", contract_segment(contract_name)?, name_segment(name)?)))
+}
+
+pub(crate) fn constant(contract_address: &StacksAddress, contract_name: &str, name: &str) -> Result<HttpRequest, ClientError> {
+    Ok(HttpRequest::get(&format!("/v2/constant_val/{contract_address}/{}/{}", contract_segment(contract_name)?, name_segment(name)?)))
+}
+
+/// A value of `/v2/data_var` or `/v2/constant_val`, `None` when it does not exist.
+pub(crate) fn optional_value(response: HttpResponse) -> Result<Option<ClarityValue>, ClientError> {
+    let data: Option<ClarityData> = optional(response)?;
+    Ok(data.map(|data| ClarityValue::from_hex(&data.data)).transpose()?)
+}
+
+pub(crate) fn map_entry(contract_address: &StacksAddress, contract_name: &str, map_name: &str, key: &ClarityValue) -> Result<HttpRequest, ClientError> {
+    let path = format!("/v2/map_entry/{contract_address}/{}/{}?proof=0", contract_segment(contract_name)?, name_segment(map_name)?);
+    HttpRequest::post_json(&path, &key.to_hex()?)
+}
+
+pub(crate) fn value(response: HttpResponse) -> Result<ClarityValue, ClientError> {
+    let data: ClarityData = required(response)?;
+    Ok(ClarityValue::from_hex(&data.data)?)
+}
+
+pub(crate) fn broadcast(transaction: &StacksTransaction) -> Result<HttpRequest, ClientError> {
+    Ok(HttpRequest::post("/v2/transactions", "application/octet-stream", transaction.serialize()?))
+}
+
+/// The txid of an accepted transaction, the reason of its rejection otherwise.
+pub(crate) fn broadcast_result(response: HttpResponse) -> Result<String, ClientError> {
+    if response.status == 400 {
+        if let Ok(ApiErrorBody { reason: Some(reason), reason_data, txid, .. }) = serde_json::from_str(&response.body) {
+            return Err(ClientError::Rejected { reason: RejectReason::from_reason(&reason), reason_data, txid });
+        }
+    }
+    required(response)
+}
+
+pub(crate) fn account(principal: &Principal) -> HttpRequest {
+    HttpRequest::get(&format!("/v2/accounts/{principal}?proof=0"))
+}
+
+pub(crate) fn contract_source(contract_address: &StacksAddress, contract_name: &str) -> Result<HttpRequest, ClientError> {
+    Ok(HttpRequest::get(&format!("/v2/contracts/source/{contract_address}/{}?proof=0", contract_segment(contract_name)?)))
+}
+
+pub(crate) fn contract_interface(contract_address: &StacksAddress, contract_name: &str) -> Result<HttpRequest, ClientError> {
+    Ok(HttpRequest::get(&format!("/v2/contracts/interface/{contract_address}/{}", contract_segment(contract_name)?)))
+}
+
+/// `path` with the query parameters that are set.
+fn with_query(path: &str, parameters: &[(&str, Option<String>)]) -> String {
+    let query = parameters.iter().filter_map(|(name, value)| value.as_ref().map(|value| format!("{name}={value}"))).collect::<Vec<_>>();
+    match query.is_empty() {
+        true => path.to_string(),
+        false => format!("{path}?{}", query.join("&")),
+    }
+}
+
+pub(crate) fn mempool_transactions(sender: Option<&StacksAddress>, limit: Option<u32>, offset: Option<u32>) -> HttpRequest {
+    let parameters = [("sender_address", sender.map(StacksAddress::to_string)), ("limit", limit.map(|l| l.to_string())), ("offset", offset.map(|o| o.to_string()))];
+    HttpRequest::get(&with_query("/extended/v1/tx/mempool", &parameters))
+}
+
+pub(crate) fn dropped_mempool_transactions(limit: Option<u32>, offset: Option<u32>) -> HttpRequest {
+    HttpRequest::get(&with_query("/extended/v1/tx/mempool/dropped", &page_parameters(limit, offset)))
+}
+
+fn page_parameters(limit: Option<u32>, offset: Option<u32>) -> [(&'static str, Option<String>); 2] {
+    [("limit", limit.map(|l| l.to_string())), ("offset", offset.map(|o| o.to_string()))]
+}
+
+pub(crate) fn account_transactions(principal: &Principal, limit: Option<u32>, offset: Option<u32>) -> HttpRequest {
+    HttpRequest::get(&with_query(&format!("/extended/v1/address/{principal}/transactions"), &page_parameters(limit, offset)))
+}
+
+pub(crate) fn address_transactions(principal: &Principal, limit: Option<u32>, offset: Option<u32>) -> HttpRequest {
+    HttpRequest::get(&with_query(&format!("/extended/v2/addresses/{principal}/transactions"), &page_parameters(limit, offset)))
+}
+
+pub(crate) fn asset_events(principal: &Principal, limit: Option<u32>, offset: Option<u32>) -> HttpRequest {
+    HttpRequest::get(&with_query(&format!("/extended/v1/address/{principal}/assets"), &page_parameters(limit, offset)))
+}
+
+pub(crate) fn nft_holdings(principal: &Principal, limit: Option<u32>, offset: Option<u32>) -> HttpRequest {
+    let [limit, offset] = page_parameters(limit, offset);
+    HttpRequest::get(&with_query("/extended/v1/tokens/nft/holdings", &[("principal", Some(principal.to_string())), limit, offset]))
+}
+
+pub(crate) fn address_nonces(address: &StacksAddress) -> HttpRequest {
+    HttpRequest::get(&format!("/extended/v1/address/{address}/nonces"))
+}
+
+pub(crate) fn transaction(txid: &str) -> HttpRequest {
+    HttpRequest::get(&format!("/extended/v1/tx/0x{}", segment(txid.trim_start_matches("0x"))))
+}
+
+pub(crate) fn block(block: &BlockRef) -> HttpRequest {
+    HttpRequest::get(&format!("/extended/v2/blocks/{}", segment(&block.to_string())))
+}
+
+pub(crate) fn burn_block(block: &BlockRef) -> HttpRequest {
+    HttpRequest::get(&format!("/extended/v2/burn-blocks/{}", segment(&block.to_string())))
+}
+
+pub(crate) fn info() -> HttpRequest {
+    HttpRequest::get("/v2/info")
+}
+
+pub(crate) fn pox_info() -> HttpRequest {
+    HttpRequest::get("/v2/pox")
+}
+
+pub(crate) fn tenure_info() -> HttpRequest {
+    HttpRequest::get("/v3/tenures/info")
+}
+
+pub(crate) fn sortitions(query: &SortitionQuery) -> HttpRequest {
+    match query {
+        SortitionQuery::Latest => HttpRequest::get("/v3/sortitions"),
+        SortitionQuery::BurnHeight(height) => HttpRequest::get(&format!("/v3/sortitions/burn_height/{height}")),
+        SortitionQuery::BurnHash(hash) => HttpRequest::get(&format!("/v3/sortitions/burn/{}", segment(hash.trim_start_matches("0x")))),
+        SortitionQuery::ConsensusHash(hash) => HttpRequest::get(&format!("/v3/sortitions/consensus/{}", segment(hash.trim_start_matches("0x")))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use super::*;
+
+    #[test]
+    fn test_path_segments() {
+        let address = StacksAddress::from_str("SP000000000000000000002Q6VF78").unwrap();
+        assert_eq!(contract_interface(&address, "pox-4").unwrap(), HttpRequest::get("/v2/contracts/interface/SP000000000000000000002Q6VF78/pox-4"));
+        assert_eq!(constant(&address, "pox-4", "is-ok?").unwrap(), HttpRequest::get("/v2/constant_val/SP000000000000000000002Q6VF78/pox-4/is-ok%3F"));
+        assert_eq!(data_var(&address, "pox-4", "/").unwrap(), HttpRequest::get("/v2/data_var/SP000000000000000000002Q6VF78/pox-4/%2F?proof=0"));
+        assert!(matches!(data_var(&address, "..", ".."), Err(ClientError::InvalidName(name)) if name == ".."));
+        assert!(matches!(data_var(&address, "pox-4", ".."), Err(ClientError::InvalidName(_))));
+        assert!(matches!(contract_source(&address, "../../v2/info"), Err(ClientError::InvalidName(_))));
+        assert!(matches!(call_read_only(&address, "pox-4", "a#b", &[], "SP000000000000000000002Q6VF78"), Err(ClientError::InvalidName(_))));
+        assert_eq!(transaction("0xab/../cd"), HttpRequest::get("/extended/v1/tx/0xab%2F%2E%2E%2Fcd"));
+        assert_eq!(transaction(".."), HttpRequest::get("/extended/v1/tx/0x%2E%2E"));
+        assert_eq!(block(&BlockRef::Hash(String::from("ab?x=1"))), HttpRequest::get("/extended/v2/blocks/0xab%3Fx%3D1"));
+        assert_eq!(sortitions(&SortitionQuery::ConsensusHash(String::from("0xab cd"))), HttpRequest::get("/v3/sortitions/consensus/ab%20cd"));
+    }
+}
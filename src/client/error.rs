@@ -0,0 +1,157 @@
+use std::fmt;
+
+use serde::de::DeserializeOwned;
+use serde::Deserialize;
+
+use crate::clarity::ClarityError;
+use crate::transaction::TransactionError;
+
+use super::types::{RejectReason, TxStatus};
+
+const HTTP_NOT_FOUND: u16 = 404;
+
+/// Error payload returned by the Stacks node and the Hiro API.
+///
+/// The node reports transaction rejections as `{"error", "reason", "reason_data", "txid"}`,
+/// the Hiro API uses `{"error", "message"}`; every field but `error` is optional.
+#[derive(Clone, Debug, PartialEq, Deserialize)]
+pub struct ApiErrorBody {
+    pub error: String,
+    #[serde(default)]
+    pub message: Option<String>,
+    #[serde(default)]
+    pub reason: Option<String>,
+    #[serde(default)]
+    pub reason_data: Option<serde_json::Value>,
+    #[serde(default)]
+    pub txid: Option<String>,
+}
+
+#[derive(Debug)]
+pub enum ClientError {
+    /// The request never got a response (connection, DNS, timeout, ...)
+    Transport(String),
+    /// The server answered with an error status
+    Api {
+        status: u16,
+        /// Typed error payload, when the body is a known error JSON
+        body: Option<Box<ApiErrorBody>>,
+        raw: String,
+    },
+    /// The response body does not match the expected type
+    Decode(serde_json::Error),
+    /// A Clarity value of the request or the response
+    Clarity(ClarityError),
+    /// A read-only call failed at runtime, with the cause reported by the node
+    CallFailed(String),
+    /// The transaction to send cannot be encoded
+    Transaction(TransactionError),
+    /// The node rejected a broadcast transaction
+    Rejected {
+        reason: RejectReason,
+        /// Details of the reason, e.g. the expected and actual nonces of `BadNonce`
+        reason_data: Option<serde_json::Value>,
+        txid: Option<String>,
+    },
+    /// A transaction waited for was dropped from the mempool
+    Dropped { txid: String, status: TxStatus },
+    /// A transaction waited for was not mined in time
+    Timeout(String),
+    /// The API refused a websocket subscription
+    Subscription(String),
+    /// Error of a Rosetta endpoint
+    Rosetta { code: i64, message: String, retriable: bool },
+    /// A rate limit of zero, negative or not finite requests per second
+    InvalidRateLimit(f64),
+    /// A contract, function, variable or map name that is not a valid Clarity name
+    InvalidName(String),
+}
+
+impl fmt::Display for ClientError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        match self {
+            ClientError::Transport(error) => f.write_str(&format!("Transport error: {error}")),
+            ClientError::Api { status, body: Some(body), .. } => match (&body.reason, &body.message) {
+                (Some(reason), _) => f.write_str(&format!("API error {status}: {} ({reason})", body.error)),
+                (None, Some(message)) => f.write_str(&format!("API error {status}: {} ({message})", body.error)),
+                (None, None) => f.write_str(&format!("API error {status}: {}", body.error)),
+            },
+            ClientError::Api { status, body: None, raw } => f.write_str(&format!("API error {status}: {raw}")),
+            ClientError::Decode(error) => f.write_str(&format!("Cannot decode response: {error}")),
+            ClientError::Clarity(error) => f.write_str(&format!("Invalid Clarity value: {error}")),
+            ClientError::CallFailed(cause) => f.write_str(&format!("Read-only call failed: {cause}")),
+            ClientError::Transaction(error) => f.write_str(&format!("Invalid transaction: {error}")),
+            ClientError::Rejected { reason, .. } => f.write_str(&format!("Transaction rejected: {reason}")),
+            ClientError::Dropped { txid, status } => f.write_str(&format!("Transaction {txid} dropped: {status:?}")),
+            ClientError::Timeout(txid) => f.write_str(&format!("Transaction {txid} not confirmed in time")),
+            ClientError::Subscription(error) => f.write_str(&format!("Subscription refused: {error}")),
+            ClientError::Rosetta { code, message, .. } => f.write_str(&format!("Rosetta error {code}: {message}")),
+            ClientError::InvalidRateLimit(v) => f.write_str(&format!("Invalid rate limit of {v} requests per second")),
+            ClientError::InvalidName(name) => f.write_str(&format!("Invalid Clarity name: {name}")),
+        }
+    }
+}
+
+impl std::error::Error for ClientError {}
+
+impl From<ClarityError> for ClientError {
+    fn from(value: ClarityError) -> Self {
+        ClientError::Clarity(value)
+    }
+}
+
+impl From<TransactionError> for ClientError {
+    fn from(value: TransactionError) -> Self {
+        ClientError::Transaction(value)
+    }
+}
+
+/// Turns an HTTP response into the result of a getter: `Ok(None)` when the resource does not
+/// exist (404), `Ok(Some(_))` on success, and a [`ClientError::Api`] for any other status.
+///
+/// Usage:
+/// ```rust
+/// use stacks_rs::client::error::parse_response;
+/// let missing: Option<u64> = parse_response(404, r#"{"error": "could not find transaction by ID"}"#).unwrap();
+/// assert_eq!(missing, None);
+/// let found: Option<u64> = parse_response(200, "42").unwrap();
+/// assert_eq!(found, Some(42));
+/// ```
+pub fn parse_response<T: DeserializeOwned>(status: u16, body: &str) -> Result<Option<T>, ClientError> {
+    match status {
+        200..=299 => serde_json::from_str(body).map(Some).map_err(ClientError::Decode),
+        HTTP_NOT_FOUND => Ok(None),
+        _ => Err(ClientError::Api { status, body: serde_json::from_str(body).ok().map(Box::new), raw: String::from(body) }),
+    }
+}
+
+/// Like [`parse_response`], for resources that always exist: a 404 is an error too.
+pub fn parse_required<T: DeserializeOwned>(status: u16, body: &str) -> Result<T, ClientError> {
+    parse_response(status, body)?.ok_or_else(|| ClientError::Api { status, body: serde_json::from_str(body).ok().map(Box::new), raw: String::from(body) })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_node_rejection() {
+        let body = r#"{"error":"transaction rejected","reason":"ConflictingNonceInMempool","reason_data":null,"txid":"0x01"}"#;
+        let error = parse_response::<String>(400, body).unwrap_err();
+        match &error {
+            ClientError::Api { status: 400, body: Some(body), .. } => {
+                assert_eq!(body.reason.as_deref(), Some("ConflictingNonceInMempool"));
+                assert_eq!(body.txid.as_deref(), Some("0x01"));
+            }
+            _ => panic!("unexpected error {error:?}"),
+        }
+        assert_eq!(error.to_string(), "API error 400: transaction rejected (ConflictingNonceInMempool)");
+    }
+
+    #[test]
+    fn test_parse_untyped_error() {
+        let error = parse_response::<String>(502, "Bad Gateway").unwrap_err();
+        assert!(matches!(error, ClientError::Api { status: 502, body: None, .. }));
+        assert!(matches!(parse_response::<u64>(200, "\"not a number\""), Err(ClientError::Decode(_))));
+    }
+}
@@ -0,0 +1,124 @@
+//! Fee estimation by the node, `/v2/fees/transaction`.
+
+use serde::{Deserialize, Serialize};
+
+use crate::transaction::codec::Codec;
+use crate::transaction::fee::FeeEstimates;
+use crate::transaction::payload::Payload;
+use crate::transaction::stacks_transaction::StacksTransaction;
+use crate::transaction::TransactionError;
+
+use super::endpoints;
+use super::error::ClientError;
+use super::http::HttpTransport;
+
+pub const FEES_TRANSACTION_PATH: &str = "/v2/fees/transaction";
+
+/// Body of `POST /v2/fees/transaction`.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct FeeEstimateRequest {
+    /// Hex encoded payload, without `0x`
+    pub transaction_payload: String,
+    /// Length of the signed transaction, in bytes
+    pub estimated_len: Option<u64>,
+}
+
+impl FeeEstimateRequest {
+    pub fn new(payload: &Payload, estimated_len: Option<u64>) -> Result<Self, TransactionError> {
+        let mut bytes = vec![];
+        payload.encode(&mut bytes)?;
+        Ok(FeeEstimateRequest { transaction_payload: hex::encode(bytes), estimated_len })
+    }
+
+    /// The request for the payload and the [`StacksTransaction::estimated_len`] of `transaction`.
+    pub fn for_transaction(transaction: &StacksTransaction) -> Result<Self, TransactionError> {
+        Self::new(&transaction.payload, Some(transaction.estimated_len()? as u64))
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Deserialize)]
+pub struct FeeEstimation {
+    pub fee_rate: f64,
+    /// In micro-STX
+    pub fee: u64,
+}
+
+/// Response of `POST /v2/fees/transaction`.
+#[derive(Clone, Debug, PartialEq, Deserialize)]
+pub struct FeeEstimateResponse {
+    pub estimated_cost_scalar: u64,
+    #[serde(default)]
+    pub cost_scalar_change_by_byte: Option<f64>,
+    /// Low, medium and high estimations, in that order
+    pub estimations: Vec<FeeEstimation>,
+}
+
+impl FeeEstimateResponse {
+    /// The three fees, `None` unless the node returned exactly three estimations.
+    pub fn estimates(&self) -> Option<FeeEstimates> {
+        match self.estimations.as_slice() {
+            [low, medium, high] => Some(FeeEstimates { low: low.fee, medium: medium.fee, high: high.fee }),
+            _ => None,
+        }
+    }
+}
+
+/// Asks the node for the low, medium and high fees of `request`.
+pub fn estimate_transaction_fees(transport: &impl HttpTransport, request: &FeeEstimateRequest) -> Result<FeeEstimates, ClientError> {
+    endpoints::fee_estimates(endpoints::estimate_fees(request)?.send(transport)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use crate::address::principal::Principal;
+    use crate::transaction::payload::TokenTransferPayload;
+
+    use super::super::mock::MockTransport;
+    use super::*;
+
+    const RESPONSE: &str = r#"{
+        "estimated_cost": {"write_length": 0, "write_count": 0, "read_length": 0, "read_count": 0, "runtime": 0},
+        "estimated_cost_scalar": 14,
+        "cost_scalar_change_by_byte": 0.00476837158203125,
+        "estimations": [
+            {"fee_rate": 0.0, "fee": 180},
+            {"fee_rate": 0.02, "fee": 200},
+            {"fee_rate": 9.5, "fee": 3000}
+        ]
+    }"#;
+
+    fn recorder(status: u16, body: &str) -> MockTransport {
+        MockTransport::new().with(FEES_TRANSACTION_PATH, status, body)
+    }
+
+    fn request() -> FeeEstimateRequest {
+        let recipient = Principal::from_str("SP3FGQ8Z7JY9BWYZ5WM53E0M9NK7WHJF0691NZ159").unwrap();
+        let payload = Payload::TokenTransfer(TokenTransferPayload::new(recipient, 1000, "").unwrap());
+        FeeEstimateRequest::new(&payload, Some(180)).unwrap()
+    }
+
+    #[test]
+    fn test_estimate_fees() {
+        let transport = recorder(200, RESPONSE);
+        let estimates = estimate_transaction_fees(&transport, &request()).unwrap();
+        assert_eq!(estimates, FeeEstimates { low: 180, medium: 200, high: 3000 });
+
+        let requests = transport.requests.borrow();
+        assert_eq!(requests[0].0, FEES_TRANSACTION_PATH);
+        let body: serde_json::Value = serde_json::from_slice(&requests[0].1).unwrap();
+        assert_eq!(body["estimated_len"], 180);
+        assert!(body["transaction_payload"].as_str().unwrap().starts_with("000516"));
+    }
+
+    #[test]
+    fn test_estimate_fees_errors() {
+        let body = r#"{"error": "Estimator RPC endpoint failed to estimate fees for tx: NoEstimateAvailable"}"#;
+        let error = estimate_transaction_fees(&recorder(400, body), &request()).unwrap_err();
+        assert!(matches!(error, ClientError::Api { status: 400, body: Some(_), .. }));
+        assert!(matches!(estimate_transaction_fees(&recorder(404, ""), &request()), Err(ClientError::Api { status: 404, .. })));
+        let short = r#"{"estimated_cost_scalar": 14, "estimations": [{"fee_rate": 0.0, "fee": 180}]}"#;
+        assert!(matches!(estimate_transaction_fees(&recorder(200, short), &request()), Err(ClientError::Api { status: 200, .. })));
+    }
+}
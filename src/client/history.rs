@@ -0,0 +1,210 @@
+//! The transaction history of an address, filtered by type and time, with the payloads of the
+//! API decoded into [`Payload`]s.
+
+use std::fmt;
+use std::str::FromStr;
+
+use serde::de::Error;
+
+use crate::address::principal::Principal;
+use crate::address::stacks_address::StacksAddress;
+use crate::clarity::value::ClarityValue;
+use crate::transaction::envelope::PayloadSummary;
+use crate::transaction::payload::{ContractCallPayload, Payload, PayloadType, SmartContractPayload, TokenTransferPayload};
+use crate::transaction::MEMO_LENGTH;
+
+use super::error::ClientError;
+use super::types::{AddressTransaction, ApiTransaction, TxType};
+
+/// A payload the API serves that does not decode.
+fn invalid(error: impl fmt::Display) -> ClientError {
+    ClientError::Decode(serde_json::Error::custom(format!("invalid payload: {error}")))
+}
+
+/// `address.name` of a contract id.
+fn contract_id(contract_id: &str) -> Result<(StacksAddress, String), ClientError> {
+    let (address, name) = contract_id.split_once('.').ok_or_else(|| invalid(contract_id))?;
+    Ok((StacksAddress::from_str(address).map_err(invalid)?, name.to_string()))
+}
+
+impl ApiTransaction {
+    /// The payload of a transfer, contract call or deploy, decoded from the JSON of the API
+    /// (contract call arguments from their hex); `None` for the other types.
+    pub fn payload(&self) -> Result<Option<Payload>, ClientError> {
+        let payload = match (self.tx_type, &self.token_transfer, &self.contract_call, &self.smart_contract) {
+            (TxType::TokenTransfer, Some(transfer), _, _) => {
+                let recipient = Principal::from_str(&transfer.recipient_address).map_err(invalid)?;
+                let memo_bytes = hex::decode(transfer.memo.trim_start_matches("0x")).map_err(invalid)?;
+                let mut memo = [0u8; MEMO_LENGTH];
+                memo[..memo_bytes.len().min(MEMO_LENGTH)].copy_from_slice(&memo_bytes[..memo_bytes.len().min(MEMO_LENGTH)]);
+                Payload::TokenTransfer(TokenTransferPayload { recipient, amount: transfer.amount, memo })
+            }
+            (TxType::ContractCall, _, Some(call), _) => {
+                let (contract_address, contract_name) = contract_id(&call.contract_id)?;
+                let function_args = call.function_args.iter().map(|arg| ClarityValue::from_hex(&arg.hex)).collect::<Result<_, _>>()?;
+                Payload::ContractCall(ContractCallPayload { contract_address, contract_name, function_name: call.function_name.clone(), function_args })
+            }
+            (TxType::SmartContract, _, _, Some(contract)) => {
+                let (_, contract_name) = contract_id(&contract.contract_id)?;
+                Payload::SmartContract(SmartContractPayload { contract_name, code_body: contract.source_code.clone(), clarity_version: None })
+            }
+            _ => return Ok(None),
+        };
+        Ok(Some(payload))
+    }
+
+    /// A readable summary of the payload, as [`TransactionEnvelope`](crate::transaction::envelope::TransactionEnvelope) shows it.
+    pub fn summary(&self) -> Result<PayloadSummary, ClientError> {
+        if let Some(payload) = self.payload()? {
+            return Ok(PayloadSummary::from(&payload));
+        }
+        let payload_type = match self.tx_type {
+            TxType::Coinbase => PayloadType::Coinbase,
+            TxType::PoisonMicroblock => PayloadType::PoisonMicroblock,
+            TxType::TenureChange => PayloadType::TenureChange,
+            _ => return Err(invalid(format!("{:?} without its payload", self.tx_type))),
+        };
+        Ok(PayloadSummary::Other { payload_type: payload_type.value() })
+    }
+}
+
+/// Which transactions of a history to keep.
+///
+/// Times are unix times of the blocks; pending transactions count as happening now.
+///
+/// Usage:
+/// ```rust
+/// use stacks_rs::client::history::TransactionFilter;
+/// use stacks_rs::client::types::TxType;
+/// let filter = TransactionFilter::new().with_type(TxType::TokenTransfer).since(1_700_000_000);
+/// assert!(filter.includes(TxType::TokenTransfer, Some(1_700_000_100)));
+/// assert!(!filter.includes(TxType::ContractCall, Some(1_700_000_100)));
+/// assert!(!filter.includes(TxType::TokenTransfer, Some(1_600_000_000)));
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct TransactionFilter {
+    /// Any type when empty
+    pub types: Vec<TxType>,
+    pub start_time: Option<u64>,
+    pub end_time: Option<u64>,
+}
+
+impl TransactionFilter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_type(mut self, tx_type: TxType) -> Self {
+        self.types.push(tx_type);
+        self
+    }
+
+    /// Transactions from `start_time` on, included.
+    pub fn since(mut self, start_time: u64) -> Self {
+        self.start_time = Some(start_time);
+        self
+    }
+
+    /// Transactions up to `end_time`, included.
+    pub fn until(mut self, end_time: u64) -> Self {
+        self.end_time = Some(end_time);
+        self
+    }
+
+    /// Whether a transaction of `tx_type` mined at `time`, pending if none, is kept.
+    pub fn includes(&self, tx_type: TxType, time: Option<u64>) -> bool {
+        let in_range = match time {
+            Some(time) => self.start_time.is_none_or(|start| start <= time) && self.end_time.is_none_or(|end| time <= end),
+            None => self.end_time.is_none(),
+        };
+        in_range && (self.types.is_empty() || self.types.contains(&tx_type))
+    }
+
+    pub fn matches(&self, transaction: &ApiTransaction) -> bool {
+        self.includes(transaction.tx_type, transaction.block_time)
+    }
+
+    /// Whether `transaction` is older than the range, and so are the ones listed after it,
+    /// newest first.
+    pub(crate) fn is_past(&self, transaction: &ApiTransaction) -> bool {
+        self.start_time.zip(transaction.block_time).is_some_and(|(start, time)| time < start)
+    }
+}
+
+/// Filters a history listed newest first, stopping at the first transaction older than the range.
+pub(crate) fn filter_history(
+    history: impl Iterator<Item = Result<AddressTransaction, ClientError>>,
+    filter: TransactionFilter,
+) -> impl Iterator<Item = Result<AddressTransaction, ClientError>> {
+    let past = filter.clone();
+    history
+        .take_while(move |transaction| transaction.as_ref().map_or(true, |transaction| !past.is_past(&transaction.tx)))
+        .filter(move |transaction| transaction.as_ref().map_or(true, |transaction| filter.matches(&transaction.tx)))
+}
+
+/// The stream counterpart of [`filter_history`].
+#[cfg(feature = "tokio")]
+pub(crate) fn filter_history_stream(
+    history: impl futures_util::Stream<Item = Result<AddressTransaction, ClientError>>,
+    filter: TransactionFilter,
+) -> impl futures_util::Stream<Item = Result<AddressTransaction, ClientError>> {
+    use futures_util::{future, StreamExt};
+
+    let past = filter.clone();
+    history
+        .take_while(move |transaction| future::ready(transaction.as_ref().map_or(true, |transaction| !past.is_past(&transaction.tx))))
+        .filter(move |transaction| future::ready(transaction.as_ref().map_or(true, |transaction| filter.matches(&transaction.tx))))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::types::TxStatus;
+    use super::*;
+
+    fn transaction(tx_type: &str, payload: &str) -> ApiTransaction {
+        let json = format!(
+            r#"{{"tx_id": "0x01", "tx_status": "success", "tx_type": "{tx_type}", "nonce": 1, "fee_rate": "200",
+            "sender_address": "SP2J6ZY48GV1EZ5V2V5RB9MP66SW86PYKKNRV9EJ7", "sponsored": false, "block_time": 1700000000{payload}}}"#
+        );
+        serde_json::from_str(&json).unwrap()
+    }
+
+    #[test]
+    fn test_payloads() {
+        let transfer = transaction(
+            "token_transfer",
+            r#", "token_transfer": {"recipient_address": "SP000000000000000000002Q6VF78", "amount": "1000", "memo": "0x68656c6c6f0000000000000000000000000000000000000000000000000000000000"}"#,
+        );
+        let summary = PayloadSummary::TokenTransfer { recipient: String::from("SP000000000000000000002Q6VF78"), amount: 1000, memo: String::from("hello") };
+        assert_eq!(transfer.summary().unwrap(), summary);
+
+        let arg = ClarityValue::UInt(100);
+        let call = transaction(
+            "contract_call",
+            &format!(r#", "contract_call": {{"contract_id": "SP3K8BC0PPEVCV7NZ6QSRWPQ2JE9E5B6N3PA0KBR9.pool", "function_name": "join", "function_signature": "", "function_args": [{{"hex": "{}", "repr": "u100", "name": "amount", "type": "uint"}}]}}"#, arg.to_hex().unwrap()),
+        );
+        match call.payload().unwrap() {
+            Some(Payload::ContractCall(payload)) => assert_eq!((payload.contract_name.as_str(), payload.function_args), ("pool", vec![arg])),
+            payload => panic!("unexpected payload {payload:?}"),
+        }
+
+        let deploy = transaction("smart_contract", r#", "smart_contract": {"clarity_version": 2, "contract_id": "SP3K8BC0PPEVCV7NZ6QSRWPQ2JE9E5B6N3PA0KBR9.hello", "source_code": "(define-constant a u1)"}"#);
+        assert_eq!(deploy.summary().unwrap(), PayloadSummary::SmartContract { contract_name: String::from("hello"), code_length: 22 });
+        assert_eq!(transaction("tenure_change", "").summary().unwrap(), PayloadSummary::Other { payload_type: 0x07 });
+        assert_eq!(transaction("tenure_change", "").tx_status, TxStatus::Success);
+        assert!(matches!(transaction("contract_call", "").summary(), Err(ClientError::Decode(_))));
+    }
+
+    #[test]
+    fn test_filter_history() {
+        let entry = |tx_type: &str, time: u64| {
+            let mut tx = transaction(tx_type, "");
+            tx.block_time = Some(time);
+            Ok(AddressTransaction { tx, stx_sent: 0, stx_received: 0 })
+        };
+        let history = vec![entry("token_transfer", 500), entry("contract_call", 400), entry("token_transfer", 300), entry("token_transfer", 100), Err(ClientError::Transport(String::new()))];
+        let filter = TransactionFilter::new().with_type(TxType::TokenTransfer).since(200).until(450);
+        let kept: Vec<_> = filter_history(history.into_iter(), filter).map(|entry| entry.unwrap().tx.block_time).collect();
+        assert_eq!(kept, vec![Some(300)]);
+    }
+}
@@ -0,0 +1,163 @@
+//! The HTTP layer under the clients, kept to what the node endpoints need.
+
+use std::future::Future;
+use std::time::Duration;
+
+use serde::Serialize;
+
+use super::error::ClientError;
+
+/// Status and body of an HTTP response.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct HttpResponse {
+    pub status: u16,
+    pub body: String,
+    /// The `Retry-After` header, in seconds, e.g. of a 429
+    pub retry_after: Option<Duration>,
+}
+
+impl HttpResponse {
+    pub fn new(status: u16, body: &str) -> Self {
+        HttpResponse { status, body: body.to_string(), retry_after: None }
+    }
+}
+
+/// A `Retry-After` header in seconds; HTTP dates are not supported.
+pub fn parse_retry_after(value: &str) -> Option<Duration> {
+    value.trim().parse().ok().map(Duration::from_secs)
+}
+
+/// A request to a node, `path` being relative to its base URL.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum HttpRequest {
+    Get { path: String },
+    Post { path: String, content_type: &'static str, body: Vec<u8> },
+}
+
+impl HttpRequest {
+    pub fn get(path: &str) -> Self {
+        HttpRequest::Get { path: path.to_string() }
+    }
+
+    pub fn post(path: &str, content_type: &'static str, body: Vec<u8>) -> Self {
+        HttpRequest::Post { path: path.to_string(), content_type, body }
+    }
+
+    pub fn post_json(path: &str, body: &impl Serialize) -> Result<Self, ClientError> {
+        let body = serde_json::to_vec(body).map_err(ClientError::Decode)?;
+        Ok(Self::post(path, "application/json", body))
+    }
+
+    pub fn send(&self, transport: &impl HttpTransport) -> Result<HttpResponse, ClientError> {
+        match self {
+            HttpRequest::Get { path } => transport.get(path),
+            HttpRequest::Post { path, content_type, body } => transport.post(path, content_type, body),
+        }
+    }
+
+    pub async fn send_async(&self, transport: &impl AsyncHttpTransport) -> Result<HttpResponse, ClientError> {
+        match self {
+            HttpRequest::Get { path } => transport.get(path).await,
+            HttpRequest::Post { path, content_type, body } => transport.post(path, content_type, body).await,
+        }
+    }
+}
+
+/// Sends requests to a node, `path` being relative to its base URL (e.g. `/v2/info`).
+///
+/// Errors are [`ClientError::Transport`] only: error statuses are valid responses.
+pub trait HttpTransport {
+    fn get(&self, path: &str) -> Result<HttpResponse, ClientError>;
+
+    fn post(&self, path: &str, content_type: &str, body: &[u8]) -> Result<HttpResponse, ClientError>;
+}
+
+/// The async counterpart of [`HttpTransport`].
+pub trait AsyncHttpTransport {
+    fn get(&self, path: &str) -> impl Future<Output = Result<HttpResponse, ClientError>> + Send;
+
+    fn post(&self, path: &str, content_type: &str, body: &[u8]) -> impl Future<Output = Result<HttpResponse, ClientError>> + Send;
+}
+
+/// Blocking transport over `ureq`, with the TLS roots of `webpki-roots`.
+#[cfg(feature = "blocking")]
+#[derive(Clone, Debug)]
+pub struct UreqTransport {
+    agent: ureq::Agent,
+    base_url: String,
+}
+
+#[cfg(feature = "blocking")]
+impl UreqTransport {
+    pub fn new(base_url: &str) -> Self {
+        Self::with_agent(base_url, ureq::Agent::new())
+    }
+
+    /// Sends through `agent`, e.g. one with a proxy or timeouts.
+    pub fn with_agent(base_url: &str, agent: ureq::Agent) -> Self {
+        UreqTransport { agent, base_url: base_url.trim_end_matches('/').to_string() }
+    }
+
+    fn response(result: Result<ureq::Response, ureq::Error>) -> Result<HttpResponse, ClientError> {
+        let response = match result {
+            Ok(response) => response,
+            Err(ureq::Error::Status(_, response)) => response,
+            Err(ureq::Error::Transport(error)) => return Err(ClientError::Transport(error.to_string())),
+        };
+        let status = response.status();
+        let retry_after = response.header("Retry-After").and_then(parse_retry_after);
+        let body = response.into_string().map_err(|e| ClientError::Transport(e.to_string()))?;
+        Ok(HttpResponse { status, body, retry_after })
+    }
+}
+
+#[cfg(feature = "blocking")]
+impl HttpTransport for UreqTransport {
+    fn get(&self, path: &str) -> Result<HttpResponse, ClientError> {
+        Self::response(self.agent.get(&format!("{}{path}", self.base_url)).call())
+    }
+
+    fn post(&self, path: &str, content_type: &str, body: &[u8]) -> Result<HttpResponse, ClientError> {
+        Self::response(self.agent.post(&format!("{}{path}", self.base_url)).set("Content-Type", content_type).send_bytes(body))
+    }
+}
+
+/// Async transport over `reqwest`, for tokio runtimes.
+#[cfg(feature = "tokio")]
+#[derive(Clone, Debug)]
+pub struct ReqwestTransport {
+    client: reqwest::Client,
+    base_url: String,
+}
+
+#[cfg(feature = "tokio")]
+impl ReqwestTransport {
+    pub fn new(base_url: &str) -> Self {
+        Self::with_client(base_url, reqwest::Client::new())
+    }
+
+    /// Sends through `client`, e.g. one with a proxy or timeouts.
+    pub fn with_client(base_url: &str, client: reqwest::Client) -> Self {
+        ReqwestTransport { client, base_url: base_url.trim_end_matches('/').to_string() }
+    }
+
+    async fn response(request: reqwest::RequestBuilder) -> Result<HttpResponse, ClientError> {
+        let response = request.send().await.map_err(|e| ClientError::Transport(e.to_string()))?;
+        let status = response.status().as_u16();
+        let retry_after = response.headers().get("Retry-After").and_then(|value| value.to_str().ok()).and_then(parse_retry_after);
+        let body = response.text().await.map_err(|e| ClientError::Transport(e.to_string()))?;
+        Ok(HttpResponse { status, body, retry_after })
+    }
+}
+
+#[cfg(feature = "tokio")]
+impl AsyncHttpTransport for ReqwestTransport {
+    fn get(&self, path: &str) -> impl Future<Output = Result<HttpResponse, ClientError>> + Send {
+        Self::response(self.client.get(format!("{}{path}", self.base_url)))
+    }
+
+    fn post(&self, path: &str, content_type: &str, body: &[u8]) -> impl Future<Output = Result<HttpResponse, ClientError>> + Send {
+        let request = self.client.post(format!("{}{path}", self.base_url)).header("Content-Type", content_type).body(body.to_vec());
+        Self::response(request)
+    }
+}
@@ -0,0 +1,257 @@
+//! Client of the Hiro Token Metadata service (`/metadata/v1`): names, symbols, decimals and
+//! SIP-016 metadata of fungible and non-fungible tokens.
+
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Deserializer};
+use serde_json::Value;
+
+use crate::address::principal::Principal;
+#[cfg(feature = "blocking")]
+use crate::network::StacksNetwork;
+use crate::transaction::asset::MAX_DECIMALS;
+
+use super::cache::{NoCache, ResponseCache, METADATA_TTL};
+use super::error::{parse_response, ClientError};
+use super::http::HttpTransport;
+#[cfg(feature = "blocking")]
+use super::http::UreqTransport;
+
+pub const METADATA_PATH: &str = "/metadata/v1";
+
+/// SIP-016 metadata of a token, as served at its token URI.
+#[derive(Clone, Debug, PartialEq, Deserialize)]
+pub struct Sip016Metadata {
+    /// Version of the SIP, 16
+    pub sip: u32,
+    #[serde(default)]
+    pub name: Option<String>,
+    #[serde(default)]
+    pub description: Option<String>,
+    /// URI of the image, as published
+    #[serde(default)]
+    pub image: Option<String>,
+    /// Copy of the image on the CDN of the service
+    #[serde(default)]
+    pub cached_image: Option<String>,
+    #[serde(default)]
+    pub cached_thumbnail_image: Option<String>,
+    #[serde(default)]
+    pub attributes: Vec<Sip016Attribute>,
+    #[serde(default)]
+    pub properties: Option<Value>,
+}
+
+#[derive(Clone, Debug, PartialEq, Deserialize)]
+pub struct Sip016Attribute {
+    pub trait_type: String,
+    pub value: Value,
+    #[serde(default)]
+    pub display_type: Option<String>,
+}
+
+/// Metadata of a fungible token, `/metadata/v1/ft/{principal}`.
+#[derive(Clone, Debug, PartialEq, Deserialize)]
+pub struct FtMetadata {
+    #[serde(default)]
+    pub name: Option<String>,
+    #[serde(default)]
+    pub symbol: Option<String>,
+    /// At most [`MAX_DECIMALS`], larger values are rejected when deserializing
+    #[serde(default, deserialize_with = "optional_decimals")]
+    pub decimals: Option<u32>,
+    /// In the smallest unit
+    #[serde(default, deserialize_with = "optional_decimal")]
+    pub total_supply: Option<u128>,
+    #[serde(default)]
+    pub token_uri: Option<String>,
+    #[serde(default)]
+    pub description: Option<String>,
+    #[serde(default)]
+    pub image_uri: Option<String>,
+    #[serde(default)]
+    pub image_thumbnail_uri: Option<String>,
+    /// e.g. `SP3K8BC0PPEVCV7NZ6QSRWPQ2JE9E5B6N3PA0KBR9.token-alex::alex`
+    #[serde(default)]
+    pub asset_identifier: Option<String>,
+    #[serde(default)]
+    pub metadata: Option<Sip016Metadata>,
+}
+
+impl FtMetadata {
+    /// `amount` in the smallest unit as a decimal number of tokens, e.g. `1.5` for 1500000 of
+    /// a token with 6 decimals.
+    ///
+    /// Usage:
+    /// ```rust
+    /// use stacks_rs::client::metadata::FtMetadata;
+    /// let metadata: FtMetadata = serde_json::from_str(r#"{"symbol": "ALEX", "decimals": 8}"#).unwrap();
+    /// assert_eq!(metadata.format_amount(150_000_000), "1.5");
+    /// assert_eq!(metadata.format_amount(42), "0.00000042");
+    /// ```
+    pub fn format_amount(&self, amount: u128) -> String {
+        let decimals = self.decimals.unwrap_or(0) as usize;
+        if decimals == 0 {
+            return amount.to_string();
+        }
+        let digits = format!("{amount:0>width$}", width = decimals + 1);
+        let (units, fraction) = digits.split_at(digits.len() - decimals);
+        match fraction.trim_end_matches('0') {
+            "" => units.to_string(),
+            fraction => format!("{units}.{fraction}"),
+        }
+    }
+}
+
+/// Metadata of a non-fungible token, `/metadata/v1/nft/{principal}/{token_id}`.
+#[derive(Clone, Debug, PartialEq, Deserialize)]
+pub struct NftMetadata {
+    #[serde(default)]
+    pub token_uri: Option<String>,
+    #[serde(default)]
+    pub metadata: Option<Sip016Metadata>,
+}
+
+fn optional_decimal<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<u128>, D::Error> {
+    use serde::de::Error;
+    Option::<String>::deserialize(deserializer)?.map(|amount| amount.parse().map_err(D::Error::custom)).transpose()
+}
+
+fn optional_decimals<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<u32>, D::Error> {
+    use serde::de::Error;
+    match Option::<u32>::deserialize(deserializer)? {
+        Some(decimals) if decimals > MAX_DECIMALS as u32 => Err(D::Error::custom(format!("{decimals} decimals, at most {MAX_DECIMALS}"))),
+        decimals => Ok(decimals),
+    }
+}
+
+/// Blocking client of the Token Metadata service of a network, at its API URL.
+///
+/// Usage:
+/// ```rust,no_run
+/// # #[cfg(feature = "blocking")]
+/// # {
+/// use std::str::FromStr;
+/// use stacks_rs::address::principal::Principal;
+/// use stacks_rs::client::cache::MemoryCache;
+/// use stacks_rs::client::metadata::TokenMetadataClient;
+/// use stacks_rs::network::StacksNetwork;
+/// let client = TokenMetadataClient::new(StacksNetwork::mainnet()).with_cache(MemoryCache::new());
+/// let token = Principal::from_str("SP3K8BC0PPEVCV7NZ6QSRWPQ2JE9E5B6N3PA0KBR9.token-alex").unwrap();
+/// let metadata = client.get_ft_metadata(&token).unwrap().unwrap();
+/// println!("{} {}", metadata.format_amount(150_000_000), metadata.symbol.unwrap_or_default());
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct TokenMetadataClient<T, C = NoCache> {
+    transport: T,
+    cache: C,
+}
+
+#[cfg(feature = "blocking")]
+impl TokenMetadataClient<UreqTransport> {
+    pub fn new(network: StacksNetwork) -> Self {
+        Self::with_transport(UreqTransport::new(&network.api_url))
+    }
+}
+
+impl<T: HttpTransport> TokenMetadataClient<T> {
+    /// A client sending through `transport`, already pointing at the API.
+    pub fn with_transport(transport: T) -> Self {
+        TokenMetadataClient { transport, cache: NoCache }
+    }
+}
+
+impl<T: HttpTransport, C: ResponseCache> TokenMetadataClient<T, C> {
+    /// Keeps the metadata fetched in `cache`, for [`METADATA_TTL`].
+    pub fn with_cache<D: ResponseCache>(self, cache: D) -> TokenMetadataClient<T, D> {
+        TokenMetadataClient { transport: self.transport, cache }
+    }
+
+    pub fn transport(&self) -> &T {
+        &self.transport
+    }
+
+    pub fn cache(&self) -> &C {
+        &self.cache
+    }
+
+    fn get<R: DeserializeOwned>(&self, path: &str) -> Result<Option<R>, ClientError> {
+        if let Some(body) = self.cache.get(path) {
+            return serde_json::from_str(&body).map(Some).map_err(ClientError::Decode);
+        }
+        let response = self.transport.get(path)?;
+        let metadata = parse_response(response.status, &response.body)?;
+        if metadata.is_some() {
+            self.cache.insert(path, &response.body, Some(METADATA_TTL));
+        }
+        Ok(metadata)
+    }
+
+    /// Metadata of the fungible token of the contract `token`, `None` if the service does not
+    /// know it.
+    pub fn get_ft_metadata(&self, token: &Principal) -> Result<Option<FtMetadata>, ClientError> {
+        self.get(&format!("{METADATA_PATH}/ft/{token}"))
+    }
+
+    /// Metadata of the token `token_id` of the non-fungible token of the contract `token`.
+    pub fn get_nft_metadata(&self, token: &Principal, token_id: u128) -> Result<Option<NftMetadata>, ClientError> {
+        self.get(&format!("{METADATA_PATH}/nft/{token}/{token_id}"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use super::super::cache::MemoryCache;
+    use super::super::mock::MockTransport;
+    use super::*;
+
+    const TOKEN: &str = "SP3K8BC0PPEVCV7NZ6QSRWPQ2JE9E5B6N3PA0KBR9.token-alex";
+
+    #[test]
+    fn test_ft_metadata() {
+        let body = r#"{"name": "ALEX Token", "symbol": "ALEX", "decimals": 8, "total_supply": "60000000000000000", "token_uri": "https://cdn.alexlab.co/metadata/token-alex.json",
+            "description": "ALEX", "image_uri": "https://assets.hiro.so/alex.png", "tx_id": "0x01", "sender_address": "SP3K8BC0PPEVCV7NZ6QSRWPQ2JE9E5B6N3PA0KBR9",
+            "asset_identifier": "SP3K8BC0PPEVCV7NZ6QSRWPQ2JE9E5B6N3PA0KBR9.token-alex::alex",
+            "metadata": {"sip": 16, "name": "ALEX Token", "image": "https://cdn.alexlab.co/alex.png", "cached_image": "https://assets.hiro.so/alex.png"}}"#;
+        let transport = MockTransport::new().with(&format!("/metadata/v1/ft/{TOKEN}"), 200, body).with("/metadata/v1/ft/SP000000000000000000002Q6VF78.none", 404, r#"{"error": "Token not found"}"#);
+        let client = TokenMetadataClient::with_transport(transport).with_cache(MemoryCache::new());
+        let token = Principal::from_str(TOKEN).unwrap();
+        let metadata = client.get_ft_metadata(&token).unwrap().unwrap();
+        assert_eq!((metadata.symbol.as_deref(), metadata.decimals, metadata.total_supply), (Some("ALEX"), Some(8), Some(60_000_000_000_000_000)));
+        assert_eq!(metadata.metadata.as_ref().unwrap().sip, 16);
+        assert_eq!(metadata.format_amount(100_000_000), "1");
+
+        assert_eq!(client.get_ft_metadata(&token).unwrap().unwrap(), metadata);
+        assert_eq!(client.transport().requests.borrow().len(), 1);
+        let unknown = Principal::from_str("SP000000000000000000002Q6VF78.none").unwrap();
+        assert_eq!(client.get_ft_metadata(&unknown).unwrap(), None);
+        assert_eq!(client.cache().get("/metadata/v1/ft/SP000000000000000000002Q6VF78.none"), None);
+    }
+
+    #[test]
+    fn test_ft_decimals() {
+        let metadata: FtMetadata = serde_json::from_str(r#"{"decimals": 38}"#).unwrap();
+        assert_eq!(metadata.format_amount(u128::MAX), "3.40282366920938463463374607431768211455");
+        assert!(serde_json::from_str::<FtMetadata>(r#"{"decimals": 39}"#).is_err());
+        assert!(serde_json::from_str::<FtMetadata>(r#"{"decimals": 4294967295}"#).is_err());
+        assert_eq!(serde_json::from_str::<FtMetadata>(r#"{"decimals": null}"#).unwrap().decimals, None);
+    }
+
+    #[test]
+    fn test_nft_metadata() {
+        let body = r#"{"token_uri": "ipfs://QmTWRu/1.json", "metadata": {"sip": 16, "name": "Guild #1", "image": "ipfs://QmXy/1.png",
+            "attributes": [{"trait_type": "Background", "value": "Blue"}, {"trait_type": "Level", "value": 3, "display_type": "number"}], "properties": {"collection": "Guild"}}}"#;
+        let transport = MockTransport::new().with("/metadata/v1/nft/SP2X0TZ59D5SZ8ACQ6YMCHHNR2ZN51Z32E2CJ173.guild/1", 200, body);
+        let client = TokenMetadataClient::with_transport(transport);
+        let token = Principal::from_str("SP2X0TZ59D5SZ8ACQ6YMCHHNR2ZN51Z32E2CJ173.guild").unwrap();
+        let metadata = client.get_nft_metadata(&token, 1).unwrap().unwrap().metadata.unwrap();
+        assert_eq!(metadata.name.as_deref(), Some("Guild #1"));
+        assert_eq!(metadata.attributes[1].value, Value::from(3));
+        assert_eq!(metadata.attributes[1].display_type.as_deref(), Some("number"));
+
+        client.get_nft_metadata(&token, 1).unwrap();
+        assert_eq!(client.transport().requests.borrow().len(), 2);
+    }
+}
@@ -0,0 +1,72 @@
+//! A transport answering canned responses by path, for the client tests.
+
+use std::cell::RefCell;
+use std::collections::{HashMap, VecDeque};
+use std::future::{self, Future};
+use std::pin::pin;
+use std::task::{Context, Poll, Waker};
+
+use super::error::ClientError;
+use super::http::{AsyncHttpTransport, HttpResponse, HttpTransport};
+
+#[derive(Default)]
+pub(crate) struct MockTransport {
+    /// Responses by path, answered in order, the last one for good
+    responses: RefCell<HashMap<String, VecDeque<HttpResponse>>>,
+    /// Path and body of the requests sent so far
+    pub(crate) requests: RefCell<Vec<(String, Vec<u8>)>>,
+}
+
+impl MockTransport {
+    pub(crate) fn new() -> Self {
+        Self::default()
+    }
+
+    pub(crate) fn with(self, path: &str, status: u16, body: &str) -> Self {
+        self.with_response(path, HttpResponse::new(status, body))
+    }
+
+    pub(crate) fn with_response(mut self, path: &str, response: HttpResponse) -> Self {
+        self.responses.get_mut().entry(path.to_string()).or_default().push_back(response);
+        self
+    }
+
+    fn respond(&self, path: &str, body: &[u8]) -> Result<HttpResponse, ClientError> {
+        self.requests.borrow_mut().push((path.to_string(), body.to_vec()));
+        let mut responses = self.responses.borrow_mut();
+        let queue = responses.get_mut(path).ok_or_else(|| ClientError::Transport(format!("no response for {path}")))?;
+        match queue.len() {
+            1 => Ok(queue[0].clone()),
+            _ => Ok(queue.pop_front().expect("queued responses")),
+        }
+    }
+}
+
+impl HttpTransport for MockTransport {
+    fn get(&self, path: &str) -> Result<HttpResponse, ClientError> {
+        self.respond(path, &[])
+    }
+
+    fn post(&self, path: &str, _content_type: &str, body: &[u8]) -> Result<HttpResponse, ClientError> {
+        self.respond(path, body)
+    }
+}
+
+impl AsyncHttpTransport for MockTransport {
+    fn get(&self, path: &str) -> impl Future<Output = Result<HttpResponse, ClientError>> + Send {
+        future::ready(self.respond(path, &[]))
+    }
+
+    fn post(&self, path: &str, _content_type: &str, body: &[u8]) -> impl Future<Output = Result<HttpResponse, ClientError>> + Send {
+        future::ready(self.respond(path, body))
+    }
+}
+
+/// Runs a future of the async clients over a [`MockTransport`], which is always ready.
+pub(crate) fn block_on<F: Future>(future: F) -> F::Output {
+    let mut future = pin!(future);
+    match future.as_mut().poll(&mut Context::from_waker(Waker::noop())) {
+        Poll::Ready(output) => output,
+        Poll::Pending => panic!("the mock transport never waits"),
+    }
+}
@@ -0,0 +1,19 @@
+pub mod api;
+pub mod async_api;
+pub mod async_rpc;
+pub mod cache;
+pub mod confirmation;
+mod endpoints;
+pub mod error;
+pub mod fees;
+pub mod history;
+pub mod http;
+pub mod metadata;
+#[cfg(test)]
+pub(crate) mod mock;
+pub mod pagination;
+pub mod retry;
+pub mod rosetta;
+pub mod rpc;
+pub mod types;
+pub mod websocket;
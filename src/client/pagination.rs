@@ -0,0 +1,193 @@
+//! Lists of the API read page after page, following `limit` and `offset`.
+
+use std::collections::VecDeque;
+
+use super::error::ClientError;
+use super::types::Page;
+
+/// Items per page by default, the largest most list endpoints of the API allow.
+pub const DEFAULT_PAGE_SIZE: u32 = 50;
+
+/// An iterator over the items of a list of the API, fetching a page whenever it runs out.
+///
+/// A page that cannot be fetched is yielded as an error, which ends the iteration.
+///
+/// Usage:
+/// ```rust
+/// use stacks_rs::client::pagination::Paginated;
+/// use stacks_rs::client::types::Page;
+/// let numbers: Vec<u32> = (0..7).collect();
+/// let pages = Paginated::new(|limit, offset| {
+///     let results = numbers.iter().skip(offset as usize).take(limit as usize).copied().collect();
+///     Ok(Page { limit, offset, total: 7, results })
+/// });
+/// let all = pages.page_size(3).collect::<Result<Vec<_>, _>>().unwrap();
+/// assert_eq!(all, numbers);
+/// ```
+pub struct Paginated<T, F> {
+    fetch: F,
+    page_size: u32,
+    offset: u32,
+    buffer: VecDeque<T>,
+    done: bool,
+}
+
+impl<T, F: FnMut(u32, u32) -> Result<Page<T>, ClientError>> Paginated<T, F> {
+    /// Fetches the pages with `fetch(limit, offset)`.
+    pub fn new(fetch: F) -> Self {
+        Paginated { fetch, page_size: DEFAULT_PAGE_SIZE, offset: 0, buffer: VecDeque::new(), done: false }
+    }
+
+    /// [`DEFAULT_PAGE_SIZE`] by default.
+    pub fn page_size(mut self, page_size: u32) -> Self {
+        self.page_size = page_size.max(1);
+        self
+    }
+
+    /// Starts from the item `offset` instead of the first one.
+    pub fn offset(mut self, offset: u32) -> Self {
+        self.offset = offset;
+        self
+    }
+}
+
+impl<T, F: FnMut(u32, u32) -> Result<Page<T>, ClientError>> Iterator for Paginated<T, F> {
+    type Item = Result<T, ClientError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.buffer.is_empty() && !self.done {
+            match (self.fetch)(self.page_size, self.offset) {
+                Ok(page) => {
+                    self.offset += page.results.len() as u32;
+                    self.done = page.results.is_empty() || self.offset >= page.total;
+                    self.buffer.extend(page.results);
+                }
+                Err(error) => {
+                    self.done = true;
+                    return Some(Err(error));
+                }
+            }
+        }
+        self.buffer.pop_front().map(Ok)
+    }
+}
+
+/// A stream over the items of a list of the API, fetching up to `concurrency` pages at once
+/// once the first page told its length.
+///
+/// Items come in order; a page that cannot be fetched is yielded as an error, which ends the
+/// stream.
+#[cfg(feature = "tokio")]
+pub fn paginate_stream<'a, T: 'a, F, Fut>(fetch: F, page_size: u32, concurrency: usize) -> impl futures_util::Stream<Item = Result<T, ClientError>> + 'a
+where
+    F: Fn(u32, u32) -> Fut + 'a,
+    Fut: std::future::Future<Output = Result<Page<T>, ClientError>> + 'a,
+{
+    use futures_util::stream::{self, FuturesOrdered, StreamExt};
+
+    struct State<T, F, Fut: std::future::Future> {
+        fetch: F,
+        next_offset: u32,
+        total: Option<u32>,
+        in_flight: FuturesOrdered<Fut>,
+        buffer: VecDeque<T>,
+        done: bool,
+    }
+
+    let page_size = page_size.max(1);
+    let state = State { fetch, next_offset: 0, total: None, in_flight: FuturesOrdered::new(), buffer: VecDeque::new(), done: false };
+    stream::unfold(state, move |mut state| async move {
+        loop {
+            if let Some(item) = state.buffer.pop_front() {
+                return Some((Ok(item), state));
+            }
+            if state.done {
+                return None;
+            }
+            // the first page alone, the next ones up to `concurrency` at once
+            let limit = state.total.map_or(1, |_| concurrency.max(1));
+            while state.in_flight.len() < limit && state.total.is_none_or(|total| state.next_offset < total) {
+                state.in_flight.push_back((state.fetch)(page_size, state.next_offset));
+                state.next_offset += page_size;
+            }
+            match state.in_flight.next().await {
+                Some(Ok(page)) => {
+                    state.total = Some(page.total);
+                    state.done = page.results.is_empty() || (page.offset + page.results.len() as u32 >= page.total && state.in_flight.is_empty());
+                    state.buffer.extend(page.results);
+                }
+                Some(Err(error)) => {
+                    state.done = true;
+                    return Some((Err(error), state));
+                }
+                None => state.done = true,
+            }
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn page(items: &[u32], limit: u32, offset: u32) -> Result<Page<u32>, ClientError> {
+        let results = items.iter().skip(offset as usize).take(limit as usize).copied().collect();
+        Ok(Page { limit, offset, total: items.len() as u32, results })
+    }
+
+    #[test]
+    fn test_paginated() {
+        let items: Vec<u32> = (0..5).collect();
+        let mut offsets = vec![];
+        let all: Vec<u32> = Paginated::new(|limit, offset| {
+            offsets.push(offset);
+            page(&items, limit, offset)
+        })
+        .page_size(2)
+        .collect::<Result<_, _>>()
+        .unwrap();
+        assert_eq!((all, offsets), (items.clone(), vec![0, 2, 4]));
+
+        let from_3: Vec<u32> = Paginated::new(|limit, offset| page(&items, limit, offset)).offset(3).map(Result::unwrap).collect();
+        assert_eq!(from_3, vec![3, 4]);
+        assert_eq!(Paginated::new(|limit, offset| page(&[], limit, offset)).count(), 0);
+
+        let failing = Paginated::new(|limit, offset| match offset {
+            0 => page(&items, limit, offset),
+            _ => Err(ClientError::Transport(String::from("reset"))),
+        });
+        let results: Vec<_> = failing.page_size(2).collect();
+        assert_eq!(results.len(), 3);
+        assert!(matches!(results[2], Err(ClientError::Transport(_))));
+    }
+
+    #[cfg(feature = "tokio")]
+    #[test]
+    fn test_paginate_stream() {
+        use std::cell::RefCell;
+
+        use futures_util::StreamExt;
+
+        use super::super::mock::block_on;
+
+        let items: Vec<u32> = (0..7).collect();
+        let offsets = RefCell::new(vec![]);
+        let fetch = |limit, offset| {
+            offsets.borrow_mut().push(offset);
+            std::future::ready(page(&items, limit, offset))
+        };
+        let all: Vec<u32> = block_on(paginate_stream(fetch, 2, 3).map(Result::unwrap).collect());
+        assert_eq!(all, items);
+        assert_eq!(*offsets.borrow(), vec![0, 2, 4, 6]);
+
+        let failing = |limit, offset| {
+            std::future::ready(match offset {
+                4 => Err(ClientError::Transport(String::from("reset"))),
+                _ => page(&items, limit, offset),
+            })
+        };
+        let results: Vec<_> = block_on(paginate_stream(failing, 2, 2).collect());
+        assert_eq!(results.len(), 5);
+        assert!(matches!(results[4], Err(ClientError::Transport(_))));
+    }
+}
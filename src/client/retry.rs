@@ -0,0 +1,291 @@
+//! Retries with exponential backoff and client-side rate limiting, as a transport wrapping
+//! another one.
+//!
+//! Public endpoints such as Hiro's rate limit their clients: [`RetryTransport`] spaces the
+//! requests out and waits as long as a 429 `Retry-After` asks before sending again.
+
+use std::sync::Mutex;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use rand::Rng;
+
+use super::error::ClientError;
+use super::http::{HttpResponse, HttpTransport};
+
+/// HTTP statuses worth sending a request again for.
+const RETRYABLE_STATUSES: [u16; 4] = [429, 502, 503, 504];
+
+/// When and how long to wait before sending a request again.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RetryPolicy {
+    /// Retries after the first attempt
+    pub max_retries: u32,
+    /// Backoff of the first retry, doubled for each next one
+    pub initial_backoff: Duration,
+    pub max_backoff: Duration,
+    /// Randomizes each backoff between half and all of it, so clients do not retry in step
+    pub jitter: bool,
+    /// Retries POSTs too, which are not idempotent: a broadcast may go through twice
+    pub retry_posts: bool,
+    /// Longest `Retry-After` waited for; the response is returned as is past it
+    pub max_retry_after: Duration,
+}
+
+impl Default for RetryPolicy {
+    /// 3 retries of GETs, from 500 ms up to 10 s, with jitter.
+    fn default() -> Self {
+        RetryPolicy {
+            max_retries: 3,
+            initial_backoff: Duration::from_millis(500),
+            max_backoff: Duration::from_secs(10),
+            jitter: true,
+            retry_posts: false,
+            max_retry_after: Duration::from_secs(60),
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// A policy never retrying, e.g. to only rate limit.
+    pub fn none() -> Self {
+        RetryPolicy { max_retries: 0, ..Self::default() }
+    }
+
+    /// Backoff before the retry `attempt`, from 0, without jitter.
+    ///
+    /// Usage:
+    /// ```rust
+    /// use std::time::Duration;
+    /// use stacks_rs::client::retry::RetryPolicy;
+    /// let policy = RetryPolicy::default();
+    /// assert_eq!(policy.backoff(0), Duration::from_millis(500));
+    /// assert_eq!(policy.backoff(2), Duration::from_secs(2));
+    /// assert_eq!(policy.backoff(8), Duration::from_secs(10));
+    /// ```
+    pub fn backoff(&self, attempt: u32) -> Duration {
+        self.initial_backoff.saturating_mul(2u32.saturating_pow(attempt)).min(self.max_backoff)
+    }
+
+    /// How long to wait before retrying after `result`, `None` to give up.
+    fn retry_delay(&self, attempt: u32, post: bool, result: &Result<HttpResponse, ClientError>) -> Option<Duration> {
+        if attempt >= self.max_retries || (post && !self.retry_posts) {
+            return None;
+        }
+        let retry_after = match result {
+            Err(ClientError::Transport(_)) => None,
+            Ok(response) if RETRYABLE_STATUSES.contains(&response.status) => response.retry_after,
+            _ => return None,
+        };
+        match retry_after {
+            Some(retry_after) if retry_after > self.max_retry_after => None,
+            Some(retry_after) => Some(retry_after),
+            None if self.jitter => Some(self.backoff(attempt).mul_f64(rand::thread_rng().gen_range(0.5..=1.0))),
+            None => Some(self.backoff(attempt)),
+        }
+    }
+}
+
+/// Client-side rate limit: a bucket of `burst` requests refilled at `requests_per_second`.
+#[derive(Debug)]
+pub struct RateLimiter {
+    requests_per_second: f64,
+    burst: f64,
+    /// Available requests and when they were counted
+    bucket: Mutex<(f64, Instant)>,
+}
+
+impl RateLimiter {
+    /// Fails with [`ClientError::InvalidRateLimit`] unless `requests_per_second` is finite and positive.
+    pub fn new(requests_per_second: f64, burst: u32) -> Result<Self, ClientError> {
+        if !(requests_per_second.is_finite() && requests_per_second > 0.0) {
+            return Err(ClientError::InvalidRateLimit(requests_per_second));
+        }
+        let burst = f64::from(burst.max(1));
+        Ok(RateLimiter { requests_per_second, burst, bucket: Mutex::new((burst, Instant::now())) })
+    }
+
+    /// Takes a request from the bucket, returning how long to wait before sending it.
+    pub fn acquire(&self) -> Duration {
+        let mut bucket = self.bucket.lock().expect("rate limiter lock");
+        let now = Instant::now();
+        let available = (bucket.0 + now.duration_since(bucket.1).as_secs_f64() * self.requests_per_second).min(self.burst) - 1.0;
+        *bucket = (available, now);
+        match available < 0.0 {
+            true => Duration::from_secs_f64(-available / self.requests_per_second),
+            false => Duration::ZERO,
+        }
+    }
+}
+
+/// A transport retrying the requests of `inner` following a [`RetryPolicy`], optionally rate
+/// limited.
+///
+/// Usage:
+/// ```rust,no_run
+/// # #[cfg(feature = "blocking")]
+/// # {
+/// use stacks_rs::client::http::UreqTransport;
+/// use stacks_rs::client::retry::RetryTransport;
+/// use stacks_rs::client::rpc::StacksRpcClient;
+/// use stacks_rs::network::StacksNetwork;
+/// let network = StacksNetwork::mainnet();
+/// let transport = RetryTransport::new(UreqTransport::new(&network.node_url)).with_rate_limit(5.0, 10).unwrap();
+/// let client = StacksRpcClient::with_transport(network, transport);
+/// let fee_rate = client.get_transfer_fee_rate().unwrap();
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct RetryTransport<T> {
+    inner: T,
+    policy: RetryPolicy,
+    rate_limiter: Option<RateLimiter>,
+}
+
+impl<T> RetryTransport<T> {
+    /// Retries with the default [`RetryPolicy`], without rate limit.
+    pub fn new(inner: T) -> Self {
+        RetryTransport { inner, policy: RetryPolicy::default(), rate_limiter: None }
+    }
+
+    pub fn with_policy(mut self, policy: RetryPolicy) -> Self {
+        self.policy = policy;
+        self
+    }
+
+    /// Sends at most `requests_per_second`, with bursts of `burst` requests; see [`RateLimiter::new`].
+    pub fn with_rate_limit(mut self, requests_per_second: f64, burst: u32) -> Result<Self, ClientError> {
+        self.rate_limiter = Some(RateLimiter::new(requests_per_second, burst)?);
+        Ok(self)
+    }
+
+    pub fn inner(&self) -> &T {
+        &self.inner
+    }
+
+    fn rate_limit_delay(&self) -> Duration {
+        self.rate_limiter.as_ref().map_or(Duration::ZERO, RateLimiter::acquire)
+    }
+}
+
+impl<T: HttpTransport> RetryTransport<T> {
+    fn send(&self, post: bool, send: impl Fn() -> Result<HttpResponse, ClientError>) -> Result<HttpResponse, ClientError> {
+        let mut attempt = 0;
+        loop {
+            thread::sleep(self.rate_limit_delay());
+            let result = send();
+            match self.policy.retry_delay(attempt, post, &result) {
+                Some(delay) => thread::sleep(delay),
+                None => return result,
+            }
+            attempt += 1;
+        }
+    }
+}
+
+impl<T: HttpTransport> HttpTransport for RetryTransport<T> {
+    fn get(&self, path: &str) -> Result<HttpResponse, ClientError> {
+        self.send(false, || self.inner.get(path))
+    }
+
+    fn post(&self, path: &str, content_type: &str, body: &[u8]) -> Result<HttpResponse, ClientError> {
+        self.send(true, || self.inner.post(path, content_type, body))
+    }
+}
+
+#[cfg(feature = "tokio")]
+impl<T: super::http::AsyncHttpTransport + Sync> RetryTransport<T> {
+    async fn send_async<F: std::future::Future<Output = Result<HttpResponse, ClientError>>>(&self, post: bool, send: impl Fn() -> F) -> Result<HttpResponse, ClientError> {
+        let mut attempt = 0;
+        loop {
+            tokio::time::sleep(self.rate_limit_delay()).await;
+            let result = send().await;
+            match self.policy.retry_delay(attempt, post, &result) {
+                Some(delay) => tokio::time::sleep(delay).await,
+                None => return result,
+            }
+            attempt += 1;
+        }
+    }
+}
+
+#[cfg(feature = "tokio")]
+impl<T: super::http::AsyncHttpTransport + Sync> super::http::AsyncHttpTransport for RetryTransport<T> {
+    fn get(&self, path: &str) -> impl std::future::Future<Output = Result<HttpResponse, ClientError>> + Send {
+        self.send_async(false, move || self.inner.get(path))
+    }
+
+    fn post(&self, path: &str, content_type: &str, body: &[u8]) -> impl std::future::Future<Output = Result<HttpResponse, ClientError>> + Send {
+        self.send_async(true, move || self.inner.post(path, content_type, body))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::mock::MockTransport;
+    use super::*;
+
+    fn fast() -> RetryPolicy {
+        RetryPolicy { initial_backoff: Duration::from_millis(1), max_backoff: Duration::from_millis(2), ..RetryPolicy::default() }
+    }
+
+    #[test]
+    fn test_retries() {
+        let transport = MockTransport::new().with("/v2/info", 503, "").with("/v2/info", 502, "").with("/v2/info", 200, "{}");
+        let retrying = RetryTransport::new(transport).with_policy(fast());
+        assert_eq!(retrying.get("/v2/info").unwrap().status, 200);
+        assert_eq!(retrying.inner().requests.borrow().len(), 3);
+
+        let unreachable = RetryTransport::new(MockTransport::new()).with_policy(fast());
+        assert!(matches!(unreachable.get("/v2/info"), Err(ClientError::Transport(_))));
+        assert_eq!(unreachable.inner().requests.borrow().len(), 4);
+
+        let not_found = RetryTransport::new(MockTransport::new().with("/v2/info", 404, "")).with_policy(fast());
+        assert_eq!(not_found.get("/v2/info").unwrap().status, 404);
+        assert_eq!(not_found.inner().requests.borrow().len(), 1);
+    }
+
+    #[test]
+    fn test_posts() {
+        let transport = || MockTransport::new().with("/v2/transactions", 503, "").with("/v2/transactions", 200, "\"0x01\"");
+        let default = RetryTransport::new(transport()).with_policy(fast());
+        assert_eq!(default.post("/v2/transactions", "application/octet-stream", &[0]).unwrap().status, 503);
+        let retrying = RetryTransport::new(transport()).with_policy(RetryPolicy { retry_posts: true, ..fast() });
+        assert_eq!(retrying.post("/v2/transactions", "application/octet-stream", &[0]).unwrap().status, 200);
+    }
+
+    #[test]
+    fn test_retry_after() {
+        let limited = |seconds| HttpResponse { retry_after: Some(Duration::from_secs(seconds)), ..HttpResponse::new(429, "Too Many Requests") };
+        let policy = fast();
+        assert_eq!(policy.retry_delay(0, false, &Ok(limited(3))), Some(Duration::from_secs(3)));
+        assert_eq!(policy.retry_delay(0, false, &Ok(limited(120))), None);
+        assert_eq!(policy.retry_delay(3, false, &Ok(limited(3))), None);
+        let jittered = policy.retry_delay(1, false, &Ok(HttpResponse::new(429, ""))).unwrap();
+        assert!(jittered >= Duration::from_millis(1) && jittered <= Duration::from_millis(2), "{jittered:?}");
+        let steady = RetryPolicy { jitter: false, ..fast() };
+        assert_eq!(steady.retry_delay(1, false, &Err(ClientError::Transport(String::new()))), Some(Duration::from_millis(2)));
+        assert!(policy.retry_delay(0, false, &Ok(HttpResponse::new(500, ""))).is_none());
+
+        let transport = MockTransport::new().with_response("/v2/info", limited(0)).with("/v2/info", 200, "{}");
+        assert_eq!(RetryTransport::new(transport).get("/v2/info").unwrap().status, 200);
+        assert_eq!(super::super::http::parse_retry_after(" 120 "), Some(Duration::from_secs(120)));
+        assert_eq!(super::super::http::parse_retry_after("Wed, 21 Oct 2015 07:28:00 GMT"), None);
+    }
+
+    #[test]
+    fn test_rate_limiter() {
+        let limiter = RateLimiter::new(10.0, 2).unwrap();
+        assert_eq!(limiter.acquire(), Duration::ZERO);
+        assert_eq!(limiter.acquire(), Duration::ZERO);
+        let delay = limiter.acquire();
+        assert!(delay > Duration::from_millis(90) && delay <= Duration::from_millis(100), "{delay:?}");
+        let delay = limiter.acquire();
+        assert!(delay > Duration::from_millis(190) && delay <= Duration::from_millis(200), "{delay:?}");
+
+        for requests_per_second in [0.0, -1.0, f64::NAN, f64::INFINITY] {
+            assert!(matches!(RateLimiter::new(requests_per_second, 2), Err(ClientError::InvalidRateLimit(_))));
+        }
+        assert!(RetryTransport::new(MockTransport::new()).with_rate_limit(0.0, 1).is_err());
+    }
+}
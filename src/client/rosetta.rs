@@ -0,0 +1,482 @@
+//! Client of the Rosetta endpoints of the Stacks Blockchain API (`/rosetta/v1`): network status,
+//! balances and the construction flow.
+//!
+//! Signing stays local: [`SigningPayload::check`] recomputes the digest the API asks to sign from
+//! the unsigned transaction, and [`SigningPayload::sign`] signs it with this crate.
+
+use secp256k1::{PublicKey as Secp256k1PublicKey, SecretKey};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+
+use crate::crypto::context::secp256k1_context;
+use crate::crypto::signature::recoverable::sign_recoverable;
+use crate::network::{NetworkKind, StacksNetwork};
+use crate::transaction::auth::AuthType;
+use crate::transaction::signer::{initial_sighash, presign_sighash};
+use crate::transaction::stacks_transaction::StacksTransaction;
+use crate::transaction::TransactionError;
+
+use super::error::{parse_required, ClientError};
+use super::http::{HttpRequest, HttpResponse, HttpTransport};
+#[cfg(feature = "blocking")]
+use super::http::UreqTransport;
+
+pub const ROSETTA_PATH: &str = "/rosetta/v1";
+/// Type of the operations of a STX transfer.
+pub const TOKEN_TRANSFER: &str = "token_transfer";
+pub const SIGNATURE_TYPE: &str = "ecdsa_recovery";
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NetworkIdentifier {
+    pub blockchain: String,
+    pub network: String,
+}
+
+impl NetworkIdentifier {
+    /// `stacks` and `mainnet` or `testnet`.
+    pub fn for_network(network: &StacksNetwork) -> Self {
+        let name = match network.kind {
+            NetworkKind::Mainnet => "mainnet",
+            NetworkKind::Testnet | NetworkKind::Mocknet => "testnet",
+        };
+        NetworkIdentifier { blockchain: String::from("stacks"), network: name.to_string() }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BlockIdentifier {
+    pub index: u64,
+    pub hash: String,
+}
+
+/// A block by height, hash or both; the latest one when neither.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PartialBlockIdentifier {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub index: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub hash: Option<String>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AccountIdentifier {
+    pub address: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub metadata: Option<Value>,
+}
+
+impl AccountIdentifier {
+    pub fn new(address: &str) -> Self {
+        AccountIdentifier { address: address.to_string(), metadata: None }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Currency {
+    pub symbol: String,
+    pub decimals: u32,
+}
+
+impl Currency {
+    pub fn stx() -> Self {
+        Currency { symbol: String::from("STX"), decimals: 6 }
+    }
+}
+
+/// An amount in the smallest unit of `currency`, negative for a debit.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Amount {
+    pub value: String,
+    pub currency: Currency,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct OperationIdentifier {
+    pub index: u64,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Operation {
+    pub operation_identifier: OperationIdentifier,
+    pub r#type: String,
+    /// Set on operations of blocks, not on the ones to construct
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub status: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub account: Option<AccountIdentifier>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub amount: Option<Amount>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub metadata: Option<Value>,
+}
+
+/// The operations of a transfer of `amount` micro-STX: a debit of `sender` and a credit of
+/// `recipient`.
+pub fn token_transfer_operations(sender: &str, recipient: &str, amount: u64) -> Vec<Operation> {
+    let operation = |index, address: &str, value: String| Operation {
+        operation_identifier: OperationIdentifier { index },
+        r#type: TOKEN_TRANSFER.to_string(),
+        status: None,
+        account: Some(AccountIdentifier::new(address)),
+        amount: Some(Amount { value, currency: Currency::stx() }),
+        metadata: None,
+    };
+    vec![operation(0, sender, format!("-{amount}")), operation(1, recipient, amount.to_string())]
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RosettaPublicKey {
+    /// Compressed, hex encoded
+    pub hex_bytes: String,
+    pub curve_type: String,
+}
+
+impl From<&Secp256k1PublicKey> for RosettaPublicKey {
+    fn from(value: &Secp256k1PublicKey) -> Self {
+        RosettaPublicKey { hex_bytes: hex::encode(value.serialize()), curve_type: String::from("secp256k1") }
+    }
+}
+
+/// A digest to sign for a transaction under construction.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SigningPayload {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub account_identifier: Option<AccountIdentifier>,
+    /// The digest, hex encoded
+    pub hex_bytes: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub signature_type: Option<String>,
+}
+
+impl SigningPayload {
+    /// Checks the digest is the one the origin of `unsigned_transaction` signs, so a
+    /// compromised API cannot get something else signed.
+    pub fn check(&self, unsigned_transaction: &str) -> Result<(), ClientError> {
+        let transaction = StacksTransaction::from_hex(unsigned_transaction)?;
+        let origin = transaction.auth.origin();
+        let expected = presign_sighash(&initial_sighash(&transaction)?, AuthType::Standard, origin.fee(), origin.nonce());
+        match hex::encode(expected) == self.hex_bytes.trim_start_matches("0x") {
+            true => Ok(()),
+            false => Err(ClientError::Transaction(TransactionError::InvalidSignature)),
+        }
+    }
+
+    /// The recoverable signature of the digest by `key`, as `r || s || v`.
+    pub fn sign(&self, key: &SecretKey) -> Result<Signature, ClientError> {
+        let digest: [u8; 32] = hex::decode(self.hex_bytes.trim_start_matches("0x"))
+            .ok()
+            .and_then(|digest| digest.try_into().ok())
+            .ok_or(ClientError::Transaction(TransactionError::InvalidSignature))?;
+        let signature = sign_recoverable(&digest, key);
+        Ok(Signature {
+            signing_payload: self.clone(),
+            public_key: RosettaPublicKey::from(&key.public_key(secp256k1_context())),
+            signature_type: SIGNATURE_TYPE.to_string(),
+            hex_bytes: hex::encode(signature.to_rsv()),
+        })
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Signature {
+    pub signing_payload: SigningPayload,
+    pub public_key: RosettaPublicKey,
+    pub signature_type: String,
+    pub hex_bytes: String,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TransactionIdentifier {
+    pub hash: String,
+}
+
+/// `/network/status`.
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize)]
+pub struct NetworkStatus {
+    pub current_block_identifier: BlockIdentifier,
+    /// Unix time in milliseconds
+    pub current_block_timestamp: u64,
+    pub genesis_block_identifier: BlockIdentifier,
+    #[serde(default)]
+    pub sync_status: Option<Value>,
+    #[serde(default)]
+    pub peers: Vec<Value>,
+}
+
+/// `/account/balance`.
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize)]
+pub struct AccountBalance {
+    pub block_identifier: BlockIdentifier,
+    pub balances: Vec<Amount>,
+    #[serde(default)]
+    pub metadata: Option<Value>,
+}
+
+impl AccountBalance {
+    /// The nonce of the account, its `sequence_number`.
+    pub fn nonce(&self) -> Option<u64> {
+        self.metadata.as_ref()?.get("sequence_number")?.as_u64()
+    }
+}
+
+/// `/construction/metadata`.
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize)]
+pub struct ConstructionMetadata {
+    pub metadata: Value,
+    #[serde(default)]
+    pub suggested_fee: Vec<Amount>,
+}
+
+/// `/construction/payloads`.
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize)]
+pub struct ConstructionPayloads {
+    /// Hex encoded
+    pub unsigned_transaction: String,
+    pub payloads: Vec<SigningPayload>,
+}
+
+/// `/construction/parse`.
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize)]
+pub struct ParsedTransaction {
+    pub operations: Vec<Operation>,
+    #[serde(default)]
+    pub account_identifier_signers: Vec<AccountIdentifier>,
+}
+
+/// Error body of the Rosetta endpoints.
+#[derive(Deserialize)]
+struct RosettaError {
+    code: i64,
+    message: String,
+    #[serde(default)]
+    retriable: bool,
+}
+
+#[derive(Deserialize)]
+struct OptionsResponse {
+    options: Value,
+}
+
+#[derive(Deserialize)]
+struct DeriveResponse {
+    account_identifier: AccountIdentifier,
+}
+
+#[derive(Deserialize)]
+struct CombineResponse {
+    signed_transaction: String,
+}
+
+#[derive(Deserialize)]
+struct TransactionIdentifierResponse {
+    transaction_identifier: TransactionIdentifier,
+}
+
+/// Blocking client of the Rosetta endpoints of a network, sending to its API URL.
+///
+/// Usage:
+/// ```rust,no_run
+/// # #[cfg(feature = "blocking")]
+/// # {
+/// use secp256k1::SecretKey;
+/// use stacks_rs::client::rosetta::{token_transfer_operations, RosettaClient, RosettaPublicKey};
+/// use stacks_rs::crypto::context::secp256k1_context;
+/// use stacks_rs::network::StacksNetwork;
+/// let client = RosettaClient::new(StacksNetwork::testnet());
+/// let key = SecretKey::from_byte_array(&[1; 32]).unwrap();
+/// let public_key = RosettaPublicKey::from(&key.public_key(secp256k1_context()));
+/// let sender = client.construction_derive(&public_key).unwrap().address;
+/// let operations = token_transfer_operations(&sender, "ST000000000000000000002AMW42H", 1_000_000);
+/// let options = client.construction_preprocess(&operations, None).unwrap();
+/// let metadata = client.construction_metadata(&options, &[public_key.clone()]).unwrap();
+/// let unsigned = client.construction_payloads(&operations, &metadata.metadata, &[public_key]).unwrap();
+/// unsigned.payloads[0].check(&unsigned.unsigned_transaction).unwrap();
+/// let signature = unsigned.payloads[0].sign(&key).unwrap();
+/// let signed = client.construction_combine(&unsigned.unsigned_transaction, &[signature]).unwrap();
+/// let txid = client.construction_submit(&signed).unwrap();
+/// # }
+/// ```
+#[derive(Clone, Debug)]
+pub struct RosettaClient<T> {
+    network_identifier: NetworkIdentifier,
+    transport: T,
+}
+
+#[cfg(feature = "blocking")]
+impl RosettaClient<UreqTransport> {
+    pub fn new(network: StacksNetwork) -> Self {
+        let transport = UreqTransport::new(&network.api_url);
+        Self::with_transport(&network, transport)
+    }
+}
+
+impl<T: HttpTransport> RosettaClient<T> {
+    /// A client of `network` sending through `transport`, already pointing at its API.
+    pub fn with_transport(network: &StacksNetwork, transport: T) -> Self {
+        RosettaClient { network_identifier: NetworkIdentifier::for_network(network), transport }
+    }
+
+    pub fn network_identifier(&self) -> &NetworkIdentifier {
+        &self.network_identifier
+    }
+
+    pub fn transport(&self) -> &T {
+        &self.transport
+    }
+
+    /// Posts `body` with the network identifier to the endpoint `path`.
+    fn post<R: DeserializeOwned>(&self, path: &str, mut body: Value) -> Result<R, ClientError> {
+        body["network_identifier"] = json!(self.network_identifier);
+        let response = HttpRequest::post_json(&format!("{ROSETTA_PATH}{path}"), &body)?.send(&self.transport)?;
+        rosetta_response(response)
+    }
+
+    pub fn network_status(&self) -> Result<NetworkStatus, ClientError> {
+        self.post("/network/status", json!({}))
+    }
+
+    /// STX balance and nonce of `address`, at `block` or the latest block.
+    pub fn account_balance(&self, address: &str, block: Option<PartialBlockIdentifier>) -> Result<AccountBalance, ClientError> {
+        let mut body = json!({"account_identifier": AccountIdentifier::new(address)});
+        if let Some(block) = block {
+            body["block_identifier"] = json!(block);
+        }
+        self.post("/account/balance", body)
+    }
+
+    /// The account of `public_key` on the network.
+    pub fn construction_derive(&self, public_key: &RosettaPublicKey) -> Result<AccountIdentifier, ClientError> {
+        self.post::<DeriveResponse>("/construction/derive", json!({"public_key": public_key})).map(|response| response.account_identifier)
+    }
+
+    /// The options of [`Self::construction_metadata`] for `operations`.
+    pub fn construction_preprocess(&self, operations: &[Operation], metadata: Option<Value>) -> Result<Value, ClientError> {
+        let mut body = json!({"operations": operations});
+        if let Some(metadata) = metadata {
+            body["metadata"] = metadata;
+        }
+        self.post::<OptionsResponse>("/construction/preprocess", body).map(|response| response.options)
+    }
+
+    /// Nonce, fee and the other metadata the transaction needs.
+    pub fn construction_metadata(&self, options: &Value, public_keys: &[RosettaPublicKey]) -> Result<ConstructionMetadata, ClientError> {
+        self.post("/construction/metadata", json!({"options": options, "public_keys": public_keys}))
+    }
+
+    /// The unsigned transaction and the digests to sign.
+    pub fn construction_payloads(&self, operations: &[Operation], metadata: &Value, public_keys: &[RosettaPublicKey]) -> Result<ConstructionPayloads, ClientError> {
+        self.post("/construction/payloads", json!({"operations": operations, "metadata": metadata, "public_keys": public_keys}))
+    }
+
+    /// The signed transaction, hex encoded.
+    pub fn construction_combine(&self, unsigned_transaction: &str, signatures: &[Signature]) -> Result<String, ClientError> {
+        let body = json!({"unsigned_transaction": unsigned_transaction, "signatures": signatures});
+        self.post::<CombineResponse>("/construction/combine", body).map(|response| response.signed_transaction)
+    }
+
+    /// The operations of a transaction, to check it before signing or submitting.
+    pub fn construction_parse(&self, transaction: &str, signed: bool) -> Result<ParsedTransaction, ClientError> {
+        self.post("/construction/parse", json!({"signed": signed, "transaction": transaction}))
+    }
+
+    pub fn construction_hash(&self, signed_transaction: &str) -> Result<TransactionIdentifier, ClientError> {
+        self.post::<TransactionIdentifierResponse>("/construction/hash", json!({"signed_transaction": signed_transaction})).map(|response| response.transaction_identifier)
+    }
+
+    pub fn construction_submit(&self, signed_transaction: &str) -> Result<TransactionIdentifier, ClientError> {
+        self.post::<TransactionIdentifierResponse>("/construction/submit", json!({"signed_transaction": signed_transaction})).map(|response| response.transaction_identifier)
+    }
+}
+
+/// The body of a success, the Rosetta error of a failure.
+fn rosetta_response<R: DeserializeOwned>(response: HttpResponse) -> Result<R, ClientError> {
+    if !(200..300).contains(&response.status) {
+        if let Ok(error) = serde_json::from_str::<RosettaError>(&response.body) {
+            return Err(ClientError::Rosetta { code: error.code, message: error.message, retriable: error.retriable });
+        }
+    }
+    parse_required(response.status, &response.body)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use crate::address::principal::Principal;
+    use crate::crypto::signature::recoverable::{recover, RecoverableSignature};
+    use crate::transaction::builder::TransactionBatch;
+    use crate::transaction::fee::FeeStrategy;
+
+    use super::super::mock::MockTransport;
+    use super::*;
+
+    const SENDER: &str = "ST2J6ZY48GV1EZ5V2V5RB9MP66SW86PYKKNRV9EJ7";
+
+    fn client(transport: MockTransport) -> RosettaClient<MockTransport> {
+        RosettaClient::with_transport(&StacksNetwork::testnet(), transport)
+    }
+
+    #[test]
+    fn test_account_balance() {
+        let body = r#"{"block_identifier": {"index": 150000, "hash": "0xaa"}, "balances": [{"value": "5000000", "currency": {"symbol": "STX", "decimals": 6}}],
+            "metadata": {"sequence_number": 4}}"#;
+        let client = client(MockTransport::new().with("/rosetta/v1/account/balance", 200, body));
+        let balance = client.account_balance(SENDER, Some(PartialBlockIdentifier { index: Some(150000), hash: None })).unwrap();
+        assert_eq!((balance.balances[0].value.as_str(), balance.nonce()), ("5000000", Some(4)));
+
+        let request: Value = serde_json::from_slice(&client.transport().requests.borrow()[0].1).unwrap();
+        assert_eq!(request, json!({"network_identifier": {"blockchain": "stacks", "network": "testnet"}, "account_identifier": {"address": SENDER}, "block_identifier": {"index": 150000}}));
+    }
+
+    #[test]
+    fn test_errors() {
+        let body = r#"{"code": 618, "message": "Invalid Signature", "retriable": false}"#;
+        let client = client(MockTransport::new().with("/rosetta/v1/construction/combine", 500, body));
+        let error = client.construction_combine("00", &[]).unwrap_err();
+        assert!(matches!(error, ClientError::Rosetta { code: 618, retriable: false, .. }));
+        assert_eq!(error.to_string(), "Rosetta error 618: Invalid Signature");
+    }
+
+    #[test]
+    fn test_sign_payload() {
+        let key = SecretKey::from_byte_array(&[1; 32]).unwrap();
+        let public_key = key.public_key(secp256k1_context());
+        let recipient = Principal::from_str("ST000000000000000000002AMW42H").unwrap();
+        let unsigned = TransactionBatch::new().network(NetworkKind::Testnet).nonce(4).fee_strategy(FeeStrategy::Fixed(180));
+        let unsigned = unsigned.transfer(recipient, 1_000_000, "").unwrap().build(&public_key).unwrap().remove(0);
+        let origin = unsigned.auth.origin();
+        let digest = presign_sighash(&initial_sighash(&unsigned).unwrap(), AuthType::Standard, origin.fee(), origin.nonce());
+
+        let payload = SigningPayload { account_identifier: Some(AccountIdentifier::new(SENDER)), hex_bytes: hex::encode(digest), signature_type: Some(SIGNATURE_TYPE.to_string()) };
+        let unsigned_hex = unsigned.to_hex().unwrap();
+        payload.check(&unsigned_hex).unwrap();
+        let tampered = SigningPayload { hex_bytes: hex::encode([0u8; 32]), ..payload.clone() };
+        assert!(tampered.check(&unsigned_hex).is_err());
+
+        let signature = payload.sign(&key).unwrap();
+        assert_eq!(signature.public_key.hex_bytes, hex::encode(public_key.serialize()));
+        let rsv = RecoverableSignature::from_rsv(&hex::decode(&signature.hex_bytes).unwrap()).unwrap();
+        assert_eq!(recover(&digest, &rsv).unwrap(), public_key);
+        let json = serde_json::to_value(&signature).unwrap();
+        assert_eq!(json["signature_type"], "ecdsa_recovery");
+        assert_eq!(json["public_key"]["curve_type"], "secp256k1");
+    }
+
+    #[test]
+    fn test_construction() {
+        let transport = MockTransport::new()
+            .with("/rosetta/v1/construction/preprocess", 200, r#"{"options": {"sender_address": "ST2J6ZY48GV1EZ5V2V5RB9MP66SW86PYKKNRV9EJ7", "type": "token_transfer"}}"#)
+            .with("/rosetta/v1/construction/submit", 200, r#"{"transaction_identifier": {"hash": "0x01"}}"#);
+        let client = client(transport);
+        let operations = token_transfer_operations(SENDER, "ST000000000000000000002AMW42H", 1000);
+        let options = client.construction_preprocess(&operations, None).unwrap();
+        assert_eq!(options["type"], TOKEN_TRANSFER);
+        assert_eq!(client.construction_submit("0x00").unwrap().hash, "0x01");
+
+        let request: Value = serde_json::from_slice(&client.transport().requests.borrow()[0].1).unwrap();
+        assert_eq!(request["operations"][0]["amount"], json!({"value": "-1000", "currency": {"symbol": "STX", "decimals": 6}}));
+        assert_eq!(request["operations"][1]["account"]["address"], "ST000000000000000000002AMW42H");
+        assert_eq!(request["operations"][1]["operation_identifier"]["index"], 1);
+    }
+}
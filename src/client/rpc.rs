@@ -0,0 +1,441 @@
+//! Blocking client of the RPC endpoints (`/v2`) of a Stacks node.
+
+use crate::address::principal::Principal;
+use crate::address::stacks_address::StacksAddress;
+use crate::clarity::abi::ContractAbi;
+use crate::clarity::value::ClarityValue;
+use crate::network::StacksNetwork;
+use crate::transaction::fee::FeeEstimates;
+use crate::transaction::idempotency::IdempotencyGuard;
+use crate::transaction::stacks_transaction::StacksTransaction;
+
+use super::endpoints;
+use super::error::ClientError;
+use super::fees::FeeEstimateRequest;
+use super::http::HttpTransport;
+#[cfg(feature = "blocking")]
+use super::http::UreqTransport;
+use super::types::{AccountInfo, ContractSource, NetworkDetails, NodeInfo, PoxInfo, SortitionInfo, SortitionQuery, TenureInfo};
+
+/// Blocking client of a Stacks node.
+///
+/// With the `blocking` feature, [`StacksRpcClient::new`] talks to the node URL of the network;
+/// any other [`HttpTransport`] can be plugged in with [`StacksRpcClient::with_transport`].
+///
+/// Usage:
+/// ```rust,no_run
+/// # #[cfg(feature = "blocking")]
+/// # {
+/// use std::str::FromStr;
+/// use stacks_rs::address::stacks_address::StacksAddress;
+/// use stacks_rs::clarity::value::ClarityValue;
+/// use stacks_rs::client::rpc::StacksRpcClient;
+/// use stacks_rs::network::StacksNetwork;
+/// let client = StacksRpcClient::new(StacksNetwork::mainnet());
+/// let contract = StacksAddress::from_str("SP3K8BC0PPEVCV7NZ6QSRWPQ2JE9E5B6N3PA0KBR9").unwrap();
+/// let owner = ClarityValue::from_str("'SP2J6ZY48GV1EZ5V2V5RB9MP66SW86PYKKNRV9EJ7").unwrap();
+/// let balance = client
+///     .call_read_only(&contract, "token-alex", "get-balance", &[owner], "SP2J6ZY48GV1EZ5V2V5RB9MP66SW86PYKKNRV9EJ7")
+///     .unwrap();
+/// # }
+/// ```
+#[derive(Clone, Debug)]
+pub struct StacksRpcClient<T> {
+    network: StacksNetwork,
+    transport: T,
+}
+
+#[cfg(feature = "blocking")]
+impl StacksRpcClient<UreqTransport> {
+    pub fn new(network: StacksNetwork) -> Self {
+        let transport = UreqTransport::new(&network.node_url);
+        StacksRpcClient { network, transport }
+    }
+
+    /// A client of the node at `node_url`, on the network the node reports, see [`NodeInfo::network`].
+    pub fn connect(node_url: &str) -> Result<Self, ClientError> {
+        let transport = UreqTransport::new(node_url);
+        let info: NodeInfo = endpoints::required(endpoints::info().send(&transport)?)?;
+        Ok(StacksRpcClient { network: info.network(node_url), transport })
+    }
+}
+
+impl<T: HttpTransport> StacksRpcClient<T> {
+    /// A client of `network` sending through `transport`, already pointing at its node.
+    pub fn with_transport(network: StacksNetwork, transport: T) -> Self {
+        StacksRpcClient { network, transport }
+    }
+
+    pub fn network(&self) -> &StacksNetwork {
+        &self.network
+    }
+
+    pub fn transport(&self) -> &T {
+        &self.transport
+    }
+
+    /// Micro-STX per byte of a token transfer, `/v2/fees/transfer`.
+    pub fn get_transfer_fee_rate(&self) -> Result<u64, ClientError> {
+        endpoints::required(endpoints::transfer_fee_rate().send(&self.transport)?)
+    }
+
+    /// Low, medium and high fees of a transaction, `/v2/fees/transaction`.
+    pub fn estimate_fees(&self, request: &FeeEstimateRequest) -> Result<FeeEstimates, ClientError> {
+        endpoints::fee_estimates(endpoints::estimate_fees(request)?.send(&self.transport)?)
+    }
+
+    /// Result of calling the read-only function `function_name` as `sender`.
+    pub fn call_read_only(
+        &self,
+        contract_address: &StacksAddress,
+        contract_name: &str,
+        function_name: &str,
+        arguments: &[ClarityValue],
+        sender: &str,
+    ) -> Result<ClarityValue, ClientError> {
+        let request = endpoints::call_read_only(contract_address, contract_name, function_name, arguments, sender)?;
+        endpoints::read_only_result(request.send(&self.transport)?, contract_address, contract_name)
+    }
+
+    /// Sends a signed transaction to the node, `/v2/transactions`, returning its txid as hex.
+    ///
+    /// A rejection is a [`ClientError::Rejected`] with the reason given by the node. A
+    /// transaction whose metadata can expire is checked against the tip height first, and not
+    /// sent if stale.
+    pub fn broadcast_transaction(&self, transaction: &StacksTransaction) -> Result<String, ClientError> {
+        if transaction.metadata.is_some_and(|metadata| metadata.can_expire()) {
+            transaction.check_fresh(self.get_info()?.stacks_tip_height)?;
+        }
+        endpoints::broadcast_result(endpoints::broadcast(transaction)?.send(&self.transport)?)
+    }
+
+    /// Broadcasts `transaction` unless `guard` saw its idempotency key within its window, a
+    /// [`crate::transaction::TransactionError::Duplicate`] then; the key is forgotten if the broadcast fails, so
+    /// it can be retried.
+    pub fn broadcast_once(&self, transaction: &StacksTransaction, guard: &mut IdempotencyGuard) -> Result<String, ClientError> {
+        let key = transaction.idempotency_key()?;
+        guard.check_and_record(key)?;
+        self.broadcast_transaction(transaction).inspect_err(|_| guard.forget(&key))
+    }
+
+    /// Balances and nonce of `principal`, as builders' `fetch_nonce` read it; an account never
+    /// used is empty with nonce 0.
+    pub fn get_account(&self, principal: &Principal) -> Result<AccountInfo, ClientError> {
+        endpoints::required(endpoints::account(principal).send(&self.transport)?)
+    }
+
+    /// Clarity source of a contract, `None` if it does not exist.
+    pub fn get_contract_source(&self, contract_address: &StacksAddress, contract_name: &str) -> Result<Option<ContractSource>, ClientError> {
+        endpoints::optional(endpoints::contract_source(contract_address, contract_name)?.send(&self.transport)?)
+    }
+
+    /// Interface of a contract, `None` if it does not exist.
+    ///
+    /// Calls can be checked against it with [`ContractCallBuilder::abi`](crate::transaction::builder::ContractCallBuilder::abi).
+    pub fn get_contract_interface(&self, contract_address: &StacksAddress, contract_name: &str) -> Result<Option<ContractAbi>, ClientError> {
+        endpoints::optional(endpoints::contract_interface(contract_address, contract_name)?.send(&self.transport)?)
+    }
+
+    /// Value of the data variable `name`, `None` if the contract or variable does not exist.
+    pub fn get_data_var(&self, contract_address: &StacksAddress, contract_name: &str, name: &str) -> Result<Option<ClarityValue>, ClientError> {
+        endpoints::optional_value(endpoints::data_var(contract_address, contract_name, name)?.send(&self.transport)?)
+    }
+
+    /// Value of the constant `name`, `None` if the contract or constant does not exist.
+    pub fn get_constant(&self, contract_address: &StacksAddress, contract_name: &str, name: &str) -> Result<Option<ClarityValue>, ClientError> {
+        endpoints::optional_value(endpoints::constant(contract_address, contract_name, name)?.send(&self.transport)?)
+    }
+
+    /// Entry of `key` in the map `map_name`, as `(some value)` or `none` like `map-get?`.
+    pub fn get_map_entry(&self, contract_address: &StacksAddress, contract_name: &str, map_name: &str, key: &ClarityValue) -> Result<ClarityValue, ClientError> {
+        endpoints::value(endpoints::map_entry(contract_address, contract_name, map_name, key)?.send(&self.transport)?)
+    }
+
+    /// Version, network and tips of the node, `/v2/info`.
+    pub fn get_info(&self) -> Result<NodeInfo, ClientError> {
+        endpoints::required(endpoints::info().send(&self.transport)?)
+    }
+
+    /// The network of the node, its current epoch and PoX contract, from `/v2/info` and `/v2/pox`.
+    pub fn detect_network(&self) -> Result<NetworkDetails, ClientError> {
+        Ok(NetworkDetails::new(&self.network.node_url, &self.get_info()?, &self.get_pox_info()?))
+    }
+
+    /// The PoX contract and its current and next reward cycles, `/v2/pox`.
+    pub fn get_pox_info(&self) -> Result<PoxInfo, ClientError> {
+        endpoints::required(endpoints::pox_info().send(&self.transport)?)
+    }
+
+    /// The ongoing Nakamoto tenure and the tip of the node, `/v3/tenures/info`.
+    pub fn get_tenure_info(&self) -> Result<TenureInfo, ClientError> {
+        endpoints::required(endpoints::tenure_info().send(&self.transport)?)
+    }
+
+    /// Sortitions of `/v3/sortitions`: the latest, or the one of a Bitcoin block; empty if the
+    /// node does not know the block.
+    pub fn get_sortitions(&self, query: &SortitionQuery) -> Result<Vec<SortitionInfo>, ClientError> {
+        Ok(endpoints::optional(endpoints::sortitions(query).send(&self.transport)?)?.unwrap_or_default())
+    }
+
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+    use std::time::Duration;
+
+    use secp256k1::{PublicKey, SecretKey};
+
+    use crate::crypto::context::secp256k1_context;
+    use crate::network::NetworkKind;
+    use crate::stacking::pox_address::PoxContractVersion;
+    use crate::transaction::builder::{ContractCallBuilder, TransactionBatch};
+    use crate::transaction::metadata::{ExpiryPolicy, TransactionMetadata};
+    use crate::transaction::TransactionError;
+
+    use super::super::mock::MockTransport;
+    use super::super::types::RejectReason;
+    use super::*;
+
+    const CONTRACT: &str = "SP3K8BC0PPEVCV7NZ6QSRWPQ2JE9E5B6N3PA0KBR9";
+    const SENDER: &str = "SP2J6ZY48GV1EZ5V2V5RB9MP66SW86PYKKNRV9EJ7";
+
+    const POX: &str = r#"{"contract_id": "SP000000000000000000002Q6VF78.pox-4", "pox_activation_threshold_ustx": 67154543038280,
+        "first_burnchain_block_height": 666050, "current_burnchain_block_height": 867000, "prepare_phase_block_length": 100,
+        "reward_phase_block_length": 2000, "reward_slots": 4000, "rejection_fraction": null, "total_liquid_supply_ustx": 1492418123510300,
+        "current_cycle": {"id": 95, "min_threshold_ustx": 160000000000, "stacked_ustx": 300000000000000, "is_pox_active": true},
+        "next_cycle": {"id": 96, "min_threshold_ustx": 170000000000, "min_increment_ustx": 186552265438, "stacked_ustx": 280000000000000,
+            "prepare_phase_start_block_height": 867850, "blocks_until_prepare_phase": 850,
+            "reward_phase_start_block_height": 867950, "blocks_until_reward_phase": 950, "ustx_until_pox_rejection": null},
+        "min_amount_ustx": 170000000000, "prepare_cycle_length": 100, "reward_cycle_id": 95, "reward_cycle_length": 2100,
+        "rejection_votes_left_required": null, "next_reward_cycle_in": 950, "contract_versions": [],
+        "epochs": [{"epoch_id": "Epoch25", "start_height": 840360, "end_height": 867867, "network_epoch": 10},
+            {"epoch_id": "Epoch30", "start_height": 867867, "end_height": 18446744073709551615, "network_epoch": 11}]}"#;
+
+    /// `/v2/info` of a node at Stacks height 1000010.
+    fn info(network_id: u32, parent_network_id: u32) -> String {
+        format!(
+            r#"{{"peer_version": 4207599116, "pox_consensus": "0x01", "burn_block_height": 867000, "stable_pox_consensus": "0x02",
+            "stable_burn_block_height": 866993, "server_version": "stacks-node 3.0.0.0.0", "network_id": {network_id},
+            "parent_network_id": {parent_network_id}, "stacks_tip_height": 1000010, "stacks_tip": "0x03", "stacks_tip_consensus_hash": "0x04",
+            "unanchored_tip": null, "exit_at_block_height": null, "tenure_height": 170000}}"#
+        )
+    }
+
+    fn client(transport: MockTransport) -> StacksRpcClient<MockTransport> {
+        StacksRpcClient::with_transport(StacksNetwork::mainnet(), transport)
+    }
+
+    #[test]
+    fn test_call_read_only() {
+        let path = format!("/v2/contracts/call-read/{CONTRACT}/token/get-balance");
+        let ok = ClarityValue::ResponseOk(Box::new(ClarityValue::UInt(1000)));
+        let body = format!(r#"{{"okay": true, "result": "{}"}}"#, ok.to_hex().unwrap());
+        let client = client(MockTransport::new().with(&path, 200, &body));
+        let contract = StacksAddress::from_str(CONTRACT).unwrap();
+        let owner = ClarityValue::from_str(&format!("'{SENDER}")).unwrap();
+        assert_eq!(client.call_read_only(&contract, "token", "get-balance", std::slice::from_ref(&owner), SENDER).unwrap(), ok);
+
+        let requests = client.transport().requests.borrow();
+        let request: serde_json::Value = serde_json::from_slice(&requests[0].1).unwrap();
+        assert_eq!(request["sender"], SENDER);
+        assert_eq!(request["arguments"][0], owner.to_hex().unwrap());
+    }
+
+    #[test]
+    fn test_call_read_only_errors() {
+        let path = format!("/v2/contracts/call-read/{CONTRACT}/token/get-balance");
+        let contract = StacksAddress::from_str(CONTRACT).unwrap();
+        let failed = client(MockTransport::new().with(&path, 200, r#"{"okay": false, "cause": "Unchecked(NoSuchPublicFunction)"}"#));
+        let error = failed.call_read_only(&contract, "token", "get-balance", &[], SENDER).unwrap_err();
+        assert_eq!(error.to_string(), "Read-only call failed: Unchecked(NoSuchPublicFunction)");
+
+        let rejected = client(MockTransport::new().with(&path, 400, r#"{"error": "Invalid sender"}"#));
+        assert!(matches!(rejected.call_read_only(&contract, "token", "get-balance", &[], "SP0"), Err(ClientError::Api { status: 400, .. })));
+        let unreachable = client(MockTransport::new());
+        assert!(matches!(unreachable.call_read_only(&contract, "token", "get-balance", &[], SENDER), Err(ClientError::Transport(_))));
+    }
+
+    #[test]
+    fn test_contract_state() {
+        let contract = StacksAddress::from_str(CONTRACT).unwrap();
+        let transport = MockTransport::new()
+            .with(&format!("/v2/data_var/{CONTRACT}/token/supply?proof=0"), 200, r#"{"data": "0x0100000000000000000000000000000064"}"#)
+            .with(&format!("/v2/data_var/{CONTRACT}/token/missing?proof=0"), 404, "")
+            .with(&format!("/v2/constant_val/{CONTRACT}/token/decimals"), 200, r#"{"data": "0x0100000000000000000000000000000006"}"#)
+            .with(&format!("/v2/map_entry/{CONTRACT}/token/balances?proof=0"), 200, r#"{"data": "0x09"}"#)
+            .with("/v2/fees/transfer", 200, "1");
+        let client = client(transport);
+        assert_eq!(client.get_data_var(&contract, "token", "supply").unwrap(), Some(ClarityValue::UInt(100)));
+        assert_eq!(client.get_data_var(&contract, "token", "missing").unwrap(), None);
+        assert_eq!(client.get_constant(&contract, "token", "decimals").unwrap(), Some(ClarityValue::UInt(6)));
+        assert_eq!(client.get_map_entry(&contract, "token", "balances", &ClarityValue::UInt(1)).unwrap(), ClarityValue::OptionalNone);
+        assert_eq!(client.get_transfer_fee_rate().unwrap(), 1);
+
+        let requests = client.transport().requests.borrow();
+        let key: String = serde_json::from_slice(&requests[3].1).unwrap();
+        assert_eq!(key, ClarityValue::UInt(1).to_hex().unwrap());
+    }
+
+    #[test]
+    fn test_broadcast_transaction() {
+        let transaction = StacksTransaction::from_hex(
+            "0000000001040015c31b8c1c11c515e244b75806bac48d1399c775000000000000000000000000000000000000\
+            8b316d56e35b3b8d03ab3b9dbe05eb44d64c53e7ba3c468f9a78c82a13f2174c32facb0f29faeb21075ec933db935ebc28a8793cc60e14b8ee4ef05f52c94016\
+            030200000000000516df0ba3e79792be7be5e50a370289accfc8c9e032000000000000303974657374206d656d6f00000000000000000000000000000000000000000000000000",
+        )
+        .unwrap();
+        let txid = hex::encode(transaction.txid().unwrap());
+        let accepted = client(MockTransport::new().with("/v2/transactions", 200, &format!("\"{txid}\"")));
+        assert_eq!(accepted.broadcast_transaction(&transaction).unwrap(), txid);
+        assert_eq!(accepted.transport().requests.borrow()[0].1, transaction.serialize().unwrap());
+
+        let body = format!(r#"{{"error": "transaction rejected", "reason": "ConflictingNonceInMempool", "reason_data": null, "txid": "{txid}"}}"#);
+        let conflicting = client(MockTransport::new().with("/v2/transactions", 400, &body));
+        let error = conflicting.broadcast_transaction(&transaction).unwrap_err();
+        assert!(matches!(&error, ClientError::Rejected { reason: RejectReason::ConflictingNonceInMempool, txid: Some(id), .. } if *id == txid));
+        assert_eq!(error.to_string(), "Transaction rejected: ConflictingNonceInMempool");
+
+        let body = r#"{"error": "transaction rejected", "reason": "NotEnoughFunds", "reason_data": {"expected": "0x3e8", "actual": "0x0"}}"#;
+        let unfunded = client(MockTransport::new().with("/v2/transactions", 400, body));
+        match unfunded.broadcast_transaction(&transaction).unwrap_err() {
+            ClientError::Rejected { reason, reason_data: Some(data), .. } => {
+                assert_eq!(reason, RejectReason::NotEnoughFunds);
+                assert_eq!(data["expected"], "0x3e8");
+            }
+            error => panic!("unexpected error {error:?}"),
+        }
+        let unknown = client(MockTransport::new().with("/v2/transactions", 400, r#"{"error": "transaction rejected", "reason": "SomethingNew"}"#));
+        assert!(matches!(unknown.broadcast_transaction(&transaction), Err(ClientError::Rejected { reason: RejectReason::Other(_), .. })));
+        let failing = client(MockTransport::new().with("/v2/transactions", 500, "Internal Server Error"));
+        assert!(matches!(failing.broadcast_transaction(&transaction), Err(ClientError::Api { status: 500, .. })));
+    }
+
+    #[test]
+    fn test_broadcast_once() {
+        let public_key = PublicKey::from_secret_key(secp256k1_context(), &SecretKey::from_byte_array(&[1; 32]).unwrap());
+        let contract = Principal::from_str(&format!("{CONTRACT}.pool")).unwrap();
+        let transaction = |fee| ContractCallBuilder::new(contract.clone(), "join", vec![]).fee(fee).build(&public_key).unwrap();
+        let transport = MockTransport::new()
+            .with("/v2/transactions", 500, "Internal Server Error")
+            .with("/v2/transactions", 200, r#""0x01""#)
+            .with("/v2/transactions", 200, r#""0x02""#);
+        let client = client(transport);
+        let mut guard = IdempotencyGuard::new(Duration::from_secs(600));
+
+        assert!(client.broadcast_once(&transaction(1000), &mut guard).is_err());
+        assert_eq!(client.broadcast_once(&transaction(1000), &mut guard).unwrap(), "0x01");
+        // the same call with a higher fee is still a duplicate
+        let error = client.broadcast_once(&transaction(2000), &mut guard).unwrap_err();
+        assert!(matches!(error, ClientError::Transaction(TransactionError::Duplicate(..))));
+        assert_eq!(client.transport().requests.borrow().len(), 2);
+    }
+
+    #[test]
+    fn test_broadcast_stale_transaction() {
+        let public_key = PublicKey::from_secret_key(secp256k1_context(), &SecretKey::from_byte_array(&[1; 32]).unwrap());
+        let contract = Principal::from_str(&format!("{CONTRACT}.pool")).unwrap();
+        let build = |build_height| {
+            let metadata = TransactionMetadata::new(build_height, ExpiryPolicy::AfterBlocks(6));
+            ContractCallBuilder::new(contract.clone(), "join", vec![]).fee(1000).metadata(metadata).build(&public_key).unwrap()
+        };
+        let node = || client(MockTransport::new().with("/v2/info", 200, &info(1, 3652501241)).with("/v2/transactions", 200, r#""0x01""#));
+
+        let stale = node();
+        let error = stale.broadcast_transaction(&build(1000000)).unwrap_err();
+        assert!(matches!(error, ClientError::Transaction(TransactionError::Stale { current_height: 1000010, .. })));
+        assert_eq!(stale.transport().requests.borrow().len(), 1);
+        assert!(node().broadcast_transaction(&build(1000004)).is_ok());
+    }
+
+    #[test]
+    fn test_get_account() {
+        let public_key = PublicKey::from_secret_key(secp256k1_context(), &SecretKey::from_byte_array(&[1; 32]).unwrap());
+        let network = StacksNetwork::mainnet();
+        let address = network.address(&public_key);
+        let body = r#"{"balance": "0x0000000000000000000000174876e800", "locked": "0x00000000000000000000000000000064", "unlock_height": 890000, "nonce": 7, "balance_proof": "", "nonce_proof": ""}"#;
+        let node = client(MockTransport::new().with(&format!("/v2/accounts/{address}?proof=0"), 200, body));
+        let account = node.get_account(&Principal::from(address)).unwrap();
+        assert_eq!(account, AccountInfo { balance: 100_000_000_000, locked: 100, unlock_height: 890000, nonce: 7 });
+
+        let builder = ContractCallBuilder::new(Principal::from_str(&format!("{CONTRACT}.token")).unwrap(), "transfer", vec![]);
+        let transaction = builder.fetch_nonce(&node, &public_key).unwrap().build(&public_key).unwrap();
+        assert_eq!(transaction.auth.origin().nonce(), 7);
+        let batch = TransactionBatch::new().fetch_nonce(&node, &public_key).unwrap().transfer(Principal::from(address), 1, "").unwrap();
+        assert_eq!(batch.build(&public_key).unwrap()[0].auth.origin().nonce(), 7);
+        let other = PublicKey::from_secret_key(secp256k1_context(), &SecretKey::from_byte_array(&[2; 32]).unwrap());
+        assert!(TransactionBatch::new().fetch_nonce(&node, &other).is_err());
+
+        let invalid = r#"{"balance": "0xzz", "locked": "0x0", "unlock_height": 0, "nonce": 0}"#;
+        let node = client(MockTransport::new().with(&format!("/v2/accounts/{address}?proof=0"), 200, invalid));
+        assert!(matches!(node.get_account(&Principal::from(address)), Err(ClientError::Decode(_))));
+    }
+
+    #[test]
+    fn test_contract_definition() {
+        let contract = StacksAddress::from_str(CONTRACT).unwrap();
+        let interface = r#"{"functions": [{"name": "get-balance", "access": "read_only", "args": [{"name": "who", "type": "principal"}],
+            "outputs": {"type": {"response": {"ok": "uint128", "error": "none"}}}}],
+            "variables": [], "maps": [], "fungible_tokens": [{"name": "token"}], "non_fungible_tokens": [], "epoch": "Epoch30", "clarity_version": "Clarity3"}"#;
+        let transport = MockTransport::new()
+            .with(&format!("/v2/contracts/source/{CONTRACT}/token?proof=0"), 200, r#"{"source": "(define-fungible-token token)", "publish_height": 100}"#)
+            .with(&format!("/v2/contracts/interface/{CONTRACT}/token"), 200, interface)
+            .with(&format!("/v2/contracts/interface/{CONTRACT}/missing"), 404, "No contract interface data found");
+        let client = client(transport);
+        let source = client.get_contract_source(&contract, "token").unwrap().unwrap();
+        assert_eq!((source.source.as_str(), source.publish_height), ("(define-fungible-token token)", 100));
+
+        let abi = client.get_contract_interface(&contract, "token").unwrap().unwrap();
+        assert_eq!(abi.clarity_version.as_deref(), Some("Clarity3"));
+        let function = abi.function("get-balance").unwrap();
+        assert!(function.check_args(&[ClarityValue::from_str(&format!("'{SENDER}")).unwrap()]).is_ok());
+        assert!(function.check_args(&[ClarityValue::UInt(1)]).is_err());
+        assert_eq!(client.get_contract_interface(&contract, "missing").unwrap(), None);
+    }
+
+    #[test]
+    fn test_pox_info() {
+        let client = client(MockTransport::new().with("/v2/pox", 200, POX));
+        let info = client.get_pox_info().unwrap();
+        assert_eq!((info.current_cycle.id, info.next_cycle_start(), info.min_amount_ustx), (95, 867950, 170000000000));
+        assert_eq!(info.contract_version(), Some(PoxContractVersion::Pox4));
+    }
+
+    #[test]
+    fn test_detect_network() {
+        let node = |info: String| StacksRpcClient::with_transport(StacksNetwork::devnet().with_node_url("http://node:20443"), MockTransport::new().with("/v2/info", 200, &info).with("/v2/pox", 200, POX));
+
+        let mainnet = node(info(1, 3652501241)).detect_network().unwrap();
+        assert_eq!(mainnet.network, StacksNetwork::mainnet().with_node_url("http://node:20443"));
+        assert_eq!((mainnet.epoch.as_deref(), mainnet.pox_version), (Some("Epoch25"), Some(PoxContractVersion::Pox4)));
+        assert_eq!(node(info(2147483648, 118034699)).detect_network().unwrap().network.kind, NetworkKind::Testnet);
+        let devnet = node(info(2147483648, 3669344250)).get_info().unwrap().network("http://node:20443");
+        assert_eq!((devnet.kind, devnet.chain_id, devnet.api_url.as_str()), (NetworkKind::Mocknet, 2147483648, "http://node:20443"));
+    }
+
+    #[test]
+    fn test_tenures() {
+        let tenure = r#"{"consensus_hash": "4c5a49be0e34dc603b66f090fd07d28a2f76a2ad", "tenure_start_block_id": "0a",
+            "parent_consensus_hash": "5c5a49be0e34dc603b66f090fd07d28a2f76a2ad", "parent_tenure_start_block_id": "0b",
+            "tip_block_id": "0c", "tip_height": 1000010, "reward_cycle": 98}"#;
+        let sortition = r#"[{"burn_block_hash": "0x00000000000000000001", "burn_block_height": 867000, "burn_header_timestamp": 1729999900,
+            "sortition_id": "0x01", "parent_sortition_id": "0x02", "consensus_hash": "0x4c5a49be0e34dc603b66f090fd07d28a2f76a2ad",
+            "was_sortition": true, "miner_pk_hash160": "0x03", "stacks_parent_ch": "0x04", "last_sortition_ch": "0x05",
+            "committed_block_hash": "0x06", "vrf_seed": "0x07"}]"#;
+        let transport = MockTransport::new()
+            .with("/v3/tenures/info", 200, tenure)
+            .with("/v3/sortitions", 200, sortition)
+            .with("/v3/sortitions/consensus/4c5a49be0e34dc603b66f090fd07d28a2f76a2ad", 200, sortition)
+            .with("/v3/sortitions/burn_height/1", 404, "");
+        let client = client(transport);
+
+        let tenure = client.get_tenure_info().unwrap();
+        assert_eq!((tenure.tip_height, tenure.reward_cycle), (1000010, 98));
+        let latest = client.get_sortitions(&SortitionQuery::Latest).unwrap();
+        assert!(latest[0].was_sortition);
+        assert_eq!((latest[0].burn_block_height, latest[0].miner_pk_hash160.as_deref()), (867000, Some("0x03")));
+        let by_consensus_hash = client.get_sortitions(&SortitionQuery::ConsensusHash(format!("0x{}", tenure.consensus_hash))).unwrap();
+        assert_eq!(by_consensus_hash, latest);
+        assert!(client.get_sortitions(&SortitionQuery::BurnHeight(1)).unwrap().is_empty());
+    }
+}
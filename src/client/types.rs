@@ -0,0 +1,654 @@
+//! Request and response bodies of the node and API endpoints, shared by the blocking and async
+//! clients.
+
+use std::fmt;
+use std::str::FromStr;
+
+use serde::de::Error;
+use serde::{Deserialize, Deserializer, Serialize};
+
+use crate::clarity::value::ClarityValue;
+use crate::clarity::ClarityError;
+use crate::network::{NetworkKind, StacksNetwork};
+use crate::stacking::pox_address::PoxContractVersion;
+
+/// Body of `POST /v2/contracts/call-read/{address}/{contract}/{function}`.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct ReadOnlyCallRequest {
+    pub sender: String,
+    /// Hex encoded Clarity values, with `0x`
+    pub arguments: Vec<String>,
+}
+
+impl ReadOnlyCallRequest {
+    pub fn new(sender: &str, arguments: &[ClarityValue]) -> Result<Self, ClarityError> {
+        let arguments = arguments.iter().map(ClarityValue::to_hex).collect::<Result<_, _>>()?;
+        Ok(ReadOnlyCallRequest { sender: sender.to_string(), arguments })
+    }
+}
+
+/// Response of a read-only call: the result when `okay`, the runtime error otherwise.
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize)]
+pub struct ReadOnlyCallResponse {
+    pub okay: bool,
+    #[serde(default)]
+    pub result: Option<String>,
+    #[serde(default)]
+    pub cause: Option<String>,
+}
+
+/// A Clarity value as the node returns it, e.g. from `/v2/data_var`.
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize)]
+pub struct ClarityData {
+    /// Hex encoded, with `0x`
+    pub data: String,
+    /// MARF proof, when asked for
+    #[serde(default)]
+    pub proof: Option<String>,
+}
+
+/// Source of a contract, `/v2/contracts/source/{address}/{name}`.
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize)]
+pub struct ContractSource {
+    pub source: String,
+    /// Block height the contract was deployed at
+    pub publish_height: u64,
+    /// MARF proof, when asked for
+    #[serde(default)]
+    pub proof: Option<String>,
+}
+
+/// State of an account, `/v2/accounts/{principal}?proof=0`.
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize)]
+pub struct AccountInfo {
+    /// Unlocked balance, in micro-STX
+    #[serde(deserialize_with = "hex_u128")]
+    pub balance: u128,
+    /// Balance locked by stacking, in micro-STX
+    #[serde(deserialize_with = "hex_u128")]
+    pub locked: u128,
+    /// Burn block height unlocking the locked balance
+    pub unlock_height: u64,
+    /// Nonce of the next transaction of the account
+    pub nonce: u64,
+}
+
+/// Nonces of an address seen by the API, `/extended/v1/address/{principal}/nonces`.
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize)]
+pub struct AddressNonces {
+    /// Nonce of the last transaction of the address in the mempool
+    pub last_mempool_tx_nonce: Option<u64>,
+    /// Nonce of the last mined transaction of the address
+    pub last_executed_tx_nonce: Option<u64>,
+    pub possible_next_nonce: u64,
+    /// Nonces below the last one in the mempool that no transaction uses
+    pub detected_missing_nonces: Vec<u64>,
+    pub detected_mempool_nonces: Vec<u64>,
+}
+
+impl AddressNonces {
+    /// Nonce of the next transaction of the account, once the mempool is ignored.
+    pub fn account_nonce(&self) -> u64 {
+        self.last_executed_tx_nonce.map_or(0, |nonce| nonce.saturating_add(1))
+    }
+
+    /// Nonces of the transactions of the address in the mempool.
+    pub fn mempool_nonces(&self) -> impl Iterator<Item = u64> + '_ {
+        self.detected_mempool_nonces.iter().copied().chain(self.last_mempool_tx_nonce)
+    }
+}
+
+/// A `0x` prefixed big-endian hex amount, as the node encodes balances.
+fn hex_u128<'de, D: Deserializer<'de>>(deserializer: D) -> Result<u128, D::Error> {
+    let amount = String::deserialize(deserializer)?;
+    u128::from_str_radix(amount.trim_start_matches("0x"), 16).map_err(D::Error::custom)
+}
+
+/// A page of a list of the API, e.g. `/extended/v1/tx/mempool`.
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize)]
+pub struct Page<T> {
+    pub limit: u32,
+    pub offset: u32,
+    /// Number of items of the whole list
+    pub total: u32,
+    pub results: Vec<T>,
+}
+
+/// Status of a transaction known to the API.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TxStatus {
+    /// In the mempool
+    Pending,
+    Success,
+    /// Mined, but the contract call returned an `err`
+    AbortByResponse,
+    /// Mined, but a post-condition did not hold
+    AbortByPostCondition,
+    /// Replaced by a transaction with the same nonce and a higher fee
+    DroppedReplaceByFee,
+    DroppedReplaceAcrossFork,
+    /// Evicted as its fee is too low for the mempool
+    DroppedTooExpensive,
+    /// Evicted after staying too long in the mempool
+    DroppedStaleGarbageCollect,
+    DroppedProblematic,
+    /// A status this crate does not know yet
+    #[serde(other)]
+    Unknown,
+}
+
+impl TxStatus {
+    /// Dropped from the mempool: it will not be mined unless broadcast again.
+    pub fn is_dropped(&self) -> bool {
+        matches!(
+            self,
+            TxStatus::DroppedReplaceByFee
+                | TxStatus::DroppedReplaceAcrossFork
+                | TxStatus::DroppedTooExpensive
+                | TxStatus::DroppedStaleGarbageCollect
+                | TxStatus::DroppedProblematic
+        )
+    }
+
+    /// Mined, successfully or not.
+    pub fn is_mined(&self) -> bool {
+        matches!(self, TxStatus::Success | TxStatus::AbortByResponse | TxStatus::AbortByPostCondition)
+    }
+}
+
+/// A Clarity value as the API serves it, e.g. the result of a mined transaction.
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize)]
+pub struct ClarityRepr {
+    /// Hex encoded, with `0x`
+    pub hex: String,
+    /// e.g. `(ok true)`
+    pub repr: String,
+}
+
+/// A transaction as the API serves it, in the mempool or mined, e.g. `/extended/v1/tx/{txid}`.
+///
+/// Only the fields common to every type of transaction are read.
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize)]
+pub struct ApiTransaction {
+    /// Hex, with `0x`
+    pub tx_id: String,
+    pub tx_status: TxStatus,
+    pub tx_type: TxType,
+    pub nonce: u64,
+    #[serde(deserialize_with = "decimal")]
+    pub fee_rate: u64,
+    pub sender_address: String,
+    pub sponsored: bool,
+    /// Unix time the node received it, for a transaction still or once in the mempool
+    #[serde(default)]
+    pub receipt_time: Option<u64>,
+    /// Height of its block, for a mined transaction
+    #[serde(default)]
+    pub block_height: Option<u64>,
+    /// Unix time of its block, for a mined transaction
+    #[serde(default)]
+    pub block_time: Option<u64>,
+    /// Result of a mined transaction
+    #[serde(default)]
+    pub tx_result: Option<ClarityRepr>,
+    /// Payload of a [`TxType::TokenTransfer`]
+    #[serde(default)]
+    pub token_transfer: Option<ApiTokenTransfer>,
+    /// Payload of a [`TxType::ContractCall`]
+    #[serde(default)]
+    pub contract_call: Option<ApiContractCall>,
+    /// Payload of a [`TxType::SmartContract`]
+    #[serde(default)]
+    pub smart_contract: Option<ApiSmartContract>,
+}
+
+/// Type of a transaction known to the API, its `tx_type`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TxType {
+    TokenTransfer,
+    ContractCall,
+    SmartContract,
+    Coinbase,
+    PoisonMicroblock,
+    TenureChange,
+    /// A type this crate does not know yet
+    #[serde(other)]
+    Unknown,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize)]
+pub struct ApiTokenTransfer {
+    pub recipient_address: String,
+    #[serde(deserialize_with = "decimal")]
+    pub amount: u64,
+    /// Hex encoded, with `0x`
+    pub memo: String,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize)]
+pub struct ApiContractCall {
+    /// e.g. `SP3K8BC0PPEVCV7NZ6QSRWPQ2JE9E5B6N3PA0KBR9.token-alex`
+    pub contract_id: String,
+    pub function_name: String,
+    #[serde(default)]
+    pub function_args: Vec<ClarityRepr>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize)]
+pub struct ApiSmartContract {
+    pub contract_id: String,
+    pub source_code: String,
+}
+
+/// A transaction of an address with what it moved, `/extended/v2/addresses/{address}/transactions`.
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize)]
+pub struct AddressTransaction {
+    pub tx: ApiTransaction,
+    /// In micro-STX, fee included
+    #[serde(deserialize_with = "decimal")]
+    pub stx_sent: u128,
+    #[serde(deserialize_with = "decimal")]
+    pub stx_received: u128,
+}
+
+impl ApiTransaction {
+    /// Seconds spent in the mempool at the unix time `now`, `None` once it left it.
+    pub fn pending_for(&self, now: u64) -> Option<u64> {
+        match self.tx_status {
+            TxStatus::Pending => self.receipt_time.map(|receipt_time| now.saturating_sub(receipt_time)),
+            _ => None,
+        }
+    }
+}
+
+/// A transfer, mint or burn of an asset involving an address, `/extended/v1/address/{principal}/assets`.
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize)]
+pub struct AssetEvent {
+    pub event_index: u32,
+    /// `stx_asset`, `fungible_token_asset` or `non_fungible_token_asset`
+    pub event_type: String,
+    pub tx_id: String,
+    #[serde(default)]
+    pub asset: Option<Asset>,
+}
+
+/// What an [`AssetEvent`] moved.
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize)]
+pub struct Asset {
+    /// `transfer`, `mint` or `burn`
+    pub asset_event_type: String,
+    /// e.g. `SP3K8BC0PPEVCV7NZ6QSRWPQ2JE9E5B6N3PA0KBR9.token-alex::alex`, none for STX
+    #[serde(default)]
+    pub asset_id: Option<String>,
+    #[serde(default)]
+    pub sender: Option<String>,
+    #[serde(default)]
+    pub recipient: Option<String>,
+    /// For STX and fungible tokens
+    #[serde(default, deserialize_with = "optional_decimal")]
+    pub amount: Option<u128>,
+    /// Token id, for non-fungible tokens
+    #[serde(default)]
+    pub value: Option<ClarityRepr>,
+}
+
+/// A non-fungible token held by an address, `/extended/v1/tokens/nft/holdings`.
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize)]
+pub struct NftHolding {
+    /// e.g. `SP2X0TZ59D5SZ8ACQ6YMCHHNR2ZN51Z32E2CJ173.the-explorer-guild::The-Explorer-Guild`
+    pub asset_identifier: String,
+    /// Token id
+    pub value: ClarityRepr,
+    /// Height of the block it was received in
+    pub block_height: u64,
+    pub tx_id: String,
+}
+
+/// An amount the API encodes as a decimal string.
+fn decimal<'de, D: Deserializer<'de>, T: FromStr<Err: fmt::Display>>(deserializer: D) -> Result<T, D::Error> {
+    String::deserialize(deserializer)?.parse().map_err(D::Error::custom)
+}
+
+fn optional_decimal<'de, D: Deserializer<'de>, T: FromStr<Err: fmt::Display>>(deserializer: D) -> Result<Option<T>, D::Error> {
+    Option::<String>::deserialize(deserializer)?.map(|amount| amount.parse().map_err(D::Error::custom)).transpose()
+}
+
+/// A block by height or by hash, for the block queries of the API.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum BlockRef {
+    Height(u64),
+    /// Hex, with or without `0x`
+    Hash(String),
+}
+
+impl fmt::Display for BlockRef {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        match self {
+            BlockRef::Height(height) => f.write_str(&height.to_string()),
+            BlockRef::Hash(hash) => f.write_str(&format!("0x{}", hash.trim_start_matches("0x"))),
+        }
+    }
+}
+
+/// Header of a Stacks block, `/extended/v2/blocks/{height_or_hash}`.
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize)]
+pub struct Block {
+    /// False for a block of an abandoned fork
+    pub canonical: bool,
+    pub height: u64,
+    pub hash: String,
+    pub index_block_hash: String,
+    pub parent_block_hash: String,
+    /// Missing from the blocks of the websocket events
+    #[serde(default)]
+    pub parent_index_block_hash: String,
+    /// Unix time
+    pub block_time: u64,
+    /// Number of tenures so far, since Nakamoto
+    pub tenure_height: u64,
+    pub burn_block_hash: String,
+    pub burn_block_height: u64,
+    pub burn_block_time: u64,
+    /// Txid of the block commit of the miner
+    pub miner_txid: String,
+    /// Missing from the blocks of the websocket events, which list `txs` instead
+    #[serde(default)]
+    pub tx_count: u32,
+}
+
+/// A Bitcoin block and the Stacks blocks it anchors, `/extended/v2/burn-blocks/{height_or_hash}`.
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize)]
+pub struct BurnBlock {
+    pub burn_block_hash: String,
+    pub burn_block_height: u64,
+    /// Unix time
+    pub burn_block_time: u64,
+    /// Hashes of the Stacks blocks
+    pub stacks_blocks: Vec<String>,
+    pub total_tx_count: u32,
+}
+
+/// The ongoing tenure of the node, `/v3/tenures/info`.
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize)]
+pub struct TenureInfo {
+    /// Consensus hash of the sortition electing the miner of the tenure
+    pub consensus_hash: String,
+    pub tenure_start_block_id: String,
+    pub parent_consensus_hash: String,
+    pub parent_tenure_start_block_id: String,
+    /// Index block hash of the latest block
+    pub tip_block_id: String,
+    pub tip_height: u64,
+    pub reward_cycle: u64,
+}
+
+/// Magic bytes of the Bitcoin testnet, the parent network of the Stacks testnet.
+const BITCOIN_TESTNET_MAGIC: u32 = 0x0709110b;
+
+/// Version and tips of a node, `/v2/info`.
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize)]
+pub struct NodeInfo {
+    pub peer_version: u32,
+    pub server_version: String,
+    /// Chain id of the Stacks network
+    pub network_id: u32,
+    /// Magic bytes of the Bitcoin network it settles on
+    pub parent_network_id: u32,
+    pub burn_block_height: u64,
+    pub stable_burn_block_height: u64,
+    pub pox_consensus: String,
+    pub stacks_tip_height: u64,
+    /// Block hash of the tip
+    pub stacks_tip: String,
+    pub stacks_tip_consensus_hash: String,
+    /// Missing from nodes older than Nakamoto
+    #[serde(default)]
+    pub tenure_height: Option<u64>,
+    #[serde(default)]
+    pub is_fully_synced: Option<bool>,
+}
+
+impl NodeInfo {
+    /// Kind of the network of the node: mainnet by its chain id, testnet by settling on the
+    /// Bitcoin testnet, a devnet otherwise (e.g. on regtest).
+    pub fn network_kind(&self) -> NetworkKind {
+        if self.network_id == NetworkKind::Mainnet.chain_id() {
+            NetworkKind::Mainnet
+        } else if self.parent_network_id == BITCOIN_TESTNET_MAGIC {
+            NetworkKind::Testnet
+        } else {
+            NetworkKind::Mocknet
+        }
+    }
+
+    /// The network of the node at `node_url`: the public API of mainnet and testnet, or the
+    /// node itself on a devnet, with the chain id the node reports.
+    pub fn network(&self, node_url: &str) -> StacksNetwork {
+        let network = match self.network_kind() {
+            NetworkKind::Mocknet => StacksNetwork::custom(NetworkKind::Mocknet, self.network_id, node_url),
+            kind => StacksNetwork::from(kind).with_node_url(node_url),
+        };
+        StacksNetwork { chain_id: self.network_id, ..network }
+    }
+}
+
+/// What a node reports of its network, see [`StacksRpcClient::detect_network`](super::rpc::StacksRpcClient::detect_network).
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct NetworkDetails {
+    pub network: StacksNetwork,
+    /// e.g. `Epoch30`, `None` if the node does not list epochs
+    pub epoch: Option<String>,
+    /// `None` for a PoX contract this crate does not know
+    pub pox_version: Option<PoxContractVersion>,
+    pub server_version: String,
+}
+
+impl NetworkDetails {
+    pub fn new(node_url: &str, info: &NodeInfo, pox: &PoxInfo) -> Self {
+        NetworkDetails {
+            network: info.network(node_url),
+            epoch: pox.current_epoch().map(|epoch| epoch.epoch_id.clone()),
+            pox_version: pox.contract_version(),
+            server_version: info.server_version.clone(),
+        }
+    }
+}
+
+/// State of Proof of Transfer on the node, `/v2/pox`.
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize)]
+pub struct PoxInfo {
+    /// Active PoX contract, e.g. `SP000000000000000000002Q6VF78.pox-4`
+    pub contract_id: String,
+    pub first_burnchain_block_height: u64,
+    pub current_burnchain_block_height: u64,
+    /// Smallest amount of micro-STX a `stack-stx` can lock in the next cycle
+    pub min_amount_ustx: u64,
+    pub reward_cycle_id: u64,
+    /// In burn blocks, prepare phase included
+    pub reward_cycle_length: u64,
+    pub prepare_cycle_length: u64,
+    pub reward_slots: u32,
+    pub total_liquid_supply_ustx: u64,
+    pub current_cycle: PoxCycle,
+    pub next_cycle: PoxNextCycle,
+    /// Burn blocks until the reward phase of the next cycle
+    pub next_reward_cycle_in: u64,
+    /// Missing from nodes older than 2.1
+    #[serde(default)]
+    pub epochs: Vec<StacksEpoch>,
+}
+
+impl PoxInfo {
+    /// Version of the active PoX contract, `None` for a name this crate does not know.
+    pub fn contract_version(&self) -> Option<PoxContractVersion> {
+        match self.contract_id.rsplit_once('.')?.1 {
+            "pox" => Some(PoxContractVersion::Pox),
+            "pox-2" => Some(PoxContractVersion::Pox2),
+            "pox-3" => Some(PoxContractVersion::Pox3),
+            "pox-4" => Some(PoxContractVersion::Pox4),
+            _ => None,
+        }
+    }
+
+    /// Burn block height at which the reward phase of the next cycle starts.
+    pub fn next_cycle_start(&self) -> u64 {
+        self.next_cycle.reward_phase_start_block_height
+    }
+
+    /// The Stacks epoch of the current burn block, if the node lists them.
+    pub fn current_epoch(&self) -> Option<&StacksEpoch> {
+        self.epochs.iter().find(|epoch| epoch.start_height <= self.current_burnchain_block_height && self.current_burnchain_block_height < epoch.end_height)
+    }
+}
+
+/// A Stacks epoch and the burn blocks it spans, listed by `/v2/pox`.
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize)]
+pub struct StacksEpoch {
+    /// e.g. `Epoch30`
+    pub epoch_id: String,
+    pub start_height: u64,
+    /// Excluded, `u64::MAX` for the last one
+    pub end_height: u64,
+    pub network_epoch: u8,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize)]
+pub struct PoxCycle {
+    pub id: u64,
+    pub min_threshold_ustx: u64,
+    pub stacked_ustx: u64,
+    pub is_pox_active: bool,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize)]
+pub struct PoxNextCycle {
+    pub id: u64,
+    pub min_threshold_ustx: u64,
+    pub stacked_ustx: u64,
+    pub prepare_phase_start_block_height: u64,
+    pub blocks_until_prepare_phase: i64,
+    pub reward_phase_start_block_height: u64,
+    pub blocks_until_reward_phase: u64,
+}
+
+/// A sortition of `/v3/sortitions`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum SortitionQuery {
+    Latest,
+    BurnHeight(u64),
+    /// Hash of the Bitcoin block
+    BurnHash(String),
+    ConsensusHash(String),
+}
+
+/// The outcome of the sortition of a Bitcoin block, `/v3/sortitions`.
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize)]
+pub struct SortitionInfo {
+    pub burn_block_hash: String,
+    pub burn_block_height: u64,
+    /// Unix time of the Bitcoin block
+    pub burn_header_timestamp: u64,
+    pub sortition_id: String,
+    pub parent_sortition_id: String,
+    pub consensus_hash: String,
+    /// Whether a miner won, starting a tenure
+    pub was_sortition: bool,
+    /// Hash160 of the key of the winning miner
+    #[serde(default)]
+    pub miner_pk_hash160: Option<String>,
+    /// Consensus hash of the tenure the new one builds on
+    #[serde(default)]
+    pub stacks_parent_ch: Option<String>,
+    /// Consensus hash of the latest sortition with a winner
+    #[serde(default)]
+    pub last_sortition_ch: Option<String>,
+    #[serde(default)]
+    pub committed_block_hash: Option<String>,
+}
+
+/// STX balance of an address as the API reports it, e.g. in its websocket events.
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize)]
+pub struct AddressBalance {
+    pub address: String,
+    /// In micro-STX, locked amount included
+    #[serde(deserialize_with = "decimal")]
+    pub balance: u128,
+    #[serde(deserialize_with = "decimal")]
+    pub locked: u128,
+    #[serde(deserialize_with = "decimal")]
+    pub total_sent: u128,
+    #[serde(deserialize_with = "decimal")]
+    pub total_received: u128,
+    #[serde(deserialize_with = "decimal")]
+    pub total_fees_sent: u128,
+}
+
+macro_rules! reject_reasons {
+    ($($(#[$doc:meta])* $reason:ident,)+) => {
+        /// Why a node rejected a broadcast transaction, the `reason` of its error.
+        #[derive(Clone, Debug, PartialEq, Eq)]
+        pub enum RejectReason {
+            $($(#[$doc])* $reason,)+
+            /// A reason this crate does not know yet
+            Other(String),
+        }
+
+        impl RejectReason {
+            pub fn from_reason(reason: &str) -> Self {
+                match reason {
+                    $(stringify!($reason) => RejectReason::$reason,)+
+                    _ => RejectReason::Other(reason.to_string()),
+                }
+            }
+
+            pub fn as_str(&self) -> &str {
+                match self {
+                    $(RejectReason::$reason => stringify!($reason),)+
+                    RejectReason::Other(reason) => reason,
+                }
+            }
+        }
+    };
+}
+
+reject_reasons! {
+    Serialization,
+    Deserialization,
+    SignatureValidation,
+    /// The fee is under the minimum relay fee, see `reason_data`
+    FeeTooLow,
+    /// The nonce is not the next one of the account, see `reason_data`
+    BadNonce,
+    /// The payer cannot afford the fee and the transfer, see `reason_data`
+    NotEnoughFunds,
+    NoSuchContract,
+    NoSuchPublicFunction,
+    BadFunctionArgument,
+    ContractAlreadyExists,
+    PoisonMicroblocksDoNotConflict,
+    PoisonMicroblockHasUnknownPubKeyHash,
+    PoisonMicroblockIsInvalid,
+    BadAddressVersionByte,
+    NoCoinbaseViaMempool,
+    NoTenureChangeViaMempool,
+    ServerFailureNoSuchChainTip,
+    ServerFailureDatabase,
+    ServerFailureOther,
+    /// A transaction with the same nonce is already in the mempool, replace it with a higher fee
+    ConflictingNonceInMempool,
+    /// The account has too many pending transactions in the mempool
+    TooMuchChaining,
+    BadTransactionVersion,
+    TransferRecipientIsSender,
+    TransferAmountMustBePositive,
+    EstimatorError,
+    TemporarilyBlacklisted,
+}
+
+impl fmt::Display for RejectReason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        f.write_str(self.as_str())
+    }
+}
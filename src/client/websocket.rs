@@ -0,0 +1,317 @@
+//! Subscriptions to the events of the Stacks Blockchain API, over its JSON-RPC websocket
+//! (`/extended/v1/ws`).
+//!
+//! [`SubscriptionSession`] speaks the protocol over any websocket; with the `websocket` feature,
+//! [`EventStream`] runs it over `tokio-tungstenite`.
+
+use std::collections::{BTreeSet, HashMap};
+
+use serde::Deserialize;
+use serde_json::{json, Value};
+
+use crate::address::stacks_address::StacksAddress;
+
+use super::error::ClientError;
+use super::types::{AddressBalance, ApiTransaction, Block};
+
+pub const WEBSOCKET_PATH: &str = "/extended/v1/ws";
+
+/// Events to be notified of.
+#[derive(Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Subscription {
+    /// New blocks
+    Blocks,
+    /// Transactions entering the mempool
+    Mempool,
+    /// Status changes of a transaction, by hex txid
+    Transaction(String),
+    /// Transactions sent or received by an address
+    AddressTransactions(StacksAddress),
+    /// STX balance changes of an address
+    AddressBalance(StacksAddress),
+}
+
+impl Subscription {
+    /// The `params` of the `subscribe` and `unsubscribe` requests.
+    fn params(&self) -> Value {
+        match self {
+            Subscription::Blocks => json!({"event": "block"}),
+            Subscription::Mempool => json!({"event": "mempool"}),
+            Subscription::Transaction(txid) => json!({"event": "tx_update", "tx_id": format!("0x{}", txid.trim_start_matches("0x"))}),
+            Subscription::AddressTransactions(address) => json!({"event": "address_tx_update", "address": address.to_string()}),
+            Subscription::AddressBalance(address) => json!({"event": "address_balance_update", "address": address.to_string()}),
+        }
+    }
+}
+
+/// An event of a [`Subscription`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ChainEvent {
+    Block(Box<Block>),
+    Mempool(Box<ApiTransaction>),
+    Transaction(Box<ApiTransaction>),
+    AddressTransaction { address: String, transaction: Box<ApiTransaction> },
+    AddressBalance(AddressBalance),
+}
+
+#[derive(Deserialize)]
+struct AddressTransactionParams {
+    address: String,
+    tx: ApiTransaction,
+}
+
+#[derive(Deserialize)]
+struct RpcError {
+    code: i64,
+    message: String,
+}
+
+/// A message of the API: a response to a request when it has an `id`, an event otherwise.
+#[derive(Deserialize)]
+struct RpcMessage {
+    #[serde(default)]
+    id: Option<u64>,
+    #[serde(default)]
+    method: Option<String>,
+    #[serde(default)]
+    params: Value,
+    #[serde(default)]
+    error: Option<RpcError>,
+}
+
+/// The JSON-RPC side of a websocket to the API: requests to send and events of the messages
+/// received, independent of the websocket implementation.
+///
+/// Usage:
+/// ```rust
+/// use stacks_rs::client::websocket::{ChainEvent, Subscription, SubscriptionSession};
+/// let mut session = SubscriptionSession::new();
+/// let request = session.subscribe(Subscription::Blocks);
+/// assert_eq!(request, r#"{"id":0,"jsonrpc":"2.0","method":"subscribe","params":{"event":"block"}}"#);
+/// assert!(session.receive(r#"{"jsonrpc":"2.0","id":0,"result":{"event":"block"}}"#).unwrap().is_none());
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct SubscriptionSession {
+    next_id: u64,
+    /// Requests waiting for their response
+    pending: HashMap<u64, Subscription>,
+    subscriptions: BTreeSet<Subscription>,
+}
+
+impl SubscriptionSession {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn request(&mut self, method: &str, subscription: Subscription) -> String {
+        let id = self.next_id;
+        self.next_id += 1;
+        let request = json!({"jsonrpc": "2.0", "id": id, "method": method, "params": subscription.params()});
+        self.pending.insert(id, subscription);
+        request.to_string()
+    }
+
+    /// The `subscribe` request to send.
+    pub fn subscribe(&mut self, subscription: Subscription) -> String {
+        self.subscriptions.insert(subscription.clone());
+        self.request("subscribe", subscription)
+    }
+
+    /// The `unsubscribe` request to send.
+    pub fn unsubscribe(&mut self, subscription: &Subscription) -> String {
+        self.subscriptions.remove(subscription);
+        self.request("unsubscribe", subscription.clone())
+    }
+
+    /// Subscriptions made so far, to make again on a new connection.
+    pub fn subscriptions(&self) -> impl Iterator<Item = &Subscription> {
+        self.subscriptions.iter()
+    }
+
+    /// The event of a text message of the API, `None` for a response to a request or an event
+    /// this crate does not know.
+    ///
+    /// A refused subscription is a [`ClientError::Subscription`].
+    pub fn receive(&mut self, message: &str) -> Result<Option<ChainEvent>, ClientError> {
+        let message: RpcMessage = serde_json::from_str(message).map_err(ClientError::Decode)?;
+        if let Some(id) = message.id {
+            let subscription = self.pending.remove(&id);
+            return match (message.error, subscription) {
+                (Some(error), Some(subscription)) => {
+                    self.subscriptions.remove(&subscription);
+                    Err(ClientError::Subscription(format!("{subscription:?}: {} ({})", error.message, error.code)))
+                }
+                (Some(error), None) => Err(ClientError::Subscription(format!("{} ({})", error.message, error.code))),
+                (None, _) => Ok(None),
+            };
+        }
+        let params = message.params;
+        let event = match message.method.as_deref() {
+            Some("block") => ChainEvent::Block(serde_json::from_value(params).map_err(ClientError::Decode)?),
+            Some("mempool") => ChainEvent::Mempool(serde_json::from_value(params).map_err(ClientError::Decode)?),
+            Some("tx_update") => ChainEvent::Transaction(serde_json::from_value(params).map_err(ClientError::Decode)?),
+            Some("address_tx_update") => {
+                let params: AddressTransactionParams = serde_json::from_value(params).map_err(ClientError::Decode)?;
+                ChainEvent::AddressTransaction { address: params.address, transaction: Box::new(params.tx) }
+            }
+            Some("address_balance_update") => ChainEvent::AddressBalance(serde_json::from_value(params).map_err(ClientError::Decode)?),
+            _ => return Ok(None),
+        };
+        Ok(Some(event))
+    }
+}
+
+/// `ws://` or `wss://` URL of the websocket of the API at `api_url`.
+pub fn websocket_url(api_url: &str) -> String {
+    let api_url = api_url.trim_end_matches('/');
+    let url = match api_url.split_once("://") {
+        Some(("https", host)) => format!("wss://{host}"),
+        Some((_, host)) => format!("ws://{host}"),
+        None => format!("wss://{api_url}"),
+    };
+    format!("{url}{WEBSOCKET_PATH}")
+}
+
+/// Events of the API of a network, pushed over a websocket.
+///
+/// Usage:
+/// ```rust,no_run
+/// # #[cfg(feature = "websocket")]
+/// # async fn balances() {
+/// use std::str::FromStr;
+/// use stacks_rs::address::stacks_address::StacksAddress;
+/// use stacks_rs::client::websocket::{ChainEvent, EventStream, Subscription};
+/// use stacks_rs::network::StacksNetwork;
+/// let mut events = EventStream::connect(&StacksNetwork::mainnet()).await.unwrap();
+/// let address = StacksAddress::from_str("SP2J6ZY48GV1EZ5V2V5RB9MP66SW86PYKKNRV9EJ7").unwrap();
+/// events.subscribe(Subscription::AddressBalance(address)).await.unwrap();
+/// while let Some(event) = events.next_event().await {
+///     if let ChainEvent::AddressBalance(balance) = event.unwrap() {
+///         println!("{} micro-STX", balance.balance);
+///     }
+/// }
+/// # }
+/// ```
+#[cfg(feature = "websocket")]
+pub struct EventStream {
+    socket: tokio_tungstenite::WebSocketStream<tokio_tungstenite::MaybeTlsStream<tokio::net::TcpStream>>,
+    session: SubscriptionSession,
+}
+
+#[cfg(feature = "websocket")]
+impl EventStream {
+    /// Connects to the websocket of the API URL of `network`.
+    pub async fn connect(network: &crate::network::StacksNetwork) -> Result<Self, ClientError> {
+        Self::connect_url(&websocket_url(&network.api_url)).await
+    }
+
+    pub async fn connect_url(url: &str) -> Result<Self, ClientError> {
+        let (socket, _) = tokio_tungstenite::connect_async(url).await.map_err(|e| ClientError::Transport(e.to_string()))?;
+        Ok(EventStream { socket, session: SubscriptionSession::new() })
+    }
+
+    async fn send(&mut self, request: String) -> Result<(), ClientError> {
+        use futures_util::SinkExt;
+        self.socket.send(tokio_tungstenite::tungstenite::Message::text(request)).await.map_err(|e| ClientError::Transport(e.to_string()))
+    }
+
+    pub async fn subscribe(&mut self, subscription: Subscription) -> Result<(), ClientError> {
+        let request = self.session.subscribe(subscription);
+        self.send(request).await
+    }
+
+    pub async fn unsubscribe(&mut self, subscription: &Subscription) -> Result<(), ClientError> {
+        let request = self.session.unsubscribe(subscription);
+        self.send(request).await
+    }
+
+    pub fn session(&self) -> &SubscriptionSession {
+        &self.session
+    }
+
+    /// The next event, `None` once the API closed the websocket.
+    pub async fn next_event(&mut self) -> Option<Result<ChainEvent, ClientError>> {
+        use futures_util::StreamExt;
+        use tokio_tungstenite::tungstenite::Message;
+        loop {
+            let message = match self.socket.next().await? {
+                Ok(message) => message,
+                Err(error) => return Some(Err(ClientError::Transport(error.to_string()))),
+            };
+            match message {
+                Message::Text(text) => match self.session.receive(text.as_str()) {
+                    Ok(Some(event)) => return Some(Ok(event)),
+                    Ok(None) => continue,
+                    Err(error) => return Some(Err(error)),
+                },
+                Message::Close(_) => return None,
+                // tungstenite answers pings itself
+                _ => continue,
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use super::super::types::TxStatus;
+    use super::*;
+
+    const ADDRESS: &str = "SP2J6ZY48GV1EZ5V2V5RB9MP66SW86PYKKNRV9EJ7";
+
+    #[test]
+    fn test_requests() {
+        let mut session = SubscriptionSession::new();
+        let address = StacksAddress::from_str(ADDRESS).unwrap();
+        let request: Value = serde_json::from_str(&session.subscribe(Subscription::AddressTransactions(address))).unwrap();
+        assert_eq!(request, json!({"jsonrpc": "2.0", "id": 0, "method": "subscribe", "params": {"event": "address_tx_update", "address": ADDRESS}}));
+        let request: Value = serde_json::from_str(&session.subscribe(Subscription::Transaction(String::from("ab")))).unwrap();
+        assert_eq!(request["params"], json!({"event": "tx_update", "tx_id": "0xab"}));
+        let request: Value = serde_json::from_str(&session.unsubscribe(&Subscription::AddressTransactions(address))).unwrap();
+        assert_eq!((request["id"].as_u64(), request["method"].as_str()), (Some(2), Some("unsubscribe")));
+        assert_eq!(session.subscriptions().collect::<Vec<_>>(), vec![&Subscription::Transaction(String::from("ab"))]);
+
+        let refused = r#"{"jsonrpc": "2.0", "id": 1, "error": {"code": -32602, "message": "Invalid params"}}"#;
+        assert!(matches!(session.receive(refused), Err(ClientError::Subscription(_))));
+        assert_eq!(session.subscriptions().count(), 0);
+
+        assert_eq!(websocket_url("https://api.hiro.so/"), "wss://api.hiro.so/extended/v1/ws");
+        assert_eq!(websocket_url("http://localhost:3999"), "ws://localhost:3999/extended/v1/ws");
+    }
+
+    #[test]
+    fn test_events() {
+        let mut session = SubscriptionSession::new();
+        let transaction = format!(
+            r#"{{"tx_id": "0x01", "tx_status": "success", "tx_type": "token_transfer", "nonce": 3, "fee_rate": "180",
+            "sender_address": "{ADDRESS}", "sponsored": false, "block_height": 10, "tx_result": {{"hex": "0x0703", "repr": "(ok true)"}}}}"#
+        );
+        let update = format!(r#"{{"jsonrpc": "2.0", "method": "address_tx_update", "params": {{"address": "{ADDRESS}", "tx_id": "0x01", "tx_status": "success", "tx": {transaction}}}}}"#);
+        match session.receive(&update).unwrap() {
+            Some(ChainEvent::AddressTransaction { address, transaction }) => {
+                assert_eq!(address, ADDRESS);
+                assert_eq!((transaction.tx_status, transaction.block_height), (TxStatus::Success, Some(10)));
+            }
+            event => panic!("unexpected event {event:?}"),
+        }
+
+        let balance = format!(
+            r#"{{"jsonrpc": "2.0", "method": "address_balance_update", "params": {{"address": "{ADDRESS}", "balance": "340282366920938463463374607431768211455",
+            "total_sent": "10", "total_received": "20", "total_fees_sent": "1", "total_miner_rewards_received": "0", "lock_tx_id": "", "locked": "0",
+            "lock_height": 0, "burnchain_lock_height": 0, "burnchain_unlock_height": 0}}}}"#
+        );
+        match session.receive(&balance).unwrap() {
+            Some(ChainEvent::AddressBalance(balance)) => assert_eq!((balance.balance, balance.total_received), (u128::MAX, 20)),
+            event => panic!("unexpected event {event:?}"),
+        }
+
+        let block = r#"{"jsonrpc": "2.0", "method": "block", "params": {"canonical": true, "height": 5, "hash": "0xaa", "block_time": 1730000000,
+            "tenure_height": 2, "index_block_hash": "0xbb", "parent_block_hash": "0xcc", "burn_block_time": 1729999900, "burn_block_hash": "0xee",
+            "burn_block_height": 100, "miner_txid": "0xff", "txs": ["0x01"]}}"#;
+        assert!(matches!(session.receive(block).unwrap(), Some(ChainEvent::Block(block)) if block.height == 5));
+        assert_eq!(session.receive(r#"{"jsonrpc": "2.0", "method": "nft_event", "params": {}}"#).unwrap(), None);
+        assert!(matches!(session.receive("not json"), Err(ClientError::Decode(_))));
+    }
+}
@@ -0,0 +1,224 @@
+//! Arithmetic modulo the secp256k1 group order, including zero and the point at infinity,
+//! built on the tweak operations of the `secp256k1` crate.
+
+use rand::{CryptoRng, RngCore};
+use secp256k1::constants::CURVE_ORDER;
+use secp256k1::{PublicKey, SecretKey};
+
+use crate::crypto::context::secp256k1_context;
+use crate::crypto::utils;
+
+/// Scalar mod n, `None` standing for zero (which [`SecretKey`] cannot hold).
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) struct Scalar(Option<SecretKey>);
+
+impl Scalar {
+    pub const ZERO: Scalar = Scalar(None);
+
+    pub fn one() -> Self {
+        Self::from_u32(1)
+    }
+
+    pub fn from_u32(value: u32) -> Self {
+        let mut bytes = [0u8; 32];
+        bytes[28..].copy_from_slice(&value.to_be_bytes());
+        Self::reduce(&bytes)
+    }
+
+    pub fn from_secret_key(key: &SecretKey) -> Self {
+        Self(Some(*key))
+    }
+
+    /// Parses a big-endian scalar, rejecting values not below n.
+    pub fn from_bytes(bytes: &[u8; 32]) -> Option<Self> {
+        if *bytes >= CURVE_ORDER {
+            return None;
+        }
+        Some(Self(SecretKey::from_byte_array(bytes).ok()))
+    }
+
+    /// Uniformly random non-zero scalar.
+    #[cfg_attr(not(feature = "frost"), allow(dead_code))]
+    pub fn random_with_rng<R: RngCore + CryptoRng>(rng: &mut R) -> Self {
+        loop {
+            let mut bytes = [0u8; 32];
+            utils::generate_random_bytes_with_rng(rng, &mut bytes, 32);
+            if let Ok(key) = SecretKey::from_byte_array(&bytes) {
+                return Self(Some(key));
+            }
+        }
+    }
+
+    /// Interprets `bytes` as a big-endian integer reduced mod n (e.g. a hash output).
+    pub fn reduce(bytes: &[u8; 32]) -> Self {
+        if *bytes < CURVE_ORDER {
+            return Self(SecretKey::from_byte_array(bytes).ok());
+        }
+        // 2^256 < 2n, so a single subtraction is enough
+        let mut reduced = [0u8; 32];
+        let mut borrow = 0i16;
+        for i in (0..32).rev() {
+            let mut digit = bytes[i] as i16 - CURVE_ORDER[i] as i16 - borrow;
+            borrow = (digit < 0) as i16;
+            if digit < 0 {
+                digit += 256;
+            }
+            reduced[i] = digit as u8;
+        }
+        Self(SecretKey::from_byte_array(&reduced).ok())
+    }
+
+    pub fn to_bytes(self) -> [u8; 32] {
+        self.0.map(|key| key.secret_bytes()).unwrap_or([0u8; 32])
+    }
+
+    fn tweak(key: SecretKey) -> secp256k1::Scalar {
+        secp256k1::Scalar::from(key)
+    }
+
+    pub fn add(self, other: Self) -> Self {
+        match (self.0, other.0) {
+            (None, _) => other,
+            (_, None) => self,
+            // the only failure is a zero sum
+            (Some(a), Some(b)) => Self(a.add_tweak(&Self::tweak(b)).ok()),
+        }
+    }
+
+    pub fn neg(self) -> Self {
+        Self(self.0.map(|key| key.negate()))
+    }
+
+    #[cfg_attr(not(feature = "frost"), allow(dead_code))]
+    pub fn sub(self, other: Self) -> Self {
+        self.add(other.neg())
+    }
+
+    pub fn mul(self, other: Self) -> Self {
+        match (self.0, other.0) {
+            (Some(a), Some(b)) => Self(a.mul_tweak(&Self::tweak(b)).ok()),
+            _ => Self::ZERO,
+        }
+    }
+
+    /// Multiplicative inverse (`self^(n-2)`), zero for zero.
+    #[cfg_attr(not(feature = "frost"), allow(dead_code))]
+    pub fn invert(self) -> Self {
+        let mut exponent = CURVE_ORDER;
+        exponent[31] -= 2;
+        let mut result = Self::one();
+        for byte in exponent {
+            for bit in (0..8).rev() {
+                result = result.mul(result);
+                if (byte >> bit) & 1 == 1 {
+                    result = result.mul(self);
+                }
+            }
+        }
+        result
+    }
+}
+
+/// Curve point, `None` standing for the point at infinity.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) struct Point(Option<PublicKey>);
+
+impl Point {
+    pub const INFINITY: Point = Point(None);
+
+    pub fn from_public_key(key: &PublicKey) -> Self {
+        Self(Some(*key))
+    }
+
+    /// `scalar * G`
+    pub fn mul_base(scalar: Scalar) -> Self {
+        Self(scalar.0.map(|key| key.public_key(secp256k1_context())))
+    }
+
+    pub fn public_key(self) -> Option<PublicKey> {
+        self.0
+    }
+
+    pub fn is_infinity(self) -> bool {
+        self.0.is_none()
+    }
+
+    pub fn add(self, other: Self) -> Self {
+        match (self.0, other.0) {
+            (None, _) => other,
+            (_, None) => self,
+            (Some(a), Some(b)) => Self(a.combine(&b).ok()),
+        }
+    }
+
+    pub fn neg(self) -> Self {
+        Self(self.0.map(|key| key.negate(secp256k1_context())))
+    }
+
+    pub fn mul(self, scalar: Scalar) -> Self {
+        match (self.0, scalar.0) {
+            (Some(point), Some(scalar)) => Self(point.mul_tweak(secp256k1_context(), &Scalar::tweak(scalar)).ok()),
+            _ => Self::INFINITY,
+        }
+    }
+
+    /// Whether the y coordinate is even; false for infinity.
+    pub fn has_even_y(self) -> bool {
+        self.0.is_some_and(|key| key.serialize()[0] == 0x02)
+    }
+
+    /// x coordinate, zero for infinity.
+    pub fn x_bytes(self) -> [u8; 32] {
+        self.0.map(|key| key.serialize()[1..].try_into().unwrap()).unwrap_or([0u8; 32])
+    }
+
+    /// Compressed encoding, 33 zero bytes for infinity.
+    pub fn to_bytes_ext(self) -> [u8; 33] {
+        self.0.map(|key| key.serialize()).unwrap_or([0u8; 33])
+    }
+
+    /// Parses [`Point::to_bytes_ext`].
+    pub fn from_bytes_ext(bytes: &[u8]) -> Option<Self> {
+        if bytes.len() == 33 && bytes.iter().all(|byte| *byte == 0) {
+            return Some(Self::INFINITY);
+        }
+        PublicKey::from_slice(bytes).ok().filter(|_| bytes.len() == 33).map(|key| Self(Some(key)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_scalar_arithmetic() {
+        let two = Scalar::from_u32(2);
+        let three = Scalar::from_u32(3);
+        assert_eq!(two.add(three), Scalar::from_u32(5));
+        assert_eq!(two.mul(three), Scalar::from_u32(6));
+        assert_eq!(two.add(two.neg()), Scalar::ZERO);
+        assert_eq!(three.sub(two), Scalar::one());
+        assert_eq!(three.mul(three.invert()), Scalar::one());
+        assert_eq!(Scalar::ZERO.invert(), Scalar::ZERO);
+
+        // n + 1 reduces to 1, n reduces to 0
+        let mut bytes = CURVE_ORDER;
+        assert_eq!(Scalar::reduce(&bytes), Scalar::ZERO);
+        assert_eq!(Scalar::from_bytes(&bytes), None);
+        bytes[31] += 1;
+        assert_eq!(Scalar::reduce(&bytes), Scalar::one());
+        assert_eq!(hex::encode(Scalar::reduce(&[0xff; 32]).to_bytes()), "000000000000000000000000000000014551231950b75fc4402da1732fc9bebe");
+    }
+
+    #[test]
+    fn test_point_arithmetic() {
+        let g = Point::mul_base(Scalar::one());
+        assert_eq!(g.add(g), Point::mul_base(Scalar::from_u32(2)));
+        assert_eq!(g.mul(Scalar::from_u32(3)), Point::mul_base(Scalar::from_u32(3)));
+        assert_eq!(g.add(g.neg()), Point::INFINITY);
+        assert_eq!(g.mul(Scalar::ZERO), Point::INFINITY);
+        assert_eq!(Point::from_bytes_ext(&Point::INFINITY.to_bytes_ext()), Some(Point::INFINITY));
+        assert_eq!(Point::from_bytes_ext(&g.to_bytes_ext()), Some(g));
+        assert!(g.has_even_y());
+    }
+}
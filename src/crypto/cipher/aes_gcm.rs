@@ -0,0 +1,86 @@
+use ::aes_gcm::aead::{Aead, KeyInit, Payload};
+use ::aes_gcm::Aes256Gcm as Aes256GcmImpl;
+
+use super::{Cipher, CipherError, Nonce};
+
+/// AES-256-GCM.
+///
+/// Usage:
+/// ```rust
+/// use stacks_rs::crypto::cipher::{aes_gcm::Aes256Gcm, Cipher};
+/// let cipher = Aes256Gcm::new(&[0x42u8; 32]).unwrap();
+/// let sealed = cipher.seal(b"hello", b"header").unwrap();
+/// assert_eq!(cipher.open(&sealed, b"header").unwrap(), b"hello");
+/// ```
+#[derive(Clone)]
+pub struct Aes256Gcm {
+    cipher: Aes256GcmImpl,
+}
+
+impl Cipher for Aes256Gcm {
+    const KEY_LENGTH: usize = 32;
+
+    fn new(key: &[u8]) -> Result<Self, CipherError> {
+        let cipher = Aes256GcmImpl::new_from_slice(key).map_err(|_| CipherError::InvalidKeyLength(key.len()))?;
+        Ok(Self { cipher })
+    }
+
+    fn encrypt(&self, nonce: &Nonce, plaintext: &[u8], aad: &[u8]) -> Result<Vec<u8>, CipherError> {
+        self.cipher
+            .encrypt(nonce.into(), Payload { msg: plaintext, aad })
+            .map_err(|_| CipherError::AuthenticationFailed)
+    }
+
+    fn decrypt(&self, nonce: &Nonce, ciphertext: &[u8], aad: &[u8]) -> Result<Vec<u8>, CipherError> {
+        self.cipher
+            .decrypt(nonce.into(), Payload { msg: ciphertext, aad })
+            .map_err(|_| CipherError::AuthenticationFailed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::crypto::cipher::{NonceCounter, NONCE_LENGTH, TAG_LENGTH};
+
+    #[test]
+    fn test_aes256_gcm_vectors() {
+        // NIST GCM spec, test cases 13 and 14
+        let cipher = Aes256Gcm::new(&[0u8; 32]).unwrap();
+        let nonce = [0u8; NONCE_LENGTH];
+        assert_eq!(hex::encode(cipher.encrypt(&nonce, b"", b"").unwrap()), "530f8afbc74536b9a963b4f1c4cb738b");
+        assert_eq!(
+            hex::encode(cipher.encrypt(&nonce, &[0u8; 16], b"").unwrap()),
+            "cea7403d4d606b6e074ec5d3baf39d18d0d1c8a799996bf0265b98b5d48ab919"
+        );
+    }
+
+    #[test]
+    fn test_seal_open() {
+        let cipher = Aes256Gcm::new(&[0x42u8; 32]).unwrap();
+        let sealed = cipher.seal(b"attack at dawn", b"aad").unwrap();
+        assert_eq!(sealed.len(), NONCE_LENGTH + 14 + TAG_LENGTH);
+        assert_eq!(cipher.open(&sealed, b"aad").unwrap(), b"attack at dawn");
+
+        assert_eq!(cipher.open(&sealed, b"other aad"), Err(CipherError::AuthenticationFailed));
+        let mut tampered = sealed.clone();
+        tampered[NONCE_LENGTH] ^= 1;
+        assert_eq!(cipher.open(&tampered, b"aad"), Err(CipherError::AuthenticationFailed));
+        assert_eq!(cipher.open(&sealed[..NONCE_LENGTH + 4], b"aad"), Err(CipherError::Truncated));
+
+        let other = Aes256Gcm::new(&[0x43u8; 32]).unwrap();
+        assert_eq!(other.open(&sealed, b"aad"), Err(CipherError::AuthenticationFailed));
+        assert!(matches!(Aes256Gcm::new(&[0u8; 16]), Err(CipherError::InvalidKeyLength(16))));
+    }
+
+    #[test]
+    fn test_counter_nonces() {
+        let cipher = Aes256Gcm::new(&[0x42u8; 32]).unwrap();
+        let mut nonces = NonceCounter::random();
+        let first = nonces.next_nonce().unwrap();
+        let second = nonces.next_nonce().unwrap();
+        assert_ne!(cipher.encrypt(&first, b"same", b"").unwrap(), cipher.encrypt(&second, b"same", b"").unwrap());
+        let ciphertext = cipher.encrypt(&second, b"same", b"").unwrap();
+        assert_eq!(cipher.decrypt(&second, &ciphertext, b"").unwrap(), b"same");
+    }
+}
@@ -0,0 +1,143 @@
+//! AEAD ciphers sealing the keystores.
+//!
+//! Gaia content is not sealed with them: stacks.js `encryptContent` uses ECIES, see [`crate::crypto::ecies`].
+
+use std::fmt;
+
+use rand::rngs::OsRng;
+use rand::{CryptoRng, RngCore};
+
+use super::utils;
+
+pub mod aes_gcm;
+pub mod chacha20_poly1305;
+
+/// Length of the nonces of the AEAD ciphers (96 bits).
+pub const NONCE_LENGTH: usize = 12;
+/// Length of the authentication tag appended to the ciphertext.
+pub const TAG_LENGTH: usize = 16;
+
+pub type Nonce = [u8; NONCE_LENGTH];
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CipherError {
+    InvalidKeyLength(usize),
+    /// The sealed message is shorter than a nonce and a tag
+    Truncated,
+    /// Wrong key, nonce or AAD, or the message was tampered with
+    AuthenticationFailed,
+    /// The nonce counter wrapped around
+    NonceExhausted,
+}
+
+impl fmt::Display for CipherError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        match self {
+            CipherError::InvalidKeyLength(v) => f.write_str(&format!("Invalid key length {v}")),
+            CipherError::Truncated => f.write_str("Sealed message is truncated"),
+            CipherError::AuthenticationFailed => f.write_str("Authentication failed"),
+            CipherError::NonceExhausted => f.write_str("Nonce counter exhausted"),
+        }
+    }
+}
+
+impl std::error::Error for CipherError {}
+
+/// Authenticated encryption with associated data, with 96-bit nonces and 128-bit tags.
+pub trait Cipher: Sized {
+    const KEY_LENGTH: usize;
+
+    fn new(key: &[u8]) -> Result<Self, CipherError>;
+
+    /// Returns `ciphertext || tag`. A `nonce` must never be reused with the same key.
+    fn encrypt(&self, nonce: &Nonce, plaintext: &[u8], aad: &[u8]) -> Result<Vec<u8>, CipherError>;
+
+    /// Inverse of [`Cipher::encrypt`], fails if the tag doesn't match.
+    fn decrypt(&self, nonce: &Nonce, ciphertext: &[u8], aad: &[u8]) -> Result<Vec<u8>, CipherError>;
+
+    /// Encrypts with a random nonce, returns `nonce || ciphertext || tag`.
+    fn seal(&self, plaintext: &[u8], aad: &[u8]) -> Result<Vec<u8>, CipherError> {
+        self.seal_with_rng(plaintext, aad, &mut OsRng)
+    }
+
+    /// Same as [`Cipher::seal`] but draws the nonce from `rng`.
+    fn seal_with_rng<R: RngCore + CryptoRng>(&self, plaintext: &[u8], aad: &[u8], rng: &mut R) -> Result<Vec<u8>, CipherError> {
+        let nonce = random_nonce_with_rng(rng);
+        Ok([&nonce[..], &self.encrypt(&nonce, plaintext, aad)?].concat())
+    }
+
+    /// Inverse of [`Cipher::seal`].
+    fn open(&self, sealed: &[u8], aad: &[u8]) -> Result<Vec<u8>, CipherError> {
+        if sealed.len() < NONCE_LENGTH + TAG_LENGTH {
+            return Err(CipherError::Truncated);
+        }
+        let (nonce, ciphertext) = sealed.split_at(NONCE_LENGTH);
+        self.decrypt(nonce.try_into().unwrap(), ciphertext, aad)
+    }
+}
+
+pub fn random_nonce() -> Nonce {
+    random_nonce_with_rng(&mut OsRng)
+}
+
+pub fn random_nonce_with_rng<R: RngCore + CryptoRng>(rng: &mut R) -> Nonce {
+    let mut nonce = [0u8; NONCE_LENGTH];
+    utils::generate_random_bytes_with_rng(rng, &mut nonce, NONCE_LENGTH);
+    nonce
+}
+
+/// Deterministic nonces for many messages under the same key: a fixed 4-byte prefix
+/// followed by a 64-bit big-endian counter.
+#[derive(Clone, Debug)]
+pub struct NonceCounter {
+    prefix: [u8; 4],
+    counter: u64,
+    exhausted: bool,
+}
+
+impl NonceCounter {
+    pub fn new(prefix: [u8; 4]) -> Self {
+        Self { prefix, counter: 0, exhausted: false }
+    }
+
+    /// Counter with a random prefix, so that different senders sharing a key don't collide.
+    pub fn random() -> Self {
+        Self::random_with_rng(&mut OsRng)
+    }
+
+    pub fn random_with_rng<R: RngCore + CryptoRng>(rng: &mut R) -> Self {
+        let mut prefix = [0u8; 4];
+        utils::generate_random_bytes_with_rng(rng, &mut prefix, 4);
+        Self::new(prefix)
+    }
+
+    pub fn next_nonce(&mut self) -> Result<Nonce, CipherError> {
+        if self.exhausted {
+            return Err(CipherError::NonceExhausted);
+        }
+        let mut nonce = [0u8; NONCE_LENGTH];
+        nonce[..4].copy_from_slice(&self.prefix);
+        nonce[4..].copy_from_slice(&self.counter.to_be_bytes());
+        match self.counter.checked_add(1) {
+            Some(counter) => self.counter = counter,
+            None => self.exhausted = true,
+        }
+        Ok(nonce)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_nonce_counter() {
+        let mut nonces = NonceCounter::new([1, 2, 3, 4]);
+        assert_eq!(hex::encode(nonces.next_nonce().unwrap()), "010203040000000000000000");
+        assert_eq!(hex::encode(nonces.next_nonce().unwrap()), "010203040000000000000001");
+
+        let mut nonces = NonceCounter { prefix: [0; 4], counter: u64::MAX, exhausted: false };
+        assert_eq!(hex::encode(nonces.next_nonce().unwrap()), "00000000ffffffffffffffff");
+        assert_eq!(nonces.next_nonce(), Err(CipherError::NonceExhausted));
+    }
+}
@@ -0,0 +1,28 @@
+pub use subtle::{Choice, ConstantTimeEq};
+
+/// Compares `a` and `b` in a time that depends only on their lengths, not on their contents.
+/// Use it instead of `==` on keys, MACs and other secret material.
+///
+/// Usage:
+/// ```rust
+/// use stacks_rs::crypto::constant_time::ct_eq;
+/// assert!(ct_eq(&[1, 2, 3], &[1, 2, 3]));
+/// assert!(!ct_eq(&[1, 2, 3], &[1, 2, 4]));
+/// ```
+pub fn ct_eq(a: &[u8], b: &[u8]) -> bool {
+    a.ct_eq(b).into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ct_eq() {
+        assert!(ct_eq(&[], &[]));
+        assert!(ct_eq(&[0xaa; 32], &[0xaa; 32]));
+        assert!(!ct_eq(&[0xaa; 32], &[0xab; 32]));
+        // different lengths never match
+        assert!(!ct_eq(&[0xaa; 32], &[0xaa; 31]));
+    }
+}
@@ -0,0 +1,21 @@
+use std::sync::OnceLock;
+
+use secp256k1::{All, Secp256k1};
+
+static SECP256K1_CONTEXT: OnceLock<Secp256k1<All>> = OnceLock::new();
+
+/// Returns the process-wide secp256k1 context (signing + verification).
+///
+/// The context is created lazily on first use and then shared by every key operation,
+/// since building a fresh [`Secp256k1`] context is far more expensive than the operations themselves.
+///
+/// Usage:
+/// ```rust
+/// use stacks_rs::crypto::context::secp256k1_context;
+/// use secp256k1::SecretKey;
+/// let s_key = SecretKey::from_byte_array(&[1u8; 32]).unwrap();
+/// let p_key = s_key.public_key(secp256k1_context());
+/// ```
+pub fn secp256k1_context() -> &'static Secp256k1<All> {
+    SECP256K1_CONTEXT.get_or_init(Secp256k1::new)
+}
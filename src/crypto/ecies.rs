@@ -0,0 +1,227 @@
+use std::fmt;
+
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+use rand::rngs::OsRng;
+use rand::{CryptoRng, RngCore};
+use secp256k1::{PublicKey, Scalar, SecretKey};
+use serde::{Deserialize, Serialize};
+
+use crate::crypto::context::secp256k1_context;
+use crate::crypto::encryption::{cbc_decrypt, cbc_encrypt, Aes256CbcDec, Aes256CbcEnc};
+use crate::crypto::hash::{Hasher, Sha512};
+use crate::crypto::hmac::{Hmac, HmacSha256};
+use crate::crypto::utils;
+
+pub const IV_LENGTH: usize = 16;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum EciesError {
+    InvalidPublicKey,
+    /// A field of the cipher object is not valid hex/base64 or JSON
+    InvalidEncoding(String),
+    /// The MAC does not match, i.e. wrong key or tampered content
+    MacMismatch,
+    AesUnpadError,
+}
+
+impl fmt::Display for EciesError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        match self {
+            EciesError::InvalidPublicKey => f.write_str("Invalid ephemeral public key"),
+            EciesError::InvalidEncoding(v) => f.write_str(&format!("Invalid cipher object: {v}")),
+            EciesError::MacMismatch => f.write_str("MAC mismatch"),
+            EciesError::AesUnpadError => f.write_str("AES unpad error"),
+        }
+    }
+}
+
+impl std::error::Error for EciesError {}
+
+/// Encoding of [`CipherObject::cipher_text`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CipherTextEncoding {
+    #[default]
+    Hex,
+    Base64,
+}
+
+/// Encrypted content in the JSON layout of `@stacks/encryption`.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CipherObject {
+    pub iv: String,
+    #[serde(rename = "ephemeralPK")]
+    pub ephemeral_pk: String,
+    pub cipher_text: String,
+    pub mac: String,
+    pub was_string: bool,
+    /// Absent (hex) in objects produced by older stacks.js versions
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cipher_text_encoding: Option<CipherTextEncoding>,
+}
+
+impl CipherObject {
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).unwrap()
+    }
+
+    pub fn from_json(json: &str) -> Result<Self, EciesError> {
+        serde_json::from_str(json).map_err(|err| EciesError::InvalidEncoding(format!("{err}")))
+    }
+}
+
+/// AES-256 and HMAC-SHA256 keys, the two halves of `sha512(x coordinate of the ECDH point)`.
+fn shared_keys(secret_key: &SecretKey, public_key: &PublicKey) -> ([u8; 32], [u8; 32]) {
+    let point = public_key.mul_tweak(secp256k1_context(), &Scalar::from(*secret_key)).unwrap();
+    let digest = Sha512::hash(&point.serialize()[1..]);
+    (digest[..32].try_into().unwrap(), digest[32..].try_into().unwrap())
+}
+
+fn mac(hmac_key: &[u8], iv: &[u8], ephemeral_pk: &[u8], cipher_text: &[u8]) -> Hmac<HmacSha256> {
+    let mut hmac = Hmac::<HmacSha256>::new(hmac_key).unwrap();
+    hmac.update(iv).update(ephemeral_pk).update(cipher_text);
+    hmac
+}
+
+/// Encrypts `content` to `public_key` like stacks.js `encryptECIES`.
+/// `was_string` tells the decrypting side to return a string, as stacks.js does.
+///
+/// Usage:
+/// ```rust
+/// use secp256k1::SecretKey;
+/// use stacks_rs::crypto::context::secp256k1_context;
+/// use stacks_rs::crypto::ecies::{decrypt_ecies, encrypt_ecies, CipherTextEncoding};
+/// let key = SecretKey::from_byte_array(&[1u8; 32]).unwrap();
+/// let cipher = encrypt_ecies(&key.public_key(secp256k1_context()), b"hello", true, CipherTextEncoding::Hex);
+/// assert_eq!(decrypt_ecies(&key, &cipher).unwrap(), b"hello");
+/// ```
+pub fn encrypt_ecies(public_key: &PublicKey, content: &[u8], was_string: bool, encoding: CipherTextEncoding) -> CipherObject {
+    encrypt_ecies_with_rng(public_key, content, was_string, encoding, &mut OsRng)
+}
+
+/// Same as [`encrypt_ecies`] but draws the ephemeral key and the IV from `rng`.
+pub fn encrypt_ecies_with_rng<R: RngCore + CryptoRng>(
+    public_key: &PublicKey,
+    content: &[u8],
+    was_string: bool,
+    encoding: CipherTextEncoding,
+    rng: &mut R,
+) -> CipherObject {
+    let ephemeral_key = loop {
+        let mut bytes = [0u8; 32];
+        utils::generate_random_bytes_with_rng(rng, &mut bytes, 32);
+        if let Ok(key) = SecretKey::from_byte_array(&bytes) {
+            break key;
+        }
+    };
+    let mut iv = [0u8; IV_LENGTH];
+    utils::generate_random_bytes_with_rng(rng, &mut iv, IV_LENGTH);
+    encrypt_with_ephemeral_key(public_key, content, was_string, encoding, &ephemeral_key, &iv)
+}
+
+fn encrypt_with_ephemeral_key(
+    public_key: &PublicKey,
+    content: &[u8],
+    was_string: bool,
+    encoding: CipherTextEncoding,
+    ephemeral_key: &SecretKey,
+    iv: &[u8; IV_LENGTH],
+) -> CipherObject {
+    let (encryption_key, hmac_key) = shared_keys(ephemeral_key, public_key);
+    let cipher_text = cbc_encrypt::<Aes256CbcEnc>(&encryption_key, iv, content);
+    let ephemeral_pk = ephemeral_key.public_key(secp256k1_context()).serialize();
+    let mac = mac(&hmac_key, iv, &ephemeral_pk, &cipher_text).finalize();
+
+    CipherObject {
+        iv: hex::encode(iv),
+        ephemeral_pk: hex::encode(ephemeral_pk),
+        cipher_text: match encoding {
+            CipherTextEncoding::Hex => hex::encode(&cipher_text),
+            CipherTextEncoding::Base64 => BASE64.encode(&cipher_text),
+        },
+        mac: hex::encode(mac),
+        was_string,
+        cipher_text_encoding: (encoding == CipherTextEncoding::Base64).then_some(encoding),
+    }
+}
+
+/// Decrypts a cipher object addressed to `private_key`, checking its MAC first.
+pub fn decrypt_ecies(private_key: &SecretKey, cipher: &CipherObject) -> Result<Vec<u8>, EciesError> {
+    let decode_hex = |field: &str| hex::decode(field).map_err(|err| EciesError::InvalidEncoding(format!("{err}")));
+    let iv = decode_hex(&cipher.iv)?;
+    if iv.len() != IV_LENGTH {
+        return Err(EciesError::InvalidEncoding(format!("IV length {}", iv.len())));
+    }
+    let ephemeral_pk = PublicKey::from_slice(&decode_hex(&cipher.ephemeral_pk)?).map_err(|_| EciesError::InvalidPublicKey)?;
+    let cipher_text = match cipher.cipher_text_encoding.unwrap_or_default() {
+        CipherTextEncoding::Hex => decode_hex(&cipher.cipher_text)?,
+        CipherTextEncoding::Base64 => BASE64
+            .decode(&cipher.cipher_text)
+            .map_err(|err| EciesError::InvalidEncoding(format!("{err}")))?,
+    };
+
+    let (encryption_key, hmac_key) = shared_keys(private_key, &ephemeral_pk);
+    mac(&hmac_key, &iv, &ephemeral_pk.serialize(), &cipher_text)
+        .verify(&decode_hex(&cipher.mac)?)
+        .map_err(|_| EciesError::MacMismatch)?;
+    cbc_decrypt::<Aes256CbcDec>(&encryption_key, &iv, &cipher_text).map_err(|_| EciesError::AesUnpadError)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Private key of the recipient of the stacks.js encryption tests.
+    fn recipient() -> SecretKey {
+        SecretKey::from_byte_array(&hex::decode("a5c61c6ca7b3e7e55edee68566aeab22e4da26baa285c7bd10e8d2218aa3b229").unwrap().try_into().unwrap()).unwrap()
+    }
+
+    #[test]
+    fn test_known_answer() {
+        // reproduced with Python `cryptography`: SHA-512 of the ECDH x coordinate split into the
+        // AES-256-CBC and HMAC-SHA256 keys, MAC over `iv || ephemeral key || ciphertext`
+        let public_key = recipient().public_key(secp256k1_context());
+        assert_eq!(hex::encode(public_key.serialize()), "027d28f9951ce46538951e3697c62588a87f1f1f295de4a14fdd4c780fc52cfe69");
+        let ephemeral_key = SecretKey::from_byte_array(&[2u8; 32]).unwrap();
+        let cipher = encrypt_with_ephemeral_key(
+            &public_key,
+            b"hello world",
+            true,
+            CipherTextEncoding::Hex,
+            &ephemeral_key,
+            &[3u8; IV_LENGTH],
+        );
+        assert_eq!(cipher.ephemeral_pk, "024d4b6cd1361032ca9bd2aeb9d900aa4d45d9ead80ac9423374c451a7254d0766");
+        assert_eq!(cipher.cipher_text, "7c28342fa5eb824f8f3b39879efde671");
+        assert_eq!(cipher.mac, "695ab1bc387a7ea5899df7a51c49aa9fae49bad3db7859c82fec35aa5a97766e");
+        assert_eq!(decrypt_ecies(&recipient(), &cipher).unwrap(), b"hello world");
+    }
+
+    #[test]
+    fn test_json_roundtrip() {
+        let public_key = recipient().public_key(secp256k1_context());
+        for encoding in [CipherTextEncoding::Hex, CipherTextEncoding::Base64] {
+            let cipher = encrypt_ecies(&public_key, b"hello world", false, encoding);
+            let json = cipher.to_json();
+            assert_eq!(json.contains("cipherTextEncoding"), encoding == CipherTextEncoding::Base64);
+            let parsed = CipherObject::from_json(&json).unwrap();
+            assert_eq!(parsed, cipher);
+            assert_eq!(decrypt_ecies(&recipient(), &parsed).unwrap(), b"hello world");
+        }
+    }
+
+    #[test]
+    fn test_tampering() {
+        let cipher = encrypt_ecies(&recipient().public_key(secp256k1_context()), b"hello world", true, CipherTextEncoding::Hex);
+        let other = SecretKey::from_byte_array(&[1u8; 32]).unwrap();
+        assert_eq!(decrypt_ecies(&other, &cipher), Err(EciesError::MacMismatch));
+
+        let mut tampered = cipher.clone();
+        tampered.cipher_text.replace_range(0..2, if &cipher.cipher_text[0..2] == "00" { "01" } else { "00" });
+        assert_eq!(decrypt_ecies(&recipient(), &tampered), Err(EciesError::MacMismatch));
+
+        assert!(matches!(CipherObject::from_json("{}"), Err(EciesError::InvalidEncoding(_))));
+    }
+}
@@ -5,6 +5,8 @@ use aes::cipher::{
 
 pub type Aes128CbcEnc = cbc::Encryptor<aes::Aes128>;
 pub type Aes128CbcDec = cbc::Decryptor<aes::Aes128>;
+pub type Aes256CbcEnc = cbc::Encryptor<aes::Aes256>;
+pub type Aes256CbcDec = cbc::Decryptor<aes::Aes256>;
 
 /// Returns resulting ciphertext (Uses Pkcs7 padding)
 ///
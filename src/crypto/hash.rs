@@ -1,3 +1,4 @@
+use ripemd::Ripemd160;
 use sha2::Digest;
 
 pub type U32 = aes::cipher::consts::U32;
@@ -34,16 +35,114 @@ where
     hasher.finalize().to_vec()
 }
 
+/// Hash function with a fixed-size output.
+///
+/// Usage:
+/// ```rust
+/// use stacks_rs::crypto::hash::{Hash160, Hasher, Sha256};
+/// let digest = Sha256::hash("aaaaaa".as_bytes());
+/// let mut hasher = Hash160::default();
+/// hasher.update("aaa".as_bytes()).update("aaa".as_bytes());
+/// assert_eq!(hasher.finalize(), Hash160::hash("aaaaaa".as_bytes()));
+/// ```
+pub trait Hasher: Default {
+    type Output: AsRef<[u8]> + Copy + Eq + std::fmt::Debug;
+
+    fn update(&mut self, data: &[u8]) -> &mut Self;
+    fn finalize(self) -> Self::Output;
+
+    fn hash(data: &[u8]) -> Self::Output {
+        let mut hasher = Self::default();
+        hasher.update(data);
+        hasher.finalize()
+    }
+}
+
+macro_rules! digest_hasher {
+    ($(#[$doc:meta])* $name:ident, $digest:ty, $len:expr) => {
+        $(#[$doc])*
+        #[derive(Clone, Default)]
+        pub struct $name($digest);
+
+        impl Hasher for $name {
+            type Output = [u8; $len];
+
+            fn update(&mut self, data: &[u8]) -> &mut Self {
+                Digest::update(&mut self.0, data);
+                self
+            }
+
+            fn finalize(self) -> Self::Output {
+                self.0.finalize().into()
+            }
+        }
+    };
+}
+
+digest_hasher!(
+    /// SHA-256
+    Sha256, sha2::Sha256, 32
+);
+digest_hasher!(
+    /// SHA-512
+    Sha512, sha2::Sha512, 64
+);
+digest_hasher!(
+    /// SHA-512/256, used by Stacks for txids and block hashes
+    Sha512_256, sha2::Sha512_256, 32
+);
+
+/// SHA-256 of the SHA-256, used by Bitcoin checksums and txids
+#[derive(Clone, Default)]
+pub struct DoubleSha256(sha2::Sha256);
+
+impl Hasher for DoubleSha256 {
+    type Output = [u8; 32];
+
+    fn update(&mut self, data: &[u8]) -> &mut Self {
+        Digest::update(&mut self.0, data);
+        self
+    }
+
+    fn finalize(self) -> Self::Output {
+        sha2::Sha256::digest(self.0.finalize()).into()
+    }
+}
+
+/// RIPEMD-160 of the SHA-256, used for addresses and key fingerprints
+#[derive(Clone, Default)]
+pub struct Hash160(sha2::Sha256);
+
+impl Hasher for Hash160 {
+    type Output = [u8; 20];
+
+    fn update(&mut self, data: &[u8]) -> &mut Self {
+        Digest::update(&mut self.0, data);
+        self
+    }
+
+    fn finalize(self) -> Self::Output {
+        Ripemd160::digest(self.0.finalize()).into()
+    }
+}
+
+/// `SHA256(SHA256(tag) || SHA256(tag) || data)` as defined in BIP340.
+pub fn tagged_hash(tag: &str, data: &[u8]) -> [u8; 32] {
+    let tag_hash = Sha256::hash(tag.as_bytes());
+    let mut hasher = Sha256::default();
+    hasher.update(&tag_hash).update(&tag_hash).update(data);
+    hasher.finalize()
+}
+
 #[cfg(test)]
 mod tests {
     use aes::cipher::consts::{U32, U64};
-    use sha2::{Sha256, Sha512};
 
     use super::*;
 
     #[test]
     fn test_sha256() {
-        let digest = compute_hash::<Sha256, U32>("aaaaaaaaaaaaaaaa".as_bytes());
+        let digest = compute_hash::<sha2::Sha256, U32>("aaaaaaaaaaaaaaaa".as_bytes());
         let hex_digest = hex::encode(digest);
         assert_eq!(
             hex_digest,
@@ -53,8 +152,28 @@ mod tests {
 
     #[test]
     fn test_sha512() {
-        let digest = compute_hash::<Sha512, U64>("aaaaaaaaaaaaaaaa".as_bytes());
+        let digest = compute_hash::<sha2::Sha512, U64>("aaaaaaaaaaaaaaaa".as_bytes());
         let hex_digest = hex::encode(digest);
         assert_eq!(hex_digest, "987d0fc93db6a73fdb16493690fb42455c7c6fbafe9a276965424b12afad3512fb808d902faa8a019d639dc5ad07c235805e08f396147cf435913cfed501f65a")
     }
+
+    #[test]
+    fn test_hashers() {
+        let data = "abc".as_bytes();
+        assert_eq!(hex::encode(Sha256::hash(data)), "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad");
+        assert_eq!(hex::encode(Sha512_256::hash(data)), "53048e2681941ef99b2e29b76b4c7dabe4c2d0c634fc6d46e0e2f13107e7af23");
+        assert_eq!(hex::encode(DoubleSha256::hash(data)), "4f8b42c22dd3729b519ba6f68d2da7cc5b2d606d05daed5ad5128cc03e6c6358");
+        assert_eq!(hex::encode(Hash160::hash(data)), "bb1be98c142444d7a56aa3981c3942a978e4dc33");
+        assert_eq!(Sha512::hash(data).to_vec(), compute_hash::<sha2::Sha512, U64>(data));
+
+        let mut hasher = Sha512_256::default();
+        hasher.update("a".as_bytes()).update("bc".as_bytes());
+        assert_eq!(hasher.finalize(), Sha512_256::hash(data));
+    }
+
+    #[test]
+    fn test_hash160_matches_stacks_common() {
+        let data = "aaaaaaaaaaaaaaaa".as_bytes();
+        assert_eq!(Hash160::hash(data), stacks_common::util::hash::Hash160::from_data(data).0);
+    }
 }
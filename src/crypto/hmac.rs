@@ -1,4 +1,5 @@
 use std::fmt;
+use std::io;
 
 use super::utils;
 use aes::cipher::{
@@ -12,21 +13,25 @@ use hmac::{
         core_api::{BufferKindUser, CoreProxy, FixedOutputCore, UpdateCore},
         HashMarker,
     },
-    Hmac, Mac,
+    Hmac as HmacCore, Mac,
 };
 use sha2::{Sha256, Sha512};
 
-pub type HmacSha256 = Hmac<Sha256>;
-pub type HmacSha512 = Hmac<Sha512>;
+pub type HmacSha256 = HmacCore<Sha256>;
+pub type HmacSha512 = HmacCore<Sha512>;
 
 #[derive(Clone, Copy, Debug)]
 pub enum HmacError {
     InvalidKeyLength(InvalidLength),
+    Mismatch,
 }
 
 impl fmt::Display for HmacError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
-        f.write_str("Invalid Key Length")
+        match self {
+            HmacError::InvalidKeyLength(_) => f.write_str("Invalid Key Length"),
+            HmacError::Mismatch => f.write_str("HMAC mismatch"),
+        }
     }
 }
 impl std::error::Error for HmacError {}
@@ -51,6 +56,61 @@ where
     Ok(hmac_digest.finalize().into_bytes().to_vec())
 }
 
+/// Incremental HMAC, for payloads too large to be buffered in memory.
+///
+/// Also implements [`io::Write`], so a reader can be streamed into it with [`io::copy`].
+///
+/// Usage:
+/// ```
+/// use stacks_rs::crypto::hmac::{compute_hmac, Hmac, HmacSha256};
+/// let mut hmac = Hmac::<HmacSha256>::new("Jefe".as_bytes()).unwrap();
+/// hmac.update("what do ya want ".as_bytes()).update("for nothing?".as_bytes());
+/// let digest = hmac.finalize();
+/// assert_eq!(digest, compute_hmac::<HmacSha256>("what do ya want for nothing?".as_bytes(), "Jefe".as_bytes()).unwrap());
+/// ```
+#[derive(Clone)]
+pub struct Hmac<D> {
+    mac: D,
+}
+
+impl<D> Hmac<D>
+where
+    D: KeyInit + Mac,
+{
+    pub fn new(mac_key: &[u8]) -> Result<Self, HmacError> {
+        let mac = <D as KeyInit>::new_from_slice(mac_key).map_err(HmacError::InvalidKeyLength)?;
+        Ok(Self { mac })
+    }
+
+    pub fn update(&mut self, chunk: &[u8]) -> &mut Self {
+        self.mac.update(chunk);
+        self
+    }
+
+    pub fn finalize(self) -> Vec<u8> {
+        self.mac.finalize().into_bytes().to_vec()
+    }
+
+    /// Checks the digest against `tag` in constant time.
+    pub fn verify(self, tag: &[u8]) -> Result<(), HmacError> {
+        self.mac.verify_slice(tag).map_err(|_| HmacError::Mismatch)
+    }
+}
+
+impl<D> io::Write for Hmac<D>
+where
+    D: KeyInit + Mac,
+{
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.update(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
 pub fn get_pbkdf2_hmac_keys<D>(
     password: &[u8],
     salt: Option<[u8; 16]>,
@@ -113,4 +173,24 @@ mod tests {
         let hex_digest = hex::encode(digest);
         assert_eq!(hex_digest, "164b7a7bfcf819e2e395fbe73b56e0a387bd64222e831fd610270cd7ea2505549758bf75c05a994a6d034f65f8f0e6fdcaeab1a34d4a6b4b636e070a38bce737");
     }
+
+    #[test]
+    fn test_streaming_hmac() {
+        let payload = vec![0x5au8; 10_000];
+        let key = "key".as_bytes();
+        let expected = compute_hmac::<HmacSha512>(&payload, key).unwrap();
+
+        let mut hmac = Hmac::<HmacSha512>::new(key).unwrap();
+        for chunk in payload.chunks(333) {
+            hmac.update(chunk);
+        }
+        assert_eq!(hmac.clone().finalize(), expected);
+        assert!(hmac.verify(&expected).is_ok());
+
+        let mut hmac = Hmac::<HmacSha512>::new(key).unwrap();
+        assert_eq!(io::copy(&mut payload.as_slice(), &mut hmac).unwrap(), 10_000);
+        let mut tampered = expected.clone();
+        tampered[0] ^= 1;
+        assert!(matches!(hmac.verify(&tampered), Err(HmacError::Mismatch)));
+    }
 }
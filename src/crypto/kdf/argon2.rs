@@ -0,0 +1,91 @@
+use ::argon2::{Algorithm, Argon2, Params, Version};
+
+use super::KdfError;
+
+/// Parameters of an Argon2id derivation: memory in KiB, number of passes and lanes.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Argon2Params {
+    pub memory_kib: u32,
+    pub iterations: u32,
+    pub parallelism: u32,
+}
+
+impl Argon2Params {
+    /// OWASP minimum for interactive logins (19 MiB, 2 passes, 1 lane).
+    pub const INTERACTIVE: Argon2Params = Argon2Params { memory_kib: 19 * 1024, iterations: 2, parallelism: 1 };
+    /// Second recommended option of RFC 9106 (64 MiB, 3 passes, 4 lanes), for keystores.
+    pub const SENSITIVE: Argon2Params = Argon2Params { memory_kib: 64 * 1024, iterations: 3, parallelism: 4 };
+
+    pub fn new(memory_kib: u32, iterations: u32, parallelism: u32) -> Result<Self, KdfError> {
+        let params = Self { memory_kib, iterations, parallelism };
+        params.validate()?;
+        Ok(params)
+    }
+
+    /// Checks the ranges of RFC 9106 (at least 8 KiB of memory per lane, 1 pass and 1 lane).
+    pub fn validate(&self) -> Result<(), KdfError> {
+        self.argon2_params(None).map(|_| ())
+    }
+
+    fn argon2_params(&self, output_len: Option<usize>) -> Result<Params, KdfError> {
+        Params::new(self.memory_kib, self.iterations, self.parallelism, output_len).map_err(|err| {
+            KdfError::InvalidParams(format!(
+                "m = {} KiB, t = {}, p = {}: {err}",
+                self.memory_kib, self.iterations, self.parallelism
+            ))
+        })
+    }
+}
+
+/// Minimum output length.
+pub const MIN_OUTPUT_LEN: usize = Params::MIN_OUTPUT_LEN;
+
+/// Argon2id (version 0x13) of `password` and `salt`, filling `output`.
+/// The salt must be at least 8 bytes long.
+///
+/// Usage:
+/// ```rust
+/// use stacks_rs::crypto::kdf::argon2::{argon2id, Argon2Params};
+/// let mut key = [0u8; 32];
+/// argon2id(b"password", b"somesalt", &Argon2Params::new(64, 1, 1).unwrap(), &mut key).unwrap();
+/// ```
+pub fn argon2id(password: &[u8], salt: &[u8], params: &Argon2Params, output: &mut [u8]) -> Result<(), KdfError> {
+    if output.len() < MIN_OUTPUT_LEN {
+        return Err(KdfError::InvalidOutputLength(output.len()));
+    }
+    let argon2 = Argon2::new(Algorithm::Argon2id, Version::V0x13, params.argon2_params(Some(output.len()))?);
+    argon2
+        .hash_password_into(password, salt, output)
+        .map_err(|err| KdfError::InvalidParams(format!("{err}")))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_argon2id_vector() {
+        // $argon2id$v=19$m=65536,t=2,p=1$c29tZXNhbHQ$CTFhFdXPJO1aFaMaO6Mm5c8y7cJHAph8ArZWb2GRPPc
+        let mut output = [0u8; 32];
+        argon2id(b"password", b"somesalt", &Argon2Params::new(65536, 2, 1).unwrap(), &mut output).unwrap();
+        assert_eq!(hex::encode(output), "09316115d5cf24ed5a15a31a3ba326e5cf32edc24702987c02b6566f61913cf7");
+    }
+
+    #[test]
+    fn test_argon2_param_validation() {
+        assert!(Argon2Params::INTERACTIVE.validate().is_ok());
+        assert!(Argon2Params::SENSITIVE.validate().is_ok());
+        assert!(Argon2Params::new(0, 2, 1).is_err());
+        assert!(Argon2Params::new(64, 0, 1).is_err());
+        assert!(Argon2Params::new(64, 1, 0).is_err());
+
+        let mut output = [0u8; 3];
+        assert_eq!(
+            argon2id(b"password", b"somesalt", &Argon2Params::new(64, 1, 1).unwrap(), &mut output),
+            Err(KdfError::InvalidOutputLength(3))
+        );
+        // salts shorter than 8 bytes are rejected
+        let mut output = [0u8; 32];
+        assert!(argon2id(b"password", b"salt", &Argon2Params::new(64, 1, 1).unwrap(), &mut output).is_err());
+    }
+}
@@ -0,0 +1,23 @@
+use std::fmt;
+
+pub mod argon2;
+pub mod pbkdf2;
+pub mod scrypt;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum KdfError {
+    /// The parameters are out of the range accepted by the KDF
+    InvalidParams(String),
+    InvalidOutputLength(usize),
+}
+
+impl fmt::Display for KdfError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        match self {
+            KdfError::InvalidParams(v) => f.write_str(&format!("Invalid KDF parameters: {v}")),
+            KdfError::InvalidOutputLength(v) => f.write_str(&format!("Invalid KDF output length {v}")),
+        }
+    }
+}
+
+impl std::error::Error for KdfError {}
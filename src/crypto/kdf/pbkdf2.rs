@@ -0,0 +1,101 @@
+use std::borrow::Cow;
+
+use bip39::Mnemonic;
+use sha2::{Sha256, Sha512};
+
+use super::KdfError;
+
+/// Iterations used by BIP39 to derive the seed from the mnemonic.
+pub const BIP39_ROUNDS: u32 = 2048;
+/// Length of a BIP39 seed.
+pub const BIP39_SEED_LEN: usize = 64;
+/// Salt prefix of the BIP39 seed derivation, followed by the passphrase.
+const BIP39_SALT_PREFIX: &str = "mnemonic";
+
+/// Parameters of a PBKDF2 derivation.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Pbkdf2Params {
+    pub rounds: u32,
+}
+
+impl Pbkdf2Params {
+    pub fn new(rounds: u32) -> Result<Self, KdfError> {
+        if rounds == 0 {
+            return Err(KdfError::InvalidParams(String::from("rounds must be greater than 0")));
+        }
+        Ok(Self { rounds })
+    }
+}
+
+/// PBKDF2-HMAC-SHA512 of `password` and `salt`, filling `output`.
+///
+/// Usage:
+/// ```rust
+/// use stacks_rs::crypto::kdf::pbkdf2::{pbkdf2_hmac_sha512, Pbkdf2Params};
+/// let mut key = [0u8; 32];
+/// pbkdf2_hmac_sha512(b"password", b"salt", &Pbkdf2Params::new(100_000).unwrap(), &mut key).unwrap();
+/// ```
+pub fn pbkdf2_hmac_sha512(password: &[u8], salt: &[u8], params: &Pbkdf2Params, output: &mut [u8]) -> Result<(), KdfError> {
+    if output.is_empty() {
+        return Err(KdfError::InvalidOutputLength(0));
+    }
+    ::pbkdf2::pbkdf2_hmac::<Sha512>(password, salt, params.rounds, output);
+    Ok(())
+}
+
+/// PBKDF2-HMAC-SHA256 of `password` and `salt`, filling `output`.
+pub fn pbkdf2_hmac_sha256(password: &[u8], salt: &[u8], params: &Pbkdf2Params, output: &mut [u8]) -> Result<(), KdfError> {
+    if output.is_empty() {
+        return Err(KdfError::InvalidOutputLength(0));
+    }
+    ::pbkdf2::pbkdf2_hmac::<Sha256>(password, salt, params.rounds, output);
+    Ok(())
+}
+
+/// BIP39 seed of `mnemonic`: PBKDF2-HMAC-SHA512 with 2048 rounds, salted with `"mnemonic" || passphrase`.
+/// The passphrase is NFKD-normalized first.
+pub fn bip39_seed(mnemonic: &Mnemonic, passphrase: &str) -> [u8; BIP39_SEED_LEN] {
+    let mut passphrase = Cow::Borrowed(passphrase);
+    Mnemonic::normalize_utf8_cow(&mut passphrase);
+    let salt = format!("{BIP39_SALT_PREFIX}{passphrase}");
+
+    let mut seed = [0u8; BIP39_SEED_LEN];
+    ::pbkdf2::pbkdf2_hmac::<Sha512>(mnemonic.to_string().as_bytes(), salt.as_bytes(), BIP39_ROUNDS, &mut seed);
+    seed
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use super::*;
+
+    #[test]
+    fn test_pbkdf2_vectors() {
+        // RFC 7914 section 11
+        let mut output = [0u8; 64];
+        pbkdf2_hmac_sha256(b"passwd", b"salt", &Pbkdf2Params::new(1).unwrap(), &mut output).unwrap();
+        assert_eq!(hex::encode(output), "55ac046e56e3089fec1691c22544b605f94185216dde0465e68b9d57c20dacbc49ca9cccf179b645991664b39d77ef317c71b845b1e30bd509112041d3a19783");
+
+        let mut output = [0u8; 64];
+        pbkdf2_hmac_sha512(b"password", b"salt", &Pbkdf2Params::new(1).unwrap(), &mut output).unwrap();
+        assert_eq!(hex::encode(output), "867f70cf1ade02cff3752599a3a53dc4af34c7a669815ae5d513554e1c8cf252c02d470a285a0501bad999bfe943c08f050235d7d68b1da55e63f73b60a57fce");
+    }
+
+    #[test]
+    fn test_pbkdf2_invalid_params() {
+        assert!(Pbkdf2Params::new(0).is_err());
+        assert_eq!(
+            pbkdf2_hmac_sha512(b"password", b"salt", &Pbkdf2Params::new(1).unwrap(), &mut []),
+            Err(KdfError::InvalidOutputLength(0))
+        );
+    }
+
+    #[test]
+    fn test_bip39_seed() {
+        // BIP39 test vector with passphrase "TREZOR"
+        let mnemonic = Mnemonic::from_str("abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon about").unwrap();
+        assert_eq!(hex::encode(bip39_seed(&mnemonic, "TREZOR")), "c55257c360c07c72029aebc1b53c05ed0362ada38ead3e3e9efa3708e53495531f09a6987599d18264c1e1c92f2cf141630c7a3c4ab7c81b2f001698e7463b04");
+        assert_eq!(bip39_seed(&mnemonic, "パスワード"), mnemonic.to_seed("パスワード"));
+    }
+}
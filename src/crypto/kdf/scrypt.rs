@@ -0,0 +1,89 @@
+use super::KdfError;
+
+/// Parameters of a scrypt derivation: `N = 2^log_n`, block size `r` and parallelism `p`.
+///
+/// Only keystores derive their keys with scrypt; mnemonics encrypted for stacks.js stay on PBKDF2.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ScryptParams {
+    pub log_n: u8,
+    pub r: u32,
+    pub p: u32,
+}
+
+impl ScryptParams {
+    /// Fast enough for interactive logins (`N = 2^14`, about 16 MiB of memory).
+    pub const INTERACTIVE: ScryptParams = ScryptParams { log_n: 14, r: 8, p: 1 };
+    /// For keystores and long-lived secrets (`N = 2^20`, about 1 GiB of memory).
+    pub const SENSITIVE: ScryptParams = ScryptParams { log_n: 20, r: 8, p: 1 };
+
+    pub fn new(log_n: u8, r: u32, p: u32) -> Result<Self, KdfError> {
+        let params = Self { log_n, r, p };
+        params.validate()?;
+        Ok(params)
+    }
+
+    /// Checks the constraints of RFC 7914 (`N > 1`, `N < 2^(16 r)`, `p r < 2^30`).
+    pub fn validate(&self) -> Result<(), KdfError> {
+        if self.log_n == 0 || self.r == 0 || self.p == 0 {
+            return Err(KdfError::InvalidParams(String::from("log_n, r and p must be greater than 0")));
+        }
+        // the output length is checked separately, 32 is always valid here
+        ::scrypt::Params::new(self.log_n, self.r, self.p, 32)
+            .map(|_| ())
+            .map_err(|_| KdfError::InvalidParams(format!("log_n = {}, r = {}, p = {}", self.log_n, self.r, self.p)))
+    }
+}
+
+/// Minimum and maximum output lengths.
+pub const OUTPUT_LEN_RANGE: std::ops::RangeInclusive<usize> = 10..=64;
+
+/// scrypt of `password` and `salt`, filling `output` (10 to 64 bytes).
+///
+/// Usage:
+/// ```rust
+/// use stacks_rs::crypto::kdf::scrypt::{scrypt, ScryptParams};
+/// let mut key = [0u8; 32];
+/// scrypt(b"password", b"salt", &ScryptParams::new(10, 8, 1).unwrap(), &mut key).unwrap();
+/// ```
+pub fn scrypt(password: &[u8], salt: &[u8], params: &ScryptParams, output: &mut [u8]) -> Result<(), KdfError> {
+    if !OUTPUT_LEN_RANGE.contains(&output.len()) {
+        return Err(KdfError::InvalidOutputLength(output.len()));
+    }
+    params.validate()?;
+    let params = ::scrypt::Params::new(params.log_n, params.r, params.p, output.len())
+        .map_err(|_| KdfError::InvalidParams(format!("{params:?}")))?;
+    ::scrypt::scrypt(password, salt, &params, output).map_err(|_| KdfError::InvalidOutputLength(output.len()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_scrypt_vectors() {
+        // RFC 7914 section 12
+        let mut output = [0u8; 64];
+        scrypt(b"", b"", &ScryptParams::new(4, 1, 1).unwrap(), &mut output).unwrap();
+        assert_eq!(hex::encode(output), "77d6576238657b203b19ca42c18a0497f16b4844e3074ae8dfdffa3fede21442fcd0069ded0948f8326a753a0fc81f17e8d3e0fb2e0d3628cf35e20c38d18906");
+
+        let mut output = [0u8; 64];
+        scrypt(b"password", b"NaCl", &ScryptParams::new(10, 8, 16).unwrap(), &mut output).unwrap();
+        assert_eq!(hex::encode(output), "fdbabe1c9d3472007856e7190d01e9fe7c6ad7cbc8237830e77376634b3731622eaf30d92e22a3886ff109279d9830dac727afb94a83ee6d8360cbdfa2cc0640");
+    }
+
+    #[test]
+    fn test_scrypt_param_validation() {
+        assert!(ScryptParams::INTERACTIVE.validate().is_ok());
+        assert!(ScryptParams::SENSITIVE.validate().is_ok());
+        assert!(ScryptParams::new(0, 8, 1).is_err());
+        assert!(ScryptParams::new(14, 0, 1).is_err());
+        // N must be less than 2^(16 r)
+        assert!(ScryptParams::new(16, 1, 1).is_err());
+
+        let mut short = [0u8; 8];
+        assert_eq!(
+            scrypt(b"password", b"salt", &ScryptParams::new(4, 1, 1).unwrap(), &mut short),
+            Err(KdfError::InvalidOutputLength(8))
+        );
+    }
+}
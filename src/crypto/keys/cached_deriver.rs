@@ -0,0 +1,148 @@
+use std::collections::HashMap;
+
+use crate::bip32::derivation_path::DerivationPath;
+
+use super::common_attrs::KeyFingerprint;
+use super::derivable::{DerivableKey, DerivationError};
+
+/// Default number of intermediate nodes kept by a [`CachedDeriver`].
+pub const DEFAULT_CACHE_CAPACITY: usize = 64;
+
+/// Fingerprint of the root key and indexes of the path prefix
+type NodeKey = (KeyFingerprint, Vec<u32>);
+
+/// Derives keys from a root key, memoizing the intermediate nodes in an LRU cache.
+///
+/// Deriving `m/44'/5757'/0'/0/i` for many `i` only computes the last step once
+/// `m/44'/5757'/0'/0` is cached.
+///
+/// Usage:
+/// ```rust
+/// use std::str::FromStr;
+/// use stacks_rs::bip32::derivation_path::DerivationPath;
+/// use stacks_rs::crypto::keys::cached_deriver::CachedDeriver;
+/// use stacks_rs::crypto::keys::derivable::DerivableMasterKey;
+/// use stacks_rs::crypto::keys::extended_private_key::ExtendedPrivateKey;
+/// let seed = hex::decode("000102030405060708090a0b0c0d0e0f").unwrap();
+/// let mut deriver = CachedDeriver::new(ExtendedPrivateKey::master_from_seed(&seed).unwrap());
+/// for i in 0..10 {
+///     let path = DerivationPath::from_str(&format!("m/44'/5757'/0'/0/{i}")).unwrap();
+///     deriver.derive(&path).unwrap();
+/// }
+/// ```
+pub struct CachedDeriver<K: DerivableKey> {
+    root: K,
+    root_fingerprint: KeyFingerprint,
+    capacity: usize,
+    /// Cached nodes with the tick of their last use
+    nodes: HashMap<NodeKey, (K, u64)>,
+    tick: u64,
+}
+
+impl<K: DerivableKey> CachedDeriver<K> {
+    pub fn new(root: K) -> Self {
+        Self::with_capacity(root, DEFAULT_CACHE_CAPACITY)
+    }
+
+    pub fn with_capacity(root: K, capacity: usize) -> Self {
+        let root_fingerprint = root.key_fingerprint();
+        CachedDeriver { root, root_fingerprint, capacity, nodes: HashMap::new(), tick: 0 }
+    }
+
+    pub fn root(&self) -> &K {
+        &self.root
+    }
+
+    /// Number of cached nodes.
+    pub fn len(&self) -> usize {
+        self.nodes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.nodes.clear();
+    }
+
+    /// Derives `path` from the root key, starting from the deepest cached prefix.
+    /// Every intermediate node is cached, the final key is not.
+    pub fn derive(&mut self, path: &DerivationPath) -> Result<K, DerivationError> {
+        let indexes: Vec<u32> = path.path.iter().map(|child_number| child_number.index).collect();
+        self.tick += 1;
+
+        let (mut depth, mut key) = (0, self.root.clone());
+        for prefix_len in (1..indexes.len()).rev() {
+            if let Some((node, last_used)) = self.nodes.get_mut(&(self.root_fingerprint, indexes[..prefix_len].to_vec())) {
+                *last_used = self.tick;
+                (depth, key) = (prefix_len, node.clone());
+                break;
+            }
+        }
+
+        for (i, child_number) in path.path.iter().enumerate().skip(depth) {
+            key = key.ckd(*child_number)?;
+            if i + 1 < indexes.len() {
+                self.insert(indexes[..=i].to_vec(), key.clone());
+            }
+        }
+        Ok(key)
+    }
+
+    fn insert(&mut self, prefix: Vec<u32>, node: K) {
+        if self.capacity == 0 {
+            return;
+        }
+        if self.nodes.len() >= self.capacity {
+            let least_recently_used = self.nodes.iter().min_by_key(|(_, (_, last_used))| *last_used).map(|(key, _)| key.clone());
+            if let Some(key) = least_recently_used {
+                self.nodes.remove(&key);
+            }
+        }
+        // deeper nodes are used more recently than their parents
+        self.tick += 1;
+        self.nodes.insert((self.root_fingerprint, prefix), (node, self.tick));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use crate::crypto::keys::derivable::DerivableMasterKey;
+    use crate::crypto::keys::extended_private_key::{ExtendedPrivateKey, ExtendedPrivateKeyMethods};
+
+    use super::*;
+
+    fn master() -> ExtendedPrivateKey {
+        let seed = hex::decode("000102030405060708090a0b0c0d0e0f").unwrap();
+        ExtendedPrivateKey::master_from_seed(&seed).unwrap()
+    }
+
+    #[test]
+    fn test_cached_deriver_matches_uncached() {
+        let mut deriver = CachedDeriver::new(master());
+        for i in 0..5 {
+            let path = DerivationPath::from_str(&format!("m/44'/5757'/0'/0/{i}")).unwrap();
+            let cached = deriver.derive(&path).unwrap();
+            let uncached = master().derive_path(&path).unwrap();
+            assert_eq!(cached.to_bytes(), uncached.to_bytes());
+            assert_eq!(cached.attrs.parent_fingerprint, uncached.attrs.parent_fingerprint);
+        }
+        // m/44', m/44'/5757', m/44'/5757'/0', m/44'/5757'/0'/0
+        assert_eq!(deriver.len(), 4);
+    }
+
+    #[test]
+    fn test_cached_deriver_evicts_least_recently_used() {
+        let mut deriver = CachedDeriver::with_capacity(master(), 2);
+        deriver.derive(&DerivationPath::from_str("m/0/0/0").unwrap()).unwrap();
+        assert_eq!(deriver.len(), 2);
+        deriver.derive(&DerivationPath::from_str("m/1/0").unwrap()).unwrap();
+        // m/0 was the least recently used node
+        assert!(!deriver.nodes.contains_key(&(deriver.root_fingerprint, vec![0])));
+        assert!(deriver.nodes.contains_key(&(deriver.root_fingerprint, vec![0, 0])));
+        assert!(deriver.nodes.contains_key(&(deriver.root_fingerprint, vec![1])));
+    }
+}
@@ -1,4 +1,5 @@
 use crate::bip32::child_number::ChildNumber;
+use crate::bip32::key_version::KeyMetadata;
 
 const FINGERPRINT_LEN: usize = 4;
 
@@ -9,14 +10,21 @@ pub struct ExtendedKeyAttrs {
     pub depth: u8,
     pub parent_fingerprint: KeyFingerprint,
     pub child_number: ChildNumber,
+    /// Network and address scheme, used to pick the version when serializing the key
+    pub metadata: KeyMetadata,
 }
 
 impl ExtendedKeyAttrs {
     pub fn new(depth: u8, parent_fingerprint: KeyFingerprint, child_number: ChildNumber) -> Self {
-        Self { depth: depth, parent_fingerprint: parent_fingerprint, child_number: child_number }
+        Self { depth: depth, parent_fingerprint: parent_fingerprint, child_number: child_number, metadata: KeyMetadata::default() }
     }
 
     pub fn default() -> Self {
-        Self { depth: 0, parent_fingerprint: [0u8; FINGERPRINT_LEN], child_number: ChildNumber::new(0).unwrap()}
+        Self { depth: 0, parent_fingerprint: [0u8; FINGERPRINT_LEN], child_number: ChildNumber::new(0).unwrap(), metadata: KeyMetadata::default() }
     }
-}
\ No newline at end of file
+
+    /// Attributes of the child `child_number`, inheriting the metadata of the parent.
+    pub fn child(&self, parent_fingerprint: KeyFingerprint, child_number: ChildNumber) -> Self {
+        Self { depth: self.depth + 1, parent_fingerprint, child_number, metadata: self.metadata }
+    }
+}
@@ -0,0 +1,175 @@
+use std::fmt;
+
+use crate::bip32::child_number::ChildNumber;
+use crate::bip32::derivation_path::{DerivationPath, DerivationPolicy};
+use crate::crypto::context::secp256k1_context;
+
+use super::common_attrs::{ExtendedKeyAttrs, KeyFingerprint};
+use super::extended_private_key::{ExtendedPrivateKey, ExtendedPrivateKeyMethods};
+use super::extended_public_key::{ExtendedPublicKey, ExtendedPublicKeyMethods};
+use super::ChainCode;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DerivationError {
+    /// Hardened children can only be derived from private keys
+    HardenedFromPublicKey,
+    /// The backend cannot derive the master key from the seed
+    InvalidSeed,
+    /// Non-hardened step at this depth, rejected by the [`DerivationPolicy`]
+    NonHardenedStep(u8),
+}
+
+impl fmt::Display for DerivationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        match *self {
+            DerivationError::HardenedFromPublicKey => f.write_str("Cannot derive a hardened child from a public key"),
+            DerivationError::InvalidSeed => f.write_str("Invalid seed"),
+            DerivationError::NonHardenedStep(depth) => f.write_str(&format!("Non-hardened derivation at depth {depth} is not allowed")),
+        }
+    }
+}
+
+impl std::error::Error for DerivationError {}
+
+/// A key of a BIP32 / SLIP-0010 hierarchy.
+///
+/// Backends (another secp256k1 implementation, ed25519, ...) only implement the
+/// child key derivation of their curve; the [`ExtendedKeyAttrs`] bookkeeping and
+/// the path walking are shared.
+///
+/// Usage:
+/// ```rust
+/// use std::str::FromStr;
+/// use stacks_rs::bip32::derivation_path::DerivationPath;
+/// use stacks_rs::crypto::keys::derivable::{DerivableKey, DerivableMasterKey};
+/// use stacks_rs::crypto::keys::extended_private_key::ExtendedPrivateKey;
+/// let seed = hex::decode("000102030405060708090a0b0c0d0e0f").unwrap();
+/// let master = ExtendedPrivateKey::master_from_seed(&seed).unwrap();
+/// let key = master.derive_path(&DerivationPath::from_str("m/44'/5757'/0'/0/0").unwrap()).unwrap();
+/// assert_eq!(key.attrs().depth, 5);
+/// ```
+pub trait DerivableKey: Sized + Clone {
+    fn attrs(&self) -> &ExtendedKeyAttrs;
+    fn chain_code(&self) -> &ChainCode;
+    /// Fingerprint of the key, used as the parent fingerprint of its children
+    fn key_fingerprint(&self) -> KeyFingerprint;
+    /// Child key derivation (CKDpriv / CKDpub)
+    fn ckd(&self, child_number: ChildNumber) -> Result<Self, DerivationError>;
+
+    /// Derives every step of `path` starting from this key.
+    fn derive_path(&self, path: &DerivationPath) -> Result<Self, DerivationError> {
+        self.derive_steps(&path.path)
+    }
+
+    fn derive_steps(&self, steps: &[ChildNumber]) -> Result<Self, DerivationError> {
+        steps.iter().try_fold(self.clone(), |key, child_number| key.ckd(*child_number))
+    }
+
+    /// Same as [`DerivableKey::derive_path`], but checks every step against `policy`
+    /// (at the absolute depth of the derived key) before deriving anything.
+    fn derive_path_with_policy(&self, path: &DerivationPath, policy: &DerivationPolicy) -> Result<Self, DerivationError> {
+        for (i, child_number) in path.path.iter().enumerate() {
+            let depth = self.attrs().depth.saturating_add(i as u8 + 1);
+            if !policy.allows(depth, child_number) {
+                return Err(DerivationError::NonHardenedStep(depth));
+            }
+        }
+        self.derive_path(path)
+    }
+}
+
+/// A [`DerivableKey`] that can be the root of a hierarchy.
+pub trait DerivableMasterKey: DerivableKey {
+    fn master_from_seed(seed: &[u8]) -> Result<Self, DerivationError>;
+}
+
+impl DerivableKey for ExtendedPrivateKey {
+    fn attrs(&self) -> &ExtendedKeyAttrs {
+        &self.attrs
+    }
+
+    fn chain_code(&self) -> &ChainCode {
+        &self.chain_code
+    }
+
+    fn key_fingerprint(&self) -> KeyFingerprint {
+        self.fingerprint()
+    }
+
+    fn ckd(&self, child_number: ChildNumber) -> Result<Self, DerivationError> {
+        Ok(self.derive_child(child_number))
+    }
+}
+
+impl DerivableMasterKey for ExtendedPrivateKey {
+    fn master_from_seed(seed: &[u8]) -> Result<Self, DerivationError> {
+        Self::new(seed).map_err(|_| DerivationError::InvalidSeed)
+    }
+}
+
+impl DerivableKey for ExtendedPublicKey {
+    fn attrs(&self) -> &ExtendedKeyAttrs {
+        &self.attrs
+    }
+
+    fn chain_code(&self) -> &ChainCode {
+        &self.chain_code
+    }
+
+    fn key_fingerprint(&self) -> KeyFingerprint {
+        self.fingerprint()
+    }
+
+    fn ckd(&self, child_number: ChildNumber) -> Result<Self, DerivationError> {
+        self.derive_child_with_context(secp256k1_context(), child_number)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use super::*;
+
+    /// Same path walked by any backend
+    fn derive<K: DerivableKey>(key: &K, path: &str) -> Result<K, DerivationError> {
+        key.derive_path(&DerivationPath::from_str(path).unwrap())
+    }
+
+    #[test]
+    fn test_derivable_key_backends_agree() {
+        let seed = hex::decode("000102030405060708090a0b0c0d0e0f").unwrap();
+        let master = ExtendedPrivateKey::master_from_seed(&seed).unwrap();
+        let account = derive(&master, "m/44'/5757'/0'").unwrap();
+        let account_xpub = ExtendedPublicKey::from(&account);
+
+        let private_child = derive(&account, "m/0/7").unwrap();
+        let public_child = derive(&account_xpub, "m/0/7").unwrap();
+        assert_eq!(&private_child.public_key(), public_child.public_key());
+        assert_eq!(private_child.attrs().depth, public_child.attrs().depth);
+        assert_eq!(private_child.attrs().parent_fingerprint, public_child.attrs().parent_fingerprint);
+        assert_eq!(private_child.chain_code(), public_child.chain_code());
+    }
+
+    #[test]
+    fn test_derive_path_with_policy() {
+        let seed = hex::decode("000102030405060708090a0b0c0d0e0f").unwrap();
+        let master = ExtendedPrivateKey::master_from_seed(&seed).unwrap();
+        let policy = DerivationPolicy::bip44();
+        let account = master.derive_path_with_policy(&DerivationPath::from_str("m/44'/5757'/0'").unwrap(), &policy).unwrap();
+        // below the account level non-hardened steps are allowed
+        assert!(account.derive_path_with_policy(&DerivationPath::from_str("m/0/0").unwrap(), &policy).is_ok());
+        assert_eq!(
+            master.derive_path_with_policy(&DerivationPath::from_str("m/44'/5757'/0/0").unwrap(), &policy).err(),
+            Some(DerivationError::NonHardenedStep(3))
+        );
+    }
+
+    #[test]
+    fn test_derivable_key_hardened_from_public() {
+        let seed = hex::decode("000102030405060708090a0b0c0d0e0f").unwrap();
+        let xpub = ExtendedPublicKey::from(&ExtendedPrivateKey::master_from_seed(&seed).unwrap());
+        assert_eq!(derive(&xpub, "m/0/1'").err(), Some(DerivationError::HardenedFromPublicKey));
+        assert_eq!(xpub.derive_steps(&[]).unwrap().public_key(), xpub.public_key());
+    }
+}
@@ -1,7 +1,12 @@
-use secp256k1::{PublicKey, Scalar, SecretKey};
-use stacks_common::util::hash::Hash160;
-use crate::{bip32::{child_number::ChildNumber, derivation_path::DerivationPath, extended_keys::ExtendedKey, key_version::Version}, crypto::hmac::{self, HmacSha512}};
-use super::{common_attrs::{ExtendedKeyAttrs, KeyFingerprint}, ChainCode, EXTENDED_KEY_LENGHT, KEY_LENGHT};
+use std::ops::Range;
+
+use ::hmac::Mac;
+#[cfg(feature = "rayon")]
+use rayon::prelude::*;
+use secp256k1::{PublicKey, Scalar, Secp256k1, SecretKey, Signing};
+use subtle::{Choice, ConstantTimeEq};
+use crate::{bip32::{child_number::ChildNumber, derivation_path::DerivationPath, extended_keys::ExtendedKey, key_version::{KeyMetadata, Version}}, crypto::{context::secp256k1_context, hash::{Hash160, Hasher}, hmac::{self, HmacSha512}}};
+use super::{common_attrs::{ExtendedKeyAttrs, KeyFingerprint}, key_bytes::{to_array, KeyBytesError}, ChainCode, EXTENDED_KEY_LENGHT, KEY_LENGHT};
 
 
 const BITCOIN_SEED_STRING: [u8; 12] = [
@@ -9,6 +14,7 @@ const BITCOIN_SEED_STRING: [u8; 12] = [
 ];
 
 
+#[derive(Clone)]
 pub struct ExtendedPrivateKey {
     pub attrs: ExtendedKeyAttrs,
     pub chain_code: ChainCode,
@@ -17,12 +23,24 @@ pub struct ExtendedPrivateKey {
 
 pub trait ExtendedPrivateKeyMethods {
     fn new(seed: &[u8]) -> Result<Self, hmac::HmacError> where Self: Sized;
+    fn from_parts(secret_bytes: &[u8], chain_code: &[u8], attrs: ExtendedKeyAttrs) -> Result<Self, KeyBytesError> where Self: Sized;
+    fn from_bytes(bytes: &[u8], attrs: ExtendedKeyAttrs) -> Result<Self, KeyBytesError> where Self: Sized;
+    fn from_hex(hex_key: &str, attrs: ExtendedKeyAttrs) -> Result<Self, KeyBytesError> where Self: Sized;
+    fn to_bytes(&self) -> [u8; KEY_LENGHT * 2];
+    fn to_hex(&self) -> String;
     fn derive_child(&self, child_number: ChildNumber) -> Self;
+    fn derive_child_with_context<C: Signing>(&self, secp: &Secp256k1<C>, child_number: ChildNumber) -> Self;
+    fn derive_children(&self, range: Range<u32>) -> ExtendedPrivateKeyChildren<'_>;
+    #[cfg(feature = "rayon")]
+    fn derive_children_par(&self, range: Range<u32>) -> Vec<ExtendedPrivateKey>;
     fn derive_from_path(seed: &[u8], derivation_path: DerivationPath) -> Self;
     fn public_key(&self) -> PublicKey;
+    fn public_key_with_context<C: Signing>(&self, secp: &Secp256k1<C>) -> PublicKey;
     fn fingerprint(&self) -> KeyFingerprint;
     fn to_extended_key_bytes(&self) -> [u8; EXTENDED_KEY_LENGHT];
-    fn to_extended_key(&self, version: Version) -> ExtendedKey;
+    fn to_extended_key(&self) -> ExtendedKey;
+    fn to_extended_key_with_version(&self, version: Version) -> ExtendedKey;
+    fn with_metadata(self, metadata: KeyMetadata) -> Self where Self: Sized;
 }
 
 impl ExtendedPrivateKeyMethods for ExtendedPrivateKey {
@@ -42,34 +60,75 @@ impl ExtendedPrivateKeyMethods for ExtendedPrivateKey {
         })
     }
 
+    /// Builds a key from its raw secret key and chain code (32 bytes each).
+    /// The secret key must be in the secp256k1 range.
+    fn from_parts(secret_bytes: &[u8], chain_code: &[u8], attrs: ExtendedKeyAttrs) -> Result<Self, KeyBytesError> {
+        let s_key = SecretKey::from_byte_array(&to_array(secret_bytes)?).map_err(KeyBytesError::InvalidKey)?;
+        Ok(Self { attrs, chain_code: to_array(chain_code)?, s_key })
+    }
+
+    /// Inverse of [`ExtendedPrivateKeyMethods::to_bytes`].
+    fn from_bytes(bytes: &[u8], attrs: ExtendedKeyAttrs) -> Result<Self, KeyBytesError> {
+        let bytes: [u8; KEY_LENGHT * 2] = to_array(bytes)?;
+        Self::from_parts(&bytes[..KEY_LENGHT], &bytes[KEY_LENGHT..], attrs)
+    }
+
+    /// Inverse of [`ExtendedPrivateKeyMethods::to_hex`].
+    fn from_hex(hex_key: &str, attrs: ExtendedKeyAttrs) -> Result<Self, KeyBytesError> {
+        Self::from_bytes(&hex::decode(hex_key).map_err(KeyBytesError::InvalidHex)?, attrs)
+    }
+
+    /// Raw `secret key (32) || chain code (32)`, without the attributes.
+    fn to_bytes(&self) -> [u8; KEY_LENGHT * 2] {
+        let mut bytes = [0u8; KEY_LENGHT * 2];
+        bytes[..KEY_LENGHT].copy_from_slice(&self.s_key.secret_bytes());
+        bytes[KEY_LENGHT..].copy_from_slice(&self.chain_code);
+        bytes
+    }
+
+    fn to_hex(&self) -> String {
+        hex::encode(self.to_bytes())
+    }
+
+    /// Derives the child key using the shared secp256k1 context.
     fn derive_child(&self, child_number: ChildNumber) -> Self {
+        self.derive_child_with_context(secp256k1_context(), child_number)
+    }
+
+    /// Derives the child key using a caller-supplied secp256k1 context.
+    fn derive_child_with_context<C: Signing>(&self, secp: &Secp256k1<C>, child_number: ChildNumber) -> Self {
         // TODO: check/propagate errors
         if self.attrs.depth >= 5 {
             // RETURN ERR
         }
-        // key bytes + 4 byte index
-        let mut payload = [0u8; 37];
-        match child_number.is_hardened {
-            true => payload[..33].copy_from_slice(&self.to_extended_key_bytes()),
-            false => payload[..33].copy_from_slice(&self.public_key().serialize()),
-        }
-        payload[33..37].copy_from_slice(&child_number.index.to_be_bytes());
-        let i = hmac::compute_hmac::<HmacSha512>(&payload, &self.chain_code).unwrap();
-        
-        let mut tweak_bytes: [u8; 32] = [0u8; KEY_LENGHT];
-        let mut child_chain_code = [0u8; KEY_LENGHT];
-
-        tweak_bytes.copy_from_slice(&i[0..KEY_LENGHT]);
-        child_chain_code.copy_from_slice(&i[KEY_LENGHT..KEY_LENGHT*2]);
+        let public_key = self.public_key_with_context(secp);
+        let mac = HmacSha512::new_from_slice(&self.chain_code).unwrap();
+        self.child_from_mac(&mac, &public_key.serialize(), fingerprint_of(&public_key), child_number)
+    }
 
-        let child_s_key = self.s_key.add_tweak(&Scalar::from_be_bytes(tweak_bytes).unwrap()).unwrap();
-        Self {
-            attrs: ExtendedKeyAttrs::new(self.attrs.depth+1, self.fingerprint(), child_number),
-            chain_code: child_chain_code,
-            s_key: child_s_key
+    /// Returns an iterator over the children at every index of `range`.
+    ///
+    /// Faster than calling [`ExtendedPrivateKeyMethods::derive_child`] in a loop, since the
+    /// parent public key, its fingerprint and the chain-code-keyed HMAC are computed only once.
+    fn derive_children(&self, range: Range<u32>) -> ExtendedPrivateKeyChildren<'_> {
+        let public_key = self.public_key();
+        ExtendedPrivateKeyChildren {
+            parent: self,
+            mac: HmacSha512::new_from_slice(&self.chain_code).unwrap(),
+            public_key_bytes: public_key.serialize(),
+            fingerprint: fingerprint_of(&public_key),
+            indexes: range,
         }
     }
 
+    /// Same as [`ExtendedPrivateKeyMethods::derive_children`] but splits `range` across the rayon thread pool.
+    /// The children are returned in index order.
+    #[cfg(feature = "rayon")]
+    fn derive_children_par(&self, range: Range<u32>) -> Vec<ExtendedPrivateKey> {
+        let children = self.derive_children(range.clone());
+        range.into_par_iter().map(|index| children.derive(index)).collect()
+    }
+
     fn derive_from_path(seed: &[u8], derivation_path: DerivationPath) -> Self {
         // TODO: check/propagate errors
         let mut key = Self::new(seed).unwrap();
@@ -81,23 +140,22 @@ impl ExtendedPrivateKeyMethods for ExtendedPrivateKey {
             path = child_number;
         }
         Self {  
-            attrs: ExtendedKeyAttrs::new(depth, key.fingerprint(), path),
+            attrs: ExtendedKeyAttrs { metadata: key.attrs.metadata, ..ExtendedKeyAttrs::new(depth, key.fingerprint(), path) },
             s_key: key.s_key,
             chain_code: key.chain_code 
         }
     }
 
     fn public_key(&self) -> PublicKey {
-        let secp = &secp256k1::Secp256k1::new();
+        self.public_key_with_context(secp256k1_context())
+    }
+
+    fn public_key_with_context<C: Signing>(&self, secp: &Secp256k1<C>) -> PublicKey {
         self.s_key.public_key(secp)
     }
 
     fn fingerprint(&self) -> KeyFingerprint {
-        let public_key = self.public_key();
-        let res = Hash160::from_data(&public_key.serialize());
-        let mut fingerprint = [0u8; 4];
-        fingerprint.copy_from_slice(&res.as_bytes()[0..4]);
-        fingerprint
+        fingerprint_of(&self.public_key())
     }
 
     fn to_extended_key_bytes(&self) -> [u8; EXTENDED_KEY_LENGHT] {
@@ -107,7 +165,19 @@ impl ExtendedPrivateKeyMethods for ExtendedPrivateKey {
         key_bytes
     }   
 
-    fn to_extended_key(&self, version: Version) -> ExtendedKey {
+    /// Sets the network and address scheme of the key (inherited by its children).
+    fn with_metadata(mut self, metadata: KeyMetadata) -> Self {
+        self.attrs.metadata = metadata;
+        self
+    }
+
+    /// Serializes the key using the SLIP-132 version matching its network and address scheme.
+    fn to_extended_key(&self) -> ExtendedKey {
+        self.to_extended_key_with_version(Version::from_metadata(&self.attrs.metadata, true))
+    }
+
+    /// Serializes the key using an explicit `version`, regardless of its metadata.
+    fn to_extended_key_with_version(&self, version: Version) -> ExtendedKey {
         ExtendedKey {
             version: version,
             attrs: self.attrs,
@@ -116,3 +186,89 @@ impl ExtendedPrivateKeyMethods for ExtendedPrivateKey {
         }
     }
 }
+
+impl ConstantTimeEq for ExtendedPrivateKey {
+    /// Compares the secret key and chain code in constant time, the attributes are ignored.
+    fn ct_eq(&self, other: &Self) -> Choice {
+        self.to_bytes().ct_eq(&other.to_bytes())
+    }
+}
+
+impl PartialEq for ExtendedPrivateKey {
+    fn eq(&self, other: &Self) -> bool {
+        self.ct_eq(other).into()
+    }
+}
+
+impl Eq for ExtendedPrivateKey {}
+
+/// First 4 bytes of the Hash160 of the compressed `public_key`.
+fn fingerprint_of(public_key: &PublicKey) -> KeyFingerprint {
+    let res = Hash160::hash(&public_key.serialize());
+    let mut fingerprint = [0u8; 4];
+    fingerprint.copy_from_slice(&res[0..4]);
+    fingerprint
+}
+
+impl ExtendedPrivateKey {
+    /// CKDpriv starting from an HMAC already keyed with the parent chain code.
+    fn child_from_mac(
+        &self,
+        mac: &HmacSha512,
+        public_key_bytes: &[u8; EXTENDED_KEY_LENGHT],
+        fingerprint: KeyFingerprint,
+        child_number: ChildNumber,
+    ) -> Self {
+        let mut mac = mac.clone();
+        match child_number.is_hardened {
+            true => mac.update(&self.to_extended_key_bytes()),
+            false => mac.update(public_key_bytes),
+        }
+        mac.update(&child_number.index.to_be_bytes());
+        let i = mac.finalize().into_bytes();
+
+        let mut tweak_bytes: [u8; 32] = [0u8; KEY_LENGHT];
+        let mut child_chain_code = [0u8; KEY_LENGHT];
+
+        tweak_bytes.copy_from_slice(&i[0..KEY_LENGHT]);
+        child_chain_code.copy_from_slice(&i[KEY_LENGHT..KEY_LENGHT*2]);
+
+        let child_s_key = self.s_key.add_tweak(&Scalar::from_be_bytes(tweak_bytes).unwrap()).unwrap();
+        Self {
+            attrs: self.attrs.child(fingerprint, child_number),
+            chain_code: child_chain_code,
+            s_key: child_s_key
+        }
+    }
+}
+
+/// Iterator over the children of an [`ExtendedPrivateKey`], see [`ExtendedPrivateKeyMethods::derive_children`].
+pub struct ExtendedPrivateKeyChildren<'a> {
+    parent: &'a ExtendedPrivateKey,
+    mac: HmacSha512,
+    public_key_bytes: [u8; EXTENDED_KEY_LENGHT],
+    fingerprint: KeyFingerprint,
+    indexes: Range<u32>,
+}
+
+impl ExtendedPrivateKeyChildren<'_> {
+    fn derive(&self, index: u32) -> ExtendedPrivateKey {
+        let child_number = ChildNumber::new(index).unwrap();
+        self.parent.child_from_mac(&self.mac, &self.public_key_bytes, self.fingerprint, child_number)
+    }
+}
+
+impl Iterator for ExtendedPrivateKeyChildren<'_> {
+    type Item = ExtendedPrivateKey;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let index = self.indexes.next()?;
+        Some(self.derive(index))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.indexes.size_hint()
+    }
+}
+
+impl ExactSizeIterator for ExtendedPrivateKeyChildren<'_> {}
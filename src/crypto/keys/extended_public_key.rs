@@ -1,12 +1,20 @@
-use secp256k1::{PublicKey, Scalar};
-use stacks_common::util::hash::Hash160;
+use std::ops::Range;
+
+use ::hmac::Mac;
+#[cfg(feature = "rayon")]
+use rayon::prelude::*;
+use secp256k1::{All, PublicKey, Scalar, Secp256k1, Verification};
+use crate::crypto::hash::{Hash160, Hasher};
 use crate::bip32::extended_keys::ExtendedKey;
-use crate::crypto::hmac::{self, HmacSha512};
-use crate::bip32::{child_number::ChildNumber, key_version::Version};
+use crate::crypto::context::secp256k1_context;
+use crate::crypto::hmac::HmacSha512;
+use crate::bip32::{child_number::ChildNumber, key_version::{KeyMetadata, Version}};
 
+use super::derivable::DerivationError;
 use super::extended_private_key::ExtendedPrivateKeyMethods;
-use super::{common_attrs::{ExtendedKeyAttrs, KeyFingerprint}, extended_private_key::ExtendedPrivateKey, ChainCode, EXTENDED_KEY_LENGHT, KEY_LENGHT};
+use super::{common_attrs::{ExtendedKeyAttrs, KeyFingerprint}, extended_private_key::ExtendedPrivateKey, key_bytes::{to_array, KeyBytesError}, ChainCode, EXTENDED_KEY_LENGHT, KEY_LENGHT};
 
+#[derive(Clone)]
 pub struct ExtendedPublicKey {
     pub(crate) attrs: ExtendedKeyAttrs,
     pub(crate) chain_code: ChainCode,
@@ -15,11 +23,22 @@ pub struct ExtendedPublicKey {
 
 pub trait ExtendedPublicKeyMethods {
     fn new(public_key: PublicKey, chain_code: ChainCode, attrs: ExtendedKeyAttrs) -> Self;
+    fn from_parts(public_key_bytes: &[u8], chain_code: &[u8], attrs: ExtendedKeyAttrs) -> Result<Self, KeyBytesError> where Self: Sized;
+    fn from_bytes(bytes: &[u8], attrs: ExtendedKeyAttrs) -> Result<Self, KeyBytesError> where Self: Sized;
+    fn from_hex(hex_key: &str, attrs: ExtendedKeyAttrs) -> Result<Self, KeyBytesError> where Self: Sized;
+    fn to_bytes(&self) -> [u8; EXTENDED_KEY_LENGHT + KEY_LENGHT];
+    fn to_hex(&self) -> String;
     fn public_key(&self) -> &PublicKey;
     fn derive_child(&self, child_number: ChildNumber) -> Result<Self, ()> where Self: Sized;
+    fn derive_child_with_context<C: Verification>(&self, secp: &Secp256k1<C>, child_number: ChildNumber) -> Result<Self, DerivationError> where Self: Sized;
+    fn derive_children(&self, range: Range<u32>) -> ExtendedPublicKeyChildren<'_>;
+    #[cfg(feature = "rayon")]
+    fn derive_children_par(&self, range: Range<u32>) -> Vec<Result<ExtendedPublicKey, DerivationError>>;
     fn fingerprint(&self) -> KeyFingerprint;
     fn public_key_bytes(&self) -> [u8; EXTENDED_KEY_LENGHT];
-    fn to_extended_key(&self, version: Version) -> ExtendedKey;
+    fn to_extended_key(&self) -> ExtendedKey;
+    fn to_extended_key_with_version(&self, version: Version) -> ExtendedKey;
+    fn with_metadata(self, metadata: KeyMetadata) -> Self where Self: Sized;
 }
 
 impl ExtendedPublicKeyMethods for ExtendedPublicKey {
@@ -27,14 +46,44 @@ impl ExtendedPublicKeyMethods for ExtendedPublicKey {
         Self { attrs: attrs, chain_code: chain_code, p_key: public_key }
     }
 
+    /// Builds a key from its compressed public key (33 bytes) and chain code (32 bytes).
+    /// The public key must be a valid secp256k1 point.
+    fn from_parts(public_key_bytes: &[u8], chain_code: &[u8], attrs: ExtendedKeyAttrs) -> Result<Self, KeyBytesError> {
+        let p_key = PublicKey::from_byte_array_compressed(&to_array(public_key_bytes)?).map_err(KeyBytesError::InvalidKey)?;
+        Ok(Self { attrs, chain_code: to_array(chain_code)?, p_key })
+    }
+
+    /// Inverse of [`ExtendedPublicKeyMethods::to_bytes`].
+    fn from_bytes(bytes: &[u8], attrs: ExtendedKeyAttrs) -> Result<Self, KeyBytesError> {
+        let bytes: [u8; EXTENDED_KEY_LENGHT + KEY_LENGHT] = to_array(bytes)?;
+        Self::from_parts(&bytes[..EXTENDED_KEY_LENGHT], &bytes[EXTENDED_KEY_LENGHT..], attrs)
+    }
+
+    /// Inverse of [`ExtendedPublicKeyMethods::to_hex`].
+    fn from_hex(hex_key: &str, attrs: ExtendedKeyAttrs) -> Result<Self, KeyBytesError> {
+        Self::from_bytes(&hex::decode(hex_key).map_err(KeyBytesError::InvalidHex)?, attrs)
+    }
+
+    /// Raw `compressed public key (33) || chain code (32)`, without the attributes.
+    fn to_bytes(&self) -> [u8; EXTENDED_KEY_LENGHT + KEY_LENGHT] {
+        let mut bytes = [0u8; EXTENDED_KEY_LENGHT + KEY_LENGHT];
+        bytes[..EXTENDED_KEY_LENGHT].copy_from_slice(&self.public_key_bytes());
+        bytes[EXTENDED_KEY_LENGHT..].copy_from_slice(&self.chain_code);
+        bytes
+    }
+
+    fn to_hex(&self) -> String {
+        hex::encode(self.to_bytes())
+    }
+
     fn public_key(&self) -> &PublicKey {
         &self.p_key
     }
 
     fn fingerprint(&self) -> KeyFingerprint {
-        let res = Hash160::from_data(&self.p_key.serialize());
+        let res = Hash160::hash(&self.p_key.serialize());
         let mut fingerprint = [0u8; 4];
-        fingerprint.copy_from_slice(&res.as_bytes()[0..4]);
+        fingerprint.copy_from_slice(&res[0..4]);
         fingerprint
     }
 
@@ -42,35 +91,56 @@ impl ExtendedPublicKeyMethods for ExtendedPublicKey {
         self.public_key().serialize()
     }
 
+    /// Derives the (non-hardened) child key using the shared secp256k1 context.
     fn derive_child(&self, child_number: ChildNumber) -> Result<Self, ()> {
+        self.derive_child_with_context(secp256k1_context(), child_number).map_err(|_| ())
+    }
+
+    /// Derives the (non-hardened) child key using a caller-supplied secp256k1 context.
+    /// Hardened indexes are a [`DerivationError::HardenedFromPublicKey`].
+    fn derive_child_with_context<C: Verification>(&self, secp: &Secp256k1<C>, child_number: ChildNumber) -> Result<Self, DerivationError> {
         // TODO: check/propagate errors
         if self.attrs.depth >= 5 {
             // RETURN ERR
         }
-        // key bytes + 4 byte index
-        let mut payload = [0u8; 37];
-        match child_number.is_hardened {
-            true => Err(()),
-            false => Ok(payload[..33].copy_from_slice(&self.p_key.serialize())),
-        }?;
-        payload[33..37].copy_from_slice(&child_number.index.to_be_bytes());
-        let i = hmac::compute_hmac::<HmacSha512>(&payload, &self.chain_code).unwrap();
-        
-        let mut tweak_bytes: [u8; 32] = [0u8; KEY_LENGHT];
-        let mut child_chain_code = [0u8; KEY_LENGHT];
+        let mac = HmacSha512::new_from_slice(&self.chain_code).unwrap();
+        self.child_from_mac(secp, &mac, self.fingerprint(), child_number)
+    }
 
-        tweak_bytes.copy_from_slice(&i[0..KEY_LENGHT]);
-        child_chain_code.copy_from_slice(&i[KEY_LENGHT..KEY_LENGHT*2]);
+    /// Returns an iterator over the (non-hardened) children at every index of `range`.
+    ///
+    /// Hardened indexes yield [`DerivationError::HardenedFromPublicKey`], like [`ExtendedPublicKeyMethods::derive_child_with_context`].
+    fn derive_children(&self, range: Range<u32>) -> ExtendedPublicKeyChildren<'_> {
+        ExtendedPublicKeyChildren {
+            parent: self,
+            secp: secp256k1_context(),
+            mac: HmacSha512::new_from_slice(&self.chain_code).unwrap(),
+            fingerprint: self.fingerprint(),
+            indexes: range,
+        }
+    }
 
-        let child_p_key = self.p_key.add_exp_tweak(&secp256k1::Secp256k1::new() ,&Scalar::from_be_bytes(tweak_bytes).unwrap()).unwrap();
-        Ok(Self {
-            attrs: ExtendedKeyAttrs::new(self.attrs.depth+1, self.fingerprint(), child_number),
-            chain_code: child_chain_code,
-            p_key: child_p_key
-        })
+    /// Same as [`ExtendedPublicKeyMethods::derive_children`] but splits `range` across the rayon thread pool.
+    /// The children are returned in index order.
+    #[cfg(feature = "rayon")]
+    fn derive_children_par(&self, range: Range<u32>) -> Vec<Result<ExtendedPublicKey, DerivationError>> {
+        let children = self.derive_children(range.clone());
+        range.into_par_iter().map(|index| children.derive(index)).collect()
+    }
+
+    /// Sets the network and address scheme of the key (inherited by its children).
+    fn with_metadata(mut self, metadata: KeyMetadata) -> Self {
+        self.attrs.metadata = metadata;
+        self
+    }
+
+    /// Serializes the key using the SLIP-132 version matching its network and address scheme.
+    fn to_extended_key(&self) -> ExtendedKey {
+        self.to_extended_key_with_version(Version::from_metadata(&self.attrs.metadata, false))
     }
 
-    fn to_extended_key(&self, version: Version) -> ExtendedKey {
+    /// Serializes the key using an explicit `version`, regardless of its metadata.
+    fn to_extended_key_with_version(&self, version: Version) -> ExtendedKey {
         ExtendedKey {
             version: version,
             attrs: self.attrs,
@@ -89,3 +159,66 @@ impl From<&ExtendedPrivateKey> for ExtendedPublicKey {
         }
     }
 }
+
+impl ExtendedPublicKey {
+    /// CKDpub starting from an HMAC already keyed with the parent chain code.
+    fn child_from_mac<C: Verification>(
+        &self,
+        secp: &Secp256k1<C>,
+        mac: &HmacSha512,
+        fingerprint: KeyFingerprint,
+        child_number: ChildNumber,
+    ) -> Result<Self, DerivationError> {
+        if child_number.is_hardened {
+            return Err(DerivationError::HardenedFromPublicKey);
+        }
+        let mut mac = mac.clone();
+        mac.update(&self.p_key.serialize());
+        mac.update(&child_number.index.to_be_bytes());
+        let i = mac.finalize().into_bytes();
+
+        let mut tweak_bytes: [u8; 32] = [0u8; KEY_LENGHT];
+        let mut child_chain_code = [0u8; KEY_LENGHT];
+
+        tweak_bytes.copy_from_slice(&i[0..KEY_LENGHT]);
+        child_chain_code.copy_from_slice(&i[KEY_LENGHT..KEY_LENGHT*2]);
+
+        let child_p_key = self.p_key.add_exp_tweak(secp, &Scalar::from_be_bytes(tweak_bytes).unwrap()).unwrap();
+        Ok(Self {
+            attrs: self.attrs.child(fingerprint, child_number),
+            chain_code: child_chain_code,
+            p_key: child_p_key
+        })
+    }
+}
+
+/// Iterator over the children of an [`ExtendedPublicKey`], see [`ExtendedPublicKeyMethods::derive_children`].
+pub struct ExtendedPublicKeyChildren<'a> {
+    parent: &'a ExtendedPublicKey,
+    secp: &'static Secp256k1<All>,
+    mac: HmacSha512,
+    fingerprint: KeyFingerprint,
+    indexes: Range<u32>,
+}
+
+impl ExtendedPublicKeyChildren<'_> {
+    fn derive(&self, index: u32) -> Result<ExtendedPublicKey, DerivationError> {
+        let child_number = ChildNumber::new(index).unwrap();
+        self.parent.child_from_mac(self.secp, &self.mac, self.fingerprint, child_number)
+    }
+}
+
+impl Iterator for ExtendedPublicKeyChildren<'_> {
+    type Item = Result<ExtendedPublicKey, DerivationError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let index = self.indexes.next()?;
+        Some(self.derive(index))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.indexes.size_hint()
+    }
+}
+
+impl ExactSizeIterator for ExtendedPublicKeyChildren<'_> {}
@@ -0,0 +1,26 @@
+use std::fmt;
+
+#[derive(Debug)]
+pub enum KeyBytesError {
+    InvalidLength { expected: usize, got: usize },
+    InvalidHex(hex::FromHexError),
+    /// The bytes are out of range for a secp256k1 key
+    InvalidKey(secp256k1::Error),
+}
+
+impl fmt::Display for KeyBytesError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        match self {
+            KeyBytesError::InvalidLength { expected, got } => f.write_str(&format!("Invalid length! Got {got}, expected {expected}")),
+            KeyBytesError::InvalidHex(error) => f.write_str(&format!("Invalid hex: {error}")),
+            KeyBytesError::InvalidKey(error) => f.write_str(&format!("{error}")),
+        }
+    }
+}
+
+impl std::error::Error for KeyBytesError {}
+
+/// Copies `bytes` into an array, checking its length.
+pub(crate) fn to_array<const N: usize>(bytes: &[u8]) -> Result<[u8; N], KeyBytesError> {
+    bytes.try_into().map_err(|_| KeyBytesError::InvalidLength { expected: N, got: bytes.len() })
+}
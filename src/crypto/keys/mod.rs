@@ -1,6 +1,11 @@
 pub mod extended_private_key;
 pub mod extended_public_key;
+pub mod cached_deriver;
 pub mod common_attrs;
+pub mod derivable;
+pub mod key_bytes;
+pub mod stacks_key;
+pub mod wif;
 
 pub const KEY_LENGHT: usize = 32;
 pub const EXTENDED_KEY_LENGHT: usize = 33;
@@ -0,0 +1,75 @@
+use secp256k1::{PublicKey, SecretKey};
+use crate::address::c32::c32_address;
+
+use crate::crypto::context::secp256k1_context;
+use crate::crypto::hash::{Hash160, Hasher};
+use crate::network::{AddressVersion, NetworkKind};
+
+/// Suffix stacks.js appends to private keys whose public key is compressed
+const COMPRESSED_SUFFIX: &str = "01";
+
+/// Stacks conveniences on plain secp256k1 keys, for keys that do not come from an extended key.
+///
+/// Usage:
+/// ```rust
+/// use secp256k1::SecretKey;
+/// use stacks_rs::crypto::keys::stacks_key::StacksKeyMethods;
+/// use stacks_rs::network::NetworkKind;
+/// let s_key = SecretKey::from_byte_array(&[1u8; 32]).unwrap();
+/// assert!(s_key.stacks_address(&NetworkKind::Mainnet).starts_with("SP"));
+/// assert_eq!(s_key.to_stacks_hex().len(), 66);
+/// ```
+pub trait StacksKeyMethods {
+    /// Hash160 of the compressed public key
+    fn hash160(&self) -> [u8; 20];
+    /// Single-sig (P2PKH) Stacks address of the key on `network`
+    fn stacks_address(&self, network: &NetworkKind) -> String {
+        c32_address(AddressVersion::single_sig(network).value(), &self.hash160()).unwrap()
+    }
+    /// Hex encoding used by stacks.js and the Stacks CLI
+    fn to_stacks_hex(&self) -> String;
+}
+
+impl StacksKeyMethods for PublicKey {
+    fn hash160(&self) -> [u8; 20] {
+        Hash160::hash(&self.serialize())
+    }
+
+    /// Compressed public key.
+    fn to_stacks_hex(&self) -> String {
+        hex::encode(self.serialize())
+    }
+}
+
+impl StacksKeyMethods for SecretKey {
+    fn hash160(&self) -> [u8; 20] {
+        self.public_key(secp256k1_context()).hash160()
+    }
+
+    /// Secret key followed by `01`, marking that its public key is compressed.
+    fn to_stacks_hex(&self) -> String {
+        hex::encode(self.secret_bytes()) + COMPRESSED_SUFFIX
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use stacks_common::address::AddressHashMode;
+    use stacks_common::types::chainstate::StacksAddress;
+    use stacks_common::util::secp256k1::{Secp256k1PrivateKey, Secp256k1PublicKey};
+
+    use super::*;
+
+    #[test]
+    fn test_stacks_key_methods_match_stacks_common() {
+        let s_key = SecretKey::from_byte_array(&[2u8; 32]).unwrap();
+        let stacks_private_key = Secp256k1PrivateKey::from_hex(&s_key.to_stacks_hex()).unwrap();
+        let stacks_public_key = Secp256k1PublicKey::from_private(&stacks_private_key);
+        assert_eq!(s_key.public_key(secp256k1_context()).to_stacks_hex(), stacks_public_key.to_hex());
+
+        for (network, version) in [(NetworkKind::Mainnet, 22), (NetworkKind::Testnet, 26)] {
+            let address = StacksAddress::from_public_keys(version, &AddressHashMode::SerializeP2PKH, 1, &vec![stacks_public_key]).unwrap();
+            assert_eq!(s_key.stacks_address(&network), address.to_string());
+        }
+    }
+}
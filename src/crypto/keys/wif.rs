@@ -0,0 +1,143 @@
+use std::fmt;
+
+use secp256k1::SecretKey;
+use stacks_common::address::b58;
+
+use crate::crypto::keys::KEY_LENGHT;
+use crate::network::NetworkKind;
+
+const MAINNET_WIF_PREFIX: u8 = 0x80;
+const TESTNET_WIF_PREFIX: u8 = 0xef;
+/// Suffix marking keys whose public key is serialized compressed
+const COMPRESSED_SUFFIX: u8 = 0x01;
+
+#[derive(Debug)]
+pub enum WifError {
+    /// Bad Base58 characters or checksum
+    InvalidEncoding(stacks_common::address::Error),
+    InvalidLength(usize),
+    /// The network byte is not a known WIF prefix
+    InvalidNetwork(u8),
+    /// The network byte is valid but does not match the expected network
+    WrongNetwork(u8),
+    InvalidKey(secp256k1::Error),
+}
+
+impl fmt::Display for WifError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        match self {
+            WifError::InvalidEncoding(error) => f.write_str(&format!("Invalid encoding: {error}")),
+            WifError::InvalidLength(v) => f.write_str(&format!("Invalid WIF length {v}")),
+            WifError::InvalidNetwork(v) => f.write_str(&format!("Invalid network byte {v:#04x}")),
+            WifError::WrongNetwork(v) => f.write_str(&format!("Network byte {v:#04x} does not match the expected network")),
+            WifError::InvalidKey(error) => f.write_str(&format!("{error}")),
+        }
+    }
+}
+
+impl std::error::Error for WifError {}
+
+/// Wallet Import Format (WIF) interop with Bitcoin tooling.
+///
+/// Usage:
+/// ```rust
+/// use secp256k1::SecretKey;
+/// use stacks_rs::crypto::keys::wif::WifMethods;
+/// use stacks_rs::network::NetworkKind;
+/// let s_key = SecretKey::from_byte_array(&[1u8; 32]).unwrap();
+/// let wif = s_key.to_wif(&NetworkKind::Mainnet, true);
+/// assert_eq!(SecretKey::from_wif(&wif, &NetworkKind::Mainnet).unwrap(), (s_key, true));
+/// ```
+pub trait WifMethods {
+    fn to_wif(&self, network: &NetworkKind, compressed: bool) -> String;
+    fn from_wif(wif: &str, network: &NetworkKind) -> Result<(Self, bool), WifError> where Self: Sized;
+}
+
+fn wif_prefix(network: &NetworkKind) -> u8 {
+    match network {
+        NetworkKind::Mainnet => MAINNET_WIF_PREFIX,
+        NetworkKind::Testnet | NetworkKind::Mocknet => TESTNET_WIF_PREFIX,
+    }
+}
+
+impl WifMethods for SecretKey {
+    /// Encodes the key as `prefix || key || [0x01 if compressed]` in Base58Check.
+    fn to_wif(&self, network: &NetworkKind, compressed: bool) -> String {
+        let mut bytes = Vec::with_capacity(KEY_LENGHT + 2);
+        bytes.push(wif_prefix(network));
+        bytes.extend_from_slice(&self.secret_bytes());
+        if compressed {
+            bytes.push(COMPRESSED_SUFFIX);
+        }
+        b58::check_encode_slice(&bytes)
+    }
+
+    /// Decodes a WIF key of `network`, returning the key and whether it is compressed.
+    fn from_wif(wif: &str, network: &NetworkKind) -> Result<(Self, bool), WifError> {
+        let bytes = b58::from_check(wif).map_err(WifError::InvalidEncoding)?;
+        let compressed = match bytes.len() {
+            l if l == KEY_LENGHT + 1 => false,
+            l if l == KEY_LENGHT + 2 && bytes[KEY_LENGHT + 1] == COMPRESSED_SUFFIX => true,
+            l => return Err(WifError::InvalidLength(l)),
+        };
+        match bytes[0] {
+            prefix if prefix == wif_prefix(network) => {}
+            prefix @ (MAINNET_WIF_PREFIX | TESTNET_WIF_PREFIX) => return Err(WifError::WrongNetwork(prefix)),
+            prefix => return Err(WifError::InvalidNetwork(prefix)),
+        }
+        let mut key_bytes = [0u8; KEY_LENGHT];
+        key_bytes.copy_from_slice(&bytes[1..KEY_LENGHT + 1]);
+        let s_key = SecretKey::from_byte_array(&key_bytes).map_err(WifError::InvalidKey)?;
+        Ok((s_key, compressed))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const KEY_HEX: &str = "0c28fca386c7a227600b2fe50b7cae11ec86d3bf1fbe471be89827e19d72aa1d";
+
+    fn test_key() -> SecretKey {
+        let mut bytes = [0u8; KEY_LENGHT];
+        bytes.copy_from_slice(&hex::decode(KEY_HEX).unwrap());
+        SecretKey::from_byte_array(&bytes).unwrap()
+    }
+
+    #[test]
+    fn test_wif_vectors() {
+        let s_key = test_key();
+        assert_eq!(s_key.to_wif(&NetworkKind::Mainnet, false), "5HueCGU8rMjxEXxiPuD5BDku4MkFqeZyd4dZ1jvhTVqvbTLvyTJ");
+        assert_eq!(s_key.to_wif(&NetworkKind::Mainnet, true), "KwdMAjGmerYanjeui5SHS7JkmpZvVipYvB2LJGU1ZxJwYvP98617");
+        assert_eq!(
+            SecretKey::from_wif("KwdMAjGmerYanjeui5SHS7JkmpZvVipYvB2LJGU1ZxJwYvP98617", &NetworkKind::Mainnet).unwrap(),
+            (s_key, true)
+        );
+        assert_eq!(
+            SecretKey::from_wif("5HueCGU8rMjxEXxiPuD5BDku4MkFqeZyd4dZ1jvhTVqvbTLvyTJ", &NetworkKind::Mainnet).unwrap(),
+            (s_key, false)
+        );
+    }
+
+    #[test]
+    fn test_wif_network_validation() {
+        let wif = test_key().to_wif(&NetworkKind::Testnet, true);
+        assert_eq!(SecretKey::from_wif(&wif, &NetworkKind::Mocknet).unwrap(), (test_key(), true));
+        assert!(matches!(
+            SecretKey::from_wif(&wif, &NetworkKind::Mainnet),
+            Err(WifError::WrongNetwork(TESTNET_WIF_PREFIX))
+        ));
+
+        let mut bytes = vec![0x42];
+        bytes.extend_from_slice(&test_key().secret_bytes());
+        let unknown = b58::check_encode_slice(&bytes);
+        assert!(matches!(SecretKey::from_wif(&unknown, &NetworkKind::Mainnet), Err(WifError::InvalidNetwork(0x42))));
+    }
+
+    #[test]
+    fn test_wif_invalid() {
+        assert!(matches!(SecretKey::from_wif("invalid", &NetworkKind::Mainnet), Err(WifError::InvalidEncoding(_))));
+        let short = b58::check_encode_slice(&[MAINNET_WIF_PREFIX, 1, 2, 3]);
+        assert!(matches!(SecretKey::from_wif(&short, &NetworkKind::Mainnet), Err(WifError::InvalidLength(4))));
+    }
+}
@@ -2,4 +2,13 @@ pub mod encryption;
 pub mod hmac;
 pub mod hash;
 pub mod utils;
-pub mod keys;
\ No newline at end of file
+pub mod keys;
+pub mod context;
+pub mod kdf;
+pub mod cipher;
+pub mod constant_time;
+pub mod signature;
+pub mod taproot;
+pub mod ecies;
+pub mod vrf;
+pub(crate) mod arith;
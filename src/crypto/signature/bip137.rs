@@ -0,0 +1,209 @@
+use std::fmt;
+
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+use secp256k1::{PublicKey, SecretKey};
+
+use crate::crypto::hash::{DoubleSha256, Hash160, Hasher};
+
+use super::bip322::compact_size;
+use super::recoverable::{self, RecoverableSignature, RECOVERABLE_SIGNATURE_LENGTH};
+use super::SignatureError;
+
+/// Length-prefixed magic of Bitcoin signed messages.
+pub const MESSAGE_MAGIC: &[u8] = b"\x18Bitcoin Signed Message:\n";
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Bip137Error {
+    InvalidEncoding(String),
+    InvalidHeader(u8),
+    Signature(SignatureError),
+    /// The recovered public key does not match the address
+    AddressMismatch,
+}
+
+impl fmt::Display for Bip137Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        match self {
+            Bip137Error::InvalidEncoding(v) => f.write_str(&format!("Invalid signature encoding: {v}")),
+            Bip137Error::InvalidHeader(v) => f.write_str(&format!("Invalid header byte {v}")),
+            Bip137Error::Signature(v) => f.write_str(&format!("{v}")),
+            Bip137Error::AddressMismatch => f.write_str("Signature does not match the address"),
+        }
+    }
+}
+
+impl std::error::Error for Bip137Error {}
+
+impl From<SignatureError> for Bip137Error {
+    fn from(err: SignatureError) -> Self {
+        Bip137Error::Signature(err)
+    }
+}
+
+/// Address type encoded in the header byte of a BIP137 signature.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Bip137AddressType {
+    /// P2PKH of the uncompressed public key (headers `27..=30`)
+    P2pkhUncompressed,
+    /// P2PKH of the compressed public key (headers `31..=34`)
+    P2pkh,
+    /// P2SH-wrapped P2WPKH (headers `35..=38`)
+    P2shP2wpkh,
+    /// Native segwit P2WPKH (headers `39..=42`)
+    P2wpkh,
+}
+
+impl Bip137AddressType {
+    fn header_offset(&self) -> u8 {
+        match self {
+            Bip137AddressType::P2pkhUncompressed => 27,
+            Bip137AddressType::P2pkh => 31,
+            Bip137AddressType::P2shP2wpkh => 35,
+            Bip137AddressType::P2wpkh => 39,
+        }
+    }
+
+    /// Address type and recovery id of a header byte.
+    pub fn from_header(header: u8) -> Result<(Self, u8), Bip137Error> {
+        let address_type = match header {
+            27..=30 => Bip137AddressType::P2pkhUncompressed,
+            31..=34 => Bip137AddressType::P2pkh,
+            35..=38 => Bip137AddressType::P2shP2wpkh,
+            39..=42 => Bip137AddressType::P2wpkh,
+            _ => return Err(Bip137Error::InvalidHeader(header)),
+        };
+        Ok((address_type, header - address_type.header_offset()))
+    }
+
+    /// scriptPubKey of the address of `public_key`.
+    pub fn script_pubkey(&self, public_key: &PublicKey) -> Vec<u8> {
+        match self {
+            Bip137AddressType::P2pkhUncompressed => p2pkh_script(&Hash160::hash(&public_key.serialize_uncompressed())),
+            Bip137AddressType::P2pkh => p2pkh_script(&Hash160::hash(&public_key.serialize())),
+            Bip137AddressType::P2shP2wpkh => {
+                let redeem_script = p2wpkh_script(&Hash160::hash(&public_key.serialize()));
+                [&[0xa9, 0x14][..], &Hash160::hash(&redeem_script), &[0x87]].concat()
+            }
+            Bip137AddressType::P2wpkh => p2wpkh_script(&Hash160::hash(&public_key.serialize())),
+        }
+    }
+}
+
+fn p2pkh_script(hash: &[u8; 20]) -> Vec<u8> {
+    [&[0x76, 0xa9, 0x14][..], hash, &[0x88, 0xac]].concat()
+}
+
+fn p2wpkh_script(hash: &[u8; 20]) -> Vec<u8> {
+    [&[0x00, 0x14][..], hash].concat()
+}
+
+/// `dsha256(magic || compact_size(len) || message)`
+pub fn message_hash(message: &[u8]) -> [u8; 32] {
+    DoubleSha256::hash(&[MESSAGE_MAGIC, &compact_size(message.len()), message].concat())
+}
+
+/// Signs `message` for the `address_type` address of `key`, returning the base64 signature.
+///
+/// Usage:
+/// ```rust
+/// use secp256k1::SecretKey;
+/// use stacks_rs::crypto::context::secp256k1_context;
+/// use stacks_rs::crypto::signature::bip137::{sign_message, verify_message, Bip137AddressType};
+/// let key = SecretKey::from_byte_array(&[1u8; 32]).unwrap();
+/// let signature = sign_message(b"Hello World", &key, Bip137AddressType::P2wpkh);
+/// let script = Bip137AddressType::P2wpkh.script_pubkey(&key.public_key(secp256k1_context()));
+/// assert!(verify_message(b"Hello World", &script, &signature).is_ok());
+/// ```
+pub fn sign_message(message: &[u8], key: &SecretKey, address_type: Bip137AddressType) -> String {
+    let mut bytes = recoverable::sign_recoverable(&message_hash(message), key).to_vrs();
+    bytes[0] += address_type.header_offset();
+    BASE64.encode(bytes)
+}
+
+/// Recovers the public key and the address type of a base64 signature of `message`.
+pub fn recover_message(message: &[u8], signature: &str) -> Result<(PublicKey, Bip137AddressType), Bip137Error> {
+    let bytes = BASE64.decode(signature).map_err(|err| Bip137Error::InvalidEncoding(format!("{err}")))?;
+    if bytes.len() != RECOVERABLE_SIGNATURE_LENGTH {
+        return Err(SignatureError::InvalidLength(bytes.len()).into());
+    }
+    let (address_type, recovery_id) = Bip137AddressType::from_header(bytes[0])?;
+    let signature = RecoverableSignature::from_vrs(&[&[recovery_id][..], &bytes[1..]].concat())?;
+    Ok((recoverable::recover(&message_hash(message), &signature)?, address_type))
+}
+
+/// Verifies a base64 signature of `message` for the address with `script_pubkey`.
+pub fn verify_message(message: &[u8], script_pubkey: &[u8], signature: &str) -> Result<(), Bip137Error> {
+    let (public_key, address_type) = recover_message(message, signature)?;
+    if address_type.script_pubkey(&public_key) != script_pubkey {
+        return Err(Bip137Error::AddressMismatch);
+    }
+    Ok(())
+}
+
+/// Verifies a signature of `message` by `public_key`, whatever address type its header claims.
+pub fn verify_message_with_public_key(message: &[u8], public_key: &PublicKey, signature: &str) -> Result<(), Bip137Error> {
+    let (recovered, _) = recover_message(message, signature)?;
+    if recovered != *public_key {
+        return Err(Bip137Error::AddressMismatch);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::crypto::context::secp256k1_context;
+    use crate::crypto::keys::wif::WifMethods;
+    use crate::network::NetworkKind;
+
+    // bitcoinjs-message README vector
+    const WIF: &str = "5KYZdUEo39z3FPrtuX2QbbwGnNP5zTd7yyr2SC1j299sBCnWjss";
+    const MESSAGE: &[u8] = b"This is an example of a signed message.";
+    const SIGNATURE: &str = "G9L5yLFjti0QTHhPyFrZCT1V/MMnBtXKmoiKDZ78NDBjERki6ZTQZdSMCtkgoNmp17By9ItJr8o7ChX0XxY91nk=";
+
+    fn key() -> SecretKey {
+        SecretKey::from_wif(WIF, &NetworkKind::Mainnet).unwrap().0
+    }
+
+    #[test]
+    fn test_sign_vector() {
+        assert_eq!(sign_message(MESSAGE, &key(), Bip137AddressType::P2pkhUncompressed), SIGNATURE);
+        let script = Bip137AddressType::P2pkhUncompressed.script_pubkey(&key().public_key(secp256k1_context()));
+        assert!(verify_message(MESSAGE, &script, SIGNATURE).is_ok());
+    }
+
+    #[test]
+    fn test_address_types() {
+        let public_key = key().public_key(secp256k1_context());
+        for address_type in [
+            Bip137AddressType::P2pkhUncompressed,
+            Bip137AddressType::P2pkh,
+            Bip137AddressType::P2shP2wpkh,
+            Bip137AddressType::P2wpkh,
+        ] {
+            let signature = sign_message(MESSAGE, &key(), address_type);
+            assert_eq!(recover_message(MESSAGE, &signature).unwrap(), (public_key, address_type));
+            assert!(verify_message(MESSAGE, &address_type.script_pubkey(&public_key), &signature).is_ok());
+            assert!(verify_message_with_public_key(MESSAGE, &public_key, &signature).is_ok());
+        }
+
+        // the header decides which address the key is checked against
+        let signature = sign_message(MESSAGE, &key(), Bip137AddressType::P2pkh);
+        let script = Bip137AddressType::P2wpkh.script_pubkey(&public_key);
+        assert_eq!(verify_message(MESSAGE, &script, &signature), Err(Bip137Error::AddressMismatch));
+        assert_eq!(verify_message(b"other", &Bip137AddressType::P2pkh.script_pubkey(&public_key), &signature), Err(Bip137Error::AddressMismatch));
+    }
+
+    #[test]
+    fn test_invalid_signatures() {
+        let mut bytes = BASE64.decode(SIGNATURE).unwrap();
+        bytes[0] = 43;
+        assert_eq!(recover_message(MESSAGE, &BASE64.encode(&bytes)), Err(Bip137Error::InvalidHeader(43)));
+        assert_eq!(
+            recover_message(MESSAGE, &BASE64.encode(&bytes[..64])),
+            Err(Bip137Error::Signature(SignatureError::InvalidLength(64)))
+        );
+        assert!(matches!(recover_message(MESSAGE, "not base64!"), Err(Bip137Error::InvalidEncoding(_))));
+    }
+}
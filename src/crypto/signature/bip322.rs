@@ -0,0 +1,354 @@
+use std::fmt;
+
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+use secp256k1::{PublicKey, SecretKey, XOnlyPublicKey};
+
+use crate::crypto::context::secp256k1_context;
+use crate::crypto::hash::{tagged_hash, DoubleSha256, Hash160, Hasher, Sha256};
+use crate::crypto::taproot;
+
+use super::ecdsa::{self, EcdsaSignature};
+use super::schnorr::{self, SchnorrSignature, SCHNORR_SIGNATURE_LENGTH};
+
+const SIGHASH_DEFAULT: u8 = 0x00;
+const SIGHASH_ALL: u8 = 0x01;
+const OP_RETURN: u8 = 0x6a;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Bip322Error {
+    /// Only P2WPKH and key-path P2TR scripts are supported by the simple format
+    UnsupportedScript(String),
+    InvalidEncoding(String),
+    /// The witness does not have the shape expected for the script
+    InvalidWitness,
+    /// The public key of the witness does not match the script
+    KeyMismatch,
+    /// Only `SIGHASH_ALL` (and `SIGHASH_DEFAULT` for taproot) are accepted
+    UnsupportedSighash(u8),
+    InvalidSignature,
+}
+
+impl fmt::Display for Bip322Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        match self {
+            Bip322Error::UnsupportedScript(v) => f.write_str(&format!("Unsupported script {v}")),
+            Bip322Error::InvalidEncoding(v) => f.write_str(&format!("Invalid signature encoding: {v}")),
+            Bip322Error::InvalidWitness => f.write_str("Invalid witness"),
+            Bip322Error::KeyMismatch => f.write_str("Public key does not match the script"),
+            Bip322Error::UnsupportedSighash(v) => f.write_str(&format!("Unsupported sighash type {v:#04x}")),
+            Bip322Error::InvalidSignature => f.write_str("Invalid signature"),
+        }
+    }
+}
+
+impl std::error::Error for Bip322Error {}
+
+/// Address types that can produce BIP322 simple signatures.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Bip322AddressType {
+    /// Native segwit v0 (`bc1q...`)
+    P2wpkh,
+    /// BIP86 key-path taproot (`bc1p...`)
+    P2tr,
+}
+
+enum Script {
+    P2wpkh([u8; 20]),
+    P2tr(XOnlyPublicKey),
+}
+
+impl Script {
+    fn parse(script_pubkey: &[u8]) -> Result<Self, Bip322Error> {
+        match script_pubkey {
+            [0x00, 0x14, hash @ ..] if hash.len() == 20 => Ok(Script::P2wpkh(hash.try_into().unwrap())),
+            [0x51, 0x20, key @ ..] if key.len() == 32 => XOnlyPublicKey::from_slice(key)
+                .map(Script::P2tr)
+                .map_err(|_| Bip322Error::UnsupportedScript(hex::encode(script_pubkey))),
+            _ => Err(Bip322Error::UnsupportedScript(hex::encode(script_pubkey))),
+        }
+    }
+}
+
+/// `hash_BIP0322-signed-message(message)`
+pub fn message_hash(message: &[u8]) -> [u8; 32] {
+    tagged_hash("BIP0322-signed-message", message)
+}
+
+/// scriptPubKey of `public_key` for `address_type`.
+pub fn script_pubkey(public_key: &PublicKey, address_type: Bip322AddressType) -> Result<Vec<u8>, Bip322Error> {
+    match address_type {
+        Bip322AddressType::P2wpkh => Ok([&[0x00, 0x14][..], &Hash160::hash(&public_key.serialize())].concat()),
+        Bip322AddressType::P2tr => {
+            let (output_key, _) = taproot::tweak_public_key(&public_key.x_only_public_key().0, None)
+                .map_err(|_| Bip322Error::InvalidSignature)?;
+            Ok(taproot::p2tr_script_pubkey(&output_key).to_vec())
+        }
+    }
+}
+
+/// Signs `message` for the `address_type` address of `key`, returning the base64 "simple" signature.
+///
+/// Usage:
+/// ```rust
+/// use secp256k1::SecretKey;
+/// use stacks_rs::crypto::context::secp256k1_context;
+/// use stacks_rs::crypto::signature::bip322::{script_pubkey, sign_simple, verify_simple, Bip322AddressType};
+/// let key = SecretKey::from_byte_array(&[1u8; 32]).unwrap();
+/// let signature = sign_simple(b"Hello World", &key, Bip322AddressType::P2wpkh).unwrap();
+/// let script = script_pubkey(&key.public_key(secp256k1_context()), Bip322AddressType::P2wpkh).unwrap();
+/// assert!(verify_simple(b"Hello World", &script, &signature).is_ok());
+/// ```
+pub fn sign_simple(message: &[u8], key: &SecretKey, address_type: Bip322AddressType) -> Result<String, Bip322Error> {
+    let public_key = key.public_key(secp256k1_context());
+    let script_pubkey = script_pubkey(&public_key, address_type)?;
+    let to_spend_txid = to_spend_txid(&script_pubkey, message);
+
+    let witness = match Script::parse(&script_pubkey)? {
+        Script::P2wpkh(hash) => {
+            let sighash = p2wpkh_sighash(&to_spend_txid, &hash);
+            let signature = sign_low_r(&sighash, key);
+            vec![[signature.to_der(), vec![SIGHASH_ALL]].concat(), public_key.serialize().to_vec()]
+        }
+        Script::P2tr(_) => {
+            let sighash = p2tr_sighash(&to_spend_txid, &script_pubkey, SIGHASH_DEFAULT);
+            let tweaked = taproot::tweak_secret_key(key, None).map_err(|_| Bip322Error::InvalidSignature)?;
+            vec![schnorr::sign_schnorr(&sighash, &tweaked).to_bytes().to_vec()]
+        }
+    };
+    Ok(BASE64.encode(serialize_witness(&witness)))
+}
+
+/// Verifies a base64 "simple" signature of `message` for the address with `script_pubkey`.
+pub fn verify_simple(message: &[u8], script_pubkey: &[u8], signature: &str) -> Result<(), Bip322Error> {
+    let witness_bytes = BASE64.decode(signature).map_err(|err| Bip322Error::InvalidEncoding(format!("{err}")))?;
+    let witness = deserialize_witness(&witness_bytes)?;
+    let to_spend_txid = to_spend_txid(script_pubkey, message);
+
+    match Script::parse(script_pubkey)? {
+        Script::P2wpkh(hash) => {
+            let [signature, public_key] = witness.as_slice() else {
+                return Err(Bip322Error::InvalidWitness);
+            };
+            if Hash160::hash(public_key) != hash {
+                return Err(Bip322Error::KeyMismatch);
+            }
+            let public_key = PublicKey::from_slice(public_key).map_err(|_| Bip322Error::InvalidWitness)?;
+            let (sighash_type, der) = signature.split_last().ok_or(Bip322Error::InvalidWitness)?;
+            if *sighash_type != SIGHASH_ALL {
+                return Err(Bip322Error::UnsupportedSighash(*sighash_type));
+            }
+            let signature = EcdsaSignature::from_der(der).map_err(|_| Bip322Error::InvalidSignature)?;
+            ecdsa::verify(&p2wpkh_sighash(&to_spend_txid, &hash), &signature, &public_key).map_err(|_| Bip322Error::InvalidSignature)
+        }
+        Script::P2tr(output_key) => {
+            let [signature] = witness.as_slice() else {
+                return Err(Bip322Error::InvalidWitness);
+            };
+            let sighash_type = match signature.len() {
+                SCHNORR_SIGNATURE_LENGTH => SIGHASH_DEFAULT,
+                65 if signature[64] == SIGHASH_ALL => SIGHASH_ALL,
+                65 => return Err(Bip322Error::UnsupportedSighash(signature[64])),
+                _ => return Err(Bip322Error::InvalidWitness),
+            };
+            let signature = SchnorrSignature::from_bytes(&signature[..SCHNORR_SIGNATURE_LENGTH]).map_err(|_| Bip322Error::InvalidSignature)?;
+            let sighash = p2tr_sighash(&to_spend_txid, script_pubkey, sighash_type);
+            schnorr::verify_schnorr(&sighash, &signature, &output_key).map_err(|_| Bip322Error::InvalidSignature)
+        }
+    }
+}
+
+/// RFC6979 signature with a low R value, ground with a counter as extra entropy like Bitcoin Core
+/// so the signatures match other wallets byte for byte.
+fn sign_low_r(sighash: &[u8; 32], key: &SecretKey) -> EcdsaSignature {
+    let mut signature = ecdsa::sign(sighash, key);
+    let mut counter = 0u32;
+    while signature.to_compact()[0] >= 0x80 {
+        counter += 1;
+        let mut extra_entropy = [0u8; 32];
+        extra_entropy[..4].copy_from_slice(&counter.to_le_bytes());
+        signature = ecdsa::sign_with_extra_entropy(sighash, key, &extra_entropy);
+    }
+    signature
+}
+
+/// Bitcoin `CompactSize` length prefix.
+pub(crate) fn compact_size(len: usize) -> Vec<u8> {
+    match len {
+        0..=0xfc => vec![len as u8],
+        0xfd..=0xffff => [&[0xfd][..], &(len as u16).to_le_bytes()].concat(),
+        _ => [&[0xfe][..], &(len as u32).to_le_bytes()].concat(),
+    }
+}
+
+fn read_compact_size(bytes: &mut &[u8]) -> Result<usize, Bip322Error> {
+    let (len, rest) = match bytes.first() {
+        Some(0xfd) => (bytes.get(1..3).map(|v| u16::from_le_bytes(v.try_into().unwrap()) as usize), 3),
+        Some(0xfe) => (bytes.get(1..5).map(|v| u32::from_le_bytes(v.try_into().unwrap()) as usize), 5),
+        Some(0xff) => return Err(Bip322Error::InvalidWitness),
+        Some(len) => (Some(*len as usize), 1),
+        None => (None, 0),
+    };
+    let len = len.ok_or(Bip322Error::InvalidWitness)?;
+    *bytes = &bytes[rest..];
+    Ok(len)
+}
+
+fn serialize_witness(items: &[Vec<u8>]) -> Vec<u8> {
+    let mut bytes = compact_size(items.len());
+    for item in items {
+        bytes.extend(compact_size(item.len()));
+        bytes.extend(item);
+    }
+    bytes
+}
+
+fn deserialize_witness(mut bytes: &[u8]) -> Result<Vec<Vec<u8>>, Bip322Error> {
+    let count = read_compact_size(&mut bytes)?;
+    let mut items = Vec::new();
+    for _ in 0..count {
+        let len = read_compact_size(&mut bytes)?;
+        if bytes.len() < len {
+            return Err(Bip322Error::InvalidWitness);
+        }
+        items.push(bytes[..len].to_vec());
+        bytes = &bytes[len..];
+    }
+    if !bytes.is_empty() {
+        return Err(Bip322Error::InvalidWitness);
+    }
+    Ok(items)
+}
+
+/// txid (internal byte order) of the virtual `to_spend` transaction.
+fn to_spend_txid(script_pubkey: &[u8], message: &[u8]) -> [u8; 32] {
+    let mut tx = Vec::new();
+    tx.extend(0u32.to_le_bytes()); // version
+    tx.push(1); // inputs
+    tx.extend([0u8; 32]);
+    tx.extend(0xffff_ffffu32.to_le_bytes());
+    let script_sig = [&[0x00, 0x20][..], &message_hash(message)].concat();
+    tx.extend(compact_size(script_sig.len()));
+    tx.extend(script_sig);
+    tx.extend(0u32.to_le_bytes()); // sequence
+    tx.push(1); // outputs
+    tx.extend(0u64.to_le_bytes());
+    tx.extend(compact_size(script_pubkey.len()));
+    tx.extend(script_pubkey);
+    tx.extend(0u32.to_le_bytes()); // locktime
+    DoubleSha256::hash(&tx)
+}
+
+/// The single `OP_RETURN` output of `to_sign`.
+fn to_sign_output() -> Vec<u8> {
+    [&0u64.to_le_bytes()[..], &[1, OP_RETURN]].concat()
+}
+
+/// BIP143 sighash of the `to_sign` input spending the P2WPKH output of `to_spend`.
+fn p2wpkh_sighash(to_spend_txid: &[u8; 32], key_hash: &[u8; 20]) -> [u8; 32] {
+    let outpoint = [&to_spend_txid[..], &0u32.to_le_bytes()].concat();
+    let script_code = [&[0x19, 0x76, 0xa9, 0x14][..], key_hash, &[0x88, 0xac]].concat();
+
+    let mut preimage = Vec::new();
+    preimage.extend(0u32.to_le_bytes()); // version
+    preimage.extend(DoubleSha256::hash(&outpoint));
+    preimage.extend(DoubleSha256::hash(&0u32.to_le_bytes()));
+    preimage.extend(&outpoint);
+    preimage.extend(script_code);
+    preimage.extend(0u64.to_le_bytes()); // amount
+    preimage.extend(0u32.to_le_bytes()); // sequence
+    preimage.extend(DoubleSha256::hash(&to_sign_output()));
+    preimage.extend(0u32.to_le_bytes()); // locktime
+    preimage.extend((SIGHASH_ALL as u32).to_le_bytes());
+    DoubleSha256::hash(&preimage)
+}
+
+/// BIP341 key-path sighash of the `to_sign` input spending the P2TR output of `to_spend`.
+fn p2tr_sighash(to_spend_txid: &[u8; 32], script_pubkey: &[u8], sighash_type: u8) -> [u8; 32] {
+    let outpoint = [&to_spend_txid[..], &0u32.to_le_bytes()].concat();
+    let script_pubkey = [&compact_size(script_pubkey.len())[..], script_pubkey].concat();
+
+    let mut message = vec![0x00, sighash_type]; // epoch, hash type
+    message.extend(0u32.to_le_bytes()); // version
+    message.extend(0u32.to_le_bytes()); // locktime
+    message.extend(Sha256::hash(&outpoint));
+    message.extend(Sha256::hash(&0u64.to_le_bytes())); // amounts
+    message.extend(Sha256::hash(&script_pubkey));
+    message.extend(Sha256::hash(&0u32.to_le_bytes())); // sequences
+    message.extend(Sha256::hash(&to_sign_output()));
+    message.push(0x00); // key path, no annex
+    message.extend(0u32.to_le_bytes()); // input index
+    tagged_hash("TapSighash", &message)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::crypto::keys::wif::WifMethods;
+    use crate::network::NetworkKind;
+
+    const WIF: &str = "L3VFeEujGtevx9w18HD1fhRbCH67Az2dpCymeRE1SoPK6XQtaN2k";
+
+    fn key() -> SecretKey {
+        SecretKey::from_wif(WIF, &NetworkKind::Mainnet).unwrap().0
+    }
+
+    fn display_txid(txid: [u8; 32]) -> String {
+        let mut txid = txid;
+        txid.reverse();
+        hex::encode(txid)
+    }
+
+    #[test]
+    fn test_message_hash_vectors() {
+        assert_eq!(hex::encode(message_hash(b"")), "c90c269c4f8fcbe6880f72a721ddfbf1914268a794cbb21cfafee13770ae19f1");
+        assert_eq!(hex::encode(message_hash(b"Hello World")), "f0eb03b1a75ac6d9847f55c624a99169b5dccba2a31f5b23bea77ba270de0a7a");
+    }
+
+    #[test]
+    fn test_p2wpkh_vectors() {
+        // BIP322 test vectors for bc1q9vza2e8x573nczrlzms0wvx3gsqjx7vavgkx0l
+        let script = script_pubkey(&key().public_key(secp256k1_context()), Bip322AddressType::P2wpkh).unwrap();
+        assert_eq!(hex::encode(&script), "00142b05d564e6a7a33c087f16e0f730d1440123799d");
+        assert_eq!(display_txid(to_spend_txid(&script, b"")), "c5680aa69bb8d860bf82d4e9cd3504b55dde018de765a91bb566283c545a99a7");
+        assert_eq!(display_txid(to_spend_txid(&script, b"Hello World")), "b79d196740ad5217771c1098fc4a4b51e0535c32236c71f1ea4d61a2d603352b");
+
+        let empty = "AkcwRAIgM2gBAQqvZX15ZiysmKmQpDrG83avLIT492QBzLnQIxYCIBaTpOaD20qRlEylyxFSeEA2ba9YOixpX8z46TSDtS40ASECx/EgAxlkQpQ9hYjgGu6EBCPMVPwVIVJqO4XCsMvViHI=";
+        let hello = "AkcwRAIgZRfIY3p7/DoVTty6YZbWS71bc5Vct9p9Fia83eRmw2QCICK/ENGfwLtptFluMGs2KsqoNSk89pO7F29zJLUx9a/sASECx/EgAxlkQpQ9hYjgGu6EBCPMVPwVIVJqO4XCsMvViHI=";
+        assert!(verify_simple(b"", &script, empty).is_ok());
+        assert!(verify_simple(b"Hello World", &script, hello).is_ok());
+        assert_eq!(verify_simple(b"Hello World", &script, empty), Err(Bip322Error::InvalidSignature));
+
+        // RFC6979 signatures are deterministic
+        assert_eq!(sign_simple(b"", &key(), Bip322AddressType::P2wpkh).unwrap(), empty);
+        assert_eq!(sign_simple(b"Hello World", &key(), Bip322AddressType::P2wpkh).unwrap(), hello);
+    }
+
+    #[test]
+    fn test_p2tr_vector() {
+        // BIP322 test vector for bc1ppv609nr0vr25u07u95waq5lucwfm6tde4nydujnu8npg4q75mr5sxq8lt3 (SIGHASH_ALL)
+        let script = script_pubkey(&key().public_key(secp256k1_context()), Bip322AddressType::P2tr).unwrap();
+        assert_eq!(hex::encode(&script), "51200b34f2cc6f60d54e3fdc2d1dd053fcc393bd2db9acc8de4a7c3cc28a83d4d8e9");
+        let signature = "AUHd69PrJQEv+oKTfZ8l+WROBHuy9HKrbFCJu7U1iK2iiEy1vMU5EfMtjc+VSHM7aU0SDbak5IUZRVno2P5mjSafAQ==";
+        assert!(verify_simple(b"Hello World", &script, signature).is_ok());
+        assert_eq!(verify_simple(b"Hello", &script, signature), Err(Bip322Error::InvalidSignature));
+
+        let signature = sign_simple(b"Hello World", &key(), Bip322AddressType::P2tr).unwrap();
+        assert!(verify_simple(b"Hello World", &script, &signature).is_ok());
+    }
+
+    #[test]
+    fn test_invalid_inputs() {
+        let script = script_pubkey(&key().public_key(secp256k1_context()), Bip322AddressType::P2wpkh).unwrap();
+        let signature = sign_simple(b"Hello World", &key(), Bip322AddressType::P2wpkh).unwrap();
+
+        let other = SecretKey::from_byte_array(&[1u8; 32]).unwrap();
+        let other_script = script_pubkey(&other.public_key(secp256k1_context()), Bip322AddressType::P2wpkh).unwrap();
+        assert_eq!(verify_simple(b"Hello World", &other_script, &signature), Err(Bip322Error::KeyMismatch));
+
+        let p2pkh = hex::decode("76a9142b05d564e6a7a33c087f16e0f730d1440123799d88ac").unwrap();
+        assert!(matches!(verify_simple(b"Hello World", &p2pkh, &signature), Err(Bip322Error::UnsupportedScript(_))));
+        assert!(matches!(verify_simple(b"Hello World", &script, "not base64!"), Err(Bip322Error::InvalidEncoding(_))));
+        assert_eq!(verify_simple(b"Hello World", &script, "AQ=="), Err(Bip322Error::InvalidWitness));
+    }
+}
@@ -0,0 +1,182 @@
+use secp256k1::ecdsa::Signature;
+use secp256k1::{Message, PublicKey, SecretKey};
+
+use crate::crypto::context::secp256k1_context;
+
+use super::recoverable::RecoverableSignature;
+use super::SignatureError;
+
+pub const COMPACT_SIGNATURE_LENGTH: usize = 64;
+
+/// ECDSA signature without recovery id, convertible between the compact (`r || s`) and DER encodings.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct EcdsaSignature(Signature);
+
+impl EcdsaSignature {
+    /// Parses `r || s`. High-S signatures are accepted, see [`EcdsaSignature::normalize_s`].
+    pub fn from_compact(bytes: &[u8]) -> Result<Self, SignatureError> {
+        if bytes.len() != COMPACT_SIGNATURE_LENGTH {
+            return Err(SignatureError::InvalidLength(bytes.len()));
+        }
+        Signature::from_compact(bytes).map(Self).map_err(|_| SignatureError::InvalidSignature)
+    }
+
+    /// Parses a strict DER signature (BIP66).
+    pub fn from_der(bytes: &[u8]) -> Result<Self, SignatureError> {
+        Signature::from_der(bytes).map(Self).map_err(|_| SignatureError::InvalidSignature)
+    }
+
+    /// Parses a DER signature, tolerating the encoding violations found in old Bitcoin transactions.
+    pub fn from_der_lax(bytes: &[u8]) -> Result<Self, SignatureError> {
+        Signature::from_der_lax(bytes).map(Self).map_err(|_| SignatureError::InvalidSignature)
+    }
+
+    pub fn to_compact(&self) -> [u8; COMPACT_SIGNATURE_LENGTH] {
+        self.0.serialize_compact()
+    }
+
+    pub fn to_der(&self) -> Vec<u8> {
+        self.0.serialize_der().to_vec()
+    }
+
+    /// Whether `s` is in the lower half of the curve order, as required by the Stacks node and Bitcoin policy.
+    pub fn is_low_s(&self) -> bool {
+        let mut normalized = self.0;
+        normalized.normalize_s();
+        normalized == self.0
+    }
+
+    /// Replaces `s` with `n - s` if it is in the upper half of the curve order.
+    /// Returns whether the signature changed.
+    pub fn normalize_s(&mut self) -> bool {
+        let was_low_s = self.is_low_s();
+        self.0.normalize_s();
+        !was_low_s
+    }
+
+    pub fn as_secp256k1(&self) -> &Signature {
+        &self.0
+    }
+}
+
+impl From<Signature> for EcdsaSignature {
+    fn from(signature: Signature) -> Self {
+        Self(signature)
+    }
+}
+
+impl From<&RecoverableSignature> for EcdsaSignature {
+    fn from(signature: &RecoverableSignature) -> Self {
+        Self(signature.to_secp256k1().to_standard())
+    }
+}
+
+/// Signs `message_hash` with an RFC6979 deterministic nonce. The signature is always low-S.
+///
+/// Usage:
+/// ```rust
+/// use secp256k1::SecretKey;
+/// use stacks_rs::crypto::context::secp256k1_context;
+/// use stacks_rs::crypto::signature::ecdsa::{sign, verify, EcdsaSignature};
+/// let key = SecretKey::from_byte_array(&[1u8; 32]).unwrap();
+/// let signature = sign(&[7u8; 32], &key);
+/// let der = signature.to_der();
+/// assert!(verify(&[7u8; 32], &EcdsaSignature::from_der(&der).unwrap(), &key.public_key(secp256k1_context())).is_ok());
+/// ```
+pub fn sign(message_hash: &[u8; 32], key: &SecretKey) -> EcdsaSignature {
+    EcdsaSignature(secp256k1_context().sign_ecdsa(&Message::from_digest(*message_hash), key))
+}
+
+/// Same as [`sign`] but mixes `extra_entropy` into the RFC6979 nonce (section 3.6),
+/// yielding a different (still deterministic) signature for each value.
+pub fn sign_with_extra_entropy(message_hash: &[u8; 32], key: &SecretKey, extra_entropy: &[u8; 32]) -> EcdsaSignature {
+    EcdsaSignature(secp256k1_context().sign_ecdsa_with_noncedata(&Message::from_digest(*message_hash), key, extra_entropy))
+}
+
+/// Verifies `signature` over `message_hash`. High-S signatures are rejected, like the Stacks node does.
+pub fn verify(message_hash: &[u8; 32], signature: &EcdsaSignature, public_key: &PublicKey) -> Result<(), SignatureError> {
+    secp256k1_context()
+        .verify_ecdsa(&Message::from_digest(*message_hash), &signature.0, public_key)
+        .map_err(|_| SignatureError::InvalidSignature)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::crypto::hash::{Hasher, Sha256};
+    use crate::crypto::signature::recoverable::sign_recoverable;
+
+    /// Order of the secp256k1 group.
+    const CURVE_ORDER: &str = "fffffffffffffffffffffffffffffffebaaedce6af48a03bbfd25e8cd0364141";
+
+    fn high_s(signature: &EcdsaSignature) -> EcdsaSignature {
+        let compact = signature.to_compact();
+        let order = hex::decode(CURVE_ORDER).unwrap();
+        // s' = n - s, big-endian subtraction
+        let mut s = [0u8; 32];
+        let mut borrow = 0i16;
+        for i in (0..32).rev() {
+            let mut digit = order[i] as i16 - compact[32 + i] as i16 - borrow;
+            borrow = (digit < 0) as i16;
+            digit += borrow * 256;
+            s[i] = digit as u8;
+        }
+        EcdsaSignature::from_compact(&[&compact[..32], &s[..]].concat()).unwrap()
+    }
+
+    #[test]
+    fn test_rfc6979_vector() {
+        // private key 1, message "Satoshi Nakamoto"
+        let mut key_bytes = [0u8; 32];
+        key_bytes[31] = 1;
+        let key = SecretKey::from_byte_array(&key_bytes).unwrap();
+        let signature = sign(&Sha256::hash("Satoshi Nakamoto".as_bytes()), &key);
+        assert_eq!(
+            hex::encode(signature.to_compact()),
+            "934b1ea10a4b3c1757e2b0c017d0b6143ce3c9a7e6a4a49860d7a6ab210ee3d82442ce9d2b916064108014783e923ec36b49743e2ffa1c4496f01a512aafd9e5"
+        );
+        assert!(signature.is_low_s());
+    }
+
+    #[test]
+    fn test_der_compact_roundtrip() {
+        let key = SecretKey::from_byte_array(&[3u8; 32]).unwrap();
+        let signature = sign(&[9u8; 32], &key);
+        let der = signature.to_der();
+        assert_eq!(der[0], 0x30);
+        assert_eq!(EcdsaSignature::from_der(&der).unwrap(), signature);
+        assert_eq!(EcdsaSignature::from_der_lax(&der).unwrap(), signature);
+        assert_eq!(EcdsaSignature::from_compact(&signature.to_compact()).unwrap(), signature);
+        assert_eq!(EcdsaSignature::from_compact(&der), Err(SignatureError::InvalidLength(der.len())));
+        assert_eq!(EcdsaSignature::from_der(&der[1..]), Err(SignatureError::InvalidSignature));
+
+        // same signature as the recoverable one, minus the recovery id
+        let recoverable = sign_recoverable(&[9u8; 32], &key);
+        assert_eq!(EcdsaSignature::from(&recoverable), signature);
+    }
+
+    #[test]
+    fn test_low_s_normalization() {
+        let key = SecretKey::from_byte_array(&[3u8; 32]).unwrap();
+        let public_key = key.public_key(secp256k1_context());
+        let signature = sign(&[9u8; 32], &key);
+
+        let mut high = high_s(&signature);
+        assert!(!high.is_low_s());
+        assert_eq!(verify(&[9u8; 32], &high, &public_key), Err(SignatureError::InvalidSignature));
+        assert!(high.normalize_s());
+        assert_eq!(high, signature);
+        assert!(!high.normalize_s());
+        assert!(verify(&[9u8; 32], &high, &public_key).is_ok());
+    }
+
+    #[test]
+    fn test_extra_entropy() {
+        let key = SecretKey::from_byte_array(&[3u8; 32]).unwrap();
+        let first = sign_with_extra_entropy(&[9u8; 32], &key, &[1u8; 32]);
+        assert_eq!(first, sign_with_extra_entropy(&[9u8; 32], &key, &[1u8; 32]));
+        assert_ne!(first, sign_with_extra_entropy(&[9u8; 32], &key, &[2u8; 32]));
+        assert_ne!(first, sign(&[9u8; 32], &key));
+        assert!(verify(&[9u8; 32], &first, &key.public_key(secp256k1_context())).is_ok());
+    }
+}
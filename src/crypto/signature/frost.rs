@@ -0,0 +1,558 @@
+//! Experimental FROST t-of-n threshold signing over secp256k1 (two-round FROST with a
+//! Pedersen DKG), producing BIP340 signatures for the group key.
+//!
+//! The group key is an x-only key, so it can control a taproot output or any other
+//! BIP340 verifier. Stacks transaction spending conditions only accept ECDSA signatures,
+//! so the group cannot sign those directly. Hashes are domain separated with `FROST/...`
+//! tags and are not interoperable with other FROST ciphersuites.
+
+use std::collections::BTreeMap;
+use std::fmt;
+
+use rand::rngs::OsRng;
+use rand::{CryptoRng, RngCore};
+use secp256k1::{PublicKey, XOnlyPublicKey};
+
+use crate::crypto::arith::{Point, Scalar};
+use crate::crypto::hash::tagged_hash;
+
+use super::schnorr::{verify_schnorr, SchnorrSignature};
+
+/// Index of a participant, starting at 1.
+pub type ParticipantId = u16;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum FrostError {
+    /// The threshold must be between 2 and the number of participants
+    InvalidThreshold,
+    InvalidParticipant(ParticipantId),
+    DuplicateParticipant(ParticipantId),
+    /// The DKG proof of knowledge of a participant does not verify
+    InvalidProofOfKnowledge(ParticipantId),
+    /// A secret share does not match the sender's commitments
+    InvalidSecretShare(ParticipantId),
+    MissingPackage(ParticipantId),
+    InvalidSignatureShare(ParticipantId),
+    /// Fewer signers than the threshold
+    NotEnoughSigners,
+    InvalidEncoding,
+}
+
+impl fmt::Display for FrostError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        match self {
+            FrostError::InvalidThreshold => f.write_str("Invalid threshold"),
+            FrostError::InvalidParticipant(v) => f.write_str(&format!("Invalid participant {v}")),
+            FrostError::DuplicateParticipant(v) => f.write_str(&format!("Duplicate participant {v}")),
+            FrostError::InvalidProofOfKnowledge(v) => f.write_str(&format!("Invalid proof of knowledge from participant {v}")),
+            FrostError::InvalidSecretShare(v) => f.write_str(&format!("Invalid secret share from participant {v}")),
+            FrostError::MissingPackage(v) => f.write_str(&format!("Missing package of participant {v}")),
+            FrostError::InvalidSignatureShare(v) => f.write_str(&format!("Invalid signature share from participant {v}")),
+            FrostError::NotEnoughSigners => f.write_str("Not enough signers"),
+            FrostError::InvalidEncoding => f.write_str("Invalid encoding"),
+        }
+    }
+}
+
+impl std::error::Error for FrostError {}
+
+fn scalar_of(id: ParticipantId) -> Scalar {
+    Scalar::from_u32(id as u32)
+}
+
+fn point_of(key: &PublicKey) -> Point {
+    Point::from_public_key(key)
+}
+
+/// `sum(coefficients[k] * x^k)`
+fn evaluate_polynomial(coefficients: &[Scalar], x: Scalar) -> Scalar {
+    coefficients.iter().rev().fold(Scalar::ZERO, |acc, coefficient| acc.mul(x).add(*coefficient))
+}
+
+/// `sum(commitments[k] * x^k)`, i.e. `f(x) * G` for the committed polynomial.
+fn evaluate_commitments(commitments: &[PublicKey], x: Scalar) -> Point {
+    commitments.iter().rev().fold(Point::INFINITY, |acc, commitment| acc.mul(x).add(point_of(commitment)))
+}
+
+/// Lagrange coefficient of `id` at zero over `signers`.
+fn lagrange_coefficient(id: ParticipantId, signers: impl Iterator<Item = ParticipantId>) -> Scalar {
+    let (numerator, denominator) = signers.filter(|other| *other != id).fold((Scalar::one(), Scalar::one()), |(num, den), other| {
+        (num.mul(scalar_of(other)), den.mul(scalar_of(other).sub(scalar_of(id))))
+    });
+    numerator.mul(denominator.invert())
+}
+
+fn pok_challenge(id: ParticipantId, commitment: &PublicKey, r: &PublicKey) -> Scalar {
+    Scalar::reduce(&tagged_hash("FROST/dkg-pok", &[&id.to_be_bytes()[..], &commitment.serialize(), &r.serialize()].concat()))
+}
+
+/// Secret state of a participant between the DKG rounds. Never leaves the participant.
+pub struct DkgSecret {
+    id: ParticipantId,
+    max_signers: u16,
+    coefficients: Vec<Scalar>,
+}
+
+impl fmt::Debug for DkgSecret {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("DkgSecret").field("id", &self.id).finish_non_exhaustive()
+    }
+}
+
+/// Broadcast by every participant in the first DKG round.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DkgCommitment {
+    pub id: ParticipantId,
+    /// `a_k * G` for the coefficients of the participant's polynomial
+    pub commitments: Vec<PublicKey>,
+    /// Schnorr proof of knowledge of the constant term
+    pub proof_r: PublicKey,
+    pub proof_mu: [u8; 32],
+}
+
+/// `f_sender(recipient)`, sent privately by `sender` to the recipient in the second DKG round.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SecretShare {
+    pub sender: ParticipantId,
+    pub recipient: ParticipantId,
+    value: Scalar,
+}
+
+impl SecretShare {
+    pub fn from_bytes(sender: ParticipantId, recipient: ParticipantId, bytes: &[u8; 32]) -> Result<Self, FrostError> {
+        let value = Scalar::from_bytes(bytes).ok_or(FrostError::InvalidEncoding)?;
+        Ok(Self { sender, recipient, value })
+    }
+
+    pub fn to_bytes(&self) -> [u8; 32] {
+        self.value.to_bytes()
+    }
+}
+
+/// Starts the DKG as participant `id` of `max_signers`, any `threshold` of which can sign.
+///
+/// Usage:
+/// ```rust
+/// use stacks_rs::crypto::signature::frost::*;
+/// use stacks_rs::crypto::signature::schnorr::verify_schnorr;
+/// let (secrets, commitments): (Vec<_>, Vec<_>) = (1..=3).map(|id| dkg_part1(id, 2, 3).unwrap()).unzip();
+/// let key_packages: Vec<_> = secrets
+///     .iter()
+///     .map(|secret| {
+///         let shares: Vec<_> = secrets.iter().map(|sender| sender.secret_share(secret.id()).unwrap()).collect();
+///         dkg_part2(secret, &commitments, &shares).unwrap()
+///     })
+///     .collect();
+///
+/// // participants 1 and 3 sign
+/// let message = [7u8; 32];
+/// let (nonces, signing_commitments): (Vec<_>, Vec<_>) = [&key_packages[0], &key_packages[2]].iter().map(|key| commit(key)).unzip();
+/// let package = SigningPackage::new(&signing_commitments, message).unwrap();
+/// let shares: Vec<_> = nonces
+///     .into_iter()
+///     .zip([&key_packages[0], &key_packages[2]])
+///     .map(|(nonces, key)| sign(&package, nonces, key).unwrap())
+///     .collect();
+/// let public = key_packages[0].public();
+/// let signature = aggregate(&package, &shares, public).unwrap();
+/// assert!(verify_schnorr(&message, &signature, &public.x_only_public_key()).is_ok());
+/// ```
+pub fn dkg_part1(id: ParticipantId, threshold: u16, max_signers: u16) -> Result<(DkgSecret, DkgCommitment), FrostError> {
+    dkg_part1_with_rng(id, threshold, max_signers, &mut OsRng)
+}
+
+/// Same as [`dkg_part1`] but draws the polynomial and the proof nonce from `rng`.
+pub fn dkg_part1_with_rng<R: RngCore + CryptoRng>(
+    id: ParticipantId,
+    threshold: u16,
+    max_signers: u16,
+    rng: &mut R,
+) -> Result<(DkgSecret, DkgCommitment), FrostError> {
+    if threshold < 2 || threshold > max_signers {
+        return Err(FrostError::InvalidThreshold);
+    }
+    if id == 0 || id > max_signers {
+        return Err(FrostError::InvalidParticipant(id));
+    }
+    let coefficients: Vec<Scalar> = (0..threshold).map(|_| Scalar::random_with_rng(rng)).collect();
+    let commitments: Vec<PublicKey> = coefficients.iter().map(|a| Point::mul_base(*a).public_key().unwrap()).collect();
+
+    let k = Scalar::random_with_rng(rng);
+    let proof_r = Point::mul_base(k).public_key().unwrap();
+    let mu = k.add(coefficients[0].mul(pok_challenge(id, &commitments[0], &proof_r)));
+
+    let commitment = DkgCommitment { id, commitments, proof_r, proof_mu: mu.to_bytes() };
+    Ok((DkgSecret { id, max_signers, coefficients }, commitment))
+}
+
+impl DkgSecret {
+    pub fn id(&self) -> ParticipantId {
+        self.id
+    }
+
+    /// Second DKG round: the share of this participant's polynomial for `recipient`.
+    pub fn secret_share(&self, recipient: ParticipantId) -> Result<SecretShare, FrostError> {
+        if recipient == 0 || recipient > self.max_signers {
+            return Err(FrostError::InvalidParticipant(recipient));
+        }
+        let value = evaluate_polynomial(&self.coefficients, scalar_of(recipient));
+        Ok(SecretShare { sender: self.id, recipient, value })
+    }
+}
+
+/// Group public key and the public verifying share of every participant.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PublicKeyPackage {
+    pub threshold: u16,
+    pub group_key: PublicKey,
+    pub verifying_shares: BTreeMap<ParticipantId, PublicKey>,
+}
+
+impl PublicKeyPackage {
+    /// Key the aggregated BIP340 signatures verify against.
+    pub fn x_only_public_key(&self) -> XOnlyPublicKey {
+        self.group_key.x_only_public_key().0
+    }
+
+    /// 1 or -1, negating the shares of a group key with an odd y coordinate.
+    fn parity(&self) -> Scalar {
+        if point_of(&self.group_key).has_even_y() { Scalar::one() } else { Scalar::one().neg() }
+    }
+}
+
+/// Long-lived signing material of one participant.
+pub struct KeyPackage {
+    id: ParticipantId,
+    signing_share: Scalar,
+    public: PublicKeyPackage,
+}
+
+impl fmt::Debug for KeyPackage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("KeyPackage").field("id", &self.id).field("public", &self.public).finish_non_exhaustive()
+    }
+}
+
+impl KeyPackage {
+    pub fn id(&self) -> ParticipantId {
+        self.id
+    }
+
+    pub fn public(&self) -> &PublicKeyPackage {
+        &self.public
+    }
+
+    /// Secret signing share, to store encrypted (e.g. in a keystore).
+    pub fn signing_share_bytes(&self) -> [u8; 32] {
+        self.signing_share.to_bytes()
+    }
+
+    pub fn from_signing_share_bytes(id: ParticipantId, bytes: &[u8; 32], public: PublicKeyPackage) -> Result<Self, FrostError> {
+        let signing_share = Scalar::from_bytes(bytes).ok_or(FrostError::InvalidEncoding)?;
+        if public.verifying_shares.get(&id) != Point::mul_base(signing_share).public_key().as_ref() {
+            return Err(FrostError::InvalidParticipant(id));
+        }
+        Ok(Self { id, signing_share, public })
+    }
+}
+
+/// Finishes the DKG: checks every participant's proof and share, and derives the key package.
+pub fn dkg_part2(secret: &DkgSecret, commitments: &[DkgCommitment], shares: &[SecretShare]) -> Result<KeyPackage, FrostError> {
+    let threshold = secret.coefficients.len();
+    let mut by_id = BTreeMap::new();
+    for commitment in commitments {
+        if commitment.id == 0 || commitment.id > secret.max_signers || commitment.commitments.len() != threshold {
+            return Err(FrostError::InvalidParticipant(commitment.id));
+        }
+        if by_id.insert(commitment.id, commitment).is_some() {
+            return Err(FrostError::DuplicateParticipant(commitment.id));
+        }
+        let mu = Scalar::from_bytes(&commitment.proof_mu).ok_or(FrostError::InvalidProofOfKnowledge(commitment.id))?;
+        let challenge = pok_challenge(commitment.id, &commitment.commitments[0], &commitment.proof_r);
+        if Point::mul_base(mu) != point_of(&commitment.proof_r).add(point_of(&commitment.commitments[0]).mul(challenge)) {
+            return Err(FrostError::InvalidProofOfKnowledge(commitment.id));
+        }
+    }
+    if let Some(missing) = (1..=secret.max_signers).find(|id| !by_id.contains_key(id)) {
+        return Err(FrostError::MissingPackage(missing));
+    }
+
+    let mut signing_share = Scalar::ZERO;
+    let mut senders = BTreeMap::new();
+    for share in shares {
+        if share.recipient != secret.id {
+            return Err(FrostError::InvalidParticipant(share.recipient));
+        }
+        let commitment = by_id.get(&share.sender).ok_or(FrostError::InvalidParticipant(share.sender))?;
+        if senders.insert(share.sender, ()).is_some() {
+            return Err(FrostError::DuplicateParticipant(share.sender));
+        }
+        if Point::mul_base(share.value) != evaluate_commitments(&commitment.commitments, scalar_of(secret.id)) {
+            return Err(FrostError::InvalidSecretShare(share.sender));
+        }
+        signing_share = signing_share.add(share.value);
+    }
+    if let Some(missing) = by_id.keys().find(|id| !senders.contains_key(*id)) {
+        return Err(FrostError::MissingPackage(*missing));
+    }
+
+    let group_key = by_id.values().map(|commitment| point_of(&commitment.commitments[0])).fold(Point::INFINITY, Point::add);
+    let verifying_shares = by_id
+        .keys()
+        .map(|id| {
+            let share = by_id.values().map(|commitment| evaluate_commitments(&commitment.commitments, scalar_of(*id))).fold(Point::INFINITY, Point::add);
+            (*id, share.public_key().unwrap())
+        })
+        .collect();
+    let public = PublicKeyPackage { threshold: threshold as u16, group_key: group_key.public_key().unwrap(), verifying_shares };
+    Ok(KeyPackage { id: secret.id, signing_share, public })
+}
+
+/// One-time nonces of a signer. Consumed by [`sign`] since reusing them leaks the signing share.
+pub struct SigningNonces {
+    hiding: Scalar,
+    binding: Scalar,
+    commitments: SigningCommitments,
+}
+
+/// Public commitments to a signer's nonces, sent to the coordinator.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SigningCommitments {
+    pub id: ParticipantId,
+    pub hiding: PublicKey,
+    pub binding: PublicKey,
+}
+
+/// First signing round: fresh nonces of `key`.
+pub fn commit(key: &KeyPackage) -> (SigningNonces, SigningCommitments) {
+    commit_with_rng(key, &mut OsRng)
+}
+
+pub fn commit_with_rng<R: RngCore + CryptoRng>(key: &KeyPackage, rng: &mut R) -> (SigningNonces, SigningCommitments) {
+    let (hiding, binding) = (Scalar::random_with_rng(rng), Scalar::random_with_rng(rng));
+    let commitments = SigningCommitments {
+        id: key.id,
+        hiding: Point::mul_base(hiding).public_key().unwrap(),
+        binding: Point::mul_base(binding).public_key().unwrap(),
+    };
+    (SigningNonces { hiding, binding, commitments }, commitments)
+}
+
+/// Message and nonce commitments of one signing session, assembled by the coordinator.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SigningPackage {
+    commitments: BTreeMap<ParticipantId, SigningCommitments>,
+    message: [u8; 32],
+}
+
+impl SigningPackage {
+    pub fn new(commitments: &[SigningCommitments], message: [u8; 32]) -> Result<Self, FrostError> {
+        let mut by_id = BTreeMap::new();
+        for commitment in commitments {
+            if by_id.insert(commitment.id, *commitment).is_some() {
+                return Err(FrostError::DuplicateParticipant(commitment.id));
+            }
+        }
+        Ok(Self { commitments: by_id, message })
+    }
+
+    pub fn signers(&self) -> impl Iterator<Item = ParticipantId> + '_ {
+        self.commitments.keys().copied()
+    }
+
+    fn binding_factors(&self, public: &PublicKeyPackage) -> BTreeMap<ParticipantId, Scalar> {
+        let encoded: Vec<u8> = self
+            .commitments
+            .values()
+            .flat_map(|c| [&c.id.to_be_bytes()[..], &c.hiding.serialize(), &c.binding.serialize()].concat())
+            .collect();
+        let prefix = [&public.group_key.serialize()[..], &self.message, &tagged_hash("FROST/commitments", &encoded)].concat();
+        self.commitments
+            .keys()
+            .map(|id| (*id, Scalar::reduce(&tagged_hash("FROST/rho", &[&prefix[..], &id.to_be_bytes()].concat()))))
+            .collect()
+    }
+
+    /// Group commitment `R`, each signer's share of it and the BIP340 challenge.
+    fn group_commitment(&self, public: &PublicKeyPackage) -> (Point, BTreeMap<ParticipantId, Point>, Scalar) {
+        let factors = self.binding_factors(public);
+        let shares: BTreeMap<_, _> = self
+            .commitments
+            .values()
+            .map(|c| (c.id, point_of(&c.hiding).add(point_of(&c.binding).mul(factors[&c.id]))))
+            .collect();
+        let r = shares.values().fold(Point::INFINITY, |acc, share| acc.add(*share));
+        let challenge = Scalar::reduce(&tagged_hash(
+            "BIP0340/challenge",
+            &[&r.x_bytes()[..], &public.x_only_public_key().serialize(), &self.message].concat(),
+        ));
+        (r, shares, challenge)
+    }
+
+    fn check_signers(&self, public: &PublicKeyPackage) -> Result<(), FrostError> {
+        if self.commitments.len() < public.threshold as usize {
+            return Err(FrostError::NotEnoughSigners);
+        }
+        match self.signers().find(|id| !public.verifying_shares.contains_key(id)) {
+            Some(id) => Err(FrostError::InvalidParticipant(id)),
+            None => Ok(()),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SignatureShare {
+    pub id: ParticipantId,
+    value: Scalar,
+}
+
+impl SignatureShare {
+    pub fn from_bytes(id: ParticipantId, bytes: &[u8; 32]) -> Result<Self, FrostError> {
+        let value = Scalar::from_bytes(bytes).ok_or(FrostError::InvalidEncoding)?;
+        Ok(Self { id, value })
+    }
+
+    pub fn to_bytes(&self) -> [u8; 32] {
+        self.value.to_bytes()
+    }
+}
+
+/// Second signing round: the signer's share of the signature of the package's message.
+pub fn sign(package: &SigningPackage, nonces: SigningNonces, key: &KeyPackage) -> Result<SignatureShare, FrostError> {
+    package.check_signers(&key.public)?;
+    if package.commitments.get(&key.id) != Some(&nonces.commitments) {
+        return Err(FrostError::InvalidParticipant(key.id));
+    }
+    let (r, _, challenge) = package.group_commitment(&key.public);
+    let rho = package.binding_factors(&key.public)[&key.id];
+    let mut k = nonces.hiding.add(nonces.binding.mul(rho));
+    if !r.has_even_y() {
+        k = k.neg();
+    }
+    let lambda = lagrange_coefficient(key.id, package.signers());
+    let value = k.add(challenge.mul(lambda).mul(key.public.parity()).mul(key.signing_share));
+    Ok(SignatureShare { id: key.id, value })
+}
+
+/// Checks the signature share of one signer, identifying misbehaving participants.
+pub fn verify_share(package: &SigningPackage, share: &SignatureShare, public: &PublicKeyPackage) -> Result<(), FrostError> {
+    let (r, commitment_shares, challenge) = package.group_commitment(public);
+    let commitment_share = commitment_shares.get(&share.id).ok_or(FrostError::InvalidParticipant(share.id))?;
+    let verifying_share = public.verifying_shares.get(&share.id).ok_or(FrostError::InvalidParticipant(share.id))?;
+    let commitment_share = if r.has_even_y() { *commitment_share } else { commitment_share.neg() };
+    let lambda = lagrange_coefficient(share.id, package.signers());
+    let expected = commitment_share.add(point_of(verifying_share).mul(challenge.mul(lambda).mul(public.parity())));
+    if Point::mul_base(share.value) != expected {
+        return Err(FrostError::InvalidSignatureShare(share.id));
+    }
+    Ok(())
+}
+
+/// Combines the signature shares into a BIP340 signature, verifying each share on failure.
+pub fn aggregate(package: &SigningPackage, shares: &[SignatureShare], public: &PublicKeyPackage) -> Result<SchnorrSignature, FrostError> {
+    package.check_signers(public)?;
+    if let Some(id) = package.signers().find(|id| !shares.iter().any(|share| share.id == *id)) {
+        return Err(FrostError::MissingPackage(id));
+    }
+    let (r, _, _) = package.group_commitment(public);
+    let z = shares.iter().fold(Scalar::ZERO, |acc, share| acc.add(share.value));
+    let signature = SchnorrSignature::from_bytes(&[&r.x_bytes()[..], &z.to_bytes()].concat()).map_err(|_| FrostError::InvalidEncoding)?;
+    if verify_schnorr(&package.message, &signature, &public.x_only_public_key()).is_err() {
+        for share in shares {
+            verify_share(package, share, public)?;
+        }
+        return Err(FrostError::InvalidSignatureShare(0));
+    }
+    Ok(signature)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn run_dkg(threshold: u16, max_signers: u16) -> Vec<KeyPackage> {
+        let (secrets, commitments): (Vec<_>, Vec<_>) =
+            (1..=max_signers).map(|id| dkg_part1(id, threshold, max_signers).unwrap()).unzip();
+        secrets
+            .iter()
+            .map(|secret| {
+                let shares: Vec<_> = secrets.iter().map(|sender| sender.secret_share(secret.id()).unwrap()).collect();
+                dkg_part2(secret, &commitments, &shares).unwrap()
+            })
+            .collect()
+    }
+
+    fn sign_with(keys: &[&KeyPackage], message: [u8; 32]) -> Result<SchnorrSignature, FrostError> {
+        let (nonces, commitments): (Vec<_>, Vec<_>) = keys.iter().map(|key| commit(key)).unzip();
+        let package = SigningPackage::new(&commitments, message)?;
+        let shares = nonces
+            .into_iter()
+            .zip(keys)
+            .map(|(nonces, key)| sign(&package, nonces, key))
+            .collect::<Result<Vec<_>, _>>()?;
+        for share in &shares {
+            verify_share(&package, share, keys[0].public())?;
+        }
+        aggregate(&package, &shares, keys[0].public())
+    }
+
+    #[test]
+    fn test_dkg_and_threshold_signing() {
+        let keys = run_dkg(3, 5);
+        let public = keys[0].public();
+        assert!(keys.iter().all(|key| key.public() == public));
+        assert_eq!(public.verifying_shares.len(), 5);
+
+        let message = [0x42u8; 32];
+        for signers in [[0, 1, 2], [0, 2, 4], [4, 3, 1]] {
+            let signer_keys: Vec<_> = signers.iter().map(|i| &keys[*i]).collect();
+            let signature = sign_with(&signer_keys, message).unwrap();
+            assert!(verify_schnorr(&message, &signature, &public.x_only_public_key()).is_ok());
+        }
+        // more signers than the threshold also works, fewer does not
+        let all: Vec<_> = keys.iter().collect();
+        assert!(sign_with(&all, message).is_ok());
+        assert_eq!(sign_with(&all[..2], message).unwrap_err(), FrostError::NotEnoughSigners);
+    }
+
+    #[test]
+    fn test_signing_share_roundtrip() {
+        let keys = run_dkg(2, 3);
+        let restored = KeyPackage::from_signing_share_bytes(2, &keys[1].signing_share_bytes(), keys[1].public().clone()).unwrap();
+        assert!(sign_with(&[&keys[0], &restored], [1u8; 32]).is_ok());
+        assert!(KeyPackage::from_signing_share_bytes(3, &keys[1].signing_share_bytes(), keys[1].public().clone()).is_err());
+    }
+
+    #[test]
+    fn test_dkg_misbehavior() {
+        assert_eq!(dkg_part1(1, 1, 3).unwrap_err(), FrostError::InvalidThreshold);
+        assert_eq!(dkg_part1(4, 2, 3).unwrap_err(), FrostError::InvalidParticipant(4));
+
+        let (secrets, mut commitments): (Vec<_>, Vec<_>) = (1..=3).map(|id| dkg_part1(id, 2, 3).unwrap()).unzip();
+        let mut shares: Vec<_> = secrets.iter().map(|sender| sender.secret_share(1).unwrap()).collect();
+
+        // a share that does not match its sender's polynomial
+        let good = shares[1];
+        shares[1] = secrets[1].secret_share(2).unwrap();
+        shares[1].recipient = 1;
+        assert_eq!(dkg_part2(&secrets[0], &commitments, &shares).unwrap_err(), FrostError::InvalidSecretShare(2));
+        shares[1] = good;
+
+        // a proof of knowledge for another participant's commitment
+        commitments[2].proof_r = commitments[1].proof_r;
+        assert_eq!(dkg_part2(&secrets[0], &commitments, &shares).unwrap_err(), FrostError::InvalidProofOfKnowledge(3));
+
+        assert_eq!(dkg_part2(&secrets[0], &commitments[..2], &shares).unwrap_err(), FrostError::MissingPackage(3));
+    }
+
+    #[test]
+    fn test_invalid_signature_share() {
+        let keys = run_dkg(2, 3);
+        let (nonces, commitments): (Vec<_>, Vec<_>) = keys[..2].iter().map(commit).unzip();
+        let package = SigningPackage::new(&commitments, [5u8; 32]).unwrap();
+        let mut shares: Vec<_> = nonces.into_iter().zip(&keys).map(|(nonces, key)| sign(&package, nonces, key).unwrap()).collect();
+        shares[1].value = shares[1].value.add(Scalar::one());
+        assert_eq!(verify_share(&package, &shares[1], keys[0].public()).unwrap_err(), FrostError::InvalidSignatureShare(2));
+        assert_eq!(aggregate(&package, &shares, keys[0].public()).unwrap_err(), FrostError::InvalidSignatureShare(2));
+    }
+}
@@ -0,0 +1,34 @@
+use std::fmt;
+
+pub mod bip137;
+pub mod bip322;
+pub mod ecdsa;
+#[cfg(feature = "frost")]
+pub mod frost;
+pub mod musig2;
+pub mod recoverable;
+pub mod schnorr;
+pub mod sip018;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SignatureError {
+    InvalidLength(usize),
+    InvalidRecoveryId(u8),
+    /// The bytes are not a valid signature (e.g. `r` or `s` out of range)
+    InvalidSignature,
+    /// No public key matches the signature and message
+    RecoveryFailed,
+}
+
+impl fmt::Display for SignatureError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        match self {
+            SignatureError::InvalidLength(v) => f.write_str(&format!("Invalid signature length {v}")),
+            SignatureError::InvalidRecoveryId(v) => f.write_str(&format!("Invalid recovery id {v}")),
+            SignatureError::InvalidSignature => f.write_str("Invalid signature"),
+            SignatureError::RecoveryFailed => f.write_str("Public key recovery failed"),
+        }
+    }
+}
+
+impl std::error::Error for SignatureError {}
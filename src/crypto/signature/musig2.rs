@@ -0,0 +1,475 @@
+use std::fmt;
+
+use rand::rngs::OsRng;
+use rand::{CryptoRng, RngCore};
+use secp256k1::{PublicKey, SecretKey, XOnlyPublicKey};
+
+use crate::crypto::arith::{Point, Scalar};
+use crate::crypto::context::secp256k1_context;
+use crate::crypto::hash::tagged_hash;
+use crate::crypto::{taproot, utils};
+
+use super::schnorr::SchnorrSignature;
+
+pub const PUBLIC_NONCE_LENGTH: usize = 66;
+pub const PARTIAL_SIGNATURE_LENGTH: usize = 32;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum MuSig2Error {
+    /// No public keys to aggregate
+    EmptyKeyList,
+    /// The aggregated key or a tweaked key is the point at infinity
+    InfiniteKey,
+    InvalidTweak,
+    InvalidPublicNonce(usize),
+    InvalidPartialSignature(usize),
+    /// The secret key does not match the public key of the secret nonce
+    KeyMismatch,
+    /// The signer's public key is not part of the aggregation
+    UnknownSigner,
+}
+
+impl fmt::Display for MuSig2Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        match self {
+            MuSig2Error::EmptyKeyList => f.write_str("No public keys to aggregate"),
+            MuSig2Error::InfiniteKey => f.write_str("Aggregated public key is infinity"),
+            MuSig2Error::InvalidTweak => f.write_str("Invalid tweak"),
+            MuSig2Error::InvalidPublicNonce(v) => f.write_str(&format!("Invalid public nonce of signer {v}")),
+            MuSig2Error::InvalidPartialSignature(v) => f.write_str(&format!("Invalid partial signature of signer {v}")),
+            MuSig2Error::KeyMismatch => f.write_str("Secret key does not match the secret nonce"),
+            MuSig2Error::UnknownSigner => f.write_str("Signer is not part of the key aggregation"),
+        }
+    }
+}
+
+impl std::error::Error for MuSig2Error {}
+
+/// Sorts public keys by their compressed encoding (BIP327 `KeySort`), making the aggregated key
+/// independent of the order the participants are listed in.
+pub fn sort_public_keys(public_keys: &mut [PublicKey]) {
+    public_keys.sort_by_key(|key| key.serialize());
+}
+
+/// Aggregated key of a list of signers, with the tweaks applied to it (BIP327 `KeyAgg Context`).
+///
+/// Usage:
+/// ```rust
+/// use secp256k1::SecretKey;
+/// use stacks_rs::crypto::context::secp256k1_context;
+/// use stacks_rs::crypto::signature::musig2::{nonce_gen, nonce_agg, KeyAggContext, Session};
+/// use stacks_rs::crypto::signature::schnorr::verify_schnorr;
+/// let keys = [SecretKey::from_byte_array(&[1u8; 32]).unwrap(), SecretKey::from_byte_array(&[2u8; 32]).unwrap()];
+/// let public_keys: Vec<_> = keys.iter().map(|key| key.public_key(secp256k1_context())).collect();
+/// let ctx = KeyAggContext::new(&public_keys).unwrap().with_taproot_tweak(None).unwrap();
+///
+/// let message = [7u8; 32];
+/// let (sec_nonces, pub_nonces): (Vec<_>, Vec<_>) = keys
+///     .iter()
+///     .map(|key| nonce_gen(Some(key), &key.public_key(secp256k1_context()), Some(&ctx.x_only_public_key()), Some(&message), None))
+///     .unzip();
+/// let session = Session::new(&ctx, &nonce_agg(&pub_nonces).unwrap(), &message);
+/// let partial_signatures: Vec<_> = sec_nonces
+///     .into_iter()
+///     .zip(&keys)
+///     .map(|(sec_nonce, key)| session.partial_sign(sec_nonce, key).unwrap())
+///     .collect();
+/// let signature = session.aggregate(&partial_signatures);
+/// assert!(verify_schnorr(&message, &signature, &ctx.x_only_public_key()).is_ok());
+/// ```
+#[derive(Clone, Debug)]
+pub struct KeyAggContext {
+    public_keys: Vec<PublicKey>,
+    keys_hash: [u8; 32],
+    second_key: Option<PublicKey>,
+    q: Point,
+    gacc: Scalar,
+    tacc: Scalar,
+}
+
+impl KeyAggContext {
+    /// Aggregates `public_keys` in the given order, see [`sort_public_keys`].
+    pub fn new(public_keys: &[PublicKey]) -> Result<Self, MuSig2Error> {
+        let first = public_keys.first().ok_or(MuSig2Error::EmptyKeyList)?;
+        let serialized: Vec<u8> = public_keys.iter().flat_map(|key| key.serialize()).collect();
+        let mut ctx = Self {
+            public_keys: public_keys.to_vec(),
+            keys_hash: tagged_hash("KeyAgg list", &serialized),
+            second_key: public_keys.iter().find(|key| *key != first).copied(),
+            q: Point::INFINITY,
+            gacc: Scalar::one(),
+            tacc: Scalar::ZERO,
+        };
+        ctx.q = public_keys
+            .iter()
+            .map(|key| Point::from_public_key(key).mul(ctx.coefficient(key)))
+            .fold(Point::INFINITY, Point::add);
+        if ctx.q.is_infinity() {
+            return Err(MuSig2Error::InfiniteKey);
+        }
+        Ok(ctx)
+    }
+
+    fn coefficient(&self, public_key: &PublicKey) -> Scalar {
+        if Some(*public_key) == self.second_key {
+            return Scalar::one();
+        }
+        Scalar::reduce(&tagged_hash("KeyAgg coefficient", &[&self.keys_hash[..], &public_key.serialize()].concat()))
+    }
+
+    pub fn public_keys(&self) -> &[PublicKey] {
+        &self.public_keys
+    }
+
+    /// Aggregated (and tweaked) public key.
+    pub fn public_key(&self) -> PublicKey {
+        self.q.public_key().unwrap()
+    }
+
+    /// X-only aggregated key, the key BIP340 signatures of the group verify against.
+    pub fn x_only_public_key(&self) -> XOnlyPublicKey {
+        self.public_key().x_only_public_key().0
+    }
+
+    fn apply_tweak(mut self, tweak: &[u8; 32], x_only: bool) -> Result<Self, MuSig2Error> {
+        let t = Scalar::from_bytes(tweak).ok_or(MuSig2Error::InvalidTweak)?;
+        let g = if x_only && !self.q.has_even_y() { Scalar::one().neg() } else { Scalar::one() };
+        self.q = self.q.mul(g).add(Point::mul_base(t));
+        if self.q.is_infinity() {
+            return Err(MuSig2Error::InfiniteKey);
+        }
+        self.gacc = g.mul(self.gacc);
+        self.tacc = t.add(g.mul(self.tacc));
+        Ok(self)
+    }
+
+    /// Adds `tweak * G` to the aggregated key, e.g. for BIP32 derivation from it.
+    pub fn with_plain_tweak(self, tweak: &[u8; 32]) -> Result<Self, MuSig2Error> {
+        self.apply_tweak(tweak, false)
+    }
+
+    /// Adds `tweak * G` to the x-only aggregated key.
+    pub fn with_xonly_tweak(self, tweak: &[u8; 32]) -> Result<Self, MuSig2Error> {
+        self.apply_tweak(tweak, true)
+    }
+
+    /// Applies the BIP341 taproot tweak, so the group can spend the key path of
+    /// [`taproot::p2tr_script_pubkey`] of [`KeyAggContext::x_only_public_key`].
+    pub fn with_taproot_tweak(self, merkle_root: Option<&[u8; 32]>) -> Result<Self, MuSig2Error> {
+        let tweak = taproot::tap_tweak(&self.x_only_public_key(), merkle_root).map_err(|_| MuSig2Error::InvalidTweak)?;
+        self.with_xonly_tweak(&tweak.to_be_bytes())
+    }
+}
+
+/// Secret half of a signer's nonce. Consumed by [`Session::partial_sign`] since reusing it leaks the key.
+pub struct SecretNonce {
+    k1: Scalar,
+    k2: Scalar,
+    public_key: PublicKey,
+}
+
+/// Public half of a signer's nonce, sent to the other signers.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PublicNonce([u8; PUBLIC_NONCE_LENGTH]);
+
+impl PublicNonce {
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, MuSig2Error> {
+        let bytes: [u8; PUBLIC_NONCE_LENGTH] = bytes.try_into().map_err(|_| MuSig2Error::InvalidPublicNonce(0))?;
+        PublicKey::from_slice(&bytes[..33]).map_err(|_| MuSig2Error::InvalidPublicNonce(0))?;
+        PublicKey::from_slice(&bytes[33..]).map_err(|_| MuSig2Error::InvalidPublicNonce(0))?;
+        Ok(Self(bytes))
+    }
+
+    pub fn to_bytes(&self) -> [u8; PUBLIC_NONCE_LENGTH] {
+        self.0
+    }
+}
+
+/// Sum of the public nonces of all signers, computed by any of them or a coordinator.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct AggregatedNonce([u8; PUBLIC_NONCE_LENGTH]);
+
+impl AggregatedNonce {
+    /// Parses an aggregated nonce, whose halves may be infinity (33 zero bytes).
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, MuSig2Error> {
+        let bytes: [u8; PUBLIC_NONCE_LENGTH] = bytes.try_into().map_err(|_| MuSig2Error::InvalidPublicNonce(0))?;
+        Point::from_bytes_ext(&bytes[..33]).ok_or(MuSig2Error::InvalidPublicNonce(0))?;
+        Point::from_bytes_ext(&bytes[33..]).ok_or(MuSig2Error::InvalidPublicNonce(0))?;
+        Ok(Self(bytes))
+    }
+
+    pub fn to_bytes(&self) -> [u8; PUBLIC_NONCE_LENGTH] {
+        self.0
+    }
+
+    fn points(&self) -> (Point, Point) {
+        (Point::from_bytes_ext(&self.0[..33]).unwrap(), Point::from_bytes_ext(&self.0[33..]).unwrap())
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PartialSignature(Scalar);
+
+impl PartialSignature {
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, MuSig2Error> {
+        let bytes: [u8; PARTIAL_SIGNATURE_LENGTH] = bytes.try_into().map_err(|_| MuSig2Error::InvalidPartialSignature(0))?;
+        Scalar::from_bytes(&bytes).map(Self).ok_or(MuSig2Error::InvalidPartialSignature(0))
+    }
+
+    pub fn to_bytes(&self) -> [u8; PARTIAL_SIGNATURE_LENGTH] {
+        self.0.to_bytes()
+    }
+}
+
+/// Generates a fresh nonce pair (BIP327 `NonceGen`). All arguments but `public_key` are optional
+/// and only add defense in depth against a broken RNG.
+pub fn nonce_gen(
+    secret_key: Option<&SecretKey>,
+    public_key: &PublicKey,
+    aggregated_key: Option<&XOnlyPublicKey>,
+    message: Option<&[u8]>,
+    extra_in: Option<&[u8]>,
+) -> (SecretNonce, PublicNonce) {
+    nonce_gen_with_rng(secret_key, public_key, aggregated_key, message, extra_in, &mut OsRng)
+}
+
+/// Same as [`nonce_gen`] but draws the randomness from `rng`.
+pub fn nonce_gen_with_rng<R: RngCore + CryptoRng>(
+    secret_key: Option<&SecretKey>,
+    public_key: &PublicKey,
+    aggregated_key: Option<&XOnlyPublicKey>,
+    message: Option<&[u8]>,
+    extra_in: Option<&[u8]>,
+    rng: &mut R,
+) -> (SecretNonce, PublicNonce) {
+    let mut rand = [0u8; 32];
+    utils::generate_random_bytes_with_rng(rng, &mut rand, 32);
+    nonce_gen_internal(&rand, secret_key, public_key, aggregated_key, message, extra_in)
+}
+
+fn nonce_gen_internal(
+    rand: &[u8; 32],
+    secret_key: Option<&SecretKey>,
+    public_key: &PublicKey,
+    aggregated_key: Option<&XOnlyPublicKey>,
+    message: Option<&[u8]>,
+    extra_in: Option<&[u8]>,
+) -> (SecretNonce, PublicNonce) {
+    let rand = match secret_key {
+        Some(secret_key) => {
+            let mut masked = tagged_hash("MuSig/aux", rand);
+            masked.iter_mut().zip(secret_key.secret_bytes()).for_each(|(byte, key)| *byte ^= key);
+            masked
+        }
+        None => *rand,
+    };
+    let public_key_bytes = public_key.serialize();
+    let aggregated_key_bytes = aggregated_key.map(|key| key.serialize().to_vec()).unwrap_or_default();
+    let message_prefixed = match message {
+        None => vec![0x00],
+        Some(message) => [&[0x01][..], &(message.len() as u64).to_be_bytes(), message].concat(),
+    };
+    let extra_in = extra_in.unwrap_or_default();
+
+    let k = |i: u8| {
+        let data = [
+            &rand[..],
+            &[public_key_bytes.len() as u8],
+            &public_key_bytes,
+            &[aggregated_key_bytes.len() as u8],
+            &aggregated_key_bytes,
+            &message_prefixed,
+            &(extra_in.len() as u32).to_be_bytes(),
+            extra_in,
+            &[i],
+        ]
+        .concat();
+        Scalar::reduce(&tagged_hash("MuSig/nonce", &data))
+    };
+    let (k1, k2) = (k(0), k(1));
+
+    let mut pub_nonce = [0u8; PUBLIC_NONCE_LENGTH];
+    pub_nonce[..33].copy_from_slice(&Point::mul_base(k1).to_bytes_ext());
+    pub_nonce[33..].copy_from_slice(&Point::mul_base(k2).to_bytes_ext());
+    (SecretNonce { k1, k2, public_key: *public_key }, PublicNonce(pub_nonce))
+}
+
+/// Sums the public nonces of all signers (BIP327 `NonceAgg`).
+pub fn nonce_agg(pub_nonces: &[PublicNonce]) -> Result<AggregatedNonce, MuSig2Error> {
+    let mut bytes = [0u8; PUBLIC_NONCE_LENGTH];
+    for (j, range) in [(0..33), (33..66)].into_iter().enumerate() {
+        let mut sum = Point::INFINITY;
+        for (i, nonce) in pub_nonces.iter().enumerate() {
+            let point = PublicKey::from_slice(&nonce.0[range.clone()]).map_err(|_| MuSig2Error::InvalidPublicNonce(i))?;
+            sum = sum.add(Point::from_public_key(&point));
+        }
+        bytes[j * 33..(j + 1) * 33].copy_from_slice(&sum.to_bytes_ext());
+    }
+    Ok(AggregatedNonce(bytes))
+}
+
+/// Signing session of one message, shared by all signers once the nonces are aggregated.
+#[derive(Clone, Debug)]
+pub struct Session {
+    ctx: KeyAggContext,
+    b: Scalar,
+    r: Point,
+    e: Scalar,
+}
+
+impl Session {
+    pub fn new(ctx: &KeyAggContext, aggregated_nonce: &AggregatedNonce, message: &[u8]) -> Self {
+        let q_bytes = ctx.q.x_bytes();
+        let b = Scalar::reduce(&tagged_hash("MuSig/noncecoef", &[&aggregated_nonce.0[..], &q_bytes, message].concat()));
+        let (r1, r2) = aggregated_nonce.points();
+        let mut r = r1.add(r2.mul(b));
+        if r.is_infinity() {
+            r = Point::mul_base(Scalar::one());
+        }
+        let e = Scalar::reduce(&tagged_hash("BIP0340/challenge", &[&r.x_bytes()[..], &q_bytes, message].concat()));
+        Self { ctx: ctx.clone(), b, r, e }
+    }
+
+    /// `g * gacc` of BIP327, the sign adjustment of the signers' keys.
+    fn key_sign(&self) -> Scalar {
+        let g = if self.ctx.q.has_even_y() { Scalar::one() } else { Scalar::one().neg() };
+        g.mul(self.ctx.gacc)
+    }
+
+    pub fn partial_sign(&self, sec_nonce: SecretNonce, secret_key: &SecretKey) -> Result<PartialSignature, MuSig2Error> {
+        if secret_key.public_key(secp256k1_context()) != sec_nonce.public_key {
+            return Err(MuSig2Error::KeyMismatch);
+        }
+        if !self.ctx.public_keys.contains(&sec_nonce.public_key) {
+            return Err(MuSig2Error::UnknownSigner);
+        }
+        let (k1, k2) = if self.r.has_even_y() { (sec_nonce.k1, sec_nonce.k2) } else { (sec_nonce.k1.neg(), sec_nonce.k2.neg()) };
+        let a = self.ctx.coefficient(&sec_nonce.public_key);
+        let d = self.key_sign().mul(Scalar::from_secret_key(secret_key));
+        let s = k1.add(self.b.mul(k2)).add(self.e.mul(a).mul(d));
+        Ok(PartialSignature(s))
+    }
+
+    /// Checks the partial signature of the signer with `public_key` and `pub_nonce` (BIP327 `PartialSigVerify`).
+    pub fn verify_partial(&self, partial_signature: &PartialSignature, pub_nonce: &PublicNonce, public_key: &PublicKey) -> Result<(), MuSig2Error> {
+        if !self.ctx.public_keys.contains(public_key) {
+            return Err(MuSig2Error::UnknownSigner);
+        }
+        let r1 = Point::from_bytes_ext(&pub_nonce.0[..33]).ok_or(MuSig2Error::InvalidPublicNonce(0))?;
+        let r2 = Point::from_bytes_ext(&pub_nonce.0[33..]).ok_or(MuSig2Error::InvalidPublicNonce(0))?;
+        let mut re = r1.add(r2.mul(self.b));
+        if !self.r.has_even_y() {
+            re = re.neg();
+        }
+        let a = self.ctx.coefficient(public_key);
+        let expected = re.add(Point::from_public_key(public_key).mul(self.e.mul(a).mul(self.key_sign())));
+        if Point::mul_base(partial_signature.0) != expected {
+            return Err(MuSig2Error::InvalidPartialSignature(0));
+        }
+        Ok(())
+    }
+
+    /// Sums the partial signatures into a BIP340 signature for [`KeyAggContext::x_only_public_key`].
+    pub fn aggregate(&self, partial_signatures: &[PartialSignature]) -> SchnorrSignature {
+        let g = if self.ctx.q.has_even_y() { Scalar::one() } else { Scalar::one().neg() };
+        let s = partial_signatures
+            .iter()
+            .fold(self.e.mul(g).mul(self.ctx.tacc), |sum, partial| sum.add(partial.0));
+        SchnorrSignature::from_bytes(&[&self.r.x_bytes()[..], &s.to_bytes()].concat()).unwrap()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::crypto::signature::schnorr::verify_schnorr;
+
+    fn public_key(hex_key: &str) -> PublicKey {
+        PublicKey::from_slice(&hex::decode(hex_key).unwrap()).unwrap()
+    }
+
+    fn agg_x_only(keys: &[PublicKey]) -> String {
+        hex::encode(KeyAggContext::new(keys).unwrap().x_only_public_key().serialize()).to_uppercase()
+    }
+
+    #[test]
+    fn test_key_agg_vectors() {
+        // BIP327 key_agg_vectors.json
+        let x1 = public_key("02F9308A019258C31049344F85F89D5229B531C845836F99B08601F113BCE036F9");
+        let x2 = public_key("03DFF1D77F2A671C5F36183726DB2341BE58FEAE1DA2DECED843240F7B502BA659");
+        let x3 = public_key("023590A94E768F8E1815C2F24B4D80A8E3149316C3518CE7B7AD338368D038CA66");
+        assert_eq!(agg_x_only(&[x1, x2, x3]), "90539EEDE565F5D054F32CC0C220126889ED1E5D193BAF15AEF344FE59D4610C");
+        assert_eq!(agg_x_only(&[x3, x2, x1]), "6204DE8B083426DC6EAF9502D27024D53FC826BF7D2012148A0575435DF54B2B");
+        assert_eq!(agg_x_only(&[x1, x1, x1]), "B436E3BAD62B8CD409969A224731C193D051162D8C5AE8B109306127DA3AA935");
+        assert_eq!(agg_x_only(&[x1, x1, x2, x2]), "69BC22BFA5D106306E48A20679DE1D7389386124D07571D0D872686028C26A3E");
+
+        let mut sorted = [x3, x2, x1];
+        sort_public_keys(&mut sorted);
+        assert_eq!(sorted, [x3, x1, x2]);
+        assert_eq!(KeyAggContext::new(&[]).unwrap_err(), MuSig2Error::EmptyKeyList);
+    }
+
+    fn sign_with(keys: &[SecretKey], ctx: &KeyAggContext, message: &[u8]) -> SchnorrSignature {
+        let public_keys: Vec<_> = keys.iter().map(|key| key.public_key(secp256k1_context())).collect();
+        let (sec_nonces, pub_nonces): (Vec<_>, Vec<_>) = keys
+            .iter()
+            .zip(&public_keys)
+            .map(|(key, public_key)| nonce_gen(Some(key), public_key, Some(&ctx.x_only_public_key()), Some(message), None))
+            .unzip();
+        let session = Session::new(ctx, &nonce_agg(&pub_nonces).unwrap(), message);
+        let partial_signatures: Vec<_> = sec_nonces
+            .into_iter()
+            .zip(keys)
+            .map(|(sec_nonce, key)| session.partial_sign(sec_nonce, key).unwrap())
+            .collect();
+        for ((partial, pub_nonce), public_key) in partial_signatures.iter().zip(&pub_nonces).zip(&public_keys) {
+            assert!(session.verify_partial(partial, pub_nonce, public_key).is_ok());
+        }
+        // a partial signature does not verify for another signer
+        assert!(session.verify_partial(&partial_signatures[0], &pub_nonces[1], &public_keys[1]).is_err());
+        session.aggregate(&partial_signatures)
+    }
+
+    #[test]
+    fn test_sign_and_aggregate() {
+        let keys: Vec<_> = (1..=3u8).map(|i| SecretKey::from_byte_array(&[i; 32]).unwrap()).collect();
+        let mut public_keys: Vec<_> = keys.iter().map(|key| key.public_key(secp256k1_context())).collect();
+        sort_public_keys(&mut public_keys);
+        let message = [0x42u8; 32];
+
+        let ctx = KeyAggContext::new(&public_keys).unwrap();
+        let signature = sign_with(&keys, &ctx, &message);
+        assert!(verify_schnorr(&message, &signature, &ctx.x_only_public_key()).is_ok());
+
+        // key path spend of the group's P2TR output
+        let tweaked = ctx.clone().with_taproot_tweak(None).unwrap();
+        let (output_key, _) = taproot::tweak_public_key(&ctx.x_only_public_key(), None).unwrap();
+        assert_eq!(tweaked.x_only_public_key(), output_key);
+        let signature = sign_with(&keys, &tweaked, &message);
+        assert!(verify_schnorr(&message, &signature, &output_key).is_ok());
+
+        // plain tweaks followed by x-only tweaks
+        let tweaked = ctx.with_plain_tweak(&[7u8; 32]).unwrap().with_xonly_tweak(&[9u8; 32]).unwrap();
+        let signature = sign_with(&keys, &tweaked, &message);
+        assert!(verify_schnorr(&message, &signature, &tweaked.x_only_public_key()).is_ok());
+    }
+
+    #[test]
+    fn test_signer_checks() {
+        let keys: Vec<_> = (1..=2u8).map(|i| SecretKey::from_byte_array(&[i; 32]).unwrap()).collect();
+        let public_keys: Vec<_> = keys.iter().map(|key| key.public_key(secp256k1_context())).collect();
+        let ctx = KeyAggContext::new(&public_keys).unwrap();
+
+        let (sec_nonce, pub_nonce) = nonce_gen(None, &public_keys[0], None, None, None);
+        let session = Session::new(&ctx, &nonce_agg(&[pub_nonce]).unwrap(), b"message");
+        assert_eq!(session.partial_sign(sec_nonce, &keys[1]).unwrap_err(), MuSig2Error::KeyMismatch);
+
+        let outsider = SecretKey::from_byte_array(&[9u8; 32]).unwrap();
+        let (sec_nonce, _) = nonce_gen(None, &outsider.public_key(secp256k1_context()), None, None, None);
+        assert_eq!(session.partial_sign(sec_nonce, &outsider).unwrap_err(), MuSig2Error::UnknownSigner);
+
+        assert_eq!(PublicNonce::from_bytes(&pub_nonce.to_bytes()).unwrap(), pub_nonce);
+        assert!(PublicNonce::from_bytes(&[0u8; PUBLIC_NONCE_LENGTH]).is_err());
+        assert!(AggregatedNonce::from_bytes(&[0u8; PUBLIC_NONCE_LENGTH]).is_ok());
+    }
+}
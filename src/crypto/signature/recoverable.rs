@@ -0,0 +1,156 @@
+use secp256k1::ecdsa::{RecoverableSignature as Secp256k1RecoverableSignature, RecoveryId};
+use secp256k1::{Message, PublicKey, SecretKey};
+
+use crate::crypto::context::secp256k1_context;
+
+use super::SignatureError;
+
+pub const RECOVERABLE_SIGNATURE_LENGTH: usize = 65;
+
+/// Offset of the Bitcoin signed-message header byte (`27..=30` uncompressed, `31..=34` compressed).
+const BITCOIN_HEADER_OFFSET: u8 = 27;
+const BITCOIN_COMPRESSED_HEADER_OFFSET: u8 = 31;
+
+/// 65-byte recoverable ECDSA signature in the `VRS` layout used by Stacks transactions
+/// (`recovery id (1) || r (32) || s (32)`).
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct RecoverableSignature([u8; RECOVERABLE_SIGNATURE_LENGTH]);
+
+impl RecoverableSignature {
+    /// Parses a `VRS` signature. The recovery id is normalized, so Bitcoin header bytes are accepted too.
+    pub fn from_vrs(bytes: &[u8]) -> Result<Self, SignatureError> {
+        let bytes: [u8; RECOVERABLE_SIGNATURE_LENGTH] = bytes.try_into().map_err(|_| SignatureError::InvalidLength(bytes.len()))?;
+        Self::from_parts(bytes[0], bytes[1..].try_into().unwrap())
+    }
+
+    /// Parses an `RSV` signature, as produced by stacks.js `signMessageHashRsv`.
+    pub fn from_rsv(bytes: &[u8]) -> Result<Self, SignatureError> {
+        let bytes: [u8; RECOVERABLE_SIGNATURE_LENGTH] = bytes.try_into().map_err(|_| SignatureError::InvalidLength(bytes.len()))?;
+        Self::from_parts(bytes[64], bytes[..64].try_into().unwrap())
+    }
+
+    fn from_parts(recovery_id: u8, compact: &[u8; 64]) -> Result<Self, SignatureError> {
+        let recovery_id = normalize_recovery_id(recovery_id)?;
+        // rejects r and s out of range
+        Secp256k1RecoverableSignature::from_compact(compact, RecoveryId::try_from(recovery_id as i32).unwrap())
+            .map_err(|_| SignatureError::InvalidSignature)?;
+        let mut bytes = [0u8; RECOVERABLE_SIGNATURE_LENGTH];
+        bytes[0] = recovery_id;
+        bytes[1..].copy_from_slice(compact);
+        Ok(Self(bytes))
+    }
+
+    pub fn to_vrs(&self) -> [u8; RECOVERABLE_SIGNATURE_LENGTH] {
+        self.0
+    }
+
+    pub fn to_rsv(&self) -> [u8; RECOVERABLE_SIGNATURE_LENGTH] {
+        let mut bytes = [0u8; RECOVERABLE_SIGNATURE_LENGTH];
+        bytes[..64].copy_from_slice(&self.0[1..]);
+        bytes[64] = self.0[0];
+        bytes
+    }
+
+    /// Recovery id, between 0 and 3.
+    pub fn recovery_id(&self) -> u8 {
+        self.0[0]
+    }
+
+    pub fn to_secp256k1(&self) -> Secp256k1RecoverableSignature {
+        let recovery_id = RecoveryId::try_from(self.0[0] as i32).unwrap();
+        Secp256k1RecoverableSignature::from_compact(&self.0[1..], recovery_id).unwrap()
+    }
+
+    pub fn from_secp256k1(signature: &Secp256k1RecoverableSignature) -> Self {
+        let (recovery_id, compact) = signature.serialize_compact();
+        let mut bytes = [0u8; RECOVERABLE_SIGNATURE_LENGTH];
+        bytes[0] = i32::from(recovery_id) as u8;
+        bytes[1..].copy_from_slice(&compact);
+        Self(bytes)
+    }
+}
+
+/// Maps the recovery id to `0..=3`, accepting the Bitcoin signed-message header bytes (`27..=34`).
+pub fn normalize_recovery_id(recovery_id: u8) -> Result<u8, SignatureError> {
+    match recovery_id {
+        0..=3 => Ok(recovery_id),
+        27..=30 => Ok(recovery_id - BITCOIN_HEADER_OFFSET),
+        31..=34 => Ok(recovery_id - BITCOIN_COMPRESSED_HEADER_OFFSET),
+        _ => Err(SignatureError::InvalidRecoveryId(recovery_id)),
+    }
+}
+
+/// Signs the 32-byte `message_hash` (RFC6979 nonce, low-S).
+///
+/// Usage:
+/// ```rust
+/// use secp256k1::SecretKey;
+/// use stacks_rs::crypto::signature::recoverable::{recover, sign_recoverable};
+/// use stacks_rs::crypto::context::secp256k1_context;
+/// let key = SecretKey::from_byte_array(&[1u8; 32]).unwrap();
+/// let signature = sign_recoverable(&[7u8; 32], &key);
+/// assert_eq!(recover(&[7u8; 32], &signature).unwrap(), key.public_key(secp256k1_context()));
+/// ```
+pub fn sign_recoverable(message_hash: &[u8; 32], key: &SecretKey) -> RecoverableSignature {
+    let message = Message::from_digest(*message_hash);
+    RecoverableSignature::from_secp256k1(&secp256k1_context().sign_ecdsa_recoverable(&message, key))
+}
+
+/// Recovers the public key that produced `signature` over `message_hash`.
+pub fn recover(message_hash: &[u8; 32], signature: &RecoverableSignature) -> Result<PublicKey, SignatureError> {
+    let message = Message::from_digest(*message_hash);
+    secp256k1_context()
+        .recover_ecdsa(&message, &signature.to_secp256k1())
+        .map_err(|_| SignatureError::RecoveryFailed)
+}
+
+#[cfg(test)]
+mod tests {
+    use stacks_common::types::PrivateKey;
+    use stacks_common::util::secp256k1::Secp256k1PrivateKey;
+
+    use super::*;
+    use crate::crypto::hash::{Hasher, Sha512_256};
+
+    #[test]
+    fn test_sign_matches_stacks_common() {
+        let key = SecretKey::from_byte_array(&[3u8; 32]).unwrap();
+        let message_hash = Sha512_256::hash("hello world".as_bytes());
+        let signature = sign_recoverable(&message_hash, &key);
+
+        let stacks_key = Secp256k1PrivateKey::from_slice(&key.secret_bytes()).unwrap();
+        let expected = stacks_key.sign(&message_hash).unwrap();
+        assert_eq!(signature.to_vrs(), expected.0);
+        assert_eq!(signature.to_rsv().to_vec(), expected.to_rsv());
+        assert_eq!(recover(&message_hash, &signature).unwrap(), key.public_key(secp256k1_context()));
+    }
+
+    #[test]
+    fn test_vrs_rsv_roundtrip() {
+        let key = SecretKey::from_byte_array(&[3u8; 32]).unwrap();
+        let signature = sign_recoverable(&[9u8; 32], &key);
+        assert_eq!(RecoverableSignature::from_vrs(&signature.to_vrs()).unwrap(), signature);
+        assert_eq!(RecoverableSignature::from_rsv(&signature.to_rsv()).unwrap(), signature);
+
+        // Bitcoin header bytes are normalized
+        let mut header = signature.to_vrs();
+        header[0] += 31;
+        assert_eq!(RecoverableSignature::from_vrs(&header).unwrap(), signature);
+    }
+
+    #[test]
+    fn test_invalid_signatures() {
+        assert_eq!(RecoverableSignature::from_vrs(&[0u8; 64]), Err(SignatureError::InvalidLength(64)));
+        let mut bytes = [1u8; RECOVERABLE_SIGNATURE_LENGTH];
+        bytes[0] = 4;
+        assert_eq!(RecoverableSignature::from_vrs(&bytes), Err(SignatureError::InvalidRecoveryId(4)));
+        bytes[0] = 0;
+        bytes[1..33].copy_from_slice(&[0xff; 32]);
+        assert_eq!(RecoverableSignature::from_vrs(&bytes), Err(SignatureError::InvalidSignature));
+
+        // a valid signature over another message recovers another key
+        let key = SecretKey::from_byte_array(&[3u8; 32]).unwrap();
+        let signature = sign_recoverable(&[9u8; 32], &key);
+        assert_ne!(recover(&[8u8; 32], &signature).ok(), Some(key.public_key(secp256k1_context())));
+    }
+}
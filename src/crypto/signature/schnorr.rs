@@ -0,0 +1,132 @@
+use secp256k1::schnorr::Signature;
+use secp256k1::{Keypair, SecretKey, XOnlyPublicKey};
+
+use crate::crypto::context::secp256k1_context;
+use crate::crypto::keys::extended_private_key::{ExtendedPrivateKey, ExtendedPrivateKeyMethods};
+use crate::crypto::keys::extended_public_key::{ExtendedPublicKey, ExtendedPublicKeyMethods};
+use crate::crypto::utils;
+
+use super::SignatureError;
+
+pub const SCHNORR_SIGNATURE_LENGTH: usize = 64;
+
+/// BIP340 Schnorr signature (`R.x || s`).
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SchnorrSignature(Signature);
+
+impl SchnorrSignature {
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, SignatureError> {
+        if bytes.len() != SCHNORR_SIGNATURE_LENGTH {
+            return Err(SignatureError::InvalidLength(bytes.len()));
+        }
+        Signature::from_slice(bytes).map(Self).map_err(|_| SignatureError::InvalidSignature)
+    }
+
+    pub fn to_bytes(&self) -> [u8; SCHNORR_SIGNATURE_LENGTH] {
+        self.0.to_byte_array()
+    }
+
+    pub fn as_secp256k1(&self) -> &Signature {
+        &self.0
+    }
+}
+
+/// x-only (BIP340) public key of the keys of the hierarchy, used by Schnorr signatures and taproot.
+pub trait XOnlyKeyMethods {
+    fn x_only_public_key(&self) -> XOnlyPublicKey;
+}
+
+impl XOnlyKeyMethods for ExtendedPrivateKey {
+    fn x_only_public_key(&self) -> XOnlyPublicKey {
+        self.public_key().x_only_public_key().0
+    }
+}
+
+impl XOnlyKeyMethods for ExtendedPublicKey {
+    fn x_only_public_key(&self) -> XOnlyPublicKey {
+        self.public_key().x_only_public_key().0
+    }
+}
+
+/// Signs `message` with fresh auxiliary randomness from the OS RNG.
+///
+/// Usage:
+/// ```rust
+/// use secp256k1::SecretKey;
+/// use stacks_rs::crypto::context::secp256k1_context;
+/// use stacks_rs::crypto::signature::schnorr::{sign_schnorr, verify_schnorr};
+/// let key = SecretKey::from_byte_array(&[1u8; 32]).unwrap();
+/// let signature = sign_schnorr(&[7u8; 32], &key);
+/// let (x_only, _parity) = key.x_only_public_key(secp256k1_context());
+/// assert!(verify_schnorr(&[7u8; 32], &signature, &x_only).is_ok());
+/// ```
+pub fn sign_schnorr(message: &[u8; 32], key: &SecretKey) -> SchnorrSignature {
+    let mut aux_rand = [0u8; 32];
+    utils::generate_random_bytes(&mut aux_rand, 32);
+    sign_schnorr_with_aux_rand(message, key, &aux_rand)
+}
+
+/// Signs `message` with the given auxiliary randomness, deterministic for a given `aux_rand`.
+pub fn sign_schnorr_with_aux_rand(message: &[u8; 32], key: &SecretKey, aux_rand: &[u8; 32]) -> SchnorrSignature {
+    let keypair = Keypair::from_secret_key(secp256k1_context(), key);
+    SchnorrSignature(secp256k1_context().sign_schnorr_with_aux_rand(message, &keypair, aux_rand))
+}
+
+pub fn verify_schnorr(message: &[u8; 32], signature: &SchnorrSignature, public_key: &XOnlyPublicKey) -> Result<(), SignatureError> {
+    secp256k1_context()
+        .verify_schnorr(&signature.0, message, public_key)
+        .map_err(|_| SignatureError::InvalidSignature)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hex32(value: &str) -> [u8; 32] {
+        hex::decode(value).unwrap().try_into().unwrap()
+    }
+
+    #[test]
+    fn test_bip340_vectors() {
+        // (secret key, public key, aux rand, message, signature)
+        let vectors = [
+            (
+                "0000000000000000000000000000000000000000000000000000000000000003",
+                "f9308a019258c31049344f85f89d5229b531c845836f99b08601f113bce036f9",
+                "0000000000000000000000000000000000000000000000000000000000000000",
+                "0000000000000000000000000000000000000000000000000000000000000000",
+                "e907831f80848d1069a5371b402410364bdf1c5f8307b0084c55f1ce2dca821525f66a4a85ea8b71e482a74f382d2ce5ebeee8fdb2172f477df4900d310536c0",
+            ),
+            (
+                "b7e151628aed2a6abf7158809cf4f3c762e7160f38b4da56a784d9045190cfef",
+                "dff1d77f2a671c5f36183726db2341be58feae1da2deced843240f7b502ba659",
+                "0000000000000000000000000000000000000000000000000000000000000001",
+                "243f6a8885a308d313198a2e03707344a4093822299f31d0082efa98ec4e6c89",
+                "6896bd60eeae296db48a229ff71dfe071bde413e6d43f917dc8dcf8c78de33418906d11ac976abccb20b091292bff4ea897efcb639ea871cfa95f6de339e4b0a",
+            ),
+        ];
+        for (secret_key, public_key, aux_rand, message, expected) in vectors {
+            let key = SecretKey::from_byte_array(&hex32(secret_key)).unwrap();
+            let (x_only, _) = key.x_only_public_key(secp256k1_context());
+            assert_eq!(hex::encode(x_only.serialize()), public_key);
+
+            let signature = sign_schnorr_with_aux_rand(&hex32(message), &key, &hex32(aux_rand));
+            assert_eq!(hex::encode(signature.to_bytes()), expected);
+            assert!(verify_schnorr(&hex32(message), &signature, &x_only).is_ok());
+        }
+    }
+
+    #[test]
+    fn test_sign_with_extended_key() {
+        let seed = hex::decode("000102030405060708090a0b0c0d0e0f").unwrap();
+        let xprv = ExtendedPrivateKey::new(&seed).unwrap();
+        let xpub = ExtendedPublicKey::from(&xprv);
+        assert_eq!(xprv.x_only_public_key(), xpub.x_only_public_key());
+
+        let signature = sign_schnorr(&[7u8; 32], &xprv.s_key);
+        assert_eq!(SchnorrSignature::from_bytes(&signature.to_bytes()).unwrap(), signature);
+        assert!(verify_schnorr(&[7u8; 32], &signature, &xpub.x_only_public_key()).is_ok());
+        assert_eq!(verify_schnorr(&[8u8; 32], &signature, &xpub.x_only_public_key()), Err(SignatureError::InvalidSignature));
+        assert_eq!(SchnorrSignature::from_bytes(&[0u8; 65]), Err(SignatureError::InvalidLength(65)));
+    }
+}
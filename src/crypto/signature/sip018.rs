@@ -0,0 +1,145 @@
+use secp256k1::{PublicKey, SecretKey};
+
+use crate::crypto::hash::{Hasher, Sha256};
+use crate::network::NetworkKind;
+
+use super::recoverable::{self, RecoverableSignature};
+use super::SignatureError;
+
+/// Prefix of SIP-018 structured data hashes (`"SIP018"`), as used by stacks.js `signStructuredData`.
+pub const STRUCTURED_DATA_PREFIX: &[u8] = b"SIP018";
+
+const CLARITY_TYPE_UINT: u8 = 0x01;
+const CLARITY_TYPE_TUPLE: u8 = 0x0c;
+const CLARITY_TYPE_STRING_ASCII: u8 = 0x0d;
+
+/// SIP-018 domain, the `{ name, version, chain-id }` tuple binding a signature to an application and chain.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct StructuredDataDomain {
+    pub name: String,
+    pub version: String,
+    pub chain_id: u32,
+}
+
+impl StructuredDataDomain {
+    pub fn new(name: &str, version: &str, network: &NetworkKind) -> Self {
+        Self { name: name.to_string(), version: version.to_string(), chain_id: network.chain_id() }
+    }
+
+    /// Consensus serialization of the domain tuple (keys in lexicographic order).
+    pub fn to_clarity_bytes(&self) -> Vec<u8> {
+        let mut bytes = vec![CLARITY_TYPE_TUPLE];
+        bytes.extend(3u32.to_be_bytes());
+        tuple_key(&mut bytes, "chain-id");
+        bytes.push(CLARITY_TYPE_UINT);
+        bytes.extend((self.chain_id as u128).to_be_bytes());
+        tuple_key(&mut bytes, "name");
+        string_ascii(&mut bytes, &self.name);
+        tuple_key(&mut bytes, "version");
+        string_ascii(&mut bytes, &self.version);
+        bytes
+    }
+
+    pub fn hash(&self) -> [u8; 32] {
+        Sha256::hash(&self.to_clarity_bytes())
+    }
+}
+
+fn tuple_key(bytes: &mut Vec<u8>, key: &str) {
+    bytes.push(key.len() as u8);
+    bytes.extend(key.as_bytes());
+}
+
+fn string_ascii(bytes: &mut Vec<u8>, value: &str) {
+    bytes.push(CLARITY_TYPE_STRING_ASCII);
+    bytes.extend((value.len() as u32).to_be_bytes());
+    bytes.extend(value.as_bytes());
+}
+
+/// `sha256(prefix || sha256(domain) || sha256(message))`, where `message` is a consensus-serialized Clarity value.
+pub fn structured_data_hash(domain: &StructuredDataDomain, message: &[u8]) -> [u8; 32] {
+    Sha256::hash(&[STRUCTURED_DATA_PREFIX, &domain.hash(), &Sha256::hash(message)].concat())
+}
+
+/// Signs the serialized Clarity `message` for `domain` with a (derived) key.
+/// Use [`RecoverableSignature::to_rsv`] for the hex layout returned by stacks.js.
+///
+/// Usage:
+/// ```rust
+/// use secp256k1::SecretKey;
+/// use stacks_rs::crypto::context::secp256k1_context;
+/// use stacks_rs::crypto::signature::sip018::{sign_structured_data, verify_structured_data, StructuredDataDomain};
+/// use stacks_rs::network::NetworkKind;
+/// let key = SecretKey::from_byte_array(&[1u8; 32]).unwrap();
+/// let domain = StructuredDataDomain::new("My App", "1.0.0", &NetworkKind::Mainnet);
+/// // u1
+/// let message = [&[0x01][..], &1u128.to_be_bytes()].concat();
+/// let signature = sign_structured_data(&message, &domain, &key);
+/// assert!(verify_structured_data(&message, &domain, &signature, &key.public_key(secp256k1_context())).is_ok());
+/// ```
+pub fn sign_structured_data(message: &[u8], domain: &StructuredDataDomain, key: &SecretKey) -> RecoverableSignature {
+    recoverable::sign_recoverable(&structured_data_hash(domain, message), key)
+}
+
+/// Recovers the public key that signed `message` for `domain`.
+pub fn recover_structured_data(message: &[u8], domain: &StructuredDataDomain, signature: &RecoverableSignature) -> Result<PublicKey, SignatureError> {
+    recoverable::recover(&structured_data_hash(domain, message), signature)
+}
+
+pub fn verify_structured_data(
+    message: &[u8],
+    domain: &StructuredDataDomain,
+    signature: &RecoverableSignature,
+    public_key: &PublicKey,
+) -> Result<(), SignatureError> {
+    if recover_structured_data(message, domain, signature)? != *public_key {
+        return Err(SignatureError::InvalidSignature);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::crypto::context::secp256k1_context;
+
+    fn domain() -> StructuredDataDomain {
+        StructuredDataDomain::new("Test App", "1.0.0", &NetworkKind::Mainnet)
+    }
+
+    /// `"Hello World"` as a Clarity string-ascii
+    fn message() -> Vec<u8> {
+        let mut bytes = Vec::new();
+        string_ascii(&mut bytes, "Hello World");
+        bytes
+    }
+
+    #[test]
+    fn test_sip018_vectors() {
+        assert_eq!(hex::encode(domain().hash()), "2538b5dc06c5ae2f11549261d7ae174d9f77a55a92b00f330884695497be5065");
+        assert_eq!(hex::encode(Sha256::hash(&message())), "5297eef9765c466d945ad1cb2c81b30b9fed6c165575dc9226e9edf78b8cd9e8");
+        assert_eq!(
+            hex::encode(structured_data_hash(&domain(), &message())),
+            "1bfdab6d4158313ce34073fbb8d6b0fc32c154d439def12247a0f44bb2225259"
+        );
+
+        let key = SecretKey::from_slice(&hex::decode("753b7cc01a1a2e86221266a154af739463fce51219d97e4f856cd7200c3bd2a6").unwrap()).unwrap();
+        let signature = sign_structured_data(&message(), &domain(), &key);
+        assert_eq!(
+            hex::encode(signature.to_rsv()),
+            "8b94e45701d857c9f1d1d70e8b2ca076045dae4920fb0160be0642a68cd78de072ab527b5c5277a593baeb2a8b657c216b99f7abb5d14af35b4bf12ba6460ba401"
+        );
+    }
+
+    #[test]
+    fn test_domain_separation() {
+        let key = SecretKey::from_byte_array(&[1u8; 32]).unwrap();
+        let public_key = key.public_key(secp256k1_context());
+        let signature = sign_structured_data(&message(), &domain(), &key);
+        assert!(verify_structured_data(&message(), &domain(), &signature, &public_key).is_ok());
+
+        let testnet = StructuredDataDomain::new("Test App", "1.0.0", &NetworkKind::Testnet);
+        assert_eq!(testnet.chain_id, 0x80000000);
+        assert_eq!(verify_structured_data(&message(), &testnet, &signature, &public_key), Err(SignatureError::InvalidSignature));
+    }
+}
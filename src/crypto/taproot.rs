@@ -0,0 +1,159 @@
+use std::fmt;
+
+use secp256k1::{Keypair, Parity, Scalar, SecretKey, XOnlyPublicKey};
+
+use super::context::secp256k1_context;
+use super::hash::tagged_hash;
+use super::keys::extended_private_key::ExtendedPrivateKey;
+use super::keys::extended_public_key::{ExtendedPublicKey, ExtendedPublicKeyMethods};
+
+/// Length of a P2TR scriptPubKey (`OP_1 OP_PUSHBYTES_32 <output key>`).
+pub const P2TR_SCRIPT_LENGTH: usize = 34;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TaprootError {
+    /// The tweak is not a valid scalar, or the tweaked key is the point at infinity (negligible odds)
+    InvalidTweak,
+}
+
+impl fmt::Display for TaprootError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        match self {
+            TaprootError::InvalidTweak => f.write_str("Invalid taproot tweak"),
+        }
+    }
+}
+
+impl std::error::Error for TaprootError {}
+
+/// BIP341 tweak `t = hash_TapTweak(P || merkle_root)`, without merkle root for key-path only outputs (BIP86).
+pub fn tap_tweak(internal_key: &XOnlyPublicKey, merkle_root: Option<&[u8; 32]>) -> Result<Scalar, TaprootError> {
+    let mut data = internal_key.serialize().to_vec();
+    if let Some(merkle_root) = merkle_root {
+        data.extend(merkle_root);
+    }
+    Scalar::from_be_bytes(tagged_hash("TapTweak", &data)).map_err(|_| TaprootError::InvalidTweak)
+}
+
+/// Output key `Q = P + t G` of the internal key `P`, along with the parity of `Q`.
+///
+/// Usage:
+/// ```rust
+/// use secp256k1::SecretKey;
+/// use stacks_rs::crypto::context::secp256k1_context;
+/// use stacks_rs::crypto::taproot::{p2tr_script_pubkey, tweak_public_key};
+/// let key = SecretKey::from_byte_array(&[1u8; 32]).unwrap();
+/// let (internal_key, _) = key.x_only_public_key(secp256k1_context());
+/// let (output_key, _) = tweak_public_key(&internal_key, None).unwrap();
+/// assert_eq!(p2tr_script_pubkey(&output_key)[..2], [0x51, 0x20]);
+/// ```
+pub fn tweak_public_key(internal_key: &XOnlyPublicKey, merkle_root: Option<&[u8; 32]>) -> Result<(XOnlyPublicKey, Parity), TaprootError> {
+    let tweak = tap_tweak(internal_key, merkle_root)?;
+    internal_key.add_tweak(secp256k1_context(), &tweak).map_err(|_| TaprootError::InvalidTweak)
+}
+
+/// Secret key matching the output key of [`tweak_public_key`], for key-path spends.
+/// The key is negated first if its public key has an odd Y, as BIP341 requires.
+pub fn tweak_secret_key(key: &SecretKey, merkle_root: Option<&[u8; 32]>) -> Result<SecretKey, TaprootError> {
+    let keypair = Keypair::from_secret_key(secp256k1_context(), key);
+    let (internal_key, _) = keypair.x_only_public_key();
+    let tweak = tap_tweak(&internal_key, merkle_root)?;
+    let tweaked = keypair.add_xonly_tweak(secp256k1_context(), &tweak).map_err(|_| TaprootError::InvalidTweak)?;
+    Ok(tweaked.secret_key())
+}
+
+/// `OP_1 OP_PUSHBYTES_32 <output key>`
+pub fn p2tr_script_pubkey(output_key: &XOnlyPublicKey) -> [u8; P2TR_SCRIPT_LENGTH] {
+    let mut script = [0u8; P2TR_SCRIPT_LENGTH];
+    script[0] = 0x51;
+    script[1] = 0x20;
+    script[2..].copy_from_slice(&output_key.serialize());
+    script
+}
+
+/// Taproot output keys of the keys of the hierarchy, e.g. derived at `m/86'/0'/0'/0/i`.
+pub trait TaprootKeyMethods {
+    fn taproot_output_key(&self, merkle_root: Option<&[u8; 32]>) -> Result<XOnlyPublicKey, TaprootError>;
+}
+
+impl TaprootKeyMethods for ExtendedPublicKey {
+    fn taproot_output_key(&self, merkle_root: Option<&[u8; 32]>) -> Result<XOnlyPublicKey, TaprootError> {
+        let (internal_key, _) = self.public_key().x_only_public_key();
+        Ok(tweak_public_key(&internal_key, merkle_root)?.0)
+    }
+}
+
+impl TaprootKeyMethods for ExtendedPrivateKey {
+    fn taproot_output_key(&self, merkle_root: Option<&[u8; 32]>) -> Result<XOnlyPublicKey, TaprootError> {
+        ExtendedPublicKey::from(self).taproot_output_key(merkle_root)
+    }
+}
+
+/// Tweaked secret keys of the private keys of the hierarchy.
+pub trait TaprootSecretKeyMethods {
+    /// Tweaked secret key for key-path spends of [`TaprootKeyMethods::taproot_output_key`].
+    fn taproot_secret_key(&self, merkle_root: Option<&[u8; 32]>) -> Result<SecretKey, TaprootError>;
+}
+
+impl TaprootSecretKeyMethods for ExtendedPrivateKey {
+    fn taproot_secret_key(&self, merkle_root: Option<&[u8; 32]>) -> Result<SecretKey, TaprootError> {
+        tweak_secret_key(&self.s_key, merkle_root)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use super::*;
+    use crate::bip32::derivation_path::DerivationPath;
+    use crate::crypto::keys::extended_private_key::ExtendedPrivateKeyMethods;
+    use crate::crypto::signature::schnorr::{sign_schnorr, verify_schnorr};
+
+    fn x_only(value: &str) -> XOnlyPublicKey {
+        XOnlyPublicKey::from_slice(&hex::decode(value).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn test_bip86_vector() {
+        // BIP86, first receiving address of "abandon abandon ... about"
+        let seed = bip39::Mnemonic::from_str("abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon about")
+            .unwrap()
+            .to_seed("");
+        let key = ExtendedPrivateKey::derive_from_path(&seed, DerivationPath::from_str("m/86'/0'/0'/0/0").unwrap());
+        let (internal_key, _) = key.public_key().x_only_public_key();
+        assert_eq!(hex::encode(internal_key.serialize()), "cc8a4bc64d897bddc5fbc2f670f7a8ba0b386779106cf1223c6fc5d7cd6fc115");
+
+        let output_key = key.taproot_output_key(None).unwrap();
+        assert_eq!(hex::encode(output_key.serialize()), "a60869f0dbcf1dc659c9cecbaf8050135ea9e8cdc487053f1dc6880949dc684c");
+        assert_eq!(
+            hex::encode(p2tr_script_pubkey(&output_key)),
+            "5120a60869f0dbcf1dc659c9cecbaf8050135ea9e8cdc487053f1dc6880949dc684c"
+        );
+    }
+
+    #[test]
+    fn test_bip341_merkle_root_vector() {
+        // BIP341 wallet test vectors, scriptPubKey[1]
+        let internal_key = x_only("187791b6f712a8ea41c8ecdd0ee77fab3e85263b37e1ec18a3651926b3a6cf27");
+        let merkle_root: [u8; 32] = hex::decode("5b75adecf53548f3ec6ad7d78383bf84cc57b55a3127c72b9a2481752dd88b21").unwrap().try_into().unwrap();
+        let tweak = tap_tweak(&internal_key, Some(&merkle_root)).unwrap();
+        assert_eq!(hex::encode(tweak.to_be_bytes()), "cbd8679ba636c1110ea247542cfbd964131a6be84f873f7f3b62a777528ed001");
+        let (output_key, _) = tweak_public_key(&internal_key, Some(&merkle_root)).unwrap();
+        assert_eq!(hex::encode(output_key.serialize()), "147c9c57132f6e7ecddba9800bb0c4449251c92a1e60371ee77557b6620f3ea3");
+    }
+
+    #[test]
+    fn test_tweaked_secret_key_signs_for_output_key() {
+        let seed = hex::decode("000102030405060708090a0b0c0d0e0f").unwrap();
+        let key = ExtendedPrivateKey::new(&seed).unwrap();
+        for merkle_root in [None, Some(&[7u8; 32])] {
+            let output_key = key.taproot_output_key(merkle_root).unwrap();
+            let tweaked = key.taproot_secret_key(merkle_root).unwrap();
+            assert_eq!(tweaked.x_only_public_key(secp256k1_context()).0, output_key);
+
+            let signature = sign_schnorr(&[9u8; 32], &tweaked);
+            assert!(verify_schnorr(&[9u8; 32], &signature, &output_key).is_ok());
+        }
+    }
+}
@@ -1,8 +1,15 @@
-use rand::{thread_rng, RngCore};
+use rand::rngs::OsRng;
+use rand::{CryptoRng, RngCore};
 
 
-/// Generates `n` rand bytes and stores them in `dest`
+/// Generates `n` rand bytes from the OS RNG and stores them in `dest`
 pub fn generate_random_bytes(dest: &mut [u8], n: usize) -> &[u8] {
-    thread_rng().fill_bytes( &mut dest[0..n]);
+    generate_random_bytes_with_rng(&mut OsRng, dest, n)
+}
+
+/// Same as [`generate_random_bytes`] but draws the bytes from `rng`,
+/// e.g. an HSM-backed RNG or a seeded one in tests.
+pub fn generate_random_bytes_with_rng<'a, R: RngCore + CryptoRng>(rng: &mut R, dest: &'a mut [u8], n: usize) -> &'a [u8] {
+    rng.fill_bytes(&mut dest[0..n]);
     dest
-}
\ No newline at end of file
+}
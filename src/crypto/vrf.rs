@@ -0,0 +1,121 @@
+//! ECVRF used by Stacks miners to commit to sortition seeds.
+//!
+//! stacks-node proves over edwards25519 (ECVRF-EDWARDS25519-SHA512-ELL2, draft-irtf-cfrg-vrf-03),
+//! not secp256k1, so this wraps the `stacks-common` implementation the node itself uses.
+
+use std::fmt;
+
+use stacks_common::types::chainstate::VRFSeed;
+use stacks_common::util::vrf::VRF;
+pub use stacks_common::util::vrf::{VRFPrivateKey, VRFProof, VRFPublicKey};
+
+use crate::crypto::hash::{Hasher, Sha256};
+use crate::crypto::keys::extended_private_key::ExtendedPrivateKey;
+
+pub const VRF_PROOF_LENGTH: usize = 80;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum VrfError {
+    InvalidPublicKey,
+    InvalidProof,
+    /// The proof is well-formed but was not made by the key over the message
+    VerificationFailed,
+}
+
+impl fmt::Display for VrfError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        match self {
+            VrfError::InvalidPublicKey => f.write_str("Invalid VRF public key"),
+            VrfError::InvalidProof => f.write_str("Invalid VRF proof"),
+            VrfError::VerificationFailed => f.write_str("VRF proof verification failed"),
+        }
+    }
+}
+
+impl std::error::Error for VrfError {}
+
+/// VRF keypair of `secret_state` for the key registration at `block_height`,
+/// derived like the stacks-node keychain: `sha256(secret_state || block_height (8 bytes BE))`.
+pub fn vrf_keypair(secret_state: &[u8], block_height: u64) -> (VRFPrivateKey, VRFPublicKey) {
+    let seed = Sha256::hash(&[secret_state, &block_height.to_be_bytes()].concat());
+    // every 32-byte string is an Ed25519 secret key
+    let private_key = VRFPrivateKey::from_bytes(&seed).unwrap();
+    let public_key = VRFPublicKey::from_private(&private_key);
+    (private_key, public_key)
+}
+
+/// Derives the miner's VRF keys from a key of the seed hierarchy.
+///
+/// Usage:
+/// ```rust
+/// use stacks_rs::crypto::keys::extended_private_key::{ExtendedPrivateKey, ExtendedPrivateKeyMethods};
+/// use stacks_rs::crypto::vrf::{prove, verify, VrfKeyMethods};
+/// let key = ExtendedPrivateKey::new(&[7u8; 32]).unwrap();
+/// let (private_key, public_key) = key.vrf_keypair(100);
+/// let proof = prove(&private_key, b"sortition");
+/// assert!(verify(&public_key, &proof, b"sortition").is_ok());
+/// ```
+pub trait VrfKeyMethods {
+    fn vrf_keypair(&self, block_height: u64) -> (VRFPrivateKey, VRFPublicKey);
+}
+
+impl VrfKeyMethods for ExtendedPrivateKey {
+    fn vrf_keypair(&self, block_height: u64) -> (VRFPrivateKey, VRFPublicKey) {
+        vrf_keypair(&self.s_key.secret_bytes(), block_height)
+    }
+}
+
+pub fn prove(private_key: &VRFPrivateKey, message: &[u8]) -> VRFProof {
+    VRF::prove(private_key, message)
+}
+
+pub fn verify(public_key: &VRFPublicKey, proof: &VRFProof, message: &[u8]) -> Result<(), VrfError> {
+    match VRF::verify(public_key, proof, message) {
+        Ok(true) => Ok(()),
+        Ok(false) => Err(VrfError::VerificationFailed),
+        Err(_) => Err(VrfError::InvalidPublicKey),
+    }
+}
+
+pub fn proof_from_bytes(bytes: &[u8]) -> Result<VRFProof, VrfError> {
+    VRFProof::from_bytes(bytes).ok_or(VrfError::InvalidProof)
+}
+
+/// Sortition seed committed to by a proof (`sha512/256` of the proof).
+pub fn seed_from_proof(proof: &VRFProof) -> [u8; 32] {
+    VRFSeed::from_proof(proof).0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::crypto::keys::extended_private_key::ExtendedPrivateKeyMethods;
+
+    #[test]
+    fn test_vrf_vector() {
+        // draft-irtf-cfrg-vrf-04 A.3, example 2
+        let private_key = VRFPrivateKey::from_hex("4ccd089b28ff96da9db6c346ec114e0f5b8a319f35aba624da8cf6ed4fb8a6fb").unwrap();
+        let proof = prove(&private_key, &[0x72]);
+        assert_eq!(
+            proof.to_hex(),
+            "84a63e74eca8fdd64e9972dcda1c6f33d03ce3cd4d333fd6cc789db12b5a7b9d03f1cb6b2bf7cd81a2a20bacf6e1c04e59f2fa16d9119c73a45a97194b504fb9a5c8cf37f6da85e03368d6882e511008"
+        );
+        let public_key = VRFPublicKey::from_private(&private_key);
+        assert!(verify(&public_key, &proof, &[0x72]).is_ok());
+        assert_eq!(verify(&public_key, &proof, &[0x73]), Err(VrfError::VerificationFailed));
+
+        let parsed = proof_from_bytes(&proof.to_bytes()).unwrap();
+        assert_eq!(seed_from_proof(&parsed), seed_from_proof(&proof));
+        assert_eq!(proof_from_bytes(&[0u8; VRF_PROOF_LENGTH - 1]).unwrap_err(), VrfError::InvalidProof);
+    }
+
+    #[test]
+    fn test_keypair_derivation() {
+        let key = ExtendedPrivateKey::new(&[7u8; 32]).unwrap();
+        let (private_key, public_key) = key.vrf_keypair(100);
+        assert_eq!(private_key.to_bytes(), vrf_keypair(&key.s_key.secret_bytes(), 100).0.to_bytes());
+        assert_eq!(public_key, VRFPublicKey::from_private(&private_key));
+        // one key per registration height
+        assert_ne!(key.vrf_keypair(101).1, public_key);
+    }
+}
@@ -1,5 +1,16 @@
 pub mod network;
+#[deprecated(note = "superseded by the `transaction` module")]
 pub mod transactions;
+pub mod transaction;
 pub mod wallet;
 pub mod crypto;
 pub mod bip32;
+pub mod stacking;
+pub mod client;
+pub mod address;
+pub mod clarity;
+pub mod ur;
+
+/// Lets the tests use the paths of generated code, see [`clarity::codegen`].
+#[cfg(test)]
+extern crate self as stacks_rs;
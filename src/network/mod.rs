@@ -1,4 +1,12 @@
-#[derive(PartialEq, Eq, Clone)]
+use secp256k1::PublicKey;
+use serde::{Deserialize, Serialize};
+
+use crate::address::stacks_address::StacksAddress;
+use crate::transaction::fee::FeeStrategy;
+use crate::transaction::stacks_transaction::{TransactionVersion, MIN_FEE_RATE};
+
+#[derive(PartialEq, Eq, Clone, Copy, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
 pub enum NetworkKind {
     Mainnet,
     Testnet,
@@ -6,7 +14,7 @@ pub enum NetworkKind {
 }
 
 impl NetworkKind {
-    fn chain_id(&self) -> u32 {
+    pub(crate) fn chain_id(&self) -> u32 {
         match *self {
             NetworkKind::Mainnet => 0x00000001,
             NetworkKind::Testnet => 0x80000000,
@@ -35,6 +43,101 @@ impl Network {
     }
 }
 
+/// Everything that differs between Stacks networks: chain id, versions, endpoints and fees.
+///
+/// Builders and clients take a whole `StacksNetwork`, so a testnet transaction cannot get a
+/// mainnet chain id or be sent to a mainnet node by mistake.
+///
+/// Usage:
+/// ```rust
+/// use stacks_rs::network::{AddressVersion, StacksNetwork};
+/// let devnet = StacksNetwork::devnet().with_node_url("http://devnet:20443");
+/// assert_eq!(devnet.chain_id, 0x80000000);
+/// assert_eq!(devnet.single_sig_version(), AddressVersion::TestnetSingleSig);
+/// assert_eq!(devnet.node_url, "http://devnet:20443");
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct StacksNetwork {
+    pub kind: NetworkKind,
+    pub chain_id: u32,
+    /// RPC endpoint of a Stacks node (`/v2/...`)
+    pub node_url: String,
+    /// Stacks Blockchain API (`/extended/...`)
+    pub api_url: String,
+    /// Fee of the transactions built for this network, unless set on the builder
+    pub default_fee: FeeStrategy,
+}
+
+impl StacksNetwork {
+    pub fn mainnet() -> Self {
+        Self::custom(NetworkKind::Mainnet, NetworkKind::Mainnet.chain_id(), "https://api.hiro.so")
+    }
+
+    pub fn testnet() -> Self {
+        Self::custom(NetworkKind::Testnet, NetworkKind::Testnet.chain_id(), "https://api.testnet.hiro.so")
+    }
+
+    /// A local devnet, with the ports of Clarinet.
+    pub fn devnet() -> Self {
+        Self::custom(NetworkKind::Mocknet, NetworkKind::Mocknet.chain_id(), "http://localhost:20443").with_api_url("http://localhost:3999")
+    }
+
+    /// A network of `kind` versions with its own chain id, e.g. a subnet, whose node also
+    /// serves the API.
+    pub fn custom(kind: NetworkKind, chain_id: u32, node_url: &str) -> Self {
+        StacksNetwork {
+            kind,
+            chain_id,
+            node_url: node_url.trim_end_matches('/').to_string(),
+            api_url: node_url.trim_end_matches('/').to_string(),
+            default_fee: FeeStrategy::Rate(MIN_FEE_RATE),
+        }
+    }
+
+    pub fn with_node_url(mut self, node_url: &str) -> Self {
+        self.node_url = node_url.trim_end_matches('/').to_string();
+        self
+    }
+
+    pub fn with_api_url(mut self, api_url: &str) -> Self {
+        self.api_url = api_url.trim_end_matches('/').to_string();
+        self
+    }
+
+    pub fn with_default_fee(mut self, default_fee: FeeStrategy) -> Self {
+        self.default_fee = default_fee;
+        self
+    }
+
+    pub fn transaction_version(&self) -> TransactionVersion {
+        TransactionVersion::from_network(&self.kind)
+    }
+
+    pub fn single_sig_version(&self) -> AddressVersion {
+        AddressVersion::single_sig(&self.kind)
+    }
+
+    pub fn multi_sig_version(&self) -> AddressVersion {
+        AddressVersion::multi_sig(&self.kind)
+    }
+
+    /// Single-sig address of `public_key` on this network.
+    pub fn address(&self, public_key: &PublicKey) -> StacksAddress {
+        StacksAddress::from_public_key(public_key, self.single_sig_version())
+    }
+}
+
+impl From<NetworkKind> for StacksNetwork {
+    fn from(kind: NetworkKind) -> Self {
+        match kind {
+            NetworkKind::Mainnet => StacksNetwork::mainnet(),
+            NetworkKind::Testnet => StacksNetwork::testnet(),
+            NetworkKind::Mocknet => StacksNetwork::devnet(),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum AddressVersion {
     MainnetSingleSig,
     MainnetMultiSig,
@@ -43,7 +146,45 @@ pub enum AddressVersion {
 }
 
 impl AddressVersion {
-    fn value(&self) -> u8 {
+    /// Version of the single-sig addresses of `network`.
+    pub fn single_sig(network: &NetworkKind) -> Self {
+        match network {
+            NetworkKind::Mainnet => AddressVersion::MainnetSingleSig,
+            NetworkKind::Testnet | NetworkKind::Mocknet => AddressVersion::TestnetSingleSig,
+        }
+    }
+
+    /// Version of the multi-sig addresses of `network`.
+    pub fn multi_sig(network: &NetworkKind) -> Self {
+        match network {
+            NetworkKind::Mainnet => AddressVersion::MainnetMultiSig,
+            NetworkKind::Testnet | NetworkKind::Mocknet => AddressVersion::TestnetMultiSig,
+        }
+    }
+
+    /// Inverse of [`AddressVersion::value`].
+    pub fn from_value(value: u8) -> Option<Self> {
+        match value {
+            22 => Some(AddressVersion::MainnetSingleSig),
+            20 => Some(AddressVersion::MainnetMultiSig),
+            26 => Some(AddressVersion::TestnetSingleSig),
+            21 => Some(AddressVersion::TestnetMultiSig),
+            _ => None,
+        }
+    }
+
+    pub fn network(&self) -> NetworkKind {
+        match *self {
+            AddressVersion::MainnetSingleSig | AddressVersion::MainnetMultiSig => NetworkKind::Mainnet,
+            AddressVersion::TestnetSingleSig | AddressVersion::TestnetMultiSig => NetworkKind::Testnet,
+        }
+    }
+
+    pub fn is_multi_sig(&self) -> bool {
+        matches!(self, AddressVersion::MainnetMultiSig | AddressVersion::TestnetMultiSig)
+    }
+
+    pub fn value(&self) -> u8 {
         match *self {
             AddressVersion::MainnetSingleSig => 22, // `P` — A single-sig address for mainnet (starting with `SP`)
             AddressVersion::MainnetMultiSig => 20, // `M` — A multi-sig address for mainnet (starting with `SM`)
@@ -80,4 +221,19 @@ mod tests {
         assert_eq!(mocknet.is_mainnet(), false);
         assert_eq!(mocknet.url, String::from("https://www.mystacksnode.com/"));
     }
+
+    #[test]
+    fn test_stacks_network() {
+        let public_key = PublicKey::from_slice(&hex::decode("03ef788b3830c00abe8f64f62dc32fc863bc0b2cafeb073b6c8e1c7657d9c2c3ab").unwrap()).unwrap();
+        let mainnet = StacksNetwork::from(NetworkKind::Mainnet);
+        assert_eq!((mainnet.chain_id, mainnet.transaction_version()), (1, TransactionVersion::Mainnet));
+        assert_eq!(mainnet.multi_sig_version(), AddressVersion::MainnetMultiSig);
+        assert!(mainnet.address(&public_key).to_string().starts_with("SP"));
+        assert!(StacksNetwork::testnet().address(&public_key).to_string().starts_with("ST"));
+
+        let subnet = StacksNetwork::custom(NetworkKind::Testnet, 0x55005500, "http://subnet:20443/").with_default_fee(FeeStrategy::Fixed(10));
+        assert_eq!((subnet.node_url.as_str(), subnet.api_url.as_str()), ("http://subnet:20443", "http://subnet:20443"));
+        assert_eq!((subnet.chain_id, subnet.default_fee), (0x55005500, FeeStrategy::Fixed(10)));
+        assert_eq!(subnet.transaction_version(), TransactionVersion::Testnet);
+    }
 }
@@ -0,0 +1,698 @@
+//! Builders of the PoX-4 contract calls, on top of [`ContractCallBuilder`]: they take the
+//! contract and cycle of the node's [`PoxInfo`] and check the arguments PoX-4 would reject.
+
+use std::collections::BTreeMap;
+use std::fmt;
+use std::str::FromStr;
+
+use secp256k1::PublicKey;
+
+use crate::address::principal::Principal;
+use crate::clarity::value::ClarityValue;
+use crate::client::types::PoxInfo;
+use crate::crypto::signature::recoverable::RecoverableSignature;
+use crate::transaction::builder::ContractCallBuilder;
+
+use super::config::{StackingConfig, StackingMode, MAX_LOCK_CYCLES};
+use super::pox_address::{PoxAddress, PoxAddressError, PoxContractVersion};
+use super::signer::{SignerKeyMessage, SignerTopic};
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum StackingError {
+    /// The active PoX contract is not `pox-4`
+    UnsupportedContract(String),
+    InvalidLockPeriod(u8),
+    AmountTooLow { amount: u64, minimum: u64 },
+    /// The signer authorizes less than the amount to lock
+    AmountAboveMaximum { amount: u64, max_amount: u64 },
+    PoxAddress(PoxAddressError),
+    /// The call returned `(err code)`, e.g. 4 for `ERR_STACKING_NO_SUCH_PRINCIPAL`
+    ContractError(i128),
+    /// The result is not the `(response uint int)` of `stack-aggregation-commit-indexed`
+    UnexpectedResult(String),
+    /// The [`StackingConfig`] is invalid or of the other stacking mode
+    InvalidConfig(String),
+}
+
+impl fmt::Display for StackingError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        match self {
+            StackingError::UnsupportedContract(v) => f.write_str(&format!("Unsupported PoX contract {v}, expected pox-4")),
+            StackingError::InvalidLockPeriod(v) => f.write_str(&format!("Invalid lock period {v}, must be between 1 and {MAX_LOCK_CYCLES}")),
+            StackingError::AmountTooLow { amount, minimum } => f.write_str(&format!("Amount {amount} below the minimum of {minimum} micro-STX")),
+            StackingError::AmountAboveMaximum { amount, max_amount } => {
+                f.write_str(&format!("Amount {amount} above the {max_amount} micro-STX authorized by the signer"))
+            }
+            StackingError::PoxAddress(error) => f.write_str(&format!("{error}")),
+            StackingError::ContractError(v) => f.write_str(&format!("PoX contract error {v}")),
+            StackingError::UnexpectedResult(v) => f.write_str(&format!("Unexpected PoX contract result {v}")),
+            StackingError::InvalidConfig(v) => f.write_str(&format!("Invalid stacking config: {v}")),
+        }
+    }
+}
+
+impl std::error::Error for StackingError {}
+
+impl From<PoxAddressError> for StackingError {
+    fn from(error: PoxAddressError) -> Self {
+        StackingError::PoxAddress(error)
+    }
+}
+
+fn check_lock_period(lock_period: u8) -> Result<(), StackingError> {
+    match (1..=MAX_LOCK_CYCLES).contains(&lock_period) {
+        true => Ok(()),
+        false => Err(StackingError::InvalidLockPeriod(lock_period)),
+    }
+}
+
+/// The active PoX contract, if it is `pox-4`.
+fn pox4_contract(pox: &PoxInfo) -> Result<Principal, StackingError> {
+    match pox.contract_version() {
+        Some(PoxContractVersion::Pox4) => Principal::from_str(&pox.contract_id).map_err(|_| StackingError::UnsupportedContract(pox.contract_id.clone())),
+        _ => Err(StackingError::UnsupportedContract(pox.contract_id.clone())),
+    }
+}
+
+fn check_config(config: &StackingConfig) -> Result<(), StackingError> {
+    config.validate().map_err(|err| StackingError::InvalidConfig(format!("{err}")))
+}
+
+/// The `signer-sig`, `signer-key`, `max-amount` and `auth-id` arguments.
+fn signer_args(signature: Option<&RecoverableSignature>, signer_key: &PublicKey, max_amount: u64, auth_id: u128) -> [ClarityValue; 4] {
+    let signature = match signature {
+        Some(signature) => ClarityValue::OptionalSome(Box::new(ClarityValue::Buffer(signature.to_rsv().to_vec()))),
+        None => ClarityValue::OptionalNone,
+    };
+    [signature, ClarityValue::Buffer(signer_key.serialize().to_vec()), ClarityValue::UInt(max_amount as u128), ClarityValue::UInt(auth_id)]
+}
+
+/// Builds the PoX-4 `stack-stx` call locking `amount` micro-STX of the sender for
+/// `lock_period` cycles, from the current burn block, rewarding `pox_address`.
+///
+/// Locking is no transfer: under the default [`PostConditionMode::Deny`] and no post conditions,
+/// the call aborts if it moves any asset of the stacker. Without a signer signature, the signer
+/// must have authorized its key with `set-signer-key-authorization` instead.
+///
+/// [`PostConditionMode::Deny`]: crate::transaction::post_condition::PostConditionMode::Deny
+///
+/// Usage:
+/// ```rust,no_run
+/// # #[cfg(feature = "blocking")]
+/// # {
+/// use std::str::FromStr;
+/// use secp256k1::{PublicKey, SecretKey};
+/// use stacks_rs::client::rpc::StacksRpcClient;
+/// use stacks_rs::crypto::context::secp256k1_context;
+/// use stacks_rs::network::{NetworkKind, StacksNetwork};
+/// use stacks_rs::stacking::builder::StackStxBuilder;
+/// use stacks_rs::stacking::pox_address::PoxAddress;
+/// let client = StacksRpcClient::new(StacksNetwork::mainnet());
+/// let pox = client.get_pox_info().unwrap();
+/// let pox_address = PoxAddress::from_address("bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4", &NetworkKind::Mainnet).unwrap();
+/// let signer_key = SecretKey::from_byte_array(&[2u8; 32]).unwrap();
+/// let builder = StackStxBuilder::new(&pox, 200_000_000_000, pox_address, signer_key.public_key(secp256k1_context())).lock_period(6).auth_id(1);
+/// let signature = builder.signer_key_message().sign(client.network().chain_id, &signer_key);
+/// let stacker = PublicKey::from_str("03ef788b3830c00abe8f64f62dc32fc863bc0b2cafeb073b6c8e1c7657d9c2c3ab").unwrap();
+/// let transaction = builder.signer_signature(signature).contract_call().unwrap().nonce(3).build(&stacker).unwrap();
+/// # }
+/// ```
+#[derive(Clone, Debug)]
+pub struct StackStxBuilder {
+    pox: PoxInfo,
+    amount: u64,
+    pox_address: PoxAddress,
+    start_burn_height: u64,
+    lock_period: u8,
+    signer_key: PublicKey,
+    signer_signature: Option<RecoverableSignature>,
+    max_amount: Option<u64>,
+    auth_id: u128,
+}
+
+impl StackStxBuilder {
+    pub fn new(pox: &PoxInfo, amount: u64, pox_address: PoxAddress, signer_key: PublicKey) -> Self {
+        StackStxBuilder {
+            pox: pox.clone(),
+            amount,
+            pox_address,
+            start_burn_height: pox.current_burnchain_block_height,
+            lock_period: 1,
+            signer_key,
+            signer_signature: None,
+            max_amount: None,
+            auth_id: 0,
+        }
+    }
+
+    /// Builder of a solo [`StackingConfig`], locking its amount of `balance` for its cycles; the
+    /// signer key is the one at the config's signer key path.
+    pub fn from_config(config: &StackingConfig, pox: &PoxInfo, balance: u64, signer_key: PublicKey) -> Result<Self, StackingError> {
+        check_config(config)?;
+        let (StackingMode::Solo { .. }, Some(reward_address)) = (&config.mode, &config.reward_address) else {
+            return Err(StackingError::InvalidConfig(String::from("stack-stx requires a solo config")));
+        };
+        let pox_address = PoxAddress::from_address(reward_address, &config.network)?;
+        Ok(Self::new(pox, config.amount.resolve(balance), pox_address, signer_key).lock_period(config.cycles))
+    }
+
+    /// Number of reward cycles, 1 by default.
+    pub fn lock_period(mut self, lock_period: u8) -> Self {
+        self.lock_period = lock_period;
+        self
+    }
+
+    /// The current burn block height of the [`PoxInfo`] by default.
+    pub fn start_burn_height(mut self, start_burn_height: u64) -> Self {
+        self.start_burn_height = start_burn_height;
+        self
+    }
+
+    pub fn signer_signature(mut self, signature: RecoverableSignature) -> Self {
+        self.signer_signature = Some(signature);
+        self
+    }
+
+    /// Most micro-STX the signer authorizes, the amount by default.
+    pub fn max_amount(mut self, max_amount: u64) -> Self {
+        self.max_amount = Some(max_amount);
+        self
+    }
+
+    /// 0 by default; a signature can only be used once with a given auth id.
+    pub fn auth_id(mut self, auth_id: u128) -> Self {
+        self.auth_id = auth_id;
+        self
+    }
+
+    /// The message the signer signs to authorize this call.
+    pub fn signer_key_message(&self) -> SignerKeyMessage {
+        SignerKeyMessage {
+            pox_address: self.pox_address.clone(),
+            reward_cycle: self.pox.reward_cycle_id,
+            topic: SignerTopic::StackStx,
+            period: self.lock_period,
+            max_amount: self.max_amount.unwrap_or(self.amount),
+            auth_id: self.auth_id,
+        }
+    }
+
+    /// The contract call, to set the nonce and fee of and build.
+    pub fn contract_call(self) -> Result<ContractCallBuilder, StackingError> {
+        let contract = pox4_contract(&self.pox)?;
+        check_lock_period(self.lock_period)?;
+        if self.amount < self.pox.min_amount_ustx {
+            return Err(StackingError::AmountTooLow { amount: self.amount, minimum: self.pox.min_amount_ustx });
+        }
+        let max_amount = self.max_amount.unwrap_or(self.amount);
+        if self.amount > max_amount {
+            return Err(StackingError::AmountAboveMaximum { amount: self.amount, max_amount });
+        }
+        self.pox_address.check_allowed(PoxContractVersion::Pox4)?;
+
+        let mut args = vec![
+            ClarityValue::UInt(self.amount as u128),
+            self.pox_address.to_clarity_value(),
+            ClarityValue::UInt(self.start_burn_height as u128),
+            ClarityValue::UInt(self.lock_period as u128),
+        ];
+        args.extend(signer_args(self.signer_signature.as_ref(), &self.signer_key, max_amount, self.auth_id));
+        Ok(ContractCallBuilder::new(contract, "stack-stx", args))
+    }
+}
+
+/// Builds the PoX-4 `delegate-stx` call with which a stacker lets the pool operator `delegate_to`
+/// lock up to `amount` micro-STX of its balance, the stacker's side of pool stacking.
+///
+/// Usage:
+/// ```rust
+/// use std::str::FromStr;
+/// use secp256k1::SecretKey;
+/// use stacks_rs::address::principal::Principal;
+/// use stacks_rs::client::types::PoxInfo;
+/// use stacks_rs::crypto::context::secp256k1_context;
+/// use stacks_rs::stacking::builder::DelegateStxBuilder;
+/// # let pox: PoxInfo = serde_json::from_str(r#"{"contract_id": "SP000000000000000000002Q6VF78.pox-4", "first_burnchain_block_height": 666050,
+/// #     "current_burnchain_block_height": 867000, "min_amount_ustx": 170000000000, "reward_cycle_id": 95, "reward_cycle_length": 2100,
+/// #     "prepare_cycle_length": 100, "reward_slots": 4000, "total_liquid_supply_ustx": 1492418123510300,
+/// #     "current_cycle": {"id": 95, "min_threshold_ustx": 160000000000, "stacked_ustx": 0, "is_pox_active": true},
+/// #     "next_cycle": {"id": 96, "min_threshold_ustx": 170000000000, "stacked_ustx": 0, "prepare_phase_start_block_height": 867850,
+/// #     "blocks_until_prepare_phase": 850, "reward_phase_start_block_height": 867950, "blocks_until_reward_phase": 950}, "next_reward_cycle_in": 950}"#).unwrap();
+/// let pool = Principal::from_str("SP3FGQ8Z7JY9BWYZ5WM53E0M9NK7WHJF0691NZ159.pool").unwrap();
+/// let stacker = SecretKey::from_byte_array(&[1u8; 32]).unwrap().public_key(secp256k1_context());
+/// let call = DelegateStxBuilder::new(&pox, 100_000_000_000, pool).until_burn_height(870000).contract_call().unwrap();
+/// let transaction = call.nonce(0).fee(1000).build(&stacker).unwrap();
+/// ```
+#[derive(Clone, Debug)]
+pub struct DelegateStxBuilder {
+    pox: PoxInfo,
+    amount: u64,
+    delegate_to: Principal,
+    until_burn_height: Option<u64>,
+    pox_address: Option<PoxAddress>,
+}
+
+impl DelegateStxBuilder {
+    pub fn new(pox: &PoxInfo, amount: u64, delegate_to: Principal) -> Self {
+        DelegateStxBuilder { pox: pox.clone(), amount, delegate_to, until_burn_height: None, pox_address: None }
+    }
+
+    /// Builder of a pool [`StackingConfig`], delegating its amount of `balance`.
+    pub fn from_config(config: &StackingConfig, pox: &PoxInfo, balance: u64) -> Result<Self, StackingError> {
+        check_config(config)?;
+        let StackingMode::Pool { pool_address, until_burn_height } = &config.mode else {
+            return Err(StackingError::InvalidConfig(String::from("delegate-stx requires a pool config")));
+        };
+        let delegate_to = Principal::from_str(pool_address).map_err(|err| StackingError::InvalidConfig(format!("{err}")))?;
+        let mut builder = Self::new(pox, config.amount.resolve(balance), delegate_to);
+        builder.until_burn_height = *until_burn_height;
+        if let Some(reward_address) = &config.reward_address {
+            builder.pox_address = Some(PoxAddress::from_address(reward_address, &config.network)?);
+        }
+        Ok(builder)
+    }
+
+    /// Burn block height the delegation expires at, never by default.
+    pub fn until_burn_height(mut self, until_burn_height: u64) -> Self {
+        self.until_burn_height = Some(until_burn_height);
+        self
+    }
+
+    /// Only lets the operator lock for `pox_address`, any address by default.
+    pub fn pox_address(mut self, pox_address: PoxAddress) -> Self {
+        self.pox_address = Some(pox_address);
+        self
+    }
+
+    /// The contract call, to set the nonce and fee of and build.
+    pub fn contract_call(self) -> Result<ContractCallBuilder, StackingError> {
+        let contract = pox4_contract(&self.pox)?;
+        let pox_address = match self.pox_address {
+            Some(pox_address) => {
+                pox_address.check_allowed(PoxContractVersion::Pox4)?;
+                ClarityValue::OptionalSome(Box::new(pox_address.to_clarity_value()))
+            }
+            None => ClarityValue::OptionalNone,
+        };
+        let until_burn_height = match self.until_burn_height {
+            Some(height) => ClarityValue::OptionalSome(Box::new(ClarityValue::UInt(height as u128))),
+            None => ClarityValue::OptionalNone,
+        };
+        let args = vec![ClarityValue::UInt(self.amount as u128), ClarityValue::Principal(self.delegate_to), until_burn_height, pox_address];
+        Ok(ContractCallBuilder::new(contract, "delegate-stx", args))
+    }
+}
+
+/// Builds the PoX-4 `delegate-stack-stx` call with which a pool operator locks `amount`
+/// micro-STX delegated to it by `stacker`, the first step of the pool operator flow.
+///
+/// The locked amounts are then committed per reward cycle with
+/// [`StackAggregationCommitBuilder`], and amounts locked later added with
+/// [`StackAggregationIncreaseBuilder`].
+#[derive(Clone, Debug)]
+pub struct DelegateStackStxBuilder {
+    pox: PoxInfo,
+    stacker: Principal,
+    amount: u64,
+    pox_address: PoxAddress,
+    start_burn_height: u64,
+    lock_period: u8,
+}
+
+impl DelegateStackStxBuilder {
+    pub fn new(pox: &PoxInfo, stacker: Principal, amount: u64, pox_address: PoxAddress) -> Self {
+        DelegateStackStxBuilder { pox: pox.clone(), stacker, amount, pox_address, start_burn_height: pox.current_burnchain_block_height, lock_period: 1 }
+    }
+
+    /// Number of reward cycles, 1 by default.
+    pub fn lock_period(mut self, lock_period: u8) -> Self {
+        self.lock_period = lock_period;
+        self
+    }
+
+    /// The current burn block height of the [`PoxInfo`] by default.
+    pub fn start_burn_height(mut self, start_burn_height: u64) -> Self {
+        self.start_burn_height = start_burn_height;
+        self
+    }
+
+    /// The contract call, to set the nonce and fee of and build.
+    pub fn contract_call(self) -> Result<ContractCallBuilder, StackingError> {
+        let contract = pox4_contract(&self.pox)?;
+        check_lock_period(self.lock_period)?;
+        self.pox_address.check_allowed(PoxContractVersion::Pox4)?;
+        let args = vec![
+            ClarityValue::Principal(self.stacker),
+            ClarityValue::UInt(self.amount as u128),
+            self.pox_address.to_clarity_value(),
+            ClarityValue::UInt(self.start_burn_height as u128),
+            ClarityValue::UInt(self.lock_period as u128),
+        ];
+        Ok(ContractCallBuilder::new(contract, "delegate-stack-stx", args))
+    }
+}
+
+/// Builds the PoX-4 `stack-aggregation-commit-indexed` call with which a pool operator commits
+/// the STX delegated to `pox_address` for `reward_cycle`, once they pass the stacking minimum.
+///
+/// The call returns the index of the address in the reward set of the cycle, which
+/// [`reward_cycle_index`] reads from the result and [`StackAggregationIncreaseBuilder`] takes
+/// to add the amounts locked afterwards.
+///
+/// Usage:
+/// ```rust,no_run
+/// # #[cfg(feature = "blocking")]
+/// # {
+/// use secp256k1::SecretKey;
+/// use stacks_rs::client::api::StacksApiClient;
+/// use stacks_rs::client::confirmation::ConfirmationPolicy;
+/// use stacks_rs::client::rpc::StacksRpcClient;
+/// use stacks_rs::crypto::context::secp256k1_context;
+/// use stacks_rs::network::{NetworkKind, StacksNetwork};
+/// use stacks_rs::stacking::builder::{reward_cycle_index, StackAggregationCommitBuilder};
+/// use stacks_rs::stacking::pox_address::PoxAddress;
+/// use stacks_rs::transaction::signer::TransactionSigner;
+/// let client = StacksRpcClient::new(StacksNetwork::mainnet());
+/// let pox = client.get_pox_info().unwrap();
+/// let pox_address = PoxAddress::from_address("bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4", &NetworkKind::Mainnet).unwrap();
+/// let signer_key = SecretKey::from_byte_array(&[2u8; 32]).unwrap();
+/// let operator = SecretKey::from_byte_array(&[3u8; 32]).unwrap();
+/// let builder = StackAggregationCommitBuilder::new(&pox, pox_address, pox.next_cycle.id, signer_key.public_key(secp256k1_context())).auth_id(1);
+/// let signature = builder.signer_key_message().sign(client.network().chain_id, &signer_key);
+/// let unsigned = builder.signer_signature(signature).contract_call().unwrap().nonce(7).build(&operator.public_key(secp256k1_context())).unwrap();
+/// let mut signer = TransactionSigner::new(unsigned).unwrap();
+/// signer.sign_with(&operator).unwrap();
+/// let transaction = signer.finish().unwrap();
+/// let txid = client.broadcast_transaction(&transaction).unwrap();
+/// let outcome = StacksApiClient::new(StacksNetwork::mainnet()).wait_for_confirmation(&txid, &ConfirmationPolicy::default()).unwrap();
+/// let index = reward_cycle_index(outcome.result()).unwrap();
+/// # }
+/// ```
+#[derive(Clone, Debug)]
+pub struct StackAggregationCommitBuilder {
+    pox: PoxInfo,
+    pox_address: PoxAddress,
+    reward_cycle: u64,
+    signer_key: PublicKey,
+    signer_signature: Option<RecoverableSignature>,
+    max_amount: u64,
+    auth_id: u128,
+}
+
+impl StackAggregationCommitBuilder {
+    pub fn new(pox: &PoxInfo, pox_address: PoxAddress, reward_cycle: u64, signer_key: PublicKey) -> Self {
+        StackAggregationCommitBuilder { pox: pox.clone(), pox_address, reward_cycle, signer_key, signer_signature: None, max_amount: u64::MAX, auth_id: 0 }
+    }
+
+    pub fn signer_signature(mut self, signature: RecoverableSignature) -> Self {
+        self.signer_signature = Some(signature);
+        self
+    }
+
+    /// Most micro-STX the signer authorizes, any amount by default.
+    pub fn max_amount(mut self, max_amount: u64) -> Self {
+        self.max_amount = max_amount;
+        self
+    }
+
+    /// 0 by default; a signature can only be used once with a given auth id.
+    pub fn auth_id(mut self, auth_id: u128) -> Self {
+        self.auth_id = auth_id;
+        self
+    }
+
+    /// The message the signer signs to authorize this call.
+    pub fn signer_key_message(&self) -> SignerKeyMessage {
+        SignerKeyMessage {
+            pox_address: self.pox_address.clone(),
+            reward_cycle: self.reward_cycle,
+            topic: SignerTopic::AggregationCommit,
+            period: 1,
+            max_amount: self.max_amount,
+            auth_id: self.auth_id,
+        }
+    }
+
+    /// The contract call, to set the nonce and fee of and build.
+    pub fn contract_call(self) -> Result<ContractCallBuilder, StackingError> {
+        let contract = pox4_contract(&self.pox)?;
+        self.pox_address.check_allowed(PoxContractVersion::Pox4)?;
+        let mut args = vec![self.pox_address.to_clarity_value(), ClarityValue::UInt(self.reward_cycle as u128)];
+        args.extend(signer_args(self.signer_signature.as_ref(), &self.signer_key, self.max_amount, self.auth_id));
+        Ok(ContractCallBuilder::new(contract, "stack-aggregation-commit-indexed", args))
+    }
+}
+
+/// Builds the PoX-4 `stack-aggregation-increase` call adding the STX delegated to `pox_address`
+/// since its commit for `reward_cycle`, at the `reward_cycle_index` the commit returned.
+#[derive(Clone, Debug)]
+pub struct StackAggregationIncreaseBuilder {
+    pox: PoxInfo,
+    pox_address: PoxAddress,
+    reward_cycle: u64,
+    reward_cycle_index: u128,
+    signer_key: PublicKey,
+    signer_signature: Option<RecoverableSignature>,
+    max_amount: u64,
+    auth_id: u128,
+}
+
+impl StackAggregationIncreaseBuilder {
+    pub fn new(pox: &PoxInfo, pox_address: PoxAddress, reward_cycle: u64, reward_cycle_index: u128, signer_key: PublicKey) -> Self {
+        StackAggregationIncreaseBuilder {
+            pox: pox.clone(),
+            pox_address,
+            reward_cycle,
+            reward_cycle_index,
+            signer_key,
+            signer_signature: None,
+            max_amount: u64::MAX,
+            auth_id: 0,
+        }
+    }
+
+    pub fn signer_signature(mut self, signature: RecoverableSignature) -> Self {
+        self.signer_signature = Some(signature);
+        self
+    }
+
+    /// Most micro-STX the signer authorizes, any amount by default.
+    pub fn max_amount(mut self, max_amount: u64) -> Self {
+        self.max_amount = max_amount;
+        self
+    }
+
+    /// 0 by default; a signature can only be used once with a given auth id.
+    pub fn auth_id(mut self, auth_id: u128) -> Self {
+        self.auth_id = auth_id;
+        self
+    }
+
+    /// The message the signer signs to authorize this call.
+    pub fn signer_key_message(&self) -> SignerKeyMessage {
+        SignerKeyMessage {
+            pox_address: self.pox_address.clone(),
+            reward_cycle: self.reward_cycle,
+            topic: SignerTopic::AggregationIncrease,
+            period: 1,
+            max_amount: self.max_amount,
+            auth_id: self.auth_id,
+        }
+    }
+
+    /// The contract call, to set the nonce and fee of and build.
+    pub fn contract_call(self) -> Result<ContractCallBuilder, StackingError> {
+        let contract = pox4_contract(&self.pox)?;
+        self.pox_address.check_allowed(PoxContractVersion::Pox4)?;
+        let mut args = vec![
+            self.pox_address.to_clarity_value(),
+            ClarityValue::UInt(self.reward_cycle as u128),
+            ClarityValue::UInt(self.reward_cycle_index),
+        ];
+        args.extend(signer_args(self.signer_signature.as_ref(), &self.signer_key, self.max_amount, self.auth_id));
+        Ok(ContractCallBuilder::new(contract, "stack-aggregation-increase", args))
+    }
+}
+
+/// The reward cycle index returned by `stack-aggregation-commit-indexed`, from its `(ok uN)`
+/// result; an `(err code)` is the error of the contract.
+pub fn reward_cycle_index(result: &ClarityValue) -> Result<u128, StackingError> {
+    match result {
+        ClarityValue::ResponseOk(index) => match **index {
+            ClarityValue::UInt(index) => Ok(index),
+            _ => Err(StackingError::UnexpectedResult(format!("{result:?}"))),
+        },
+        ClarityValue::ResponseErr(code) => match **code {
+            ClarityValue::Int(code) => Err(StackingError::ContractError(code)),
+            _ => Err(StackingError::UnexpectedResult(format!("{result:?}"))),
+        },
+        _ => Err(StackingError::UnexpectedResult(format!("{result:?}"))),
+    }
+}
+
+/// The reward cycle indexes of a pool's commits, by reward cycle, to send its increases to.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct RewardCycleIndexes {
+    indexes: BTreeMap<u64, u128>,
+}
+
+impl RewardCycleIndexes {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Records the index returned by the commit for `reward_cycle`, see [`reward_cycle_index`].
+    pub fn record(&mut self, reward_cycle: u64, result: &ClarityValue) -> Result<u128, StackingError> {
+        let index = reward_cycle_index(result)?;
+        self.indexes.insert(reward_cycle, index);
+        Ok(index)
+    }
+
+    pub fn get(&self, reward_cycle: u64) -> Option<u128> {
+        self.indexes.get(&reward_cycle).copied()
+    }
+
+    /// The reward cycles committed from `reward_cycle` on, to increase when more is delegated.
+    pub fn committed_from(&self, reward_cycle: u64) -> impl Iterator<Item = (u64, u128)> + '_ {
+        self.indexes.range(reward_cycle..).map(|(cycle, index)| (*cycle, *index))
+    }
+
+    /// The increase of the commit for `reward_cycle`, `None` if it was never committed.
+    pub fn increase(&self, pox: &PoxInfo, pox_address: PoxAddress, reward_cycle: u64, signer_key: PublicKey) -> Option<StackAggregationIncreaseBuilder> {
+        Some(StackAggregationIncreaseBuilder::new(pox, pox_address, reward_cycle, self.get(reward_cycle)?, signer_key))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use secp256k1::SecretKey;
+
+    use crate::crypto::context::secp256k1_context;
+    use crate::network::NetworkKind;
+    use crate::transaction::payload::Payload;
+
+    use super::*;
+
+    fn pox_info(contract_id: &str) -> PoxInfo {
+        let json = format!(
+            r#"{{"contract_id": "{contract_id}", "first_burnchain_block_height": 666050, "current_burnchain_block_height": 867000,
+            "min_amount_ustx": 170000000000, "reward_cycle_id": 95, "reward_cycle_length": 2100, "prepare_cycle_length": 100, "reward_slots": 4000,
+            "total_liquid_supply_ustx": 1492418123510300, "current_cycle": {{"id": 95, "min_threshold_ustx": 160000000000, "stacked_ustx": 0, "is_pox_active": true}},
+            "next_cycle": {{"id": 96, "min_threshold_ustx": 170000000000, "stacked_ustx": 0, "prepare_phase_start_block_height": 867850,
+            "blocks_until_prepare_phase": 850, "reward_phase_start_block_height": 867950, "blocks_until_reward_phase": 950}}, "next_reward_cycle_in": 950}}"#
+        );
+        serde_json::from_str(&json).unwrap()
+    }
+
+    fn pox_address() -> PoxAddress {
+        PoxAddress::from_address("bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4", &NetworkKind::Mainnet).unwrap()
+    }
+
+    fn signer_key() -> SecretKey {
+        SecretKey::from_byte_array(&[2u8; 32]).unwrap()
+    }
+
+    #[test]
+    fn test_stack_stx() {
+        let pox = pox_info("SP000000000000000000002Q6VF78.pox-4");
+        let signer = signer_key().public_key(secp256k1_context());
+        let builder = StackStxBuilder::new(&pox, 200_000_000_000, pox_address(), signer).lock_period(6).max_amount(300_000_000_000).auth_id(9);
+        let message = builder.signer_key_message();
+        assert_eq!((message.reward_cycle, message.period, message.max_amount), (95, 6, 300_000_000_000));
+        let signature = message.sign(0x00000001, &signer_key());
+
+        let stacker = SecretKey::from_byte_array(&[1u8; 32]).unwrap().public_key(secp256k1_context());
+        let transaction = builder.signer_signature(signature).contract_call().unwrap().nonce(3).fee(1000).build(&stacker).unwrap();
+        let Payload::ContractCall(call) = &transaction.payload else { panic!("not a contract call") };
+        assert_eq!((call.contract_name.as_str(), call.function_name.as_str()), ("pox-4", "stack-stx"));
+        assert_eq!(call.function_args.len(), 8);
+        assert_eq!(call.function_args[2], ClarityValue::UInt(867000));
+        assert_eq!(call.function_args[4], ClarityValue::OptionalSome(Box::new(ClarityValue::Buffer(signature.to_rsv().to_vec()))));
+        assert_eq!(call.function_args[7], ClarityValue::UInt(9));
+    }
+
+    #[test]
+    fn test_pool_operator_flow() {
+        let pox = pox_info("SP000000000000000000002Q6VF78.pox-4");
+        let signer = signer_key().public_key(secp256k1_context());
+        let operator = SecretKey::from_byte_array(&[3u8; 32]).unwrap().public_key(secp256k1_context());
+        let stacker = Principal::from_str("SP2J6ZY48GV1EZ5V2V5RB9MP66SW86PYKKNRV9EJ7").unwrap();
+        let function = |builder: ContractCallBuilder| {
+            let transaction = builder.fee(1000).build(&operator).unwrap();
+            let Payload::ContractCall(call) = transaction.payload else { panic!("not a contract call") };
+            (call.function_name, call.function_args)
+        };
+
+        let (name, args) = function(DelegateStackStxBuilder::new(&pox, stacker.clone(), 100_000_000_000, pox_address()).lock_period(2).contract_call().unwrap());
+        assert_eq!((name.as_str(), &args[0], &args[4]), ("delegate-stack-stx", &ClarityValue::Principal(stacker), &ClarityValue::UInt(2)));
+
+        let commit = StackAggregationCommitBuilder::new(&pox, pox_address(), 96, signer).max_amount(500_000_000_000).auth_id(2);
+        let message = commit.signer_key_message();
+        assert_eq!((message.topic, message.reward_cycle, message.period), (SignerTopic::AggregationCommit, 96, 1));
+        let signature = message.sign(0x00000001, &signer_key());
+        let (name, args) = function(commit.signer_signature(signature).contract_call().unwrap());
+        assert_eq!((name.as_str(), args.len(), &args[1]), ("stack-aggregation-commit-indexed", 6, &ClarityValue::UInt(96)));
+
+        let mut indexes = RewardCycleIndexes::new();
+        assert_eq!(indexes.record(96, &ClarityValue::ResponseOk(Box::new(ClarityValue::UInt(12)))), Ok(12));
+        assert_eq!(indexes.record(97, &ClarityValue::ResponseErr(Box::new(ClarityValue::Int(4)))), Err(StackingError::ContractError(4)));
+        assert_eq!(indexes.committed_from(95).collect::<Vec<_>>(), vec![(96, 12)]);
+        assert!(indexes.increase(&pox, pox_address(), 97, signer).is_none());
+
+        let increase = indexes.increase(&pox, pox_address(), 96, signer).unwrap();
+        assert_eq!(increase.signer_key_message().topic, SignerTopic::AggregationIncrease);
+        let (name, args) = function(increase.contract_call().unwrap());
+        assert_eq!((name.as_str(), &args[2], &args[3]), ("stack-aggregation-increase", &ClarityValue::UInt(12), &ClarityValue::OptionalNone));
+        assert!(matches!(reward_cycle_index(&ClarityValue::Bool(true)), Err(StackingError::UnexpectedResult(_))));
+    }
+
+    #[test]
+    fn test_from_config() {
+        use crate::stacking::config::AmountStrategy;
+
+        let pox = pox_info("SP000000000000000000002Q6VF78.pox-4");
+        let signer = signer_key().public_key(secp256k1_context());
+        let stacker = SecretKey::from_byte_array(&[1u8; 32]).unwrap().public_key(secp256k1_context());
+        let solo = StackingConfig {
+            network: NetworkKind::Mainnet,
+            mode: StackingMode::Solo { signer_key_path: String::from("m/44'/5757'/0'/0/0") },
+            cycles: 6,
+            amount: AmountStrategy::AllExcept { reserve_ustx: 1_000_000 },
+            reward_address: Some(String::from("bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4")),
+        };
+        let builder = StackStxBuilder::from_config(&solo, &pox, 200_001_000_000, signer).unwrap();
+        assert_eq!(builder.signer_key_message().period, 6);
+        let transaction = builder.contract_call().unwrap().fee(1000).build(&stacker).unwrap();
+        let Payload::ContractCall(call) = &transaction.payload else { panic!("not a contract call") };
+        assert_eq!(call.function_args[..2], [ClarityValue::UInt(200_000_000_000), pox_address().to_clarity_value()]);
+        assert!(matches!(DelegateStxBuilder::from_config(&solo, &pox, 1000), Err(StackingError::InvalidConfig(_))));
+
+        let pool = StackingConfig {
+            mode: StackingMode::Pool { pool_address: String::from("SP3FGQ8Z7JY9BWYZ5WM53E0M9NK7WHJF0691NZ159.pool"), until_burn_height: Some(870000) },
+            amount: AmountStrategy::Percentage { percent: 50 },
+            reward_address: None,
+            ..solo.clone()
+        };
+        let transaction = DelegateStxBuilder::from_config(&pool, &pox, 1000).unwrap().contract_call().unwrap().fee(1000).build(&stacker).unwrap();
+        let Payload::ContractCall(call) = &transaction.payload else { panic!("not a contract call") };
+        assert_eq!(call.function_name.as_str(), "delegate-stx");
+        assert_eq!(call.function_args[0], ClarityValue::UInt(500));
+        assert_eq!(call.function_args[2..], [ClarityValue::OptionalSome(Box::new(ClarityValue::UInt(870000))), ClarityValue::OptionalNone]);
+        assert!(matches!(StackStxBuilder::from_config(&pool, &pox, 1000, signer), Err(StackingError::InvalidConfig(_))));
+
+        let invalid = StackingConfig { cycles: 13, ..solo };
+        assert!(matches!(StackStxBuilder::from_config(&invalid, &pox, 1000, signer), Err(StackingError::InvalidConfig(_))));
+    }
+
+    #[test]
+    fn test_stack_stx_checks() {
+        let pox = pox_info("SP000000000000000000002Q6VF78.pox-4");
+        let signer = signer_key().public_key(secp256k1_context());
+        let builder = |amount| StackStxBuilder::new(&pox, amount, pox_address(), signer);
+        assert_eq!(builder(200_000_000_000).lock_period(13).contract_call().err(), Some(StackingError::InvalidLockPeriod(13)));
+        assert_eq!(builder(1000).contract_call().err(), Some(StackingError::AmountTooLow { amount: 1000, minimum: 170_000_000_000 }));
+        assert!(matches!(builder(200_000_000_000).max_amount(180_000_000_000).contract_call(), Err(StackingError::AmountAboveMaximum { .. })));
+        let pox3 = StackStxBuilder::new(&pox_info("SP000000000000000000002Q6VF78.pox-3"), 200_000_000_000, pox_address(), signer);
+        assert!(matches!(pox3.contract_call(), Err(StackingError::UnsupportedContract(_))));
+    }
+}
@@ -0,0 +1,248 @@
+use std::{fmt, fs, path::Path, str::FromStr};
+
+use serde::{Deserialize, Serialize};
+use crate::address::c32::c32_address_decode;
+
+use crate::bip32::derivation_path::DerivationPath;
+use crate::network::NetworkKind;
+
+/// Maximum number of reward cycles a single `stack-stx` call can lock for.
+pub const MAX_LOCK_CYCLES: u8 = 12;
+
+#[derive(Debug)]
+pub enum ConfigError {
+    Io(std::io::Error),
+    Toml(toml::de::Error),
+    Json(serde_json::Error),
+    /// The file extension is neither `.toml` nor `.json`
+    UnsupportedFormat(String),
+    InvalidCycles(u8),
+    InvalidPercentage(u8),
+    InvalidSignerKeyPath(String),
+    InvalidPoolAddress(String),
+    MissingRewardAddress,
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        match self {
+            ConfigError::Io(error) => f.write_str(&format!("{error}")),
+            ConfigError::Toml(error) => f.write_str(&format!("Invalid TOML config: {error}")),
+            ConfigError::Json(error) => f.write_str(&format!("Invalid JSON config: {error}")),
+            ConfigError::UnsupportedFormat(v) => f.write_str(&format!("Unsupported config format {v}")),
+            ConfigError::InvalidCycles(v) => f.write_str(&format!("Invalid number of cycles {v}, must be between 1 and {MAX_LOCK_CYCLES}")),
+            ConfigError::InvalidPercentage(v) => f.write_str(&format!("Invalid percentage {v}")),
+            ConfigError::InvalidSignerKeyPath(v) => f.write_str(&format!("Invalid signer key path {v}")),
+            ConfigError::InvalidPoolAddress(v) => f.write_str(&format!("Invalid pool address {v}")),
+            ConfigError::MissingRewardAddress => f.write_str("Solo stacking requires a reward address"),
+        }
+    }
+}
+
+impl std::error::Error for ConfigError {}
+
+/// How the stacker takes part in PoX.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "kebab-case", rename_all_fields = "kebab-case")]
+pub enum StackingMode {
+    /// Stack directly with `stack-stx`, signing with the key at `signer_key_path`
+    Solo { signer_key_path: String },
+    /// Delegate to a pool operator with `delegate-stx`
+    Pool {
+        /// Stacks principal of the pool operator
+        pool_address: String,
+        /// Burn block height at which the delegation expires, if any
+        until_burn_height: Option<u64>,
+    },
+}
+
+/// How much STX to lock, given the unlocked balance of the account.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "strategy", rename_all = "kebab-case", rename_all_fields = "kebab-case")]
+pub enum AmountStrategy {
+    /// A fixed amount of micro-STX
+    Fixed { ustx: u64 },
+    /// A percentage (0-100) of the balance
+    Percentage { percent: u8 },
+    /// The whole balance except `reserve_ustx`, e.g. kept to pay fees
+    AllExcept { reserve_ustx: u64 },
+}
+
+impl AmountStrategy {
+    /// Amount of micro-STX to lock out of `balance`, never more than `balance`.
+    pub fn resolve(&self, balance: u64) -> u64 {
+        match *self {
+            AmountStrategy::Fixed { ustx } => ustx.min(balance),
+            AmountStrategy::Percentage { percent } => (balance as u128 * percent as u128 / 100) as u64,
+            AmountStrategy::AllExcept { reserve_ustx } => balance.saturating_sub(reserve_ustx),
+        }
+    }
+}
+
+/// Declarative description of a stacking operation, loadable from TOML or JSON.
+///
+/// The reward address is the Bitcoin address PoX rewards are paid to; it is kept as
+/// written and only parsed when the operation is built, by [`StackStxBuilder::from_config`] for
+/// solo stacking or [`DelegateStxBuilder::from_config`] for pools.
+///
+/// [`StackStxBuilder::from_config`]: super::builder::StackStxBuilder::from_config
+/// [`DelegateStxBuilder::from_config`]: super::builder::DelegateStxBuilder::from_config
+///
+/// Usage:
+/// ```rust
+/// use stacks_rs::stacking::config::{AmountStrategy, StackingConfig};
+/// let config = StackingConfig::from_toml_str(r#"
+///     network = "mainnet"
+///     cycles = 6
+///     reward-address = "1BoatSLRHtKNngkdXEeobR76b53LETtpyT"
+///
+///     [mode]
+///     type = "solo"
+///     signer-key-path = "m/44'/5757'/0'/0/0"
+///
+///     [amount]
+///     strategy = "all-except"
+///     reserve-ustx = 1000000
+/// "#).unwrap();
+/// assert_eq!(config.amount, AmountStrategy::AllExcept { reserve_ustx: 1_000_000 });
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct StackingConfig {
+    pub network: NetworkKind,
+    pub mode: StackingMode,
+    /// Number of reward cycles to lock for
+    pub cycles: u8,
+    pub amount: AmountStrategy,
+    /// Required for solo stacking, optional for pools (pool operators usually set their own)
+    pub reward_address: Option<String>,
+}
+
+impl StackingConfig {
+    pub fn from_toml_str(config: &str) -> Result<Self, ConfigError> {
+        let config: Self = toml::from_str(config).map_err(ConfigError::Toml)?;
+        config.validate()?;
+        Ok(config)
+    }
+
+    pub fn from_json_str(config: &str) -> Result<Self, ConfigError> {
+        let config: Self = serde_json::from_str(config).map_err(ConfigError::Json)?;
+        config.validate()?;
+        Ok(config)
+    }
+
+    /// Loads a `.toml` or `.json` config file.
+    pub fn from_file(path: &Path) -> Result<Self, ConfigError> {
+        let config = fs::read_to_string(path).map_err(ConfigError::Io)?;
+        match path.extension().and_then(|extension| extension.to_str()) {
+            Some("toml") => Self::from_toml_str(&config),
+            Some("json") => Self::from_json_str(&config),
+            _ => Err(ConfigError::UnsupportedFormat(path.display().to_string())),
+        }
+    }
+
+    pub fn to_toml_string(&self) -> Result<String, toml::ser::Error> {
+        toml::to_string(self)
+    }
+
+    pub fn to_json_string(&self) -> Result<String, serde_json::Error> {
+        serde_json::to_string_pretty(self)
+    }
+
+    /// Checks the values serde cannot check by itself.
+    pub fn validate(&self) -> Result<(), ConfigError> {
+        if self.cycles == 0 || self.cycles > MAX_LOCK_CYCLES {
+            return Err(ConfigError::InvalidCycles(self.cycles));
+        }
+        if let AmountStrategy::Percentage { percent } = self.amount {
+            if percent > 100 {
+                return Err(ConfigError::InvalidPercentage(percent));
+            }
+        }
+        match &self.mode {
+            StackingMode::Solo { signer_key_path } => {
+                DerivationPath::from_str(signer_key_path)
+                    .map_err(|_| ConfigError::InvalidSignerKeyPath(signer_key_path.clone()))?;
+                if self.reward_address.is_none() {
+                    return Err(ConfigError::MissingRewardAddress);
+                }
+            }
+            StackingMode::Pool { pool_address, .. } => {
+                // the pool may be a contract principal, only its address is checked
+                let address = pool_address.split('.').next().unwrap_or_default();
+                c32_address_decode(address).map_err(|_| ConfigError::InvalidPoolAddress(pool_address.clone()))?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Derivation path of the signer key, for solo stacking.
+    pub fn signer_key_path(&self) -> Option<DerivationPath> {
+        match &self.mode {
+            StackingMode::Solo { signer_key_path } => DerivationPath::from_str(signer_key_path).ok(),
+            StackingMode::Pool { .. } => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const POOL_JSON: &str = r#"{
+        "network": "testnet",
+        "mode": { "type": "pool", "pool-address": "SP3FGQ8Z7JY9BWYZ5WM53E0M9NK7WHJF0691NZ159.pool", "until-burn-height": null },
+        "cycles": 12,
+        "amount": { "strategy": "percentage", "percent": 50 },
+        "reward-address": null
+    }"#;
+
+    #[test]
+    fn test_pool_config_from_json() {
+        let config = StackingConfig::from_json_str(POOL_JSON).unwrap();
+        assert_eq!(config.network, NetworkKind::Testnet);
+        assert_eq!(config.amount.resolve(1_001), 500);
+        assert!(config.signer_key_path().is_none());
+    }
+
+    #[test]
+    fn test_config_roundtrip() {
+        let config = StackingConfig::from_json_str(POOL_JSON).unwrap();
+        assert_eq!(StackingConfig::from_toml_str(&config.to_toml_string().unwrap()).unwrap(), config);
+        assert_eq!(StackingConfig::from_json_str(&config.to_json_string().unwrap()).unwrap(), config);
+    }
+
+    #[test]
+    fn test_invalid_config() {
+        assert!(matches!(
+            StackingConfig::from_json_str(&POOL_JSON.replace("12", "13")),
+            Err(ConfigError::InvalidCycles(13))
+        ));
+        assert!(matches!(
+            StackingConfig::from_json_str(&POOL_JSON.replace("\"percent\": 50", "\"percent\": 101")),
+            Err(ConfigError::InvalidPercentage(101))
+        ));
+        assert!(matches!(
+            StackingConfig::from_json_str(&POOL_JSON.replace("SP3FGQ8Z7JY9BWYZ5WM53E0M9NK7WHJF0691NZ159", "invalid")),
+            Err(ConfigError::InvalidPoolAddress(_))
+        ));
+        assert!(matches!(StackingConfig::from_json_str("{}"), Err(ConfigError::Json(_))));
+
+        let solo = StackingConfig {
+            network: NetworkKind::Mainnet,
+            mode: StackingMode::Solo { signer_key_path: String::from("m/44'/5757'/0'/0/0") },
+            cycles: 1,
+            amount: AmountStrategy::Fixed { ustx: 100 },
+            reward_address: None,
+        };
+        assert!(matches!(solo.validate(), Err(ConfigError::MissingRewardAddress)));
+    }
+
+    #[test]
+    fn test_amount_strategies() {
+        assert_eq!(AmountStrategy::Fixed { ustx: 100 }.resolve(50), 50);
+        assert_eq!(AmountStrategy::AllExcept { reserve_ustx: 10 }.resolve(50), 40);
+        assert_eq!(AmountStrategy::AllExcept { reserve_ustx: 100 }.resolve(50), 0);
+        assert_eq!(AmountStrategy::Percentage { percent: 100 }.resolve(u64::MAX), u64::MAX);
+    }
+}
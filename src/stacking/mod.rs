@@ -0,0 +1,5 @@
+pub mod builder;
+pub mod config;
+pub mod pox_address;
+pub mod rewards;
+pub mod signer;
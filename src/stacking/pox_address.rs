@@ -0,0 +1,385 @@
+use std::collections::BTreeMap;
+use std::fmt;
+
+use stacks_common::address::b58;
+
+use crate::address::bech32::{decode_segwit_address, encode_segwit_address, Bech32Error};
+use crate::clarity::value::ClarityValue;
+use crate::network::NetworkKind;
+
+const CLARITY_TYPE_BUFFER: u8 = 0x02;
+const CLARITY_TYPE_TUPLE: u8 = 0x0c;
+
+const BTC_MAINNET_P2PKH: u8 = 0x00;
+const BTC_MAINNET_P2SH: u8 = 0x05;
+const BTC_TESTNET_P2PKH: u8 = 0x6f;
+const BTC_TESTNET_P2SH: u8 = 0xc4;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum PoxAddressError {
+    InvalidAddress(String),
+    /// A segwit address with an unexpected network prefix or program
+    InvalidSegwitAddress(Bech32Error),
+    /// The address version byte belongs to another network
+    WrongNetwork(String),
+    UnsupportedVersion(u8),
+    /// The version is not accepted by the given PoX contract
+    VersionNotAllowed(u8, PoxContractVersion),
+    InvalidHashLength(usize),
+    /// The bytes are not a serialized `{ hashbytes, version }` tuple
+    InvalidTuple,
+}
+
+impl fmt::Display for PoxAddressError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        match self {
+            PoxAddressError::InvalidAddress(v) => f.write_str(&format!("Invalid Bitcoin address {v}")),
+            PoxAddressError::InvalidSegwitAddress(error) => f.write_str(&format!("Invalid segwit address: {error}")),
+            PoxAddressError::WrongNetwork(v) => f.write_str(&format!("Address {v} belongs to another network")),
+            PoxAddressError::UnsupportedVersion(v) => f.write_str(&format!("Unsupported PoX address version {v}")),
+            PoxAddressError::VersionNotAllowed(v, pox) => f.write_str(&format!("PoX address version {v} is not allowed by {pox}")),
+            PoxAddressError::InvalidHashLength(v) => f.write_str(&format!("Invalid PoX address hash length {v}")),
+            PoxAddressError::InvalidTuple => f.write_str("Invalid PoX address tuple"),
+        }
+    }
+}
+
+impl std::error::Error for PoxAddressError {}
+
+/// Version of the PoX contract a reward address is submitted to.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum PoxContractVersion {
+    Pox,
+    Pox2,
+    Pox3,
+    Pox4,
+}
+
+impl PoxContractVersion {
+    /// Highest address version accepted: `pox` only knows the hash-based (20 bytes) types,
+    /// segwit and taproot outputs were added with `pox-2`.
+    pub fn max_address_version(&self) -> u8 {
+        match self {
+            PoxContractVersion::Pox => PoxAddressVersion::P2shP2wsh.value(),
+            _ => PoxAddressVersion::P2tr.value(),
+        }
+    }
+}
+
+impl fmt::Display for PoxContractVersion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        match self {
+            PoxContractVersion::Pox => f.write_str("pox"),
+            PoxContractVersion::Pox2 => f.write_str("pox-2"),
+            PoxContractVersion::Pox3 => f.write_str("pox-3"),
+            PoxContractVersion::Pox4 => f.write_str("pox-4"),
+        }
+    }
+}
+
+/// The `version` field of a PoX address tuple.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PoxAddressVersion {
+    P2pkh,
+    P2sh,
+    P2shP2wpkh,
+    P2shP2wsh,
+    P2wpkh,
+    P2wsh,
+    P2tr,
+}
+
+impl PoxAddressVersion {
+    pub fn value(&self) -> u8 {
+        match *self {
+            PoxAddressVersion::P2pkh => 0x00,
+            PoxAddressVersion::P2sh => 0x01,
+            PoxAddressVersion::P2shP2wpkh => 0x02,
+            PoxAddressVersion::P2shP2wsh => 0x03,
+            PoxAddressVersion::P2wpkh => 0x04,
+            PoxAddressVersion::P2wsh => 0x05,
+            PoxAddressVersion::P2tr => 0x06,
+        }
+    }
+
+    pub fn from_value(value: u8) -> Option<Self> {
+        match value {
+            0x00 => Some(PoxAddressVersion::P2pkh),
+            0x01 => Some(PoxAddressVersion::P2sh),
+            0x02 => Some(PoxAddressVersion::P2shP2wpkh),
+            0x03 => Some(PoxAddressVersion::P2shP2wsh),
+            0x04 => Some(PoxAddressVersion::P2wpkh),
+            0x05 => Some(PoxAddressVersion::P2wsh),
+            0x06 => Some(PoxAddressVersion::P2tr),
+            _ => None,
+        }
+    }
+
+    /// Length of `hashbytes`: a hash160 for the base58 types and P2WPKH, 32 bytes otherwise.
+    pub fn hash_length(&self) -> usize {
+        match self {
+            PoxAddressVersion::P2wsh | PoxAddressVersion::P2tr => 32,
+            _ => 20,
+        }
+    }
+}
+
+/// A Bitcoin reward address as the PoX contracts take it: `{ version: (buff 1), hashbytes: (buff 32) }`.
+///
+/// Base58 P2SH addresses are parsed as [`PoxAddressVersion::P2sh`]: the wrapped segwit
+/// versions cannot be told apart from the address alone, and all three render the same.
+///
+/// Usage:
+/// ```rust
+/// use stacks_rs::network::NetworkKind;
+/// use stacks_rs::stacking::pox_address::{PoxAddress, PoxAddressVersion};
+/// let address = PoxAddress::from_address("1BoatSLRHtKNngkdXEeobR76b53LETtpyT", &NetworkKind::Mainnet).unwrap();
+/// assert_eq!(address.version(), PoxAddressVersion::P2pkh);
+/// assert_eq!(PoxAddress::from_clarity_bytes(&address.to_clarity_bytes()).unwrap(), address);
+/// assert_eq!(address.to_address(&NetworkKind::Mainnet), "1BoatSLRHtKNngkdXEeobR76b53LETtpyT");
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PoxAddress {
+    version: PoxAddressVersion,
+    hashbytes: Vec<u8>,
+}
+
+impl PoxAddress {
+    pub fn new(version: PoxAddressVersion, hashbytes: &[u8]) -> Result<Self, PoxAddressError> {
+        if hashbytes.len() != version.hash_length() {
+            return Err(PoxAddressError::InvalidHashLength(hashbytes.len()));
+        }
+        Ok(Self { version, hashbytes: hashbytes.to_vec() })
+    }
+
+    /// Parses a base58 or segwit address of `network`.
+    pub fn from_address(address: &str, network: &NetworkKind) -> Result<Self, PoxAddressError> {
+        let hrp = segwit_hrp(network);
+        if address.to_lowercase().starts_with(&format!("{hrp}1")) {
+            let (witness_version, program) =
+                decode_segwit_address(hrp, address).map_err(PoxAddressError::InvalidSegwitAddress)?;
+            let version = match (witness_version, program.len()) {
+                (0, 20) => PoxAddressVersion::P2wpkh,
+                (0, 32) => PoxAddressVersion::P2wsh,
+                (1, 32) => PoxAddressVersion::P2tr,
+                _ => return Err(PoxAddressError::InvalidAddress(address.to_string())),
+            };
+            return Self::new(version, &program);
+        }
+
+        let bytes = b58::from_check(address).map_err(|_| PoxAddressError::InvalidAddress(address.to_string()))?;
+        let (btc_version, hash) = bytes.split_first().ok_or(PoxAddressError::InvalidAddress(address.to_string()))?;
+        let (p2pkh, p2sh) = base58_versions(network);
+        let version = match *btc_version {
+            v if v == p2pkh => PoxAddressVersion::P2pkh,
+            v if v == p2sh => PoxAddressVersion::P2sh,
+            BTC_MAINNET_P2PKH | BTC_MAINNET_P2SH | BTC_TESTNET_P2PKH | BTC_TESTNET_P2SH => {
+                return Err(PoxAddressError::WrongNetwork(address.to_string()))
+            }
+            _ => return Err(PoxAddressError::InvalidAddress(address.to_string())),
+        };
+        Self::new(version, hash)
+    }
+
+    /// Bitcoin address of `network` paying to this output.
+    pub fn to_address(&self, network: &NetworkKind) -> String {
+        let (p2pkh, p2sh) = base58_versions(network);
+        match self.version {
+            PoxAddressVersion::P2pkh => b58::check_encode_slice(&[&[p2pkh], self.hashbytes.as_slice()].concat()),
+            PoxAddressVersion::P2sh | PoxAddressVersion::P2shP2wpkh | PoxAddressVersion::P2shP2wsh => {
+                b58::check_encode_slice(&[&[p2sh], self.hashbytes.as_slice()].concat())
+            }
+            // lengths are checked on construction, so encoding cannot fail
+            PoxAddressVersion::P2wpkh | PoxAddressVersion::P2wsh => {
+                encode_segwit_address(segwit_hrp(network), 0, &self.hashbytes).unwrap()
+            }
+            PoxAddressVersion::P2tr => encode_segwit_address(segwit_hrp(network), 1, &self.hashbytes).unwrap(),
+        }
+    }
+
+    pub fn version(&self) -> PoxAddressVersion {
+        self.version
+    }
+
+    pub fn hashbytes(&self) -> &[u8] {
+        &self.hashbytes
+    }
+
+    /// Fails if `pox` does not accept this address type.
+    pub fn check_allowed(&self, pox: PoxContractVersion) -> Result<(), PoxAddressError> {
+        if self.version.value() > pox.max_address_version() {
+            return Err(PoxAddressError::VersionNotAllowed(self.version.value(), pox));
+        }
+        Ok(())
+    }
+
+    /// Consensus serialization of the `{ hashbytes, version }` tuple.
+    pub fn to_clarity_bytes(&self) -> Vec<u8> {
+        let mut bytes = vec![CLARITY_TYPE_TUPLE];
+        bytes.extend(2u32.to_be_bytes());
+        tuple_key(&mut bytes, "hashbytes");
+        buffer(&mut bytes, &self.hashbytes);
+        tuple_key(&mut bytes, "version");
+        buffer(&mut bytes, &[self.version.value()]);
+        bytes
+    }
+
+    /// The `{ hashbytes, version }` tuple, as a contract call argument.
+    pub fn to_clarity_value(&self) -> ClarityValue {
+        ClarityValue::Tuple(BTreeMap::from([
+            (String::from("hashbytes"), ClarityValue::Buffer(self.hashbytes.clone())),
+            (String::from("version"), ClarityValue::Buffer(vec![self.version.value()])),
+        ]))
+    }
+
+    /// Parses [`PoxAddress::to_clarity_bytes`], e.g. the `pox-addr` of a stacker's state.
+    pub fn from_clarity_bytes(bytes: &[u8]) -> Result<Self, PoxAddressError> {
+        let mut reader = TupleReader { bytes };
+        reader.expect(&[CLARITY_TYPE_TUPLE])?;
+        reader.expect(&2u32.to_be_bytes())?;
+        reader.expect(&[9])?;
+        reader.expect(b"hashbytes")?;
+        let hashbytes = reader.buffer()?;
+        reader.expect(&[7])?;
+        reader.expect(b"version")?;
+        let version = match reader.buffer()? {
+            [version] => *version,
+            _ => return Err(PoxAddressError::InvalidTuple),
+        };
+        if !reader.bytes.is_empty() {
+            return Err(PoxAddressError::InvalidTuple);
+        }
+        let version = PoxAddressVersion::from_value(version).ok_or(PoxAddressError::UnsupportedVersion(version))?;
+        Self::new(version, hashbytes)
+    }
+}
+
+fn segwit_hrp(network: &NetworkKind) -> &'static str {
+    match network {
+        NetworkKind::Mainnet => "bc",
+        NetworkKind::Testnet => "tb",
+        NetworkKind::Mocknet => "bcrt",
+    }
+}
+
+fn base58_versions(network: &NetworkKind) -> (u8, u8) {
+    match network {
+        NetworkKind::Mainnet => (BTC_MAINNET_P2PKH, BTC_MAINNET_P2SH),
+        NetworkKind::Testnet | NetworkKind::Mocknet => (BTC_TESTNET_P2PKH, BTC_TESTNET_P2SH),
+    }
+}
+
+fn tuple_key(bytes: &mut Vec<u8>, key: &str) {
+    bytes.push(key.len() as u8);
+    bytes.extend(key.as_bytes());
+}
+
+fn buffer(bytes: &mut Vec<u8>, value: &[u8]) {
+    bytes.push(CLARITY_TYPE_BUFFER);
+    bytes.extend((value.len() as u32).to_be_bytes());
+    bytes.extend(value);
+}
+
+struct TupleReader<'a> {
+    bytes: &'a [u8],
+}
+
+impl<'a> TupleReader<'a> {
+    fn take(&mut self, length: usize) -> Result<&'a [u8], PoxAddressError> {
+        if self.bytes.len() < length {
+            return Err(PoxAddressError::InvalidTuple);
+        }
+        let (taken, rest) = self.bytes.split_at(length);
+        self.bytes = rest;
+        Ok(taken)
+    }
+
+    fn expect(&mut self, expected: &[u8]) -> Result<(), PoxAddressError> {
+        if self.take(expected.len())? != expected {
+            return Err(PoxAddressError::InvalidTuple);
+        }
+        Ok(())
+    }
+
+    fn buffer(&mut self) -> Result<&'a [u8], PoxAddressError> {
+        self.expect(&[CLARITY_TYPE_BUFFER])?;
+        let length = u32::from_be_bytes(self.take(4)?.try_into().unwrap());
+        self.take(length as usize)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_address_roundtrip() {
+        let vectors = [
+            (NetworkKind::Mainnet, "1BoatSLRHtKNngkdXEeobR76b53LETtpyT", PoxAddressVersion::P2pkh),
+            (NetworkKind::Mainnet, "3EktnHQD7RiAE6uzMj2ZifT9YgRrkSgzQX", PoxAddressVersion::P2sh),
+            (NetworkKind::Testnet, "mvWRFPELmpCHSkFQ7o9EVdCd9eXeUTa9T8", PoxAddressVersion::P2pkh),
+            (NetworkKind::Testnet, "2N3vVYSK5XRgVSGWy21PnsRmBUywSQNdCsf", PoxAddressVersion::P2sh),
+            (NetworkKind::Mainnet, "bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4", PoxAddressVersion::P2wpkh),
+            (NetworkKind::Testnet, "tb1qrp33g0q5c5txsp9arysrx4k6zdkfs4nce4xj0gdcccefvpysxf3q0sl5k7", PoxAddressVersion::P2wsh),
+            (NetworkKind::Mainnet, "bc1p0xlxvlhemja6c4dqv22uapctqupfhlxm9h8z3k2e72q4k9hcz7vqzk5jj0", PoxAddressVersion::P2tr),
+        ];
+        for (network, address, version) in vectors {
+            let pox_address = PoxAddress::from_address(address, &network).unwrap();
+            assert_eq!(pox_address.version(), version);
+            assert_eq!(pox_address.to_address(&network), address);
+            assert_eq!(PoxAddress::from_clarity_bytes(&pox_address.to_clarity_bytes()).unwrap(), pox_address);
+        }
+
+        let wrapped = PoxAddress::new(PoxAddressVersion::P2shP2wpkh, &[0x11; 20]).unwrap();
+        assert!(wrapped.to_address(&NetworkKind::Mainnet).starts_with('3'));
+    }
+
+    #[test]
+    fn test_clarity_tuple() {
+        // `{ hashbytes: 0x751e76e8199196d454941c45d1b3a323f1433bd6, version: 0x04 }`
+        let address = PoxAddress::from_address("bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4", &NetworkKind::Mainnet).unwrap();
+        assert_eq!(
+            hex::encode(address.to_clarity_bytes()),
+            "0c000000020968617368627974657302000000147\
+             51e76e8199196d454941c45d1b3a323f1433bd60776657273696f6e020000000104"
+        );
+        assert_eq!(address.to_clarity_value().serialize().unwrap(), address.to_clarity_bytes());
+
+        let mut bytes = address.to_clarity_bytes();
+        *bytes.last_mut().unwrap() = 0x07;
+        assert_eq!(PoxAddress::from_clarity_bytes(&bytes), Err(PoxAddressError::UnsupportedVersion(7)));
+        *bytes.last_mut().unwrap() = 0x05;
+        assert_eq!(PoxAddress::from_clarity_bytes(&bytes), Err(PoxAddressError::InvalidHashLength(20)));
+        assert_eq!(PoxAddress::from_clarity_bytes(&bytes[..bytes.len() - 1]), Err(PoxAddressError::InvalidTuple));
+    }
+
+    #[test]
+    fn test_invalid_addresses() {
+        assert!(matches!(
+            PoxAddress::from_address("1BoatSLRHtKNngkdXEeobR76b53LETtpyT", &NetworkKind::Testnet),
+            Err(PoxAddressError::WrongNetwork(_))
+        ));
+        assert!(matches!(
+            PoxAddress::from_address("1BoatSLRHtKNngkdXEeobR76b53LETtpyU", &NetworkKind::Mainnet),
+            Err(PoxAddressError::InvalidAddress(_))
+        ));
+        assert!(matches!(
+            PoxAddress::from_address("bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kemeawh", &NetworkKind::Mainnet),
+            Err(PoxAddressError::InvalidSegwitAddress(Bech32Error::InvalidVariant))
+        ));
+        // a testnet segwit address is not a mainnet base58 one either
+        assert!(PoxAddress::from_address("tb1qw508d6qejxtdg4y5r3zarvary0c5xw7kxpjzsx", &NetworkKind::Mainnet).is_err());
+        assert_eq!(PoxAddress::new(PoxAddressVersion::P2tr, &[0; 20]), Err(PoxAddressError::InvalidHashLength(20)));
+    }
+
+    #[test]
+    fn test_allowed_versions() {
+        let p2sh = PoxAddress::new(PoxAddressVersion::P2shP2wsh, &[0; 20]).unwrap();
+        let p2tr = PoxAddress::new(PoxAddressVersion::P2tr, &[0; 32]).unwrap();
+        assert!(p2sh.check_allowed(PoxContractVersion::Pox).is_ok());
+        assert_eq!(p2tr.check_allowed(PoxContractVersion::Pox), Err(PoxAddressError::VersionNotAllowed(6, PoxContractVersion::Pox)));
+        for pox in [PoxContractVersion::Pox2, PoxContractVersion::Pox3, PoxContractVersion::Pox4] {
+            assert!(p2tr.check_allowed(pox).is_ok());
+        }
+    }
+}
@@ -0,0 +1,170 @@
+use std::fmt;
+
+use super::config::MAX_LOCK_CYCLES;
+
+/// The stacking threshold is rounded up to a multiple of 10,000 STX.
+pub const THRESHOLD_STEP_USTX: u64 = 10_000 * 1_000_000;
+/// The threshold is computed as if at least 1/4 of the liquid STX were stacked.
+const MIN_PARTICIPATION_DIVISOR: u64 = 4;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ProjectionError {
+    NoRewardSlots,
+    /// No BTC commitment data to extrapolate from
+    EmptyCommitHistory,
+    InvalidCycles(u8),
+}
+
+impl fmt::Display for ProjectionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        match *self {
+            ProjectionError::NoRewardSlots => f.write_str("The reward cycle has no reward slots"),
+            ProjectionError::EmptyCommitHistory => f.write_str("Empty BTC commitment history"),
+            ProjectionError::InvalidCycles(v) => f.write_str(&format!("Invalid number of cycles {v}, must be between 1 and {MAX_LOCK_CYCLES}")),
+        }
+    }
+}
+
+impl std::error::Error for ProjectionError {}
+
+/// State of the current reward cycle, as reported by the node PoX info.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CycleData {
+    /// Number of reward slots in a cycle (e.g. 4000 on mainnet)
+    pub reward_slots: u32,
+    /// Liquid (unlocked and locked) supply of micro-STX
+    pub liquid_ustx: u64,
+    /// Micro-STX stacked for the upcoming cycle
+    pub total_stacked_ustx: u64,
+    /// BTC committed by miners in each of the past cycles, in satoshis, oldest first
+    pub btc_committed_sats: Vec<u64>,
+}
+
+impl CycleData {
+    /// Stacking threshold given `participation_ustx` stacked micro-STX, as PoX computes it.
+    pub fn threshold(&self, participation_ustx: u64) -> u64 {
+        let scale_by = participation_ustx.max(self.liquid_ustx / MIN_PARTICIPATION_DIVISOR);
+        let threshold = scale_by / self.reward_slots.max(1) as u64;
+        match threshold % THRESHOLD_STEP_USTX {
+            0 => threshold,
+            remainder => threshold + THRESHOLD_STEP_USTX - remainder,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RewardProjection {
+    /// Threshold once the proposed amount is stacked
+    pub threshold_ustx: u64,
+    /// Reward slots won in each cycle
+    pub slots: u64,
+    /// Part of the amount locked without winning a slot
+    pub idle_ustx: u64,
+    pub sats_per_slot: u64,
+    pub sats_per_cycle: u64,
+    pub total_sats: u64,
+}
+
+/// Projects the PoX rewards of locking `amount_ustx` for `cycles` cycles.
+///
+/// Assumptions:
+/// - the other stackers keep `total_stacked_ustx` stacked, so participation is that plus `amount_ustx`
+///   and the threshold stays the same for every cycle;
+/// - miners commit as much BTC per cycle as the average of `btc_committed_sats`;
+/// - the commitments are evenly spread over the reward slots, and the share of unfilled slots is burnt.
+///
+/// Fees, pool operator cuts and BTC price changes are not considered.
+///
+/// Usage:
+/// ```rust
+/// use stacks_rs::stacking::rewards::{project_rewards, CycleData};
+/// let cycle = CycleData {
+///     reward_slots: 4000,
+///     liquid_ustx: 1_400_000_000_000_000,
+///     total_stacked_ustx: 400_000_000_000_000,
+///     btc_committed_sats: vec![4_000_000_000, 4_400_000_000],
+/// };
+/// let projection = project_rewards(&cycle, 250_000_000_000, 2).unwrap();
+/// assert_eq!(projection.slots, 2);
+/// ```
+pub fn project_rewards(cycle: &CycleData, amount_ustx: u64, cycles: u8) -> Result<RewardProjection, ProjectionError> {
+    if cycle.reward_slots == 0 {
+        return Err(ProjectionError::NoRewardSlots);
+    }
+    if cycle.btc_committed_sats.is_empty() {
+        return Err(ProjectionError::EmptyCommitHistory);
+    }
+    if cycles == 0 || cycles > MAX_LOCK_CYCLES {
+        return Err(ProjectionError::InvalidCycles(cycles));
+    }
+
+    let threshold_ustx = cycle.threshold(cycle.total_stacked_ustx.saturating_add(amount_ustx));
+    let slots = amount_ustx / threshold_ustx;
+    let average_committed = cycle.btc_committed_sats.iter().map(|sats| *sats as u128).sum::<u128>()
+        / cycle.btc_committed_sats.len() as u128;
+    let sats_per_slot = (average_committed / cycle.reward_slots as u128) as u64;
+    let sats_per_cycle = sats_per_slot * slots;
+
+    Ok(RewardProjection {
+        threshold_ustx,
+        slots,
+        idle_ustx: amount_ustx % threshold_ustx,
+        sats_per_slot,
+        sats_per_cycle,
+        total_sats: sats_per_cycle * cycles as u64,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cycle() -> CycleData {
+        CycleData {
+            reward_slots: 4000,
+            liquid_ustx: 1_400_000_000_000_000,
+            total_stacked_ustx: 400_000_000_000_000,
+            btc_committed_sats: vec![4_000_000_000, 4_400_000_000],
+        }
+    }
+
+    #[test]
+    fn test_threshold() {
+        // 400M STX / 4000 slots = 100k STX, already a multiple of 10k STX
+        assert_eq!(cycle().threshold(400_000_000_000_000), 100_000_000_000);
+        // rounded up to the next 10k STX
+        assert_eq!(cycle().threshold(400_004_000_000_000), 110_000_000_000);
+        // participation below 1/4 of the liquid supply
+        assert_eq!(cycle().threshold(0), 90_000_000_000);
+    }
+
+    #[test]
+    fn test_project_rewards() {
+        let projection = project_rewards(&cycle(), 250_000_000_000, 3).unwrap();
+        assert_eq!(
+            projection,
+            RewardProjection {
+                threshold_ustx: 110_000_000_000,
+                slots: 2,
+                idle_ustx: 30_000_000_000,
+                sats_per_slot: 1_050_000,
+                sats_per_cycle: 2_100_000,
+                total_sats: 6_300_000,
+            }
+        );
+
+        let below_threshold = project_rewards(&cycle(), 50_000_000_000, 1).unwrap();
+        assert_eq!(below_threshold.slots, 0);
+        assert_eq!(below_threshold.total_sats, 0);
+    }
+
+    #[test]
+    fn test_project_rewards_invalid() {
+        let mut no_history = cycle();
+        no_history.btc_committed_sats.clear();
+        assert_eq!(project_rewards(&no_history, 1, 1), Err(ProjectionError::EmptyCommitHistory));
+        assert_eq!(project_rewards(&cycle(), 1, 13), Err(ProjectionError::InvalidCycles(13)));
+        let no_slots = CycleData { reward_slots: 0, ..cycle() };
+        assert_eq!(project_rewards(&no_slots, 1, 1), Err(ProjectionError::NoRewardSlots));
+    }
+}
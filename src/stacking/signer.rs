@@ -0,0 +1,135 @@
+//! Signer key authorizations of PoX-4: the SIP-018 message a signer signs to let a stacker or
+//! pool operator use its key for a reward address, cycle and amount.
+
+use std::collections::BTreeMap;
+use std::fmt;
+
+use secp256k1::{PublicKey, SecretKey};
+
+use crate::clarity::value::ClarityValue;
+use crate::crypto::signature::recoverable::RecoverableSignature;
+use crate::crypto::signature::sip018::{sign_structured_data, verify_structured_data, StructuredDataDomain};
+use crate::crypto::signature::SignatureError;
+
+use super::pox_address::PoxAddress;
+
+/// `name` of the SIP-018 domain of signer signatures.
+pub const SIGNER_DOMAIN_NAME: &str = "pox-4-signer";
+pub const SIGNER_DOMAIN_VERSION: &str = "1.0.0";
+
+/// The PoX-4 function a signer signature authorizes.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum SignerTopic {
+    StackStx,
+    StackExtend,
+    StackIncrease,
+    AggregationCommit,
+    AggregationIncrease,
+}
+
+impl SignerTopic {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SignerTopic::StackStx => "stack-stx",
+            SignerTopic::StackExtend => "stack-extend",
+            SignerTopic::StackIncrease => "stack-increase",
+            SignerTopic::AggregationCommit => "agg-commit",
+            SignerTopic::AggregationIncrease => "agg-increase",
+        }
+    }
+}
+
+impl fmt::Display for SignerTopic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        f.write_str(self.as_str())
+    }
+}
+
+/// The message of a signer signature, checked by PoX-4 against the arguments of the call.
+///
+/// `reward_cycle` is the current cycle for `stack-stx`, `stack-extend` and `stack-increase`, and
+/// the committed one for aggregations; `period` is the lock period, 1 for aggregations.
+///
+/// Usage:
+/// ```rust
+/// use secp256k1::SecretKey;
+/// use stacks_rs::crypto::context::secp256k1_context;
+/// use stacks_rs::network::NetworkKind;
+/// use stacks_rs::stacking::pox_address::PoxAddress;
+/// use stacks_rs::stacking::signer::{SignerKeyMessage, SignerTopic};
+/// let pox_address = PoxAddress::from_address("bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4", &NetworkKind::Mainnet).unwrap();
+/// let message = SignerKeyMessage { pox_address, reward_cycle: 95, topic: SignerTopic::StackStx, period: 6, max_amount: 200_000_000_000, auth_id: 1 };
+/// let key = SecretKey::from_byte_array(&[1u8; 32]).unwrap();
+/// let signature = message.sign(0x00000001, &key);
+/// assert!(message.verify(0x00000001, &signature, &key.public_key(secp256k1_context())).is_ok());
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SignerKeyMessage {
+    pub pox_address: PoxAddress,
+    pub reward_cycle: u64,
+    pub topic: SignerTopic,
+    pub period: u8,
+    /// Most micro-STX the authorization allows to lock
+    pub max_amount: u64,
+    /// Nonce of the authorization, used once
+    pub auth_id: u128,
+}
+
+impl SignerKeyMessage {
+    /// The SIP-018 domain of signer signatures on the chain `chain_id`.
+    pub fn domain(chain_id: u32) -> StructuredDataDomain {
+        StructuredDataDomain { name: SIGNER_DOMAIN_NAME.to_string(), version: SIGNER_DOMAIN_VERSION.to_string(), chain_id }
+    }
+
+    pub fn to_clarity_value(&self) -> ClarityValue {
+        ClarityValue::Tuple(BTreeMap::from([
+            (String::from("pox-addr"), self.pox_address.to_clarity_value()),
+            (String::from("reward-cycle"), ClarityValue::UInt(self.reward_cycle as u128)),
+            (String::from("topic"), ClarityValue::StringAscii(self.topic.to_string())),
+            (String::from("period"), ClarityValue::UInt(self.period as u128)),
+            (String::from("max-amount"), ClarityValue::UInt(self.max_amount as u128)),
+            (String::from("auth-id"), ClarityValue::UInt(self.auth_id)),
+        ]))
+    }
+
+    fn to_clarity_bytes(&self) -> Vec<u8> {
+        // keys, ASCII topic and buffers are all valid
+        self.to_clarity_value().serialize().expect("valid signer message")
+    }
+
+    /// Signs the message for the chain `chain_id` with the signer's key; PoX-4 takes the
+    /// [`RecoverableSignature::to_rsv`] bytes.
+    pub fn sign(&self, chain_id: u32, key: &SecretKey) -> RecoverableSignature {
+        sign_structured_data(&self.to_clarity_bytes(), &Self::domain(chain_id), key)
+    }
+
+    pub fn verify(&self, chain_id: u32, signature: &RecoverableSignature, signer_key: &PublicKey) -> Result<(), SignatureError> {
+        verify_structured_data(&self.to_clarity_bytes(), &Self::domain(chain_id), signature, signer_key)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::crypto::context::secp256k1_context;
+    use crate::network::NetworkKind;
+
+    use super::*;
+
+    #[test]
+    fn test_signer_message() {
+        let pox_address = PoxAddress::from_address("bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4", &NetworkKind::Mainnet).unwrap();
+        let message = SignerKeyMessage { pox_address, reward_cycle: 95, topic: SignerTopic::AggregationCommit, period: 1, max_amount: 1000, auth_id: 7 };
+        let bytes = message.to_clarity_bytes();
+        // a tuple of 6 entries, sorted: auth-id first
+        assert_eq!(hex::encode(&bytes[..13]), "0c0000000607617574682d6964");
+        assert!(bytes.windows(10).any(|window| window == b"agg-commit"));
+
+        let key = SecretKey::from_byte_array(&[2u8; 32]).unwrap();
+        let signature = message.sign(0x80000000, &key);
+        let signer_key = key.public_key(secp256k1_context());
+        assert!(message.verify(0x80000000, &signature, &signer_key).is_ok());
+        assert!(message.verify(0x00000001, &signature, &signer_key).is_err());
+        let other_cycle = SignerKeyMessage { reward_cycle: 96, ..message };
+        assert!(other_cycle.verify(0x80000000, &signature, &signer_key).is_err());
+    }
+}
@@ -0,0 +1,159 @@
+//! Interning of the assets of post-conditions and display of their amounts.
+
+use std::collections::HashMap;
+use std::str::FromStr;
+use std::sync::Arc;
+
+use super::post_condition::AssetInfo;
+use super::TransactionError;
+
+/// Most decimals of an amount held in a `u128`.
+pub const MAX_DECIMALS: u8 = 38;
+
+/// Parses each asset identifier once and hands out shared references to it, for the many
+/// post-conditions on the same asset.
+///
+/// Usage:
+/// ```rust
+/// use std::str::FromStr;
+/// use stacks_rs::address::principal::Principal;
+/// use stacks_rs::transaction::asset::AssetInterner;
+/// use stacks_rs::transaction::post_condition::PostCondition;
+/// let mut interner = AssetInterner::new();
+/// let sender = Principal::from_str("SP3FGQ8Z7JY9BWYZ5WM53E0M9NK7WHJF0691NZ159").unwrap();
+/// let post_conditions: Vec<PostCondition> = (1..=3)
+///     .map(|amount| {
+///         let asset = interner.intern("SP3FGQ8Z7JY9BWYZ5WM53E0M9NK7WHJF0691NZ159.token::gold").unwrap();
+///         PostCondition::fungible(sender.clone(), (*asset).clone()).will_send_eq(amount)
+///     })
+///     .collect();
+/// assert_eq!(interner.len(), 1);
+/// ```
+#[derive(Debug, Default)]
+pub struct AssetInterner {
+    assets: HashMap<String, Arc<AssetInfo>>,
+}
+
+impl AssetInterner {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the interned asset for `asset_id`, parsing it on first use.
+    pub fn intern(&mut self, asset_id: &str) -> Result<Arc<AssetInfo>, TransactionError> {
+        if let Some(asset) = self.assets.get(asset_id) {
+            return Ok(asset.clone());
+        }
+        let asset = Arc::new(AssetInfo::from_str(asset_id)?);
+        self.assets.insert(asset_id.to_string(), asset.clone());
+        Ok(asset)
+    }
+
+    pub fn len(&self) -> usize {
+        self.assets.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.assets.is_empty()
+    }
+}
+
+/// How to display amounts of an asset.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct AssetDisplayInfo {
+    pub symbol: String,
+    pub decimals: u8,
+}
+
+impl AssetDisplayInfo {
+    /// Formats a raw `amount` with the asset decimals and symbol, e.g. `1.5 USDA`; more than
+    /// [`MAX_DECIMALS`] decimals is a [`TransactionError::InvalidDecimals`].
+    pub fn format_amount(&self, amount: u128) -> Result<String, TransactionError> {
+        let unit = 10u128.checked_pow(self.decimals as u32).ok_or(TransactionError::InvalidDecimals(self.decimals))?;
+        let fraction = format!("{:0width$}", amount % unit, width = self.decimals as usize);
+        Ok(match fraction.trim_end_matches('0') {
+            "" => format!("{} {}", amount / unit, self.symbol),
+            fraction => format!("{}.{} {}", amount / unit, fraction, self.symbol),
+        })
+    }
+}
+
+/// Looks up the symbol and decimals of an asset (e.g. from the token contract or an indexer).
+pub trait AssetMetadataSource {
+    type Error: From<TransactionError>;
+
+    fn resolve(&mut self, asset: &AssetInfo) -> Result<AssetDisplayInfo, Self::Error>;
+}
+
+/// Caches the display info of every asset, so each asset is resolved only once.
+pub struct AssetRegistry<S: AssetMetadataSource> {
+    source: S,
+    display_info: HashMap<String, AssetDisplayInfo>,
+}
+
+impl<S: AssetMetadataSource> AssetRegistry<S> {
+    pub fn new(source: S) -> Self {
+        AssetRegistry { source, display_info: HashMap::new() }
+    }
+
+    pub fn display_info(&mut self, asset: &AssetInfo) -> Result<&AssetDisplayInfo, S::Error> {
+        let key = asset.to_string();
+        if !self.display_info.contains_key(&key) {
+            let info = self.source.resolve(asset)?;
+            self.display_info.insert(key.clone(), info);
+        }
+        Ok(&self.display_info[&key])
+    }
+
+    pub fn format_amount(&mut self, asset: &AssetInfo, amount: u128) -> Result<String, S::Error> {
+        Ok(self.display_info(asset)?.format_amount(amount)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ASSET_ID: &str = "SP3FGQ8Z7JY9BWYZ5WM53E0M9NK7WHJF0691NZ159.my-token::token";
+
+    #[test]
+    fn test_interner_reuses_assets() {
+        let mut interner = AssetInterner::new();
+        let first = interner.intern(ASSET_ID).unwrap();
+        let second = interner.intern(ASSET_ID).unwrap();
+        assert!(Arc::ptr_eq(&first, &second));
+        assert_eq!(interner.len(), 1);
+        assert!(interner.intern("SP3FGQ8Z7JY9BWYZ5WM53E0M9NK7WHJF0691NZ159.my-token").is_err());
+        assert_eq!(interner.len(), 1);
+    }
+
+    #[test]
+    fn test_format_amount() {
+        let display = |decimals| AssetDisplayInfo { symbol: String::from("TKN"), decimals };
+        assert_eq!(display(0).format_amount(42).unwrap(), "42 TKN");
+        assert_eq!(display(38).format_amount(u128::MAX).unwrap(), "3.40282366920938463463374607431768211455 TKN");
+        assert_eq!(display(39).format_amount(1), Err(TransactionError::InvalidDecimals(39)));
+        assert_eq!(display(255).format_amount(1), Err(TransactionError::InvalidDecimals(255)));
+    }
+
+    struct CountingSource(u32);
+
+    impl AssetMetadataSource for CountingSource {
+        type Error = TransactionError;
+
+        fn resolve(&mut self, _asset: &AssetInfo) -> Result<AssetDisplayInfo, TransactionError> {
+            self.0 += 1;
+            Ok(AssetDisplayInfo { symbol: String::from("TKN"), decimals: 6 })
+        }
+    }
+
+    #[test]
+    fn test_registry_resolves_once() {
+        let asset = AssetInfo::from_str(ASSET_ID).unwrap();
+        let mut registry = AssetRegistry::new(CountingSource(0));
+        assert_eq!(registry.format_amount(&asset, 1_500_000).unwrap(), "1.5 TKN");
+        assert_eq!(registry.format_amount(&asset, 42).unwrap(), "0.000042 TKN");
+        assert_eq!(registry.format_amount(&asset, 2_000_000).unwrap(), "2 TKN");
+        assert_eq!(registry.source.0, 1);
+    }
+}
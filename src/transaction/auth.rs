@@ -0,0 +1,412 @@
+//! Transaction authorization: who pays for a transaction and the signatures proving it.
+
+use secp256k1::PublicKey;
+
+use crate::crypto::hash::{Hash160, Hasher, Sha256};
+use crate::crypto::signature::recoverable::{RecoverableSignature, RECOVERABLE_SIGNATURE_LENGTH};
+use crate::crypto::signature::SignatureError;
+
+use super::codec::{encode_list, Codec, Reader};
+use super::TransactionError;
+
+/// Most keys of a multisig account, as `OP_n` goes up to `OP_16`.
+pub const MAX_MULTI_SIG_KEYS: usize = 16;
+
+byte_enum!(
+    /// Hash mode of a single-signature spending condition.
+    SingleSigHashMode, "single-sig hash mode" {
+        P2PKH = 0x00,
+        P2WPKH = 0x02,
+    }
+);
+
+byte_enum!(
+    /// Hash mode of a multi-signature spending condition.
+    MultiSigHashMode, "multi-sig hash mode" {
+        P2SH = 0x01,
+        P2WSH = 0x03,
+        /// Order-independent P2SH (SIP-027): every co-signer signs the same digest
+        P2SHNonSequential = 0x05,
+        /// Order-independent P2WSH (SIP-027)
+        P2WSHNonSequential = 0x07,
+    }
+);
+
+impl MultiSigHashMode {
+    /// Whether each signature covers the previous ones, so that co-signers sign in turn.
+    pub fn is_sequential(&self) -> bool {
+        matches!(self, MultiSigHashMode::P2SH | MultiSigHashMode::P2WSH)
+    }
+}
+
+byte_enum!(PublicKeyEncoding, "public key encoding" {
+    Compressed = 0x00,
+    Uncompressed = 0x01,
+});
+
+/// 65-byte recoverable signature of a spending condition (`VRS`), all zeros until signed.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct MessageSignature(pub [u8; RECOVERABLE_SIGNATURE_LENGTH]);
+
+impl MessageSignature {
+    pub fn empty() -> Self {
+        MessageSignature([0; RECOVERABLE_SIGNATURE_LENGTH])
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0 == [0; RECOVERABLE_SIGNATURE_LENGTH]
+    }
+
+    pub fn to_recoverable(&self) -> Result<RecoverableSignature, SignatureError> {
+        RecoverableSignature::from_vrs(&self.0)
+    }
+}
+
+impl From<RecoverableSignature> for MessageSignature {
+    fn from(value: RecoverableSignature) -> Self {
+        MessageSignature(value.to_vrs())
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SingleSigSpendingCondition {
+    pub hash_mode: SingleSigHashMode,
+    /// Hash160 of the signer's public key (or of its P2WPKH script)
+    pub signer: [u8; 20],
+    pub nonce: u64,
+    pub fee: u64,
+    pub key_encoding: PublicKeyEncoding,
+    pub signature: MessageSignature,
+}
+
+impl SingleSigSpendingCondition {
+    /// Unsigned P2PKH condition of a compressed public key.
+    pub fn new(public_key: &PublicKey, nonce: u64, fee: u64) -> Self {
+        SingleSigSpendingCondition {
+            hash_mode: SingleSigHashMode::P2PKH,
+            signer: Hash160::hash(&public_key.serialize()),
+            nonce,
+            fee,
+            key_encoding: PublicKeyEncoding::Compressed,
+            signature: MessageSignature::empty(),
+        }
+    }
+}
+
+/// One public key or signature of a multi-signature spending condition, in signing order.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum AuthField {
+    /// Key of a signer who did not sign
+    PublicKey(PublicKey, PublicKeyEncoding),
+    Signature(MessageSignature, PublicKeyEncoding),
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MultiSigSpendingCondition {
+    pub hash_mode: MultiSigHashMode,
+    /// Hash160 of the redeem script
+    pub signer: [u8; 20],
+    pub nonce: u64,
+    pub fee: u64,
+    pub fields: Vec<AuthField>,
+    pub signatures_required: u16,
+}
+
+impl MultiSigSpendingCondition {
+    /// Unsigned condition of the account of `public_keys` (compressed, in signing order), see
+    /// [`check_threshold`].
+    pub fn new(hash_mode: MultiSigHashMode, public_keys: &[PublicKey], signatures_required: u16, nonce: u64, fee: u64) -> Result<Self, TransactionError> {
+        let keys: Vec<_> = public_keys.iter().map(|public_key| (*public_key, PublicKeyEncoding::Compressed)).collect();
+        Ok(MultiSigSpendingCondition {
+            hash_mode,
+            signer: multi_sig_signer(hash_mode, &keys, signatures_required)?,
+            nonce,
+            fee,
+            fields: vec![],
+            signatures_required,
+        })
+    }
+
+    pub fn signature_count(&self) -> usize {
+        self.fields.iter().filter(|field| matches!(field, AuthField::Signature(..))).count()
+    }
+}
+
+/// Checks `1 <= signatures_required <= key_count <= 16`, or returns
+/// [`TransactionError::InvalidThreshold`].
+pub fn check_threshold(signatures_required: u16, key_count: usize) -> Result<(), TransactionError> {
+    match signatures_required >= 1 && signatures_required as usize <= key_count && key_count <= MAX_MULTI_SIG_KEYS {
+        true => Ok(()),
+        false => Err(TransactionError::InvalidThreshold(signatures_required, key_count)),
+    }
+}
+
+/// `OP_m <keys> OP_n OP_CHECKMULTISIG`, the script whose hash is the multisig account.
+pub fn redeem_script(public_keys: &[(PublicKey, PublicKeyEncoding)], signatures_required: u16) -> Result<Vec<u8>, TransactionError> {
+    check_threshold(signatures_required, public_keys.len())?;
+    let mut script = vec![0x50 + signatures_required as u8];
+    for (public_key, encoding) in public_keys {
+        match encoding {
+            PublicKeyEncoding::Compressed => {
+                script.push(33);
+                script.extend(public_key.serialize());
+            }
+            PublicKeyEncoding::Uncompressed => {
+                script.push(65);
+                script.extend(public_key.serialize_uncompressed());
+            }
+        }
+    }
+    script.extend([0x50 + public_keys.len() as u8, 0xae]);
+    Ok(script)
+}
+
+/// Signer hash of a single-sig spending condition.
+pub(crate) fn single_sig_signer(hash_mode: SingleSigHashMode, public_key: &PublicKey, encoding: PublicKeyEncoding) -> [u8; 20] {
+    match (hash_mode, encoding) {
+        (SingleSigHashMode::P2PKH, PublicKeyEncoding::Compressed) => Hash160::hash(&public_key.serialize()),
+        (SingleSigHashMode::P2PKH, PublicKeyEncoding::Uncompressed) => Hash160::hash(&public_key.serialize_uncompressed()),
+        // the P2WPKH script wrapped in P2SH
+        (SingleSigHashMode::P2WPKH, _) => Hash160::hash(&[&[0x00, 0x14][..], &Hash160::hash(&public_key.serialize())].concat()),
+    }
+}
+
+/// Signer hash of a multisig spending condition.
+pub(crate) fn multi_sig_signer(
+    hash_mode: MultiSigHashMode,
+    public_keys: &[(PublicKey, PublicKeyEncoding)],
+    signatures_required: u16,
+) -> Result<[u8; 20], TransactionError> {
+    let script = redeem_script(public_keys, signatures_required)?;
+    Ok(match hash_mode {
+        MultiSigHashMode::P2SH | MultiSigHashMode::P2SHNonSequential => Hash160::hash(&script),
+        MultiSigHashMode::P2WSH | MultiSigHashMode::P2WSHNonSequential => Hash160::hash(&[&[0x00, 0x20][..], &Sha256::hash(&script)].concat()),
+    })
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum SpendingCondition {
+    SingleSig(SingleSigSpendingCondition),
+    MultiSig(MultiSigSpendingCondition),
+}
+
+impl SpendingCondition {
+    pub fn nonce(&self) -> u64 {
+        match self {
+            SpendingCondition::SingleSig(condition) => condition.nonce,
+            SpendingCondition::MultiSig(condition) => condition.nonce,
+        }
+    }
+
+    pub fn fee(&self) -> u64 {
+        match self {
+            SpendingCondition::SingleSig(condition) => condition.fee,
+            SpendingCondition::MultiSig(condition) => condition.fee,
+        }
+    }
+
+    pub fn signer(&self) -> &[u8; 20] {
+        match self {
+            SpendingCondition::SingleSig(condition) => &condition.signer,
+            SpendingCondition::MultiSig(condition) => &condition.signer,
+        }
+    }
+
+    pub(crate) fn set_fee(&mut self, fee: u64) {
+        match self {
+            SpendingCondition::SingleSig(condition) => condition.fee = fee,
+            SpendingCondition::MultiSig(condition) => condition.fee = fee,
+        }
+    }
+
+    /// The condition as it is signed: no nonce, fee nor signatures.
+    pub(crate) fn cleared(&self) -> Self {
+        match self {
+            SpendingCondition::SingleSig(condition) => {
+                SpendingCondition::SingleSig(SingleSigSpendingCondition { nonce: 0, fee: 0, signature: MessageSignature::empty(), ..condition.clone() })
+            }
+            SpendingCondition::MultiSig(condition) => {
+                SpendingCondition::MultiSig(MultiSigSpendingCondition { nonce: 0, fee: 0, fields: vec![], ..condition.clone() })
+            }
+        }
+    }
+}
+
+/// The origin pays its own fee (standard) or a sponsor pays it (sponsored).
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum TransactionAuth {
+    Standard(SpendingCondition),
+    /// Origin and sponsor spending conditions
+    Sponsored(SpendingCondition, SpendingCondition),
+}
+
+impl TransactionAuth {
+    pub fn origin(&self) -> &SpendingCondition {
+        match self {
+            TransactionAuth::Standard(origin) | TransactionAuth::Sponsored(origin, _) => origin,
+        }
+    }
+
+    pub fn sponsor(&self) -> Option<&SpendingCondition> {
+        match self {
+            TransactionAuth::Standard(_) => None,
+            TransactionAuth::Sponsored(_, sponsor) => Some(sponsor),
+        }
+    }
+
+    /// The condition paying the fee: the sponsor's if any.
+    pub(crate) fn payer_mut(&mut self) -> &mut SpendingCondition {
+        match self {
+            TransactionAuth::Standard(origin) => origin,
+            TransactionAuth::Sponsored(_, sponsor) => sponsor,
+        }
+    }
+
+    pub(crate) fn origin_mut(&mut self) -> &mut SpendingCondition {
+        match self {
+            TransactionAuth::Standard(origin) | TransactionAuth::Sponsored(origin, _) => origin,
+        }
+    }
+
+    /// The authorization the origin signs: cleared conditions, and the sponsor left to
+    /// [`sponsor_placeholder`].
+    pub(crate) fn cleared(&self) -> Self {
+        match self {
+            TransactionAuth::Standard(origin) => TransactionAuth::Standard(origin.cleared()),
+            TransactionAuth::Sponsored(origin, _) => TransactionAuth::Sponsored(origin.cleared(), sponsor_placeholder()),
+        }
+    }
+}
+
+/// Sponsor condition until the sponsor signs, also the one the origin signs over.
+pub(crate) fn sponsor_placeholder() -> SpendingCondition {
+    SpendingCondition::SingleSig(SingleSigSpendingCondition {
+        hash_mode: SingleSigHashMode::P2PKH,
+        signer: [0; 20],
+        nonce: 0,
+        fee: 0,
+        key_encoding: PublicKeyEncoding::Compressed,
+        signature: MessageSignature::empty(),
+    })
+}
+
+byte_enum!(AuthType, "authorization type" {
+    Standard = 0x04,
+    Sponsored = 0x05,
+});
+
+impl Codec for MessageSignature {
+    fn encode(&self, bytes: &mut Vec<u8>) -> Result<(), TransactionError> {
+        bytes.extend(self.0);
+        Ok(())
+    }
+
+    fn decode(reader: &mut Reader<'_>) -> Result<Self, TransactionError> {
+        Ok(MessageSignature(reader.array()?))
+    }
+}
+
+impl Codec for AuthField {
+    fn encode(&self, bytes: &mut Vec<u8>) -> Result<(), TransactionError> {
+        match self {
+            // keys are always written compressed, the field type keeps the encoding
+            AuthField::PublicKey(public_key, encoding) => {
+                bytes.push(encoding.value());
+                bytes.extend(public_key.serialize());
+            }
+            AuthField::Signature(signature, encoding) => {
+                bytes.push(0x02 + encoding.value());
+                signature.encode(bytes)?;
+            }
+        }
+        Ok(())
+    }
+
+    fn decode(reader: &mut Reader<'_>) -> Result<Self, TransactionError> {
+        let field_type = reader.u8()?;
+        let encoding = PublicKeyEncoding::from_value(field_type & 0x01).unwrap();
+        match field_type {
+            0x00 | 0x01 => {
+                let public_key = PublicKey::from_slice(reader.take(33)?).map_err(|_| TransactionError::InvalidPublicKey)?;
+                Ok(AuthField::PublicKey(public_key, encoding))
+            }
+            0x02 | 0x03 => Ok(AuthField::Signature(MessageSignature::decode(reader)?, encoding)),
+            other => Err(TransactionError::InvalidByte("authorization field type", other)),
+        }
+    }
+}
+
+impl Codec for SpendingCondition {
+    fn encode(&self, bytes: &mut Vec<u8>) -> Result<(), TransactionError> {
+        match self {
+            SpendingCondition::SingleSig(condition) => {
+                condition.hash_mode.encode(bytes)?;
+                bytes.extend(condition.signer);
+                bytes.extend(condition.nonce.to_be_bytes());
+                bytes.extend(condition.fee.to_be_bytes());
+                condition.key_encoding.encode(bytes)?;
+                condition.signature.encode(bytes)?;
+            }
+            SpendingCondition::MultiSig(condition) => {
+                condition.hash_mode.encode(bytes)?;
+                bytes.extend(condition.signer);
+                bytes.extend(condition.nonce.to_be_bytes());
+                bytes.extend(condition.fee.to_be_bytes());
+                encode_list(bytes, &condition.fields)?;
+                bytes.extend(condition.signatures_required.to_be_bytes());
+            }
+        }
+        Ok(())
+    }
+
+    fn decode(reader: &mut Reader<'_>) -> Result<Self, TransactionError> {
+        if SingleSigHashMode::from_value(reader.peek()?).is_some() {
+            return Ok(SpendingCondition::SingleSig(SingleSigSpendingCondition {
+                hash_mode: SingleSigHashMode::decode(reader)?,
+                signer: reader.array()?,
+                nonce: reader.u64()?,
+                fee: reader.u64()?,
+                key_encoding: PublicKeyEncoding::decode(reader)?,
+                signature: MessageSignature::decode(reader)?,
+            }));
+        }
+        let condition = MultiSigSpendingCondition {
+            hash_mode: MultiSigHashMode::decode(reader)?,
+            signer: reader.array()?,
+            nonce: reader.u64()?,
+            fee: reader.u64()?,
+            fields: reader.list()?,
+            signatures_required: reader.u16()?,
+        };
+        // a partially signed condition holds fewer fields than keys, all of them are checked
+        // against the threshold once signed
+        check_threshold(condition.signatures_required, MAX_MULTI_SIG_KEYS)?;
+        if condition.fields.len() > MAX_MULTI_SIG_KEYS {
+            return Err(TransactionError::InvalidThreshold(condition.signatures_required, condition.fields.len()));
+        }
+        Ok(SpendingCondition::MultiSig(condition))
+    }
+}
+
+impl Codec for TransactionAuth {
+    fn encode(&self, bytes: &mut Vec<u8>) -> Result<(), TransactionError> {
+        match self {
+            TransactionAuth::Standard(origin) => {
+                AuthType::Standard.encode(bytes)?;
+                origin.encode(bytes)
+            }
+            TransactionAuth::Sponsored(origin, sponsor) => {
+                AuthType::Sponsored.encode(bytes)?;
+                origin.encode(bytes)?;
+                sponsor.encode(bytes)
+            }
+        }
+    }
+
+    fn decode(reader: &mut Reader<'_>) -> Result<Self, TransactionError> {
+        Ok(match AuthType::decode(reader)? {
+            AuthType::Standard => TransactionAuth::Standard(SpendingCondition::decode(reader)?),
+            AuthType::Sponsored => TransactionAuth::Sponsored(SpendingCondition::decode(reader)?, SpendingCondition::decode(reader)?),
+        })
+    }
+}
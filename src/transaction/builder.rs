@@ -0,0 +1,600 @@
+//! Builders of unsigned transactions, with the defaults of stacks.js.
+
+use secp256k1::{PublicKey, SecretKey};
+
+use crate::address::principal::Principal;
+use crate::clarity::abi::{ContractAbi, FunctionAccess};
+use crate::clarity::value::ClarityValue;
+use crate::client::error::ClientError;
+use crate::client::http::HttpTransport;
+use crate::client::rpc::StacksRpcClient;
+use crate::crypto::context::secp256k1_context;
+use crate::network::{NetworkKind, StacksNetwork};
+
+use super::auth::{sponsor_placeholder, MultiSigHashMode, MultiSigSpendingCondition, SingleSigSpendingCondition, SpendingCondition, TransactionAuth};
+use super::fee::FeeStrategy;
+use super::metadata::TransactionMetadata;
+use super::payload::{ClarityVersion, ContractCallPayload, Payload, SmartContractPayload, TokenTransferPayload};
+use super::post_condition::{PostCondition, PostConditionMode};
+use super::signer::TransactionSigner;
+use super::stacks_transaction::{AnchorMode, StacksTransaction};
+use super::TransactionError;
+
+/// Settings shared by all transaction builders.
+#[derive(Clone, Debug)]
+struct Common {
+    network: StacksNetwork,
+    nonce: u64,
+    /// The default fee of the network when not set, none for a sponsored transaction
+    fee: Option<FeeStrategy>,
+    anchor_mode: AnchorMode,
+    post_condition_mode: PostConditionMode,
+    post_conditions: Vec<PostCondition>,
+    sponsored: bool,
+    multi_sig_hash_mode: MultiSigHashMode,
+    metadata: Option<TransactionMetadata>,
+}
+
+impl Default for Common {
+    fn default() -> Self {
+        Common {
+            network: StacksNetwork::mainnet(),
+            nonce: 0,
+            fee: None,
+            anchor_mode: AnchorMode::Any,
+            post_condition_mode: PostConditionMode::Deny,
+            post_conditions: vec![],
+            sponsored: false,
+            multi_sig_hash_mode: MultiSigHashMode::P2SH,
+            metadata: None,
+        }
+    }
+}
+
+impl Common {
+    /// Next nonce of the single-sig account of `public_key` on the builder's network.
+    fn account_nonce<T: HttpTransport>(&self, node: &StacksRpcClient<T>, public_key: &PublicKey) -> Result<u64, ClientError> {
+        Ok(node.get_account(&Principal::from(self.network.address(public_key)))?.nonce)
+    }
+
+    /// The unsigned transaction, checked by encoding it (names, source, Clarity values), with
+    /// the origin fee set by the fee strategy.
+    fn transaction(self, origin: SpendingCondition, payload: Payload) -> Result<StacksTransaction, TransactionError> {
+        let auth = match self.sponsored {
+            true => TransactionAuth::Sponsored(origin, sponsor_placeholder()),
+            false => TransactionAuth::Standard(origin),
+        };
+        let mut transaction = StacksTransaction {
+            version: self.network.transaction_version(),
+            chain_id: self.network.chain_id,
+            auth,
+            anchor_mode: self.anchor_mode,
+            post_condition_mode: self.post_condition_mode,
+            post_conditions: self.post_conditions,
+            payload,
+            metadata: self.metadata,
+        };
+        transaction.serialize()?;
+        let fee = match (self.fee, self.sponsored) {
+            (Some(fee), _) => fee.fee(&transaction)?,
+            // the sponsor sets its own fee
+            (None, true) => 0,
+            (None, false) => self.network.default_fee.fee(&transaction)?,
+        };
+        transaction.auth.origin_mut().set_fee(fee);
+        Ok(transaction)
+    }
+}
+
+/// Setters of the [`Common`] settings and the `build` methods, given the builder's
+/// `into_parts(self) -> Result<(Common, Payload), TransactionError>`.
+macro_rules! builder_methods {
+    () => {
+        /// The unsigned transaction, to be signed by the owner of `public_key`.
+        pub fn build(self, public_key: &PublicKey) -> Result<StacksTransaction, TransactionError> {
+            let (common, payload) = self.into_parts()?;
+            let origin = SingleSigSpendingCondition::new(public_key, common.nonce, 0);
+            common.transaction(SpendingCondition::SingleSig(origin), payload)
+        }
+
+        /// The unsigned transaction of the multisig account of `public_keys`, in the order of
+        /// the account.
+        pub fn build_multi_sig(self, public_keys: &[PublicKey], signatures_required: u16) -> Result<StacksTransaction, TransactionError> {
+            let (common, payload) = self.into_parts()?;
+            let hash_mode = common.multi_sig_hash_mode;
+            let origin = MultiSigSpendingCondition::new(hash_mode, public_keys, signatures_required, common.nonce, 0)?;
+            common.transaction(SpendingCondition::MultiSig(origin), payload)
+        }
+
+        /// Mainnet by default.
+        pub fn network(mut self, network: NetworkKind) -> Self {
+            self.common.network = StacksNetwork::from(network);
+            self
+        }
+
+        /// Chain id, version and default fee of `network`.
+        pub fn stacks_network(mut self, network: StacksNetwork) -> Self {
+            self.common.network = network;
+            self
+        }
+
+        pub fn nonce(mut self, nonce: u64) -> Self {
+            self.common.nonce = nonce;
+            self
+        }
+
+        /// Sets the nonce to the next one of the account of `public_key`, as `node` reports it.
+        pub fn fetch_nonce<T: HttpTransport>(self, node: &StacksRpcClient<T>, public_key: &PublicKey) -> Result<Self, ClientError> {
+            let nonce = self.common.account_nonce(node, public_key)?;
+            Ok(self.nonce(nonce))
+        }
+
+        /// In micro-STX, shorthand for [`FeeStrategy::Fixed`].
+        pub fn fee(mut self, fee: u64) -> Self {
+            self.common.fee = Some(FeeStrategy::Fixed(fee));
+            self
+        }
+
+        /// Fee of the transaction, computed once it is built; the default fee of the network
+        /// by default.
+        pub fn fee_strategy(mut self, fee_strategy: FeeStrategy) -> Self {
+            self.common.fee = Some(fee_strategy);
+            self
+        }
+
+        /// [`AnchorMode::Any`] by default.
+        pub fn anchor_mode(mut self, anchor_mode: AnchorMode) -> Self {
+            self.common.anchor_mode = anchor_mode;
+            self
+        }
+
+        /// [`PostConditionMode::Deny`] by default.
+        pub fn post_condition_mode(mut self, post_condition_mode: PostConditionMode) -> Self {
+            self.common.post_condition_mode = post_condition_mode;
+            self
+        }
+
+        pub fn post_condition(mut self, post_condition: PostCondition) -> Self {
+            self.common.post_conditions.push(post_condition);
+            self
+        }
+
+        pub fn post_conditions(mut self, post_conditions: impl IntoIterator<Item = PostCondition>) -> Self {
+            self.common.post_conditions.extend(post_conditions);
+            self
+        }
+
+        /// Hash mode of [`Self::build_multi_sig`], [`MultiSigHashMode::P2SH`] by default.
+        pub fn multi_sig_hash_mode(mut self, hash_mode: MultiSigHashMode) -> Self {
+            self.common.multi_sig_hash_mode = hash_mode;
+            self
+        }
+
+        /// Leaves the fee to a sponsor, who signs after the origin.
+        pub fn sponsored(mut self, sponsored: bool) -> Self {
+            self.common.sponsored = sponsored;
+            self
+        }
+
+        /// Build height and expiry of the transaction, checked before broadcasting it.
+        pub fn metadata(mut self, metadata: TransactionMetadata) -> Self {
+            self.common.metadata = Some(metadata);
+            self
+        }
+    };
+}
+
+/// Builds an unsigned contract call, as stacks.js `makeUnsignedContractCall` does.
+///
+/// Usage:
+/// ```rust
+/// use std::str::FromStr;
+/// use secp256k1::PublicKey;
+/// use stacks_rs::address::principal::Principal;
+/// use stacks_rs::clarity::value::ClarityValue;
+/// use stacks_rs::transaction::builder::ContractCallBuilder;
+/// let contract = Principal::from_str("SP3FGQ8Z7JY9BWYZ5WM53E0M9NK7WHJF0691NZ159.pool").unwrap();
+/// let public_key = PublicKey::from_str("03ef788b3830c00abe8f64f62dc32fc863bc0b2cafeb073b6c8e1c7657d9c2c3ab").unwrap();
+/// let transaction = ContractCallBuilder::new(contract, "join", vec![ClarityValue::UInt(100)])
+///     .nonce(1)
+///     .fee(2000)
+///     .build(&public_key)
+///     .unwrap();
+/// assert_eq!(transaction.auth.origin().fee(), 2000);
+/// ```
+#[derive(Clone, Debug)]
+pub struct ContractCallBuilder {
+    contract: Principal,
+    function_name: String,
+    function_args: Vec<ClarityValue>,
+    abi: Option<ContractAbi>,
+    common: Common,
+}
+
+impl ContractCallBuilder {
+    pub fn new(contract: Principal, function_name: &str, function_args: Vec<ClarityValue>) -> Self {
+        ContractCallBuilder { contract, function_name: function_name.to_string(), function_args, abi: None, common: Common::default() }
+    }
+
+    builder_methods!();
+
+    /// Checks the call against the contract interface when building.
+    pub fn abi(mut self, abi: ContractAbi) -> Self {
+        self.abi = Some(abi);
+        self
+    }
+
+    fn into_parts(self) -> Result<(Common, Payload), TransactionError> {
+        let Principal::Contract(contract_address, contract_name) = self.contract else {
+            return Err(TransactionError::InvalidAddress(format!("{} is not a contract", self.contract)));
+        };
+        if let Some(abi) = &self.abi {
+            let function = abi
+                .function(&self.function_name)
+                .filter(|function| function.access != FunctionAccess::Private)
+                .ok_or_else(|| TransactionError::UnknownFunction(self.function_name.clone()))?;
+            function.check_args(&self.function_args)?;
+        }
+        let payload = Payload::ContractCall(ContractCallPayload {
+            contract_address,
+            contract_name,
+            function_name: self.function_name,
+            function_args: self.function_args,
+        });
+        Ok((self.common, payload))
+    }
+}
+
+/// Builds an unsigned contract deploy, as stacks.js `makeUnsignedContractDeploy` does.
+///
+/// Without a Clarity version the node deploys with its default version.
+///
+/// Usage:
+/// ```rust
+/// use std::str::FromStr;
+/// use secp256k1::PublicKey;
+/// use stacks_rs::transaction::builder::ContractDeployBuilder;
+/// use stacks_rs::transaction::payload::{ClarityVersion, PayloadType};
+/// let public_key = PublicKey::from_str("03ef788b3830c00abe8f64f62dc32fc863bc0b2cafeb073b6c8e1c7657d9c2c3ab").unwrap();
+/// let transaction = ContractDeployBuilder::new("hello", "(define-read-only (hi) u1)")
+///     .clarity_version(ClarityVersion::Clarity3)
+///     .fee(10000)
+///     .build(&public_key)
+///     .unwrap();
+/// assert_eq!(transaction.payload.payload_type(), PayloadType::VersionedSmartContract);
+/// ```
+#[derive(Clone, Debug)]
+pub struct ContractDeployBuilder {
+    contract_name: String,
+    code_body: String,
+    clarity_version: Option<ClarityVersion>,
+    common: Common,
+}
+
+impl ContractDeployBuilder {
+    pub fn new(contract_name: &str, code_body: &str) -> Self {
+        ContractDeployBuilder {
+            contract_name: contract_name.to_string(),
+            code_body: code_body.to_string(),
+            clarity_version: None,
+            common: Common::default(),
+        }
+    }
+
+    builder_methods!();
+
+    pub fn clarity_version(mut self, clarity_version: ClarityVersion) -> Self {
+        self.clarity_version = Some(clarity_version);
+        self
+    }
+
+    fn into_parts(self) -> Result<(Common, Payload), TransactionError> {
+        let payload = Payload::SmartContract(SmartContractPayload {
+            contract_name: self.contract_name,
+            code_body: self.code_body,
+            clarity_version: self.clarity_version,
+        });
+        Ok((self.common, payload))
+    }
+}
+
+/// Builds transactions of one account with consecutive nonces, e.g. for airdrops and payouts.
+///
+/// The settings and fee strategy apply to every transaction; the transactions come in the
+/// order they were added, which is the order to broadcast them in.
+///
+/// Usage:
+/// ```rust
+/// use std::str::FromStr;
+/// use secp256k1::SecretKey;
+/// use stacks_rs::address::principal::Principal;
+/// use stacks_rs::transaction::builder::TransactionBatch;
+/// use stacks_rs::transaction::fee::FeeStrategy;
+/// let key = SecretKey::from_byte_array(&[1; 32]).unwrap();
+/// let recipient = Principal::from_str("SP3FGQ8Z7JY9BWYZ5WM53E0M9NK7WHJF0691NZ159").unwrap();
+/// let transactions = TransactionBatch::new()
+///     .nonce(12)
+///     .fee_strategy(FeeStrategy::Rate(2))
+///     .transfer(recipient.clone(), 1000, "first")
+///     .unwrap()
+///     .transfer(recipient, 2000, "second")
+///     .unwrap()
+///     .sign(&key)
+///     .unwrap();
+/// let nonces: Vec<u64> = transactions.iter().map(|transaction| transaction.auth.origin().nonce()).collect();
+/// assert_eq!(nonces, [12, 13]);
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct TransactionBatch {
+    common: Common,
+    transactions: Vec<(Payload, Vec<PostCondition>)>,
+}
+
+impl TransactionBatch {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Mainnet by default.
+    pub fn network(mut self, network: NetworkKind) -> Self {
+        self.common.network = StacksNetwork::from(network);
+        self
+    }
+
+    pub fn stacks_network(mut self, network: StacksNetwork) -> Self {
+        self.common.network = network;
+        self
+    }
+
+    /// Nonce of the first transaction.
+    pub fn nonce(mut self, nonce: u64) -> Self {
+        self.common.nonce = nonce;
+        self
+    }
+
+    /// Starts at the next nonce of the account of `public_key`, as `node` reports it.
+    pub fn fetch_nonce<T: HttpTransport>(self, node: &StacksRpcClient<T>, public_key: &PublicKey) -> Result<Self, ClientError> {
+        let nonce = self.common.account_nonce(node, public_key)?;
+        Ok(self.nonce(nonce))
+    }
+
+    /// Fee of each transaction, the default fee of the network by default.
+    pub fn fee_strategy(mut self, fee_strategy: FeeStrategy) -> Self {
+        self.common.fee = Some(fee_strategy);
+        self
+    }
+
+    /// [`AnchorMode::Any`] by default.
+    pub fn anchor_mode(mut self, anchor_mode: AnchorMode) -> Self {
+        self.common.anchor_mode = anchor_mode;
+        self
+    }
+
+    /// [`PostConditionMode::Deny`] by default.
+    pub fn post_condition_mode(mut self, post_condition_mode: PostConditionMode) -> Self {
+        self.common.post_condition_mode = post_condition_mode;
+        self
+    }
+
+    /// Adds a transaction of `payload`, guarded by `post_conditions`.
+    pub fn push(mut self, payload: Payload, post_conditions: Vec<PostCondition>) -> Self {
+        self.transactions.push((payload, post_conditions));
+        self
+    }
+
+    /// Adds a transfer of `amount` micro-STX.
+    pub fn transfer(self, recipient: Principal, amount: u64, memo: &str) -> Result<Self, TransactionError> {
+        Ok(self.push(Payload::TokenTransfer(TokenTransferPayload::new(recipient, amount, memo)?), vec![]))
+    }
+
+    pub fn len(&self) -> usize {
+        self.transactions.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.transactions.is_empty()
+    }
+
+    /// The unsigned transactions, to be signed by the owner of `public_key`.
+    pub fn build(self, public_key: &PublicKey) -> Result<Vec<StacksTransaction>, TransactionError> {
+        let first_nonce = self.common.nonce;
+        self.transactions
+            .into_iter()
+            .zip(first_nonce..)
+            .map(|((payload, post_conditions), nonce)| {
+                let common = Common { nonce, post_conditions, ..self.common.clone() };
+                let origin = SingleSigSpendingCondition::new(public_key, nonce, 0);
+                common.transaction(SpendingCondition::SingleSig(origin), payload)
+            })
+            .collect()
+    }
+
+    /// The transactions signed with `key`.
+    pub fn sign(self, key: &SecretKey) -> Result<Vec<StacksTransaction>, TransactionError> {
+        self.build(&key.public_key(secp256k1_context()))?
+            .into_iter()
+            .map(|transaction| {
+                let mut signer = TransactionSigner::new(transaction)?;
+                signer.sign_with(key)?;
+                signer.finish()
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use crate::address::stacks_address::StacksAddress;
+    use crate::clarity::ClarityError;
+    use crate::crypto::hash::{Hash160, Hasher};
+    use crate::transaction::fee::FeeEstimates;
+    use crate::transaction::payload::PayloadType;
+    use crate::transaction::post_condition::PostConditionPrincipal;
+
+    use super::*;
+
+    const CONTRACT: &str = "SP3FGQ8Z7JY9BWYZ5WM53E0M9NK7WHJF0691NZ159.pool";
+    const PUBLIC_KEY: &str = "03ef788b3830c00abe8f64f62dc32fc863bc0b2cafeb073b6c8e1c7657d9c2c3ab";
+
+    fn builder(args: Vec<ClarityValue>) -> ContractCallBuilder {
+        ContractCallBuilder::new(Principal::from_str(CONTRACT).unwrap(), "join", args)
+    }
+
+    #[test]
+    fn test_contract_call() {
+        let public_key = PublicKey::from_str(PUBLIC_KEY).unwrap();
+        let transaction = builder(vec![ClarityValue::UInt(100)])
+            .network(NetworkKind::Testnet)
+            .nonce(3)
+            .fee(180)
+            .post_condition(PostCondition::stx(PostConditionPrincipal::Origin).will_send_eq(100))
+            .build(&public_key)
+            .unwrap();
+        let signer = hex::encode(Hash160::hash(&public_key.serialize()));
+        let expected = [
+            "80", "80000000", "04", "00", &signer, "0000000000000003", "00000000000000b4", "00", &"00".repeat(65),
+            "03", "02", "00000001", "00", "01", "01", "0000000000000064",
+            "02", "16df0ba3e79792be7be5e50a370289accfc8c9e032", "04", "706f6f6c", "04", "6a6f696e", "00000001", "0100000000000000000000000000000064",
+        ]
+        .concat();
+        assert_eq!(transaction.to_hex().unwrap(), expected);
+    }
+
+    #[test]
+    fn test_sponsored() {
+        let public_key = PublicKey::from_str(PUBLIC_KEY).unwrap();
+        let transaction = builder(vec![]).sponsored(true).build(&public_key).unwrap();
+        let sponsor = transaction.auth.sponsor().unwrap();
+        assert_eq!(sponsor.signer(), &[0; 20]);
+        assert_eq!((sponsor.nonce(), sponsor.fee()), (0, 0));
+        assert_eq!(transaction.chain_id, 1);
+    }
+
+    #[test]
+    fn test_fee_strategy() {
+        let public_key = PublicKey::from_str(PUBLIC_KEY).unwrap();
+        let transaction = builder(vec![]).fee_strategy(FeeStrategy::Rate(3)).build(&public_key).unwrap();
+        assert_eq!(transaction.auth.origin().fee(), 3 * transaction.estimated_len().unwrap() as u64);
+
+        let keys = [public_key, public_key];
+        let multi_sig = builder(vec![]).fee_strategy(FeeStrategy::Rate(1)).build_multi_sig(&keys, 2).unwrap();
+        assert_eq!(multi_sig.auth.origin().fee(), multi_sig.estimated_len().unwrap() as u64);
+
+        let estimates = FeeEstimates { low: 100, medium: 200, high: 300 };
+        let sponsored = builder(vec![]).sponsored(true).fee_strategy(estimates.high()).build(&public_key).unwrap();
+        assert_eq!(sponsored.auth.origin().fee(), 300);
+        assert_eq!(sponsored.auth.sponsor().unwrap().fee(), 0);
+    }
+
+    #[test]
+    fn test_stacks_network() {
+        let public_key = PublicKey::from_str(PUBLIC_KEY).unwrap();
+        let subnet = StacksNetwork::custom(NetworkKind::Testnet, 0x55005500, "http://subnet:20443").with_default_fee(FeeStrategy::Fixed(42));
+        let transaction = builder(vec![]).stacks_network(subnet.clone()).build(&public_key).unwrap();
+        assert_eq!((transaction.chain_id, transaction.auth.origin().fee()), (0x55005500, 42));
+        let transaction = builder(vec![]).stacks_network(subnet).fee(7).build(&public_key).unwrap();
+        assert_eq!(transaction.auth.origin().fee(), 7);
+
+        let transaction = builder(vec![]).build(&public_key).unwrap();
+        assert_eq!(transaction.auth.origin().fee(), transaction.estimated_len().unwrap() as u64);
+    }
+
+    #[test]
+    fn test_abi_validation() {
+        let public_key = PublicKey::from_str(PUBLIC_KEY).unwrap();
+        let abi = ContractAbi::from_json(
+            r#"{"functions": [
+                {"name": "join", "access": "public", "args": [{"name": "amount", "type": "uint128"}], "outputs": {"type": {"response": {"ok": "bool", "error": "uint128"}}}},
+                {"name": "hidden", "access": "private", "args": [], "outputs": {"type": "bool"}}
+            ], "variables": [], "maps": [], "fungible_tokens": [], "non_fungible_tokens": []}"#,
+        )
+        .unwrap();
+        assert!(builder(vec![ClarityValue::UInt(1)]).abi(abi.clone()).build(&public_key).is_ok());
+        assert_eq!(
+            builder(vec![]).abi(abi.clone()).build(&public_key),
+            Err(TransactionError::Clarity(ClarityError::WrongArgumentCount(1, 0)))
+        );
+        assert!(matches!(
+            builder(vec![ClarityValue::Int(1)]).abi(abi.clone()).build(&public_key),
+            Err(TransactionError::Clarity(ClarityError::TypeMismatch(_)))
+        ));
+        let hidden = ContractCallBuilder::new(Principal::from_str(CONTRACT).unwrap(), "hidden", vec![]).abi(abi).build(&public_key);
+        assert_eq!(hidden, Err(TransactionError::UnknownFunction(String::from("hidden"))));
+    }
+
+    #[test]
+    fn test_invalid_contract_call() {
+        let public_key = PublicKey::from_str(PUBLIC_KEY).unwrap();
+        let standard = Principal::from_str("SP3FGQ8Z7JY9BWYZ5WM53E0M9NK7WHJF0691NZ159").unwrap();
+        assert!(matches!(ContractCallBuilder::new(standard, "join", vec![]).build(&public_key), Err(TransactionError::InvalidAddress(_))));
+        let invalid = ContractCallBuilder::new(Principal::from_str(CONTRACT).unwrap(), "not a name", vec![]).build(&public_key);
+        assert_eq!(invalid, Err(TransactionError::InvalidName(String::from("not a name"))));
+    }
+
+    #[test]
+    fn test_contract_deploy() {
+        let public_key = PublicKey::from_str(PUBLIC_KEY).unwrap();
+        let source = "(define-data-var count uint u0)\n(define-public (increment)\n  (ok (var-set count (+ (var-get count) u1))))\n";
+        let transaction = ContractDeployBuilder::new("counter", source)
+            .clarity_version(ClarityVersion::Clarity2)
+            .network(NetworkKind::Testnet)
+            .nonce(1)
+            .fee(5000)
+            .build(&public_key)
+            .unwrap();
+        let signer = hex::encode(Hash160::hash(&public_key.serialize()));
+        let expected = [
+            "80", "80000000", "04", "00", &signer, "0000000000000001", "0000000000001388", "00", &"00".repeat(65),
+            "03", "02", "00000000", "06", "02", "07", &hex::encode("counter"), &format!("{:08x}", source.len()), &hex::encode(source),
+        ]
+        .concat();
+        assert_eq!(transaction.to_hex().unwrap(), expected);
+
+        let unversioned = ContractDeployBuilder::new("counter", source).build(&public_key).unwrap();
+        assert_eq!(unversioned.payload.payload_type(), PayloadType::SmartContract);
+    }
+
+    #[test]
+    fn test_invalid_contract_deploy() {
+        let public_key = PublicKey::from_str(PUBLIC_KEY).unwrap();
+        assert_eq!(
+            ContractDeployBuilder::new("1counter", "(ok u1)").build(&public_key),
+            Err(TransactionError::InvalidContractName(String::from("1counter")))
+        );
+        assert_eq!(ContractDeployBuilder::new("counter", "(ok \"\u{e9}\")").build(&public_key), Err(TransactionError::InvalidCodeBody));
+    }
+
+    #[test]
+    fn test_batch() {
+        let key = SecretKey::from_byte_array(&[1; 32]).unwrap();
+        let recipient = Principal::from_str("SP3FGQ8Z7JY9BWYZ5WM53E0M9NK7WHJF0691NZ159").unwrap();
+        let post_condition = PostCondition::stx(PostConditionPrincipal::Origin).will_send_lte(500);
+        let call = Payload::ContractCall(ContractCallPayload {
+            contract_address: StacksAddress::from_str("SP3FGQ8Z7JY9BWYZ5WM53E0M9NK7WHJF0691NZ159").unwrap(),
+            contract_name: String::from("pool"),
+            function_name: String::from("join"),
+            function_args: vec![],
+        });
+        let batch = TransactionBatch::new()
+            .network(NetworkKind::Testnet)
+            .nonce(5)
+            .fee_strategy(FeeStrategy::Fixed(300))
+            .transfer(recipient.clone(), 100, "")
+            .unwrap()
+            .push(call.clone(), vec![post_condition.clone()])
+            .transfer(recipient.clone(), 200, "")
+            .unwrap();
+        assert_eq!(batch.len(), 3);
+        let transactions = batch.sign(&key).unwrap();
+        let origins: Vec<(u64, u64)> = transactions.iter().map(|transaction| (transaction.auth.origin().nonce(), transaction.auth.origin().fee())).collect();
+        assert_eq!(origins, [(5, 300), (6, 300), (7, 300)]);
+        assert_eq!((&transactions[1].payload, &transactions[1].post_conditions), (&call, &vec![post_condition]));
+        assert!(transactions[0].post_conditions.is_empty());
+        assert!(transactions.iter().all(|transaction| transaction.chain_id == NetworkKind::Testnet.chain_id() && transaction.verify().is_ok()));
+
+        assert_eq!(TransactionBatch::new().transfer(recipient, 1, &"m".repeat(35)).unwrap_err(), TransactionError::MemoTooLong(35));
+        assert!(TransactionBatch::new().sign(&key).unwrap().is_empty());
+    }
+}
@@ -0,0 +1,109 @@
+//! Byte-level helpers of the transaction wire format.
+
+use crate::address::stacks_address::StacksAddress;
+use crate::address::ADDRESS_HASH_LENGTH;
+use crate::clarity::value::ClarityValue;
+
+use super::TransactionError;
+
+pub(crate) trait Codec: Sized {
+    fn encode(&self, bytes: &mut Vec<u8>) -> Result<(), TransactionError>;
+    fn decode(reader: &mut Reader<'_>) -> Result<Self, TransactionError>;
+}
+
+pub(crate) struct Reader<'a> {
+    bytes: &'a [u8],
+}
+
+impl<'a> Reader<'a> {
+    pub(crate) fn new(bytes: &'a [u8]) -> Self {
+        Reader { bytes }
+    }
+
+    pub(crate) fn remaining(&self) -> usize {
+        self.bytes.len()
+    }
+
+    pub(crate) fn peek(&self) -> Result<u8, TransactionError> {
+        self.bytes.first().copied().ok_or(TransactionError::UnexpectedEnd)
+    }
+
+    pub(crate) fn take(&mut self, length: usize) -> Result<&'a [u8], TransactionError> {
+        if self.bytes.len() < length {
+            return Err(TransactionError::UnexpectedEnd);
+        }
+        let (taken, rest) = self.bytes.split_at(length);
+        self.bytes = rest;
+        Ok(taken)
+    }
+
+    pub(crate) fn array<const N: usize>(&mut self) -> Result<[u8; N], TransactionError> {
+        Ok(self.take(N)?.try_into().unwrap())
+    }
+
+    pub(crate) fn u8(&mut self) -> Result<u8, TransactionError> {
+        let [value] = self.array()?;
+        Ok(value)
+    }
+
+    pub(crate) fn u16(&mut self) -> Result<u16, TransactionError> {
+        Ok(u16::from_be_bytes(self.array()?))
+    }
+
+    pub(crate) fn u32(&mut self) -> Result<u32, TransactionError> {
+        Ok(u32::from_be_bytes(self.array()?))
+    }
+
+    pub(crate) fn u64(&mut self) -> Result<u64, TransactionError> {
+        Ok(u64::from_be_bytes(self.array()?))
+    }
+
+    /// `version (1) || hash160 (20)`.
+    pub(crate) fn address(&mut self) -> Result<StacksAddress, TransactionError> {
+        let version = self.u8()?;
+        let hash160: [u8; ADDRESS_HASH_LENGTH] = self.array()?;
+        StacksAddress::new(version, hash160).map_err(|err| TransactionError::InvalidAddress(format!("{err}")))
+    }
+
+    /// Name prefixed with its one-byte length.
+    pub(crate) fn name(&mut self) -> Result<String, TransactionError> {
+        let length = self.u8()?;
+        let bytes = self.take(length as usize)?;
+        String::from_utf8(bytes.to_vec()).map_err(|_| TransactionError::InvalidName(String::from_utf8_lossy(bytes).into_owned()))
+    }
+
+    pub(crate) fn clarity_value(&mut self) -> Result<ClarityValue, TransactionError> {
+        let (value, read) = ClarityValue::deserialize_prefix(self.bytes)?;
+        self.bytes = &self.bytes[read..];
+        Ok(value)
+    }
+
+    /// Elements prefixed with their four-byte count.
+    pub(crate) fn list<T: Codec>(&mut self) -> Result<Vec<T>, TransactionError> {
+        let count = self.u32()? as usize;
+        // every element takes at least one byte, which bounds the allocation
+        if count > self.bytes.len() {
+            return Err(TransactionError::UnexpectedEnd);
+        }
+        (0..count).map(|_| T::decode(self)).collect()
+    }
+}
+
+pub(crate) fn encode_address(bytes: &mut Vec<u8>, address: &StacksAddress) {
+    bytes.push(address.version());
+    bytes.extend(address.hash160());
+}
+
+/// Names are at most 128 bytes, validated by the caller.
+pub(crate) fn encode_name(bytes: &mut Vec<u8>, name: &str) {
+    bytes.push(name.len() as u8);
+    bytes.extend(name.as_bytes());
+}
+
+pub(crate) fn encode_list<T: Codec>(bytes: &mut Vec<u8>, items: &[T]) -> Result<(), TransactionError> {
+    bytes.extend((items.len() as u32).to_be_bytes());
+    for item in items {
+        item.encode(bytes)?;
+    }
+    Ok(())
+}
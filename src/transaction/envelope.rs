@@ -0,0 +1,212 @@
+//! A JSON envelope of unsigned and partially signed transactions, to carry them to an
+//! offline signer and back.
+
+use serde::{Deserialize, Serialize};
+
+use crate::network::NetworkKind;
+
+use super::auth::SpendingCondition;
+use super::payload::Payload;
+use super::stacks_transaction::{StacksTransaction, TransactionVersion};
+use super::TransactionError;
+
+/// Version of the envelope format, bumped on incompatible changes.
+pub const ENVELOPE_VERSION: u32 = 1;
+
+/// What the transaction does, for the signer to review before signing.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum PayloadSummary {
+    TokenTransfer {
+        recipient: String,
+        /// In micro-STX
+        amount: u64,
+        memo: String,
+    },
+    ContractCall {
+        contract: String,
+        function_name: String,
+        /// Clarity representation of the arguments
+        function_args: Vec<String>,
+    },
+    SmartContract {
+        contract_name: String,
+        code_length: usize,
+    },
+    /// Payloads only miners and signers send
+    Other {
+        payload_type: u8,
+    },
+}
+
+impl From<&Payload> for PayloadSummary {
+    fn from(payload: &Payload) -> Self {
+        match payload {
+            Payload::TokenTransfer(transfer) => PayloadSummary::TokenTransfer {
+                recipient: transfer.recipient.to_string(),
+                amount: transfer.amount,
+                memo: transfer.memo_str().unwrap_or_default().to_string(),
+            },
+            Payload::ContractCall(call) => PayloadSummary::ContractCall {
+                contract: format!("{}.{}", call.contract_address, call.contract_name),
+                function_name: call.function_name.clone(),
+                function_args: call.function_args.iter().map(ToString::to_string).collect(),
+            },
+            Payload::SmartContract(contract) => {
+                PayloadSummary::SmartContract { contract_name: contract.contract_name.clone(), code_length: contract.code_body.len() }
+            }
+            _ => PayloadSummary::Other { payload_type: payload.payload_type().value() },
+        }
+    }
+}
+
+/// An unsigned or partially signed transaction with a readable summary of its content.
+///
+/// The summary is for display only: [`TransactionEnvelope::transaction`] checks it against the
+/// encoded transaction, so an edited envelope is rejected rather than signed.
+///
+/// Usage:
+/// ```rust
+/// use std::str::FromStr;
+/// use secp256k1::PublicKey;
+/// use stacks_rs::address::principal::Principal;
+/// use stacks_rs::transaction::builder::ContractCallBuilder;
+/// use stacks_rs::transaction::envelope::TransactionEnvelope;
+/// let contract = Principal::from_str("SP3FGQ8Z7JY9BWYZ5WM53E0M9NK7WHJF0691NZ159.pool").unwrap();
+/// let public_key = PublicKey::from_str("03ef788b3830c00abe8f64f62dc32fc863bc0b2cafeb073b6c8e1c7657d9c2c3ab").unwrap();
+/// let transaction = ContractCallBuilder::new(contract, "leave", vec![]).fee(2000).build(&public_key).unwrap();
+/// let json = TransactionEnvelope::new(&transaction).unwrap().to_json().unwrap();
+/// // on the offline machine
+/// let envelope = TransactionEnvelope::from_json(&json).unwrap();
+/// assert_eq!(envelope.fee, 2000);
+/// assert_eq!(envelope.transaction().unwrap(), transaction);
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TransactionEnvelope {
+    pub version: u32,
+    /// Mainnet or testnet, from the transaction version
+    pub network: NetworkKind,
+    pub nonce: u64,
+    /// Fee of the payer (the sponsor of a sponsored transaction), in micro-STX
+    pub fee: u64,
+    pub sponsored: bool,
+    /// Signatures of the origin
+    pub signatures: usize,
+    pub signatures_required: usize,
+    pub post_conditions: usize,
+    pub payload: PayloadSummary,
+    /// Hex encoded transaction, without `0x`
+    pub transaction: String,
+}
+
+impl TransactionEnvelope {
+    pub fn new(transaction: &StacksTransaction) -> Result<Self, TransactionError> {
+        let origin = transaction.auth.origin();
+        let (signatures, signatures_required) = match origin {
+            SpendingCondition::SingleSig(condition) => (usize::from(!condition.signature.is_empty()), 1),
+            SpendingCondition::MultiSig(condition) => (condition.signature_count(), condition.signatures_required as usize),
+        };
+        Ok(TransactionEnvelope {
+            version: ENVELOPE_VERSION,
+            network: match transaction.version {
+                TransactionVersion::Mainnet => NetworkKind::Mainnet,
+                TransactionVersion::Testnet => NetworkKind::Testnet,
+            },
+            nonce: origin.nonce(),
+            fee: transaction.auth.sponsor().unwrap_or(origin).fee(),
+            sponsored: transaction.auth.sponsor().is_some(),
+            signatures,
+            signatures_required,
+            post_conditions: transaction.post_conditions.len(),
+            payload: PayloadSummary::from(&transaction.payload),
+            transaction: hex::encode(transaction.serialize()?),
+        })
+    }
+
+    pub fn to_json(&self) -> Result<String, TransactionError> {
+        serde_json::to_string_pretty(self).map_err(|err| TransactionError::InvalidEnvelope(err.to_string()))
+    }
+
+    pub fn from_json(json: &str) -> Result<Self, TransactionError> {
+        let envelope: TransactionEnvelope = serde_json::from_str(json).map_err(|err| TransactionError::InvalidEnvelope(err.to_string()))?;
+        if envelope.version != ENVELOPE_VERSION {
+            return Err(TransactionError::InvalidEnvelope(format!("unsupported version {}", envelope.version)));
+        }
+        Ok(envelope)
+    }
+
+    /// The transaction of the envelope, checked against the other fields.
+    pub fn transaction(&self) -> Result<StacksTransaction, TransactionError> {
+        let transaction = StacksTransaction::from_hex(&self.transaction)?;
+        if TransactionEnvelope::new(&transaction)? != *self {
+            return Err(TransactionError::InvalidEnvelope(String::from("fields do not match the transaction")));
+        }
+        Ok(transaction)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use secp256k1::{PublicKey, SecretKey};
+
+    use crate::address::principal::Principal;
+    use crate::clarity::value::ClarityValue;
+    use crate::crypto::context::secp256k1_context;
+    use crate::transaction::builder::ContractCallBuilder;
+    use crate::transaction::signer::TransactionSigner;
+
+    use super::*;
+
+    fn keys() -> Vec<SecretKey> {
+        (1..=3).map(|i| SecretKey::from_byte_array(&[i; 32]).unwrap()).collect()
+    }
+
+    fn transaction() -> StacksTransaction {
+        let public_keys: Vec<PublicKey> = keys().iter().map(|key| key.public_key(secp256k1_context())).collect();
+        let contract = Principal::from_str("SP3FGQ8Z7JY9BWYZ5WM53E0M9NK7WHJF0691NZ159.pool").unwrap();
+        ContractCallBuilder::new(contract, "join", vec![ClarityValue::UInt(100)])
+            .nonce(4)
+            .fee(3000)
+            .build_multi_sig(&public_keys, 2)
+            .unwrap()
+    }
+
+    #[test]
+    fn test_partially_signed_round_trip() {
+        let mut signer = TransactionSigner::new(transaction()).unwrap();
+        signer.sign_with(&keys()[0]).unwrap();
+        let partial = signer.transaction().clone();
+
+        let envelope = TransactionEnvelope::from_json(&TransactionEnvelope::new(&partial).unwrap().to_json().unwrap()).unwrap();
+        assert_eq!((envelope.nonce, envelope.fee, envelope.network), (4, 3000, NetworkKind::Mainnet));
+        assert_eq!((envelope.signatures, envelope.signatures_required), (1, 2));
+        let function_args = vec![String::from("u100")];
+        let expected = PayloadSummary::ContractCall {
+            contract: String::from("SP3FGQ8Z7JY9BWYZ5WM53E0M9NK7WHJF0691NZ159.pool"),
+            function_name: String::from("join"),
+            function_args,
+        };
+        assert_eq!(envelope.payload, expected);
+
+        // the offline machine adds the second signature
+        let mut signer = TransactionSigner::new(envelope.transaction().unwrap()).unwrap();
+        signer.sign_with(&keys()[1]).unwrap();
+        signer.append_pubkey(&keys()[2].public_key(secp256k1_context())).unwrap();
+        let back = TransactionEnvelope::new(&signer.finish().unwrap()).unwrap();
+        assert_eq!(back.signatures, 2);
+        assert!(back.transaction().unwrap().verify().is_ok());
+    }
+
+    #[test]
+    fn test_tampered_envelope() {
+        let mut envelope = TransactionEnvelope::new(&transaction()).unwrap();
+        envelope.fee = 30;
+        assert!(matches!(envelope.transaction(), Err(TransactionError::InvalidEnvelope(_))));
+
+        let json = TransactionEnvelope::new(&transaction()).unwrap().to_json().unwrap().replace("\"version\": 1", "\"version\": 2");
+        assert!(matches!(TransactionEnvelope::from_json(&json), Err(TransactionError::InvalidEnvelope(_))));
+        assert!(matches!(TransactionEnvelope::from_json("{}"), Err(TransactionError::InvalidEnvelope(_))));
+    }
+}
@@ -0,0 +1,81 @@
+//! Fee strategies of the transaction builders.
+
+use super::stacks_transaction::StacksTransaction;
+use super::TransactionError;
+
+/// Which of the node's estimates to pay.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FeePriority {
+    Low,
+    Medium,
+    High,
+}
+
+/// Low, medium and high fees estimated by a node for a transaction, in micro-STX.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct FeeEstimates {
+    pub low: u64,
+    pub medium: u64,
+    pub high: u64,
+}
+
+impl FeeEstimates {
+    pub fn fee(&self, priority: FeePriority) -> u64 {
+        match priority {
+            FeePriority::Low => self.low,
+            FeePriority::Medium => self.medium,
+            FeePriority::High => self.high,
+        }
+    }
+
+    pub fn low(self) -> FeeStrategy {
+        FeeStrategy::Estimate(self, FeePriority::Low)
+    }
+
+    pub fn medium(self) -> FeeStrategy {
+        FeeStrategy::Estimate(self, FeePriority::Medium)
+    }
+
+    pub fn high(self) -> FeeStrategy {
+        FeeStrategy::Estimate(self, FeePriority::High)
+    }
+}
+
+/// How a builder sets the fee of the transaction.
+///
+/// Usage:
+/// ```rust
+/// use std::str::FromStr;
+/// use secp256k1::PublicKey;
+/// use stacks_rs::address::principal::Principal;
+/// use stacks_rs::transaction::builder::ContractCallBuilder;
+/// use stacks_rs::transaction::fee::{FeeEstimates, FeeStrategy};
+/// let contract = Principal::from_str("SP3FGQ8Z7JY9BWYZ5WM53E0M9NK7WHJF0691NZ159.pool").unwrap();
+/// let public_key = PublicKey::from_str("03ef788b3830c00abe8f64f62dc32fc863bc0b2cafeb073b6c8e1c7657d9c2c3ab").unwrap();
+/// let estimates = FeeEstimates { low: 1000, medium: 2000, high: 5000 };
+/// let transaction = ContractCallBuilder::new(contract, "leave", vec![])
+///     .fee_strategy(estimates.medium())
+///     .build(&public_key)
+///     .unwrap();
+/// assert_eq!(transaction.auth.origin().fee(), 2000);
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FeeStrategy {
+    /// This fee, in micro-STX
+    Fixed(u64),
+    /// Micro-STX per byte of the signed transaction
+    Rate(u64),
+    /// One of the fees estimated by a node
+    Estimate(FeeEstimates, FeePriority),
+}
+
+impl FeeStrategy {
+    /// The fee of `transaction` under this strategy.
+    pub fn fee(&self, transaction: &StacksTransaction) -> Result<u64, TransactionError> {
+        match self {
+            FeeStrategy::Fixed(fee) => Ok(*fee),
+            FeeStrategy::Rate(fee_rate) => transaction.fee_for_rate(*fee_rate),
+            FeeStrategy::Estimate(estimates, priority) => Ok(estimates.fee(*priority)),
+        }
+    }
+}
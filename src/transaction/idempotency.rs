@@ -0,0 +1,129 @@
+//! Idempotency keys of transactions, guarding retry loops against duplicate broadcasts.
+
+use std::collections::HashMap;
+use std::fmt;
+use std::time::{Duration, Instant};
+
+use crate::crypto::hash::{Hasher, Sha256};
+
+use super::codec::Codec;
+use super::stacks_transaction::StacksTransaction;
+use super::TransactionError;
+
+/// Deterministic key of a transaction: the SHA256 of its payload, origin nonce and origin.
+///
+/// Two transactions with the same key do the same thing, so broadcasting both is a duplicate
+/// send (e.g. a retry loop rebuilding and re-signing a transaction that already went through).
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct IdempotencyKey(pub [u8; 32]);
+
+impl fmt::Display for IdempotencyKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        f.write_str(&hex::encode(self.0))
+    }
+}
+
+impl StacksTransaction {
+    /// The [`IdempotencyKey`] of the transaction, the same whatever its fee and signatures.
+    pub fn idempotency_key(&self) -> Result<IdempotencyKey, TransactionError> {
+        let mut payload = Vec::new();
+        self.payload.encode(&mut payload)?;
+        let origin = self.auth.origin();
+        let mut hasher = Sha256::default();
+        hasher.update(&payload).update(&origin.nonce().to_be_bytes()).update(origin.signer());
+        Ok(IdempotencyKey(hasher.finalize()))
+    }
+}
+
+/// Remembers the keys of the broadcast transactions and refuses to broadcast the same key
+/// again within `window`; the RPC clients take it in `broadcast_once`.
+///
+/// Usage:
+/// ```rust
+/// use std::time::Duration;
+/// use stacks_rs::transaction::idempotency::{IdempotencyGuard, IdempotencyKey};
+/// let mut guard = IdempotencyGuard::new(Duration::from_secs(600));
+/// let key = IdempotencyKey([1u8; 32]);
+/// assert!(guard.check_and_record(key).is_ok());
+/// assert!(guard.check_and_record(key).is_err());
+/// ```
+#[derive(Clone, Debug)]
+pub struct IdempotencyGuard {
+    window: Duration,
+    seen: HashMap<IdempotencyKey, Instant>,
+}
+
+impl IdempotencyGuard {
+    pub fn new(window: Duration) -> Self {
+        IdempotencyGuard { window, seen: HashMap::new() }
+    }
+
+    /// Records `key` as broadcast now, or returns [`TransactionError::Duplicate`] if it was
+    /// already broadcast within the window.
+    pub fn check_and_record(&mut self, key: IdempotencyKey) -> Result<(), TransactionError> {
+        self.check_and_record_at(key, Instant::now())
+    }
+
+    pub fn check_and_record_at(&mut self, key: IdempotencyKey, now: Instant) -> Result<(), TransactionError> {
+        self.prune(now);
+        if let Some(broadcast_at) = self.seen.get(&key) {
+            return Err(TransactionError::Duplicate(key, now.duration_since(*broadcast_at)));
+        }
+        self.seen.insert(key, now);
+        Ok(())
+    }
+
+    /// Forgets `key`, e.g. when its broadcast failed and it can be safely retried.
+    pub fn forget(&mut self, key: &IdempotencyKey) {
+        self.seen.remove(key);
+    }
+
+    /// Drops the keys older than the window.
+    fn prune(&mut self, now: Instant) {
+        let window = self.window;
+        self.seen.retain(|_, broadcast_at| now.duration_since(*broadcast_at) < window);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use secp256k1::SecretKey;
+
+    use crate::address::principal::Principal;
+    use crate::crypto::context::secp256k1_context;
+    use crate::transaction::builder::TransactionBatch;
+    use crate::transaction::fee::FeeStrategy;
+
+    use super::*;
+
+    fn transfer(amount: u64, nonce: u64, fee: u64) -> StacksTransaction {
+        let recipient = Principal::from_str("SP3FGQ8Z7JY9BWYZ5WM53E0M9NK7WHJF0691NZ159").unwrap();
+        let public_key = SecretKey::from_byte_array(&[2; 32]).unwrap().public_key(secp256k1_context());
+        let batch = TransactionBatch::new().nonce(nonce).fee_strategy(FeeStrategy::Fixed(fee)).transfer(recipient, amount, "test memo").unwrap();
+        batch.build(&public_key).unwrap().remove(0)
+    }
+
+    #[test]
+    fn test_idempotency_key() {
+        let key = transfer(10000, 1, 180).idempotency_key().unwrap();
+        assert_eq!(key, transfer(10000, 1, 300).idempotency_key().unwrap());
+        assert_ne!(key, transfer(10000, 2, 180).idempotency_key().unwrap());
+        assert_ne!(key, transfer(10001, 1, 180).idempotency_key().unwrap());
+    }
+
+    #[test]
+    fn test_idempotency_guard_window() {
+        let mut guard = IdempotencyGuard::new(Duration::from_secs(60));
+        let key = transfer(10000, 1, 180).idempotency_key().unwrap();
+        let start = Instant::now();
+
+        assert!(guard.check_and_record_at(key, start).is_ok());
+        assert_eq!(guard.check_and_record_at(key, start + Duration::from_secs(30)), Err(TransactionError::Duplicate(key, Duration::from_secs(30))));
+        assert!(guard.check_and_record_at(key, start + Duration::from_secs(60)).is_ok());
+
+        guard.forget(&key);
+        assert!(guard.check_and_record_at(key, start + Duration::from_secs(61)).is_ok());
+    }
+}
@@ -0,0 +1,81 @@
+//! Client-side metadata of built transactions, never encoded on the wire.
+
+use super::TransactionError;
+
+/// When a built transaction should no longer be broadcast.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ExpiryPolicy {
+    /// The transaction never expires
+    #[default]
+    Never,
+    /// The transaction expires once the chain is this many blocks past its build height
+    AfterBlocks(u64),
+}
+
+/// Build height and expiry of a transaction, checked by the RPC clients before broadcasting
+/// it so a stale payload is not replayed.
+///
+/// Usage:
+/// ```rust
+/// use stacks_rs::transaction::metadata::{ExpiryPolicy, TransactionMetadata};
+/// let metadata = TransactionMetadata::new(100, ExpiryPolicy::AfterBlocks(6));
+/// assert!(metadata.check_fresh(106).is_ok());
+/// assert!(metadata.is_stale(107));
+/// ```
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct TransactionMetadata {
+    /// Stacks block height the transaction was built at
+    pub build_height: Option<u64>,
+    pub expiry: ExpiryPolicy,
+}
+
+impl TransactionMetadata {
+    pub fn new(build_height: u64, expiry: ExpiryPolicy) -> Self {
+        TransactionMetadata { build_height: Some(build_height), expiry }
+    }
+
+    /// Whether the transaction expires at some height: it has a build height and an expiry.
+    pub fn can_expire(&self) -> bool {
+        self.build_height.is_some() && self.expiry != ExpiryPolicy::Never
+    }
+
+    /// Returns `true` if the transaction expired at `current_height`.
+    /// Transactions without a build height never expire.
+    pub fn is_stale(&self, current_height: u64) -> bool {
+        self.check_fresh(current_height).is_err()
+    }
+
+    /// Returns [`TransactionError::Stale`] if the transaction expired at `current_height`.
+    pub fn check_fresh(&self, current_height: u64) -> Result<(), TransactionError> {
+        match (self.build_height, self.expiry) {
+            (Some(build_height), ExpiryPolicy::AfterBlocks(max_age)) if current_height.saturating_sub(build_height) > max_age => {
+                Err(TransactionError::Stale { build_height, current_height, max_age })
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_transaction_expiry() {
+        let metadata = TransactionMetadata::new(100, ExpiryPolicy::AfterBlocks(6));
+        assert!(metadata.can_expire());
+        assert!(!metadata.is_stale(100));
+        assert!(!metadata.is_stale(106));
+        assert_eq!(metadata.check_fresh(107), Err(TransactionError::Stale { build_height: 100, current_height: 107, max_age: 6 }));
+    }
+
+    #[test]
+    fn test_transaction_without_expiry() {
+        let never = TransactionMetadata::new(100, ExpiryPolicy::Never);
+        assert!(!never.can_expire());
+        assert!(!never.is_stale(1_000_000));
+        let no_height = TransactionMetadata { build_height: None, expiry: ExpiryPolicy::AfterBlocks(1) };
+        assert!(!no_height.can_expire());
+        assert!(!no_height.is_stale(1_000_000));
+    }
+}
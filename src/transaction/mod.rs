@@ -0,0 +1,169 @@
+//! Stacks transactions and their wire format (SIP-005).
+//!
+//! Usage:
+//! ```rust
+//! use stacks_rs::transaction::stacks_transaction::StacksTransaction;
+//! let hex = "0000000001040015c31b8c1c11c515e244b75806bac48d1399c775000000000000000000000000000000000000\
+//!     8b316d56e35b3b8d03ab3b9dbe05eb44d64c53e7ba3c468f9a78c82a13f2174c32facb0f29faeb21075ec933db935ebc28a8793cc60e14b8ee4ef05f52c94016\
+//!     030200000000000516df0ba3e79792be7be5e50a370289accfc8c9e032000000000000303974657374206d656d6f00000000000000000000000000000000000000000000000000";
+//! let transaction = StacksTransaction::from_hex(hex).unwrap();
+//! assert_eq!(transaction.chain_id, 1);
+//! assert_eq!(transaction.to_hex().unwrap(), hex);
+//! ```
+
+use std::fmt;
+use std::time::Duration;
+
+use crate::clarity::ClarityError;
+
+/// One-byte enumeration of the wire format, with its `value`, `from_value` and codec.
+macro_rules! byte_enum {
+    ($(#[$meta:meta])* $name:ident, $field:literal { $($(#[$variant_meta:meta])* $variant:ident = $value:literal),+ $(,)? }) => {
+        $(#[$meta])*
+        #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+        pub enum $name {
+            $($(#[$variant_meta])* $variant),+
+        }
+
+        impl $name {
+            pub fn value(&self) -> u8 {
+                match self {
+                    $($name::$variant => $value),+
+                }
+            }
+
+            pub fn from_value(value: u8) -> Option<Self> {
+                match value {
+                    $($value => Some($name::$variant),)+
+                    _ => None,
+                }
+            }
+        }
+
+        impl $crate::transaction::codec::Codec for $name {
+            fn encode(&self, bytes: &mut Vec<u8>) -> Result<(), TransactionError> {
+                bytes.push(self.value());
+                Ok(())
+            }
+
+            fn decode(reader: &mut $crate::transaction::codec::Reader<'_>) -> Result<Self, TransactionError> {
+                let value = reader.u8()?;
+                Self::from_value(value).ok_or(TransactionError::InvalidByte($field, value))
+            }
+        }
+    };
+}
+
+pub mod asset;
+pub mod auth;
+pub mod builder;
+pub(crate) mod codec;
+pub mod envelope;
+pub mod fee;
+pub mod idempotency;
+pub mod metadata;
+pub mod nonce;
+pub mod payload;
+pub mod post_condition;
+pub mod signer;
+pub mod stacks_transaction;
+
+/// Length of the memo of a token transfer, zero-padded.
+pub const MEMO_LENGTH: usize = 34;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum TransactionError {
+    /// The input ends in the middle of a transaction
+    UnexpectedEnd,
+    /// Bytes left after a complete transaction
+    TrailingBytes(usize),
+    /// Unknown value of a one-byte field: the field and the byte
+    InvalidByte(&'static str, u8),
+    InvalidAddress(String),
+    InvalidPublicKey,
+    InvalidContractName(String),
+    /// Invalid function or asset name
+    InvalidName(String),
+    /// Contract source must be printable ASCII
+    InvalidCodeBody,
+    MemoTooLong(usize),
+    /// Not a public or read-only function of the contract interface
+    UnknownFunction(String),
+    InvalidHex(String),
+    /// The signing key, or the multisig keys in their order, do not hash to the signer
+    SignerMismatch,
+    /// The spending condition has all the signatures it requires
+    AlreadySigned,
+    /// The public key already signed or was appended
+    DuplicateKey,
+    /// A signature from which no public key can be recovered
+    InvalidSignature,
+    /// Public keys are only appended to multisig spending conditions
+    NotMultiSig,
+    /// Only a sponsored transaction has a sponsor to sign
+    NotSponsored,
+    /// Signatures required and keys of a multisig account, not `1 <= required <= keys <= 16`
+    InvalidThreshold(u16, usize),
+    /// Signatures present and required
+    MissingSignatures(usize, usize),
+    /// Signatures present and required
+    TooManySignatures(usize, usize),
+    /// Fee asked for and fee of the transaction it replaces
+    FeeTooLow(u64, u64),
+    /// The sponsor pays the fee of a sponsored transaction
+    Sponsored,
+    /// An envelope that is not JSON, or whose fields do not match its transaction
+    InvalidEnvelope(String),
+    /// A Clarity value of the payload or of a post-condition
+    Clarity(ClarityError),
+    /// The transaction expired, see [`metadata::TransactionMetadata`]
+    Stale { build_height: u64, current_height: u64, max_age: u64 },
+    /// More decimals than an amount can have, see [`asset::MAX_DECIMALS`]
+    InvalidDecimals(u8),
+    /// The transaction was already broadcast, this long ago
+    Duplicate(idempotency::IdempotencyKey, Duration),
+}
+
+impl fmt::Display for TransactionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        match self {
+            TransactionError::UnexpectedEnd => f.write_str("Unexpected end of transaction"),
+            TransactionError::TrailingBytes(v) => f.write_str(&format!("{v} trailing bytes after transaction")),
+            TransactionError::InvalidByte(field, v) => f.write_str(&format!("Invalid {field} {v:#04x}")),
+            TransactionError::InvalidAddress(v) => f.write_str(&format!("Invalid address: {v}")),
+            TransactionError::InvalidPublicKey => f.write_str("Invalid public key"),
+            TransactionError::InvalidContractName(v) => f.write_str(&format!("Invalid contract name {v:?}")),
+            TransactionError::InvalidName(v) => f.write_str(&format!("Invalid name {v:?}")),
+            TransactionError::InvalidCodeBody => f.write_str("Contract source is not printable ASCII"),
+            TransactionError::MemoTooLong(v) => f.write_str(&format!("Memo too long ({v} bytes, max is {MEMO_LENGTH})")),
+            TransactionError::UnknownFunction(v) => f.write_str(&format!("No callable function {v:?} in contract interface")),
+            TransactionError::InvalidHex(v) => f.write_str(&format!("Invalid hex: {v}")),
+            TransactionError::SignerMismatch => f.write_str("Public keys do not match the signer of the spending condition"),
+            TransactionError::AlreadySigned => f.write_str("Spending condition already has all its signatures"),
+            TransactionError::DuplicateKey => f.write_str("Public key already in the spending condition"),
+            TransactionError::InvalidSignature => f.write_str("Invalid signature"),
+            TransactionError::NotMultiSig => f.write_str("Not a multisig spending condition"),
+            TransactionError::NotSponsored => f.write_str("Not a sponsored transaction"),
+            TransactionError::InvalidThreshold(required, keys) => f.write_str(&format!("Invalid multisig threshold: {required} of {keys} keys")),
+            TransactionError::MissingSignatures(v, required) => f.write_str(&format!("{v} of {required} signatures")),
+            TransactionError::TooManySignatures(v, required) => f.write_str(&format!("{v} signatures where {required} are required")),
+            TransactionError::FeeTooLow(v, fee) => f.write_str(&format!("Fee {v} is not higher than the current fee {fee}")),
+            TransactionError::Sponsored => f.write_str("The sponsor pays the fee of a sponsored transaction"),
+            TransactionError::InvalidEnvelope(v) => f.write_str(&format!("Invalid transaction envelope: {v}")),
+            TransactionError::Clarity(v) => f.write_str(&format!("Invalid Clarity value: {v}")),
+            TransactionError::Stale { build_height, current_height, max_age } => f.write_str(&format!(
+                "Stale transaction: built at height {build_height}, current height is {current_height}, max age is {max_age} blocks"
+            )),
+            TransactionError::InvalidDecimals(v) => f.write_str(&format!("Invalid decimals {v}, max is {}", asset::MAX_DECIMALS)),
+            TransactionError::Duplicate(key, elapsed) => f.write_str(&format!("Duplicate transaction {key}, already broadcast {}s ago", elapsed.as_secs())),
+        }
+    }
+}
+
+impl std::error::Error for TransactionError {}
+
+impl From<ClarityError> for TransactionError {
+    fn from(value: ClarityError) -> Self {
+        TransactionError::Clarity(value)
+    }
+}
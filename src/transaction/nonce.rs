@@ -0,0 +1,192 @@
+//! Nonces of the transactions being built, per address.
+
+use std::collections::{BTreeSet, HashMap};
+use std::sync::Mutex;
+
+/// Most transactions of an address the node chains in its mempool; nonces further than this
+/// past the account nonce cannot be mined before the ones below, so they are not tracked.
+pub const MAX_NONCE_GAP: u64 = 25;
+
+/// Nonces of one address.
+#[derive(Clone, Debug, Default)]
+struct AddressNonces {
+    /// Next nonce never handed out
+    next: u64,
+    /// Handed out, and neither confirmed nor rejected
+    pending: BTreeSet<u64>,
+    /// Below `next` but free, handed out again first
+    free: BTreeSet<u64>,
+}
+
+/// Hands out sequential nonces per address, safely from several threads.
+///
+/// The manager starts from the nonces reported by the node ([`NonceManager::reconcile`]) and
+/// takes back the nonce of a rejected transaction ([`NonceManager::release`]), so the next
+/// transaction fills the gap instead of leaving the following ones stuck in the mempool.
+/// `StacksApiClient::reconcile_nonces` reconciles an address with the nonces the API sees.
+///
+/// Usage:
+/// ```rust
+/// use stacks_rs::transaction::nonce::NonceManager;
+/// let manager = NonceManager::new();
+/// let address = "SP3FGQ8Z7JY9BWYZ5WM53E0M9NK7WHJF0691NZ159";
+/// manager.reconcile(address, 5, []);
+/// assert_eq!(manager.next_nonce(address), 5);
+/// assert_eq!(manager.next_nonce(address), 6);
+/// manager.release(address, 5);
+/// assert_eq!(manager.next_nonce(address), 5);
+/// assert_eq!(manager.next_nonce(address), 7);
+/// ```
+#[derive(Debug, Default)]
+pub struct NonceManager {
+    addresses: Mutex<HashMap<String, AddressNonces>>,
+}
+
+impl NonceManager {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The lowest free nonce of `address`, 0 for an address the manager knows nothing of.
+    pub fn next_nonce(&self, address: &str) -> u64 {
+        let mut addresses = self.addresses.lock().expect("nonce manager lock poisoned");
+        let nonces = addresses.entry(address.to_string()).or_default();
+        let nonce = match nonces.free.pop_first() {
+            Some(nonce) => nonce,
+            None => {
+                let nonce = nonces.next;
+                nonces.next = nonces.next.saturating_add(1);
+                nonce
+            }
+        };
+        nonces.pending.insert(nonce);
+        nonce
+    }
+
+    /// Last nonce handed out for `address`, if any.
+    pub fn last_used(&self, address: &str) -> Option<u64> {
+        let addresses = self.addresses.lock().expect("nonce manager lock poisoned");
+        addresses.get(address).and_then(|nonces| nonces.pending.last().copied())
+    }
+
+    /// Marks the transaction of `nonce` as accepted by the node.
+    pub fn confirm(&self, address: &str, nonce: u64) {
+        let mut addresses = self.addresses.lock().expect("nonce manager lock poisoned");
+        if let Some(nonces) = addresses.get_mut(address) {
+            nonces.pending.remove(&nonce);
+        }
+    }
+
+    /// Takes back `nonce`, e.g. after the node rejected its transaction.
+    pub fn release(&self, address: &str, nonce: u64) {
+        let mut addresses = self.addresses.lock().expect("nonce manager lock poisoned");
+        if let Some(nonces) = addresses.get_mut(address) {
+            if nonces.pending.remove(&nonce) && nonce < nonces.next {
+                nonces.free.insert(nonce);
+            }
+        }
+    }
+
+    /// Aligns `address` with the node: `account_nonce` is the next nonce of its account and
+    /// `mempool_nonces` those of its transactions waiting in the mempool.
+    ///
+    /// Nonces below `account_nonce` are forgotten, nonces in the mempool are no longer handed
+    /// out, and the ones missing from both the mempool and the pending transactions become free.
+    /// Only the [`MAX_NONCE_GAP`] nonces from `account_nonce` are kept, the next nonce handed
+    /// out is at most the last of them.
+    pub fn reconcile(&self, address: &str, account_nonce: u64, mempool_nonces: impl IntoIterator<Item = u64>) {
+        let window = account_nonce..account_nonce.saturating_add(MAX_NONCE_GAP);
+        let mempool: BTreeSet<u64> = mempool_nonces.into_iter().filter(|nonce| window.contains(nonce)).collect();
+        let mut addresses = self.addresses.lock().expect("nonce manager lock poisoned");
+        let nonces = addresses.entry(address.to_string()).or_default();
+        let mempool_next = mempool.last().and_then(|nonce| nonce.checked_add(1)).unwrap_or(account_nonce);
+        nonces.next = nonces.next.max(mempool_next).clamp(window.start, window.end);
+        nonces.pending.retain(|nonce| window.contains(nonce) && *nonce < nonces.next && !mempool.contains(nonce));
+        nonces.free = (account_nonce..nonces.next).filter(|nonce| !mempool.contains(nonce) && !nonces.pending.contains(nonce)).collect();
+    }
+
+    /// Forgets everything about `address`, to start over from the node.
+    pub fn reset(&self, address: &str) {
+        self.addresses.lock().expect("nonce manager lock poisoned").remove(address);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+    use std::thread;
+
+    use super::*;
+
+    const ADDRESS: &str = "SP3FGQ8Z7JY9BWYZ5WM53E0M9NK7WHJF0691NZ159";
+
+    #[test]
+    fn test_concurrent_nonces() {
+        let manager = Arc::new(NonceManager::new());
+        manager.reconcile(ADDRESS, 10, []);
+        let handles: Vec<_> = (0..8)
+            .map(|_| {
+                let manager = Arc::clone(&manager);
+                thread::spawn(move || (0..25).map(|_| manager.next_nonce(ADDRESS)).collect::<Vec<_>>())
+            })
+            .collect();
+        let mut nonces: Vec<u64> = handles.into_iter().flat_map(|handle| handle.join().unwrap()).collect();
+        nonces.sort();
+        assert_eq!(nonces, (10..210).collect::<Vec<_>>());
+        assert_eq!(manager.last_used(ADDRESS), Some(209));
+        assert_eq!(manager.next_nonce("SP000000000000000000002Q6VF78"), 0);
+    }
+
+    #[test]
+    fn test_release_rejected() {
+        let manager = NonceManager::new();
+        let nonces: Vec<u64> = (0..4).map(|_| manager.next_nonce(ADDRESS)).collect();
+        assert_eq!(nonces, [0, 1, 2, 3]);
+        manager.confirm(ADDRESS, 0);
+        manager.release(ADDRESS, 2);
+        manager.release(ADDRESS, 1);
+        // released twice, or never handed out
+        manager.release(ADDRESS, 1);
+        manager.release(ADDRESS, 9);
+        assert_eq!(manager.next_nonce(ADDRESS), 1);
+        assert_eq!(manager.next_nonce(ADDRESS), 2);
+        assert_eq!(manager.next_nonce(ADDRESS), 4);
+    }
+
+    #[test]
+    fn test_reconcile() {
+        let manager = NonceManager::new();
+        (0..3).for_each(|_| {
+            manager.next_nonce(ADDRESS);
+        });
+        // 2 still pending here, 4 and 6 sent from elsewhere: 3 and 5 are gaps
+        manager.reconcile(ADDRESS, 2, [1, 4, 6]);
+        assert_eq!(manager.next_nonce(ADDRESS), 3);
+        assert_eq!(manager.next_nonce(ADDRESS), 5);
+        assert_eq!(manager.next_nonce(ADDRESS), 7);
+
+        // the node is ahead of the manager
+        manager.reconcile(ADDRESS, 20, []);
+        assert_eq!(manager.next_nonce(ADDRESS), 20);
+        assert_eq!(manager.last_used(ADDRESS), Some(20));
+
+        manager.reset(ADDRESS);
+        assert_eq!(manager.last_used(ADDRESS), None);
+        assert_eq!(manager.next_nonce(ADDRESS), 0);
+    }
+
+    #[test]
+    fn test_reconcile_bounds() {
+        let manager = NonceManager::new();
+        manager.reconcile(ADDRESS, u64::MAX - 1, [u64::MAX - 1, u64::MAX]);
+        assert_eq!(manager.next_nonce(ADDRESS), u64::MAX);
+
+        // far away mempool nonces, and a node going back, leave at most MAX_NONCE_GAP nonces
+        manager.reconcile(ADDRESS, 1 << 60, [u64::MAX]);
+        assert_eq!(manager.next_nonce(ADDRESS), 1 << 60);
+        manager.reconcile(ADDRESS, 0, [3]);
+        let nonces: Vec<u64> = (0..MAX_NONCE_GAP).map(|_| manager.next_nonce(ADDRESS)).collect();
+        assert_eq!(nonces, (0..=MAX_NONCE_GAP).filter(|nonce| *nonce != 3).collect::<Vec<_>>());
+        assert_eq!(manager.last_used(ADDRESS), Some(MAX_NONCE_GAP));
+    }
+}
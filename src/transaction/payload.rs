@@ -0,0 +1,317 @@
+//! What a transaction does: transfer STX, call a contract or deploy one, and the payloads of
+//! miners and signers (coinbase, tenure change, poison microblock).
+
+use crate::address::principal::Principal;
+use crate::address::stacks_address::StacksAddress;
+use crate::clarity::value::{is_valid_clarity_name, ClarityValue};
+
+use super::auth::MessageSignature;
+use super::codec::{encode_address, encode_name, Codec, Reader};
+use super::{TransactionError, MEMO_LENGTH};
+
+byte_enum!(ClarityVersion, "Clarity version" {
+    Clarity1 = 0x01,
+    Clarity2 = 0x02,
+    Clarity3 = 0x03,
+});
+
+byte_enum!(PayloadType, "payload type" {
+    TokenTransfer = 0x00,
+    SmartContract = 0x01,
+    ContractCall = 0x02,
+    PoisonMicroblock = 0x03,
+    Coinbase = 0x04,
+    CoinbaseToAltRecipient = 0x05,
+    VersionedSmartContract = 0x06,
+    TenureChange = 0x07,
+    NakamotoCoinbase = 0x08,
+});
+
+byte_enum!(TenureChangeCause, "tenure change cause" {
+    /// A new tenure began with a winning block-commit
+    BlockFound = 0x00,
+    /// The current tenure goes on with a fresh budget
+    Extended = 0x01,
+});
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TokenTransferPayload {
+    pub recipient: Principal,
+    /// In micro-STX
+    pub amount: u64,
+    /// Zero-padded
+    pub memo: [u8; MEMO_LENGTH],
+}
+
+impl TokenTransferPayload {
+    pub fn new(recipient: Principal, amount: u64, memo: &str) -> Result<Self, TransactionError> {
+        if memo.len() > MEMO_LENGTH {
+            return Err(TransactionError::MemoTooLong(memo.len()));
+        }
+        let mut padded = [0u8; MEMO_LENGTH];
+        padded[..memo.len()].copy_from_slice(memo.as_bytes());
+        Ok(TokenTransferPayload { recipient, amount, memo: padded })
+    }
+
+    /// The memo without its padding, if it is text.
+    pub fn memo_str(&self) -> Option<&str> {
+        std::str::from_utf8(&self.memo).ok().map(|memo| memo.trim_end_matches('\0'))
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ContractCallPayload {
+    pub contract_address: StacksAddress,
+    pub contract_name: String,
+    pub function_name: String,
+    pub function_args: Vec<ClarityValue>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SmartContractPayload {
+    pub contract_name: String,
+    pub code_body: String,
+    /// Written as a versioned deploy when set, the node's default version applies otherwise
+    pub clarity_version: Option<ClarityVersion>,
+}
+
+/// Header of a microblock, as reported by a poison-microblock transaction.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MicroblockHeader {
+    pub version: u8,
+    pub sequence: u16,
+    pub prev_block: [u8; 32],
+    pub tx_merkle_root: [u8; 32],
+    pub signature: MessageSignature,
+}
+
+/// Coinbase of a block: its type depends on which of `recipient` and `vrf_proof` are set.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CoinbasePayload {
+    pub data: [u8; 32],
+    /// Receives the reward instead of the miner
+    pub recipient: Option<Principal>,
+    /// Nakamoto coinbases carry the miner's VRF proof, see [`crate::crypto::vrf`]
+    pub vrf_proof: Option<[u8; 80]>,
+}
+
+/// Start or extension of a Nakamoto tenure, issued by the miner.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TenureChangePayload {
+    pub tenure_consensus_hash: [u8; 20],
+    pub prev_tenure_consensus_hash: [u8; 20],
+    pub burn_view_consensus_hash: [u8; 20],
+    /// Id of the last block of the previous tenure
+    pub previous_tenure_end: [u8; 32],
+    pub previous_tenure_blocks: u32,
+    pub cause: TenureChangeCause,
+    /// Hash160 of the miner's public key
+    pub pubkey_hash: [u8; 20],
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Payload {
+    TokenTransfer(TokenTransferPayload),
+    ContractCall(ContractCallPayload),
+    SmartContract(SmartContractPayload),
+    /// Two conflicting microblocks of the same parent, reported to slash their miner
+    PoisonMicroblock(MicroblockHeader, MicroblockHeader),
+    Coinbase(CoinbasePayload),
+    TenureChange(TenureChangePayload),
+}
+
+impl Payload {
+    pub fn payload_type(&self) -> PayloadType {
+        match self {
+            Payload::TokenTransfer(_) => PayloadType::TokenTransfer,
+            Payload::ContractCall(_) => PayloadType::ContractCall,
+            Payload::SmartContract(SmartContractPayload { clarity_version: None, .. }) => PayloadType::SmartContract,
+            Payload::SmartContract(_) => PayloadType::VersionedSmartContract,
+            Payload::PoisonMicroblock(..) => PayloadType::PoisonMicroblock,
+            Payload::Coinbase(CoinbasePayload { vrf_proof: Some(_), .. }) => PayloadType::NakamotoCoinbase,
+            Payload::Coinbase(CoinbasePayload { recipient: Some(_), .. }) => PayloadType::CoinbaseToAltRecipient,
+            Payload::Coinbase(_) => PayloadType::Coinbase,
+            Payload::TenureChange(_) => PayloadType::TenureChange,
+        }
+    }
+}
+
+/// Contract source: printable ASCII and whitespace.
+fn is_valid_code_body(code_body: &str) -> bool {
+    code_body.bytes().all(|byte| byte.is_ascii_graphic() || matches!(byte, b' ' | b'\t' | b'\r' | b'\n'))
+}
+
+fn check_contract_name(contract_name: &str) -> Result<(), TransactionError> {
+    match Principal::is_valid_contract_name(contract_name) {
+        true => Ok(()),
+        false => Err(TransactionError::InvalidContractName(contract_name.to_string())),
+    }
+}
+
+impl Codec for MicroblockHeader {
+    fn encode(&self, bytes: &mut Vec<u8>) -> Result<(), TransactionError> {
+        bytes.push(self.version);
+        bytes.extend(self.sequence.to_be_bytes());
+        bytes.extend(self.prev_block);
+        bytes.extend(self.tx_merkle_root);
+        self.signature.encode(bytes)
+    }
+
+    fn decode(reader: &mut Reader<'_>) -> Result<Self, TransactionError> {
+        Ok(MicroblockHeader {
+            version: reader.u8()?,
+            sequence: reader.u16()?,
+            prev_block: reader.array()?,
+            tx_merkle_root: reader.array()?,
+            signature: MessageSignature::decode(reader)?,
+        })
+    }
+}
+
+impl Codec for TenureChangePayload {
+    fn encode(&self, bytes: &mut Vec<u8>) -> Result<(), TransactionError> {
+        bytes.extend(self.tenure_consensus_hash);
+        bytes.extend(self.prev_tenure_consensus_hash);
+        bytes.extend(self.burn_view_consensus_hash);
+        bytes.extend(self.previous_tenure_end);
+        bytes.extend(self.previous_tenure_blocks.to_be_bytes());
+        self.cause.encode(bytes)?;
+        bytes.extend(self.pubkey_hash);
+        Ok(())
+    }
+
+    fn decode(reader: &mut Reader<'_>) -> Result<Self, TransactionError> {
+        Ok(TenureChangePayload {
+            tenure_consensus_hash: reader.array()?,
+            prev_tenure_consensus_hash: reader.array()?,
+            burn_view_consensus_hash: reader.array()?,
+            previous_tenure_end: reader.array()?,
+            previous_tenure_blocks: reader.u32()?,
+            cause: TenureChangeCause::decode(reader)?,
+            pubkey_hash: reader.array()?,
+        })
+    }
+}
+
+fn principal(value: ClarityValue) -> Result<Principal, TransactionError> {
+    match value {
+        ClarityValue::Principal(principal) => Ok(principal),
+        other => Err(TransactionError::InvalidAddress(format!("expected a principal, found {other}"))),
+    }
+}
+
+impl Codec for Payload {
+    fn encode(&self, bytes: &mut Vec<u8>) -> Result<(), TransactionError> {
+        self.payload_type().encode(bytes)?;
+        match self {
+            Payload::TokenTransfer(payload) => {
+                bytes.extend(ClarityValue::Principal(payload.recipient.clone()).serialize()?);
+                bytes.extend(payload.amount.to_be_bytes());
+                bytes.extend(payload.memo);
+            }
+            Payload::ContractCall(payload) => {
+                check_contract_name(&payload.contract_name)?;
+                if !is_valid_clarity_name(&payload.function_name) {
+                    return Err(TransactionError::InvalidName(payload.function_name.clone()));
+                }
+                encode_address(bytes, &payload.contract_address);
+                encode_name(bytes, &payload.contract_name);
+                encode_name(bytes, &payload.function_name);
+                bytes.extend((payload.function_args.len() as u32).to_be_bytes());
+                for arg in &payload.function_args {
+                    bytes.extend(arg.serialize()?);
+                }
+            }
+            Payload::SmartContract(payload) => {
+                check_contract_name(&payload.contract_name)?;
+                if !is_valid_code_body(&payload.code_body) {
+                    return Err(TransactionError::InvalidCodeBody);
+                }
+                if let Some(version) = payload.clarity_version {
+                    version.encode(bytes)?;
+                }
+                encode_name(bytes, &payload.contract_name);
+                bytes.extend((payload.code_body.len() as u32).to_be_bytes());
+                bytes.extend(payload.code_body.as_bytes());
+            }
+            Payload::PoisonMicroblock(first, second) => {
+                first.encode(bytes)?;
+                second.encode(bytes)?;
+            }
+            Payload::Coinbase(payload) => {
+                bytes.extend(payload.data);
+                let recipient = payload.recipient.clone().map(ClarityValue::Principal);
+                match (recipient, payload.vrf_proof) {
+                    (recipient, Some(vrf_proof)) => {
+                        bytes.extend(ClarityValue::from(recipient).serialize()?);
+                        bytes.extend(vrf_proof);
+                    }
+                    (Some(recipient), None) => bytes.extend(recipient.serialize()?),
+                    (None, None) => {}
+                }
+            }
+            Payload::TenureChange(payload) => payload.encode(bytes)?,
+        }
+        Ok(())
+    }
+
+    fn decode(reader: &mut Reader<'_>) -> Result<Self, TransactionError> {
+        let payload = match PayloadType::decode(reader)? {
+            PayloadType::TokenTransfer => {
+                let recipient = principal(reader.clarity_value()?)?;
+                Payload::TokenTransfer(TokenTransferPayload { recipient, amount: reader.u64()?, memo: reader.array()? })
+            }
+            PayloadType::ContractCall => {
+                let payload = ContractCallPayload {
+                    contract_address: reader.address()?,
+                    contract_name: reader.name()?,
+                    function_name: reader.name()?,
+                    function_args: {
+                        let count = reader.u32()? as usize;
+                        if count > reader.remaining() {
+                            return Err(TransactionError::UnexpectedEnd);
+                        }
+                        (0..count).map(|_| reader.clarity_value()).collect::<Result<_, _>>()?
+                    },
+                };
+                check_contract_name(&payload.contract_name)?;
+                if !is_valid_clarity_name(&payload.function_name) {
+                    return Err(TransactionError::InvalidName(payload.function_name));
+                }
+                Payload::ContractCall(payload)
+            }
+            payload_type @ (PayloadType::SmartContract | PayloadType::VersionedSmartContract) => {
+                let clarity_version = match payload_type {
+                    PayloadType::VersionedSmartContract => Some(ClarityVersion::decode(reader)?),
+                    _ => None,
+                };
+                let contract_name = reader.name()?;
+                check_contract_name(&contract_name)?;
+                let length = reader.u32()? as usize;
+                let code_body = String::from_utf8(reader.take(length)?.to_vec()).map_err(|_| TransactionError::InvalidCodeBody)?;
+                if !is_valid_code_body(&code_body) {
+                    return Err(TransactionError::InvalidCodeBody);
+                }
+                Payload::SmartContract(SmartContractPayload { contract_name, code_body, clarity_version })
+            }
+            PayloadType::PoisonMicroblock => Payload::PoisonMicroblock(MicroblockHeader::decode(reader)?, MicroblockHeader::decode(reader)?),
+            PayloadType::Coinbase => Payload::Coinbase(CoinbasePayload { data: reader.array()?, recipient: None, vrf_proof: None }),
+            PayloadType::CoinbaseToAltRecipient => Payload::Coinbase(CoinbasePayload {
+                data: reader.array()?,
+                recipient: Some(principal(reader.clarity_value()?)?),
+                vrf_proof: None,
+            }),
+            PayloadType::NakamotoCoinbase => {
+                let data = reader.array()?;
+                let recipient = match reader.clarity_value()? {
+                    ClarityValue::OptionalNone => None,
+                    ClarityValue::OptionalSome(recipient) => Some(principal(*recipient)?),
+                    other => return Err(TransactionError::InvalidAddress(format!("expected an optional principal, found {other}"))),
+                };
+                Payload::Coinbase(CoinbasePayload { data, recipient, vrf_proof: Some(reader.array()?) })
+            }
+            PayloadType::TenureChange => Payload::TenureChange(TenureChangePayload::decode(reader)?),
+        };
+        Ok(payload)
+    }
+}
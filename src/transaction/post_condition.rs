@@ -0,0 +1,414 @@
+//! Post-conditions: limits on the assets a transaction may move, checked by the node after
+//! execution.
+//!
+//! Usage:
+//! ```rust
+//! use std::str::FromStr;
+//! use stacks_rs::address::principal::Principal;
+//! use stacks_rs::clarity::value::ClarityValue;
+//! use stacks_rs::transaction::post_condition::{PostCondition, PostConditionPrincipal};
+//! let sender = Principal::from_str("SP3FGQ8Z7JY9BWYZ5WM53E0M9NK7WHJF0691NZ159").unwrap();
+//! let stx = PostCondition::stx(sender.clone()).will_send_lte(1000);
+//! let token = PostCondition::fungible(sender.clone(), "SP3FGQ8Z7JY9BWYZ5WM53E0M9NK7WHJF0691NZ159.token::gold".parse().unwrap())
+//!     .will_send_eq(50);
+//! let badge = PostCondition::non_fungible(PostConditionPrincipal::Origin, "SP3FGQ8Z7JY9BWYZ5WM53E0M9NK7WHJF0691NZ159.nft::badge".parse().unwrap(), ClarityValue::UInt(7))
+//!     .will_not_send();
+//! ```
+
+use std::fmt;
+use std::str::FromStr;
+
+use crate::address::principal::Principal;
+use crate::address::stacks_address::StacksAddress;
+use crate::clarity::value::{is_valid_clarity_name, ClarityValue};
+
+use super::codec::{encode_address, encode_name, Codec, Reader};
+use super::TransactionError;
+
+byte_enum!(PostConditionMode, "post-condition mode" {
+    /// Assets not covered by a post-condition may move
+    Allow = 0x01,
+    /// Only assets covered by a post-condition may move
+    Deny = 0x02,
+});
+
+byte_enum!(FungibleConditionCode, "fungible condition code" {
+    Equal = 0x01,
+    Greater = 0x02,
+    GreaterEqual = 0x03,
+    Less = 0x04,
+    LessEqual = 0x05,
+});
+
+byte_enum!(NonFungibleConditionCode, "non-fungible condition code" {
+    Sent = 0x10,
+    NotSent = 0x11,
+});
+
+/// The principal whose assets a post-condition is about.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum PostConditionPrincipal {
+    /// The origin account of the transaction
+    Origin,
+    Principal(Principal),
+}
+
+/// A fungible or non-fungible token: the contract defining it and its name.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct AssetInfo {
+    pub contract_address: StacksAddress,
+    pub contract_name: String,
+    pub asset_name: String,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum PostCondition {
+    Stx {
+        principal: PostConditionPrincipal,
+        condition_code: FungibleConditionCode,
+        amount: u64,
+    },
+    Fungible {
+        principal: PostConditionPrincipal,
+        asset: AssetInfo,
+        condition_code: FungibleConditionCode,
+        amount: u64,
+    },
+    NonFungible {
+        principal: PostConditionPrincipal,
+        asset: AssetInfo,
+        /// Identifier of the token, e.g. `u1`
+        asset_value: ClarityValue,
+        condition_code: NonFungibleConditionCode,
+    },
+}
+
+impl PostConditionPrincipal {
+    pub fn origin() -> Self {
+        PostConditionPrincipal::Origin
+    }
+
+    pub fn standard(address: StacksAddress) -> Self {
+        PostConditionPrincipal::Principal(Principal::Standard(address))
+    }
+
+    /// The contract `contract_name` deployed by `address`, e.g. to limit what a pool contract
+    /// sends on behalf of its users.
+    pub fn contract(address: StacksAddress, contract_name: &str) -> Result<Self, TransactionError> {
+        let principal = Principal::contract(address, contract_name).map_err(|_| TransactionError::InvalidContractName(contract_name.to_string()))?;
+        Ok(PostConditionPrincipal::Principal(principal))
+    }
+}
+
+impl From<Principal> for PostConditionPrincipal {
+    fn from(value: Principal) -> Self {
+        PostConditionPrincipal::Principal(value)
+    }
+}
+
+impl From<StacksAddress> for PostConditionPrincipal {
+    fn from(value: StacksAddress) -> Self {
+        PostConditionPrincipal::Principal(Principal::Standard(value))
+    }
+}
+
+impl fmt::Display for AssetInfo {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        f.write_str(&format!("{}.{}::{}", self.contract_address, self.contract_name, self.asset_name))
+    }
+}
+
+impl FromStr for AssetInfo {
+    type Err = TransactionError;
+
+    /// Parses `address.contract-name::asset-name`.
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let (contract, asset_name) = value.split_once("::").ok_or_else(|| TransactionError::InvalidName(value.to_string()))?;
+        let Principal::Contract(contract_address, contract_name) =
+            Principal::from_str(contract).map_err(|err| TransactionError::InvalidAddress(format!("{err}")))?
+        else {
+            return Err(TransactionError::InvalidAddress(format!("{contract} is not a contract")));
+        };
+        if !is_valid_clarity_name(asset_name) {
+            return Err(TransactionError::InvalidName(asset_name.to_string()));
+        }
+        Ok(AssetInfo { contract_address, contract_name, asset_name: asset_name.to_string() })
+    }
+}
+
+impl PostCondition {
+    /// Post-condition on the micro-STX sent by `principal`.
+    pub fn stx(principal: impl Into<PostConditionPrincipal>) -> StxCondition {
+        StxCondition { principal: principal.into() }
+    }
+
+    /// Post-condition on the amount of `asset` sent by `principal`.
+    pub fn fungible(principal: impl Into<PostConditionPrincipal>, asset: AssetInfo) -> FungibleCondition {
+        FungibleCondition { principal: principal.into(), asset }
+    }
+
+    /// Post-condition on whether `principal` sends the `asset` token identified by `asset_value`.
+    pub fn non_fungible(principal: impl Into<PostConditionPrincipal>, asset: AssetInfo, asset_value: ClarityValue) -> NonFungibleCondition {
+        NonFungibleCondition { principal: principal.into(), asset, asset_value }
+    }
+}
+
+/// Methods completing an amount post-condition with each condition code.
+macro_rules! amount_conditions {
+    () => {
+        pub fn will_send_eq(self, amount: u64) -> PostCondition {
+            self.with_code(FungibleConditionCode::Equal, amount)
+        }
+
+        pub fn will_send_gt(self, amount: u64) -> PostCondition {
+            self.with_code(FungibleConditionCode::Greater, amount)
+        }
+
+        pub fn will_send_gte(self, amount: u64) -> PostCondition {
+            self.with_code(FungibleConditionCode::GreaterEqual, amount)
+        }
+
+        pub fn will_send_lt(self, amount: u64) -> PostCondition {
+            self.with_code(FungibleConditionCode::Less, amount)
+        }
+
+        pub fn will_send_lte(self, amount: u64) -> PostCondition {
+            self.with_code(FungibleConditionCode::LessEqual, amount)
+        }
+    };
+}
+
+/// See [`PostCondition::stx`].
+#[derive(Clone, Debug)]
+pub struct StxCondition {
+    principal: PostConditionPrincipal,
+}
+
+impl StxCondition {
+    amount_conditions!();
+
+    fn with_code(self, condition_code: FungibleConditionCode, amount: u64) -> PostCondition {
+        PostCondition::Stx { principal: self.principal, condition_code, amount }
+    }
+}
+
+/// See [`PostCondition::fungible`].
+#[derive(Clone, Debug)]
+pub struct FungibleCondition {
+    principal: PostConditionPrincipal,
+    asset: AssetInfo,
+}
+
+impl FungibleCondition {
+    amount_conditions!();
+
+    fn with_code(self, condition_code: FungibleConditionCode, amount: u64) -> PostCondition {
+        PostCondition::Fungible { principal: self.principal, asset: self.asset, condition_code, amount }
+    }
+}
+
+/// See [`PostCondition::non_fungible`].
+#[derive(Clone, Debug)]
+pub struct NonFungibleCondition {
+    principal: PostConditionPrincipal,
+    asset: AssetInfo,
+    asset_value: ClarityValue,
+}
+
+impl NonFungibleCondition {
+    pub fn will_send(self) -> PostCondition {
+        self.with_code(NonFungibleConditionCode::Sent)
+    }
+
+    pub fn will_not_send(self) -> PostCondition {
+        self.with_code(NonFungibleConditionCode::NotSent)
+    }
+
+    fn with_code(self, condition_code: NonFungibleConditionCode) -> PostCondition {
+        PostCondition::NonFungible { principal: self.principal, asset: self.asset, asset_value: self.asset_value, condition_code }
+    }
+}
+
+impl Codec for PostConditionPrincipal {
+    fn encode(&self, bytes: &mut Vec<u8>) -> Result<(), TransactionError> {
+        match self {
+            PostConditionPrincipal::Origin => bytes.push(0x01),
+            PostConditionPrincipal::Principal(Principal::Standard(address)) => {
+                bytes.push(0x02);
+                encode_address(bytes, address);
+            }
+            PostConditionPrincipal::Principal(Principal::Contract(address, contract_name)) => {
+                bytes.push(0x03);
+                encode_address(bytes, address);
+                encode_name(bytes, contract_name);
+            }
+        }
+        Ok(())
+    }
+
+    fn decode(reader: &mut Reader<'_>) -> Result<Self, TransactionError> {
+        match reader.u8()? {
+            0x01 => Ok(PostConditionPrincipal::Origin),
+            0x02 => Ok(PostConditionPrincipal::Principal(Principal::Standard(reader.address()?))),
+            0x03 => {
+                let address = reader.address()?;
+                PostConditionPrincipal::contract(address, &reader.name()?)
+            }
+            other => Err(TransactionError::InvalidByte("post-condition principal type", other)),
+        }
+    }
+}
+
+impl Codec for AssetInfo {
+    fn encode(&self, bytes: &mut Vec<u8>) -> Result<(), TransactionError> {
+        if !Principal::is_valid_contract_name(&self.contract_name) {
+            return Err(TransactionError::InvalidContractName(self.contract_name.clone()));
+        }
+        if !is_valid_clarity_name(&self.asset_name) {
+            return Err(TransactionError::InvalidName(self.asset_name.clone()));
+        }
+        encode_address(bytes, &self.contract_address);
+        encode_name(bytes, &self.contract_name);
+        encode_name(bytes, &self.asset_name);
+        Ok(())
+    }
+
+    fn decode(reader: &mut Reader<'_>) -> Result<Self, TransactionError> {
+        let asset = AssetInfo { contract_address: reader.address()?, contract_name: reader.name()?, asset_name: reader.name()? };
+        if !Principal::is_valid_contract_name(&asset.contract_name) {
+            return Err(TransactionError::InvalidContractName(asset.contract_name));
+        }
+        if !is_valid_clarity_name(&asset.asset_name) {
+            return Err(TransactionError::InvalidName(asset.asset_name));
+        }
+        Ok(asset)
+    }
+}
+
+impl Codec for PostCondition {
+    fn encode(&self, bytes: &mut Vec<u8>) -> Result<(), TransactionError> {
+        match self {
+            PostCondition::Stx { principal, condition_code, amount } => {
+                bytes.push(0x00);
+                principal.encode(bytes)?;
+                condition_code.encode(bytes)?;
+                bytes.extend(amount.to_be_bytes());
+            }
+            PostCondition::Fungible { principal, asset, condition_code, amount } => {
+                bytes.push(0x01);
+                principal.encode(bytes)?;
+                asset.encode(bytes)?;
+                condition_code.encode(bytes)?;
+                bytes.extend(amount.to_be_bytes());
+            }
+            PostCondition::NonFungible { principal, asset, asset_value, condition_code } => {
+                bytes.push(0x02);
+                principal.encode(bytes)?;
+                asset.encode(bytes)?;
+                bytes.extend(asset_value.serialize()?);
+                condition_code.encode(bytes)?;
+            }
+        }
+        Ok(())
+    }
+
+    fn decode(reader: &mut Reader<'_>) -> Result<Self, TransactionError> {
+        match reader.u8()? {
+            0x00 => Ok(PostCondition::Stx {
+                principal: PostConditionPrincipal::decode(reader)?,
+                condition_code: FungibleConditionCode::decode(reader)?,
+                amount: reader.u64()?,
+            }),
+            0x01 => Ok(PostCondition::Fungible {
+                principal: PostConditionPrincipal::decode(reader)?,
+                asset: AssetInfo::decode(reader)?,
+                condition_code: FungibleConditionCode::decode(reader)?,
+                amount: reader.u64()?,
+            }),
+            0x02 => Ok(PostCondition::NonFungible {
+                principal: PostConditionPrincipal::decode(reader)?,
+                asset: AssetInfo::decode(reader)?,
+                asset_value: reader.clarity_value()?,
+                condition_code: NonFungibleConditionCode::decode(reader)?,
+            }),
+            other => Err(TransactionError::InvalidByte("post-condition type", other)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ADDRESS: &str = "SP3FGQ8Z7JY9BWYZ5WM53E0M9NK7WHJF0691NZ159";
+
+    fn encode(post_condition: &PostCondition) -> String {
+        let mut bytes = vec![];
+        post_condition.encode(&mut bytes).unwrap();
+        hex::encode(bytes)
+    }
+
+    #[test]
+    fn test_condition_codes() {
+        let sender = StacksAddress::from_str(ADDRESS).unwrap();
+        let codes = [
+            (PostCondition::stx(sender).will_send_eq(1), FungibleConditionCode::Equal),
+            (PostCondition::stx(sender).will_send_gt(1), FungibleConditionCode::Greater),
+            (PostCondition::stx(sender).will_send_gte(1), FungibleConditionCode::GreaterEqual),
+            (PostCondition::stx(sender).will_send_lt(1), FungibleConditionCode::Less),
+            (PostCondition::stx(sender).will_send_lte(1), FungibleConditionCode::LessEqual),
+        ];
+        for (post_condition, code) in codes {
+            let principal = PostConditionPrincipal::from(sender);
+            assert_eq!(post_condition, PostCondition::Stx { principal, condition_code: code, amount: 1 });
+        }
+    }
+
+    #[test]
+    fn test_encode_conditions() {
+        let address_hex = "16df0ba3e79792be7be5e50a370289accfc8c9e032";
+        let stx = PostCondition::stx(PostConditionPrincipal::Origin).will_send_lte(1000);
+        assert_eq!(encode(&stx), "00010500000000000003e8");
+
+        let contract = Principal::from_str(&format!("{ADDRESS}.vault")).unwrap();
+        let asset = AssetInfo::from_str(&format!("{ADDRESS}.token::gold")).unwrap();
+        let fungible = PostCondition::fungible(contract, asset.clone()).will_send_gte(50);
+        let expected = ["01", "03", address_hex, "05", "7661756c74", address_hex, "05", "746f6b656e", "04", "676f6c64", "03", "0000000000000032"];
+        assert_eq!(encode(&fungible), expected.concat());
+
+        let nft = PostCondition::non_fungible(PostConditionPrincipal::Origin, asset, ClarityValue::UInt(7)).will_not_send();
+        let expected = ["02", "01", address_hex, "05", "746f6b656e", "04", "676f6c64", "0100000000000000000000000000000007", "11"];
+        assert_eq!(encode(&nft), expected.concat());
+        assert_eq!(PostCondition::decode(&mut Reader::new(&hex::decode(expected.concat()).unwrap())).unwrap(), nft);
+    }
+
+    #[test]
+    fn test_encode_principals() {
+        let address = StacksAddress::from_str(ADDRESS).unwrap();
+        let encode = |principal: PostConditionPrincipal| {
+            let mut bytes = vec![];
+            principal.encode(&mut bytes).unwrap();
+            assert_eq!(PostConditionPrincipal::decode(&mut Reader::new(&bytes)).unwrap(), principal);
+            hex::encode(bytes)
+        };
+        assert_eq!(encode(PostConditionPrincipal::origin()), "01");
+        assert_eq!(encode(PostConditionPrincipal::standard(address)), "0216df0ba3e79792be7be5e50a370289accfc8c9e032");
+        let contract = PostConditionPrincipal::contract(address, "my-token").unwrap();
+        assert_eq!(encode(contract), "0316df0ba3e79792be7be5e50a370289accfc8c9e032086d792d746f6b656e");
+        for name in ["", "1token", "my token", &"a".repeat(129)] {
+            assert!(matches!(PostConditionPrincipal::contract(address, name), Err(TransactionError::InvalidContractName(_))));
+        }
+        let invalid = hex::decode("0316df0ba3e79792be7be5e50a370289accfc8c9e03206317465737421").unwrap();
+        assert!(matches!(PostConditionPrincipal::decode(&mut Reader::new(&invalid)), Err(TransactionError::InvalidContractName(_))));
+    }
+
+    #[test]
+    fn test_parse_asset() {
+        let asset = AssetInfo::from_str(&format!("{ADDRESS}.token::gold-coin")).unwrap();
+        assert_eq!((asset.contract_name.as_str(), asset.asset_name.as_str()), ("token", "gold-coin"));
+        assert_eq!(asset.to_string(), format!("{ADDRESS}.token::gold-coin"));
+        assert!(matches!(AssetInfo::from_str(&format!("{ADDRESS}.token")), Err(TransactionError::InvalidName(_))));
+        assert!(matches!(AssetInfo::from_str(&format!("{ADDRESS}::gold")), Err(TransactionError::InvalidAddress(_))));
+        assert!(matches!(AssetInfo::from_str(&format!("{ADDRESS}.token::1gold")), Err(TransactionError::InvalidName(_))));
+    }
+}
@@ -0,0 +1,637 @@
+//! Signing of the origin spending condition, following the sighash chain of SIP-005: each
+//! signature is over the previous sighash, the authorization type, fee and nonce.
+
+use secp256k1::{PublicKey, SecretKey};
+
+use crate::crypto::context::secp256k1_context;
+use crate::crypto::hash::{Hasher, Sha512_256};
+use crate::crypto::signature::recoverable::{recover, sign_recoverable};
+
+use super::auth::{
+    multi_sig_signer, single_sig_signer, AuthField, AuthType, MessageSignature, MultiSigHashMode, PublicKeyEncoding, SingleSigHashMode,
+    SingleSigSpendingCondition, SpendingCondition, TransactionAuth,
+};
+use super::stacks_transaction::StacksTransaction;
+use super::TransactionError;
+
+/// Where signing starts: the hash of the transaction with its authorization cleared.
+pub fn initial_sighash(transaction: &StacksTransaction) -> Result<[u8; 32], TransactionError> {
+    let cleared = StacksTransaction { auth: transaction.auth.cleared(), ..transaction.clone() };
+    Ok(Sha512_256::hash(&cleared.serialize()?))
+}
+
+/// The digest a signer signs, from the sighash left by the previous signer.
+pub fn presign_sighash(sighash: &[u8; 32], auth_type: AuthType, fee: u64, nonce: u64) -> [u8; 32] {
+    Sha512_256::hash(&[&sighash[..], &[auth_type.value()], &fee.to_be_bytes(), &nonce.to_be_bytes()].concat())
+}
+
+/// The sighash the next signer starts from.
+pub fn postsign_sighash(presign: &[u8; 32], key_encoding: PublicKeyEncoding, signature: &MessageSignature) -> [u8; 32] {
+    Sha512_256::hash(&[&presign[..], &[key_encoding.value()], &signature.0].concat())
+}
+
+/// Public key of a spending condition and its encoding.
+type SignerKey = (PublicKey, PublicKeyEncoding);
+
+fn recover_key(presign: &[u8; 32], signature: &MessageSignature) -> Result<PublicKey, TransactionError> {
+    let recoverable = signature.to_recoverable().map_err(|_| TransactionError::InvalidSignature)?;
+    recover(presign, &recoverable).map_err(|_| TransactionError::InvalidSignature)
+}
+
+/// Walks the signatures of `condition` from `sighash`: the sighash left for the next signer,
+/// and the keys of the condition so far (recovered from the signatures).
+fn replay(condition: &SpendingCondition, mut sighash: [u8; 32], auth_type: AuthType) -> Result<([u8; 32], Vec<SignerKey>), TransactionError> {
+    let mut keys = vec![];
+    match condition {
+        SpendingCondition::SingleSig(condition) => {
+            if !condition.signature.is_empty() {
+                let presign = presign_sighash(&sighash, auth_type, condition.fee, condition.nonce);
+                keys.push((recover_key(&presign, &condition.signature)?, condition.key_encoding));
+                sighash = postsign_sighash(&presign, condition.key_encoding, &condition.signature);
+            }
+        }
+        SpendingCondition::MultiSig(condition) => {
+            for field in &condition.fields {
+                match field {
+                    AuthField::PublicKey(public_key, encoding) => keys.push((*public_key, *encoding)),
+                    AuthField::Signature(signature, encoding) => {
+                        let presign = presign_sighash(&sighash, auth_type, condition.fee, condition.nonce);
+                        keys.push((recover_key(&presign, signature)?, *encoding));
+                        // order-independent signatures all cover the sighash the condition starts from
+                        if condition.hash_mode.is_sequential() {
+                            sighash = postsign_sighash(&presign, *encoding, signature);
+                        }
+                    }
+                }
+            }
+        }
+    }
+    Ok((sighash, keys))
+}
+
+/// Checks the signatures of `condition` against its signer hash and threshold, returning the
+/// sighash left for the next condition.
+pub(crate) fn verify_condition(condition: &SpendingCondition, sighash: [u8; 32], auth_type: AuthType) -> Result<[u8; 32], TransactionError> {
+    let (sighash, keys) = replay(condition, sighash, auth_type)?;
+    match condition {
+        SpendingCondition::SingleSig(condition) => {
+            let [(public_key, encoding)] = keys[..] else {
+                return Err(TransactionError::MissingSignatures(0, 1));
+            };
+            if condition.hash_mode == SingleSigHashMode::P2WPKH && encoding == PublicKeyEncoding::Uncompressed {
+                return Err(TransactionError::InvalidPublicKey);
+            }
+            if single_sig_signer(condition.hash_mode, &public_key, encoding) != condition.signer {
+                return Err(TransactionError::SignerMismatch);
+            }
+        }
+        SpendingCondition::MultiSig(condition) => {
+            let (count, required) = (condition.signature_count(), condition.signatures_required as usize);
+            if count < required {
+                return Err(TransactionError::MissingSignatures(count, required));
+            }
+            // order-independent conditions only need the threshold, as in stacks-core
+            if count > required && condition.hash_mode.is_sequential() {
+                return Err(TransactionError::TooManySignatures(count, required));
+            }
+            let segwit = matches!(condition.hash_mode, MultiSigHashMode::P2WSH | MultiSigHashMode::P2WSHNonSequential);
+            if segwit && keys.iter().any(|(_, encoding)| *encoding == PublicKeyEncoding::Uncompressed) {
+                return Err(TransactionError::InvalidPublicKey);
+            }
+            if multi_sig_signer(condition.hash_mode, &keys, condition.signatures_required)? != condition.signer {
+                return Err(TransactionError::SignerMismatch);
+            }
+        }
+    }
+    Ok(sighash)
+}
+
+/// Signs the origin of a transaction, one signer after the other.
+///
+/// Co-signers of a multisig account sign or append their public key in the order of the keys
+/// of the account; a partially signed transaction can be passed on and resumed with
+/// [`TransactionSigner::new`].
+///
+/// Usage:
+/// ```rust
+/// use std::str::FromStr;
+/// use secp256k1::SecretKey;
+/// use stacks_rs::address::principal::Principal;
+/// use stacks_rs::crypto::context::secp256k1_context;
+/// use stacks_rs::transaction::builder::ContractCallBuilder;
+/// use stacks_rs::transaction::signer::TransactionSigner;
+/// let keys: Vec<_> = (1..=3).map(|i| SecretKey::from_byte_array(&[i; 32]).unwrap()).collect();
+/// let public_keys: Vec<_> = keys.iter().map(|key| key.public_key(secp256k1_context())).collect();
+/// let contract = Principal::from_str("SP3FGQ8Z7JY9BWYZ5WM53E0M9NK7WHJF0691NZ159.pool").unwrap();
+/// let transaction = ContractCallBuilder::new(contract, "join", vec![]).build_multi_sig(&public_keys, 2).unwrap();
+///
+/// let mut signer = TransactionSigner::new(transaction).unwrap();
+/// signer.sign_with(&keys[0]).unwrap();
+/// signer.append_pubkey(&public_keys[1]).unwrap();
+/// signer.sign_with(&keys[2]).unwrap();
+/// assert!(signer.is_fully_signed());
+/// let signed = signer.finish().unwrap();
+/// ```
+#[derive(Clone, Debug)]
+pub struct TransactionSigner {
+    transaction: StacksTransaction,
+    sighash: [u8; 32],
+    /// Keys of the multisig fields so far, in order
+    keys: Vec<SignerKey>,
+}
+
+impl TransactionSigner {
+    /// Starts signing `transaction`, after the signatures it already holds.
+    pub fn new(transaction: StacksTransaction) -> Result<Self, TransactionError> {
+        let (sighash, keys) = replay(transaction.auth.origin(), initial_sighash(&transaction)?, AuthType::Standard)?;
+        Ok(TransactionSigner { transaction, sighash, keys })
+    }
+
+    /// The sighash after the signatures so far.
+    pub fn sighash(&self) -> [u8; 32] {
+        self.sighash
+    }
+
+    /// The digest the next signer of the origin signs, e.g. on a hardware wallet.
+    pub fn signing_digest(&self) -> [u8; 32] {
+        let origin = self.transaction.auth.origin();
+        presign_sighash(&self.sighash, AuthType::Standard, origin.fee(), origin.nonce())
+    }
+
+    /// Signs as the next signer of the origin.
+    ///
+    /// For an order-independent multisig account whose keys were appended beforehand, the
+    /// signature takes the place of the signer's key instead, whatever the order of signing.
+    pub fn sign_with(&mut self, key: &SecretKey) -> Result<(), TransactionError> {
+        self.add_signature(sign_recoverable(&self.signing_digest(), key).into())
+    }
+
+    /// Adds the signature of [`TransactionSigner::signing_digest`] made by a remote signer, as
+    /// [`TransactionSigner::sign_with`] does.
+    pub fn add_signature(&mut self, signature: MessageSignature) -> Result<(), TransactionError> {
+        let presign = self.signing_digest();
+        let public_key = recover_key(&presign, &signature)?;
+        let position = self.keys.iter().position(|(other, _)| *other == public_key);
+        match self.transaction.auth.origin_mut() {
+            SpendingCondition::SingleSig(condition) => {
+                if !condition.signature.is_empty() {
+                    return Err(TransactionError::AlreadySigned);
+                }
+                if single_sig_signer(condition.hash_mode, &public_key, condition.key_encoding) != condition.signer {
+                    return Err(TransactionError::SignerMismatch);
+                }
+                condition.signature = signature;
+                self.sighash = postsign_sighash(&presign, condition.key_encoding, &signature);
+            }
+            SpendingCondition::MultiSig(condition) => {
+                if condition.signature_count() >= condition.signatures_required as usize {
+                    return Err(TransactionError::AlreadySigned);
+                }
+                let field = AuthField::Signature(signature, PublicKeyEncoding::Compressed);
+                match position {
+                    Some(index) if !condition.hash_mode.is_sequential() && matches!(condition.fields[index], AuthField::PublicKey(..)) => {
+                        condition.fields[index] = field;
+                    }
+                    Some(_) => return Err(TransactionError::DuplicateKey),
+                    None => {
+                        condition.fields.push(field);
+                        self.keys.push((public_key, PublicKeyEncoding::Compressed));
+                    }
+                }
+                if condition.hash_mode.is_sequential() {
+                    self.sighash = postsign_sighash(&presign, PublicKeyEncoding::Compressed, &signature);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// The digest the sponsor signs once the origin signed, over the sponsor's `fee` and
+    /// `nonce`, e.g. on a hardware wallet.
+    pub fn sponsor_signing_digest(&self, fee: u64, nonce: u64) -> Result<[u8; 32], TransactionError> {
+        if self.transaction.auth.sponsor().is_none() {
+            return Err(TransactionError::NotSponsored);
+        }
+        self.check_signed()?;
+        Ok(presign_sighash(&self.sighash, AuthType::Sponsored, fee, nonce))
+    }
+
+    /// Signs as the single-sig sponsor of a sponsored transaction, paying `fee` from its
+    /// account at `nonce`, once the origin signed.
+    pub fn sign_sponsor(&mut self, key: &SecretKey, fee: u64, nonce: u64) -> Result<(), TransactionError> {
+        let signature = sign_recoverable(&self.sponsor_signing_digest(fee, nonce)?, key).into();
+        self.add_sponsor_signature(&key.public_key(secp256k1_context()), fee, nonce, signature)
+    }
+
+    /// Adds the signature of [`TransactionSigner::sponsor_signing_digest`] made by the remote
+    /// sponsor of `public_key`, as [`TransactionSigner::sign_sponsor`] does.
+    pub fn add_sponsor_signature(&mut self, public_key: &PublicKey, fee: u64, nonce: u64, signature: MessageSignature) -> Result<(), TransactionError> {
+        let presign = self.sponsor_signing_digest(fee, nonce)?;
+        if recover_key(&presign, &signature)? != *public_key {
+            return Err(TransactionError::SignerMismatch);
+        }
+        let TransactionAuth::Sponsored(_, sponsor) = &mut self.transaction.auth else {
+            return Err(TransactionError::NotSponsored);
+        };
+        if sponsor.signer() != &[0; 20] {
+            return Err(TransactionError::AlreadySigned);
+        }
+        *sponsor = SpendingCondition::SingleSig(SingleSigSpendingCondition { signature, ..SingleSigSpendingCondition::new(public_key, nonce, fee) });
+        Ok(())
+    }
+
+    /// Takes the signatures a co-signer added to a copy of the same order-independent
+    /// multisig transaction, in place of the keys appended here.
+    pub fn merge(&mut self, other: &StacksTransaction) -> Result<(), TransactionError> {
+        let (SpendingCondition::MultiSig(condition), SpendingCondition::MultiSig(signed)) = (self.transaction.auth.origin(), other.auth.origin())
+        else {
+            return Err(TransactionError::NotMultiSig);
+        };
+        if condition.hash_mode.is_sequential() || condition.fields.len() != signed.fields.len() {
+            return Err(TransactionError::SignerMismatch);
+        }
+        let mut merged = condition.clone();
+        for (index, field) in signed.fields.iter().enumerate() {
+            if matches!(field, AuthField::Signature(..)) {
+                merged.fields[index] = field.clone();
+            }
+        }
+        if merged.signature_count() > merged.signatures_required as usize {
+            return Err(TransactionError::AlreadySigned);
+        }
+        // the signatures must be of the same keys over this transaction
+        let mut transaction = self.transaction.clone();
+        *transaction.auth.origin_mut() = SpendingCondition::MultiSig(merged);
+        let resumed = TransactionSigner::new(transaction)?;
+        if resumed.keys != self.keys {
+            return Err(TransactionError::SignerMismatch);
+        }
+        *self = resumed;
+        Ok(())
+    }
+
+    /// Adds the key of a co-signer who does not sign, in its place in the key order.
+    pub fn append_pubkey(&mut self, public_key: &PublicKey) -> Result<(), TransactionError> {
+        if self.keys.iter().any(|(other, _)| other == public_key) {
+            return Err(TransactionError::DuplicateKey);
+        }
+        let SpendingCondition::MultiSig(condition) = self.transaction.auth.origin_mut() else {
+            return Err(TransactionError::NotMultiSig);
+        };
+        condition.fields.push(AuthField::PublicKey(*public_key, PublicKeyEncoding::Compressed));
+        self.keys.push((*public_key, PublicKeyEncoding::Compressed));
+        Ok(())
+    }
+
+    /// Whether the origin has all its signatures and, for multisig, all keys in the order
+    /// of the account.
+    pub fn is_fully_signed(&self) -> bool {
+        self.check_signed().is_ok()
+    }
+
+    fn check_signed(&self) -> Result<(), TransactionError> {
+        match self.transaction.auth.origin() {
+            SpendingCondition::SingleSig(condition) => match condition.signature.is_empty() {
+                true => Err(TransactionError::MissingSignatures(0, 1)),
+                false => Ok(()),
+            },
+            SpendingCondition::MultiSig(condition) => {
+                let required = condition.signatures_required as usize;
+                if condition.signature_count() < required {
+                    return Err(TransactionError::MissingSignatures(condition.signature_count(), required));
+                }
+                match multi_sig_signer(condition.hash_mode, &self.keys, condition.signatures_required)? == condition.signer {
+                    true => Ok(()),
+                    false => Err(TransactionError::SignerMismatch),
+                }
+            }
+        }
+    }
+
+    pub fn transaction(&self) -> &StacksTransaction {
+        &self.transaction
+    }
+
+    /// The signed transaction, once [`TransactionSigner::is_fully_signed`].
+    pub fn finish(self) -> Result<StacksTransaction, TransactionError> {
+        self.check_signed()?;
+        Ok(self.transaction)
+    }
+}
+
+/// A replacement of the stuck `transaction`: same nonce, payload and post-conditions, with the
+/// fee raised to `new_fee` and signed again by `keys`.
+///
+/// For a multisig origin, the keys of the account sign in its order until the threshold is
+/// met and the keys of the other co-signers are appended. A sponsored transaction is bumped
+/// by its sponsor, not here.
+///
+/// Usage:
+/// ```rust
+/// use std::str::FromStr;
+/// use secp256k1::SecretKey;
+/// use stacks_rs::address::principal::Principal;
+/// use stacks_rs::crypto::context::secp256k1_context;
+/// use stacks_rs::transaction::builder::ContractCallBuilder;
+/// use stacks_rs::transaction::signer::{replace_with_higher_fee, TransactionSigner};
+/// let key = SecretKey::from_byte_array(&[1; 32]).unwrap();
+/// let contract = Principal::from_str("SP3FGQ8Z7JY9BWYZ5WM53E0M9NK7WHJF0691NZ159.pool").unwrap();
+/// let transaction = ContractCallBuilder::new(contract, "join", vec![]).nonce(7).fee(200).build(&key.public_key(secp256k1_context())).unwrap();
+/// let mut signer = TransactionSigner::new(transaction).unwrap();
+/// signer.sign_with(&key).unwrap();
+/// let stuck = signer.finish().unwrap();
+///
+/// let replacement = replace_with_higher_fee(&stuck, 2000, &[key]).unwrap();
+/// assert_eq!((replacement.auth.origin().nonce(), replacement.auth.origin().fee()), (7, 2000));
+/// assert!(replacement.verify().is_ok());
+/// ```
+pub fn replace_with_higher_fee(transaction: &StacksTransaction, new_fee: u64, keys: &[SecretKey]) -> Result<StacksTransaction, TransactionError> {
+    if transaction.auth.sponsor().is_some() {
+        return Err(TransactionError::Sponsored);
+    }
+    let origin = transaction.auth.origin();
+    if new_fee <= origin.fee() {
+        return Err(TransactionError::FeeTooLow(new_fee, origin.fee()));
+    }
+    let (_, account_keys) = replay(origin, initial_sighash(transaction)?, AuthType::Standard)?;
+    let mut replacement = transaction.clone();
+    match replacement.auth.origin_mut() {
+        SpendingCondition::SingleSig(condition) => condition.signature = MessageSignature::empty(),
+        SpendingCondition::MultiSig(condition) => condition.fields.clear(),
+    }
+    replacement.auth.origin_mut().set_fee(new_fee);
+
+    let required = match replacement.auth.origin() {
+        SpendingCondition::SingleSig(_) => None,
+        SpendingCondition::MultiSig(condition) => Some(condition.signatures_required as usize),
+    };
+    let mut signer = TransactionSigner::new(replacement)?;
+    match required {
+        None => {
+            if !keys.iter().any(|key| signer.sign_with(key).is_ok()) {
+                return Err(TransactionError::SignerMismatch);
+            }
+        }
+        Some(required) => {
+            let mut signatures = 0;
+            for (public_key, _) in account_keys {
+                match keys.iter().find(|key| key.public_key(secp256k1_context()) == public_key) {
+                    Some(key) if signatures < required => {
+                        signer.sign_with(key)?;
+                        signatures += 1;
+                    }
+                    _ => signer.append_pubkey(&public_key)?,
+                }
+            }
+        }
+    }
+    signer.finish()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use crate::address::principal::Principal;
+    use crate::crypto::hash::Hash160;
+    use crate::transaction::auth::{check_threshold, redeem_script, MultiSigSpendingCondition, MAX_MULTI_SIG_KEYS};
+    use crate::transaction::builder::ContractCallBuilder;
+
+    use super::*;
+
+    /// Token transfer of the stacks.js test suite, signed by `KEY`.
+    const TOKEN_TRANSFER: &str = "0000000001040015c31b8c1c11c515e244b75806bac48d1399c775000000000000000000000000000000000000\
+        8b316d56e35b3b8d03ab3b9dbe05eb44d64c53e7ba3c468f9a78c82a13f2174c32facb0f29faeb21075ec933db935ebc28a8793cc60e14b8ee4ef05f52c94016\
+        030200000000000516df0ba3e79792be7be5e50a370289accfc8c9e032000000000000303974657374206d656d6f00000000000000000000000000000000000000000000000000";
+    const KEY: &str = "edf9aee84d9b7abc145504dde6726c64f369d37ee34ded868fabd876c26570bc";
+
+    fn keys() -> Vec<SecretKey> {
+        (1..=3).map(|i| SecretKey::from_byte_array(&[i; 32]).unwrap()).collect()
+    }
+
+    fn multi_sig_transaction(keys: &[SecretKey]) -> StacksTransaction {
+        let public_keys: Vec<_> = keys.iter().map(|key| key.public_key(secp256k1_context())).collect();
+        let contract = Principal::from_str("SP3FGQ8Z7JY9BWYZ5WM53E0M9NK7WHJF0691NZ159.pool").unwrap();
+        ContractCallBuilder::new(contract, "join", vec![]).nonce(4).fee(1000).build_multi_sig(&public_keys, 2).unwrap()
+    }
+
+    #[test]
+    fn test_single_sig() {
+        let signed = StacksTransaction::from_hex(TOKEN_TRANSFER).unwrap();
+        let mut unsigned = signed.clone();
+        let SpendingCondition::SingleSig(condition) = unsigned.auth.origin_mut() else { panic!("not single-sig") };
+        condition.signature = MessageSignature::empty();
+
+        let mut signer = TransactionSigner::new(unsigned).unwrap();
+        assert!(!signer.is_fully_signed());
+        signer.sign_with(&SecretKey::from_str(KEY).unwrap()).unwrap();
+        assert_eq!(signer.sign_with(&SecretKey::from_str(KEY).unwrap()), Err(TransactionError::AlreadySigned));
+        assert_eq!(signer.finish().unwrap().to_hex().unwrap(), TOKEN_TRANSFER);
+    }
+
+    #[test]
+    fn test_single_sig_wrong_key() {
+        let mut signer = TransactionSigner::new(StacksTransaction::from_hex(TOKEN_TRANSFER).unwrap()).unwrap();
+        assert_eq!(signer.sign_with(&keys()[0]), Err(TransactionError::AlreadySigned));
+        assert_eq!(signer.append_pubkey(&keys()[0].public_key(secp256k1_context())), Err(TransactionError::NotMultiSig));
+
+        let mut transaction = StacksTransaction::from_hex(TOKEN_TRANSFER).unwrap();
+        let SpendingCondition::SingleSig(condition) = transaction.auth.origin_mut() else { panic!("not single-sig") };
+        condition.signature = MessageSignature::empty();
+        let mut signer = TransactionSigner::new(transaction).unwrap();
+        assert_eq!(signer.sign_with(&keys()[0]), Err(TransactionError::SignerMismatch));
+    }
+
+    #[test]
+    fn test_multi_sig() {
+        let keys = keys();
+        let mut signer = TransactionSigner::new(multi_sig_transaction(&keys)).unwrap();
+        signer.sign_with(&keys[0]).unwrap();
+        assert_eq!(signer.sign_with(&keys[0]), Err(TransactionError::DuplicateKey));
+        assert!(!signer.is_fully_signed());
+
+        // handed over to the next signer as hex
+        let partial = signer.transaction().to_hex().unwrap();
+        let mut signer = TransactionSigner::new(StacksTransaction::from_hex(&partial).unwrap()).unwrap();
+        signer.sign_with(&keys[1]).unwrap();
+        assert_eq!(signer.sign_with(&keys[2]), Err(TransactionError::AlreadySigned));
+        assert!(!signer.is_fully_signed());
+        signer.append_pubkey(&keys[2].public_key(secp256k1_context())).unwrap();
+        assert!(signer.is_fully_signed());
+        let signed = signer.finish().unwrap();
+
+        let TransactionAuth::Standard(SpendingCondition::MultiSig(condition)) = &signed.auth else { panic!("not multisig") };
+        assert_eq!((condition.nonce, condition.fee, condition.fields.len()), (4, 1000, 3));
+        // recovering the keys of the signatures gives back the account
+        let resumed = TransactionSigner::new(signed.clone()).unwrap();
+        assert!(resumed.is_fully_signed());
+        assert_eq!(resumed.keys.iter().map(|(key, _)| *key).collect::<Vec<_>>(), keys.iter().map(|key| key.public_key(secp256k1_context())).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn test_multi_sig_key_order() {
+        let keys = keys();
+        let mut signer = TransactionSigner::new(multi_sig_transaction(&keys)).unwrap();
+        signer.sign_with(&keys[1]).unwrap();
+        signer.sign_with(&keys[0]).unwrap();
+        signer.append_pubkey(&keys[2].public_key(secp256k1_context())).unwrap();
+        assert!(!signer.is_fully_signed());
+        assert_eq!(signer.finish(), Err(TransactionError::SignerMismatch));
+
+        let mut signer = TransactionSigner::new(multi_sig_transaction(&keys)).unwrap();
+        signer.sign_with(&keys[0]).unwrap();
+        assert_eq!(signer.finish(), Err(TransactionError::MissingSignatures(1, 2)));
+    }
+
+    #[test]
+    fn test_redeem_script() {
+        let public_keys: Vec<_> = keys().iter().map(|key| key.public_key(secp256k1_context())).collect();
+        let condition = MultiSigSpendingCondition::new(MultiSigHashMode::P2SH, &public_keys, 2, 0, 0).unwrap();
+        let script = redeem_script(&public_keys.iter().map(|key| (*key, PublicKeyEncoding::Compressed)).collect::<Vec<_>>(), 2).unwrap();
+        assert_eq!((script[0], script[script.len() - 2], script[script.len() - 1], script.len()), (0x52, 0x53, 0xae, 3 + 3 * 34));
+        assert_eq!(condition.signer, Hash160::hash(&script));
+    }
+
+    #[test]
+    fn test_multi_sig_threshold() {
+        let public_keys: Vec<_> = (1..=17).map(|i| SecretKey::from_byte_array(&[i; 32]).unwrap().public_key(secp256k1_context())).collect();
+        assert!(MultiSigSpendingCondition::new(MultiSigHashMode::P2SH, &public_keys[..16], 16, 0, 0).is_ok());
+        for (required, count) in [(0, 3), (4, 3), (17, 17), (1, 17), (300, 300)] {
+            let keys = public_keys.iter().cycle().take(count).copied().collect::<Vec<_>>();
+            assert_eq!(MultiSigSpendingCondition::new(MultiSigHashMode::P2SH, &keys, required, 0, 0), Err(TransactionError::InvalidThreshold(required, count)));
+        }
+        assert!(check_threshold(1, 0).is_err());
+        assert!(ContractCallBuilder::new(Principal::from_str("SP3FGQ8Z7JY9BWYZ5WM53E0M9NK7WHJF0691NZ159.pool").unwrap(), "join", vec![])
+            .build_multi_sig(&public_keys[..2], 3)
+            .is_err());
+
+        // decoding refuses a threshold or fields no account can have
+        let mut transaction = multi_sig_transaction(&keys());
+        let SpendingCondition::MultiSig(condition) = transaction.auth.origin_mut() else { panic!("not multisig") };
+        condition.signatures_required = 0;
+        assert_eq!(StacksTransaction::deserialize(&transaction.serialize().unwrap()), Err(TransactionError::InvalidThreshold(0, MAX_MULTI_SIG_KEYS)));
+        let SpendingCondition::MultiSig(condition) = transaction.auth.origin_mut() else { panic!("not multisig") };
+        condition.signatures_required = 2;
+        condition.fields = vec![AuthField::PublicKey(public_keys[0], PublicKeyEncoding::Compressed); MAX_MULTI_SIG_KEYS + 1];
+        assert_eq!(StacksTransaction::deserialize(&transaction.serialize().unwrap()), Err(TransactionError::InvalidThreshold(2, MAX_MULTI_SIG_KEYS + 1)));
+    }
+
+    #[test]
+    fn test_non_sequential_multi_sig() {
+        let keys = keys();
+        let public_keys: Vec<_> = keys.iter().map(|key| key.public_key(secp256k1_context())).collect();
+        let contract = Principal::from_str("SP3FGQ8Z7JY9BWYZ5WM53E0M9NK7WHJF0691NZ159.pool").unwrap();
+        let transaction = ContractCallBuilder::new(contract, "join", vec![])
+            .multi_sig_hash_mode(MultiSigHashMode::P2SHNonSequential)
+            .build_multi_sig(&public_keys, 2)
+            .unwrap();
+        let mut unsigned = TransactionSigner::new(transaction).unwrap();
+        for public_key in &public_keys {
+            unsigned.append_pubkey(public_key).unwrap();
+        }
+
+        // the third co-signer signs before the first, each on their own copy
+        let mut first = unsigned.clone();
+        first.sign_with(&keys[2]).unwrap();
+        first.sign_with(&keys[0]).unwrap();
+        let mut second = unsigned.clone();
+        second.sign_with(&keys[0]).unwrap();
+        let mut third = unsigned.clone();
+        third.sign_with(&keys[2]).unwrap();
+        second.merge(third.transaction()).unwrap();
+        assert!(first.is_fully_signed());
+        assert_eq!(first.transaction(), second.transaction());
+        assert_eq!(second.merge(first.transaction()), Ok(()));
+        assert_eq!(unsigned.clone().merge(&StacksTransaction::from_hex(TOKEN_TRANSFER).unwrap()), Err(TransactionError::NotMultiSig));
+
+        let signed = first.finish().unwrap();
+        let SpendingCondition::MultiSig(condition) = signed.auth.origin() else { panic!("not multisig") };
+        assert!(matches!(condition.fields[..], [AuthField::Signature(..), AuthField::PublicKey(..), AuthField::Signature(..)]));
+        // written with the order-independent hash mode, and read back
+        let hex = signed.to_hex().unwrap();
+        assert_eq!(&hex[12..14], "05");
+        let resumed = TransactionSigner::new(StacksTransaction::from_hex(&hex).unwrap()).unwrap();
+        assert!(resumed.is_fully_signed());
+        assert_eq!(resumed.sighash, initial_sighash(&signed).unwrap());
+    }
+
+    #[test]
+    fn test_non_sequential_merge_mismatch() {
+        let keys = keys();
+        let public_keys: Vec<_> = keys.iter().map(|key| key.public_key(secp256k1_context())).collect();
+        let builder = || {
+            let contract = Principal::from_str("SP3FGQ8Z7JY9BWYZ5WM53E0M9NK7WHJF0691NZ159.pool").unwrap();
+            ContractCallBuilder::new(contract, "join", vec![]).multi_sig_hash_mode(MultiSigHashMode::P2WSHNonSequential)
+        };
+        let mut signer = TransactionSigner::new(builder().build_multi_sig(&public_keys, 1).unwrap()).unwrap();
+        let mut other = TransactionSigner::new(builder().nonce(1).build_multi_sig(&public_keys, 1).unwrap()).unwrap();
+        for public_key in &public_keys {
+            signer.append_pubkey(public_key).unwrap();
+            other.append_pubkey(public_key).unwrap();
+        }
+        other.sign_with(&keys[1]).unwrap();
+        // signed over another nonce
+        assert_eq!(signer.merge(other.transaction()), Err(TransactionError::SignerMismatch));
+        signer.sign_with(&keys[0]).unwrap();
+        assert_eq!(signer.sign_with(&keys[1]), Err(TransactionError::AlreadySigned));
+        assert!(signer.is_fully_signed());
+    }
+
+    #[test]
+    fn test_remote_signer() {
+        let mut transaction = StacksTransaction::from_hex(TOKEN_TRANSFER).unwrap();
+        let SpendingCondition::SingleSig(condition) = transaction.auth.origin_mut() else { panic!("not single-sig") };
+        condition.signature = MessageSignature::empty();
+        let mut signer = TransactionSigner::new(transaction.clone()).unwrap();
+        assert_eq!(signer.sighash(), initial_sighash(&transaction).unwrap());
+        assert_eq!(signer.signing_digest(), presign_sighash(&signer.sighash(), AuthType::Standard, 0, 0));
+
+        // signed elsewhere, e.g. on a device holding the key
+        let signature = sign_recoverable(&signer.signing_digest(), &SecretKey::from_str(KEY).unwrap());
+        assert_eq!(signer.add_signature(MessageSignature::from(sign_recoverable(&[1; 32], &keys()[0]))), Err(TransactionError::SignerMismatch));
+        signer.add_signature(signature.into()).unwrap();
+        assert_eq!(signer.transaction().to_hex().unwrap(), TOKEN_TRANSFER);
+        assert_eq!(signer.add_signature(MessageSignature([0xff; 65])), Err(TransactionError::InvalidSignature));
+    }
+
+    #[test]
+    fn test_replace_with_higher_fee() {
+        let stuck = StacksTransaction::from_hex(TOKEN_TRANSFER).unwrap();
+        let key = SecretKey::from_str(KEY).unwrap();
+        let replacement = replace_with_higher_fee(&stuck, 1000, &[keys()[0], key]).unwrap();
+        assert_eq!((replacement.auth.origin().nonce(), replacement.auth.origin().fee()), (0, 1000));
+        assert_eq!((&replacement.payload, &replacement.post_conditions), (&stuck.payload, &stuck.post_conditions));
+        assert_ne!(replacement.txid().unwrap(), stuck.txid().unwrap());
+        assert!(replacement.verify().is_ok());
+
+        assert_eq!(replace_with_higher_fee(&stuck, 0, &[key]), Err(TransactionError::FeeTooLow(0, 0)));
+        assert_eq!(replace_with_higher_fee(&replacement, 1000, &[key]), Err(TransactionError::FeeTooLow(1000, 1000)));
+        assert_eq!(replace_with_higher_fee(&stuck, 1000, &keys()), Err(TransactionError::SignerMismatch));
+
+        let contract = Principal::from_str("SP3FGQ8Z7JY9BWYZ5WM53E0M9NK7WHJF0691NZ159.pool").unwrap();
+        let sponsored = ContractCallBuilder::new(contract, "join", vec![]).sponsored(true).build(&key.public_key(secp256k1_context())).unwrap();
+        assert_eq!(replace_with_higher_fee(&sponsored, 1000, &[key]), Err(TransactionError::Sponsored));
+    }
+
+    #[test]
+    fn test_replace_multi_sig_with_higher_fee() {
+        let keys = keys();
+        for hash_mode in [MultiSigHashMode::P2SH, MultiSigHashMode::P2SHNonSequential] {
+            let mut transaction = multi_sig_transaction(&keys);
+            let SpendingCondition::MultiSig(condition) = transaction.auth.origin_mut() else { panic!("not multisig") };
+            *condition = MultiSigSpendingCondition::new(hash_mode, &keys.iter().map(|key| key.public_key(secp256k1_context())).collect::<Vec<_>>(), 2, 4, 1000).unwrap();
+            let mut signer = TransactionSigner::new(transaction).unwrap();
+            signer.sign_with(&keys[0]).unwrap();
+            signer.append_pubkey(&keys[1].public_key(secp256k1_context())).unwrap();
+            signer.sign_with(&keys[2]).unwrap();
+            let stuck = signer.finish().unwrap();
+
+            // any two co-signers can bump it
+            let replacement = replace_with_higher_fee(&stuck, 5000, &[keys[2], keys[1]]).unwrap();
+            assert_eq!(replacement.auth.origin().fee(), 5000);
+            assert_eq!(replacement.auth.origin().signer(), stuck.auth.origin().signer());
+            assert!(replacement.verify().is_ok());
+            assert_eq!(replace_with_higher_fee(&stuck, 5000, &keys[..1]), Err(TransactionError::MissingSignatures(1, 2)));
+        }
+    }
+}
@@ -0,0 +1,583 @@
+use crate::crypto::hash::{Hasher, Sha512_256};
+use crate::crypto::signature::recoverable::RECOVERABLE_SIGNATURE_LENGTH;
+use crate::network::NetworkKind;
+
+use super::auth::TransactionAuth;
+use super::codec::{encode_list, Codec, Reader};
+use super::metadata::TransactionMetadata;
+use super::payload::Payload;
+use super::post_condition::{PostCondition, PostConditionMode};
+use super::auth::{AuthType, SpendingCondition};
+use super::signer::{initial_sighash, verify_condition};
+use super::TransactionError;
+
+/// Lowest fee rate nodes relay, in micro-STX per byte.
+pub const MIN_FEE_RATE: u64 = 1;
+
+byte_enum!(TransactionVersion, "transaction version" {
+    Mainnet = 0x00,
+    Testnet = 0x80,
+});
+
+impl TransactionVersion {
+    pub fn from_network(network: &NetworkKind) -> Self {
+        match network {
+            NetworkKind::Mainnet => TransactionVersion::Mainnet,
+            NetworkKind::Testnet | NetworkKind::Mocknet => TransactionVersion::Testnet,
+        }
+    }
+}
+
+byte_enum!(AnchorMode, "anchor mode" {
+    /// Must be included in an anchored block
+    OnChainOnly = 0x01,
+    /// Must be included in a microblock
+    OffChainOnly = 0x02,
+    /// The miner chooses
+    Any = 0x03,
+});
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct StacksTransaction {
+    pub version: TransactionVersion,
+    pub chain_id: u32,
+    pub auth: TransactionAuth,
+    pub anchor_mode: AnchorMode,
+    pub post_condition_mode: PostConditionMode,
+    pub post_conditions: Vec<PostCondition>,
+    pub payload: Payload,
+    /// Client-side only, not part of the serialized transaction
+    pub metadata: Option<TransactionMetadata>,
+}
+
+impl StacksTransaction {
+    /// Wire format of the transaction, as broadcast to a node.
+    pub fn serialize(&self) -> Result<Vec<u8>, TransactionError> {
+        let mut bytes = Vec::new();
+        self.encode(&mut bytes)?;
+        Ok(bytes)
+    }
+
+    /// Inverse of [`StacksTransaction::serialize`], rejecting trailing bytes.
+    pub fn deserialize(bytes: &[u8]) -> Result<Self, TransactionError> {
+        let mut reader = Reader::new(bytes);
+        let transaction = Self::decode(&mut reader)?;
+        if reader.remaining() != 0 {
+            return Err(TransactionError::TrailingBytes(reader.remaining()));
+        }
+        Ok(transaction)
+    }
+
+    /// The transaction id: SHA-512/256 of the wire format, signed or not.
+    ///
+    /// Usage:
+    /// ```rust
+    /// use stacks_rs::transaction::stacks_transaction::StacksTransaction;
+    /// let transaction = StacksTransaction::from_hex(
+    ///     "0000000001040015c31b8c1c11c515e244b75806bac48d1399c775000000000000000000000000000000000000\
+    ///     8b316d56e35b3b8d03ab3b9dbe05eb44d64c53e7ba3c468f9a78c82a13f2174c32facb0f29faeb21075ec933db935ebc28a8793cc60e14b8ee4ef05f52c94016\
+    ///     030200000000000516df0ba3e79792be7be5e50a370289accfc8c9e032000000000000303974657374206d656d6f00000000000000000000000000000000000000000000000000",
+    /// )
+    /// .unwrap();
+    /// assert_eq!(hex::encode(transaction.txid().unwrap()), "84cccb05f4bd0e1b08905ef1f1350ad635a6474448310548bdccfa04e0121bab");
+    /// ```
+    pub fn txid(&self) -> Result<[u8; 32], TransactionError> {
+        Ok(Sha512_256::hash(&self.serialize()?))
+    }
+
+    /// Hash the origin signs over first, see [`crate::transaction::signer`].
+    pub fn initial_sighash(&self) -> Result<[u8; 32], TransactionError> {
+        initial_sighash(self)
+    }
+
+    /// Length of the transaction once signed: missing multisig signatures are counted, keys
+    /// still to be appended are not.
+    pub fn estimated_len(&self) -> Result<usize, TransactionError> {
+        let missing_signatures = |condition: &SpendingCondition| match condition {
+            SpendingCondition::SingleSig(_) => 0,
+            SpendingCondition::MultiSig(condition) => (condition.signatures_required as usize).saturating_sub(condition.signature_count()),
+        };
+        let missing = missing_signatures(self.auth.origin()) + self.auth.sponsor().map_or(0, missing_signatures);
+        Ok(self.serialize()?.len() + missing * (1 + RECOVERABLE_SIGNATURE_LENGTH))
+    }
+
+    /// Fee of `fee_rate` micro-STX per byte of [`StacksTransaction::estimated_len`], at least
+    /// [`MIN_FEE_RATE`] per byte as nodes relay nothing cheaper.
+    pub fn fee_for_rate(&self, fee_rate: u64) -> Result<u64, TransactionError> {
+        Ok(fee_rate.max(MIN_FEE_RATE) * self.estimated_len()? as u64)
+    }
+
+    /// Sets the fee of the sponsor if any, of the origin otherwise.
+    ///
+    /// The fee is signed over: set it before signing.
+    pub fn set_fee(&mut self, fee: u64) {
+        self.auth.payer_mut().set_fee(fee);
+    }
+
+    /// Checks the signatures of the origin, then of the sponsor, against their signer hash and
+    /// number of required signatures.
+    pub fn verify(&self) -> Result<(), TransactionError> {
+        let sighash = verify_condition(self.auth.origin(), initial_sighash(self)?, AuthType::Standard)?;
+        if let Some(sponsor) = self.auth.sponsor() {
+            verify_condition(sponsor, sighash, AuthType::Sponsored)?;
+        }
+        Ok(())
+    }
+
+    pub fn with_metadata(mut self, metadata: TransactionMetadata) -> Self {
+        self.metadata = Some(metadata);
+        self
+    }
+
+    /// Returns [`TransactionError::Stale`] if the transaction is too old to be broadcast at
+    /// `current_height`, see [`TransactionMetadata`].
+    pub fn check_fresh(&self, current_height: u64) -> Result<(), TransactionError> {
+        self.metadata.map_or(Ok(()), |metadata| metadata.check_fresh(current_height))
+    }
+
+    pub fn to_hex(&self) -> Result<String, TransactionError> {
+        Ok(hex::encode(self.serialize()?))
+    }
+
+    /// Parses serialized hex, with or without the `0x` prefix.
+    pub fn from_hex(value: &str) -> Result<Self, TransactionError> {
+        let value = value.strip_prefix("0x").unwrap_or(value);
+        let bytes = hex::decode(value).map_err(|err| TransactionError::InvalidHex(format!("{err}")))?;
+        Self::deserialize(&bytes)
+    }
+}
+
+impl Codec for StacksTransaction {
+    fn encode(&self, bytes: &mut Vec<u8>) -> Result<(), TransactionError> {
+        self.version.encode(bytes)?;
+        bytes.extend(self.chain_id.to_be_bytes());
+        self.auth.encode(bytes)?;
+        self.anchor_mode.encode(bytes)?;
+        self.post_condition_mode.encode(bytes)?;
+        encode_list(bytes, &self.post_conditions)?;
+        self.payload.encode(bytes)
+    }
+
+    fn decode(reader: &mut Reader<'_>) -> Result<Self, TransactionError> {
+        Ok(StacksTransaction {
+            version: TransactionVersion::decode(reader)?,
+            chain_id: reader.u32()?,
+            auth: TransactionAuth::decode(reader)?,
+            anchor_mode: AnchorMode::decode(reader)?,
+            post_condition_mode: PostConditionMode::decode(reader)?,
+            post_conditions: reader.list()?,
+            payload: Payload::decode(reader)?,
+            metadata: None,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use secp256k1::{PublicKey, SecretKey};
+
+    use crate::address::principal::Principal;
+    use crate::address::stacks_address::StacksAddress;
+    use crate::clarity::value::ClarityValue;
+    use crate::crypto::context::secp256k1_context;
+    use crate::crypto::hash::{Hash160, Sha256};
+    use crate::crypto::signature::recoverable::sign_recoverable;
+    use crate::transaction::auth::*;
+    use crate::transaction::builder::ContractCallBuilder;
+    use crate::transaction::payload::*;
+    use crate::transaction::post_condition::*;
+    use crate::transaction::signer::{presign_sighash, TransactionSigner};
+
+    use super::*;
+
+    const ADDRESS: &str = "SP3FGQ8Z7JY9BWYZ5WM53E0M9NK7WHJF0691NZ159";
+    const ADDRESS_HEX: &str = "16df0ba3e79792be7be5e50a370289accfc8c9e032";
+
+    /// Signed token transfer of the stacks.js test suite.
+    const TOKEN_TRANSFER: &str = "0000000001040015c31b8c1c11c515e244b75806bac48d1399c775000000000000000000000000000000000000\
+        8b316d56e35b3b8d03ab3b9dbe05eb44d64c53e7ba3c468f9a78c82a13f2174c32facb0f29faeb21075ec933db935ebc28a8793cc60e14b8ee4ef05f52c94016\
+        030200000000000516df0ba3e79792be7be5e50a370289accfc8c9e032000000000000303974657374206d656d6f00000000000000000000000000000000000000000000000000";
+
+    fn condition(hash_mode: SingleSigHashMode, nonce: u64, fee: u64) -> SpendingCondition {
+        SpendingCondition::SingleSig(SingleSigSpendingCondition {
+            hash_mode,
+            signer: [0x11; 20],
+            nonce,
+            fee,
+            key_encoding: PublicKeyEncoding::Compressed,
+            signature: MessageSignature::empty(),
+        })
+    }
+
+    #[test]
+    fn test_token_transfer() {
+        let transaction = StacksTransaction::from_hex(TOKEN_TRANSFER).unwrap();
+        assert_eq!(transaction.version, TransactionVersion::Mainnet);
+        assert_eq!(transaction.anchor_mode, AnchorMode::Any);
+        assert_eq!(transaction.post_condition_mode, PostConditionMode::Deny);
+        assert_eq!(hex::encode(transaction.auth.origin().signer()), "15c31b8c1c11c515e244b75806bac48d1399c775");
+        let Payload::TokenTransfer(payload) = &transaction.payload else { panic!("not a token transfer") };
+        assert_eq!(payload.recipient, Principal::from_str(ADDRESS).unwrap());
+        assert_eq!(payload.amount, 12345);
+        assert_eq!(payload.memo_str(), Some("test memo"));
+        assert_eq!(transaction.to_hex().unwrap(), TOKEN_TRANSFER);
+        assert_eq!(StacksTransaction::from_hex(&format!("0x{TOKEN_TRANSFER}")).unwrap(), transaction);
+        assert_eq!(hex::encode(transaction.txid().unwrap()), "84cccb05f4bd0e1b08905ef1f1350ad635a6474448310548bdccfa04e0121bab");
+    }
+
+    /// 2-of-3 order-independent token transfers laid out field by field (SIP-005, SIP-027), signed
+    /// by the third then the first of the keys `[1; 32]`, `[2; 32]`, `[3; 32]`.
+    fn non_sequential_transfer(hash_mode: &str, signer: &str, first_signature: &str, second_signature: &str) -> String {
+        [
+            "00",
+            "00000001",
+            "04",
+            hash_mode,
+            signer,
+            // nonce 3, fee 600
+            "0000000000000003",
+            "0000000000000258",
+            // signature, key of the second co-signer, signature, 2 required
+            "00000003",
+            first_signature,
+            "00024d4b6cd1361032ca9bd2aeb9d900aa4d45d9ead80ac9423374c451a7254d0766",
+            second_signature,
+            "0002",
+            // any anchor mode, deny, no post-conditions
+            "03",
+            "02",
+            "00000000",
+            "000516df0ba3e79792be7be5e50a370289accfc8c9e032",
+            "0000000000003039",
+            "74657374206d656d6f00000000000000000000000000000000000000000000000000",
+        ]
+        .concat()
+    }
+
+    #[test]
+    fn test_non_sequential_multi_sig_layout() {
+        let keys = "21031b84c5567b126440995d3ed5aaba0565d71e1834604819ff9c17f5e9d5dd078f\
+            21024d4b6cd1361032ca9bd2aeb9d900aa4d45d9ead80ac9423374c451a7254d0766\
+            2102531fe6068134503d2723133227c867ac8fa6c83c537e9a44c3c5bdbdcb1fe337";
+        let script = hex::decode(format!("52{keys}53ae")).unwrap();
+        let p2sh = non_sequential_transfer(
+            "05",
+            "ae79902ae33900b679c76ced8576362e4abb15e8",
+            "0200aa3151d1713f8723902ed9099784d3f8f73257d53bdbe3fffa5d838a929012a977198552a98060d74d7d155ce883dac3e5e43d4fcfea35c64e85c71bc8176805",
+            "0200a6881a5d42bad1aa84f1ae521eaaca22bd3982896a07e88f1d4f40e7da8add780e0e5d80d6c91fcdef801a2874a73bc8d8c77a0e1ee1d9038caa8234f26784cd",
+        );
+        let p2wsh = non_sequential_transfer(
+            "07",
+            "564d22ea4f58d584581a583a1c166b3bac576c93",
+            "02017296378520dce2bb32b9434cf66ee8c7307beae3257a1827e9fe5f42a7b8e28909141704870c75b97241093c83db9d6b8de1424fd9ad6c4f5df3c1ee619a2a7b",
+            "0201b81d8ad5ec683004196017fb85916e95fddbc6351b5d338d40b654e3cc9d3c6f189d5e39bb4c612959d6bfab605887e90fe5eea7308a6264905c7853e63ee64c",
+        );
+        let cases = [
+            (p2sh, MultiSigHashMode::P2SHNonSequential, Hash160::hash(&script), "c9a192498a36b32531b3d72d2ffa9da8f0f3f6d1be2610f27ce1580e8dcbe202"),
+            (p2wsh, MultiSigHashMode::P2WSHNonSequential, Hash160::hash(&[&[0x00, 0x20][..], &Sha256::hash(&script)].concat()), "b65286503ae98a08804d019a75b4943cd295039d3406c120cf59dc851d55844c"),
+        ];
+        for (hex, hash_mode, signer, txid) in cases {
+            let transaction = StacksTransaction::from_hex(&hex).unwrap();
+            let SpendingCondition::MultiSig(condition) = transaction.auth.origin() else { panic!("not multisig") };
+            assert_eq!((condition.hash_mode, condition.signer, condition.signature_count()), (hash_mode, signer, 2));
+            assert_eq!(transaction.verify(), Ok(()));
+            assert_eq!(transaction.to_hex().unwrap(), hex);
+            assert_eq!(hex::encode(transaction.txid().unwrap()), txid);
+        }
+    }
+
+    #[test]
+    fn test_sponsored_contract_call() {
+        let address = StacksAddress::from_str(ADDRESS).unwrap();
+        let transaction = StacksTransaction {
+            version: TransactionVersion::Testnet,
+            chain_id: NetworkKind::Testnet.chain_id(),
+            auth: TransactionAuth::Sponsored(condition(SingleSigHashMode::P2PKH, 1, 0), condition(SingleSigHashMode::P2WPKH, 7, 300)),
+            anchor_mode: AnchorMode::OnChainOnly,
+            post_condition_mode: PostConditionMode::Allow,
+            post_conditions: vec![
+                PostCondition::Stx { principal: PostConditionPrincipal::Origin, condition_code: FungibleConditionCode::LessEqual, amount: 100 },
+                PostCondition::NonFungible {
+                    principal: PostConditionPrincipal::Principal(Principal::contract(address, "pool").unwrap()),
+                    asset: AssetInfo { contract_address: address, contract_name: String::from("nft"), asset_name: String::from("badge") },
+                    asset_value: ClarityValue::UInt(1),
+                    condition_code: NonFungibleConditionCode::Sent,
+                },
+            ],
+            payload: Payload::ContractCall(ContractCallPayload {
+                contract_address: address,
+                contract_name: String::from("pool"),
+                function_name: String::from("join"),
+                function_args: vec![ClarityValue::Bool(true)],
+            }),
+            metadata: None,
+        };
+        let empty_signature = "00".repeat(65);
+        let expected = [
+            "80", "80000000", "05",
+            // origin, then sponsor
+            "00", &"11".repeat(20), "0000000000000001", "0000000000000000", "00", &empty_signature,
+            "02", &"11".repeat(20), "0000000000000007", "000000000000012c", "00", &empty_signature,
+            "01", "01", "00000002",
+            "00", "01", "05", "0000000000000064",
+            "02", "03", ADDRESS_HEX, "04", "706f6f6c", ADDRESS_HEX, "03", "6e6674", "05", "6261646765", "0100000000000000000000000000000001", "10",
+            "02", ADDRESS_HEX, "04", "706f6f6c", "04", "6a6f696e", "00000001", "03",
+        ]
+        .concat();
+        assert_eq!(transaction.to_hex().unwrap(), expected);
+        assert_eq!(StacksTransaction::from_hex(&expected).unwrap(), transaction);
+    }
+
+    #[test]
+    fn test_multisig_deploy() {
+        let public_key = PublicKey::from_str("03ef788b3830c00abe8f64f62dc32fc863bc0b2cafeb073b6c8e1c7657d9c2c3ab").unwrap();
+        let signature = MessageSignature([0x22; 65]);
+        let auth = TransactionAuth::Standard(SpendingCondition::MultiSig(MultiSigSpendingCondition {
+            hash_mode: MultiSigHashMode::P2SH,
+            signer: [0x33; 20],
+            nonce: 2,
+            fee: 1000,
+            fields: vec![AuthField::Signature(signature, PublicKeyEncoding::Compressed), AuthField::PublicKey(public_key, PublicKeyEncoding::Uncompressed)],
+            signatures_required: 1,
+        }));
+        let mut transaction = StacksTransaction {
+            version: TransactionVersion::Mainnet,
+            chain_id: 1,
+            auth,
+            anchor_mode: AnchorMode::Any,
+            post_condition_mode: PostConditionMode::Deny,
+            post_conditions: vec![],
+            payload: Payload::SmartContract(SmartContractPayload {
+                contract_name: String::from("hello"),
+                code_body: String::from("(define-read-only (hi) 1)\n"),
+                clarity_version: Some(ClarityVersion::Clarity2),
+            }),
+            metadata: None,
+        };
+        let fields = ["00000002", "02", &"22".repeat(65), "01", "03ef788b3830c00abe8f64f62dc32fc863bc0b2cafeb073b6c8e1c7657d9c2c3ab"].concat();
+        let body = hex::encode("(define-read-only (hi) 1)\n");
+        let expected = [
+            "00", "00000001", "04", "01", &"33".repeat(20), "0000000000000002", "00000000000003e8", &fields, "0001",
+            "03", "02", "00000000", "06", "02", "05", "68656c6c6f", "0000001a", &body,
+        ]
+        .concat();
+        assert_eq!(transaction.to_hex().unwrap(), expected);
+        assert_eq!(StacksTransaction::from_hex(&expected).unwrap(), transaction);
+
+        // without a version it is the original deploy payload
+        if let Payload::SmartContract(payload) = &mut transaction.payload {
+            payload.clarity_version = None;
+        }
+        let hex = transaction.to_hex().unwrap();
+        assert!(hex.ends_with(&["01", "05", "68656c6c6f", "0000001a", &body].concat()));
+        assert_eq!(StacksTransaction::from_hex(&hex).unwrap(), transaction);
+    }
+
+    #[test]
+    fn test_invalid_transactions() {
+        let bytes = hex::decode(TOKEN_TRANSFER).unwrap();
+        assert_eq!(StacksTransaction::deserialize(&bytes[..100]), Err(TransactionError::UnexpectedEnd));
+        assert_eq!(StacksTransaction::deserialize(&[&bytes[..], &[0]].concat()), Err(TransactionError::TrailingBytes(1)));
+
+        let mut invalid = bytes.clone();
+        invalid[0] = 0x01;
+        assert_eq!(StacksTransaction::deserialize(&invalid), Err(TransactionError::InvalidByte("transaction version", 0x01)));
+        let mut invalid = bytes.clone();
+        invalid[5] = 0x06;
+        assert_eq!(StacksTransaction::deserialize(&invalid), Err(TransactionError::InvalidByte("authorization type", 0x06)));
+        let mut invalid = bytes.clone();
+        invalid[6] = 0x04;
+        assert_eq!(StacksTransaction::deserialize(&invalid), Err(TransactionError::InvalidByte("multi-sig hash mode", 0x04)));
+        let mut invalid = bytes;
+        invalid[115] = 0x09;
+        assert_eq!(StacksTransaction::deserialize(&invalid), Err(TransactionError::InvalidByte("payload type", 0x09)));
+
+        assert!(matches!(StacksTransaction::from_hex("zz"), Err(TransactionError::InvalidHex(_))));
+        assert_eq!(TokenTransferPayload::new(Principal::from_str(ADDRESS).unwrap(), 1, &"m".repeat(35)), Err(TransactionError::MemoTooLong(35)));
+    }
+
+    /// Mainnet transaction of an unsigned single-sig origin with `payload`.
+    fn with_payload(payload: &str) -> String {
+        ["00", "00000001", "04", "00", &"aa".repeat(20), "0000000000000005", "0000000000000000", "00", &"00".repeat(65), "01", "02", "00000000", payload]
+            .concat()
+    }
+
+    fn decode_payload(payload: &str) -> Payload {
+        let hex = with_payload(payload);
+        let transaction = StacksTransaction::from_hex(&hex).unwrap();
+        assert_eq!(transaction.to_hex().unwrap(), hex);
+        transaction.payload
+    }
+
+    #[test]
+    fn test_coinbase_payloads() {
+        let data = "11".repeat(32);
+        let coinbase = decode_payload(&["04", &data].concat());
+        assert_eq!(coinbase, Payload::Coinbase(CoinbasePayload { data: [0x11; 32], recipient: None, vrf_proof: None }));
+
+        let contract = Principal::contract(StacksAddress::from_str(ADDRESS).unwrap(), "miner").unwrap();
+        let to_alt = decode_payload(&["05", &data, "06", ADDRESS_HEX, "05", "6d696e6572"].concat());
+        assert_eq!(to_alt, Payload::Coinbase(CoinbasePayload { data: [0x11; 32], recipient: Some(contract.clone()), vrf_proof: None }));
+        assert_eq!(to_alt.payload_type(), PayloadType::CoinbaseToAltRecipient);
+
+        let proof = "22".repeat(80);
+        let nakamoto = decode_payload(&["08", &data, "09", &proof].concat());
+        assert_eq!(nakamoto, Payload::Coinbase(CoinbasePayload { data: [0x11; 32], recipient: None, vrf_proof: Some([0x22; 80]) }));
+        let nakamoto = decode_payload(&["08", &data, "0a", "06", ADDRESS_HEX, "05", "6d696e6572", &proof].concat());
+        assert_eq!(nakamoto, Payload::Coinbase(CoinbasePayload { data: [0x11; 32], recipient: Some(contract), vrf_proof: Some([0x22; 80]) }));
+
+        let invalid = with_payload(&["08", &data, "0100000000000000000000000000000001", &proof].concat());
+        assert!(matches!(StacksTransaction::from_hex(&invalid), Err(TransactionError::InvalidAddress(_))));
+    }
+
+    #[test]
+    fn test_tenure_change_payload() {
+        let payload = ["07", &"01".repeat(20), &"02".repeat(20), &"03".repeat(20), &"04".repeat(32), "0000000a", "01", &"05".repeat(20)].concat();
+        let expected = TenureChangePayload {
+            tenure_consensus_hash: [1; 20],
+            prev_tenure_consensus_hash: [2; 20],
+            burn_view_consensus_hash: [3; 20],
+            previous_tenure_end: [4; 32],
+            previous_tenure_blocks: 10,
+            cause: TenureChangeCause::Extended,
+            pubkey_hash: [5; 20],
+        };
+        assert_eq!(decode_payload(&payload), Payload::TenureChange(expected));
+
+        let invalid = with_payload(&payload.replace("0000000a01", "0000000a07"));
+        assert_eq!(StacksTransaction::from_hex(&invalid), Err(TransactionError::InvalidByte("tenure change cause", 0x07)));
+    }
+
+    #[test]
+    fn test_poison_microblock_payload() {
+        let header = |sequence: &str, byte: &str| ["00", sequence, &byte.repeat(32), &"33".repeat(32), &"44".repeat(65)].concat();
+        let payload = decode_payload(&["03", &header("0001", "01"), &header("0001", "02")].concat());
+        let Payload::PoisonMicroblock(first, second) = payload else { panic!("not a poison microblock") };
+        assert_eq!((first.sequence, first.prev_block, second.prev_block), (1, [1; 32], [2; 32]));
+        assert_eq!(second.signature, MessageSignature([0x44; 65]));
+
+        let truncated = with_payload(&["03", &header("0001", "01")].concat());
+        assert_eq!(StacksTransaction::from_hex(&truncated), Err(TransactionError::UnexpectedEnd));
+    }
+
+    #[test]
+    fn test_verify_single_sig() {
+        let transaction = StacksTransaction::from_hex(TOKEN_TRANSFER).unwrap();
+        assert_eq!(transaction.verify(), Ok(()));
+
+        let mut tampered = transaction.clone();
+        let Payload::TokenTransfer(payload) = &mut tampered.payload else { panic!("not a token transfer") };
+        payload.amount += 1;
+        assert_eq!(tampered.verify(), Err(TransactionError::SignerMismatch));
+
+        let mut unsigned = transaction.clone();
+        let SpendingCondition::SingleSig(condition) = unsigned.auth.origin_mut() else { panic!("not single-sig") };
+        condition.signature = MessageSignature::empty();
+        assert_eq!(unsigned.verify(), Err(TransactionError::MissingSignatures(0, 1)));
+        let SpendingCondition::SingleSig(condition) = unsigned.auth.origin_mut() else { panic!("not single-sig") };
+        condition.signature = MessageSignature([0xff; 65]);
+        assert_eq!(unsigned.verify(), Err(TransactionError::InvalidSignature));
+    }
+
+    #[test]
+    fn test_verify_multi_sig() {
+        let keys: Vec<_> = (1..=3).map(|i| SecretKey::from_byte_array(&[i; 32]).unwrap()).collect();
+        let public_keys: Vec<_> = keys.iter().map(|key| key.public_key(secp256k1_context())).collect();
+        for hash_mode in [MultiSigHashMode::P2SH, MultiSigHashMode::P2WSH, MultiSigHashMode::P2SHNonSequential] {
+            let contract = Principal::contract(StacksAddress::from_str(ADDRESS).unwrap(), "pool").unwrap();
+            let transaction = ContractCallBuilder::new(contract, "join", vec![]).multi_sig_hash_mode(hash_mode).build_multi_sig(&public_keys, 2).unwrap();
+            let mut signer = TransactionSigner::new(transaction).unwrap();
+            signer.sign_with(&keys[0]).unwrap();
+            assert_eq!(signer.transaction().verify(), Err(TransactionError::MissingSignatures(1, 2)));
+            signer.sign_with(&keys[1]).unwrap();
+            // the third key is missing from the account
+            assert_eq!(signer.transaction().verify(), Err(TransactionError::SignerMismatch));
+            signer.append_pubkey(&public_keys[2]).unwrap();
+            let digest = signer.signing_digest();
+            let mut signed = signer.finish().unwrap();
+            assert_eq!(signed.verify(), Ok(()));
+
+            let SpendingCondition::MultiSig(condition) = signed.auth.origin_mut() else { panic!("not multisig") };
+            match hash_mode.is_sequential() {
+                true => {
+                    condition.signatures_required = 1;
+                    assert_eq!(signed.verify(), Err(TransactionError::TooManySignatures(2, 1)));
+                }
+                // order-independent signatures only need to reach the threshold
+                false => {
+                    condition.fields[2] = AuthField::Signature(sign_recoverable(&digest, &keys[2]).into(), PublicKeyEncoding::Compressed);
+                    assert_eq!(signed.verify(), Ok(()));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_verify_sponsored() {
+        let origin_key = SecretKey::from_byte_array(&[1; 32]).unwrap();
+        let sponsor_key = SecretKey::from_byte_array(&[2; 32]).unwrap();
+        let contract = Principal::contract(StacksAddress::from_str(ADDRESS).unwrap(), "pool").unwrap();
+        let transaction = ContractCallBuilder::new(contract.clone(), "join", vec![]).sponsored(true).build(&origin_key.public_key(secp256k1_context())).unwrap();
+        let mut signer = TransactionSigner::new(transaction).unwrap();
+        // the sponsor signs after the origin, over its own fee and nonce
+        assert_eq!(signer.sign_sponsor(&sponsor_key, 500, 9), Err(TransactionError::MissingSignatures(0, 1)));
+        signer.sign_with(&origin_key).unwrap();
+        let origin_sighash = signer.sighash();
+        assert!(matches!(signer.transaction().verify(), Err(TransactionError::MissingSignatures(0, 1))));
+        signer.sign_sponsor(&sponsor_key, 500, 9).unwrap();
+        assert_eq!(signer.sign_sponsor(&sponsor_key, 500, 9), Err(TransactionError::AlreadySigned));
+        let mut transaction = signer.finish().unwrap();
+        assert_eq!(transaction.verify(), Ok(()));
+        let sponsor = transaction.auth.sponsor().unwrap();
+        assert_eq!((sponsor.fee(), sponsor.nonce()), (500, 9));
+
+        // signed as if it were the origin
+        let mut sponsor = SingleSigSpendingCondition::new(&sponsor_key.public_key(secp256k1_context()), 9, 500);
+        sponsor.signature = sign_recoverable(&presign_sighash(&origin_sighash, AuthType::Standard, 500, 9), &sponsor_key).into();
+        let TransactionAuth::Sponsored(_, condition) = &mut transaction.auth else { panic!("not sponsored") };
+        *condition = SpendingCondition::SingleSig(sponsor);
+        assert_eq!(transaction.verify(), Err(TransactionError::SignerMismatch));
+
+        // a remote sponsor signs the digest
+        let unsponsored = ContractCallBuilder::new(contract.clone(), "join", vec![]).build(&origin_key.public_key(secp256k1_context())).unwrap();
+        assert_eq!(TransactionSigner::new(unsponsored).unwrap().sponsor_signing_digest(500, 9), Err(TransactionError::NotSponsored));
+        let transaction = ContractCallBuilder::new(contract, "join", vec![]).sponsored(true).build(&origin_key.public_key(secp256k1_context())).unwrap();
+        let mut signer = TransactionSigner::new(transaction).unwrap();
+        signer.sign_with(&origin_key).unwrap();
+        let digest = signer.sponsor_signing_digest(500, 9).unwrap();
+        let signature = MessageSignature::from(sign_recoverable(&digest, &sponsor_key));
+        assert_eq!(signer.add_sponsor_signature(&origin_key.public_key(secp256k1_context()), 500, 9, signature), Err(TransactionError::SignerMismatch));
+        signer.add_sponsor_signature(&sponsor_key.public_key(secp256k1_context()), 500, 9, signature).unwrap();
+        assert_eq!(signer.finish().unwrap().verify(), Ok(()));
+    }
+
+    #[test]
+    fn test_fee_estimation() {
+        let mut transaction = StacksTransaction::from_hex(TOKEN_TRANSFER).unwrap();
+        assert_eq!(transaction.estimated_len(), Ok(180));
+        assert_eq!(transaction.fee_for_rate(10), Ok(1800));
+        assert_eq!(transaction.fee_for_rate(0), Ok(180));
+        transaction.set_fee(1800);
+        assert_eq!(transaction.auth.origin().fee(), 1800);
+        assert_eq!(transaction.estimated_len(), Ok(180));
+
+        let keys: Vec<_> = (1..=3).map(|i| SecretKey::from_byte_array(&[i; 32]).unwrap()).collect();
+        let public_keys: Vec<_> = keys.iter().map(|key| key.public_key(secp256k1_context())).collect();
+        let contract = Principal::contract(StacksAddress::from_str(ADDRESS).unwrap(), "pool").unwrap();
+        let unsigned = ContractCallBuilder::new(contract, "join", vec![]).sponsored(true).build_multi_sig(&public_keys, 2).unwrap();
+        let mut signer = TransactionSigner::new(unsigned.clone()).unwrap();
+        signer.sign_with(&keys[0]).unwrap();
+        signer.sign_with(&keys[1]).unwrap();
+        signer.append_pubkey(&public_keys[2]).unwrap();
+        let signed = signer.finish().unwrap();
+        // all but the appended key
+        assert_eq!(unsigned.estimated_len(), Ok(signed.serialize().unwrap().len() - 34));
+
+        // the sponsor pays
+        let mut sponsored = signed;
+        sponsored.set_fee(300);
+        assert_eq!((sponsored.auth.origin().fee(), sponsored.auth.sponsor().unwrap().fee()), (0, 300));
+    }
+}
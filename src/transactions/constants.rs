@@ -26,12 +26,14 @@ impl TransactionVersion {
 
 pub enum PayloadType {
     TokenTransfer,
+    ContractCall,
 }
 
 impl PayloadType {
     pub fn value(&self) -> u8 {
         return match *self {
             PayloadType::TokenTransfer => 0x00,
+            PayloadType::ContractCall => 0x02,
         };
     }
 }
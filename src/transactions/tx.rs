@@ -1,3 +1,6 @@
+use crate::address::principal::Principal;
+use crate::clarity::value::{is_valid_clarity_name, ClarityValue};
+use crate::clarity::ClarityError;
 use crate::network::NetworkKind;
 use crate::transactions::authorization::*;
 use crate::transactions::clarity::ClarityType;
@@ -17,6 +20,12 @@ use std::fmt;
 pub enum PayloadSerializationError {
     MemoTooLong(usize),
     InvalidAddress(Error),
+    InvalidContractName(String),
+    InvalidAssetName(String),
+    /// Not in the `<address>.<contract name>::<asset name>` form
+    InvalidAssetId(String),
+    InvalidFunctionName(String),
+    InvalidClarityValue(ClarityError),
 }
 
 impl fmt::Display for PayloadSerializationError {
@@ -29,12 +38,44 @@ impl fmt::Display for PayloadSerializationError {
             PayloadSerializationError::InvalidAddress(_) => {
                 f.write_str(&format!("Invalid address!"))
             }
+            PayloadSerializationError::InvalidContractName(ref v) => {
+                f.write_str(&format!("Invalid contract name {}!", v))
+            }
+            PayloadSerializationError::InvalidAssetName(ref v) => {
+                f.write_str(&format!("Invalid asset name {}!", v))
+            }
+            PayloadSerializationError::InvalidAssetId(ref v) => {
+                f.write_str(&format!("Invalid asset identifier {}!", v))
+            }
+            PayloadSerializationError::InvalidFunctionName(ref v) => {
+                f.write_str(&format!("Invalid function name {}!", v))
+            }
+            PayloadSerializationError::InvalidClarityValue(ref v) => {
+                f.write_str(&format!("Invalid Clarity value: {}", v))
+            }
         }
     }
 }
 
 impl std::error::Error for PayloadSerializationError {}
 
+/// Serializes a c32check `address` as `version (1) || hash160 (20)`.
+pub(crate) fn serialize_address(address: &str) -> Result<Vec<u8>, PayloadSerializationError> {
+    let (version, data) =
+        c32_address_decode(address).map_err(PayloadSerializationError::InvalidAddress)?;
+    if data.len() != 20 {
+        return Err(PayloadSerializationError::InvalidAddress(
+            Error::InvalidLength(data.len()),
+        ));
+    }
+    let mut hash_bytes = [0u8; 20];
+    hash_bytes.copy_from_slice(&data[..]);
+    let addr = StacksAddress::new(version, Hash160(hash_bytes));
+    let mut serialization = vec![addr.version];
+    serialization.extend(addr.bytes.as_bytes());
+    Ok(serialization)
+}
+
 pub trait Serialize {
     fn serialize(&self) -> Result<Vec<u8>, PayloadSerializationError>;
     fn deserialize(serialized: Vec<u8>) -> Self;
@@ -55,18 +96,8 @@ impl Serialize for TokenTransferPayload {
 
         let mut serialization: Vec<u8> = vec![];
         serialization.extend(vec![PayloadType::TokenTransfer.value()]);
-        match c32_address_decode(&self.recipient) {
-            Ok(v) => {
-                let (version, data) = v;
-                let mut hash_bytes = [0u8; 20];
-                hash_bytes.copy_from_slice(&data[..]);
-                let addr = StacksAddress::new(version, Hash160(hash_bytes));
-                serialization.extend(vec![ClarityType::Address.value()]);
-                serialization.extend(addr.version.to_be_bytes());
-                serialization.extend(addr.bytes.as_bytes().to_vec());
-            }
-            Err(e) => return Err(PayloadSerializationError::InvalidAddress(e)),
-        }
+        serialization.extend(vec![ClarityType::Address.value()]);
+        serialization.extend(serialize_address(&self.recipient)?);
 
         serialization.extend(self.amount.to_be_bytes());
 
@@ -99,8 +130,85 @@ impl Serialize for TokenTransferPayload {
     }
 }
 
+pub struct ContractCallPayload {
+    pub contract_address: String,
+    pub contract_name: String,
+    pub function_name: String,
+    pub function_args: Vec<ClarityValue>,
+}
+
+impl Serialize for ContractCallPayload {
+    fn serialize(&self) -> Result<Vec<u8>, PayloadSerializationError> {
+        if !Principal::is_valid_contract_name(&self.contract_name) {
+            return Err(PayloadSerializationError::InvalidContractName(
+                self.contract_name.clone(),
+            ));
+        }
+        if !is_valid_clarity_name(&self.function_name) {
+            return Err(PayloadSerializationError::InvalidFunctionName(
+                self.function_name.clone(),
+            ));
+        }
+
+        let mut serialization: Vec<u8> = vec![PayloadType::ContractCall.value()];
+        serialization.extend(serialize_address(&self.contract_address)?);
+        serialization.push(self.contract_name.len() as u8);
+        serialization.extend(self.contract_name.as_bytes());
+        serialization.push(self.function_name.len() as u8);
+        serialization.extend(self.function_name.as_bytes());
+
+        serialization.extend((self.function_args.len() as u32).to_be_bytes());
+        for arg in &self.function_args {
+            serialization.extend(
+                arg.serialize()
+                    .map_err(PayloadSerializationError::InvalidClarityValue)?,
+            );
+        }
+
+        Ok(serialization)
+    }
+
+    fn deserialize(serialized: Vec<u8>) -> ContractCallPayload {
+        assert!(serialized.len() >= 23, "Slice has fewer than 23 elements!");
+        let contract_address = c32_address(serialized[1], &serialized[2..22]).unwrap();
+
+        let mut position = 22;
+        let mut name = || {
+            let name_len = serialized[position] as usize;
+            assert!(serialized.len() >= position + 1 + name_len);
+            let name = String::from_utf8(serialized[position + 1..position + 1 + name_len].to_vec())
+                .expect("Invalid UTF-8");
+            position += 1 + name_len;
+            name
+        };
+        let contract_name = name();
+        let function_name = name();
+
+        assert!(serialized.len() >= position + 4);
+        let mut count_bytes: [u8; 4] = [0; 4];
+        count_bytes.copy_from_slice(&serialized[position..position + 4]);
+        position += 4;
+
+        let mut function_args = vec![];
+        for _ in 0..u32::from_be_bytes(count_bytes) {
+            let (arg, read) = ClarityValue::deserialize_prefix(&serialized[position..])
+                .expect("Invalid Clarity value");
+            function_args.push(arg);
+            position += read;
+        }
+
+        ContractCallPayload {
+            contract_address,
+            contract_name,
+            function_name,
+            function_args,
+        }
+    }
+}
+
 pub enum Payload {
     TokenTransfer(TokenTransferPayload),
+    ContractCall(ContractCallPayload),
 }
 
 pub struct StacksTransaction {
@@ -149,7 +257,7 @@ pub fn build_single_sig_stx_token_transfer_transaction(
             memo,
             recipient,
         }),
-        network: network.clone(),
+        network,
         post_condition_mode: PostConditionMode::Deny, // Token transfer cannot have post conditions
         version: TransactionVersion::from_network(&network),
         authorization: Authorization::Standard(authorization),
@@ -296,6 +404,48 @@ mod tests {
                     String::from("SP3FGQ8Z7JY9BWYZ5WM53E0M9NK7WHJF0691NZ159")
                 );
             }
+            Payload::ContractCall(_) => {
+                assert_eq!(true, false);
+            }
         }
     }
+
+    #[test]
+    fn payload_contract_call_serialize() {
+        let payload = ContractCallPayload {
+            contract_address: String::from("SP3FGQ8Z7JY9BWYZ5WM53E0M9NK7WHJF0691NZ159"),
+            contract_name: String::from("token"),
+            function_name: String::from("get-balance"),
+            function_args: vec![ClarityValue::UInt(1), ClarityValue::Bool(true)],
+        };
+
+        let serialized = payload.serialize().unwrap();
+        assert_eq!(hex::encode(&serialized), String::from("0216df0ba3e79792be7be5e50a370289accfc8c9e03205746f6b656e0b6765742d62616c616e636500000002010000000000000000000000000000000103"));
+
+        let deserialized = ContractCallPayload::deserialize(serialized);
+        assert_eq!(deserialized.contract_address, payload.contract_address);
+        assert_eq!(deserialized.function_name, payload.function_name);
+        assert_eq!(deserialized.function_args, payload.function_args);
+    }
+
+    #[test]
+    fn payload_contract_call_serialize_invalid_names() {
+        let mut payload = ContractCallPayload {
+            contract_address: String::from("SP3FGQ8Z7JY9BWYZ5WM53E0M9NK7WHJF0691NZ159"),
+            contract_name: String::from("1token"),
+            function_name: String::from("transfer"),
+            function_args: vec![],
+        };
+        assert!(matches!(
+            payload.serialize(),
+            Err(PayloadSerializationError::InvalidContractName(_))
+        ));
+
+        payload.contract_name = String::from("token");
+        payload.function_name = String::from("transfer token");
+        assert!(matches!(
+            payload.serialize(),
+            Err(PayloadSerializationError::InvalidFunctionName(_))
+        ));
+    }
 }
@@ -0,0 +1,117 @@
+//! Bytewords (BCR-2020-012): bytes as four-letter English words, with a CRC32 checksum.
+
+use super::UrError;
+
+const WORDS: &str = "ableacidalsoapexaquaarchatomauntawayaxisbackbaldbarnbeltbetabiasbluebodybragbrewbulbbuzzcalmcashcatschefcityclawcodecolacookcostcruxcurlcuspcyandarkdatadaysdelidicedietdoordowndrawdropdrumdulldutyeacheasyechoedgeepicevenexamexiteyesfactfairfernfigsfilmfishfizzflapflewfluxfoxyfreefrogfuelfundgalagamegeargemsgiftgirlglowgoodgraygrimgurugushgyrohalfhanghardhawkheathelphighhillholyhopehornhutsicedideaidleinchinkyintoirisironitemjadejazzjoinjoltjowljudojugsjumpjunkjurykeepkenokeptkeyskickkilnkingkitekiwiknoblamblavalazyleaflegsliarlimplionlistlogoloudloveluaulucklungmainmanymathmazememomenumeowmildmintmissmonknailnavyneednewsnextnoonnotenumbobeyoboeomitonyxopenovalowlspaidpartpeckplaypluspoempoolposepuffpumapurrquadquizraceramprealredorichroadrockroofrubyruinrunsrustsafesagascarsetssilkskewslotsoapsolosongstubsurfswantacotasktaxitenttiedtimetinytoiltombtoystriptunatwinuglyundouniturgeuservastveryvetovialvibeviewvisavoidvowswallwandwarmwaspwavewaxywebswhatwhenwhizwolfworkyankyawnyellyogayurtzapszerozestzinczonezoom";
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Style {
+    /// Words separated by spaces
+    Standard,
+    /// Words separated by dashes, for URIs
+    Uri,
+    /// First and last letters of each word, as in URs
+    Minimal,
+}
+
+fn word(byte: u8) -> &'static str {
+    let start = byte as usize * 4;
+    &WORDS[start..start + 4]
+}
+
+/// Index of the word whose first and last letters are `first` and `last`.
+fn byte_of(first: u8, last: u8) -> Option<u8> {
+    WORDS.as_bytes().chunks(4).position(|word| word[0] == first && word[3] == last).map(|index| index as u8)
+}
+
+/// CRC-32 (ISO-HDLC) of `data`, the checksum of bytewords and multi-part URs.
+pub fn crc32(data: &[u8]) -> u32 {
+    let mut crc = !0u32;
+    for byte in data {
+        crc ^= *byte as u32;
+        for _ in 0..8 {
+            crc = (crc >> 1) ^ (0xedb88320 & (crc & 1).wrapping_neg());
+        }
+    }
+    !crc
+}
+
+/// Encodes `data` followed by its checksum.
+///
+/// Usage:
+/// ```rust
+/// use stacks_rs::ur::bytewords::{decode, encode, Style};
+/// let words = encode(&[0, 1, 2, 128, 255], Style::Standard);
+/// assert_eq!(words, "able acid also lava zoom jade need echo taxi");
+/// assert_eq!(decode(&words, Style::Standard).unwrap(), [0, 1, 2, 128, 255]);
+/// ```
+pub fn encode(data: &[u8], style: Style) -> String {
+    let mut bytes = data.to_vec();
+    bytes.extend(crc32(data).to_be_bytes());
+    let words = bytes.iter().map(|byte| word(*byte));
+    match style {
+        Style::Standard => words.collect::<Vec<_>>().join(" "),
+        Style::Uri => words.collect::<Vec<_>>().join("-"),
+        Style::Minimal => words.map(|word| format!("{}{}", &word[..1], &word[3..])).collect(),
+    }
+}
+
+/// Inverse of [`encode`], case-insensitive; fails on unknown words or a wrong checksum.
+pub fn decode(words: &str, style: Style) -> Result<Vec<u8>, UrError> {
+    let words = words.to_ascii_lowercase();
+    let pairs: Vec<(u8, u8)> = match style {
+        Style::Standard | Style::Uri => {
+            let separator = if style == Style::Standard { ' ' } else { '-' };
+            words
+                .split(separator)
+                .map(|word| match word.as_bytes() {
+                    [first, _, _, last] if WORDS.as_bytes().chunks(4).any(|known| known == word.as_bytes()) => Ok((*first, *last)),
+                    _ => Err(UrError::InvalidBytewords(format!("unknown word {word:?}"))),
+                })
+                .collect::<Result<_, _>>()?
+        }
+        Style::Minimal if words.len().is_multiple_of(2) => words.as_bytes().chunks(2).map(|pair| (pair[0], pair[1])).collect(),
+        Style::Minimal => return Err(UrError::InvalidBytewords(String::from("odd length"))),
+    };
+    let bytes = pairs
+        .into_iter()
+        .map(|(first, last)| byte_of(first, last).ok_or_else(|| UrError::InvalidBytewords(format!("unknown word {}{}", first as char, last as char))))
+        .collect::<Result<Vec<u8>, _>>()?;
+    if bytes.len() < 5 {
+        return Err(UrError::InvalidBytewords(String::from("too short")));
+    }
+    let (data, checksum) = bytes.split_at(bytes.len() - 4);
+    if crc32(data).to_be_bytes() != checksum {
+        return Err(UrError::InvalidChecksum);
+    }
+    Ok(data.to_vec())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use super::*;
+
+    #[test]
+    fn test_word_list() {
+        assert_eq!(WORDS.len(), 256 * 4);
+        let minimal: HashSet<(u8, u8)> = WORDS.as_bytes().chunks(4).map(|word| (word[0], word[3])).collect();
+        assert_eq!(minimal.len(), 256);
+        assert_eq!((word(0), word(128), word(255)), ("able", "lava", "zoom"));
+    }
+
+    #[test]
+    fn test_styles() {
+        let data = [0, 1, 2, 128, 255];
+        assert_eq!(encode(&data, Style::Uri), "able-acid-also-lava-zoom-jade-need-echo-taxi");
+        assert_eq!(encode(&data, Style::Minimal), "aeadaolazmjendeoti");
+        assert_eq!(decode("AEADAOLAZMJENDEOTI", Style::Minimal).unwrap(), data);
+        assert_eq!(decode("able-acid-also-lava-zoom-jade-need-echo-taxi", Style::Uri).unwrap(), data);
+        assert_eq!(crc32(b"Hello, world!"), 0xebe6c6e6);
+
+        assert_eq!(decode("aeadaolazmjendeotd", Style::Minimal), Err(UrError::InvalidChecksum));
+        assert!(matches!(decode("able acid also lava zoom jade need echo tax", Style::Standard), Err(UrError::InvalidBytewords(_))));
+        assert!(matches!(decode("aeadaolazmjendeot", Style::Minimal), Err(UrError::InvalidBytewords(_))));
+    }
+}
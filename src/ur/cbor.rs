@@ -0,0 +1,168 @@
+//! The subset of CBOR (RFC 8949) that URs carry: definite-length items, no floats.
+
+use super::UrError;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) enum Cbor {
+    Unsigned(u64),
+    Bytes(Vec<u8>),
+    Text(String),
+    Array(Vec<Cbor>),
+    Map(Vec<(Cbor, Cbor)>),
+    Tag(u64, Box<Cbor>),
+    Bool(bool),
+}
+
+fn encode_header(major: u8, value: u64, bytes: &mut Vec<u8>) {
+    let major = major << 5;
+    match value {
+        0..=23 => bytes.push(major | value as u8),
+        24..=0xff => bytes.extend([major | 24, value as u8]),
+        0x100..=0xffff => {
+            bytes.push(major | 25);
+            bytes.extend((value as u16).to_be_bytes());
+        }
+        0x10000..=0xffff_ffff => {
+            bytes.push(major | 26);
+            bytes.extend((value as u32).to_be_bytes());
+        }
+        _ => {
+            bytes.push(major | 27);
+            bytes.extend(value.to_be_bytes());
+        }
+    }
+}
+
+impl Cbor {
+    pub(crate) fn encode(&self) -> Vec<u8> {
+        let mut bytes = vec![];
+        self.encode_into(&mut bytes);
+        bytes
+    }
+
+    fn encode_into(&self, bytes: &mut Vec<u8>) {
+        match self {
+            Cbor::Unsigned(value) => encode_header(0, *value, bytes),
+            Cbor::Bytes(data) => {
+                encode_header(2, data.len() as u64, bytes);
+                bytes.extend(data);
+            }
+            Cbor::Text(text) => {
+                encode_header(3, text.len() as u64, bytes);
+                bytes.extend(text.as_bytes());
+            }
+            Cbor::Array(items) => {
+                encode_header(4, items.len() as u64, bytes);
+                items.iter().for_each(|item| item.encode_into(bytes));
+            }
+            Cbor::Map(entries) => {
+                encode_header(5, entries.len() as u64, bytes);
+                for (key, value) in entries {
+                    key.encode_into(bytes);
+                    value.encode_into(bytes);
+                }
+            }
+            Cbor::Tag(tag, item) => {
+                encode_header(6, *tag, bytes);
+                item.encode_into(bytes);
+            }
+            Cbor::Bool(value) => bytes.push(0xf4 | *value as u8),
+        }
+    }
+
+    /// Decodes exactly one item spanning all of `bytes`.
+    pub(crate) fn decode(bytes: &[u8]) -> Result<Self, UrError> {
+        let mut position = 0;
+        let item = Self::decode_item(bytes, &mut position, 0)?;
+        match position == bytes.len() {
+            true => Ok(item),
+            false => Err(UrError::InvalidCbor(format!("{} trailing bytes", bytes.len() - position))),
+        }
+    }
+
+    fn decode_item(bytes: &[u8], position: &mut usize, depth: usize) -> Result<Self, UrError> {
+        const MAX_DEPTH: usize = 16;
+        if depth > MAX_DEPTH {
+            return Err(UrError::InvalidCbor(String::from("nested too deep")));
+        }
+        let mut take = |len: usize| -> Result<&[u8], UrError> {
+            let end = position.checked_add(len).filter(|end| *end <= bytes.len()).ok_or_else(|| UrError::InvalidCbor(String::from("unexpected end")))?;
+            let taken = &bytes[*position..end];
+            *position = end;
+            Ok(taken)
+        };
+        let initial = take(1)?[0];
+        let (major, info) = (initial >> 5, initial & 0x1f);
+        let value = match info {
+            0..=23 => info as u64,
+            24 => take(1)?[0] as u64,
+            25 => u16::from_be_bytes(take(2)?.try_into().unwrap()) as u64,
+            26 => u32::from_be_bytes(take(4)?.try_into().unwrap()) as u64,
+            27 => u64::from_be_bytes(take(8)?.try_into().unwrap()),
+            _ => return Err(UrError::InvalidCbor(format!("unsupported item {initial:#04x}"))),
+        };
+        // lengths are bounded by the input, so a forged length cannot allocate much
+        let len = usize::try_from(value).ok().filter(|len| *len <= bytes.len());
+        let len = || len.ok_or_else(|| UrError::InvalidCbor(String::from("unexpected end")));
+        match major {
+            0 => Ok(Cbor::Unsigned(value)),
+            2 => Ok(Cbor::Bytes(take(len()?)?.to_vec())),
+            3 => String::from_utf8(take(len()?)?.to_vec()).map(Cbor::Text).map_err(|_| UrError::InvalidCbor(String::from("invalid text"))),
+            4 => (0..len()?).map(|_| Self::decode_item(bytes, position, depth + 1)).collect::<Result<_, _>>().map(Cbor::Array),
+            5 => (0..len()?)
+                .map(|_| Ok((Self::decode_item(bytes, position, depth + 1)?, Self::decode_item(bytes, position, depth + 1)?)))
+                .collect::<Result<_, _>>()
+                .map(Cbor::Map),
+            6 => Ok(Cbor::Tag(value, Box::new(Self::decode_item(bytes, position, depth + 1)?))),
+            7 if info == 20 || info == 21 => Ok(Cbor::Bool(info == 21)),
+            _ => Err(UrError::InvalidCbor(format!("unsupported item {initial:#04x}"))),
+        }
+    }
+
+    pub(crate) fn as_unsigned(&self) -> Result<u64, UrError> {
+        match self {
+            Cbor::Unsigned(value) => Ok(*value),
+            _ => Err(UrError::InvalidCbor(String::from("expected an unsigned integer"))),
+        }
+    }
+
+    pub(crate) fn into_bytes(self) -> Result<Vec<u8>, UrError> {
+        match self {
+            Cbor::Bytes(data) => Ok(data),
+            _ => Err(UrError::InvalidCbor(String::from("expected a byte string"))),
+        }
+    }
+
+    /// Value of the unsigned `key` of a map.
+    pub(crate) fn get(&self, key: u64) -> Option<&Cbor> {
+        match self {
+            Cbor::Map(entries) => entries.iter().find(|(k, _)| *k == Cbor::Unsigned(key)).map(|(_, value)| value),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_round_trip() {
+        let item = Cbor::Map(vec![
+            (Cbor::Unsigned(3), Cbor::Bytes(vec![0xab; 33])),
+            (Cbor::Unsigned(6), Cbor::Tag(304, Box::new(Cbor::Array(vec![Cbor::Unsigned(44), Cbor::Bool(true)])))),
+            (Cbor::Unsigned(9), Cbor::Text(String::from("stacks"))),
+            (Cbor::Unsigned(0x1_0000_0000), Cbor::Unsigned(1000)),
+        ]);
+        let bytes = item.encode();
+        assert_eq!(&bytes[..4], [0xa4, 0x03, 0x58, 0x21]);
+        assert_eq!(Cbor::decode(&bytes).unwrap(), item);
+        assert_eq!(item.get(9), Some(&Cbor::Text(String::from("stacks"))));
+
+        assert!(matches!(Cbor::decode(&bytes[..bytes.len() - 1]), Err(UrError::InvalidCbor(_))));
+        assert!(matches!(Cbor::decode(&[0x00, 0x00]), Err(UrError::InvalidCbor(_))));
+        // indefinite length, and a length beyond the input
+        assert!(matches!(Cbor::decode(&[0x5f]), Err(UrError::InvalidCbor(_))));
+        assert!(matches!(Cbor::decode(&[0x9b, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff]), Err(UrError::InvalidCbor(_))));
+    }
+}
@@ -0,0 +1,337 @@
+//! Fountain codes (BCR-2020-005): a message split in fragments, sent as an endless stream of
+//! parts mixing them, so a reader can start anywhere and miss any part.
+
+use std::collections::BTreeMap;
+
+use super::bytewords::crc32;
+use super::cbor::Cbor;
+use super::xoshiro::{WeightedSampler, Xoshiro256};
+use super::UrError;
+
+/// Smallest fragment the encoder cuts, whatever the maximum.
+pub const MIN_FRAGMENT_LENGTH: usize = 10;
+/// Longest message split or rebuilt, in bytes.
+pub const MAX_MESSAGE_LENGTH: usize = 1 << 24;
+/// Most fragments of a message.
+pub const MAX_SEQUENCE_COUNT: usize = 1 << 16;
+
+/// One part of the stream: a fragment, or several fragments XORed together.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FountainPart {
+    /// 1-based; parts up to `sequence_count` are the fragments in order
+    pub sequence: u32,
+    pub sequence_count: usize,
+    pub message_length: usize,
+    /// CRC-32 of the whole message
+    pub checksum: u32,
+    pub data: Vec<u8>,
+}
+
+impl FountainPart {
+    pub fn to_cbor(&self) -> Vec<u8> {
+        Cbor::Array(vec![
+            Cbor::Unsigned(self.sequence as u64),
+            Cbor::Unsigned(self.sequence_count as u64),
+            Cbor::Unsigned(self.message_length as u64),
+            Cbor::Unsigned(self.checksum as u64),
+            Cbor::Bytes(self.data.clone()),
+        ])
+        .encode()
+    }
+
+    pub fn from_cbor(bytes: &[u8]) -> Result<Self, UrError> {
+        let Cbor::Array(items) = Cbor::decode(bytes)? else {
+            return Err(UrError::InvalidCbor(String::from("expected a fountain part")));
+        };
+        let Ok([sequence, sequence_count, message_length, checksum, data]) = <[Cbor; 5]>::try_from(items) else {
+            return Err(UrError::InvalidCbor(String::from("expected a fountain part")));
+        };
+        let to_u32 = |item: Cbor| u32::try_from(item.as_unsigned()?).map_err(|_| UrError::InvalidCbor(String::from("integer out of range")));
+        let part = FountainPart {
+            sequence: to_u32(sequence)?,
+            sequence_count: to_u32(sequence_count)? as usize,
+            message_length: to_u32(message_length)? as usize,
+            checksum: to_u32(checksum)?,
+            data: data.into_bytes()?,
+        };
+        part.check()?;
+        Ok(part)
+    }
+
+    /// Checks the part is one of a message of at most [`MAX_MESSAGE_LENGTH`] bytes in at most
+    /// [`MAX_SEQUENCE_COUNT`] fragments of its length, before choosing its fragments.
+    fn check(&self) -> Result<(), UrError> {
+        let valid = self.sequence > 0
+            && (1..=MAX_SEQUENCE_COUNT).contains(&self.sequence_count)
+            && (1..=MAX_MESSAGE_LENGTH).contains(&self.message_length)
+            && !self.data.is_empty()
+            && self.data.len().checked_mul(self.sequence_count).is_some_and(|length| length >= self.message_length)
+            && self.sequence_count == self.message_length.div_ceil(self.data.len());
+        match valid {
+            true => Ok(()),
+            false => Err(UrError::InvalidPart),
+        }
+    }
+
+    /// Indexes of the fragments mixed in this part.
+    fn indexes(&self) -> Vec<usize> {
+        choose_fragments(self.sequence, self.sequence_count, self.checksum)
+    }
+}
+
+/// Length of the fragments: the largest that splits the message in as few fragments of at
+/// most `max_fragment_length` bytes as possible.
+pub(crate) fn fragment_length(message_length: usize, max_fragment_length: usize) -> usize {
+    let max_fragment_count = (message_length / MIN_FRAGMENT_LENGTH).max(1);
+    let mut fragment_length = message_length;
+    for fragment_count in 1..=max_fragment_count {
+        fragment_length = message_length.div_ceil(fragment_count);
+        if fragment_length <= max_fragment_length {
+            break;
+        }
+    }
+    fragment_length
+}
+
+/// The fragments of part `sequence`: the fragment itself for the first `sequence_count` parts,
+/// then a pseudo-random set whose size follows the ideal soliton distribution.
+fn choose_fragments(sequence: u32, sequence_count: usize, checksum: u32) -> Vec<usize> {
+    if sequence as usize <= sequence_count {
+        return vec![sequence as usize - 1];
+    }
+    let mut seed = sequence.to_be_bytes().to_vec();
+    seed.extend(checksum.to_be_bytes());
+    let mut rng = Xoshiro256::new(&seed);
+    let weights: Vec<f64> = (1..=sequence_count).map(|i| 1.0 / i as f64).collect();
+    let degree = WeightedSampler::new(&weights).next(&mut rng) + 1;
+    let mut indexes = rng.choose(sequence_count, degree);
+    indexes.sort_unstable();
+    indexes
+}
+
+fn xor_into(target: &mut [u8], other: &[u8]) {
+    target.iter_mut().zip(other).for_each(|(a, b)| *a ^= b);
+}
+
+/// Emits the parts of a message, forever.
+pub struct FountainEncoder {
+    fragments: Vec<Vec<u8>>,
+    message_length: usize,
+    checksum: u32,
+    sequence: u32,
+}
+
+impl FountainEncoder {
+    /// Splits `message` in fragments of at most `max_fragment_length` bytes; an empty or too
+    /// long message, or a `max_fragment_length` of 0, is a [`UrError::InvalidMessage`].
+    pub fn new(message: &[u8], max_fragment_length: usize) -> Result<Self, UrError> {
+        if message.is_empty() || message.len() > MAX_MESSAGE_LENGTH || max_fragment_length == 0 {
+            return Err(UrError::InvalidMessage(format!("cannot split {} bytes in fragments of {max_fragment_length} bytes", message.len())));
+        }
+        let length = fragment_length(message.len(), max_fragment_length);
+        if message.len().div_ceil(length) > MAX_SEQUENCE_COUNT {
+            return Err(UrError::InvalidMessage(format!("more than {MAX_SEQUENCE_COUNT} fragments of {length} bytes")));
+        }
+        let fragments = message
+            .chunks(length)
+            .map(|chunk| {
+                let mut fragment = chunk.to_vec();
+                fragment.resize(length, 0);
+                fragment
+            })
+            .collect();
+        Ok(FountainEncoder { fragments, message_length: message.len(), checksum: crc32(message), sequence: 0 })
+    }
+
+    pub fn sequence_count(&self) -> usize {
+        self.fragments.len()
+    }
+
+    /// Sequence of the last part emitted, 0 before the first.
+    pub fn sequence(&self) -> u32 {
+        self.sequence
+    }
+
+    pub fn next_part(&mut self) -> FountainPart {
+        self.sequence = self.sequence.wrapping_add(1).max(1);
+        let indexes = choose_fragments(self.sequence, self.sequence_count(), self.checksum);
+        let mut data = vec![0; self.fragments[0].len()];
+        indexes.iter().for_each(|index| xor_into(&mut data, &self.fragments[*index]));
+        FountainPart {
+            sequence: self.sequence,
+            sequence_count: self.sequence_count(),
+            message_length: self.message_length,
+            checksum: self.checksum,
+            data,
+        }
+    }
+}
+
+/// Rebuilds a message from the parts of a [`FountainEncoder`], received in any order.
+#[derive(Debug, Default)]
+pub struct FountainDecoder {
+    /// Sequence count, message length, checksum and fragment length of the first part
+    expected: Option<(usize, usize, u32, usize)>,
+    fragments: BTreeMap<usize, Vec<u8>>,
+    /// Parts still mixing several missing fragments
+    mixed: Vec<(Vec<usize>, Vec<u8>)>,
+    message: Option<Vec<u8>>,
+}
+
+impl FountainDecoder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds `part`, returning whether the message is complete.
+    pub fn receive(&mut self, part: FountainPart) -> Result<bool, UrError> {
+        if self.message.is_some() {
+            return Ok(true);
+        }
+        part.check()?;
+        let parameters = (part.sequence_count, part.message_length, part.checksum, part.data.len());
+        if *self.expected.get_or_insert(parameters) != parameters {
+            return Err(UrError::InvalidPart);
+        }
+        let mut queue = vec![(part.indexes(), part.data)];
+        while let Some((mut indexes, mut data)) = queue.pop() {
+            indexes.retain(|index| match self.fragments.get(index) {
+                Some(fragment) => {
+                    xor_into(&mut data, fragment);
+                    false
+                }
+                None => true,
+            });
+            match indexes.as_slice() {
+                [] => {}
+                [index] => {
+                    let index = *index;
+                    for (mut indexes, mut mixed) in std::mem::take(&mut self.mixed) {
+                        if let Some(position) = indexes.iter().position(|other| *other == index) {
+                            indexes.remove(position);
+                            xor_into(&mut mixed, &data);
+                        }
+                        match indexes.len() {
+                            1 => queue.push((indexes, mixed)),
+                            _ => self.mixed.push((indexes, mixed)),
+                        }
+                    }
+                    self.fragments.insert(index, data);
+                }
+                _ => {
+                    if !self.mixed.iter().any(|(other, _)| *other == indexes) {
+                        self.mixed.push((indexes, data));
+                    }
+                }
+            }
+        }
+        let (sequence_count, message_length, checksum, _) = parameters;
+        if self.fragments.len() == sequence_count {
+            let mut message: Vec<u8> = std::mem::take(&mut self.fragments).into_values().flatten().collect();
+            message.truncate(message_length);
+            if crc32(&message) != checksum {
+                *self = Self::default();
+                return Err(UrError::InvalidChecksum);
+            }
+            self.message = Some(message);
+        }
+        Ok(self.message.is_some())
+    }
+
+    /// Fragments recovered and fragments of the message, `(0, 0)` before the first part.
+    pub fn progress(&self) -> (usize, usize) {
+        match (&self.message, self.expected) {
+            (_, None) => (0, 0),
+            (Some(_), Some((count, ..))) => (count, count),
+            (None, Some((count, ..))) => (self.fragments.len(), count),
+        }
+    }
+
+    pub fn message(&self) -> Option<&[u8]> {
+        self.message.as_deref()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fragment_length() {
+        assert_eq!(fragment_length(12345, 1955), 1764);
+        assert_eq!(fragment_length(12345, 30000), 12345);
+        assert_eq!(fragment_length(5, 30), 5);
+    }
+
+    #[test]
+    fn test_mixed_parts_decode() {
+        let message = Xoshiro256::new(b"Wolf").next_data(1024);
+        let mut encoder = FountainEncoder::new(&message, 100).unwrap();
+        assert_eq!(encoder.sequence_count(), 11);
+        let mut decoder = FountainDecoder::new();
+        // a reader who misses every other part
+        loop {
+            let part = encoder.next_part();
+            if part.sequence % 2 == 1 {
+                continue;
+            }
+            let part = FountainPart::from_cbor(&part.to_cbor()).unwrap();
+            if decoder.receive(part).unwrap() {
+                break;
+            }
+        }
+        assert!(encoder.sequence() > 11);
+        assert_eq!(decoder.message(), Some(message.as_slice()));
+        assert_eq!(decoder.progress(), (11, 11));
+    }
+
+    #[test]
+    fn test_inconsistent_parts() {
+        let mut decoder = FountainDecoder::new();
+        let mut first = FountainEncoder::new(&[1; 100], 30).unwrap();
+        let mut other = FountainEncoder::new(&[2; 100], 30).unwrap();
+        decoder.receive(first.next_part()).unwrap();
+        assert_eq!(decoder.receive(other.next_part()), Err(UrError::InvalidPart));
+        let mut corrupted = first.next_part();
+        corrupted.data.pop();
+        assert_eq!(decoder.receive(corrupted), Err(UrError::InvalidPart));
+    }
+
+    #[test]
+    fn test_choose_fragments() {
+        let message = Xoshiro256::new(b"Wolf").next_data(1024);
+        let checksum = crc32(&message);
+        let indexes: Vec<Vec<usize>> = (1..=30).map(|sequence| choose_fragments(sequence, 11, checksum)).collect();
+        let expected: [&[usize]; 30] = [
+            &[0], &[1], &[2], &[3], &[4], &[5], &[6], &[7], &[8], &[9], &[10], &[9], &[2, 5, 6, 8, 9, 10], &[8], &[1, 5], &[1],
+            &[0, 2, 4, 5, 8, 10], &[5], &[2], &[2], &[0, 1, 3, 4, 5, 7, 9, 10], &[0, 1, 2, 3, 5, 6, 8, 9, 10], &[0, 2, 4, 5, 7, 8, 9, 10],
+            &[3, 5], &[4], &[0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10], &[0, 1, 3, 4, 5, 6, 7, 9, 10], &[6], &[5, 6], &[7],
+        ];
+        assert_eq!(indexes, expected);
+    }
+
+    #[test]
+    fn test_oversized_parts() {
+        let part = |sequence_count: usize, message_length: usize, data: Vec<u8>| {
+            let part = FountainPart { sequence: 12, sequence_count, message_length, checksum: 1, data };
+            FountainPart::from_cbor(&part.to_cbor())
+        };
+        assert!(part(2, 20, vec![0; 10]).is_ok());
+        assert_eq!(part(u32::MAX as usize, u32::MAX as usize, vec![0; 1]), Err(UrError::InvalidPart));
+        assert_eq!(part(MAX_SEQUENCE_COUNT + 1, MAX_SEQUENCE_COUNT + 1, vec![0; 1]), Err(UrError::InvalidPart));
+        assert_eq!(part(1, MAX_MESSAGE_LENGTH + 1, vec![0; MAX_MESSAGE_LENGTH + 1]), Err(UrError::InvalidPart));
+        // fragments too short for the message
+        assert_eq!(part(2, 30, vec![0; 10]), Err(UrError::InvalidPart));
+        assert_eq!(part(0, 0, vec![]), Err(UrError::InvalidPart));
+        let empty = FountainPart { sequence: 12, sequence_count: 0, message_length: 0, checksum: 1, data: vec![0] };
+        assert_eq!(FountainDecoder::new().receive(empty), Err(UrError::InvalidPart));
+    }
+
+    #[test]
+    fn test_invalid_messages() {
+        assert!(matches!(FountainEncoder::new(&[], 10), Err(UrError::InvalidMessage(_))));
+        assert!(matches!(FountainEncoder::new(&[1; 10], 0), Err(UrError::InvalidMessage(_))));
+        assert!(matches!(FountainEncoder::new(&vec![1; MAX_MESSAGE_LENGTH + 1], 100), Err(UrError::InvalidMessage(_))));
+        assert!(matches!(FountainEncoder::new(&vec![1; 20 * MAX_SEQUENCE_COUNT], 10), Err(UrError::InvalidMessage(_))));
+    }
+}
@@ -0,0 +1,387 @@
+//! Uniform Resources (BCR-2020-005): CBOR payloads as bytewords, split in fountain-coded parts
+//! for animated QR codes, to move transactions and keys to and from air-gapped devices.
+//!
+//! Usage:
+//! ```rust
+//! use stacks_rs::ur::{Ur, UrDecoder, UrEncoder};
+//! let ur = Ur::bytes(&[7; 200]);
+//! let mut encoder = UrEncoder::new(&ur, 60).unwrap();
+//! let mut decoder = UrDecoder::new();
+//! while !decoder.is_complete() {
+//!     decoder.receive(&encoder.next_part()).unwrap();
+//! }
+//! assert_eq!(decoder.result(), Some(&ur));
+//! assert_eq!(decoder.result().unwrap().to_bytes().unwrap(), [7; 200]);
+//! ```
+
+use std::fmt;
+use std::str::FromStr;
+
+use secp256k1::PublicKey;
+
+use crate::bip32::child_number::ChildNumber;
+use crate::crypto::keys::common_attrs::ExtendedKeyAttrs;
+use crate::crypto::keys::extended_public_key::{ExtendedPublicKey, ExtendedPublicKeyMethods};
+use crate::transaction::stacks_transaction::StacksTransaction;
+use crate::transaction::TransactionError;
+
+use bytewords::Style;
+use cbor::Cbor;
+use fountain::{FountainDecoder, FountainEncoder, FountainPart};
+
+pub mod bytewords;
+pub(crate) mod cbor;
+pub mod fountain;
+pub(crate) mod xoshiro;
+
+/// A CBOR byte string.
+pub const UR_TYPE_BYTES: &str = "bytes";
+/// A serialized Stacks transaction, signed or not, as a CBOR byte string.
+pub const UR_TYPE_TRANSACTION: &str = "stacks-tx";
+/// An extended public key (BCR-2020-007).
+pub const UR_TYPE_HDKEY: &str = "crypto-hdkey";
+
+const HDKEY_IS_PRIVATE: u64 = 2;
+const HDKEY_KEY_DATA: u64 = 3;
+const HDKEY_CHAIN_CODE: u64 = 4;
+const HDKEY_PARENT_FINGERPRINT: u64 = 8;
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum UrError {
+    InvalidBytewords(String),
+    InvalidChecksum,
+    InvalidCbor(String),
+    /// A fountain part that is inconsistent, too large, or does not belong to the message being
+    /// decoded
+    InvalidPart,
+    /// A message the fountain encoder cannot split: empty, too long, or in empty fragments
+    InvalidMessage(String),
+    /// Not of the form `ur:type/payload` or `ur:type/seq-count/payload`
+    InvalidUr(String),
+    /// The UR holds another type than the one asked for
+    UnexpectedType(String),
+    InvalidTransaction(TransactionError),
+    InvalidKey,
+}
+
+impl fmt::Display for UrError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        match self {
+            UrError::InvalidBytewords(v) => f.write_str(&format!("Invalid bytewords: {v}")),
+            UrError::InvalidChecksum => f.write_str("Invalid checksum"),
+            UrError::InvalidCbor(v) => f.write_str(&format!("Invalid CBOR: {v}")),
+            UrError::InvalidPart => f.write_str("Invalid part of the message being decoded"),
+            UrError::InvalidMessage(v) => f.write_str(&format!("Invalid message: {v}")),
+            UrError::InvalidUr(v) => f.write_str(&format!("Invalid UR {v:?}")),
+            UrError::UnexpectedType(v) => f.write_str(&format!("Unexpected UR type {v:?}")),
+            UrError::InvalidTransaction(v) => f.write_str(&format!("Invalid transaction: {v}")),
+            UrError::InvalidKey => f.write_str("Invalid extended public key"),
+        }
+    }
+}
+
+impl std::error::Error for UrError {}
+
+impl From<TransactionError> for UrError {
+    fn from(value: TransactionError) -> Self {
+        UrError::InvalidTransaction(value)
+    }
+}
+
+fn is_valid_type(ur_type: &str) -> bool {
+    !ur_type.is_empty() && ur_type.bytes().all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == b'-')
+}
+
+/// A typed CBOR payload.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Ur {
+    ur_type: String,
+    cbor: Vec<u8>,
+}
+
+impl Ur {
+    pub fn new(ur_type: &str, cbor: Vec<u8>) -> Result<Self, UrError> {
+        if !is_valid_type(ur_type) {
+            return Err(UrError::InvalidUr(ur_type.to_string()));
+        }
+        Ok(Ur { ur_type: ur_type.to_string(), cbor })
+    }
+
+    pub fn bytes(data: &[u8]) -> Self {
+        Ur { ur_type: String::from(UR_TYPE_BYTES), cbor: Cbor::Bytes(data.to_vec()).encode() }
+    }
+
+    pub fn transaction(transaction: &StacksTransaction) -> Result<Self, TransactionError> {
+        Ok(Ur { ur_type: String::from(UR_TYPE_TRANSACTION), cbor: Cbor::Bytes(transaction.serialize()?).encode() })
+    }
+
+    /// The key data, chain code and parent fingerprint of `key`; its depth and child number
+    /// are not carried.
+    pub fn hdkey(key: &ExtendedPublicKey) -> Self {
+        let cbor = Cbor::Map(vec![
+            (Cbor::Unsigned(HDKEY_KEY_DATA), Cbor::Bytes(key.public_key().serialize().to_vec())),
+            (Cbor::Unsigned(HDKEY_CHAIN_CODE), Cbor::Bytes(key.chain_code.to_vec())),
+            (Cbor::Unsigned(HDKEY_PARENT_FINGERPRINT), Cbor::Unsigned(u32::from_be_bytes(key.attrs.parent_fingerprint) as u64)),
+        ]);
+        Ur { ur_type: String::from(UR_TYPE_HDKEY), cbor: cbor.encode() }
+    }
+
+    pub fn ur_type(&self) -> &str {
+        &self.ur_type
+    }
+
+    pub fn cbor(&self) -> &[u8] {
+        &self.cbor
+    }
+
+    fn expect_type(&self, ur_type: &str) -> Result<Cbor, UrError> {
+        match self.ur_type == ur_type {
+            true => Cbor::decode(&self.cbor),
+            false => Err(UrError::UnexpectedType(self.ur_type.clone())),
+        }
+    }
+
+    pub fn to_bytes(&self) -> Result<Vec<u8>, UrError> {
+        self.expect_type(UR_TYPE_BYTES)?.into_bytes()
+    }
+
+    pub fn to_transaction(&self) -> Result<StacksTransaction, UrError> {
+        Ok(StacksTransaction::deserialize(&self.expect_type(UR_TYPE_TRANSACTION)?.into_bytes()?)?)
+    }
+
+    /// Inverse of [`Ur::hdkey`], at depth 0 unless set by the caller.
+    pub fn to_hdkey(&self) -> Result<ExtendedPublicKey, UrError> {
+        let map = self.expect_type(UR_TYPE_HDKEY)?;
+        if map.get(HDKEY_IS_PRIVATE) == Some(&Cbor::Bool(true)) {
+            return Err(UrError::InvalidKey);
+        }
+        let field = |key| map.get(key).cloned().ok_or(UrError::InvalidKey)?.into_bytes();
+        let (key_data, chain_code) = (field(HDKEY_KEY_DATA)?, field(HDKEY_CHAIN_CODE)?);
+        let parent_fingerprint = match map.get(HDKEY_PARENT_FINGERPRINT) {
+            Some(fingerprint) => u32::try_from(fingerprint.as_unsigned()?).map_err(|_| UrError::InvalidKey)?,
+            None => 0,
+        };
+        let attrs = ExtendedKeyAttrs::new(0, parent_fingerprint.to_be_bytes(), ChildNumber::new(0).expect("0 is a valid child number"));
+        PublicKey::from_slice(&key_data).map_err(|_| UrError::InvalidKey)?;
+        ExtendedPublicKey::from_parts(&key_data, &chain_code, attrs).map_err(|_| UrError::InvalidKey)
+    }
+}
+
+/// The single-part form, `ur:type/bytewords`.
+impl fmt::Display for Ur {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        f.write_str(&format!("ur:{}/{}", self.ur_type, bytewords::encode(&self.cbor, Style::Minimal)))
+    }
+}
+
+/// Parses a single-part UR; multi-part URs go through a [`UrDecoder`].
+impl FromStr for Ur {
+    type Err = UrError;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match parse(value)? {
+            (ur_type, None, body) => Ur::new(&ur_type, bytewords::decode(&body, Style::Minimal)?),
+            (_, Some(_), _) => Err(UrError::InvalidUr(value.to_string())),
+        }
+    }
+}
+
+/// Sequence and count of a part of a multi-part UR.
+type PartSequence = Option<(u32, usize)>;
+
+/// Type, part sequence and payload of `value`.
+fn parse(value: &str) -> Result<(String, PartSequence, String), UrError> {
+    let invalid = || UrError::InvalidUr(value.to_string());
+    let lowercase = value.to_ascii_lowercase();
+    let rest = lowercase.strip_prefix("ur:").ok_or_else(invalid)?;
+    let components: Vec<&str> = rest.split('/').collect();
+    let (ur_type, sequence, body) = match components.as_slice() {
+        [ur_type, body] => (*ur_type, None, *body),
+        [ur_type, sequence, body] => {
+            let (sequence, count) = sequence.split_once('-').ok_or_else(invalid)?;
+            let sequence = (sequence.parse().map_err(|_| invalid())?, count.parse().map_err(|_| invalid())?);
+            (*ur_type, Some(sequence), *body)
+        }
+        _ => return Err(invalid()),
+    };
+    if !is_valid_type(ur_type) {
+        return Err(invalid());
+    }
+    Ok((ur_type.to_string(), sequence, body.to_string()))
+}
+
+/// Emits the parts of a UR: the single-part UR when it fits in one fragment, otherwise
+/// `ur:type/seq-count/bytewords` parts, first the fragments then mixes of them, forever.
+pub struct UrEncoder {
+    ur_type: String,
+    fountain: FountainEncoder,
+}
+
+impl UrEncoder {
+    /// `max_fragment_length` bytes of CBOR per part, before the bytewords encoding; see
+    /// [`FountainEncoder::new`] for the errors.
+    pub fn new(ur: &Ur, max_fragment_length: usize) -> Result<Self, UrError> {
+        Ok(UrEncoder { ur_type: ur.ur_type.clone(), fountain: FountainEncoder::new(&ur.cbor, max_fragment_length)? })
+    }
+
+    pub fn is_single_part(&self) -> bool {
+        self.fountain.sequence_count() == 1
+    }
+
+    /// Parts holding the fragments of the UR, the minimum to read to decode it.
+    pub fn sequence_count(&self) -> usize {
+        self.fountain.sequence_count()
+    }
+
+    pub fn next_part(&mut self) -> String {
+        let part = self.fountain.next_part();
+        if self.is_single_part() {
+            return format!("ur:{}/{}", self.ur_type, bytewords::encode(&part.data[..part.message_length], Style::Minimal));
+        }
+        let body = bytewords::encode(&part.to_cbor(), Style::Minimal);
+        format!("ur:{}/{}-{}/{}", self.ur_type, part.sequence, part.sequence_count, body)
+    }
+}
+
+/// Collects the parts of a UR, in any order and with any missing, until it is complete.
+#[derive(Debug, Default)]
+pub struct UrDecoder {
+    ur_type: Option<String>,
+    fountain: FountainDecoder,
+    result: Option<Ur>,
+}
+
+impl UrDecoder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a single-part UR or a part of a multi-part one.
+    pub fn receive(&mut self, part: &str) -> Result<(), UrError> {
+        if self.result.is_some() {
+            return Ok(());
+        }
+        let (ur_type, sequence, body) = parse(part)?;
+        if self.ur_type.as_ref().is_some_and(|expected| *expected != ur_type) {
+            return Err(UrError::UnexpectedType(ur_type));
+        }
+        let bytes = bytewords::decode(&body, Style::Minimal)?;
+        let Some((sequence, sequence_count)) = sequence else {
+            self.result = Some(Ur::new(&ur_type, bytes)?);
+            return Ok(());
+        };
+        let fountain_part = FountainPart::from_cbor(&bytes)?;
+        if (fountain_part.sequence, fountain_part.sequence_count) != (sequence, sequence_count) {
+            return Err(UrError::InvalidPart);
+        }
+        self.ur_type = Some(ur_type.clone());
+        if self.fountain.receive(fountain_part)? {
+            let message = self.fountain.message().expect("complete message").to_vec();
+            self.result = Some(Ur::new(&ur_type, message)?);
+        }
+        Ok(())
+    }
+
+    pub fn is_complete(&self) -> bool {
+        self.result.is_some()
+    }
+
+    /// Fragments recovered and fragments of the UR.
+    pub fn progress(&self) -> (usize, usize) {
+        match &self.result {
+            Some(_) if self.ur_type.is_none() => (1, 1),
+            _ => self.fountain.progress(),
+        }
+    }
+
+    pub fn result(&self) -> Option<&Ur> {
+        self.result.as_ref()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use secp256k1::SecretKey;
+
+    use crate::address::principal::Principal;
+    use crate::crypto::context::secp256k1_context;
+    use crate::crypto::keys::extended_private_key::{ExtendedPrivateKey, ExtendedPrivateKeyMethods};
+    use crate::transaction::builder::ContractCallBuilder;
+
+    use super::xoshiro::Xoshiro256;
+    use super::*;
+
+    #[test]
+    fn test_reference_parts() {
+        let ur = Ur::bytes(&Xoshiro256::new(b"Wolf").next_data(256));
+        let mut encoder = UrEncoder::new(&ur, 30).unwrap();
+        let parts: Vec<String> = (0..20).map(|_| encoder.next_part()).collect();
+        assert_eq!(parts[0], "ur:bytes/1-9/lpadascfadaxcywenbpljkhdcahkadaemejtswhhylkepmykhhtsytsnoyoyaxaedsuttydmmhhpktpmsrjtdkgslpgh");
+
+        let mut decoder = UrDecoder::new();
+        for part in parts.iter().skip(3) {
+            decoder.receive(part).unwrap();
+        }
+        assert_eq!(decoder.result(), Some(&ur));
+    }
+
+    #[test]
+    fn test_single_part() {
+        let ur = Ur::bytes(&[0, 1, 2, 128, 255]);
+        let text = ur.to_string();
+        assert!(text.starts_with("ur:bytes/feaeadaolazm"));
+        assert_eq!(Ur::from_str(&text.to_uppercase()).unwrap(), ur);
+
+        let mut encoder = UrEncoder::new(&ur, 100).unwrap();
+        assert!(encoder.is_single_part());
+        assert_eq!(encoder.next_part(), text);
+        let mut decoder = UrDecoder::new();
+        decoder.receive(&text).unwrap();
+        assert_eq!((decoder.result(), decoder.progress()), (Some(&ur), (1, 1)));
+
+        assert!(matches!(Ur::from_str("bytes/ahadaeaolazmvwfhfmgm"), Err(UrError::InvalidUr(_))));
+        assert!(matches!(Ur::from_str("ur:bytes/1-2/ahadae"), Err(UrError::InvalidUr(_))));
+        assert!(matches!(Ur::from_str("ur:by_tes/ahadae"), Err(UrError::InvalidUr(_))));
+        assert_eq!(ur.to_transaction(), Err(UrError::UnexpectedType(String::from(UR_TYPE_BYTES))));
+    }
+
+    #[test]
+    fn test_transaction() {
+        let key = SecretKey::from_slice(&[1; 32]).unwrap();
+        let contract = Principal::from_str("SP3FGQ8Z7JY9BWYZ5WM53E0M9NK7WHJF0691NZ159.pool").unwrap();
+        let transaction = ContractCallBuilder::new(contract, "leave", vec![]).build(&key.public_key(secp256k1_context())).unwrap();
+        let ur = Ur::transaction(&transaction).unwrap();
+        let mut encoder = UrEncoder::new(&ur, 50).unwrap();
+        let mut decoder = UrDecoder::new();
+        decoder.receive(&encoder.next_part()).unwrap();
+        assert_eq!(decoder.progress(), (1, encoder.sequence_count()));
+        while !decoder.is_complete() {
+            decoder.receive(&encoder.next_part()).unwrap();
+        }
+        assert_eq!(decoder.result().unwrap().to_transaction().unwrap(), transaction);
+        assert_eq!(decoder.receive("ur:crypto-hdkey/1-3/lpadaxcfaxhd"), Ok(()));
+
+        let mut mixed = UrDecoder::new();
+        mixed.receive(&encoder.next_part()).unwrap();
+        let other = UrEncoder::new(&Ur::new("stacks-tx-other", ur.cbor().to_vec()).unwrap(), 50).unwrap().next_part();
+        assert!(matches!(mixed.receive(&other), Err(UrError::UnexpectedType(_))));
+    }
+
+    #[test]
+    fn test_hdkey() {
+        let root = ExtendedPrivateKey::new(&[7; 64]).unwrap();
+        let child = root.derive_child(ChildNumber::new(5).unwrap());
+        let key = ExtendedPublicKey::from(&child);
+        let ur = Ur::from_str(&Ur::hdkey(&key).to_string()).unwrap();
+        assert_eq!(ur.ur_type(), UR_TYPE_HDKEY);
+        let decoded = ur.to_hdkey().unwrap();
+        assert_eq!(decoded.public_key(), key.public_key());
+        assert_eq!(decoded.chain_code, key.chain_code);
+        assert_eq!(decoded.attrs.parent_fingerprint, key.attrs.parent_fingerprint);
+
+        let private = Cbor::Map(vec![(Cbor::Unsigned(HDKEY_IS_PRIVATE), Cbor::Bool(true))]);
+        assert!(matches!(Ur::new(UR_TYPE_HDKEY, private.encode()).unwrap().to_hdkey(), Err(UrError::InvalidKey)));
+    }
+}
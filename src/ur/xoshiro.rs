@@ -0,0 +1,148 @@
+//! The xoshiro256** generator of the fountain codes, seeded from a SHA-256 digest so that
+//! encoders and decoders pick the same fragments.
+
+use crate::crypto::hash::{Hasher, Sha256};
+
+pub(crate) struct Xoshiro256 {
+    s: [u64; 4],
+}
+
+impl Xoshiro256 {
+    pub(crate) fn new(seed: &[u8]) -> Self {
+        let digest = Sha256::hash(seed);
+        let mut s = [0u64; 4];
+        for (i, word) in digest.chunks(8).enumerate() {
+            s[i] = u64::from_be_bytes(word.try_into().expect("8-byte chunk"));
+        }
+        Xoshiro256 { s }
+    }
+
+    pub(crate) fn next_u64(&mut self) -> u64 {
+        let result = self.s[1].wrapping_mul(5).rotate_left(7).wrapping_mul(9);
+        let t = self.s[1] << 17;
+        self.s[2] ^= self.s[0];
+        self.s[3] ^= self.s[1];
+        self.s[1] ^= self.s[2];
+        self.s[0] ^= self.s[3];
+        self.s[2] ^= t;
+        self.s[3] = self.s[3].rotate_left(45);
+        result
+    }
+
+    /// Uniform in `[0, 1)`.
+    pub(crate) fn next_double(&mut self) -> f64 {
+        self.next_u64() as f64 / (u64::MAX as f64 + 1.0)
+    }
+
+    /// Uniform in `[low, high]`.
+    pub(crate) fn next_int(&mut self, low: u64, high: u64) -> u64 {
+        (self.next_double() * (high - low + 1) as f64) as u64 + low
+    }
+
+    #[cfg(test)]
+    pub(crate) fn next_data(&mut self, len: usize) -> Vec<u8> {
+        (0..len).map(|_| self.next_int(0, 255) as u8).collect()
+    }
+
+    /// The first `count` items of a random order of `0..n`, drawn as the reference shuffle does:
+    /// each draw takes the remaining item of a random rank. The remaining items are counted in a
+    /// Fenwick tree, so a draw is a search in it rather than a removal from a list.
+    pub(crate) fn choose(&mut self, n: usize, count: usize) -> Vec<usize> {
+        // tree[i] counts the remaining items of (i - lowbit(i), i], 1-based
+        let mut tree: Vec<usize> = (0..=n).map(|i| i & i.wrapping_neg()).collect();
+        let top = match n {
+            0 => 0,
+            n => 1 << n.ilog2(),
+        };
+        let mut chosen = Vec::with_capacity(count.min(n));
+        while chosen.len() < count.min(n) {
+            let mut rank = self.next_int(0, (n - chosen.len()) as u64 - 1) as usize;
+            let mut position = 0;
+            let mut step = top;
+            while step > 0 {
+                if position + step <= n && tree[position + step] <= rank {
+                    position += step;
+                    rank -= tree[position];
+                }
+                step >>= 1;
+            }
+            let mut i = position + 1;
+            while i <= n {
+                tree[i] -= 1;
+                i += i & i.wrapping_neg();
+            }
+            chosen.push(position);
+        }
+        chosen
+    }
+}
+
+/// Walker's alias method: draws indexes with the given (unnormalized) probabilities.
+pub(crate) struct WeightedSampler {
+    probabilities: Vec<f64>,
+    aliases: Vec<usize>,
+}
+
+impl WeightedSampler {
+    pub(crate) fn new(weights: &[f64]) -> Self {
+        let n = weights.len();
+        let sum: f64 = weights.iter().sum();
+        let mut scaled: Vec<f64> = weights.iter().map(|weight| weight * n as f64 / sum).collect();
+        let (mut small, mut large) = (vec![], vec![]);
+        for j in (0..n).rev() {
+            if scaled[j] < 1.0 {
+                small.push(j);
+            } else {
+                large.push(j);
+            }
+        }
+        let mut probabilities = vec![0.0; n];
+        let mut aliases = vec![0; n];
+        while !small.is_empty() && !large.is_empty() {
+            let (a, g) = (small.pop().unwrap(), large.pop().unwrap());
+            probabilities[a] = scaled[a];
+            aliases[a] = g;
+            scaled[g] += scaled[a] - 1.0;
+            if scaled[g] < 1.0 {
+                small.push(g);
+            } else {
+                large.push(g);
+            }
+        }
+        for j in large.into_iter().chain(small) {
+            probabilities[j] = 1.0;
+        }
+        WeightedSampler { probabilities, aliases }
+    }
+
+    pub(crate) fn next(&self, rng: &mut Xoshiro256) -> usize {
+        let (r1, r2) = (rng.next_double(), rng.next_double());
+        let i = (self.probabilities.len() as f64 * r1) as usize;
+        if r2 < self.probabilities[i] {
+            i
+        } else {
+            self.aliases[i]
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rng() {
+        let mut rng = Xoshiro256::new(b"Wolf");
+        let numbers: Vec<u64> = (0..10).map(|_| rng.next_u64() % 100).collect();
+        assert_eq!(numbers, [42, 81, 85, 8, 82, 84, 76, 73, 70, 88]);
+    }
+
+    #[test]
+    fn test_shuffle() {
+        let mut rng = Xoshiro256::new(b"Wolf");
+        let shuffles: Vec<Vec<usize>> = (0..3).map(|_| rng.choose(10, 10).iter().map(|i| i + 1).collect()).collect();
+        assert_eq!(shuffles, [[6, 4, 9, 3, 10, 5, 7, 8, 1, 2], [10, 8, 6, 5, 1, 2, 3, 9, 7, 4], [6, 4, 5, 8, 9, 3, 2, 1, 7, 10]]);
+        assert_eq!(Xoshiro256::new(b"Wolf").choose(10, 3), [5, 3, 8]);
+        assert!(rng.choose(0, 3).is_empty());
+    }
+}
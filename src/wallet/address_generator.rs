@@ -0,0 +1,199 @@
+#[cfg(feature = "rayon")]
+use rayon::prelude::*;
+use secp256k1::PublicKey;
+use stacks_common::address::b58;
+
+use crate::address::stacks_address::StacksAddress;
+use crate::bip32::child_number::ChildNumber;
+use crate::crypto::keys::extended_public_key::{ExtendedPublicKey, ExtendedPublicKeyMethods};
+use crate::crypto::keys::stacks_key::StacksKeyMethods;
+use crate::network::{AddressVersion, NetworkKind};
+
+use super::watch_only::Chain;
+
+/// Non-hardened indexes are in `0..NON_HARDENED_INDEXES`.
+const NON_HARDENED_INDEXES: u32 = 2147483648;
+/// Version byte of mainnet P2PKH Bitcoin addresses (starting with `1`)
+const BTC_MAINNET_P2PKH: u8 = 0x00;
+/// Version byte of testnet P2PKH Bitcoin addresses (starting with `m` or `n`)
+const BTC_TESTNET_P2PKH: u8 = 0x6f;
+/// Default number of addresses derived at once.
+pub const DEFAULT_BATCH_SIZE: u32 = 1024;
+
+/// An address yielded by [`AddressGenerator`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct GeneratedAddress {
+    pub index: u32,
+    pub address: StacksAddress,
+    /// P2PKH Bitcoin address of the same key, if enabled on the generator
+    pub bitcoin_address: Option<String>,
+}
+
+/// Streams the single-sig addresses of an account chain in batches, e.g. to provision
+/// deposit addresses in bulk.
+///
+/// Only the chain key is kept; every batch reuses the parent HMAC state and, with the
+/// `rayon` feature, is derived across the thread pool. The generator stops at the last
+/// non-hardened index.
+///
+/// Usage:
+/// ```rust
+/// use stacks_rs::crypto::keys::extended_private_key::{ExtendedPrivateKey, ExtendedPrivateKeyMethods};
+/// use stacks_rs::crypto::keys::extended_public_key::ExtendedPublicKey;
+/// use stacks_rs::network::NetworkKind;
+/// use stacks_rs::wallet::address_generator::AddressGenerator;
+/// let seed = hex::decode("000102030405060708090a0b0c0d0e0f").unwrap();
+/// let account_xpub = ExtendedPublicKey::from(&ExtendedPrivateKey::new(&seed).unwrap());
+/// let mut generator = AddressGenerator::new(&account_xpub, NetworkKind::Mainnet).with_batch_size(100).starting_at(1000);
+/// let batch = generator.next_batch();
+/// assert_eq!(batch.len(), 100);
+/// assert_eq!(batch[0].index, 1000);
+/// assert_eq!(generator.next().unwrap().index, 1100);
+/// ```
+pub struct AddressGenerator {
+    chain_key: ExtendedPublicKey,
+    network: NetworkKind,
+    bitcoin_addresses: bool,
+    batch_size: u32,
+    next_index: u32,
+    buffer: std::vec::IntoIter<GeneratedAddress>,
+}
+
+impl AddressGenerator {
+    /// Generator over the external chain of the account-level key (e.g. the xpub of `m/44'/5757'/0'`).
+    pub fn new(account_key: &ExtendedPublicKey, network: NetworkKind) -> Self {
+        Self::for_chain(account_key, Chain::External, network)
+    }
+
+    pub fn for_chain(account_key: &ExtendedPublicKey, chain: Chain, network: NetworkKind) -> Self {
+        Self {
+            chain_key: account_key.derive_child(ChildNumber::new(chain.index()).unwrap()).unwrap(),
+            network,
+            bitcoin_addresses: false,
+            batch_size: DEFAULT_BATCH_SIZE,
+            next_index: 0,
+            buffer: Vec::new().into_iter(),
+        }
+    }
+
+    /// Also render the P2PKH Bitcoin address of every generated key.
+    pub fn with_bitcoin_addresses(mut self, enabled: bool) -> Self {
+        self.bitcoin_addresses = enabled;
+        self
+    }
+
+    /// Number of addresses derived at once (at least 1).
+    pub fn with_batch_size(mut self, batch_size: u32) -> Self {
+        self.batch_size = batch_size.max(1);
+        self
+    }
+
+    /// Starts at `index` instead of 0, e.g. to resume after the last provisioned address.
+    /// Addresses already buffered are dropped.
+    pub fn starting_at(mut self, index: u32) -> Self {
+        self.next_index = index.min(NON_HARDENED_INDEXES);
+        self.buffer = Vec::new().into_iter();
+        self
+    }
+
+    /// Returns the buffered addresses, or derives a new batch if there are none left.
+    /// Empty once every non-hardened index has been generated.
+    pub fn next_batch(&mut self) -> Vec<GeneratedAddress> {
+        let buffered: Vec<GeneratedAddress> = self.buffer.by_ref().collect();
+        if !buffered.is_empty() {
+            return buffered;
+        }
+        let end = self.next_index.saturating_add(self.batch_size).min(NON_HARDENED_INDEXES);
+        let range = self.next_index..end;
+        self.next_index = end;
+        self.derive_batch(range)
+    }
+
+    #[cfg(not(feature = "rayon"))]
+    fn derive_batch(&self, range: std::ops::Range<u32>) -> Vec<GeneratedAddress> {
+        let start = range.start;
+        self.chain_key
+            .derive_children(range)
+            .enumerate()
+            .map(|(offset, child)| self.to_address(start + offset as u32, child.unwrap().public_key()))
+            .collect()
+    }
+
+    #[cfg(feature = "rayon")]
+    fn derive_batch(&self, range: std::ops::Range<u32>) -> Vec<GeneratedAddress> {
+        let start = range.start;
+        self.chain_key
+            .derive_children_par(range)
+            .into_par_iter()
+            .enumerate()
+            .map(|(offset, child)| self.to_address(start + offset as u32, child.unwrap().public_key()))
+            .collect()
+    }
+
+    fn to_address(&self, index: u32, public_key: &PublicKey) -> GeneratedAddress {
+        let hash = public_key.hash160();
+        let address = StacksAddress::from_hash160(hash, AddressVersion::single_sig(&self.network));
+        let bitcoin_address = self.bitcoin_addresses.then(|| {
+            let version = match self.network {
+                NetworkKind::Mainnet => BTC_MAINNET_P2PKH,
+                NetworkKind::Testnet | NetworkKind::Mocknet => BTC_TESTNET_P2PKH,
+            };
+            b58::check_encode_slice(&[&[version], hash.as_slice()].concat())
+        });
+        GeneratedAddress { index, address, bitcoin_address }
+    }
+}
+
+impl Iterator for AddressGenerator {
+    type Item = GeneratedAddress;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if let Some(address) = self.buffer.next() {
+            return Some(address);
+        }
+        self.buffer = self.next_batch().into_iter();
+        self.buffer.next()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::crypto::keys::extended_private_key::{ExtendedPrivateKey, ExtendedPrivateKeyMethods};
+    use crate::wallet::watch_only::WatchOnlyAccount;
+
+    use super::*;
+
+    fn account_xpub() -> ExtendedPublicKey {
+        let seed = hex::decode("000102030405060708090a0b0c0d0e0f").unwrap();
+        ExtendedPublicKey::from(&ExtendedPrivateKey::new(&seed).unwrap())
+    }
+
+    #[test]
+    fn test_matches_watch_only_account() {
+        let account = WatchOnlyAccount::new(&account_xpub(), NetworkKind::Testnet).with_bitcoin_addresses(true);
+        let generator = AddressGenerator::for_chain(&account_xpub(), Chain::Change, NetworkKind::Testnet)
+            .with_bitcoin_addresses(true)
+            .with_batch_size(7);
+        for (generated, expected) in generator.zip(account.addresses(Chain::Change)).take(30) {
+            assert_eq!(generated.index, expected.index);
+            assert_eq!(generated.address.to_string(), expected.stacks_address);
+            assert_eq!(generated.bitcoin_address, expected.bitcoin_address);
+        }
+    }
+
+    #[test]
+    fn test_batches() {
+        let mut generator = AddressGenerator::new(&account_xpub(), NetworkKind::Mainnet).with_batch_size(4);
+        assert_eq!(generator.next().unwrap().index, 0);
+        // the rest of the buffered batch comes first
+        let indexes: Vec<u32> = generator.next_batch().iter().map(|address| address.index).collect();
+        assert_eq!(indexes, [1, 2, 3]);
+        assert_eq!(generator.next_batch().len(), 4);
+        assert!(generator.next().unwrap().bitcoin_address.is_none());
+
+        let mut last = AddressGenerator::new(&account_xpub(), NetworkKind::Mainnet).starting_at(NON_HARDENED_INDEXES - 2);
+        assert_eq!(last.next_batch().len(), 2);
+        assert!(last.next_batch().is_empty());
+        assert!(last.next().is_none());
+    }
+}
@@ -1,7 +1,12 @@
 use std::str::FromStr;
 use bip39::{Language, Mnemonic};
+use rand::rngs::OsRng;
+use rand::{CryptoRng, RngCore};
+use crate::crypto::kdf::pbkdf2;
 use crate::crypto::utils;
 
+use super::normalize::{self, MnemonicNormalization};
+
 const ENTROPY_128_BITS: usize = 16; 
 const ENTROPY_256_BITS: usize = 32; 
 
@@ -26,9 +31,11 @@ pub struct Bip39Mnemonic {
 
 pub trait Bip39MnemonicMethods {
     fn new(entropy_bits: Option<AllowedKeyEntropyBits>) -> Result<Bip39Mnemonic, bip39::Error>;
+    fn new_with_rng<R: RngCore + CryptoRng>(entropy_bits: Option<AllowedKeyEntropyBits>, rng: &mut R) -> Result<Bip39Mnemonic, bip39::Error>;
     fn word_count(&self) -> usize;
     fn language(&self) -> Language;
     fn mnemonic_from_words(words: &str) -> Result<Bip39Mnemonic, bip39::Error>;
+    fn mnemonic_from_user_input(words: &str, normalization: MnemonicNormalization) -> Result<Bip39Mnemonic, normalize::Error>;
     fn entropy_to_mnemonic(entropy: &Vec<u8>) -> Result<Bip39Mnemonic, bip39::Error>;
     fn to_entropy(&self) -> Vec<u8>;
     fn get_seed(&self, password: &str) -> [u8; 64];
@@ -40,13 +47,18 @@ impl Bip39MnemonicMethods for Bip39Mnemonic {
     /// (16/32) bytes.
     /// By default a 24-word mnemonic is generated
     fn new(entropy_bits: Option<AllowedKeyEntropyBits>) -> Result<Self, bip39::Error> {
+        Self::new_with_rng(entropy_bits, &mut OsRng)
+    }
+
+    /// Same as [`Bip39MnemonicMethods::new`] but draws the entropy from `rng` instead of the OS RNG.
+    fn new_with_rng<R: RngCore + CryptoRng>(entropy_bits: Option<AllowedKeyEntropyBits>, rng: &mut R) -> Result<Self, bip39::Error> {
         let byte_len = if let Some(entropy_bits) = entropy_bits {
             entropy_bits.byte_len()
         } else{
             AllowedKeyEntropyBits::byte_len(&AllowedKeyEntropyBits::Entropy256Bits)
         };
         let mut entropy = vec![0; byte_len];
-        utils::generate_random_bytes(&mut entropy, byte_len);
+        utils::generate_random_bytes_with_rng(rng, &mut entropy, byte_len);
         Ok(Self {mnemonic: Mnemonic::from_entropy(&entropy)? })
     }
 
@@ -55,6 +67,12 @@ impl Bip39MnemonicMethods for Bip39Mnemonic {
         Ok(Bip39Mnemonic{mnemonic: Mnemonic::from_str(words)?})
     }
 
+    /// Converts `words` typed by a user into a [`Mnemonic`], normalizing them first.
+    /// Unknown words are reported with did-you-mean suggestions from the wordlist.
+    fn mnemonic_from_user_input(words: &str, normalization: MnemonicNormalization) -> Result<Self, normalize::Error> {
+        Ok(Bip39Mnemonic{mnemonic: normalize::parse_mnemonic(words, Language::English, normalization)?})
+    }
+
     /// Converts `entropy` into a [`Mnemonic`]
     fn entropy_to_mnemonic(entropy: &Vec<u8>) -> Result<Bip39Mnemonic, bip39::Error> {
         Ok(Self {mnemonic: Mnemonic::from_entropy(entropy)?})
@@ -65,7 +83,7 @@ impl Bip39MnemonicMethods for Bip39Mnemonic {
     }
 
     fn get_seed(&self, password: &str) -> [u8; 64] {
-        self.mnemonic.to_seed(password)
+        pbkdf2::bip39_seed(&self.mnemonic, password)
     }
 
     fn language(&self) -> Language {
@@ -95,4 +113,14 @@ mod tests {
         assert_eq!(mnemonic128.language(), bip39::Language::English);
         assert_eq!(mnemonic128.word_count(), 12);
     }
+
+    #[test]
+    fn test_generate_mnemonic_with_rng() {
+        use rand::{rngs::StdRng, SeedableRng};
+
+        let first = Bip39Mnemonic::new_with_rng(None, &mut StdRng::seed_from_u64(7)).unwrap();
+        let second = Bip39Mnemonic::new_with_rng(None, &mut StdRng::seed_from_u64(7)).unwrap();
+        assert_eq!(first, second);
+        assert_ne!(first, Bip39Mnemonic::new_with_rng(None, &mut StdRng::seed_from_u64(8)).unwrap());
+    }
 }
\ No newline at end of file
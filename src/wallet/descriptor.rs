@@ -0,0 +1,413 @@
+use std::{fmt, str::FromStr};
+
+use secp256k1::{PublicKey, XOnlyPublicKey};
+
+use crate::bip32::child_number::{ChildNumber, ChildNumberError};
+use crate::bip32::extended_keys::ExtendedKey;
+use crate::crypto::constant_time::ct_eq;
+use crate::crypto::hash::{Hash160, Hasher};
+use crate::crypto::keys::common_attrs::KeyFingerprint;
+use crate::crypto::keys::extended_public_key::{ExtendedPublicKey, ExtendedPublicKeyMethods};
+use crate::crypto::taproot;
+
+const INPUT_CHARSET: &str = "0123456789()[],'/*abcdefgh@:$%{}IJKLMNOPQRSTUVWXYZ&+-.;<=>?!^_|~ijklmnopqrstuvwxyzABCDEFGH`#\"\\ ";
+const CHECKSUM_CHARSET: &[u8] = b"qpzry9x8gf2tvdw0s3jn54khce6mua7l";
+const CHECKSUM_LEN: usize = 8;
+const GENERATOR: [u64; 5] = [0xf5dee51989, 0xa9fdca3312, 0x1bab10e32d, 0x3706b1677a, 0x644d626ffd];
+
+#[derive(Debug, PartialEq)]
+pub enum Error {
+    InvalidCharacter(char),
+    InvalidChecksum,
+    /// Only `wpkh(...)`, `sh(wpkh(...))` and key-path `tr(...)` are supported
+    UnsupportedDescriptor(String),
+    InvalidKey(String),
+    InvalidKeyOrigin(String),
+    InvalidPath(ChildNumberError),
+    /// Hardened steps cannot be derived from a public key
+    HardenedDerivation,
+    /// The descriptor has no `*` but an index was requested, or the other way around
+    WildcardMismatch,
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        match self {
+            Error::InvalidCharacter(c) => f.write_str(&format!("Invalid character {c:?}")),
+            Error::InvalidChecksum => f.write_str("Invalid descriptor checksum"),
+            Error::UnsupportedDescriptor(d) => f.write_str(&format!("Unsupported descriptor {d}")),
+            Error::InvalidKey(k) => f.write_str(&format!("Invalid key {k}")),
+            Error::InvalidKeyOrigin(o) => f.write_str(&format!("Invalid key origin {o}")),
+            Error::InvalidPath(error) => f.write_str(&format!("{error}")),
+            Error::HardenedDerivation => f.write_str("Cannot derive hardened children from a public key"),
+            Error::WildcardMismatch => f.write_str("Derivation index does not match the descriptor wildcard"),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+fn polymod(symbols: impl Iterator<Item = u64>) -> u64 {
+    let mut chk = 1u64;
+    for value in symbols {
+        let top = chk >> 35;
+        chk = ((chk & 0x7ffffffff) << 5) ^ value;
+        for (i, generator) in GENERATOR.iter().enumerate() {
+            if (top >> i) & 1 == 1 {
+                chk ^= generator;
+            }
+        }
+    }
+    chk
+}
+
+/// Descriptor checksum as defined in BIP380.
+pub fn checksum(descriptor: &str) -> Result<String, Error> {
+    let mut symbols = vec![];
+    let mut groups = vec![];
+    for c in descriptor.chars() {
+        let v = INPUT_CHARSET.find(c).ok_or(Error::InvalidCharacter(c))? as u64;
+        symbols.push(v & 31);
+        groups.push(v >> 5);
+        if groups.len() == 3 {
+            symbols.push(groups[0] * 9 + groups[1] * 3 + groups[2]);
+            groups.clear();
+        }
+    }
+    match groups.len() {
+        1 => symbols.push(groups[0]),
+        2 => symbols.push(groups[0] * 3 + groups[1]),
+        _ => {}
+    }
+    let chk = polymod(symbols.into_iter().chain([0; CHECKSUM_LEN])) ^ 1;
+    Ok((0..CHECKSUM_LEN)
+        .map(|i| CHECKSUM_CHARSET[((chk >> (5 * (7 - i))) & 31) as usize] as char)
+        .collect())
+}
+
+fn parse_path(steps: &str) -> Result<Vec<ChildNumber>, Error> {
+    steps
+        .split('/')
+        .map(|step| ChildNumber::from_str(&step.replace(['h', 'H'], "'")).map_err(Error::InvalidPath))
+        .collect()
+}
+
+fn format_path(path: &[ChildNumber]) -> String {
+    path.iter().map(|child_number| format!("/{child_number}")).collect()
+}
+
+/// Fingerprint of the master key and path of the key, e.g. `[d34db33f/84'/0'/0']`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct KeyOrigin {
+    pub fingerprint: KeyFingerprint,
+    pub path: Vec<ChildNumber>,
+}
+
+impl FromStr for KeyOrigin {
+    type Err = Error;
+
+    fn from_str(origin: &str) -> Result<Self, Self::Err> {
+        let invalid = || Error::InvalidKeyOrigin(String::from(origin));
+        let (fingerprint, path) = origin.split_once('/').unwrap_or((origin, ""));
+        let fingerprint = hex::decode(fingerprint).map_err(|_| invalid())?.try_into().map_err(|_| invalid())?;
+        let path = match path.is_empty() {
+            true => vec![],
+            false => parse_path(path)?,
+        };
+        Ok(KeyOrigin { fingerprint, path })
+    }
+}
+
+impl fmt::Display for KeyOrigin {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        f.write_str(&format!("{}{}", hex::encode(self.fingerprint), format_path(&self.path)))
+    }
+}
+
+/// Where the public key of a descriptor comes from.
+pub enum DescriptorKeySource {
+    /// A fixed public key, as hex (x-only keys are allowed in `tr(...)`)
+    Single(PublicKey),
+    /// An extended public key, the (non-hardened) path below it and whether it ends with `/*`
+    Extended { xpub: ExtendedPublicKey, path: Vec<ChildNumber>, wildcard: bool },
+}
+
+pub struct DescriptorPublicKey {
+    pub origin: Option<KeyOrigin>,
+    pub source: DescriptorKeySource,
+    /// Key expression as written, kept to print the descriptor back unchanged
+    key_string: String,
+}
+
+impl DescriptorPublicKey {
+    pub fn has_wildcard(&self) -> bool {
+        matches!(self.source, DescriptorKeySource::Extended { wildcard: true, .. })
+    }
+
+    /// Derives the public key, `index` replacing the `*` of ranged descriptors.
+    pub fn derive_public_key(&self, index: Option<u32>) -> Result<PublicKey, Error> {
+        match &self.source {
+            DescriptorKeySource::Single(public_key) if index.is_none() => Ok(*public_key),
+            DescriptorKeySource::Extended { xpub, path, wildcard } if *wildcard == index.is_some() => {
+                let mut path = path.clone();
+                if let Some(index) = index {
+                    path.push(ChildNumber::new(index).map_err(Error::InvalidPath)?);
+                }
+                let mut key = ExtendedPublicKey::new(*xpub.public_key(), xpub.chain_code, xpub.attrs);
+                for child_number in path {
+                    key = key.derive_child(child_number).map_err(|_| Error::HardenedDerivation)?;
+                }
+                Ok(*key.public_key())
+            }
+            _ => Err(Error::WildcardMismatch),
+        }
+    }
+
+    fn parse(key: &str, allow_x_only: bool) -> Result<Self, Error> {
+        let invalid = || Error::InvalidKey(String::from(key));
+        let (origin, key_expr) = match key.strip_prefix('[') {
+            Some(rest) => {
+                let (origin, key_expr) = rest.split_once(']').ok_or_else(invalid)?;
+                (Some(KeyOrigin::from_str(origin)?), key_expr)
+            }
+            None => (None, key),
+        };
+
+        let source = match hex::decode(key_expr) {
+            Ok(bytes) if bytes.len() == 33 => {
+                DescriptorKeySource::Single(PublicKey::from_slice(&bytes).map_err(|_| invalid())?)
+            }
+            Ok(bytes) if bytes.len() == 32 && allow_x_only => {
+                let x_only = XOnlyPublicKey::from_slice(&bytes).map_err(|_| invalid())?;
+                DescriptorKeySource::Single(x_only.public_key(secp256k1::Parity::Even))
+            }
+            Ok(_) => return Err(invalid()),
+            Err(_) => {
+                let (xpub, steps) = key_expr.split_once('/').unwrap_or((key_expr, ""));
+                let extended_key = ExtendedKey::from_str(xpub).map_err(|_| invalid())?;
+                if extended_key.version.is_private() {
+                    return Err(invalid());
+                }
+                let xpub = ExtendedPublicKey::try_from(extended_key).map_err(|_| invalid())?;
+                let (steps, wildcard) = match steps.strip_suffix('*') {
+                    Some(steps) => (steps.trim_end_matches('/'), true),
+                    None => (steps, false),
+                };
+                if steps.ends_with(['\'', 'h', 'H']) || steps.contains("*") {
+                    return Err(Error::HardenedDerivation);
+                }
+                let path = match steps.is_empty() {
+                    true => vec![],
+                    false => parse_path(steps)?,
+                };
+                if path.iter().any(|child_number| child_number.is_hardened) {
+                    return Err(Error::HardenedDerivation);
+                }
+                DescriptorKeySource::Extended { xpub, path, wildcard }
+            }
+        };
+        Ok(DescriptorPublicKey { origin, source, key_string: String::from(key_expr) })
+    }
+}
+
+impl fmt::Display for DescriptorPublicKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        match &self.origin {
+            Some(origin) => f.write_str(&format!("[{}]{}", origin, self.key_string)),
+            None => f.write_str(&self.key_string),
+        }
+    }
+}
+
+/// A subset of the Bitcoin output descriptors (BIP380 and following): single-key
+/// segwit, nested segwit and key-path taproot outputs.
+///
+/// Usage:
+/// ```rust
+/// use std::str::FromStr;
+/// use stacks_rs::wallet::descriptor::Descriptor;
+/// let descriptor = Descriptor::from_str(
+///     "wpkh(02f9308a019258c31049344f85f89d5229b531c845836f99b08601f113bce036f9)",
+/// ).unwrap();
+/// assert_eq!(descriptor.script_pubkey(None).unwrap().len(), 22);
+/// ```
+pub enum Descriptor {
+    /// `wpkh(KEY)`: P2WPKH
+    Wpkh(DescriptorPublicKey),
+    /// `sh(wpkh(KEY))`: P2WPKH nested in P2SH
+    ShWpkh(DescriptorPublicKey),
+    /// `tr(KEY)`: P2TR spendable by key path only
+    Tr(DescriptorPublicKey),
+}
+
+fn wpkh_script(public_key: &PublicKey) -> Vec<u8> {
+    let mut script = vec![0x00, 0x14];
+    script.extend(Hash160::hash(&public_key.serialize()));
+    script
+}
+
+impl Descriptor {
+    pub fn key(&self) -> &DescriptorPublicKey {
+        match self {
+            Descriptor::Wpkh(key) | Descriptor::ShWpkh(key) | Descriptor::Tr(key) => key,
+        }
+    }
+
+    /// Whether the descriptor describes a range of scripts (its key ends with `/*`).
+    pub fn has_wildcard(&self) -> bool {
+        self.key().has_wildcard()
+    }
+
+    /// Derives the output script, `index` replacing the `*` of ranged descriptors.
+    pub fn script_pubkey(&self, index: Option<u32>) -> Result<Vec<u8>, Error> {
+        let public_key = self.key().derive_public_key(index)?;
+        Ok(match self {
+            Descriptor::Wpkh(_) => wpkh_script(&public_key),
+            Descriptor::ShWpkh(_) => {
+                let mut script = vec![0xa9, 0x14];
+                script.extend(Hash160::hash(&wpkh_script(&public_key)));
+                script.push(0x87);
+                script
+            }
+            Descriptor::Tr(_) => {
+                // BIP86 output key: internal key tweaked with an empty script tree
+                let (internal_key, _) = public_key.x_only_public_key();
+                let (output_key, _) =
+                    taproot::tweak_public_key(&internal_key, None).map_err(|_| Error::InvalidKey(self.key().to_string()))?;
+                taproot::p2tr_script_pubkey(&output_key).to_vec()
+            }
+        })
+    }
+
+    /// The descriptor without its checksum.
+    fn body(&self) -> String {
+        match self {
+            Descriptor::Wpkh(key) => format!("wpkh({key})"),
+            Descriptor::ShWpkh(key) => format!("sh(wpkh({key}))"),
+            Descriptor::Tr(key) => format!("tr({key})"),
+        }
+    }
+}
+
+impl FromStr for Descriptor {
+    type Err = Error;
+
+    /// Parses a descriptor, verifying its checksum if present.
+    fn from_str(descriptor: &str) -> Result<Self, Self::Err> {
+        let body = match descriptor.split_once('#') {
+            Some((body, expected)) => {
+                if !ct_eq(checksum(body)?.as_bytes(), expected.as_bytes()) {
+                    return Err(Error::InvalidChecksum);
+                }
+                body
+            }
+            None => descriptor,
+        };
+        let unsupported = || Error::UnsupportedDescriptor(String::from(body));
+        if let Some(inner) = body.strip_prefix("sh(wpkh(").and_then(|d| d.strip_suffix("))")) {
+            Ok(Descriptor::ShWpkh(DescriptorPublicKey::parse(inner, false)?))
+        } else if let Some(inner) = body.strip_prefix("wpkh(").and_then(|d| d.strip_suffix(')')) {
+            Ok(Descriptor::Wpkh(DescriptorPublicKey::parse(inner, false)?))
+        } else if let Some(inner) = body.strip_prefix("tr(").and_then(|d| d.strip_suffix(')')) {
+            if inner.contains(',') {
+                // script trees are not supported
+                return Err(unsupported());
+            }
+            Ok(Descriptor::Tr(DescriptorPublicKey::parse(inner, true)?))
+        } else {
+            Err(unsupported())
+        }
+    }
+}
+
+impl fmt::Display for Descriptor {
+    /// Prints the descriptor with its checksum, as bitcoind does.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        let body = self.body();
+        let checksum = checksum(&body).map_err(|_| fmt::Error)?;
+        f.write_str(&format!("{body}#{checksum}"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bip39::Mnemonic;
+
+    use crate::bip32::derivation_path::DerivationPath;
+    use crate::bip32::key_version::Version;
+    use crate::crypto::keys::extended_private_key::{ExtendedPrivateKey, ExtendedPrivateKeyMethods};
+
+    use super::*;
+
+    const MNEMONIC: &str = "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon about";
+
+    /// Account xpub of the BIP84/BIP49/BIP86 test vectors
+    fn account_xpub(purpose: u32) -> String {
+        let seed = Mnemonic::from_str(MNEMONIC).unwrap().to_seed("");
+        let path = DerivationPath::from_str(&format!("m/{purpose}'/0'/0'")).unwrap();
+        let account_key = ExtendedPrivateKey::derive_from_path(&seed, path);
+        ExtendedPublicKey::from(&account_key).to_extended_key_with_version(Version::XPub).b58_encode()
+    }
+
+    #[test]
+    fn test_checksum() {
+        assert_eq!(checksum("raw(deadbeef)").unwrap(), "89f8spxm");
+        assert_eq!(checksum("raw(deadbeef)é"), Err(Error::InvalidCharacter('é')));
+    }
+
+    #[test]
+    fn test_wpkh() {
+        let descriptor = Descriptor::from_str(&format!("wpkh([73c5da0a/84'/0'/0']{}/0/*)", account_xpub(84))).unwrap();
+        assert!(descriptor.has_wildcard());
+        assert_eq!(
+            hex::encode(descriptor.script_pubkey(Some(0)).unwrap()),
+            "0014c0cebcd6c3d3ca8c75dc5ec62ebe55330ef910e2"
+        );
+        assert_eq!(descriptor.script_pubkey(None), Err(Error::WildcardMismatch));
+
+        let origin = &descriptor.key().origin.as_ref().unwrap();
+        assert_eq!(origin.to_string(), "73c5da0a/84'/0'/0'");
+
+        let printed = descriptor.to_string();
+        let reparsed = Descriptor::from_str(&printed).unwrap();
+        assert_eq!(reparsed.to_string(), printed);
+    }
+
+    #[test]
+    /// First BIP49 address `37VucYSaXLCAsxYyAPfbSi9eh4iEcbShgf`
+    fn test_sh_wpkh() {
+        let descriptor = Descriptor::from_str(&format!("sh(wpkh({}/0/*))", account_xpub(49))).unwrap();
+        assert_eq!(
+            hex::encode(descriptor.script_pubkey(Some(0)).unwrap()),
+            "a9143fb6e95812e57bb4691f9a4a628862a61a4f769b87"
+        );
+    }
+
+    #[test]
+    fn test_tr() {
+        let descriptor = Descriptor::from_str(&format!("tr({}/0/*)", account_xpub(86))).unwrap();
+        assert_eq!(
+            hex::encode(descriptor.script_pubkey(Some(0)).unwrap()),
+            "5120a60869f0dbcf1dc659c9cecbaf8050135ea9e8cdc487053f1dc6880949dc684c"
+        );
+
+        let x_only = Descriptor::from_str("tr(cc8a4bc64d897bddc5fbc2f670f7a8ba0b386779106cf1223c6fc5d7cd6fc115)").unwrap();
+        assert_eq!(x_only.script_pubkey(None).unwrap(), descriptor.script_pubkey(Some(0)).unwrap());
+    }
+
+    #[test]
+    fn test_invalid_descriptors() {
+        let xpub = account_xpub(84);
+        assert_eq!(
+            Descriptor::from_str("raw(deadbeef)").err(),
+            Some(Error::UnsupportedDescriptor(String::from("raw(deadbeef)")))
+        );
+        assert_eq!(Descriptor::from_str(&format!("wpkh({xpub}/0/*)#89f8spxm")).err(), Some(Error::InvalidChecksum));
+        assert_eq!(Descriptor::from_str(&format!("wpkh({xpub}/0'/*)")).err(), Some(Error::HardenedDerivation));
+        assert_eq!(Descriptor::from_str(&format!("wpkh({xpub}/0/*')")).err(), Some(Error::HardenedDerivation));
+        assert!(matches!(
+            Descriptor::from_str("wpkh(cc8a4bc64d897bddc5fbc2f670f7a8ba0b386779106cf1223c6fc5d7cd6fc115)"),
+            Err(Error::InvalidKey(_))
+        ));
+    }
+}
@@ -0,0 +1,138 @@
+use std::str::FromStr;
+
+use crate::bip32::derivation_path::DerivationPath;
+use crate::crypto::keys::extended_private_key::{ExtendedPrivateKey, ExtendedPrivateKeyMethods};
+use crate::crypto::keys::extended_public_key::ExtendedPublicKey;
+use crate::network::NetworkKind;
+
+use super::watch_only::{Chain, WatchOnlyAccount};
+use super::STX_COIN_TYPE;
+
+/// Standard BIP44 gap limit.
+pub const DEFAULT_GAP_LIMIT: u32 = 20;
+
+/// Tells whether an address has ever been used (e.g. has a balance or any transaction).
+pub trait AddressActivitySource {
+    type Error;
+
+    fn is_used(&mut self, stacks_address: &str) -> Result<bool, Self::Error>;
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DiscoveredAccount {
+    /// Index of the account (`a` in `m/44'/5757'/a'`)
+    pub account_index: u32,
+    /// Used indexes of the external chain
+    pub used_external: Vec<u32>,
+    /// Used indexes of the change chain
+    pub used_change: Vec<u32>,
+}
+
+impl DiscoveredAccount {
+    pub fn is_used(&self) -> bool {
+        !self.used_external.is_empty() || !self.used_change.is_empty()
+    }
+}
+
+/// Returns the indexes of the used addresses of `chain`, scanning until `gap_limit` consecutive unused addresses.
+pub fn discover_chain<S>(
+    account: &WatchOnlyAccount,
+    chain: Chain,
+    source: &mut S,
+    gap_limit: u32,
+) -> Result<Vec<u32>, S::Error>
+where
+    S: AddressActivitySource,
+{
+    let mut used = vec![];
+    let mut unused_streak = 0;
+    for address in account.addresses(chain) {
+        if unused_streak >= gap_limit {
+            break;
+        }
+        match source.is_used(&address.stacks_address)? {
+            true => {
+                used.push(address.index);
+                unused_streak = 0;
+            }
+            false => unused_streak += 1,
+        }
+    }
+    Ok(used)
+}
+
+/// BIP44 account discovery: scans the accounts derived from `seed` in order, stopping at the
+/// first account whose external chain has no used address. Only the used accounts are returned.
+pub fn discover_accounts<S>(
+    seed: &[u8],
+    network: NetworkKind,
+    source: &mut S,
+    gap_limit: u32,
+) -> Result<Vec<DiscoveredAccount>, S::Error>
+where
+    S: AddressActivitySource,
+{
+    let mut accounts = vec![];
+    for account_index in 0.. {
+        let path = DerivationPath::from_str(&format!("m/44'/{STX_COIN_TYPE}'/{account_index}'")).unwrap();
+        let account_key = ExtendedPrivateKey::derive_from_path(seed, path);
+        let account = WatchOnlyAccount::new(&ExtendedPublicKey::from(&account_key), network);
+
+        let used_external = discover_chain(&account, Chain::External, source, gap_limit)?;
+        if used_external.is_empty() {
+            break;
+        }
+        let used_change = discover_chain(&account, Chain::Change, source, gap_limit)?;
+        accounts.push(DiscoveredAccount { account_index, used_external, used_change });
+    }
+    Ok(accounts)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use super::*;
+
+    struct UsedAddresses(HashSet<String>);
+
+    impl AddressActivitySource for UsedAddresses {
+        type Error = ();
+
+        fn is_used(&mut self, stacks_address: &str) -> Result<bool, Self::Error> {
+            Ok(self.0.contains(stacks_address))
+        }
+    }
+
+    fn address(seed: &[u8], account_index: u32, chain: Chain, index: u32) -> String {
+        let path = DerivationPath::from_str(&format!("m/44'/5757'/{account_index}'")).unwrap();
+        let account_key = ExtendedPrivateKey::derive_from_path(seed, path);
+        let account = WatchOnlyAccount::new(&ExtendedPublicKey::from(&account_key), NetworkKind::Mainnet);
+        account.address(chain, index).unwrap().stacks_address
+    }
+
+    #[test]
+    fn test_discover_accounts() {
+        let seed = hex::decode("000102030405060708090a0b0c0d0e0f").unwrap();
+        let mut source = UsedAddresses(HashSet::from([
+            address(&seed, 0, Chain::External, 0),
+            address(&seed, 0, Chain::External, 15),
+            address(&seed, 0, Chain::Change, 1),
+            address(&seed, 1, Chain::External, 3),
+            // beyond the gap limit of account 1, never reached
+            address(&seed, 1, Chain::External, 30),
+        ]));
+
+        let accounts = discover_accounts(&seed, NetworkKind::Mainnet, &mut source, DEFAULT_GAP_LIMIT).unwrap();
+        assert_eq!(accounts.len(), 2);
+        assert_eq!(accounts[0], DiscoveredAccount { account_index: 0, used_external: vec![0, 15], used_change: vec![1] });
+        assert_eq!(accounts[1], DiscoveredAccount { account_index: 1, used_external: vec![3], used_change: vec![] });
+    }
+
+    #[test]
+    fn test_discover_no_accounts() {
+        let seed = hex::decode("000102030405060708090a0b0c0d0e0f").unwrap();
+        let mut source = UsedAddresses(HashSet::new());
+        assert!(discover_accounts(&seed, NetworkKind::Mainnet, &mut source, DEFAULT_GAP_LIMIT).unwrap().is_empty());
+    }
+}
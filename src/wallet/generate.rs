@@ -1,3 +1,8 @@
+use rand::rngs::OsRng;
+use rand::{CryptoRng, RngCore};
+
+use crate::crypto::utils;
+
 use super::lockable_mnemonic::{LockableMnemonic, LockedMnemonicMethods};
 
 pub struct Wallet {
@@ -18,14 +23,21 @@ impl Wallet {
     /// The mnemonic (or secret_key) is then encrypted using AES-128-CBC with SHA256 HMAC.
     /// The `password` is passed to a pbkdf2 for deriving the required keys
     pub fn new(lockable_mnemonic: Option<&LockableMnemonic>) -> Wallet {
+        Self::new_with_rng(lockable_mnemonic, &mut OsRng)
+    }
+
+    /// Same as [`Wallet::new`] but draws the mnemonic entropy and the encryption salt from `rng`.
+    pub fn new_with_rng<R: RngCore + CryptoRng>(lockable_mnemonic: Option<&LockableMnemonic>, rng: &mut R) -> Wallet {
         // generate mnemonic
         let lockable_mnemonic = if let Some(lockable_mnemonic) = lockable_mnemonic {
             lockable_mnemonic
         } else {
-            &LockableMnemonic::new(None)
+            &LockableMnemonic::new_with_rng(None, rng)
         };
         // encrypt mnemonic with password
-        let encrypted_secret_key = lockable_mnemonic.lock_mnemonic(None).unwrap();
+        let mut salt = [0u8; 16];
+        utils::generate_random_bytes_with_rng(rng, &mut salt, 16);
+        let encrypted_secret_key = lockable_mnemonic.lock_mnemonic(Some(salt)).unwrap();
 
         // get root_key from the mnemonic (get the seed)
         let root_key = lockable_mnemonic.get_seed();
@@ -0,0 +1,194 @@
+use std::{fmt, str::FromStr};
+
+use secp256k1::SecretKey;
+use stacks_common::address::b58;
+
+use crate::bip32::child_number::{ChildNumber, ChildNumberError};
+use crate::bip32::derivation_path::{DerivationPath, MAX_DEPTH};
+use crate::crypto::keys::extended_private_key::{ExtendedPrivateKey, ExtendedPrivateKeyMethods};
+use crate::crypto::keys::KEY_LENGHT;
+use crate::network::NetworkKind;
+
+/// Version of the export format, first byte of the payload.
+const FORMAT_VERSION: u8 = 0x01;
+/// format version + network + depth
+const HEADER_LEN: usize = 3;
+const INDEX_LEN: usize = 4;
+
+#[derive(Debug)]
+pub enum Error {
+    /// Bad Base58 characters or checksum
+    InvalidEncoding(stacks_common::address::Error),
+    UnsupportedFormatVersion(u8),
+    InvalidNetwork(u8),
+    InvalidLength(usize),
+    InvalidPath(ChildNumberError),
+    InvalidKey(secp256k1::Error),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        match self {
+            Error::InvalidEncoding(error) => f.write_str(&format!("Invalid encoding: {error}")),
+            Error::UnsupportedFormatVersion(v) => f.write_str(&format!("Unsupported format version {v}")),
+            Error::InvalidNetwork(v) => f.write_str(&format!("Invalid network byte {v}")),
+            Error::InvalidLength(v) => f.write_str(&format!("Invalid payload length {v}")),
+            Error::InvalidPath(error) => f.write_str(&format!("{error}")),
+            Error::InvalidKey(error) => f.write_str(&format!("{error}")),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+/// The private key of a single account, together with the network and the derivation path it belongs to.
+///
+/// Its string form is the Base58Check encoding (4-byte checksum) of
+/// `format version (1) || network (1) || depth (1) || path indexes (4 each, BE) || secret key (32)`.
+///
+/// Usage:
+/// ```rust
+/// use std::str::FromStr;
+/// use stacks_rs::bip32::derivation_path::DerivationPath;
+/// use stacks_rs::network::NetworkKind;
+/// use stacks_rs::wallet::key_export::AccountKeyExport;
+/// let seed = hex::decode("000102030405060708090a0b0c0d0e0f").unwrap();
+/// let path = DerivationPath::from_str("m/44'/5757'/0'/0/0").unwrap();
+/// let export = AccountKeyExport::from_seed(&seed, path, NetworkKind::Mainnet);
+/// let exported = export.to_string();
+/// assert_eq!(AccountKeyExport::from_str(&exported).unwrap(), export);
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub struct AccountKeyExport {
+    pub network: NetworkKind,
+    pub derivation_path: DerivationPath,
+    pub s_key: SecretKey,
+}
+
+impl AccountKeyExport {
+    pub fn new(network: NetworkKind, derivation_path: DerivationPath, s_key: SecretKey) -> Self {
+        Self { network, derivation_path, s_key }
+    }
+
+    /// Derives the key at `derivation_path` from `seed`.
+    pub fn from_seed(seed: &[u8], derivation_path: DerivationPath, network: NetworkKind) -> Self {
+        let key = ExtendedPrivateKey::derive_from_path(seed, derivation_path.clone());
+        Self::new(network, derivation_path, key.s_key)
+    }
+
+    fn network_byte(network: &NetworkKind) -> u8 {
+        match network {
+            NetworkKind::Mainnet => 0x00,
+            NetworkKind::Testnet => 0x01,
+            NetworkKind::Mocknet => 0x02,
+        }
+    }
+
+    fn network_from_byte(byte: u8) -> Result<NetworkKind, Error> {
+        match byte {
+            0x00 => Ok(NetworkKind::Mainnet),
+            0x01 => Ok(NetworkKind::Testnet),
+            0x02 => Ok(NetworkKind::Mocknet),
+            v => Err(Error::InvalidNetwork(v)),
+        }
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = vec![
+            FORMAT_VERSION,
+            Self::network_byte(&self.network),
+            self.derivation_path.path.len() as u8,
+        ];
+        for child_number in &self.derivation_path.path {
+            bytes.extend(child_number.index.to_be_bytes());
+        }
+        bytes.extend(self.s_key.secret_bytes());
+        bytes
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, Error> {
+        if bytes.len() < HEADER_LEN {
+            return Err(Error::InvalidLength(bytes.len()));
+        }
+        if bytes[0] != FORMAT_VERSION {
+            return Err(Error::UnsupportedFormatVersion(bytes[0]));
+        }
+        let network = Self::network_from_byte(bytes[1])?;
+        let depth = bytes[2] as usize;
+        if depth > MAX_DEPTH || bytes.len() != HEADER_LEN + depth * INDEX_LEN + KEY_LENGHT {
+            return Err(Error::InvalidLength(bytes.len()));
+        }
+
+        let mut path = Vec::with_capacity(depth);
+        for index_bytes in bytes[HEADER_LEN..HEADER_LEN + depth * INDEX_LEN].chunks_exact(INDEX_LEN) {
+            let mut index = [0u8; INDEX_LEN];
+            index.copy_from_slice(index_bytes);
+            path.push(ChildNumber::new(u32::from_be_bytes(index)).map_err(Error::InvalidPath)?);
+        }
+        let mut key_bytes = [0u8; KEY_LENGHT];
+        key_bytes.copy_from_slice(&bytes[HEADER_LEN + depth * INDEX_LEN..]);
+
+        Ok(Self {
+            network,
+            derivation_path: DerivationPath { path },
+            s_key: SecretKey::from_byte_array(&key_bytes).map_err(Error::InvalidKey)?,
+        })
+    }
+}
+
+impl fmt::Display for AccountKeyExport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        b58::check_encode_slice_to_fmt(f, &self.to_bytes())
+    }
+}
+
+impl FromStr for AccountKeyExport {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let bytes = b58::from_check(s).map_err(Error::InvalidEncoding)?;
+        Self::from_bytes(&bytes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_export_roundtrip() {
+        let seed = hex::decode("000102030405060708090a0b0c0d0e0f").unwrap();
+        let path = DerivationPath::from_str("m/44'/5757'/0'/0/7").unwrap();
+        let export = AccountKeyExport::from_seed(&seed, path.clone(), NetworkKind::Testnet);
+
+        let parsed = AccountKeyExport::from_str(&export.to_string()).unwrap();
+        assert_eq!(parsed.network, NetworkKind::Testnet);
+        assert_eq!(parsed.derivation_path, path);
+        assert_eq!(parsed.s_key, ExtendedPrivateKey::derive_from_path(&seed, path).s_key);
+    }
+
+    #[test]
+    fn test_export_bad_checksum() {
+        let export = AccountKeyExport::new(
+            NetworkKind::Mainnet,
+            DerivationPath::from_str("m/44'/5757'/0'/0/0").unwrap(),
+            SecretKey::from_byte_array(&[1u8; 32]).unwrap(),
+        );
+        let mut exported = export.to_string();
+        let last = exported.pop().unwrap();
+        exported.push(if last == '1' { '2' } else { '1' });
+        assert!(matches!(AccountKeyExport::from_str(&exported), Err(Error::InvalidEncoding(_))));
+    }
+
+    #[test]
+    fn test_export_unsupported_version() {
+        let mut bytes = AccountKeyExport::new(
+            NetworkKind::Mainnet,
+            DerivationPath { path: vec![] },
+            SecretKey::from_byte_array(&[1u8; 32]).unwrap(),
+        )
+        .to_bytes();
+        bytes[0] = 0x02;
+        assert!(matches!(AccountKeyExport::from_bytes(&bytes), Err(Error::UnsupportedFormatVersion(2))));
+    }
+}
@@ -0,0 +1,478 @@
+use std::fmt;
+use aes::cipher::block_padding::UnpadError;
+use rand::rngs::OsRng;
+use rand::{CryptoRng, RngCore};
+
+use crate::crypto::cipher::aes_gcm::Aes256Gcm;
+use crate::crypto::cipher::chacha20_poly1305::ChaCha20Poly1305;
+use crate::crypto::cipher::{Cipher, CipherError};
+use crate::crypto::constant_time::ct_eq;
+use crate::crypto::encryption::{self, Aes128CbcDec, Aes128CbcEnc};
+use crate::crypto::hmac::{self, HmacError, HmacSha256};
+use crate::crypto::kdf::argon2::{argon2id, Argon2Params};
+use crate::crypto::kdf::pbkdf2::{pbkdf2_hmac_sha512, Pbkdf2Params};
+use crate::crypto::kdf::scrypt::{scrypt, ScryptParams};
+use crate::crypto::kdf::KdfError;
+use crate::crypto::utils;
+
+/// First bytes of every keystore, to tell it apart from the headerless stacks.js format.
+pub const KEYSTORE_MAGIC: [u8; 4] = *b"STKS";
+pub const KEYSTORE_VERSION: u8 = 1;
+pub const SALT_LENGTH: usize = 16;
+/// `magic (4) || version (1) || kdf id (1) || kdf params (12) || cipher id (1) || salt (16)`
+pub const HEADER_LENGTH: usize = 4 + 1 + 1 + 12 + 1 + SALT_LENGTH;
+const HMAC_LENGTH: usize = 32;
+/// AES-128 key, HMAC-SHA256 key and IV
+const CBC_DERIVED_LENGTH: usize = 48;
+
+/// Costliest KDF parameters read from a keystore header, so opening an untrusted keystore
+/// cannot run for hours or allocate gigabytes before the password is even checked.
+pub const MAX_PBKDF2_ROUNDS: u32 = 10_000_000;
+pub const MAX_SCRYPT_LOG_N: u8 = 20;
+pub const MAX_SCRYPT_R: u32 = 16;
+pub const MAX_SCRYPT_P: u32 = 16;
+/// 1 GiB
+pub const MAX_ARGON2_MEMORY_KIB: u32 = 1024 * 1024;
+pub const MAX_ARGON2_ITERATIONS: u32 = 64;
+pub const MAX_ARGON2_PARALLELISM: u32 = 16;
+
+/// `(enc_key, mac_key, iv)`
+type CbcKeys = ([u8; 16], [u8; 16], [u8; 16]);
+
+/// Key derivation function of a keystore, recorded with its parameters in the header.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum KeystoreKdf {
+    /// PBKDF2-HMAC-SHA512, as used by stacks.js
+    Pbkdf2(Pbkdf2Params),
+    Scrypt(ScryptParams),
+    Argon2id(Argon2Params),
+}
+
+impl Default for KeystoreKdf {
+    fn default() -> Self {
+        KeystoreKdf::Argon2id(Argon2Params::SENSITIVE)
+    }
+}
+
+impl KeystoreKdf {
+    pub fn id(&self) -> u8 {
+        match self {
+            KeystoreKdf::Pbkdf2(_) => 0x01,
+            KeystoreKdf::Scrypt(_) => 0x02,
+            KeystoreKdf::Argon2id(_) => 0x03,
+        }
+    }
+
+    /// Parameters as three big-endian `u32`, unused slots are zero.
+    fn params_to_bytes(&self) -> [u8; 12] {
+        let params: [u32; 3] = match self {
+            KeystoreKdf::Pbkdf2(params) => [params.rounds, 0, 0],
+            KeystoreKdf::Scrypt(params) => [params.log_n as u32, params.r, params.p],
+            KeystoreKdf::Argon2id(params) => [params.memory_kib, params.iterations, params.parallelism],
+        };
+        let mut bytes = [0u8; 12];
+        for (chunk, param) in bytes.chunks_exact_mut(4).zip(params) {
+            chunk.copy_from_slice(&param.to_be_bytes());
+        }
+        bytes
+    }
+
+    fn from_header(id: u8, bytes: &[u8; 12]) -> Result<Self, KeystoreError> {
+        let mut params = [0u32; 3];
+        for (param, chunk) in params.iter_mut().zip(bytes.chunks_exact(4)) {
+            *param = u32::from_be_bytes(chunk.try_into().unwrap());
+        }
+        let kdf = match id {
+            0x01 => KeystoreKdf::Pbkdf2(Pbkdf2Params::new(params[0])?),
+            0x02 => {
+                let log_n = u8::try_from(params[0]).map_err(|_| KdfError::InvalidParams(format!("log_n = {}", params[0])))?;
+                KeystoreKdf::Scrypt(ScryptParams::new(log_n, params[1], params[2])?)
+            }
+            0x03 => KeystoreKdf::Argon2id(Argon2Params::new(params[0], params[1], params[2])?),
+            _ => return Err(KeystoreError::UnknownKdf(id)),
+        };
+        if !kdf.is_within_limits() {
+            return Err(KeystoreError::KdfTooCostly(kdf));
+        }
+        Ok(kdf)
+    }
+
+    /// Whether the parameters are at most the `MAX_*` constants of this module.
+    pub fn is_within_limits(&self) -> bool {
+        match self {
+            KeystoreKdf::Pbkdf2(params) => params.rounds <= MAX_PBKDF2_ROUNDS,
+            KeystoreKdf::Scrypt(params) => params.log_n <= MAX_SCRYPT_LOG_N && params.r <= MAX_SCRYPT_R && params.p <= MAX_SCRYPT_P,
+            KeystoreKdf::Argon2id(params) => {
+                params.memory_kib <= MAX_ARGON2_MEMORY_KIB && params.iterations <= MAX_ARGON2_ITERATIONS && params.parallelism <= MAX_ARGON2_PARALLELISM
+            }
+        }
+    }
+
+    fn derive(&self, password: &[u8], salt: &[u8], output: &mut [u8]) -> Result<(), KdfError> {
+        match self {
+            KeystoreKdf::Pbkdf2(params) => pbkdf2_hmac_sha512(password, salt, params, output),
+            KeystoreKdf::Scrypt(params) => scrypt(password, salt, params, output),
+            KeystoreKdf::Argon2id(params) => argon2id(password, salt, params, output),
+        }
+    }
+}
+
+/// Encryption of the keystore payload.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum KeystoreCipher {
+    /// AES-128-CBC with SHA256 HMAC, as used by stacks.js
+    Aes128CbcHmac,
+    #[default]
+    Aes256Gcm,
+    /// For targets without AES instructions
+    ChaCha20Poly1305,
+}
+
+impl KeystoreCipher {
+    pub fn id(&self) -> u8 {
+        match self {
+            KeystoreCipher::Aes128CbcHmac => 0x01,
+            KeystoreCipher::Aes256Gcm => 0x02,
+            KeystoreCipher::ChaCha20Poly1305 => 0x03,
+        }
+    }
+
+    fn from_id(id: u8) -> Result<Self, KeystoreError> {
+        match id {
+            0x01 => Ok(KeystoreCipher::Aes128CbcHmac),
+            0x02 => Ok(KeystoreCipher::Aes256Gcm),
+            0x03 => Ok(KeystoreCipher::ChaCha20Poly1305),
+            _ => Err(KeystoreError::UnknownCipher(id)),
+        }
+    }
+}
+
+/// KDF and cipher of a new keystore.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct KeystoreParams {
+    pub kdf: KeystoreKdf,
+    pub cipher: KeystoreCipher,
+}
+
+impl KeystoreParams {
+    pub fn new(kdf: KeystoreKdf, cipher: KeystoreCipher) -> Self {
+        Self { kdf, cipher }
+    }
+}
+
+/// Plaintext header of a keystore, authenticated along with the payload.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct KeystoreHeader {
+    pub kdf: KeystoreKdf,
+    pub cipher: KeystoreCipher,
+    pub salt: [u8; SALT_LENGTH],
+}
+
+impl KeystoreHeader {
+    pub fn to_bytes(&self) -> [u8; HEADER_LENGTH] {
+        let mut bytes = [0u8; HEADER_LENGTH];
+        bytes[0..4].copy_from_slice(&KEYSTORE_MAGIC);
+        bytes[4] = KEYSTORE_VERSION;
+        bytes[5] = self.kdf.id();
+        bytes[6..18].copy_from_slice(&self.kdf.params_to_bytes());
+        bytes[18] = self.cipher.id();
+        bytes[19..].copy_from_slice(&self.salt);
+        bytes
+    }
+
+    /// Parses the header at the start of `keystore`.
+    pub fn from_bytes(keystore: &[u8]) -> Result<Self, KeystoreError> {
+        if !is_keystore(keystore) {
+            return Err(KeystoreError::InvalidMagic);
+        }
+        if keystore.len() < HEADER_LENGTH {
+            return Err(KeystoreError::Truncated);
+        }
+        if keystore[4] != KEYSTORE_VERSION {
+            return Err(KeystoreError::UnsupportedVersion(keystore[4]));
+        }
+        Ok(Self {
+            kdf: KeystoreKdf::from_header(keystore[5], keystore[6..18].try_into().unwrap())?,
+            cipher: KeystoreCipher::from_id(keystore[18])?,
+            salt: keystore[19..HEADER_LENGTH].try_into().unwrap(),
+        })
+    }
+
+    fn derive_cbc_keys(&self, password: &str) -> Result<CbcKeys, KeystoreError> {
+        let mut keys_and_iv = [0u8; CBC_DERIVED_LENGTH];
+        self.kdf.derive(password.as_bytes(), &self.salt, &mut keys_and_iv)?;
+        Ok((
+            keys_and_iv[0..16].try_into().unwrap(),
+            keys_and_iv[16..32].try_into().unwrap(),
+            keys_and_iv[32..48].try_into().unwrap(),
+        ))
+    }
+
+    fn aead<C: Cipher>(&self, password: &str) -> Result<C, KeystoreError> {
+        let mut key = vec![0u8; C::KEY_LENGTH];
+        self.kdf.derive(password.as_bytes(), &self.salt, &mut key)?;
+        Ok(C::new(&key)?)
+    }
+}
+
+/// Whether `bytes` starts with the keystore magic.
+pub fn is_keystore(bytes: &[u8]) -> bool {
+    bytes.starts_with(&KEYSTORE_MAGIC)
+}
+
+/// Encrypts `plaintext` with the cipher of `params`, deriving the key from `password` with its KDF.
+/// A random salt is generated when `salt` is `None`.
+///
+/// Output: `header || payload`, where the payload is `hmac(header || ciphertext) || ciphertext`
+/// for AES-128-CBC and `nonce || ciphertext || tag` (with the header as AAD) for the AEAD ciphers.
+///
+/// Usage:
+/// ```rust
+/// use stacks_rs::crypto::kdf::argon2::Argon2Params;
+/// use stacks_rs::wallet::keystore::{open, seal, KeystoreCipher, KeystoreKdf, KeystoreParams};
+/// let kdf = KeystoreKdf::Argon2id(Argon2Params::new(64, 1, 1).unwrap());
+/// let keystore = seal(b"secret", "password", &KeystoreParams::new(kdf, KeystoreCipher::Aes256Gcm), None).unwrap();
+/// assert_eq!(open(&keystore, "password").unwrap(), b"secret");
+/// ```
+pub fn seal(plaintext: &[u8], password: &str, params: &KeystoreParams, salt: Option<[u8; SALT_LENGTH]>) -> Result<Vec<u8>, KeystoreError> {
+    seal_salted(plaintext, password, params, salt, &mut OsRng)
+}
+
+/// Same as [`seal`] but draws the salt and the AEAD nonce from `rng` instead of the OS RNG.
+pub fn seal_with_rng<R: RngCore + CryptoRng>(plaintext: &[u8], password: &str, params: &KeystoreParams, rng: &mut R) -> Result<Vec<u8>, KeystoreError> {
+    seal_salted(plaintext, password, params, None, rng)
+}
+
+fn seal_salted<R: RngCore + CryptoRng>(
+    plaintext: &[u8],
+    password: &str,
+    params: &KeystoreParams,
+    salt: Option<[u8; SALT_LENGTH]>,
+    rng: &mut R,
+) -> Result<Vec<u8>, KeystoreError> {
+    // a keystore `open` would refuse
+    if !params.kdf.is_within_limits() {
+        return Err(KeystoreError::KdfTooCostly(params.kdf));
+    }
+    let salt = salt.unwrap_or_else(|| {
+        let mut salt = [0u8; SALT_LENGTH];
+        utils::generate_random_bytes_with_rng(rng, &mut salt, SALT_LENGTH);
+        salt
+    });
+    let header = KeystoreHeader { kdf: params.kdf, cipher: params.cipher, salt };
+    let header_bytes = header.to_bytes();
+
+    let payload = match header.cipher {
+        KeystoreCipher::Aes128CbcHmac => {
+            let (enc_key, mac_key, iv) = header.derive_cbc_keys(password)?;
+            let ciphertext = encryption::cbc_encrypt::<Aes128CbcEnc>(&enc_key, &iv, plaintext);
+            let hmac_sig = hmac::compute_hmac::<HmacSha256>(&[&header_bytes[..], &ciphertext].concat(), &mac_key)?;
+            [hmac_sig, ciphertext].concat()
+        }
+        KeystoreCipher::Aes256Gcm => header.aead::<Aes256Gcm>(password)?.seal_with_rng(plaintext, &header_bytes, rng)?,
+        KeystoreCipher::ChaCha20Poly1305 => header.aead::<ChaCha20Poly1305>(password)?.seal_with_rng(plaintext, &header_bytes, rng)?,
+    };
+    Ok([&header_bytes[..], &payload].concat())
+}
+
+/// Decrypts a keystore produced by [`seal`], using the KDF and cipher recorded in its header.
+pub fn open(keystore: &[u8], password: &str) -> Result<Vec<u8>, KeystoreError> {
+    let header = KeystoreHeader::from_bytes(keystore)?;
+    let (header_bytes, payload) = keystore.split_at(HEADER_LENGTH);
+    match header.cipher {
+        KeystoreCipher::Aes128CbcHmac => open_cbc(&header, header_bytes, payload, password),
+        KeystoreCipher::Aes256Gcm => open_aead(header.aead::<Aes256Gcm>(password)?, header_bytes, payload),
+        KeystoreCipher::ChaCha20Poly1305 => open_aead(header.aead::<ChaCha20Poly1305>(password)?, header_bytes, payload),
+    }
+}
+
+fn open_aead<C: Cipher>(cipher: C, header_bytes: &[u8], payload: &[u8]) -> Result<Vec<u8>, KeystoreError> {
+    match cipher.open(payload, header_bytes) {
+        Err(CipherError::AuthenticationFailed) => Err(KeystoreError::AuthenticationFailed),
+        result => Ok(result?),
+    }
+}
+
+fn open_cbc(header: &KeystoreHeader, header_bytes: &[u8], payload: &[u8], password: &str) -> Result<Vec<u8>, KeystoreError> {
+    if payload.len() < HMAC_LENGTH {
+        return Err(KeystoreError::Truncated);
+    }
+    let (hmac_sig, ciphertext) = payload.split_at(HMAC_LENGTH);
+    let (enc_key, mac_key, iv) = header.derive_cbc_keys(password)?;
+
+    let hmac_payload = [header_bytes, ciphertext].concat();
+    let hmac_digest = hmac::compute_hmac::<HmacSha256>(&hmac_payload, &mac_key)?;
+
+    if !ct_eq(&hmac_digest, hmac_sig) {
+        return Err(KeystoreError::AuthenticationFailed);
+    }
+
+    encryption::cbc_decrypt::<Aes128CbcDec>(&enc_key, &iv, ciphertext).map_err(KeystoreError::AesUnpadError)
+}
+
+#[derive(Clone, Debug)]
+pub enum KeystoreError {
+    InvalidMagic,
+    UnsupportedVersion(u8),
+    UnknownKdf(u8),
+    UnknownCipher(u8),
+    Truncated,
+    Kdf(KdfError),
+    /// KDF parameters above the `MAX_*` constants of this module
+    KdfTooCostly(KeystoreKdf),
+    AesUnpadError(UnpadError),
+    /// Wrong password, or the keystore was tampered with
+    AuthenticationFailed,
+    HmacError(HmacError),
+    Cipher(CipherError),
+    BadMnemonic(bip39::Error),
+}
+
+impl From<KdfError> for KeystoreError {
+    fn from(err: KdfError) -> Self {
+        KeystoreError::Kdf(err)
+    }
+}
+
+impl From<CipherError> for KeystoreError {
+    fn from(err: CipherError) -> Self {
+        match err {
+            CipherError::Truncated => KeystoreError::Truncated,
+            err => KeystoreError::Cipher(err),
+        }
+    }
+}
+
+impl From<HmacError> for KeystoreError {
+    fn from(err: HmacError) -> Self {
+        KeystoreError::HmacError(err)
+    }
+}
+
+impl fmt::Display for KeystoreError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        match self {
+            KeystoreError::InvalidMagic => f.write_str("Not a keystore (invalid magic bytes)"),
+            KeystoreError::UnsupportedVersion(v) => f.write_str(&format!("Unsupported keystore version {v}")),
+            KeystoreError::UnknownKdf(v) => f.write_str(&format!("Unknown keystore KDF {v:#04x}")),
+            KeystoreError::UnknownCipher(v) => f.write_str(&format!("Unknown keystore cipher {v:#04x}")),
+            KeystoreError::Truncated => f.write_str("Keystore is truncated"),
+            KeystoreError::Kdf(err) => f.write_str(&format!("{err}")),
+            KeystoreError::KdfTooCostly(v) => f.write_str(&format!("Keystore KDF parameters too costly: {v:?}")),
+            KeystoreError::AesUnpadError(err) => f.write_str(&format!("{err}")),
+            KeystoreError::AuthenticationFailed => f.write_str("Wrong password (authentication failed)"),
+            KeystoreError::HmacError(err) => f.write_str(&format!("{err}")),
+            KeystoreError::Cipher(err) => f.write_str(&format!("{err}")),
+            KeystoreError::BadMnemonic(err) => f.write_str(&format!("{err}")),
+        }
+    }
+}
+
+impl std::error::Error for KeystoreError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TEST_SALT: Option<[u8; SALT_LENGTH]> = Some([0xffu8; SALT_LENGTH]);
+    const CIPHERS: [KeystoreCipher; 3] = [KeystoreCipher::Aes128CbcHmac, KeystoreCipher::Aes256Gcm, KeystoreCipher::ChaCha20Poly1305];
+
+    fn test_kdfs() -> [KeystoreKdf; 3] {
+        [
+            KeystoreKdf::Pbkdf2(Pbkdf2Params::new(1_000).unwrap()),
+            KeystoreKdf::Scrypt(ScryptParams::new(10, 8, 1).unwrap()),
+            KeystoreKdf::Argon2id(Argon2Params::new(256, 2, 2).unwrap()),
+        ]
+    }
+
+    #[test]
+    fn test_seal_open() {
+        for kdf in test_kdfs() {
+            for cipher in CIPHERS {
+                let keystore = seal(b"attack at dawn", "testtest", &KeystoreParams::new(kdf, cipher), TEST_SALT).unwrap();
+                assert!(is_keystore(&keystore));
+                assert_eq!(KeystoreHeader::from_bytes(&keystore).unwrap(), KeystoreHeader { kdf, cipher, salt: TEST_SALT.unwrap() });
+                assert_eq!(open(&keystore, "testtest").unwrap(), b"attack at dawn");
+                assert!(matches!(open(&keystore, "wrong"), Err(KeystoreError::AuthenticationFailed)));
+            }
+        }
+    }
+
+    #[test]
+    fn test_seal_with_rng() {
+        use rand::{rngs::StdRng, SeedableRng};
+
+        let params = KeystoreParams::new(test_kdfs()[0], KeystoreCipher::ChaCha20Poly1305);
+        let first = seal_with_rng(b"attack at dawn", "testtest", &params, &mut StdRng::seed_from_u64(7)).unwrap();
+        let second = seal_with_rng(b"attack at dawn", "testtest", &params, &mut StdRng::seed_from_u64(7)).unwrap();
+        assert_eq!(first, second);
+        assert_ne!(KeystoreHeader::from_bytes(&first).unwrap().salt, [0u8; SALT_LENGTH]);
+        assert_eq!(open(&first, "testtest").unwrap(), b"attack at dawn");
+    }
+
+    #[test]
+    fn test_kdf_limits() {
+        let header = |kdf: KeystoreKdf| KeystoreHeader { kdf, cipher: KeystoreCipher::Aes256Gcm, salt: [0xffu8; SALT_LENGTH] }.to_bytes();
+        let too_costly = [
+            KeystoreKdf::Pbkdf2(Pbkdf2Params::new(MAX_PBKDF2_ROUNDS + 1).unwrap()),
+            KeystoreKdf::Pbkdf2(Pbkdf2Params::new(u32::MAX).unwrap()),
+            KeystoreKdf::Scrypt(ScryptParams::new(MAX_SCRYPT_LOG_N + 1, 8, 1).unwrap()),
+            KeystoreKdf::Scrypt(ScryptParams::new(10, MAX_SCRYPT_R + 1, 1).unwrap()),
+            KeystoreKdf::Scrypt(ScryptParams::new(10, 8, MAX_SCRYPT_P + 1).unwrap()),
+            KeystoreKdf::Argon2id(Argon2Params::new(MAX_ARGON2_MEMORY_KIB + 1, 1, 1).unwrap()),
+            KeystoreKdf::Argon2id(Argon2Params::new(256, MAX_ARGON2_ITERATIONS + 1, 1).unwrap()),
+            KeystoreKdf::Argon2id(Argon2Params::new(256, 1, MAX_ARGON2_PARALLELISM + 1).unwrap()),
+        ];
+        for kdf in too_costly {
+            assert!(matches!(KeystoreHeader::from_bytes(&header(kdf)), Err(KeystoreError::KdfTooCostly(v)) if v == kdf));
+            assert!(matches!(seal(b"secret", "password", &KeystoreParams::new(kdf, KeystoreCipher::Aes256Gcm), None), Err(KeystoreError::KdfTooCostly(_))));
+        }
+        for kdf in test_kdfs().into_iter().chain([KeystoreKdf::default(), KeystoreKdf::Scrypt(ScryptParams::SENSITIVE)]) {
+            assert_eq!(KeystoreHeader::from_bytes(&header(kdf)).unwrap().kdf, kdf);
+        }
+    }
+
+    #[test]
+    fn test_header_layout() {
+        let kdf = KeystoreKdf::Argon2id(Argon2Params::new(256, 2, 2).unwrap());
+        let header = KeystoreHeader { kdf, cipher: KeystoreCipher::Aes256Gcm, salt: [0xffu8; SALT_LENGTH] }.to_bytes();
+        assert_eq!(
+            hex::encode(header),
+            "53544b53010300000100000000020000000202ffffffffffffffffffffffffffffffff"
+        );
+    }
+
+    #[test]
+    fn test_tampered_header() {
+        let kdf = KeystoreKdf::Argon2id(Argon2Params::new(256, 2, 2).unwrap());
+        for cipher in CIPHERS {
+            let keystore = seal(b"attack at dawn", "testtest", &KeystoreParams::new(kdf, cipher), TEST_SALT).unwrap();
+
+            // weakening the recorded parameters is caught by the authentication
+            let mut tampered = keystore.clone();
+            tampered[8] = 0x00;
+            tampered[9] = 0x80;
+            assert!(matches!(open(&tampered, "testtest"), Err(KeystoreError::AuthenticationFailed)));
+
+            let mut tampered = keystore.clone();
+            tampered[5] = 0x7f;
+            assert!(matches!(open(&tampered, "testtest"), Err(KeystoreError::UnknownKdf(0x7f))));
+
+            let mut tampered = keystore.clone();
+            tampered[18] = 0x7f;
+            assert!(matches!(open(&tampered, "testtest"), Err(KeystoreError::UnknownCipher(0x7f))));
+
+            let mut tampered = keystore.clone();
+            tampered[4] = 2;
+            assert!(matches!(open(&tampered, "testtest"), Err(KeystoreError::UnsupportedVersion(2))));
+
+            // raising them beyond the limits is refused before deriving anything
+            let mut tampered = keystore.clone();
+            tampered[6] = 0x7f;
+            assert!(matches!(open(&tampered, "testtest"), Err(KeystoreError::KdfTooCostly(_))));
+
+            assert!(matches!(open(&keystore[..HEADER_LENGTH + 8], "testtest"), Err(KeystoreError::Truncated)));
+            assert!(matches!(open(&keystore[4..], "testtest"), Err(KeystoreError::InvalidMagic)));
+        }
+    }
+}
@@ -1,13 +1,15 @@
 use std::fmt;
 use aes::cipher::block_padding::UnpadError;
-use sha2::{Sha256, Sha512};
+use rand::{CryptoRng, RngCore};
+use sha2::Sha512;
 
+use crate::crypto::constant_time::ct_eq;
 use crate::crypto::encryption::{self, Aes128CbcDec, Aes128CbcEnc};
-use crate::crypto::hash::U32;
 use crate::crypto::hmac::{HmacError, HmacSha256};
-use crate::crypto::{hash, hmac};
+use crate::crypto::hmac;
 
 use super::bip39::{Bip39Mnemonic, Bip39MnemonicMethods};
+use super::keystore::{self, KeystoreError, KeystoreParams};
 
 pub struct LockableMnemonic {
     b39_mnemonic: Bip39Mnemonic, 
@@ -16,10 +18,14 @@ pub struct LockableMnemonic {
 
 pub trait LockedMnemonicMethods {
     fn new(password: Option<String>) -> Self;
+    fn new_with_rng<R: RngCore + CryptoRng>(password: Option<String>, rng: &mut R) -> Self;
     fn from_bip39_mnemonic(mnemo: &Bip39Mnemonic, password: Option<String>) -> Result<Self, Error> where Self: Sized;
     fn from_bip39_words(words: &str, password: Option<String>) -> Result<Self, Error> where Self: Sized;
     fn lock_mnemonic(&self, salt: Option<[u8; 16]>) -> Result<Vec<u8>, Error>;
     fn unlock_mnenomic(encrypted_mnemonic: &Vec<u8>, password: &str) -> Result<Self, Error> where Self: Sized;
+    fn lock_keystore(&self, params: &KeystoreParams) -> Result<Vec<u8>, KeystoreError>;
+    fn lock_keystore_with_rng<R: RngCore + CryptoRng>(&self, params: &KeystoreParams, rng: &mut R) -> Result<Vec<u8>, KeystoreError>;
+    fn unlock_keystore(keystore: &[u8], password: &str) -> Result<Self, KeystoreError> where Self: Sized;
     fn get_seed(&self) -> [u8; 64];
 }
 
@@ -32,6 +38,14 @@ impl LockedMnemonicMethods for LockableMnemonic {
         }
     }
 
+    /// Same as [`LockedMnemonicMethods::new`] but draws the entropy from `rng`.
+    fn new_with_rng<R: RngCore + CryptoRng>(password: Option<String>, rng: &mut R) -> Self {
+        LockableMnemonic{
+            b39_mnemonic: Bip39Mnemonic::new_with_rng(None, rng).unwrap(),
+            password: password
+        }
+    }
+
     fn from_bip39_mnemonic(mnemo: &Bip39Mnemonic, password: Option<String>) -> Result<Self, Error> {   
         Ok(Self {
             b39_mnemonic: mnemo.clone(),
@@ -48,7 +62,8 @@ impl LockedMnemonicMethods for LockableMnemonic {
         })
     }
 
-    /// Encryption with AES-128-CBC with SHA256 HMAC
+    /// Encryption with AES-128-CBC with SHA256 HMAC, in the format of stacks.js `encryptMnemonic`
+    /// (PBKDF2-HMAC-SHA512 keys, `salt || hmac || ciphertext`).
     fn lock_mnemonic(&self, salt: Option<[u8; 16]>) -> Result<Vec<u8>, Error> {
         let (
             enc_key, 
@@ -103,10 +118,7 @@ impl LockedMnemonicMethods for LockableMnemonic {
             Error::HmacError(err)
         })?;
         
-        let hmac_sig_hash = hash::compute_hash::<Sha256, U32>(hmac_sig);
-        let hmac_digest_hash = hash::compute_hash::<Sha256, U32>(&hmac_digest);
-    
-        if hmac_digest_hash[..] != hmac_sig_hash[..] {
+        if !ct_eq(&hmac_digest, hmac_sig) {
             return Err(Error::HmacMismatch);
         };
     
@@ -118,6 +130,24 @@ impl LockedMnemonicMethods for LockableMnemonic {
         Ok(LockableMnemonic{b39_mnemonic: mnemonic?, password: Some(password.to_string())})
     }
 
+    /// Encrypts the mnemonic entropy into a keystore whose header records the KDF, its parameters and the cipher.
+    /// Unlike [`LockedMnemonicMethods::lock_mnemonic`], the output is not readable by stacks.js.
+    fn lock_keystore(&self, params: &KeystoreParams) -> Result<Vec<u8>, KeystoreError> {
+        keystore::seal(&self.b39_mnemonic.to_entropy(), self.password.as_deref().unwrap_or(""), params, None)
+    }
+
+    /// Same as [`LockedMnemonicMethods::lock_keystore`] but draws the salt and nonce from `rng`.
+    fn lock_keystore_with_rng<R: RngCore + CryptoRng>(&self, params: &KeystoreParams, rng: &mut R) -> Result<Vec<u8>, KeystoreError> {
+        keystore::seal_with_rng(&self.b39_mnemonic.to_entropy(), self.password.as_deref().unwrap_or(""), params, rng)
+    }
+
+    /// Decrypts a keystore produced by [`LockedMnemonicMethods::lock_keystore`].
+    fn unlock_keystore(keystore: &[u8], password: &str) -> Result<Self, KeystoreError> {
+        let entropy = keystore::open(keystore, password)?;
+        let mnemonic = Bip39Mnemonic::entropy_to_mnemonic(&entropy).map_err(KeystoreError::BadMnemonic)?;
+        Ok(LockableMnemonic{b39_mnemonic: mnemonic, password: Some(password.to_string())})
+    }
+
     fn get_seed(&self) -> [u8; 64] {
         self.b39_mnemonic.get_seed(
             self.password.as_deref().unwrap_or("")
@@ -148,6 +178,7 @@ impl fmt::Display for Error {
                     HmacError::InvalidKeyLength(err) => {
                         f.write_str(&format!("{err}"))
                     }
+                    HmacError::Mismatch => f.write_str(&format!("{hmac_error}")),
                 }
             },
             Error::BadMnemonic(error) =>  f.write_str(&format!("{error}")),
@@ -190,4 +221,20 @@ mod tests {
             .unwrap();
         assert_eq!(decrypted_mnemonic_with_password.b39_mnemonic, mnemonic_with_password.b39_mnemonic);
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_lock_unlock_keystore() {
+        use crate::crypto::kdf::argon2::Argon2Params;
+
+        let words = "march eager husband pilot waste rely exclude taste twist donkey actress scene";
+        let mnemonic = LockableMnemonic::from_bip39_words(words, Some("testtest".to_string())).unwrap();
+        use super::keystore::{KeystoreCipher, KeystoreKdf};
+
+        let kdf = KeystoreKdf::Argon2id(Argon2Params::new(256, 2, 1).unwrap());
+        let keystore = mnemonic.lock_keystore(&KeystoreParams::new(kdf, KeystoreCipher::Aes256Gcm)).unwrap();
+
+        let unlocked = LockableMnemonic::unlock_keystore(&keystore, "testtest").unwrap();
+        assert_eq!(unlocked.b39_mnemonic, mnemonic.b39_mnemonic);
+        assert!(LockableMnemonic::unlock_keystore(&keystore, "wrong").is_err());
+    }
+}
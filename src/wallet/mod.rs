@@ -1,3 +1,22 @@
 pub mod generate;
 pub mod lockable_mnemonic;
-pub mod bip39;
\ No newline at end of file
+pub mod keystore;
+pub mod bip39;
+pub mod normalize;
+pub mod key_export;
+pub mod watch_only;
+pub mod address_generator;
+pub mod descriptor;
+pub mod discovery;
+pub mod session;
+#[cfg(feature = "recovery")]
+pub mod recovery;
+#[cfg(feature = "vanity")]
+pub mod vanity;
+
+/// BIP44 coin type of Stacks.
+pub const STX_COIN_TYPE: u32 = 5757;
+
+/// BIP44 path of the Stacks (coin type `5757`) external chain of the first account.
+/// Address keys are its non-hardened children (e.g. `m/44'/5757'/0'/0/0`).
+pub const STX_DERIVATION_PATH: &str = "m/44'/5757'/0'/0";
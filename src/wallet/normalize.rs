@@ -0,0 +1,173 @@
+use std::borrow::Cow;
+use std::fmt;
+
+use bip39::{Language, Mnemonic};
+
+/// Maximum edit distance for a wordlist entry to be suggested in place of an unknown word.
+const MAX_SUGGESTION_DISTANCE: usize = 2;
+
+/// How mnemonic input typed (or pasted) by a user is normalized before being parsed.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MnemonicNormalization {
+    /// The input must already be in canonical form: NFKD, lowercase, words separated by a single space.
+    Strict,
+    /// Applies NFKD, trims, collapses any whitespace between words and lowercases the input.
+    Tolerant,
+}
+
+/// A word that is not part of the wordlist, with the closest wordlist entries.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct UnknownWord {
+    /// Position of the word in the mnemonic (starting from 0)
+    pub position: usize,
+    pub word: String,
+    /// Wordlist entries within a small edit distance of `word`, closest first
+    pub suggestions: Vec<&'static str>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Error {
+    /// Only returned in [`MnemonicNormalization::Strict`] mode
+    NotNormalized,
+    UnknownWords(Vec<UnknownWord>),
+    BadMnemonic(bip39::Error),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        match self {
+            Error::NotNormalized => f.write_str("Mnemonic is not in normalized form"),
+            Error::UnknownWords(unknown_words) => {
+                f.write_str("Unknown words in mnemonic:")?;
+                for unknown in unknown_words {
+                    write!(f, " `{}` (word {})", unknown.word, unknown.position + 1)?;
+                    if !unknown.suggestions.is_empty() {
+                        write!(f, " did you mean {}?", unknown.suggestions.join(", "))?;
+                    }
+                }
+                Ok(())
+            }
+            Error::BadMnemonic(error) => f.write_str(&format!("{error}")),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+/// Returns the normalized form of `words` according to `normalization`.
+///
+/// Usage:
+/// ```rust
+/// use stacks_rs::wallet::normalize::{normalize_mnemonic, MnemonicNormalization};
+/// let words = normalize_mnemonic("  March  EAGER\thusband ", MnemonicNormalization::Tolerant).unwrap();
+/// assert_eq!(words, "march eager husband");
+/// ```
+pub fn normalize_mnemonic(words: &str, normalization: MnemonicNormalization) -> Result<String, Error> {
+    let mut nfkd = Cow::Borrowed(words);
+    Mnemonic::normalize_utf8_cow(&mut nfkd);
+    let normalized = nfkd
+        .split_whitespace()
+        .map(|word| word.to_lowercase())
+        .collect::<Vec<String>>()
+        .join(" ");
+
+    match normalization {
+        MnemonicNormalization::Tolerant => Ok(normalized),
+        MnemonicNormalization::Strict if normalized == words => Ok(normalized),
+        MnemonicNormalization::Strict => Err(Error::NotNormalized),
+    }
+}
+
+/// Normalizes `words` and parses them into a [`Mnemonic`] of the given `language`.
+///
+/// Words that are not in the wordlist are reported all at once, together with suggestions.
+pub fn parse_mnemonic(words: &str, language: Language, normalization: MnemonicNormalization) -> Result<Mnemonic, Error> {
+    let normalized = normalize_mnemonic(words, normalization)?;
+    let unknown_words = normalized
+        .split(' ')
+        .enumerate()
+        .filter(|(_, word)| language.find_word(word).is_none())
+        .map(|(position, word)| UnknownWord {
+            position,
+            word: word.to_string(),
+            suggestions: suggest_words(word, language),
+        })
+        .collect::<Vec<UnknownWord>>();
+    if !unknown_words.is_empty() {
+        return Err(Error::UnknownWords(unknown_words));
+    }
+    Mnemonic::parse_in_normalized(language, &normalized).map_err(Error::BadMnemonic)
+}
+
+/// Returns the wordlist entries within a small edit distance of `word`, closest first.
+pub fn suggest_words(word: &str, language: Language) -> Vec<&'static str> {
+    let mut candidates = language
+        .word_list()
+        .iter()
+        .map(|candidate| (edit_distance(word, candidate), *candidate))
+        .filter(|(distance, _)| *distance <= MAX_SUGGESTION_DISTANCE)
+        .collect::<Vec<(usize, &'static str)>>();
+    candidates.sort();
+    candidates.into_iter().map(|(_, candidate)| candidate).collect()
+}
+
+/// Levenshtein distance between `a` and `b`.
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b = b.chars().collect::<Vec<char>>();
+    let mut previous = (0..=b.len()).collect::<Vec<usize>>();
+    let mut current = vec![0; b.len() + 1];
+    for (i, a_char) in a.chars().enumerate() {
+        current[0] = i + 1;
+        for (j, b_char) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(a_char != *b_char);
+            current[j + 1] = substitution.min(previous[j + 1] + 1).min(current[j] + 1);
+        }
+        std::mem::swap(&mut previous, &mut current);
+    }
+    previous[b.len()]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const WORDS: &str = "march eager husband pilot waste rely exclude taste twist donkey actress scene";
+
+    #[test]
+    fn test_tolerant_normalization() {
+        let messy = "  March eager\thusband  pilot\nwaste rely EXCLUDE taste twist donkey actress scene ";
+        let mnemonic = parse_mnemonic(messy, Language::English, MnemonicNormalization::Tolerant).unwrap();
+        assert_eq!(mnemonic, Mnemonic::parse_normalized(WORDS).unwrap());
+    }
+
+    #[test]
+    fn test_strict_normalization() {
+        assert!(parse_mnemonic(WORDS, Language::English, MnemonicNormalization::Strict).is_ok());
+        let messy = "March eager husband pilot waste rely exclude taste twist donkey actress scene";
+        assert_eq!(
+            parse_mnemonic(messy, Language::English, MnemonicNormalization::Strict),
+            Err(Error::NotNormalized)
+        );
+    }
+
+    #[test]
+    fn test_unknown_word_suggestions() {
+        let typo = "march eagre husband pilot waste rely exclude taste twist donkey actress scene";
+        match parse_mnemonic(typo, Language::English, MnemonicNormalization::Tolerant) {
+            Err(Error::UnknownWords(unknown_words)) => {
+                assert_eq!(unknown_words.len(), 1);
+                assert_eq!(unknown_words[0].position, 1);
+                assert_eq!(unknown_words[0].word, "eagre");
+                assert!(unknown_words[0].suggestions.contains(&"eager"));
+            }
+            _ => panic!("Should report the unknown word"),
+        }
+    }
+
+    #[test]
+    fn test_edit_distance() {
+        assert_eq!(edit_distance("eager", "eager"), 0);
+        assert_eq!(edit_distance("eagre", "eager"), 2);
+        assert_eq!(edit_distance("abandn", "abandon"), 1);
+    }
+}
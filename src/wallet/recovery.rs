@@ -0,0 +1,232 @@
+use std::fmt;
+use std::ops::Range;
+use std::str::FromStr;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use bip39::{Language, Mnemonic};
+use rayon::prelude::*;
+use crate::address::c32::c32_address_decode;
+
+use crate::bip32::derivation_path::DerivationPath;
+use crate::crypto::hash::{Hash160, Hasher};
+use crate::crypto::keys::extended_private_key::{ExtendedPrivateKey, ExtendedPrivateKeyMethods};
+
+use super::bip39::{Bip39Mnemonic, Bip39MnemonicMethods};
+use super::normalize::{self, MnemonicNormalization};
+use super::STX_DERIVATION_PATH;
+
+/// Placeholder for a word of the mnemonic that is not known.
+pub const UNKNOWN_WORD: &str = "?";
+/// Maximum number of [`UNKNOWN_WORD`]s that can be searched for.
+pub const MAX_UNKNOWN_WORDS: usize = 2;
+/// Number of checked candidates between two progress reports.
+const PROGRESS_STEP: u64 = 1024;
+const WORDLIST_LEN: u64 = 2048;
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Error {
+    TooManyUnknownWords(usize),
+    BadWords(normalize::Error),
+    InvalidAddress,
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        match self {
+            Error::TooManyUnknownWords(n) => f.write_str(&format!(
+                "Too many unknown words! Got {}, max is {}",
+                n, MAX_UNKNOWN_WORDS
+            )),
+            Error::BadWords(error) => f.write_str(&format!("{error}")),
+            Error::InvalidAddress => f.write_str("Invalid target address!"),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+pub struct RecoveryOptions {
+    /// BIP39 password of the mnemonic
+    pub password: String,
+    /// Address indexes (children of [`STX_DERIVATION_PATH`]) compared against the target address
+    pub address_indexes: Range<u32>,
+    /// Also try every mnemonic obtained by swapping two of its words
+    pub try_swaps: bool,
+}
+
+impl Default for RecoveryOptions {
+    fn default() -> Self {
+        Self { password: String::new(), address_indexes: 0..1, try_swaps: true }
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct RecoveryProgress {
+    /// Candidates checked so far
+    pub checked: u64,
+    /// Total number of candidates in the search space
+    pub total: u64,
+}
+
+pub struct RecoveredMnemonic {
+    pub mnemonic: Bip39Mnemonic,
+    /// Index of the address matching the target address
+    pub address_index: u32,
+}
+
+/// Searches the mnemonic whose (Stacks) address matches `target_address`.
+///
+/// Unknown words of `words` are marked with [`UNKNOWN_WORD`] (at most [`MAX_UNKNOWN_WORDS`]);
+/// with [`RecoveryOptions::try_swaps`] every pair of words is also tried in swapped order.
+/// Candidates are checked in parallel, calling `progress` every few thousand candidates.
+///
+/// Returns `Ok(None)` if no candidate matches.
+pub fn recover_mnemonic<F>(
+    words: &str,
+    target_address: &str,
+    options: &RecoveryOptions,
+    progress: F,
+) -> Result<Option<RecoveredMnemonic>, Error>
+where
+    F: Fn(RecoveryProgress) + Sync,
+{
+    let (_version, target_hash) = c32_address_decode(target_address).map_err(|_err| Error::InvalidAddress)?;
+    let template = parse_template(words)?;
+    let unknown_positions = template
+        .iter()
+        .enumerate()
+        .filter(|(_, word)| word.is_none())
+        .map(|(position, _)| position)
+        .collect::<Vec<usize>>();
+    if unknown_positions.len() > MAX_UNKNOWN_WORDS {
+        return Err(Error::TooManyUnknownWords(unknown_positions.len()));
+    }
+
+    let mut swaps = vec![None];
+    if options.try_swaps {
+        for i in 0..template.len() {
+            for j in i + 1..template.len() {
+                swaps.push(Some((i, j)));
+            }
+        }
+    }
+    let fills = WORDLIST_LEN.pow(unknown_positions.len() as u32);
+    let total = swaps.len() as u64 * fills;
+    let checked = AtomicU64::new(0);
+    let path = DerivationPath::from_str(STX_DERIVATION_PATH).unwrap();
+    let word_list = Language::English.word_list();
+
+    let found = (0..total).into_par_iter().find_map_any(|candidate| {
+        let mut indices = template.clone();
+        let mut fill = candidate % fills;
+        for position in &unknown_positions {
+            indices[*position] = Some((fill % WORDLIST_LEN) as u16);
+            fill /= WORDLIST_LEN;
+        }
+        if let Some((i, j)) = swaps[(candidate / fills) as usize] {
+            indices.swap(i, j);
+        }
+
+        let count = checked.fetch_add(1, Ordering::Relaxed) + 1;
+        if count.is_multiple_of(PROGRESS_STEP) || count == total {
+            progress(RecoveryProgress { checked: count, total });
+        }
+
+        let phrase = indices
+            .iter()
+            .map(|index| word_list[index.unwrap() as usize])
+            .collect::<Vec<&str>>()
+            .join(" ");
+        // Most candidates are discarded here by the checksum, before the expensive seed derivation
+        let mnemonic = Mnemonic::parse_in_normalized(Language::English, &phrase).ok()?;
+        let seed = mnemonic.to_seed_normalized(&options.password);
+        let account = ExtendedPrivateKey::derive_from_path(&seed, DerivationPath { path: path.path.clone() });
+        account
+            .derive_children(options.address_indexes.clone())
+            .position(|child| Hash160::hash(&child.public_key().serialize())[..] == target_hash[..])
+            .map(|position| (phrase, options.address_indexes.start + position as u32))
+    });
+
+    Ok(found.map(|(phrase, address_index)| RecoveredMnemonic {
+        mnemonic: Bip39Mnemonic::mnemonic_from_words(&phrase).unwrap(),
+        address_index,
+    }))
+}
+
+/// Converts `words` into wordlist indexes, `None` marking the [`UNKNOWN_WORD`]s.
+fn parse_template(words: &str) -> Result<Vec<Option<u16>>, Error> {
+    let normalized = normalize::normalize_mnemonic(words, MnemonicNormalization::Tolerant).map_err(Error::BadWords)?;
+    let mut unknown_words = vec![];
+    let template = normalized
+        .split(' ')
+        .enumerate()
+        .map(|(position, word)| match word {
+            UNKNOWN_WORD => None,
+            word => {
+                let index = Language::English.find_word(word);
+                if index.is_none() {
+                    unknown_words.push(normalize::UnknownWord {
+                        position,
+                        word: word.to_string(),
+                        suggestions: normalize::suggest_words(word, Language::English),
+                    });
+                }
+                index
+            }
+        })
+        .collect::<Vec<Option<u16>>>();
+    if !unknown_words.is_empty() {
+        return Err(Error::BadWords(normalize::Error::UnknownWords(unknown_words)));
+    }
+    Ok(template)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::address::c32::c32_address;
+
+    use super::*;
+
+    const WORDS: &str = "march eager husband pilot waste rely exclude taste twist donkey actress scene";
+
+    fn address_of(words: &str, index: u32) -> String {
+        let seed = Mnemonic::parse_normalized(words).unwrap().to_seed_normalized("");
+        let account = ExtendedPrivateKey::derive_from_path(&seed, DerivationPath::from_str(STX_DERIVATION_PATH).unwrap());
+        let child = account.derive_children(index..index + 1).next().unwrap();
+        let hash = Hash160::hash(&child.public_key().serialize());
+        c32_address(22, &hash).unwrap()
+    }
+
+    #[test]
+    fn test_recover_unknown_word() {
+        let target_address = address_of(WORDS, 1);
+        let words = "march eager husband pilot waste rely exclude ? twist donkey actress scene";
+        let options = RecoveryOptions { address_indexes: 0..2, try_swaps: false, ..Default::default() };
+        let last_progress = AtomicU64::new(0);
+        let recovered = recover_mnemonic(words, &target_address, &options, |progress| {
+            last_progress.fetch_max(progress.checked, Ordering::Relaxed);
+        })
+        .unwrap()
+        .unwrap();
+        assert_eq!(recovered.mnemonic, Bip39Mnemonic::mnemonic_from_words(WORDS).unwrap());
+        assert_eq!(recovered.address_index, 1);
+        assert!(last_progress.load(Ordering::Relaxed) > 0);
+    }
+
+    #[test]
+    fn test_recover_swapped_words() {
+        let target_address = address_of(WORDS, 0);
+        let words = "march eager husband pilot waste rely exclude taste twist actress donkey scene";
+        let recovered = recover_mnemonic(words, &target_address, &RecoveryOptions::default(), |_| {})
+            .unwrap()
+            .unwrap();
+        assert_eq!(recovered.mnemonic, Bip39Mnemonic::mnemonic_from_words(WORDS).unwrap());
+    }
+
+    #[test]
+    fn test_too_many_unknown_words() {
+        let words = "? ? ? pilot waste rely exclude taste twist donkey actress scene";
+        let result = recover_mnemonic(words, &address_of(WORDS, 0), &RecoveryOptions::default(), |_| {});
+        assert!(matches!(result, Err(Error::TooManyUnknownWords(3))));
+    }
+}
@@ -0,0 +1,211 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::str::FromStr;
+
+use secp256k1::SecretKey;
+
+use crate::bip32::derivation_path::DerivationPath;
+use crate::crypto::keys::cached_deriver::CachedDeriver;
+use crate::crypto::keys::derivable::DerivationError;
+use crate::crypto::keys::extended_private_key::{ExtendedPrivateKey, ExtendedPrivateKeyMethods};
+use crate::crypto::keys::extended_public_key::ExtendedPublicKey;
+use crate::network::NetworkKind;
+
+use super::generate::Wallet;
+use super::watch_only::{Chain, WatchOnlyAccount};
+
+/// First hardened index, out of reach of the account and address indexes.
+const HARDENED_INDEX: u32 = 1 << 31;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Error {
+    /// Account and address indexes are below 2^31, hardening is implied by the path
+    InvalidIndex(u32),
+    Derivation(DerivationError),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        match self {
+            Error::InvalidIndex(v) => f.write_str(&format!("Invalid index {v}, must be below 2^31")),
+            Error::Derivation(err) => f.write_str(&format!("Cannot derive key: {err}")),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+impl From<DerivationError> for Error {
+    fn from(err: DerivationError) -> Self {
+        Error::Derivation(err)
+    }
+}
+
+fn check_index(index: u32) -> Result<u32, Error> {
+    match index < HARDENED_INDEX {
+        true => Ok(index),
+        false => Err(Error::InvalidIndex(index)),
+    }
+}
+
+/// The state of a [`Wallet`] on one network: its own client, nonces and address cache.
+///
+/// Sessions of the same wallet share only the seed, so a mainnet and a testnet session
+/// can live in the same process without ever mixing nonces or addresses.
+///
+/// Usage:
+/// ```rust
+/// use stacks_rs::network::NetworkKind;
+/// use stacks_rs::wallet::generate::Wallet;
+/// let wallet = Wallet::new(None);
+/// let mut mainnet = wallet.session(NetworkKind::Mainnet, 0).unwrap();
+/// let mut testnet = wallet.session(NetworkKind::Testnet, 0).unwrap();
+/// assert!(mainnet.address(0).unwrap().starts_with("SP"));
+/// assert!(testnet.address(0).unwrap().starts_with("ST"));
+/// ```
+pub struct WalletSession<C = ()> {
+    network: NetworkKind,
+    account_index: u32,
+    client: C,
+    /// Derives the private keys from the account key
+    deriver: CachedDeriver<ExtendedPrivateKey>,
+    account: WatchOnlyAccount,
+    /// External chain addresses, by index
+    addresses: HashMap<u32, String>,
+    /// Next nonce of the external chain addresses, by index
+    nonces: HashMap<u32, u64>,
+}
+
+impl Wallet {
+    /// Opens a session on `network` for the account `account_index` (`m/44'/5757'/account_index'`),
+    /// [`Error::InvalidIndex`] from 2^31.
+    pub fn session(&self, network: NetworkKind, account_index: u32) -> Result<WalletSession, Error> {
+        let path = DerivationPath::from_str(&format!("m/44'/{}'/{}'", super::STX_COIN_TYPE, check_index(account_index)?))
+            .map_err(|_| Error::InvalidIndex(account_index))?;
+        let account_key = ExtendedPrivateKey::derive_from_path(self.root_key(), path);
+        let account = WatchOnlyAccount::new(&ExtendedPublicKey::from(&account_key), network);
+        Ok(WalletSession {
+            network,
+            account_index,
+            client: (),
+            deriver: CachedDeriver::new(account_key),
+            account,
+            addresses: HashMap::new(),
+            nonces: HashMap::new(),
+        })
+    }
+}
+
+impl<C> WalletSession<C> {
+    /// Attaches the client used to talk to a node of the session network.
+    pub fn with_client<D>(self, client: D) -> WalletSession<D> {
+        WalletSession {
+            network: self.network,
+            account_index: self.account_index,
+            client,
+            deriver: self.deriver,
+            account: self.account,
+            addresses: self.addresses,
+            nonces: self.nonces,
+        }
+    }
+
+    pub fn network(&self) -> NetworkKind {
+        self.network
+    }
+
+    pub fn account_index(&self) -> u32 {
+        self.account_index
+    }
+
+    pub fn client(&self) -> &C {
+        &self.client
+    }
+
+    pub fn client_mut(&mut self) -> &mut C {
+        &mut self.client
+    }
+
+    /// Stacks address at `index` of the external chain, cached after the first call.
+    pub fn address(&mut self, index: u32) -> Result<String, Error> {
+        if let Some(address) = self.addresses.get(&index) {
+            return Ok(address.clone());
+        }
+        let address = self.account.address(Chain::External, check_index(index)?).ok_or(Error::InvalidIndex(index))?.stacks_address;
+        self.addresses.insert(index, address.clone());
+        Ok(address)
+    }
+
+    /// Private key of the address at `index` of the external chain.
+    pub fn private_key(&mut self, index: u32) -> Result<SecretKey, Error> {
+        let path = DerivationPath::from_str(&format!("m/{}/{}", Chain::External.index(), check_index(index)?))
+            .map_err(|_| Error::InvalidIndex(index))?;
+        Ok(self.deriver.derive(&path)?.s_key)
+    }
+
+    /// Sets the next nonce of the address at `index`, e.g. as reported by the node.
+    pub fn set_nonce(&mut self, index: u32, nonce: u64) {
+        self.nonces.insert(index, nonce);
+    }
+
+    /// Returns the next nonce of the address at `index` and increments it.
+    pub fn next_nonce(&mut self, index: u32) -> u64 {
+        let nonce = self.nonces.entry(index).or_insert(0);
+        *nonce += 1;
+        *nonce - 1
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::address::c32::c32_address_decode;
+    use crate::crypto::hash::{Hash160, Hasher};
+
+    use crate::crypto::context::secp256k1_context;
+    use crate::wallet::lockable_mnemonic::{LockableMnemonic, LockedMnemonicMethods};
+
+    use super::*;
+
+    fn wallet() -> Wallet {
+        let words = "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon about";
+        Wallet::new(Some(&LockableMnemonic::from_bip39_words(words, None).unwrap()))
+    }
+
+    #[test]
+    fn test_sessions_are_isolated() {
+        let wallet = wallet();
+        let mut mainnet = wallet.session(NetworkKind::Mainnet, 0).unwrap();
+        let mut testnet = wallet.session(NetworkKind::Testnet, 0).unwrap();
+
+        mainnet.set_nonce(0, 7);
+        assert_eq!(mainnet.next_nonce(0), 7);
+        assert_eq!(mainnet.next_nonce(0), 8);
+        assert_eq!(testnet.next_nonce(0), 0);
+
+        let mainnet_address = mainnet.address(0).unwrap();
+        let testnet_address = testnet.address(0).unwrap();
+        assert!(mainnet_address.starts_with("SP"));
+        assert!(testnet_address.starts_with("ST"));
+        // same key, different network
+        assert_eq!(c32_address_decode(&mainnet_address).unwrap().1, c32_address_decode(&testnet_address).unwrap().1);
+    }
+
+    #[test]
+    fn test_session_private_key_matches_address() {
+        let mut session = wallet().session(NetworkKind::Mainnet, 0).unwrap().with_client("mainnet-client");
+        assert_eq!(*session.client(), "mainnet-client");
+        let public_key = session.private_key(3).unwrap().public_key(secp256k1_context());
+        let (_, hash) = c32_address_decode(&session.address(3).unwrap()).unwrap();
+        assert_eq!(Hash160::hash(&public_key.serialize()).to_vec(), hash);
+    }
+
+    #[test]
+    fn test_hardened_indexes() {
+        let wallet = wallet();
+        assert_eq!(wallet.session(NetworkKind::Mainnet, 1 << 31).err(), Some(Error::InvalidIndex(1 << 31)));
+        let mut session = wallet.session(NetworkKind::Mainnet, (1 << 31) - 1).unwrap();
+        assert_eq!(session.address(u32::MAX), Err(Error::InvalidIndex(u32::MAX)));
+        assert_eq!(session.private_key(1 << 31), Err(Error::InvalidIndex(1 << 31)));
+        assert!(session.address((1 << 31) - 1).is_ok());
+    }
+}
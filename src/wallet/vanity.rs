@@ -0,0 +1,195 @@
+use std::fmt;
+use std::ops::Range;
+
+use rand::rngs::{OsRng, StdRng};
+use rand::SeedableRng;
+use rayon::prelude::*;
+use regex::Regex;
+use secp256k1::SecretKey;
+
+use crate::address::c32::C32_ALPHABET;
+use crate::address::stacks_address::StacksAddress;
+use crate::bip32::child_number::ChildNumber;
+use crate::crypto::context::secp256k1_context;
+use crate::crypto::keys::extended_public_key::{ExtendedPublicKey, ExtendedPublicKeyMethods};
+use crate::crypto::utils;
+use crate::network::NetworkKind;
+
+use super::watch_only::Chain;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Error {
+    /// Prefixes and suffixes can only contain c32 characters
+    InvalidPattern(String),
+    InvalidRegex(String),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        match self {
+            Error::InvalidPattern(v) => f.write_str(&format!("Invalid vanity pattern {v:?}, only c32 characters can match")),
+            Error::InvalidRegex(v) => f.write_str(&format!("Invalid regex: {v}")),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+/// What the address must look like, matched against the whole address (e.g. `SP000...`).
+#[derive(Clone, Debug)]
+pub enum VanityPattern {
+    Prefix(String),
+    Suffix(String),
+    Regex(Regex),
+}
+
+impl VanityPattern {
+    /// Case-insensitive prefix of the address, version included (`SP`, `ST`...).
+    pub fn prefix(prefix: &str) -> Result<Self, Error> {
+        Ok(VanityPattern::Prefix(Self::c32_pattern(prefix)?))
+    }
+
+    /// Case-insensitive suffix of the address (the end of the checksum).
+    pub fn suffix(suffix: &str) -> Result<Self, Error> {
+        Ok(VanityPattern::Suffix(Self::c32_pattern(suffix)?))
+    }
+
+    pub fn regex(pattern: &str) -> Result<Self, Error> {
+        Regex::new(pattern).map(VanityPattern::Regex).map_err(|error| Error::InvalidRegex(format!("{error}")))
+    }
+
+    pub fn is_match(&self, address: &str) -> bool {
+        match self {
+            VanityPattern::Prefix(prefix) => address.starts_with(prefix.as_str()),
+            VanityPattern::Suffix(suffix) => address.ends_with(suffix.as_str()),
+            VanityPattern::Regex(regex) => regex.is_match(address),
+        }
+    }
+
+    fn c32_pattern(pattern: &str) -> Result<String, Error> {
+        let pattern = pattern.to_ascii_uppercase();
+        if pattern.is_empty() || !pattern.bytes().all(|c| C32_ALPHABET.contains(&c)) {
+            return Err(Error::InvalidPattern(pattern));
+        }
+        Ok(pattern)
+    }
+}
+
+/// How to reproduce the key of a [`VanityMatch`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum VanityDerivation {
+    /// A random key, which must be stored
+    Random(SecretKey),
+    /// Index on the external chain of the searched account (`.../0/index`)
+    Index(u32),
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct VanityMatch {
+    pub address: StacksAddress,
+    pub derivation: VanityDerivation,
+}
+
+/// Draws up to `max_attempts` random keys in parallel and returns the first whose
+/// single-sig address matches `pattern`, `None` if none does.
+///
+/// Every thread draws from its own generator seeded from the OS.
+pub fn search_random(pattern: &VanityPattern, network: &NetworkKind, max_attempts: u64) -> Option<VanityMatch> {
+    (0..max_attempts)
+        .into_par_iter()
+        .map_init(
+            || StdRng::from_rng(OsRng).unwrap(),
+            |rng, _| {
+                let secret_key = loop {
+                    let mut bytes = [0u8; 32];
+                    utils::generate_random_bytes_with_rng(rng, &mut bytes, 32);
+                    if let Ok(key) = SecretKey::from_byte_array(&bytes) {
+                        break key;
+                    }
+                };
+                let address = StacksAddress::single_sig(&secret_key.public_key(secp256k1_context()), network);
+                pattern
+                    .is_match(&address.to_string())
+                    .then_some(VanityMatch { address, derivation: VanityDerivation::Random(secret_key) })
+            },
+        )
+        .find_map_any(|found| found)
+}
+
+/// Returns the lowest index of `indexes` whose address on the external chain of the
+/// account-level key (e.g. the xpub of `m/44'/5757'/0'`) matches `pattern`.
+///
+/// Usage:
+/// ```rust
+/// use stacks_rs::crypto::keys::extended_private_key::{ExtendedPrivateKey, ExtendedPrivateKeyMethods};
+/// use stacks_rs::crypto::keys::extended_public_key::ExtendedPublicKey;
+/// use stacks_rs::network::NetworkKind;
+/// use stacks_rs::wallet::vanity::{search_account, VanityDerivation, VanityPattern};
+/// let seed = hex::decode("000102030405060708090a0b0c0d0e0f").unwrap();
+/// let account_xpub = ExtendedPublicKey::from(&ExtendedPrivateKey::new(&seed).unwrap());
+/// let pattern = VanityPattern::suffix("0").unwrap();
+/// let found = search_account(&account_xpub, &pattern, &NetworkKind::Mainnet, 0..10_000).unwrap();
+/// assert!(found.address.to_string().ends_with('0'));
+/// assert!(matches!(found.derivation, VanityDerivation::Index(_)));
+/// ```
+pub fn search_account(
+    account_key: &ExtendedPublicKey,
+    pattern: &VanityPattern,
+    network: &NetworkKind,
+    indexes: Range<u32>,
+) -> Option<VanityMatch> {
+    let chain_key = account_key.derive_child(ChildNumber::new(Chain::External.index()).unwrap()).unwrap();
+    indexes.into_par_iter().find_map_first(|index| {
+        let child = chain_key.derive_child(ChildNumber::new(index).ok()?).ok()?;
+        let address = StacksAddress::single_sig(child.public_key(), network);
+        pattern
+            .is_match(&address.to_string())
+            .then_some(VanityMatch { address, derivation: VanityDerivation::Index(index) })
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::crypto::keys::extended_private_key::{ExtendedPrivateKey, ExtendedPrivateKeyMethods};
+    use crate::wallet::address_generator::AddressGenerator;
+
+    use super::*;
+
+    #[test]
+    fn test_patterns() {
+        let address = "SP2J6ZY48GV1EZ5V2V5RB9MP66SW86PYKKNRV9EJ7";
+        assert!(VanityPattern::prefix("sp2j6").unwrap().is_match(address));
+        assert!(VanityPattern::suffix("ej7").unwrap().is_match(address));
+        assert!(VanityPattern::regex("^SP2J.*EJ7$").unwrap().is_match(address));
+        assert!(!VanityPattern::prefix("ST").unwrap().is_match(address));
+
+        assert_eq!(VanityPattern::prefix("SPU").unwrap_err(), Error::InvalidPattern(String::from("SPU")));
+        assert!(VanityPattern::suffix("").is_err());
+        assert!(matches!(VanityPattern::regex("(").unwrap_err(), Error::InvalidRegex(_)));
+    }
+
+    #[test]
+    fn test_search_account_is_reproducible() {
+        let seed = hex::decode("000102030405060708090a0b0c0d0e0f").unwrap();
+        let account_xpub = ExtendedPublicKey::from(&ExtendedPrivateKey::new(&seed).unwrap());
+        let pattern = VanityPattern::suffix("Z").unwrap();
+        let found = search_account(&account_xpub, &pattern, &NetworkKind::Testnet, 0..10_000).unwrap();
+
+        // the lowest matching index, as a sequential scan would find
+        let expected = AddressGenerator::new(&account_xpub, NetworkKind::Testnet)
+            .find(|generated| generated.address.to_string().ends_with('Z'))
+            .unwrap();
+        assert_eq!(found.derivation, VanityDerivation::Index(expected.index));
+        assert_eq!(found.address, expected.address);
+        assert!(search_account(&account_xpub, &VanityPattern::prefix("SP").unwrap(), &NetworkKind::Testnet, 0..100).is_none());
+    }
+
+    #[test]
+    fn test_search_random() {
+        let pattern = VanityPattern::prefix("ST1").unwrap();
+        let found = search_random(&pattern, &NetworkKind::Testnet, 100_000).unwrap();
+        let VanityDerivation::Random(secret_key) = found.derivation else { panic!("expected a random key") };
+        assert_eq!(StacksAddress::single_sig(&secret_key.public_key(secp256k1_context()), &NetworkKind::Testnet), found.address);
+        assert!(found.address.to_string().starts_with("ST1"));
+    }
+}
@@ -0,0 +1,232 @@
+use secp256k1::PublicKey;
+use stacks_common::address::b58;
+
+use crate::bip32::child_number::ChildNumber;
+use crate::crypto::keys::extended_public_key::{ExtendedPublicKey, ExtendedPublicKeyChildren, ExtendedPublicKeyMethods};
+use crate::crypto::keys::stacks_key::StacksKeyMethods;
+use crate::network::NetworkKind;
+
+/// Non-hardened indexes are in `0..NON_HARDENED_INDEXES`.
+const NON_HARDENED_INDEXES: u32 = 2147483648;
+/// Version byte of mainnet P2PKH Bitcoin addresses (starting with `1`)
+const BTC_MAINNET_P2PKH: u8 = 0x00;
+/// Version byte of testnet P2PKH Bitcoin addresses (starting with `m` or `n`)
+const BTC_TESTNET_P2PKH: u8 = 0x6f;
+
+/// BIP44 chain of an account.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Chain {
+    /// Receiving addresses (`.../0/i`)
+    External,
+    /// Change addresses (`.../1/i`)
+    Change,
+}
+
+impl Chain {
+    pub fn index(&self) -> u32 {
+        match *self {
+            Chain::External => 0,
+            Chain::Change => 1,
+        }
+    }
+}
+
+pub struct WatchOnlyAddress {
+    pub chain: Chain,
+    pub index: u32,
+    pub public_key: PublicKey,
+    pub stacks_address: String,
+    /// P2PKH Bitcoin address of the same key, if enabled on the account
+    pub bitcoin_address: Option<String>,
+}
+
+/// An account that can only generate addresses, built from the account-level extended public key
+/// (e.g. the xpub of `m/44'/5757'/0'`). It never holds any secret material.
+pub struct WatchOnlyAccount {
+    network: NetworkKind,
+    external: ExtendedPublicKey,
+    change: ExtendedPublicKey,
+    bitcoin_addresses: bool,
+}
+
+impl WatchOnlyAccount {
+    pub fn new(account_key: &ExtendedPublicKey, network: NetworkKind) -> Self {
+        let chain_key = |chain: Chain| account_key.derive_child(ChildNumber::new(chain.index()).unwrap()).unwrap();
+        Self {
+            network,
+            external: chain_key(Chain::External),
+            change: chain_key(Chain::Change),
+            bitcoin_addresses: false,
+        }
+    }
+
+    /// Also render the P2PKH Bitcoin address of every generated key.
+    pub fn with_bitcoin_addresses(mut self, enabled: bool) -> Self {
+        self.bitcoin_addresses = enabled;
+        self
+    }
+
+    pub fn network(&self) -> &NetworkKind {
+        &self.network
+    }
+
+    pub fn chain_key(&self, chain: Chain) -> &ExtendedPublicKey {
+        match chain {
+            Chain::External => &self.external,
+            Chain::Change => &self.change,
+        }
+    }
+
+    /// Returns the address at `index` of `chain`, `None` if `index` is hardened.
+    pub fn address(&self, chain: Chain, index: u32) -> Option<WatchOnlyAddress> {
+        let child = self.chain_key(chain).derive_child(ChildNumber::new(index).ok()?).ok()?;
+        Some(self.to_address(chain, index, child.public_key()))
+    }
+
+    /// Returns the addresses of `chain`, starting from index 0.
+    pub fn addresses(&self, chain: Chain) -> WatchOnlyAddresses<'_> {
+        WatchOnlyAddresses {
+            account: self,
+            chain,
+            next_index: 0,
+            children: self.chain_key(chain).derive_children(0..NON_HARDENED_INDEXES),
+        }
+    }
+
+    /// Returns the addresses of `chain` up to `gap_limit` consecutive addresses for which
+    /// `is_used` returns false (the trailing unused addresses are yielded too).
+    ///
+    /// Usage:
+    /// ```rust
+    /// use stacks_rs::crypto::keys::extended_private_key::{ExtendedPrivateKey, ExtendedPrivateKeyMethods};
+    /// use stacks_rs::crypto::keys::extended_public_key::ExtendedPublicKey;
+    /// use stacks_rs::network::NetworkKind;
+    /// use stacks_rs::wallet::watch_only::{Chain, WatchOnlyAccount};
+    /// let seed = hex::decode("000102030405060708090a0b0c0d0e0f").unwrap();
+    /// let account_xpub = ExtendedPublicKey::from(&ExtendedPrivateKey::new(&seed).unwrap());
+    /// let account = WatchOnlyAccount::new(&account_xpub, NetworkKind::Mainnet);
+    /// let used = account.scan(Chain::External, 20, |address| address.index < 2).count();
+    /// assert_eq!(used, 22);
+    /// ```
+    pub fn scan<F>(&self, chain: Chain, gap_limit: u32, is_used: F) -> GapLimitScan<'_, F>
+    where
+        F: FnMut(&WatchOnlyAddress) -> bool,
+    {
+        GapLimitScan { addresses: self.addresses(chain), gap_limit, unused_streak: 0, is_used }
+    }
+
+    fn to_address(&self, chain: Chain, index: u32, public_key: &PublicKey) -> WatchOnlyAddress {
+        let hash = public_key.hash160();
+        let stacks_address = public_key.stacks_address(&self.network);
+        let bitcoin_address = self.bitcoin_addresses.then(|| {
+            let version = match self.network {
+                NetworkKind::Mainnet => BTC_MAINNET_P2PKH,
+                NetworkKind::Testnet | NetworkKind::Mocknet => BTC_TESTNET_P2PKH,
+            };
+            b58::check_encode_slice(&[&[version], hash.as_slice()].concat())
+        });
+        WatchOnlyAddress { chain, index, public_key: *public_key, stacks_address, bitcoin_address }
+    }
+}
+
+/// Iterator over the addresses of a chain, see [`WatchOnlyAccount::addresses`].
+pub struct WatchOnlyAddresses<'a> {
+    account: &'a WatchOnlyAccount,
+    chain: Chain,
+    next_index: u32,
+    children: ExtendedPublicKeyChildren<'a>,
+}
+
+impl Iterator for WatchOnlyAddresses<'_> {
+    type Item = WatchOnlyAddress;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let child = self.children.next()?.ok()?;
+        let address = self.account.to_address(self.chain, self.next_index, child.public_key());
+        self.next_index += 1;
+        Some(address)
+    }
+}
+
+/// Iterator stopping after `gap_limit` consecutive unused addresses, see [`WatchOnlyAccount::scan`].
+pub struct GapLimitScan<'a, F> {
+    addresses: WatchOnlyAddresses<'a>,
+    gap_limit: u32,
+    unused_streak: u32,
+    is_used: F,
+}
+
+impl<F> Iterator for GapLimitScan<'_, F>
+where
+    F: FnMut(&WatchOnlyAddress) -> bool,
+{
+    type Item = WatchOnlyAddress;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.unused_streak >= self.gap_limit {
+            return None;
+        }
+        let address = self.addresses.next()?;
+        match (self.is_used)(&address) {
+            true => self.unused_streak = 0,
+            false => self.unused_streak += 1,
+        }
+        Some(address)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use crate::address::c32::c32_address;
+    use crate::crypto::hash::{Hash160, Hasher};
+
+    use crate::bip32::derivation_path::DerivationPath;
+    use crate::crypto::keys::extended_private_key::{ExtendedPrivateKey, ExtendedPrivateKeyMethods};
+
+    use super::*;
+
+    fn account(network: NetworkKind) -> (Vec<u8>, WatchOnlyAccount) {
+        let seed = hex::decode("000102030405060708090a0b0c0d0e0f").unwrap();
+        let account_key = ExtendedPrivateKey::derive_from_path(&seed, DerivationPath::from_str("m/44'/5757'/0'").unwrap());
+        let account_xpub = ExtendedPublicKey::from(&account_key);
+        (seed, WatchOnlyAccount::new(&account_xpub, network))
+    }
+
+    #[test]
+    fn test_watch_only_addresses_match_private_derivation() {
+        let (seed, account) = account(NetworkKind::Mainnet);
+        let account = account.with_bitcoin_addresses(true);
+        let key = ExtendedPrivateKey::derive_from_path(&seed, DerivationPath::from_str("m/44'/5757'/0'/1/4").unwrap());
+        let hash = Hash160::hash(&key.public_key().serialize());
+
+        let address = account.address(Chain::Change, 4).unwrap();
+        assert_eq!(address.public_key, key.public_key());
+        assert_eq!(address.stacks_address, c32_address(22, &hash).unwrap());
+        assert!(address.stacks_address.starts_with("SP"));
+        assert!(address.bitcoin_address.unwrap().starts_with('1'));
+
+        let from_iterator = account.addresses(Chain::Change).nth(4).unwrap();
+        assert_eq!(from_iterator.index, 4);
+        assert_eq!(from_iterator.public_key, key.public_key());
+    }
+
+    #[test]
+    fn test_watch_only_testnet() {
+        let (_seed, account) = account(NetworkKind::Testnet);
+        let address = account.address(Chain::External, 0).unwrap();
+        assert!(address.stacks_address.starts_with("ST"));
+        assert!(address.bitcoin_address.is_none());
+        assert!(account.address(Chain::External, NON_HARDENED_INDEXES).is_none());
+    }
+
+    #[test]
+    fn test_gap_limit_scan() {
+        let (_seed, account) = account(NetworkKind::Mainnet);
+        let scanned = account.scan(Chain::External, 2, |address| address.index == 0 || address.index == 3).count();
+        assert_eq!(scanned, 3);
+        let scanned = account.scan(Chain::External, 3, |address| address.index == 0 || address.index == 3).count();
+        assert_eq!(scanned, 7);
+    }
+}